@@ -17,7 +17,7 @@ use kube::{
 #[derive(CustomResource, Deserialize, Serialize, Clone, Debug, JsonSchema)]
 #[kube(group = "clux.dev", version = "v1", kind = "Foo", namespaced)]
 #[kube(status = "FooStatus")]
-#[kube(scale = r#"{"specReplicasPath":".spec.replicas", "statusReplicasPath":".status.replicas"}"#)]
+#[kube(scale(spec_replicas = ".spec.replicas", status_replicas = ".status.replicas"))]
 pub struct FooSpec {
     name: String,
     info: Option<String>,
@@ -7,6 +7,7 @@ use k8s_openapi::{
     chrono::Utc,
 };
 use kube::{
+    core::{fields, FieldSelector},
     runtime::{watcher, WatchStreamExt},
     Api, Client, ResourceExt,
 };
@@ -35,7 +36,10 @@ async fn main() -> anyhow::Result<()> {
     let mut conf = watcher::Config::default();
     if let Some(forval) = app.r#for {
         if let Some((kind, name)) = forval.split_once('/') {
-            conf = conf.fields(&format!("regarding.kind={kind},regarding.name={name}"));
+            let selector = FieldSelector::default()
+                .equal(fields::REGARDING_KIND, kind)
+                .equal(fields::REGARDING_NAME, name);
+            conf = conf.fields_from(&selector);
         } else {
             return Err(anyhow::Error::msg("Usage: --for=<KIND>/<NAME>"));
         }
@@ -7,7 +7,7 @@ use k8s_openapi::{apimachinery::pkg::apis::meta::v1::Time, chrono::Utc};
 use kube::{
     api::{Api, DynamicObject, ListParams, Patch, PatchParams, ResourceExt},
     config::KubeConfigOptions,
-    core::GroupVersionKind,
+    core::{fields, FieldSelector, GroupVersionKind},
     discovery::{ApiCapabilities, ApiResource, Discovery, Scope},
     runtime::{
         wait::{await_condition, conditions::is_deleted},
@@ -125,7 +125,7 @@ impl App {
 
     async fn watch(&self, api: Api<DynamicObject>, mut wc: watcher::Config) -> Result<()> {
         if let Some(n) = &self.name {
-            wc = wc.fields(&format!("metadata.name={n}"));
+            wc = wc.fields_from(&FieldSelector::default().equal(fields::METADATA_NAME, n));
         }
         // present a dumb table for it for now. kubectl does not do this anymore.
         let mut stream = watcher(api, wc).applied_objects().boxed();
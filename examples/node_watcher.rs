@@ -5,6 +5,7 @@ use k8s_openapi::api::core::v1::{Event, Node};
 use kube::{
     api::{Api, ListParams, ResourceExt},
     client::{scope, Client},
+    core::{fields, FieldSelector},
     runtime::{watcher, WatchStreamExt},
 };
 use tracing::*;
@@ -51,8 +52,10 @@ async fn check_for_node_failures(client: &Client, o: Node) -> anyhow::Result<()>
             .collect::<Vec<_>>(); // failed statuses
         warn!("Unschedulable Node: {}, ({:?})", name, failed);
         // Find events related to this node
-        let opts =
-            ListParams::default().fields(&format!("involvedObject.kind=Node,involvedObject.name={name}"));
+        let selector = FieldSelector::default()
+            .equal(fields::INVOLVED_OBJECT_KIND, "Node")
+            .equal(fields::INVOLVED_OBJECT_NAME, name.as_str());
+        let opts = ListParams::default().fields_from(&selector);
         let evlist = client.list::<Event>(&opts, &scope::Cluster).await?;
         for e in evlist {
             warn!("Node event: {:?}", serde_json::to_string_pretty(&e)?);
@@ -13,7 +13,7 @@ use tokio::{io::AsyncWriteExt, select};
 // Send the new terminal size to channel when it change
 async fn handle_terminal_size(mut channel: Sender<TerminalSize>) -> Result<(), anyhow::Error> {
     let (width, height) = crossterm::terminal::size()?;
-    channel.send(TerminalSize { height, width }).await?;
+    channel.send(TerminalSize::new(width, height)).await?;
 
     // create a stream to catch SIGWINCH signal
     let mut sig = signal::unix::signal(signal::unix::SignalKind::window_change())?;
@@ -23,7 +23,7 @@ async fn handle_terminal_size(mut channel: Sender<TerminalSize>) -> Result<(), a
         }
 
         let (width, height) = crossterm::terminal::size()?;
-        channel.send(TerminalSize { height, width }).await?;
+        channel.send(TerminalSize::new(width, height)).await?;
     }
 }
 
@@ -31,7 +31,7 @@ async fn handle_terminal_size(mut channel: Sender<TerminalSize>) -> Result<(), a
 // We don't support window for terminal size change, we only send the initial size
 async fn handle_terminal_size(mut channel: Sender<TerminalSize>) -> Result<(), anyhow::Error> {
     let (width, height) = crossterm::terminal::size()?;
-    channel.send(TerminalSize { height, width }).await?;
+    channel.send(TerminalSize::new(width, height)).await?;
     let mut ctrl_c = tokio::signal::windows::ctrl_c()?;
     ctrl_c.recv().await;
     Ok(())
@@ -77,10 +77,10 @@ async fn main() -> anyhow::Result<()> {
         let mut stdin = tokio_util::io::ReaderStream::new(tokio::io::stdin());
         let mut stdout = tokio::io::stdout();
 
-        let mut output = tokio_util::io::ReaderStream::new(attached.stdout().unwrap());
-        let mut input = attached.stdin().unwrap();
-
-        let term_tx = attached.terminal_size().unwrap();
+        // With tty, the process has a single raw terminal for both its input and output
+        let (tty, term_tx) = attached.tty().unwrap();
+        let (output, mut input) = tokio::io::split(tty);
+        let mut output = tokio_util::io::ReaderStream::new(output);
 
         let mut handle_terminal_size_handle = tokio::spawn(handle_terminal_size(term_tx));
 
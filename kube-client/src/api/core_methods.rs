@@ -1,11 +1,12 @@
 use either::Either;
-use futures::Stream;
+use futures::{stream, Stream, TryStreamExt};
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
 
 use crate::{api::Api, Error, Result};
 use kube_core::{
-    metadata::PartialObjectMeta, object::ObjectList, params::*, response::Status, ErrorResponse, WatchEvent,
+    apply::ApplyConflict, metadata::PartialObjectMeta, object::ObjectList, params::*, response::Status,
+    table::Table, ErrorResponse, WatchEvent,
 };
 
 /// PUSH/PUT/POST/GET abstractions
@@ -193,6 +194,43 @@ where
         self.client.request::<ObjectList<K>>(req).await
     }
 
+    /// Stream all resources matching a list query, one page at a time
+    ///
+    /// This follows the `continue` tokens of a limited [list](`Api::list`), so huge collections can be
+    /// processed without buffering the whole [`ObjectList`] in memory.
+    /// Pages hold up to [`ListParams::limit`] items, or 500 if no limit is set.
+    ///
+    /// All pages are served from the same snapshot as the first one. If that snapshot is compacted before
+    /// the last page is fetched, the stream fails with a `410 Gone` error.
+    ///
+    /// ```no_run
+    /// use kube::api::{Api, ListParams, ResourceExt};
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use futures::TryStreamExt;
+    ///
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::all(client);
+    /// let lp = ListParams::default().limit(100);
+    /// let mut stream = std::pin::pin!(pods.list_stream(&lp));
+    /// while let Some(p) = stream.try_next().await? {
+    ///     println!("Found Pod: {}", p.name_any());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn list_stream(&self, lp: &ListParams) -> impl Stream<Item = Result<K>> + '_ {
+        let mut lp = lp.clone();
+        lp.limit.get_or_insert(500);
+        stream::try_unfold(Some(lp), move |lp| async move {
+            let Some(lp) = lp else { return Ok(None) };
+            let page = self.list(&lp).await?;
+            let next = page.continue_token().map(|token| lp.continue_token(token));
+            Ok::<_, Error>(Some((stream::iter(page.items.into_iter().map(Ok)), next)))
+        })
+        .try_flatten()
+    }
+
     /// Get a list of resources that contains only their metadata as
     ///
     /// Similar to [list](`Api::list`), you use this to get everything, or a
@@ -220,6 +258,32 @@ where
         self.client.request::<ObjectList<PartialObjectMeta<K>>>(req).await
     }
 
+    /// Get a list of resources as a [`Table`], as printed by `kubectl get`
+    ///
+    /// The apiserver picks the columns, including any `additionalPrinterColumns` of a custom resource,
+    /// and includes the metadata of the object behind every row.
+    ///
+    /// ```no_run
+    /// use kube::api::{Api, ListParams};
+    /// use k8s_openapi::api::core::v1::Pod;
+    ///
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::namespaced(client, "apps");
+    /// let table = pods.list_table(&ListParams::default()).await?;
+    /// println!("{}", table.headers(false).join("\t"));
+    /// for row in table.cells(false) {
+    ///     println!("{}", row.join("\t"));
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn list_table(&self, lp: &ListParams) -> Result<Table<K>> {
+        let mut req = self.request.list_table(lp).map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("list_table");
+        self.client.request::<Table<K>>(req).await
+    }
+
     /// Create a resource
     ///
     /// This function requires a type that Serializes to `K`, which can be:
@@ -255,7 +319,9 @@ where
     /// When you get a `Status` via `Right`, this should be a a 2XX style
     /// confirmation that the object being gone.
     ///
-    /// 4XX and 5XX status types are returned as an [`Err(kube_client::Error::Api)`](crate::Error::Api).
+    /// 4XX and 5XX status types are returned as an [`Err(kube_client::Error::Api)`](crate::Error::Api),
+    /// except for a `409 Conflict` when the preconditions of the [`DeleteParams`] no longer hold,
+    /// which is returned as an [`Error::PreconditionFailed`].
     ///
     /// ```no_run
     /// use kube::api::{Api, DeleteParams};
@@ -270,10 +336,36 @@ where
     /// # Ok(())
     /// # }
     /// ```
+    ///
+    /// Preconditions make sure that only the object that was read is deleted, and not a newer version of it:
+    ///
+    /// ```no_run
+    /// use kube::{api::{Api, DeleteParams, ResourceExt}, Error};
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::namespaced(client, "apps");
+    /// let pod = pods.get("blog").await?;
+    /// let dp = DeleteParams::foreground().with_uid_precondition(pod.uid().unwrap());
+    /// match pods.delete("blog", &dp).await {
+    ///     Err(Error::PreconditionFailed(_)) => println!("blog was recreated, keeping it"),
+    ///     res => { res?; }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
     pub async fn delete(&self, name: &str, dp: &DeleteParams) -> Result<Either<K, Status>> {
         let mut req = self.request.delete(name, dp).map_err(Error::BuildRequest)?;
         req.extensions_mut().insert("delete");
-        self.client.request_status::<K>(req).await
+        self.client
+            .request_status::<K>(req)
+            .await
+            .map_err(|err| match err {
+                Error::Api(response) if response.code == 409 && dp.preconditions.is_some() => {
+                    Error::PreconditionFailed(response)
+                }
+                err => err,
+            })
     }
 
     /// Delete a collection of resources
@@ -359,6 +451,67 @@ where
         self.client.request::<K>(req).await
     }
 
+    /// Server-side apply `patch` to the resource `name`, reporting conflicts with other field managers
+    ///
+    /// Same as [`Api::patch`] with a [`Patch::Apply`], except that conflicts are returned as an
+    /// [`Error::ApplyConflict`], which lists the conflicting field managers and fields.
+    ///
+    /// ```no_run
+    /// use kube::{api::{Api, PatchParams}, Error};
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let deploys: Api<Deployment> = Api::namespaced(client, "apps");
+    /// let patch = serde_json::json!({
+    ///     "apiVersion": "apps/v1",
+    ///     "kind": "Deployment",
+    ///     "spec": { "replicas": 3 }
+    /// });
+    /// match deploys.apply("web", &PatchParams::apply("myapp"), &patch).await {
+    ///     Err(Error::ApplyConflict(conflict)) => println!("replicas are managed by {:?}", conflict.managers()),
+    ///     res => { res?; }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// [`Patch::Apply`]: super::Patch::Apply
+    pub async fn apply<P: Serialize + Debug>(&self, name: &str, pp: &PatchParams, patch: &P) -> Result<K> {
+        self.patch(name, pp, &Patch::Apply(patch))
+            .await
+            .map_err(|err| match err {
+                Error::Api(response) => match ApplyConflict::from_response(&response) {
+                    Some(conflict) => Error::ApplyConflict(conflict),
+                    None => Error::Api(response),
+                },
+                err => err,
+            })
+    }
+
+    /// Server-side apply `patch` to the resource `name`, only forcing conflicts within `force_fields`
+    ///
+    /// Same as [`Api::apply`], but when all conflicting fields are in `force_fields` (or nested within them),
+    /// the apply is retried with [`PatchParams::force`] to take ownership of them. Other conflicts are returned
+    /// as an [`Error::ApplyConflict`]. Fields are given as paths like `.spec.replicas`.
+    ///
+    /// This is useful to take over specific fields from a previous manager, such as `kubectl`,
+    /// without overwriting fields that other controllers are actively managing.
+    pub async fn apply_forcing<P: Serialize + Debug>(
+        &self,
+        name: &str,
+        pp: &PatchParams,
+        patch: &P,
+        force_fields: &[&str],
+    ) -> Result<K> {
+        match self.apply(name, pp, patch).await {
+            Err(Error::ApplyConflict(conflict)) if !pp.force && conflict.is_within(force_fields) => {
+                tracing::debug!(%conflict, "forcing apply of conflicting fields");
+                self.apply(name, &pp.clone().force(), patch).await
+            }
+            res => res,
+        }
+    }
+
     /// Patch a metadata subset of a resource's properties from [`PartialObjectMeta`]
     ///
     /// Takes a [`Patch`] along with [`PatchParams`] for the call.
@@ -0,0 +1,376 @@
+use std::{
+    io::{self, Read, Write},
+    path::{Component, Path, PathBuf},
+};
+
+use futures::FutureExt;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Status;
+use serde::de::DeserializeOwned;
+use thiserror::Error;
+use tokio::io::{AsyncRead, AsyncReadExt};
+use tokio_util::io::SyncIoBridge;
+
+use super::{remote_command, AttachParams, AttachedProcess, Execute};
+use crate::api::Api;
+
+/// Errors from copying files to and from a container.
+#[derive(Debug, Error)]
+pub enum CopyError {
+    /// The remote path does not name a file or directory.
+    #[error("invalid remote path {0:?}")]
+    InvalidRemotePath(String),
+
+    /// Failed to start `tar` in the container.
+    #[error("failed to exec tar in the container: {0}")]
+    Exec(#[source] crate::Error),
+
+    /// Failed to archive the local files and send them to the container.
+    #[error("failed to send archive: {0}")]
+    WriteArchive(#[source] io::Error),
+
+    /// Failed to receive the archive from the container and unpack it locally.
+    #[error("failed to unpack archive: {0}")]
+    ReadArchive(#[source] io::Error),
+
+    /// `tar` failed in the container.
+    #[error("tar failed in the container: {message}: {stderr}")]
+    RemoteTar {
+        /// The status message of the command
+        message: String,
+        /// The output of `tar` on stderr
+        stderr: String,
+    },
+
+    /// The connection was closed before `tar` exited in the container.
+    #[error("connection closed before tar exited")]
+    Disconnected,
+
+    /// The connection to the container failed.
+    #[error("remote command failed: {0}")]
+    RemoteCommand(#[source] remote_command::Error),
+
+    /// Failed to run archiving in a blocking task.
+    #[error("failed to spawn archiving task: {0}")]
+    Spawn(#[source] tokio::task::JoinError),
+}
+
+/// Parameters for copying files with [`Api::copy_to`] and [`Api::copy_from`].
+#[derive(Clone, Debug, Default)]
+pub struct CopyParams {
+    /// The name of the container to copy to or from.
+    /// Defaults to the only container if there is only one container in the pod.
+    pub container: Option<String>,
+}
+
+impl CopyParams {
+    /// Specify the container to copy to or from.
+    #[must_use]
+    pub fn container<T: Into<String>>(mut self, container: T) -> Self {
+        self.container = Some(container.into());
+        self
+    }
+
+    fn attach_params(&self, stdin: bool) -> AttachParams {
+        AttachParams {
+            container: self.container.clone(),
+            stdin,
+            stdout: !stdin,
+            stderr: true,
+            tty: false,
+            ..AttachParams::default()
+        }
+    }
+}
+
+impl<K> Api<K>
+where
+    K: Clone + DeserializeOwned + Execute,
+{
+    /// Copy a local file or directory into a container, like `kubectl cp`.
+    ///
+    /// The file or directory is created at `remote_path`, which must be absolute or relative to
+    /// the working directory of the container. Existing files are overwritten.
+    ///
+    /// This streams a tar archive to `tar` running in the container, which must therefore be available in its image.
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # use kube::{api::{Api, CopyParams}, Client};
+    /// # let client: Client = todo!();
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// pods.copy_to("my-pod", "./config", "/etc/app/config", &CopyParams::default()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_to(
+        &self,
+        name: &str,
+        local_path: impl AsRef<Path>,
+        remote_path: &str,
+        cp: &CopyParams,
+    ) -> Result<(), CopyError> {
+        let (remote_dir, entry_name) = split_remote_path(remote_path)
+            .ok_or_else(|| CopyError::InvalidRemotePath(remote_path.to_owned()))?;
+        let mut process = self
+            .exec(
+                name,
+                ["tar", "-xmf", "-", "-C", remote_dir],
+                &cp.attach_params(true),
+            )
+            .await
+            .map_err(CopyError::Exec)?;
+        let stdin = process.stdin().expect("stdin was requested");
+        let stderr = collect_stderr(&mut process);
+        let status = process.take_status().expect("status was not taken");
+
+        let local_path = local_path.as_ref().to_path_buf();
+        let entry_name = entry_name.to_owned();
+        let written = tokio::task::spawn_blocking(move || {
+            write_archive(SyncIoBridge::new(stdin), &local_path, &entry_name)
+        })
+        .await
+        .map_err(CopyError::Spawn)?;
+
+        let status = match written {
+            // Closing stdin disconnects from the container, so it is kept open until tar exits.
+            // tar stops reading at the end-of-archive marker, so it does not need to see the end of stdin.
+            Ok(stdin) => {
+                let status = status.await;
+                drop(stdin);
+                status
+            }
+            // tar may have exited early, in which case its error is more relevant than the closed pipe
+            Err(err) => match status.now_or_never().flatten() {
+                Some(status) if is_failure(&status) => Some(status),
+                _ => {
+                    process.abort();
+                    return Err(CopyError::WriteArchive(err));
+                }
+            },
+        };
+        check_status(status, stderr).await?;
+        process.join().await.map_err(CopyError::RemoteCommand)
+    }
+
+    /// Copy a file or directory from a container to the local filesystem, like `kubectl cp`.
+    ///
+    /// The file or directory is created at `local_path`. Existing files are overwritten.
+    /// Symlinks, hard links and special files in the container are skipped, so that they cannot
+    /// redirect files to outside of `local_path`.
+    ///
+    /// This streams a tar archive from `tar` running in the container, which must therefore be available in its image.
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # use kube::{api::{Api, CopyParams}, Client};
+    /// # let client: Client = todo!();
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// let cp = CopyParams::default().container("app");
+    /// pods.copy_from("my-pod", "/var/log/app", "./logs", &cp).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn copy_from(
+        &self,
+        name: &str,
+        remote_path: &str,
+        local_path: impl AsRef<Path>,
+        cp: &CopyParams,
+    ) -> Result<(), CopyError> {
+        let (remote_dir, entry_name) = split_remote_path(remote_path)
+            .ok_or_else(|| CopyError::InvalidRemotePath(remote_path.to_owned()))?;
+        let mut process = self
+            .exec(
+                name,
+                ["tar", "-cf", "-", "-C", remote_dir, entry_name],
+                &cp.attach_params(false),
+            )
+            .await
+            .map_err(CopyError::Exec)?;
+        let stdout = process.stdout().expect("stdout was requested");
+        let stderr = collect_stderr(&mut process);
+        let status = process.take_status().expect("status was not taken");
+
+        let local_path = local_path.as_ref().to_path_buf();
+        let entry_name = entry_name.to_owned();
+        let unpacked = tokio::task::spawn_blocking(move || {
+            unpack_archive(SyncIoBridge::new(stdout), &entry_name, &local_path)
+        })
+        .await
+        .map_err(CopyError::Spawn)?;
+
+        // An archive is still sent when tar fails on some of the files, so its status takes precedence
+        check_status(status.await, stderr).await?;
+        unpacked.map_err(CopyError::ReadArchive)?;
+        process.join().await.map_err(CopyError::RemoteCommand)
+    }
+}
+
+/// Splits a remote path into the directory to run tar in and the name of the entry to archive.
+///
+/// Returns `None` when the path does not end in a file or directory name, as for `/` or `dir/..`.
+fn split_remote_path(remote_path: &str) -> Option<(&str, &str)> {
+    let trimmed = remote_path.trim_end_matches('/');
+    let (dir, name) = match trimmed.rsplit_once('/') {
+        Some(("", name)) => ("/", name),
+        Some((dir, name)) => (dir, name),
+        None => (".", trimmed),
+    };
+    if name.is_empty() || name == "." || name == ".." {
+        return None;
+    }
+    Some((dir, name))
+}
+
+/// Reads stderr in the background, so that the remote command is never blocked on it.
+fn collect_stderr(process: &mut AttachedProcess) -> tokio::task::JoinHandle<String> {
+    let stderr = process.stderr().expect("stderr was requested");
+    tokio::spawn(read_lossy(stderr))
+}
+
+async fn read_lossy(mut reader: impl AsyncRead + Unpin) -> String {
+    let mut buf = Vec::new();
+    // Whatever was read before an error is still useful
+    let _ = reader.read_to_end(&mut buf).await;
+    String::from_utf8_lossy(&buf).trim().to_owned()
+}
+
+async fn check_status(
+    status: Option<Status>,
+    stderr: tokio::task::JoinHandle<String>,
+) -> Result<(), CopyError> {
+    match status {
+        Some(status) if is_failure(&status) => Err(CopyError::RemoteTar {
+            message: status.message.unwrap_or_default(),
+            stderr: stderr.await.unwrap_or_default(),
+        }),
+        Some(_) => Ok(()),
+        None => Err(CopyError::Disconnected),
+    }
+}
+
+fn is_failure(status: &Status) -> bool {
+    status.status.as_deref() == Some("Failure")
+}
+
+/// Writes `local_path` as a tar archive with a single top-level entry named `entry_name`.
+fn write_archive<W: Write>(writer: W, local_path: &Path, entry_name: &str) -> io::Result<W> {
+    let mut builder = tar::Builder::new(writer);
+    if local_path.is_dir() {
+        builder.append_dir_all(entry_name, local_path)?;
+    } else {
+        builder.append_path_with_name(local_path, entry_name)?;
+    }
+    // Writes the end-of-archive marker
+    let mut writer = builder.into_inner()?;
+    writer.flush()?;
+    Ok(writer)
+}
+
+/// Unpacks a tar archive with a single top-level entry named `entry_name` to `local_path`.
+///
+/// Only regular files and directories are unpacked, links could point anywhere on the local filesystem.
+fn unpack_archive<R: Read>(reader: R, entry_name: &str, local_path: &Path) -> io::Result<()> {
+    let mut archive = tar::Archive::new(reader);
+    for entry in archive.entries()? {
+        let mut entry = entry?;
+        let path = entry.path()?.into_owned();
+        let relative = path
+            .strip_prefix(entry_name)
+            .ok()
+            .filter(|relative| relative.components().all(|c| matches!(c, Component::Normal(_))))
+            .ok_or_else(|| {
+                io::Error::new(
+                    io::ErrorKind::InvalidData,
+                    format!("unexpected archive entry {}", path.display()),
+                )
+            })?;
+        let entry_type = entry.header().entry_type();
+        if !entry_type.is_file() && !entry_type.is_dir() {
+            tracing::warn!(path = %path.display(), ?entry_type, "skipping archive entry that is not a file or directory");
+            continue;
+        }
+        let dest: PathBuf = if relative.as_os_str().is_empty() {
+            local_path.to_path_buf()
+        } else {
+            local_path.join(relative)
+        };
+        if let Some(parent) = dest.parent() {
+            std::fs::create_dir_all(parent)?;
+        }
+        entry.unpack(&dest)?;
+    }
+    // tar pads the archive to a full record after the end-of-archive marker,
+    // which must be drained for the remote command to be able to exit
+    io::copy(&mut archive.into_inner(), &mut io::sink())?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{split_remote_path, unpack_archive, write_archive};
+
+    #[test]
+    fn splits_remote_paths() {
+        assert_eq!(
+            split_remote_path("/etc/app/config").unwrap(),
+            ("/etc/app", "config")
+        );
+        assert_eq!(split_remote_path("/etc/").unwrap(), ("/", "etc"));
+        assert_eq!(split_remote_path("config").unwrap(), (".", "config"));
+        assert!(split_remote_path("/").is_none());
+        assert!(split_remote_path("/etc/..").is_none());
+    }
+
+    #[test]
+    fn archive_roundtrip_renames_top_level_entry() -> Result<(), Box<dyn std::error::Error>> {
+        let src = tempfile::tempdir()?;
+        std::fs::create_dir(src.path().join("nested"))?;
+        std::fs::write(src.path().join("a.txt"), "a")?;
+        std::fs::write(src.path().join("nested").join("b.txt"), "b")?;
+
+        let archive = write_archive(Vec::new(), src.path(), "remote")?;
+        let dest = tempfile::tempdir()?;
+        let local = dest.path().join("local");
+        unpack_archive(archive.as_slice(), "remote", &local)?;
+
+        assert_eq!(std::fs::read_to_string(local.join("a.txt"))?, "a");
+        assert_eq!(std::fs::read_to_string(local.join("nested").join("b.txt"))?, "b");
+        Ok(())
+    }
+
+    #[test]
+    fn unpack_rejects_entries_outside_of_remote_path() -> Result<(), Box<dyn std::error::Error>> {
+        let src = tempfile::NamedTempFile::new()?;
+        let archive = write_archive(Vec::new(), src.path(), "other")?;
+        let dest = tempfile::tempdir()?;
+        assert!(unpack_archive(archive.as_slice(), "remote", &dest.path().join("local")).is_err());
+        Ok(())
+    }
+
+    #[test]
+    fn unpack_skips_links_out_of_local_path() -> Result<(), Box<dyn std::error::Error>> {
+        let outside = tempfile::tempdir()?;
+        let mut builder = tar::Builder::new(Vec::new());
+        let mut header = tar::Header::new_gnu();
+        header.set_entry_type(tar::EntryType::Symlink);
+        header.set_size(0);
+        builder.append_link(&mut header, "remote/link", outside.path())?;
+        let mut header = tar::Header::new_gnu();
+        header.set_size(4);
+        header.set_mode(0o644);
+        builder.append_data(&mut header, "remote/link/evil", "evil".as_bytes())?;
+        let archive = builder.into_inner()?;
+
+        let dest = tempfile::tempdir()?;
+        let local = dest.path().join("local");
+        unpack_archive(archive.as_slice(), "remote", &local)?;
+
+        assert!(!outside.path().join("evil").exists());
+        assert!(!local.join("link").is_symlink());
+        assert_eq!(std::fs::read_to_string(local.join("link").join("evil"))?, "evil");
+        Ok(())
+    }
+}
@@ -7,9 +7,12 @@
 use std::fmt::Debug;
 
 use crate::{Api, Error, Result};
-use kube_core::{params::PostParams, Resource};
+use kube_core::{params::PostParams, ErrorResponse, Resource};
 use serde::{de::DeserializeOwned, Serialize};
 
+/// How often [`Api::ensure_entry`] tries to commit before giving up on write conflicts
+const ENSURE_ENTRY_ATTEMPTS: usize = 5;
+
 impl<K: Resource + Clone + DeserializeOwned + Debug> Api<K> {
     /// Gets a given object's "slot" on the Kubernetes API, designed for "get-or-create" and "get-and-modify" patterns
     ///
@@ -56,6 +59,62 @@ impl<K: Resource + Clone + DeserializeOwned + Debug> Api<K> {
             None => Entry::Vacant(VacantEntry { api: self, name }),
         })
     }
+
+    /// Make sure that an object exists with the shape given by `modify`, retrying on write conflicts
+    ///
+    /// This runs `.or_insert(default).and_modify(modify).commit(pp)` on the [`Api::entry`], and starts over with
+    /// the latest object whenever another client created or modified it in the meantime. `modify` may therefore
+    /// be called several times, and should only change the fields it is responsible for.
+    /// Returns the object as saved by the API server.
+    ///
+    /// ```rust,no_run
+    /// # use k8s_openapi::api::core::v1::ConfigMap;
+    /// # async fn wrapper() -> Result <(), Box<dyn std::error::Error>> {
+    /// let kube = kube::Client::try_default().await?;
+    /// let cms = kube::Api::<ConfigMap>::namespaced(kube, "default");
+    /// let pp = kube::api::PostParams::default();
+    /// let cm = cms
+    ///     .ensure_entry("settings", &pp, ConfigMap::default, |cm| {
+    ///         let data = cm.data.get_or_insert_with(Default::default);
+    ///         data.insert("log-level".to_string(), "debug".to_string());
+    ///     })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails like [`OccupiedEntry::commit`], including with a conflict if the object kept changing
+    /// for several attempts in a row.
+    #[tracing::instrument(skip(self, pp, default, modify))]
+    pub async fn ensure_entry(
+        &self,
+        name: &str,
+        pp: &PostParams,
+        default: impl Fn() -> K,
+        modify: impl Fn(&mut K),
+    ) -> Result<K, CommitError>
+    where
+        K: Serialize,
+    {
+        let mut attempt = 1;
+        loop {
+            let entry = self.entry(name).await.map_err(CommitError::Save)?;
+            let mut entry = entry.or_insert(&default).and_modify(&modify);
+            match entry.commit(pp).await {
+                Ok(()) => return Ok(entry.into_object()),
+                // `AlreadyExists` when the object was created concurrently, `Conflict` when it was modified
+                Err(CommitError::Save(Error::Api(ErrorResponse { code: 409, .. })))
+                    if attempt < ENSURE_ENTRY_ATTEMPTS =>
+                {
+                    tracing::debug!(attempt, "write conflict, retrying with the latest object");
+                    attempt += 1;
+                }
+                Err(err) => return Err(err),
+            }
+        }
+    }
 }
 
 #[derive(Debug)]
@@ -586,4 +645,33 @@ mod tests {
         api.delete(object_name, &DeleteParams::default()).await?;
         Ok(())
     }
+
+    #[cfg(feature = "fake")]
+    #[tokio::test]
+    async fn ensure_entry_creates_then_updates() {
+        let client = crate::client::fake::FakeApiServer::new().with_resource::<ConfigMap>().client();
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+        let pp = PostParams::default();
+        let set = |value: &'static str| {
+            move |cm: &mut ConfigMap| {
+                let data = cm.data.get_or_insert_with(Default::default);
+                data.insert("mode".to_string(), value.to_string());
+            }
+        };
+
+        let created = api
+            .ensure_entry("settings", &pp, ConfigMap::default, set("debug"))
+            .await
+            .unwrap();
+        assert_eq!(created.data.unwrap()["mode"], "debug");
+        let updated = api
+            .ensure_entry("settings", &pp, ConfigMap::default, set("quiet"))
+            .await
+            .unwrap();
+        assert_eq!(updated.data.unwrap()["mode"], "quiet");
+        assert_ne!(
+            updated.metadata.resource_version,
+            created.metadata.resource_version
+        );
+    }
 }
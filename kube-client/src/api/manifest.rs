@@ -0,0 +1,254 @@
+//! Applying, creating and deleting the objects of multi-document YAML or JSON manifests
+//!
+//! [`Manifest`] is the primary entry point for this API.
+use either::Either;
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::{
+    api::{Api, DeleteParams, DynamicObject, GroupVersionKind, PatchParams, PostParams, ResourceExt},
+    discovery::{Discovery, Scope},
+    Client,
+};
+
+/// Errors from parsing a [`Manifest`], or from handling one of its objects
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    /// A document of the manifest is not valid YAML or JSON
+    #[error("failed to parse manifest document {0}: {1}")]
+    Parse(usize, #[source] serde_yaml::Error),
+
+    /// A document of the manifest is not a Kubernetes object
+    #[error("manifest document {0} is not an object with an apiVersion, kind and name")]
+    NotAnObject(usize),
+
+    /// The kind of an object is not served by the apiserver
+    #[error("{} of {} is not served by the apiserver", .0.kind, .0.api_version())]
+    UnknownKind(GroupVersionKind),
+
+    /// The request for an object failed
+    #[error("request failed: {0}")]
+    Request(#[source] crate::Error),
+}
+
+/// The objects of a multi-document YAML or JSON manifest, such as the output of `helm template`
+///
+/// Empty documents are skipped, and the items of `v1` `List` documents are treated as separate objects.
+/// The kinds of the objects are resolved with a [`Discovery`], so they can be of any kind the apiserver serves,
+/// including custom resources.
+///
+/// ```no_run
+/// use kube::{api::{manifest::Manifest, PatchParams}, Client, Discovery};
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::try_default().await?;
+/// let discovery = Discovery::new(client.clone()).run().await?;
+/// let manifest = Manifest::parse(&std::fs::read_to_string("bundle.yaml")?)?;
+/// let pp = PatchParams::apply("installer").force();
+/// for applied in manifest.apply(&client, &discovery, &pp).await {
+///     match applied.result {
+///         Ok(_) => println!("applied {} {}", applied.gvk.kind, applied.name),
+///         Err(err) => println!("failed to apply {} {}: {err}", applied.gvk.kind, applied.name),
+///     }
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Manifest {
+    objects: Vec<(GroupVersionKind, DynamicObject)>,
+}
+
+/// The outcome of applying, creating or deleting an object of a [`Manifest`]
+#[derive(Debug)]
+pub struct ObjectResult {
+    /// The kind of the object
+    pub gvk: GroupVersionKind,
+    /// The namespace of the object, as given in the manifest
+    pub namespace: Option<String>,
+    /// The name of the object
+    pub name: String,
+    /// The object as returned by the apiserver
+    ///
+    /// This is `None` for deletions that have completed, or for objects that did not exist.
+    pub result: Result<Option<DynamicObject>, ManifestError>,
+}
+
+impl Manifest {
+    /// Parse a manifest with any number of YAML documents separated by `---`, or JSON documents
+    pub fn parse(manifest: &str) -> Result<Self, ManifestError> {
+        let mut objects = Vec::new();
+        for (index, document) in serde_yaml::Deserializer::from_str(manifest).enumerate() {
+            let value =
+                serde_yaml::Value::deserialize(document).map_err(|err| ManifestError::Parse(index, err))?;
+            if value.is_null() {
+                continue;
+            }
+            let object: DynamicObject =
+                serde_yaml::from_value(value).map_err(|err| ManifestError::Parse(index, err))?;
+            let is_list = object
+                .types
+                .as_ref()
+                .is_some_and(|types| types.api_version == "v1" && types.kind == "List");
+            let items = if is_list {
+                let items = object.data.get("items").cloned().unwrap_or_default();
+                serde_json::from_value(items).map_err(|_| ManifestError::NotAnObject(index))?
+            } else {
+                vec![object]
+            };
+            for object in items {
+                let gvk = object
+                    .types
+                    .as_ref()
+                    .and_then(|types| GroupVersionKind::try_from(types).ok())
+                    .filter(|_| object.metadata.name.is_some())
+                    .ok_or(ManifestError::NotAnObject(index))?;
+                objects.push((gvk, object));
+            }
+        }
+        Ok(Self { objects })
+    }
+
+    /// The objects of the manifest, in order
+    pub fn objects(&self) -> impl Iterator<Item = &DynamicObject> {
+        self.objects.iter().map(|(_, object)| object)
+    }
+
+    /// Server-side apply every object in order, see [`Api::apply`]
+    ///
+    /// Namespaced objects without a namespace are applied in the default namespace of the `client`.
+    /// Failures are reported per object, and do not stop the remaining objects from being applied.
+    pub async fn apply(&self, client: &Client, discovery: &Discovery, pp: &PatchParams) -> Vec<ObjectResult> {
+        let mut results = Vec::with_capacity(self.objects.len());
+        for (gvk, object) in &self.objects {
+            let result = match api_for(client, discovery, gvk, object) {
+                Ok(api) => api
+                    .apply(&object.name_any(), pp, object)
+                    .await
+                    .map(Some)
+                    .map_err(ManifestError::Request),
+                Err(err) => Err(err),
+            };
+            results.push(ObjectResult::new(gvk, object, result));
+        }
+        results
+    }
+
+    /// Create every object in order, see [`Api::create`]
+    ///
+    /// Namespaced objects without a namespace are created in the default namespace of the `client`.
+    /// Failures are reported per object, and do not stop the remaining objects from being created.
+    pub async fn create(&self, client: &Client, discovery: &Discovery, pp: &PostParams) -> Vec<ObjectResult> {
+        let mut results = Vec::with_capacity(self.objects.len());
+        for (gvk, object) in &self.objects {
+            let result = match api_for(client, discovery, gvk, object) {
+                Ok(api) => api
+                    .create(pp, object)
+                    .await
+                    .map(Some)
+                    .map_err(ManifestError::Request),
+                Err(err) => Err(err),
+            };
+            results.push(ObjectResult::new(gvk, object, result));
+        }
+        results
+    }
+
+    /// Delete every object in reverse order, so that objects are deleted before the objects they depend on
+    ///
+    /// Objects that do not exist are reported as deleted.
+    /// Failures are reported per object, and do not stop the remaining objects from being deleted.
+    pub async fn delete(
+        &self,
+        client: &Client,
+        discovery: &Discovery,
+        dp: &DeleteParams,
+    ) -> Vec<ObjectResult> {
+        let mut results = Vec::with_capacity(self.objects.len());
+        for (gvk, object) in self.objects.iter().rev() {
+            let result = match api_for(client, discovery, gvk, object) {
+                Ok(api) => match api.delete(&object.name_any(), dp).await {
+                    Ok(Either::Left(deleting)) => Ok(Some(deleting)),
+                    Ok(Either::Right(_)) => Ok(None),
+                    Err(crate::Error::Api(err)) if err.code == 404 => Ok(None),
+                    Err(err) => Err(ManifestError::Request(err)),
+                },
+                Err(err) => Err(err),
+            };
+            results.push(ObjectResult::new(gvk, object, result));
+        }
+        results
+    }
+}
+
+impl ObjectResult {
+    fn new(
+        gvk: &GroupVersionKind,
+        object: &DynamicObject,
+        result: Result<Option<DynamicObject>, ManifestError>,
+    ) -> Self {
+        Self {
+            gvk: gvk.clone(),
+            namespace: object.namespace(),
+            name: object.name_any(),
+            result,
+        }
+    }
+}
+
+/// An [`Api`] for the kind and namespace of `object`
+fn api_for(
+    client: &Client,
+    discovery: &Discovery,
+    gvk: &GroupVersionKind,
+    object: &DynamicObject,
+) -> Result<Api<DynamicObject>, ManifestError> {
+    let (resource, caps) = discovery
+        .resolve_gvk(gvk)
+        .ok_or_else(|| ManifestError::UnknownKind(gvk.clone()))?;
+    Ok(match (caps.scope, object.namespace()) {
+        (Scope::Cluster, _) => Api::all_with(client.clone(), &resource),
+        (Scope::Namespaced, Some(ns)) => Api::namespaced_with(client.clone(), &ns, &resource),
+        (Scope::Namespaced, None) => Api::default_namespaced_with(client.clone(), &resource),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Manifest, ManifestError};
+
+    #[test]
+    fn manifest_documents_are_split_into_objects() {
+        let manifest = Manifest::parse(
+            r#"
+---
+apiVersion: v1
+kind: Namespace
+metadata:
+  name: blog
+---
+# only a comment
+---
+{"apiVersion": "v1", "kind": "List", "items": [
+  {"apiVersion": "v1", "kind": "ConfigMap", "metadata": {"name": "settings", "namespace": "blog"}},
+  {"apiVersion": "apps/v1", "kind": "Deployment", "metadata": {"name": "blog", "namespace": "blog"}}
+]}
+"#,
+        )
+        .unwrap();
+        let kinds: Vec<_> = manifest
+            .objects()
+            .map(|obj| obj.types.as_ref().unwrap().kind.as_str())
+            .collect();
+        assert_eq!(kinds, ["Namespace", "ConfigMap", "Deployment"]);
+
+        let unnamed = "apiVersion: v1\nkind: ConfigMap\nmetadata: {}\n";
+        assert!(matches!(
+            Manifest::parse(unnamed),
+            Err(ManifestError::NotAnObject(0))
+        ));
+        assert!(matches!(
+            Manifest::parse("a: [b"),
+            Err(ManifestError::Parse(0, _))
+        ));
+    }
+}
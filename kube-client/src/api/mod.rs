@@ -4,19 +4,25 @@ mod core_methods;
 #[cfg(feature = "ws")] mod remote_command;
 use std::fmt::Debug;
 
-#[cfg(feature = "ws")] pub use remote_command::{AttachedProcess, TerminalSize};
+#[cfg(feature = "ws")]
+pub use remote_command::{AttachedProcess, TerminalSize, Tty};
 #[cfg(feature = "ws")] mod portforward;
 #[cfg(feature = "ws")] pub use portforward::Portforwarder;
+#[cfg(feature = "cp")] mod cp;
+#[cfg(feature = "cp")]
+#[cfg_attr(docsrs, doc(cfg(feature = "cp")))]
+pub use cp::{CopyError, CopyParams};
 
 mod subresource;
 #[cfg(feature = "ws")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
-pub use subresource::{Attach, AttachParams, Ephemeral, Execute, Portforward};
-pub use subresource::{Evict, EvictParams, Log, LogParams, ScaleSpec, ScaleStatus};
+pub use subresource::{Attach, AttachParams, Ephemeral, Execute, KeepaliveParams, Portforward};
+pub use subresource::{Evict, EvictParams, Log, LogParams, Proxy, ScaleSpec, ScaleStatus};
 
 mod util;
 
 pub mod entry;
+pub mod manifest;
 
 // Re-exports from kube-core
 #[cfg(feature = "admission")]
@@ -29,6 +35,7 @@ pub use kube_core::{
     metadata::{ListMeta, ObjectMeta, PartialObjectMeta, PartialObjectMetaExt, TypeMeta},
     object::{NotUsed, Object, ObjectList},
     request::Request,
+    table::{Table, TableColumnDefinition, TableRow, TableRowCondition},
     watch::WatchEvent,
     Resource, ResourceExt,
 };
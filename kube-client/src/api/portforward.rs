@@ -6,10 +6,15 @@ use futures::{
     future, FutureExt, SinkExt, StreamExt,
 };
 use thiserror::Error;
-use tokio::io::{AsyncRead, AsyncWrite, AsyncWriteExt, DuplexStream};
+use tokio::{
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt, DuplexStream},
+    time::Instant,
+};
 use tokio_tungstenite::{tungstenite as ws, WebSocketStream};
 use tokio_util::io::ReaderStream;
 
+use super::KeepaliveParams;
+
 /// Errors from Portforwarder.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -64,6 +69,10 @@ pub enum Error {
     /// Failed to shutdown a pod writer channel.
     #[error("failed to shutdown write to Pod channel: {0}")]
     Shutdown(#[source] std::io::Error),
+
+    /// The server sent nothing for longer than the idle timeout, see [`KeepaliveParams`]
+    #[error("connection idle for longer than {0:?}")]
+    IdleTimeout(std::time::Duration),
 }
 
 type ErrorReceiver = oneshot::Receiver<String>;
@@ -89,7 +98,7 @@ pub struct Portforwarder {
 }
 
 impl Portforwarder {
-    pub(crate) fn new<S>(stream: WebSocketStream<S>, port_nums: &[u16]) -> Self
+    pub(crate) fn new<S>(stream: WebSocketStream<S>, port_nums: &[u16], keepalive: KeepaliveParams) -> Self
     where
         S: AsyncRead + AsyncWrite + Unpin + Sized + Send + 'static,
     {
@@ -111,6 +120,7 @@ impl Portforwarder {
             port_nums.to_vec(),
             task_ios,
             error_txs,
+            keepalive,
         ));
 
         Portforwarder {
@@ -163,6 +173,7 @@ async fn start_message_loop<S>(
     ports: Vec<u16>,
     duplexes: Vec<DuplexStream>,
     error_senders: Vec<Option<ErrorSender>>,
+    keepalive: KeepaliveParams,
 ) -> Result<(), Error>
 where
     S: AsyncRead + AsyncWrite + Unpin + Sized + Send + 'static,
@@ -182,8 +193,18 @@ where
     }
 
     let (ws_sink, ws_stream) = stream.split();
-    loops.push(from_pod_loop(ws_stream, sender).boxed());
-    loops.push(forwarder_loop(&ports, receiver, ws_sink, writers, error_senders).boxed());
+    loops.push(from_pod_loop(ws_stream, sender, keepalive.idle_timeout).boxed());
+    loops.push(
+        forwarder_loop(
+            &ports,
+            receiver,
+            ws_sink,
+            writers,
+            error_senders,
+            keepalive.ping_interval,
+        )
+        .boxed(),
+    );
 
     future::try_join_all(loops).await.map(|_| ())
 }
@@ -217,16 +238,21 @@ async fn to_pod_loop(
 async fn from_pod_loop<S>(
     mut ws_stream: futures::stream::SplitStream<WebSocketStream<S>>,
     mut sender: mpsc::Sender<Message>,
+    idle_timeout: Option<std::time::Duration>,
 ) -> Result<(), Error>
 where
     S: AsyncRead + AsyncWrite + Unpin + Sized + Send + 'static,
 {
-    while let Some(msg) = ws_stream
-        .next()
-        .await
-        .transpose()
-        .map_err(Error::ReceiveWebSocketMessage)?
-    {
+    loop {
+        let next = match idle_timeout {
+            Some(timeout) => tokio::time::timeout(timeout, ws_stream.next())
+                .await
+                .map_err(|_| Error::IdleTimeout(timeout))?,
+            None => ws_stream.next().await,
+        };
+        let Some(msg) = next.transpose().map_err(Error::ReceiveWebSocketMessage)? else {
+            break;
+        };
         match msg {
             ws::Message::Binary(mut bytes) if bytes.len() > 1 => {
                 let ch = bytes.split_to(1)[0];
@@ -253,12 +279,14 @@ where
 // On `Message::ToPod(ch, bytes)`, a WebSocket message is sent with the channel prefix.
 // On `Message::FromPod(ch, bytes)` with an even `ch`, `bytes` are written to the port's sink.
 // On `Message::FromPod(ch, bytes)` with an odd `ch`, an error message is sent to the error channel of the port.
+// Every `ping_interval`, a ping is sent to keep the connection alive.
 async fn forwarder_loop<S>(
     ports: &[u16],
     mut receiver: mpsc::Receiver<Message>,
     mut ws_sink: futures::stream::SplitSink<WebSocketStream<S>, ws::Message>,
     mut writers: Vec<tokio::io::WriteHalf<DuplexStream>>,
     mut error_senders: Vec<Option<ErrorSender>>,
+    ping_interval: Option<std::time::Duration>,
 ) -> Result<(), Error>
 where
     S: AsyncRead + AsyncWrite + Unpin + Sized + Send + 'static,
@@ -273,7 +301,31 @@ where
     let mut chan_state = vec![ChannelState::default(); 2 * ports.len()];
     let mut closed_ports = 0;
     let mut socket_shutdown = false;
-    while let Some(msg) = receiver.next().await {
+    let mut ping = ping_interval.map(|period| {
+        let mut ping = tokio::time::interval_at(Instant::now() + period, period);
+        ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ping
+    });
+    loop {
+        let ping_next = async {
+            match ping.as_mut() {
+                Some(ping) => ping.tick().await,
+                None => future::pending().await,
+            }
+        };
+        let msg = tokio::select! {
+            msg = receiver.next() => msg,
+            _ = ping_next, if !socket_shutdown => {
+                ws_sink
+                    .send(ws::Message::Ping(Default::default()))
+                    .await
+                    .map_err(Error::SendWebSocketMessage)?;
+                continue;
+            }
+        };
+        let Some(msg) = msg else {
+            break;
+        };
         match msg {
             Message::FromPod(ch, mut bytes) => {
                 let ch = ch as usize;
@@ -1,23 +1,28 @@
-use std::future::Future;
+use std::{
+    future::Future,
+    pin::Pin,
+    task::{Context, Poll},
+};
 
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::Status;
 
 use futures::{
     channel::{mpsc, oneshot},
-    FutureExt, SinkExt, StreamExt,
+    future, FutureExt, SinkExt, StreamExt,
 };
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 use tokio::{
-    io::{AsyncRead, AsyncWrite, AsyncWriteExt, DuplexStream},
+    io::{AsyncRead, AsyncWrite, AsyncWriteExt, DuplexStream, ReadBuf},
     select,
+    time::Instant,
 };
 use tokio_tungstenite::{
     tungstenite::{self as ws},
     WebSocketStream,
 };
 
-use super::AttachParams;
+use super::{AttachParams, KeepaliveParams};
 
 type StatusReceiver = oneshot::Receiver<Status>;
 type StatusSender = oneshot::Sender<Status>;
@@ -26,7 +31,7 @@ type TerminalSizeReceiver = mpsc::Receiver<TerminalSize>;
 type TerminalSizeSender = mpsc::Sender<TerminalSize>;
 
 /// TerminalSize define the size of a terminal
-#[derive(Debug, Serialize, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Serialize, Deserialize)]
 #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
 #[serde(rename_all = "PascalCase")]
 pub struct TerminalSize {
@@ -36,6 +41,13 @@ pub struct TerminalSize {
     pub height: u16,
 }
 
+impl TerminalSize {
+    /// A terminal of `width` columns and `height` rows
+    pub const fn new(width: u16, height: u16) -> Self {
+        Self { width, height }
+    }
+}
+
 /// Errors from attaching to a pod.
 #[derive(Debug, Error)]
 pub enum Error {
@@ -86,6 +98,14 @@ pub enum Error {
     /// Failed to set terminal size, tty need to be true to resize the terminal
     #[error("failed to set terminal size, tty need to be true to resize the terminal")]
     TtyNeedToBeTrue,
+
+    /// Failed to send a keepalive ping
+    #[error("failed to send a keepalive ping: {0}")]
+    SendPing(#[source] ws::Error),
+
+    /// The server sent nothing for longer than the idle timeout, see [`KeepaliveParams`]
+    #[error("connection idle for longer than {0:?}")]
+    IdleTimeout(std::time::Duration),
 }
 
 const MAX_BUF_SIZE: usize = 1024;
@@ -146,6 +166,7 @@ impl AttachedProcess {
             stderr_writer,
             status_tx,
             terminal_resize_rx,
+            ap.keepalive,
         ));
 
         AttachedProcess {
@@ -238,6 +259,43 @@ impl AttachedProcess {
         self.status_rx.take().map(|recv| recv.map(|res| res.ok()))
     }
 
+    /// Raw terminal of the process, for interactive terminal wrappers
+    ///
+    /// With `tty`, the process has a single terminal rather than separate streams: whatever is written to it
+    /// is typed into the terminal as is, including control characters such as `Ctrl-C`, and reading it
+    /// gives everything the process prints. The local terminal should be in raw mode to forward keys as typed.
+    /// Window size changes are forwarded with the returned [`TerminalSize`] sender.
+    ///
+    /// ```no_run
+    /// # use kube_client::api::{AttachedProcess, TerminalSize};
+    /// # use futures::SinkExt;
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let mut attached: AttachedProcess = todo!();
+    /// # let mut local_terminal: tokio::io::DuplexStream = todo!();
+    /// let (mut tty, mut resize) = attached.tty().unwrap();
+    /// resize.send(TerminalSize::new(80, 24)).await?;
+    /// tokio::io::copy_bidirectional(&mut tty, &mut local_terminal).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// Only available if [`AttachParams`](super::AttachParams) had `tty`, `stdin` and `stdout`,
+    /// and neither of them was taken before.
+    pub fn tty(&mut self) -> Option<(Tty, TerminalSizeSender)> {
+        let resize = self.terminal_resize_tx.clone()?;
+        if !self.has_stdin || !self.has_stdout {
+            return None;
+        }
+        match (self.stdin_writer.take(), self.stdout_reader.take()) {
+            (Some(stdin), Some(stdout)) => Some((Tty { stdin, stdout }, resize)),
+            (stdin, stdout) => {
+                self.stdin_writer = stdin;
+                self.stdout_reader = stdout;
+                None
+            }
+        }
+    }
+
     /// Async writer to change the terminal size
     /// ```no_run
     /// # use kube_client::api::{AttachedProcess, TerminalSize};
@@ -254,8 +312,46 @@ impl AttachedProcess {
     /// # }
     /// ```
     /// Only available if [`AttachParams`](super::AttachParams) had `tty`.
+    /// Each call returns a new sender, the terminal is resized by the latest size sent on any of them.
     pub fn terminal_size(&mut self) -> Option<TerminalSizeSender> {
-        self.terminal_resize_tx.take()
+        self.terminal_resize_tx.clone()
+    }
+}
+
+/// Raw terminal of a process attached with `tty`, see [`AttachedProcess::tty`]
+///
+/// Writes go to the stdin of the process, and reads come from its output.
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+pub struct Tty {
+    stdin: DuplexStream,
+    stdout: DuplexStream,
+}
+
+impl AsyncRead for Tty {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &mut ReadBuf<'_>,
+    ) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stdout).poll_read(cx, buf)
+    }
+}
+
+impl AsyncWrite for Tty {
+    fn poll_write(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: &[u8],
+    ) -> Poll<std::io::Result<usize>> {
+        Pin::new(&mut self.stdin).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stdin).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<std::io::Result<()>> {
+        Pin::new(&mut self.stdin).poll_shutdown(cx)
     }
 }
 
@@ -275,6 +371,7 @@ async fn start_message_loop<S>(
     mut stderr: Option<impl AsyncWrite + Unpin>,
     status_tx: StatusSender,
     mut terminal_size_rx: Option<TerminalSizeReceiver>,
+    keepalive: KeepaliveParams,
 ) -> Result<(), Error>
 where
     S: AsyncRead + AsyncWrite + Unpin + Sized + Send + 'static,
@@ -284,6 +381,12 @@ where
     // Work with filtered messages to reduce noise.
     let mut server_recv = raw_server_recv.filter_map(filter_message).boxed();
     let mut have_terminal_size_rx = terminal_size_rx.is_some();
+    let mut ping = keepalive.ping_interval.map(|period| {
+        let mut ping = tokio::time::interval_at(Instant::now() + period, period);
+        ping.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+        ping
+    });
+    let mut last_activity = Instant::now();
 
     loop {
         let terminal_size_next = async {
@@ -292,9 +395,24 @@ where
                 None => None,
             }
         };
+        let ping_next = async {
+            match ping.as_mut() {
+                Some(ping) => ping.tick().await,
+                None => future::pending().await,
+            }
+        };
+        let idle_deadline = keepalive.idle_timeout.map(|timeout| last_activity + timeout);
+        let idle = async move {
+            match idle_deadline {
+                Some(deadline) => tokio::time::sleep_until(deadline).await,
+                None => future::pending().await,
+            }
+        };
         select! {
             server_message = server_recv.next() => {
+                last_activity = Instant::now();
                 match server_message {
+                    Some(Ok(Message::Keepalive)) => {},
                     Some(Ok(Message::Stdout(bin))) => {
                         if let Some(stdout) = stdout.as_mut() {
                             stdout.write_all(&bin[1..]).await.map_err(Error::WriteStdout)?;
@@ -357,6 +475,12 @@ where
                     }
                 }
             },
+            _ = ping_next => {
+                server_send.send(ws::Message::Ping(Default::default())).await.map_err(Error::SendPing)?;
+            },
+            () = idle => {
+                return Err(Error::IdleTimeout(keepalive.idle_timeout.unwrap_or_default()));
+            },
         }
     }
 
@@ -371,6 +495,8 @@ enum Message {
     Stderr(Vec<u8>),
     /// To error/status channel (3)
     Status(Vec<u8>),
+    /// Ping or pong, which only shows that the connection is alive
+    Keepalive,
 }
 
 // Filter to reduce all the possible WebSocket messages into a few we expect to receive.
@@ -385,6 +511,7 @@ async fn filter_message(wsm: Result<ws::Message, ws::Error>) -> Option<Result<Me
             // We don't receive messages to stdin and resize channels.
             _ => None,
         },
+        Ok(ws::Message::Ping(_) | ws::Message::Pong(_)) => Some(Ok(Message::Keepalive)),
         // Ignore any other message types.
         // We can ignore close message because the server never sends anything special.
         // The connection terminates on `None`.
@@ -1,18 +1,20 @@
 use futures::AsyncBufRead;
+use http_body_util::BodyExt;
 use serde::{de::DeserializeOwned, Serialize};
 use std::fmt::Debug;
 
 use crate::{
     api::{Api, Patch, PatchParams, PostParams},
+    client::Body,
     Error, Result,
 };
 
-use kube_core::response::Status;
 pub use kube_core::subresource::{EvictParams, LogParams};
+use kube_core::{response::Status, ErrorResponse};
 
 #[cfg(feature = "ws")]
 #[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
-pub use kube_core::subresource::AttachParams;
+pub use kube_core::subresource::{AttachParams, KeepaliveParams};
 
 pub use k8s_openapi::api::autoscaling::v1::{Scale, ScaleSpec, ScaleStatus};
 
@@ -34,6 +36,15 @@ where
         self.client.request::<Scale>(req).await
     }
 
+    /// [Fetch the scale subresource](`Api::get_scale`) if the resource exists, returns [`None`] if it doesn't exist
+    pub async fn get_scale_opt(&self, name: &str) -> Result<Option<Scale>> {
+        match self.get_scale(name).await {
+            Ok(scale) => Ok(Some(scale)),
+            Err(Error::Api(ErrorResponse { reason, .. })) if &reason == "NotFound" => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Update the scale subresource
     pub async fn patch_scale<P: serde::Serialize + Debug>(
         &self,
@@ -75,6 +86,17 @@ where
         self.client.request::<K>(req).await
     }
 
+    /// [Display a sub-resource](`Api::get_subresource`) if it exists, returns [`None`] if it doesn't exist
+    ///
+    /// Note that this also returns [`None`] when the resource does not have the subresource.
+    pub async fn get_subresource_opt(&self, subresource_name: &str, name: &str) -> Result<Option<K>> {
+        match self.get_subresource(subresource_name, name).await {
+            Ok(obj) => Ok(Some(obj)),
+            Err(Error::Api(ErrorResponse { reason, .. })) if &reason == "NotFound" => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Create an instance of the subresource
     pub async fn create_subresource<T>(
         &self,
@@ -304,6 +326,28 @@ where
         self.client.request::<K>(req).await
     }
 
+    /// [Get the named resource with a status subresource](`Api::get_status`) if it exists, returns [`None`] if it doesn't exist
+    ///
+    /// ```no_run
+    /// # use kube::Api;
+    /// use k8s_openapi::api::batch::v1::Job;
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let jobs: Api<Job> = Api::namespaced(client, "apps");
+    /// if let Some(job) = jobs.get_status_opt("migrate").await? {
+    ///     println!("{:?}", job.status);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn get_status_opt(&self, name: &str) -> Result<Option<K>> {
+        match self.get_status(name).await {
+            Ok(obj) => Ok(Some(obj)),
+            Err(Error::Api(ErrorResponse { reason, .. })) if &reason == "NotFound" => Ok(None),
+            Err(err) => Err(err),
+        }
+    }
+
     /// Patch fields on the status object
     ///
     /// NB: Requires that the resource has a status subresource.
@@ -368,6 +412,25 @@ where
     }
 }
 
+#[cfg(feature = "fake")]
+#[tokio::test]
+async fn optional_gets_of_subresources() {
+    use crate::client::fake::FakeApiServer;
+    use k8s_openapi::api::core::v1::ConfigMap;
+
+    let existing: ConfigMap = serde_json::from_value(serde_json::json!({
+        "metadata": { "name": "web", "namespace": "default" },
+    }))
+    .unwrap();
+    let client = FakeApiServer::new().with_object(&existing).client();
+    let api: Api<ConfigMap> = Api::default_namespaced(client);
+
+    assert!(api.get_status_opt("web").await.unwrap().is_some());
+    assert!(api.get_status_opt("db").await.unwrap().is_none());
+    assert!(api.get_metadata_opt("db").await.unwrap().is_none());
+    assert!(api.get_subresource_opt("status", "db").await.unwrap().is_none());
+}
+
 // ----------------------------------------------------------------------------
 // Log subresource
 // ----------------------------------------------------------------------------
@@ -443,10 +506,54 @@ where
 fn evict_path() {
     use crate::api::{Request, Resource};
     use k8s_openapi::api::core::v1 as corev1;
-    let ep = EvictParams::default();
+    let ep = EvictParams::default().grace_period(10);
     let url = corev1::Pod::url_path(&(), Some("ns"));
     let req = Request::new(url).evict("foo", &ep).unwrap();
     assert_eq!(req.uri(), "/api/v1/namespaces/ns/pods/foo/eviction?");
+    let body: serde_json::Value = serde_json::from_slice(req.body()).unwrap();
+    assert_eq!(
+        body,
+        serde_json::json!({
+            "apiVersion": "policy/v1",
+            "kind": "Eviction",
+            "metadata": { "name": "foo" },
+            "deleteOptions": { "gracePeriodSeconds": 10 },
+        })
+    );
+}
+
+#[test]
+fn evict_blocked_by_disruption_budget() {
+    let blocked = ErrorResponse {
+        status: "Failure".into(),
+        message: "Cannot evict pod as it would violate the pod's disruption budget.".into(),
+        reason: "TooManyRequests".into(),
+        code: 429,
+        details: None,
+    };
+    assert!(is_blocked_by_disruption_budget(&blocked));
+    let throttled = ErrorResponse {
+        message: "Too many requests, please try again later.".into(),
+        ..blocked.clone()
+    };
+    assert!(!is_blocked_by_disruption_budget(&throttled));
+    let other_reason = ErrorResponse {
+        reason: "Conflict".into(),
+        ..blocked.clone()
+    };
+    assert!(!is_blocked_by_disruption_budget(&other_reason));
+
+    // Causes take precedence over the message
+    let with_causes = |reason: &str| ErrorResponse {
+        message: "Cannot evict pod.".into(),
+        details: serde_json::from_value(serde_json::json!({
+            "causes": [{ "reason": reason, "message": "The disruption budget web needs 2 healthy pods and has 1 currently" }],
+        }))
+        .unwrap(),
+        ..blocked.clone()
+    };
+    assert!(is_blocked_by_disruption_budget(&with_causes("DisruptionBudget")));
+    assert!(!is_blocked_by_disruption_budget(&with_causes("Throttled")));
 }
 
 /// Marker trait for objects that can be evicted
@@ -460,11 +567,33 @@ impl<K> Api<K>
 where
     K: DeserializeOwned + Evict,
 {
-    /// Create an eviction
+    /// Create a `policy/v1` eviction
+    ///
+    /// Evictions respect `PodDisruptionBudget`s, and fail with [`Error::EvictionBlocked`]
+    /// while an eviction would violate one.
     pub async fn evict(&self, name: &str, ep: &EvictParams) -> Result<Status> {
         let mut req = self.request.evict(name, ep).map_err(Error::BuildRequest)?;
         req.extensions_mut().insert("evict");
-        self.client.request::<Status>(req).await
+        self.client.request::<Status>(req).await.map_err(|err| match err {
+            Error::Api(response) if is_blocked_by_disruption_budget(&response) => {
+                Error::EvictionBlocked(response)
+            }
+            err => err,
+        })
+    }
+}
+
+/// Whether an eviction failed because of a disruption budget, rather than API priority and fairness throttling
+///
+/// Both are reported as `429 TooManyRequests`, but the apiserver adds a `DisruptionBudget` cause to the former.
+/// Matching the message is only a fallback for responses without causes, since messages are not part of the API.
+fn is_blocked_by_disruption_budget(response: &ErrorResponse) -> bool {
+    if response.code != 429 || response.reason != "TooManyRequests" {
+        return false;
+    }
+    match response.causes() {
+        [] => response.message.contains("disruption budget"),
+        causes => causes.iter().any(|cause| cause.reason == "DisruptionBudget"),
     }
 }
 
@@ -602,11 +731,164 @@ where
 {
     /// Forward ports of a pod
     pub async fn portforward(&self, name: &str, ports: &[u16]) -> Result<Portforwarder> {
+        self.portforward_with_keepalive(name, ports, KeepaliveParams::default())
+            .await
+    }
+
+    /// Forward ports of a pod, keeping the connection alive for long sessions
+    ///
+    /// See [`KeepaliveParams`] for what this does and does not protect against.
+    pub async fn portforward_with_keepalive(
+        &self,
+        name: &str,
+        ports: &[u16],
+        keepalive: KeepaliveParams,
+    ) -> Result<Portforwarder> {
         let req = self
             .request
             .portforward(name, ports)
             .map_err(Error::BuildRequest)?;
         let stream = self.client.connect(req).await?;
-        Ok(Portforwarder::new(stream, ports))
+        Ok(Portforwarder::new(stream, ports, keepalive))
+    }
+}
+
+// ----------------------------------------------------------------------------
+// Proxy subresource
+// ----------------------------------------------------------------------------
+
+#[test]
+fn proxy_path() {
+    use crate::api::{Request, Resource};
+    use k8s_openapi::api::core::v1 as corev1;
+    let url = corev1::Node::url_path(&(), None);
+    let req = http::Request::get("/?verbose=1").body(vec![]).unwrap();
+    let req = Request::new(url).proxy("foo", "healthz", req).unwrap();
+    assert_eq!(req.uri(), "/api/v1/nodes/foo/proxy/healthz?verbose=1");
+}
+
+/// Marker trait for objects that the apiserver can proxy requests to
+///
+/// See [`Api::proxy_request`] for usage.
+pub trait Proxy {}
+
+impl Proxy for k8s_openapi::api::core::v1::Node {}
+impl Proxy for k8s_openapi::api::core::v1::Pod {}
+impl Proxy for k8s_openapi::api::core::v1::Service {}
+
+impl<K> Api<K>
+where
+    K: DeserializeOwned + Proxy,
+{
+    /// Send a request to `subpath` on an object through the apiserver proxy
+    ///
+    /// This reaches the kubelet of a node, or the endpoints of a service, without direct network access to them.
+    /// The `name` can include a port, like `my-node:10250` or `my-service:http`, and services can also be
+    /// prefixed by a scheme, like `https:my-service:443`.
+    ///
+    /// The method, headers, body and query of `req` are kept, only its path is replaced.
+    /// The response is returned as is, error statuses of the proxied endpoint included.
+    /// Requires the `proxy` verb on the `nodes/proxy` or `services/proxy` subresource.
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use k8s_openapi::api::core::v1::Node;
+    /// # use kube::{Api, Client};
+    /// # let client: Client = todo!();
+    /// use http_body_util::BodyExt;
+    ///
+    /// let nodes: Api<Node> = Api::all(client);
+    /// let req = http::Request::get("/").body(vec![])?;
+    /// let res = nodes.proxy_request("my-node", "metrics/resource", req).await?;
+    /// let metrics = res.into_body().collect().await?.to_bytes();
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn proxy_request(
+        &self,
+        name: &str,
+        subpath: &str,
+        req: http::Request<Vec<u8>>,
+    ) -> Result<http::Response<Body>> {
+        let mut req = self
+            .request
+            .proxy(name, subpath, req)
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("proxy_request");
+        self.client.send(req.map(Body::from)).await
+    }
+
+    /// Get `path` on a port of an object through the apiserver proxy, such as the admin endpoint of a sidecar
+    ///
+    /// The `path` can include a query, like `stats?format=json`.
+    /// Error statuses of the proxied endpoint are returned as [`Error::Api`].
+    ///
+    /// ```no_run
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # use kube::{Api, Client};
+    /// # let client: Client = todo!();
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// let ready = pods.proxy_get("my-pod", 15000, "ready").await?;
+    /// let stats: serde_json::Value = pods.proxy_get_json("my-pod", 15000, "stats?format=json").await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn proxy_get(&self, name: &str, port: u16, path: &str) -> Result<Vec<u8>> {
+        let mut req = self
+            .request
+            .proxy_get(name, port, path)
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("proxy_get");
+        self.proxy_bytes(req).await
+    }
+
+    /// Get `path` on a port of an object through the apiserver proxy, and deserialize the response as JSON
+    ///
+    /// See [`Api::proxy_get`].
+    pub async fn proxy_get_json<T: DeserializeOwned>(&self, name: &str, port: u16, path: &str) -> Result<T> {
+        let bytes = self.proxy_get(name, port, path).await?;
+        serde_json::from_slice(&bytes).map_err(Error::SerdeError)
+    }
+
+    /// Post `data` as JSON to `path` on a port of an object through the apiserver proxy
+    ///
+    /// The `path` can include a query, like `drain?timeout=30s`.
+    /// Error statuses of the proxied endpoint are returned as [`Error::Api`].
+    pub async fn proxy_post<B: Serialize>(
+        &self,
+        name: &str,
+        port: u16,
+        path: &str,
+        data: &B,
+    ) -> Result<Vec<u8>> {
+        let bytes = serde_json::to_vec(data).map_err(Error::SerdeError)?;
+        let mut req = self
+            .request
+            .proxy_post(name, port, path, bytes)
+            .map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("proxy_post");
+        self.proxy_bytes(req).await
+    }
+
+    /// Post `data` as JSON to `path` on a port of an object through the apiserver proxy,
+    /// and deserialize the response as JSON
+    ///
+    /// See [`Api::proxy_post`].
+    pub async fn proxy_post_json<T: DeserializeOwned, B: Serialize>(
+        &self,
+        name: &str,
+        port: u16,
+        path: &str,
+        data: &B,
+    ) -> Result<T> {
+        let bytes = self.proxy_post(name, port, path, data).await?;
+        serde_json::from_slice(&bytes).map_err(Error::SerdeError)
+    }
+
+    async fn proxy_bytes(&self, req: http::Request<Vec<u8>>) -> Result<Vec<u8>> {
+        let res = self.client.send(req.map(Body::from)).await?;
+        let res = crate::client::handle_api_errors(res).await?;
+        Ok(res.into_body().collect().await?.to_bytes().to_vec())
     }
 }
@@ -1,28 +1,49 @@
 use crate::{
-    api::{Api, Resource},
+    api::{Api, ApiResource, DynamicObject, Resource},
     Error, Result,
 };
 use k8s_openapi::api::{
     authentication::v1::TokenRequest,
     core::v1::{Node, ServiceAccount},
 };
-use kube_core::{params::PostParams, util::Restart};
+use kube_core::{params::PostParams, util::Restart, TypeMeta};
 use serde::de::DeserializeOwned;
 
 mod csr;
 
 impl<K> Api<K>
 where
-    K: Restart + Resource + DeserializeOwned,
+    K: Restart + Resource<DynamicType = ()> + DeserializeOwned,
 {
-    /// Trigger a restart of a Resource.
+    /// Trigger a restart of a workload, like `kubectl rollout restart`.
+    ///
+    /// See [`Request::restart`](kube_core::Request::restart) for how the restart is applied.
     pub async fn restart(&self, name: &str) -> Result<K> {
-        let mut req = self.request.restart(name).map_err(Error::BuildRequest)?;
+        let mut req = self
+            .request
+            .restart(name, &TypeMeta::resource::<K>())
+            .map_err(Error::BuildRequest)?;
         req.extensions_mut().insert("restart");
         self.client.request::<K>(req).await
     }
 }
 
+impl Api<DynamicObject> {
+    /// Trigger a restart of a workload of a discovered kind, like `kubectl rollout restart`.
+    ///
+    /// Fails with [`request::Error::UnsupportedRestart`](kube_core::request::Error::UnsupportedRestart)
+    /// if `ar` is not a Deployment, DaemonSet or StatefulSet.
+    pub async fn restart_with(&self, name: &str, ar: &ApiResource) -> Result<DynamicObject> {
+        let types = TypeMeta {
+            api_version: ar.api_version.clone(),
+            kind: ar.kind.clone(),
+        };
+        let mut req = self.request.restart(name, &types).map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("restart");
+        self.client.request::<DynamicObject>(req).await
+    }
+}
+
 impl Api<Node> {
     /// Cordon a Node.
     pub async fn cordon(&self, name: &str) -> Result<Node> {
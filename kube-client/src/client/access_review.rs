@@ -0,0 +1,69 @@
+use k8s_openapi::api::authorization::v1::{SelfSubjectAccessReview, SubjectAccessReview};
+use kube_core::{
+    access_review::{AccessCheck, AccessDecision},
+    params::PostParams,
+};
+
+use crate::{Api, Client, Result};
+
+/// Checks of whether requests are authorized, like `kubectl auth can-i`
+impl Client {
+    /// Check whether this client is allowed to `verb` the `resource`, in `namespace` or in all namespaces
+    ///
+    /// The `resource` is given like to `kubectl auth can-i`, such as `pods`, `deployments.apps` or `pods/log`.
+    /// See [`Client::review_access`] for the reason of a decision, or to check specific objects.
+    ///
+    /// ```no_run
+    /// # async fn doc(client: kube::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// if !client.can_i("patch", "deployments.apps", Some("default")).await? {
+    ///     println!("missing RBAC permissions to patch deployments");
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn can_i(&self, verb: &str, resource: &str, namespace: Option<&str>) -> Result<bool> {
+        let mut check = AccessCheck::new(verb, resource);
+        if let Some(namespace) = namespace {
+            check = check.namespace(namespace);
+        }
+        Ok(self.review_access(&check).await?.allowed)
+    }
+
+    /// Review whether this client is allowed to make the request of `check`, with a [`SelfSubjectAccessReview`]
+    ///
+    /// Use [`AccessDecision::require`] to turn denials into actionable errors:
+    ///
+    /// ```no_run
+    /// # async fn doc(client: kube::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use k8s_openapi::api::core::v1::Secret;
+    /// use kube::core::access_review::AccessCheck;
+    ///
+    /// let check = AccessCheck::of::<Secret>("get").namespace("default").name("db-credentials");
+    /// client.review_access(&check).await?.require(&check)?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn review_access(&self, check: &AccessCheck) -> Result<AccessDecision> {
+        let reviews: Api<SelfSubjectAccessReview> = Api::all(self.clone());
+        let review = reviews
+            .create(&PostParams::default(), &check.self_review())
+            .await?;
+        Ok(review.status.map(AccessDecision::from).unwrap_or_default())
+    }
+
+    /// Review whether `user` in `groups` is allowed to make the request of `check`, with a [`SubjectAccessReview`]
+    ///
+    /// Requires this client to be allowed to `create` `subjectaccessreviews`.
+    pub async fn review_access_of(
+        &self,
+        check: &AccessCheck,
+        user: &str,
+        groups: Vec<String>,
+    ) -> Result<AccessDecision> {
+        let reviews: Api<SubjectAccessReview> = Api::all(self.clone());
+        let review = reviews
+            .create(&PostParams::default(), &check.subject_review(user, groups))
+            .await?;
+        Ok(review.status.map(AccessDecision::from).unwrap_or_default())
+    }
+}
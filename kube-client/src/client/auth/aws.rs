@@ -0,0 +1,343 @@
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+
+use aws_config::{sts::AssumeRoleProvider, BehaviorVersion, Region};
+use aws_credential_types::provider::{
+    error::CredentialsError, ProvideCredentials, SharedCredentialsProvider,
+};
+use aws_sigv4::{
+    http_request::{sign, SignableBody, SignableRequest, SignatureLocation, SigningSettings},
+    sign::v4,
+};
+use base64::Engine;
+use chrono::{DateTime, Utc};
+use secrecy::SecretString;
+use thiserror::Error;
+
+use super::SIXTY_SEC;
+use crate::config::ExecConfig;
+
+/// Header that binds the token to a cluster, see <https://github.com/kubernetes-sigs/aws-iam-authenticator>
+const CLUSTER_ID_HEADER: &str = "x-k8s-aws-id";
+const TOKEN_PREFIX: &str = "k8s-aws-v1.";
+/// Validity of the presigned URL, the authenticator accepts tokens for 15 minutes regardless
+const PRESIGN_EXPIRY: Duration = Duration::from_secs(60);
+/// Same expiration as reported by `aws eks get-token`
+const TOKEN_EXPIRY: chrono::TimeDelta = match chrono::TimeDelta::try_minutes(14) {
+    Some(d) => d,
+    None => panic!(),
+};
+
+#[derive(Error, Debug)]
+/// Possible errors when generating an EKS token
+pub enum Error {
+    /// No AWS region was configured for the cluster
+    #[error("no AWS region configured for cluster {0}")]
+    MissingRegion(String),
+
+    /// No AWS credentials provider was found
+    #[error("no AWS credentials provider found")]
+    NoCredentialsProvider,
+
+    /// Failed to load AWS credentials
+    #[error("failed to load AWS credentials: {0}")]
+    LoadCredentials(#[source] CredentialsError),
+
+    /// Failed to presign the STS request
+    #[error("failed to presign STS request: {0}")]
+    Sign(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+    /// Failed to build the STS request
+    #[error("failed to build STS request: {0}")]
+    BuildRequest(#[source] http::Error),
+}
+
+/// EKS token source, equivalent to `aws eks get-token` and `aws-iam-authenticator token`
+///
+/// Tokens are presigned `sts:GetCallerIdentity` requests, signed with credentials
+/// from the default AWS credentials chain.
+#[derive(Debug)]
+pub struct Eks {
+    cluster_name: String,
+    region: Option<String>,
+    profile: Option<String>,
+    role_arn: Option<String>,
+    credentials: Option<(Region, SharedCredentialsProvider)>,
+    token: Option<(SecretString, DateTime<Utc>)>,
+}
+
+impl Eks {
+    /// Recognize exec plugin configurations that run `aws eks get-token` or `aws-iam-authenticator token`
+    ///
+    /// Returns `None` for anything that cannot be handled in-process exactly like the plugin would,
+    /// such as unknown flags or environment variables, so that the plugin is executed instead.
+    pub(crate) fn from_exec_config(exec: &ExecConfig) -> Option<Self> {
+        let command = Path::new(exec.command.as_deref()?).file_stem()?.to_str()?;
+        let args = exec.args.as_deref().unwrap_or_default();
+
+        let mut cluster_name = None;
+        let mut region = None;
+        let mut profile = None;
+        let mut role_arn = None;
+        match (command, args) {
+            ("aws", [eks, get_token, rest @ ..]) if eks == "eks" && get_token == "get-token" => {
+                parse_flags(rest, |name, value| {
+                    match name {
+                        "--cluster-name" => cluster_name = Some(value),
+                        "--region" => region = Some(value),
+                        "--profile" => profile = Some(value),
+                        "--role-arn" => role_arn = Some(value),
+                        // Only affects the format of the plugin output
+                        "--output" => {}
+                        _ => return None,
+                    }
+                    Some(())
+                })?
+            }
+            ("aws-iam-authenticator", [token, rest @ ..]) if token == "token" => {
+                parse_flags(rest, |name, value| {
+                    match name {
+                        "-i" | "--cluster-id" => cluster_name = Some(value),
+                        "--region" => region = Some(value),
+                        "-r" | "--role" => role_arn = Some(value),
+                        _ => return None,
+                    }
+                    Some(())
+                })?
+            }
+            _ => return None,
+        }
+
+        for env in exec.env.iter().flatten() {
+            match (env.get("name")?.as_str(), env.get("value")?.as_str()) {
+                // Flags take precedence over the environment
+                ("AWS_PROFILE", value) => profile = profile.or(Some(value)),
+                ("AWS_REGION" | "AWS_DEFAULT_REGION", value) => region = region.or(Some(value)),
+                // Other variables can change how credentials are resolved
+                _ => return None,
+            }
+        }
+
+        Some(Self {
+            cluster_name: cluster_name?.to_owned(),
+            region: region.map(str::to_owned),
+            profile: profile.map(str::to_owned),
+            role_arn: role_arn.map(str::to_owned),
+            credentials: None,
+            token: None,
+        })
+    }
+
+    /// Get the cached token, or generate a new one if it's expiring
+    pub async fn token(&mut self) -> Result<SecretString, Error> {
+        if let Some((token, expiry)) = &self.token {
+            if Utc::now() + SIXTY_SEC < *expiry {
+                return Ok(token.clone());
+            }
+        }
+
+        let (region, provider) = self.credentials_provider().await?;
+        let credentials = provider
+            .provide_credentials()
+            .await
+            .map_err(Error::LoadCredentials)?;
+        let token = presign_token(&self.cluster_name, region.as_ref(), credentials)?;
+        self.token = Some((token.clone(), Utc::now() + TOKEN_EXPIRY));
+        Ok(token)
+    }
+
+    /// Load the credentials chain once, it caches and refreshes credentials by itself
+    async fn credentials_provider(&mut self) -> Result<(Region, SharedCredentialsProvider), Error> {
+        if let Some(credentials) = &self.credentials {
+            return Ok(credentials.clone());
+        }
+
+        let mut loader = aws_config::defaults(BehaviorVersion::latest());
+        if let Some(region) = &self.region {
+            loader = loader.region(Region::new(region.clone()));
+        }
+        if let Some(profile) = &self.profile {
+            loader = loader.profile_name(profile);
+        }
+        let config = loader.load().await;
+        let region = config
+            .region()
+            .cloned()
+            .ok_or_else(|| Error::MissingRegion(self.cluster_name.clone()))?;
+        let provider = match &self.role_arn {
+            Some(role_arn) => SharedCredentialsProvider::new(
+                AssumeRoleProvider::builder(role_arn)
+                    .session_name("kube-rs")
+                    .configure(&config)
+                    .build()
+                    .await,
+            ),
+            None => config
+                .credentials_provider()
+                .ok_or(Error::NoCredentialsProvider)?,
+        };
+        self.credentials = Some((region.clone(), provider.clone()));
+        Ok((region, provider))
+    }
+}
+
+/// Parse `--flag value` and `--flag=value` pairs, stopping at the first flag that `f` rejects
+fn parse_flags<'a>(args: &'a [String], mut f: impl FnMut(&str, &'a str) -> Option<()>) -> Option<()> {
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        if !arg.starts_with('-') {
+            return None;
+        }
+        match arg.split_once('=') {
+            Some((name, value)) => f(name, value)?,
+            None => f(arg, args.next()?)?,
+        }
+    }
+    Some(())
+}
+
+/// Presign `sts:GetCallerIdentity` with the cluster header, which the authenticator in EKS replays
+fn presign_token(
+    cluster_name: &str,
+    region: &str,
+    credentials: aws_credential_types::Credentials,
+) -> Result<SecretString, Error> {
+    let identity = credentials.into();
+    let mut settings = SigningSettings::default();
+    settings.signature_location = SignatureLocation::QueryParams;
+    settings.expires_in = Some(PRESIGN_EXPIRY);
+    let params = v4::SigningParams::builder()
+        .identity(&identity)
+        .region(region)
+        .name("sts")
+        .time(SystemTime::now())
+        .settings(settings)
+        .build()
+        .map_err(|err| Error::Sign(err.into()))?
+        .into();
+
+    let domain = if region.starts_with("cn-") {
+        "amazonaws.com.cn"
+    } else {
+        "amazonaws.com"
+    };
+    let url = format!("https://sts.{region}.{domain}/?Action=GetCallerIdentity&Version=2011-06-15");
+    let signable = SignableRequest::new(
+        "GET",
+        &url,
+        [(CLUSTER_ID_HEADER, cluster_name)].into_iter(),
+        SignableBody::Bytes(&[]),
+    )
+    .map_err(|err| Error::Sign(err.into()))?;
+    let (instructions, _signature) = sign(signable, &params)
+        .map_err(|err| Error::Sign(err.into()))?
+        .into_parts();
+
+    let mut request = http::Request::get(&url)
+        .header(CLUSTER_ID_HEADER, cluster_name)
+        .body(())
+        .map_err(Error::BuildRequest)?;
+    instructions.apply_to_request_http1x(&mut request);
+    let encoded = base64::engine::general_purpose::URL_SAFE_NO_PAD.encode(request.uri().to_string());
+    Ok(SecretString::from(format!("{TOKEN_PREFIX}{encoded}")))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn exec_config(command: &str, args: &[&str], env: &[(&str, &str)]) -> ExecConfig {
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "client.authentication.k8s.io/v1beta1",
+            "command": command,
+            "args": args,
+            "env": env.iter().map(|(name, value)| serde_json::json!({ "name": name, "value": value })).collect::<Vec<_>>(),
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn recognizes_aws_cli() {
+        let exec = exec_config(
+            "aws",
+            &[
+                "--region",
+                "eu-west-1",
+                "eks",
+                "get-token",
+                "--cluster-name",
+                "prod",
+            ],
+            &[],
+        );
+        // Global flags before the subcommand are not recognized
+        assert!(Eks::from_exec_config(&exec).is_none());
+
+        let exec = exec_config(
+            "/usr/local/bin/aws",
+            &["eks", "get-token", "--cluster-name=prod", "--region", "eu-west-1"],
+            &[("AWS_PROFILE", "admin"), ("AWS_REGION", "us-east-1")],
+        );
+        let eks = Eks::from_exec_config(&exec).unwrap();
+        assert_eq!(eks.cluster_name, "prod");
+        assert_eq!(eks.region.as_deref(), Some("eu-west-1"));
+        assert_eq!(eks.profile.as_deref(), Some("admin"));
+        assert_eq!(eks.role_arn, None);
+    }
+
+    #[test]
+    fn recognizes_aws_iam_authenticator() {
+        let exec = exec_config(
+            "aws-iam-authenticator",
+            &[
+                "token",
+                "-i",
+                "prod",
+                "-r",
+                "arn:aws:iam::123456789012:role/admin",
+            ],
+            &[],
+        );
+        let eks = Eks::from_exec_config(&exec).unwrap();
+        assert_eq!(eks.cluster_name, "prod");
+        assert_eq!(
+            eks.role_arn.as_deref(),
+            Some("arn:aws:iam::123456789012:role/admin")
+        );
+    }
+
+    #[test]
+    fn falls_back_to_plugin_for_unknown_config() {
+        let unknown_flag = exec_config(
+            "aws",
+            &["eks", "get-token", "--cluster-name", "prod", "--debug"],
+            &[],
+        );
+        assert!(Eks::from_exec_config(&unknown_flag).is_none());
+        let unknown_env = exec_config("aws", &["eks", "get-token", "--cluster-name", "prod"], &[(
+            "AWS_CONFIG_FILE",
+            "/etc/aws",
+        )]);
+        assert!(Eks::from_exec_config(&unknown_env).is_none());
+        let missing_cluster = exec_config("aws", &["eks", "get-token"], &[]);
+        assert!(Eks::from_exec_config(&missing_cluster).is_none());
+        let other_command = exec_config("gke-gcloud-auth-plugin", &[], &[]);
+        assert!(Eks::from_exec_config(&other_command).is_none());
+    }
+
+    #[test]
+    fn presigned_token_format() {
+        let credentials = aws_credential_types::Credentials::new("AKID", "SECRET", None, None, "test");
+        let token = presign_token("prod", "eu-west-1", credentials).unwrap();
+        let token = secrecy::ExposeSecret::expose_secret(&token);
+        let url = base64::engine::general_purpose::URL_SAFE_NO_PAD
+            .decode(token.strip_prefix(TOKEN_PREFIX).unwrap())
+            .unwrap();
+        let url = String::from_utf8(url).unwrap();
+        assert!(url.starts_with("https://sts.eu-west-1.amazonaws.com/?Action=GetCallerIdentity"));
+        assert!(url.contains("X-Amz-Expires=60"));
+        assert!(url.contains("X-Amz-SignedHeaders=host%3Bx-k8s-aws-id"));
+    }
+}
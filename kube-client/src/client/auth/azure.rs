@@ -0,0 +1,445 @@
+use std::{collections::HashMap, time::Duration};
+
+use super::SIXTY_SEC;
+use chrono::{DateTime, TimeZone, Utc};
+use form_urlencoded::Serializer;
+use http::{
+    header::{HeaderValue, CONTENT_TYPE},
+    Method, Request, StatusCode, Version,
+};
+use http_body_util::BodyExt;
+use hyper_util::{
+    client::legacy::{connect::HttpConnector, Client},
+    rt::TokioExecutor,
+};
+use secrecy::{ExposeSecret, SecretString};
+use serde::{de::DeserializeOwned, Deserialize, Deserializer};
+use serde_json::Number;
+use thiserror::Error;
+
+/// Possible errors when requesting a token from Microsoft Entra ID (Azure AD)
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Missing field in the auth provider config
+    #[error("missing field {0}")]
+    MissingField(&'static str),
+
+    /// The `environment` of the auth provider config is not a known Azure cloud
+    #[error("unknown azure environment {0}")]
+    UnknownEnvironment(String),
+
+    /// Failed to create an HTTPS client
+    #[cfg(feature = "openssl-tls")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "openssl-tls")))]
+    #[error("failed to create OpenSSL HTTPS connector: {0}")]
+    CreateOpensslHttpsConnector(#[source] openssl::error::ErrorStack),
+
+    /// No valid native root CA certificates found
+    #[error("No valid native root CA certificates found")]
+    NoValidNativeRootCA(#[source] std::io::Error),
+
+    /// Failed to build a request
+    #[error("failed to build request: {0}")]
+    BuildRequest(#[source] http::Error),
+
+    /// Failed to send a request
+    #[error("failed to send request: {0}")]
+    SendRequest(#[source] hyper_util::client::legacy::Error),
+
+    /// Failed to read a response
+    #[error("failed to read response: {0}")]
+    ReadResponse(#[source] hyper::Error),
+
+    /// Failed to parse a response
+    #[error("failed to parse response: {0}")]
+    ParseResponse(#[source] serde_json::Error),
+
+    /// The token endpoint rejected the request
+    #[error("token request failed with status {status}: {error}: {description}")]
+    RequestFailed {
+        /// Status code of the response
+        status: StatusCode,
+        /// OAuth error code
+        error: String,
+        /// Human readable description of the error
+        description: String,
+    },
+}
+
+#[cfg(not(any(feature = "rustls-tls", feature = "openssl-tls")))]
+compile_error!("At least one of rustls-tls or openssl-tls feature must be enabled to use azure feature");
+// Current TLS feature precedence when more than one are set:
+// 1. rustls-tls
+// 2. openssl-tls
+#[cfg(feature = "rustls-tls")]
+type HttpsConnector = hyper_rustls::HttpsConnector<HttpConnector>;
+#[cfg(all(not(feature = "rustls-tls"), feature = "openssl-tls"))]
+type HttpsConnector = hyper_openssl::HttpsConnector<HttpConnector>;
+
+/// Token source for AKS clusters with Microsoft Entra ID (Azure AD) integration
+///
+/// Reads the config of the deprecated `azure` auth provider of client-go, and requests tokens like
+/// [kubelogin](https://github.com/Azure/kubelogin) does:
+/// - with the client credentials flow, when a client secret is available as the `client-secret` config field
+///   or the `AAD_SERVICE_PRINCIPAL_CLIENT_SECRET` / `AZURE_CLIENT_SECRET` environment variables
+/// - with the refresh token from the config, if any
+/// - otherwise with the device code flow, which logs instructions for signing in as a warning
+///
+/// Tokens are requested from the v2 endpoints for the `apiserver-id` application, as used by AKS-managed
+/// Entra ID integration. The `config-mode` of the legacy AAD integration is ignored.
+/// Refreshed tokens are kept in memory, and are not written back to the kubeconfig.
+#[derive(Debug)]
+pub struct Azure {
+    /// Authority URL including the tenant, e.g. `https://login.microsoftonline.com/{tenant}`
+    authority: String,
+    client_id: String,
+    client_secret: Option<SecretString>,
+    /// Scope of the apiserver application
+    scope: String,
+    access_token: Option<(SecretString, DateTime<Utc>)>,
+    refresh_token: Option<SecretString>,
+    https_client: Client<HttpsConnector, String>,
+}
+
+impl Azure {
+    /// Config key for the cached access token.
+    const CONFIG_ACCESS_TOKEN: &'static str = "access-token";
+    /// Config key for the apiserver application ID.
+    const CONFIG_APISERVER_ID: &'static str = "apiserver-id";
+    /// Config key for the client ID.
+    const CONFIG_CLIENT_ID: &'static str = "client-id";
+    /// Config key for the client secret, not part of the upstream config.
+    const CONFIG_CLIENT_SECRET: &'static str = "client-secret";
+    /// Config key for the Azure cloud.
+    const CONFIG_ENVIRONMENT: &'static str = "environment";
+    /// Config key for the expiration of the cached access token, in seconds since the epoch.
+    const CONFIG_EXPIRES_ON: &'static str = "expires-on";
+    /// Config key for the refresh token.
+    const CONFIG_REFRESH_TOKEN: &'static str = "refresh-token";
+    /// Config key for the tenant ID.
+    const CONFIG_TENANT_ID: &'static str = "tenant-id";
+
+    /// Create an instance of this struct from the auth provider config.
+    pub fn from_config(config: &HashMap<String, String>) -> Result<Self, Error> {
+        let get_field = |name: &'static str| config.get(name).cloned().ok_or(Error::MissingField(name));
+
+        let apiserver_id = get_field(Self::CONFIG_APISERVER_ID)?;
+        let client_id = get_field(Self::CONFIG_CLIENT_ID)?;
+        let tenant_id = get_field(Self::CONFIG_TENANT_ID)?;
+        let login_host = match config.get(Self::CONFIG_ENVIRONMENT).map(String::as_str) {
+            None | Some("") | Some("AzurePublicCloud") => "login.microsoftonline.com",
+            Some("AzureChinaCloud") => "login.chinacloudapi.cn",
+            Some("AzureUSGovernmentCloud") => "login.microsoftonline.us",
+            Some(other) => return Err(Error::UnknownEnvironment(other.to_owned())),
+        };
+        let client_secret = config
+            .get(Self::CONFIG_CLIENT_SECRET)
+            .cloned()
+            .or_else(|| std::env::var("AAD_SERVICE_PRINCIPAL_CLIENT_SECRET").ok())
+            .or_else(|| std::env::var("AZURE_CLIENT_SECRET").ok())
+            .map(SecretString::from);
+        let access_token = config
+            .get(Self::CONFIG_ACCESS_TOKEN)
+            .zip(config.get(Self::CONFIG_EXPIRES_ON))
+            .and_then(|(token, expires_on)| {
+                let expires_on = Utc.timestamp_opt(expires_on.parse().ok()?, 0).single()?;
+                Some((SecretString::from(token.clone()), expires_on))
+            });
+        let refresh_token = config
+            .get(Self::CONFIG_REFRESH_TOKEN)
+            .filter(|token| !token.is_empty())
+            .cloned()
+            .map(SecretString::from);
+
+        #[cfg(all(feature = "rustls-tls", feature = "aws-lc-rs"))]
+        {
+            if rustls::crypto::CryptoProvider::get_default().is_none() {
+                // the only error here is if it's been initialized in between: we can ignore it
+                // since our semantic is only to set the default value if it does not exist.
+                let _ = rustls::crypto::aws_lc_rs::default_provider().install_default();
+            }
+        }
+
+        #[cfg(all(feature = "rustls-tls", not(feature = "webpki-roots")))]
+        let https = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_provider_and_native_roots(crate::client::tls::rustls_tls::crypto_provider())
+            .map_err(Error::NoValidNativeRootCA)?
+            .https_only()
+            .enable_http1()
+            .build();
+        #[cfg(all(feature = "rustls-tls", feature = "webpki-roots"))]
+        let https = hyper_rustls::HttpsConnectorBuilder::new()
+            .with_tls_config(
+                hyper_rustls::ConfigBuilderExt::with_webpki_roots(
+                    crate::client::tls::rustls_tls::client_config_builder(),
+                )
+                .with_no_client_auth(),
+            )
+            .https_only()
+            .enable_http1()
+            .build();
+        #[cfg(all(not(feature = "rustls-tls"), feature = "openssl-tls"))]
+        let https = hyper_openssl::HttpsConnector::new().map_err(Error::CreateOpensslHttpsConnector)?;
+
+        let https_client = Client::builder(TokioExecutor::new()).build(https);
+
+        Ok(Self {
+            authority: format!("https://{login_host}/{tenant_id}"),
+            client_id,
+            client_secret,
+            scope: format!("{apiserver_id}/.default"),
+            access_token,
+            refresh_token,
+            https_client,
+        })
+    }
+
+    /// Retrieve the access token. If the stored token is or will soon be expired, request a new one first.
+    pub async fn token(&mut self) -> Result<SecretString, Error> {
+        if let Some((token, expires_on)) = &self.access_token {
+            if Utc::now() + SIXTY_SEC < *expires_on {
+                return Ok(token.clone());
+            }
+        }
+
+        let response = if let Some(client_secret) = &self.client_secret {
+            let params = [
+                ("grant_type", "client_credentials"),
+                ("client_id", self.client_id.as_str()),
+                ("client_secret", client_secret.expose_secret()),
+                ("scope", self.scope.as_str()),
+            ];
+            self.request_token(&params).await?
+        } else if let Some(response) = self.refresh().await {
+            response
+        } else {
+            self.device_code().await?
+        };
+
+        let token = SecretString::from(response.access_token);
+        let expires_on = Utc::now() + chrono::Duration::seconds(response.expires_in);
+        self.access_token = Some((token.clone(), expires_on));
+        if let Some(refresh_token) = response.refresh_token {
+            self.refresh_token = Some(refresh_token.into());
+        }
+        Ok(token)
+    }
+
+    /// Use the refresh token, if any. Falls back to signing in again when the refresh token is rejected.
+    async fn refresh(&mut self) -> Option<TokenResponse> {
+        let refresh_token = self.refresh_token.take()?;
+        let scope = format!("{} offline_access", self.scope);
+        let params = [
+            ("grant_type", "refresh_token"),
+            ("client_id", self.client_id.as_str()),
+            ("refresh_token", refresh_token.expose_secret()),
+            ("scope", scope.as_str()),
+        ];
+        match self.request_token(&params).await {
+            Ok(response) => Some(response),
+            Err(err) => {
+                tracing::warn!("failed to refresh azure token, signing in again: {err}");
+                None
+            }
+        }
+    }
+
+    /// Sign in with the device code flow, logging the instructions from Azure as a warning.
+    ///
+    /// The instructions are logged rather than printed, so that they reach the user through
+    /// whatever `tracing` subscriber the application has set up, without writing to its stderr.
+    async fn device_code(&self) -> Result<TokenResponse, Error> {
+        let scope = format!("{} offline_access", self.scope);
+        let url = format!("{}/oauth2/v2.0/devicecode", self.authority);
+        let params = [("client_id", self.client_id.as_str()), ("scope", scope.as_str())];
+        let device_code: DeviceCodeResponse = parse_response(self.post(&url, &params).await?)?;
+        tracing::warn!("azure sign in required: {}", device_code.message);
+
+        let mut interval = Duration::from_secs(device_code.interval.max(1));
+        let params = [
+            ("grant_type", "urn:ietf:params:oauth:grant-type:device_code"),
+            ("client_id", self.client_id.as_str()),
+            ("device_code", device_code.device_code.as_str()),
+        ];
+        loop {
+            tokio::time::sleep(interval).await;
+            match self.request_token(&params).await {
+                Err(Error::RequestFailed { error, .. }) if error == "authorization_pending" => {}
+                Err(Error::RequestFailed { error, .. }) if error == "slow_down" => {
+                    interval += Duration::from_secs(5);
+                }
+                result => return result,
+            }
+        }
+    }
+
+    async fn request_token(&self, params: &[(&str, &str)]) -> Result<TokenResponse, Error> {
+        let url = format!("{}/oauth2/v2.0/token", self.authority);
+        parse_response(self.post(&url, params).await?)
+    }
+
+    async fn post(&self, url: &str, params: &[(&str, &str)]) -> Result<(StatusCode, Vec<u8>), Error> {
+        let body = Serializer::new(String::new()).extend_pairs(params).finish();
+        let request = Request::builder()
+            .uri(url)
+            .method(Method::POST)
+            .header(
+                CONTENT_TYPE,
+                HeaderValue::from_static("application/x-www-form-urlencoded"),
+            )
+            .version(Version::HTTP_11)
+            .body(body)
+            .map_err(Error::BuildRequest)?;
+        let response = self
+            .https_client
+            .request(request)
+            .await
+            .map_err(Error::SendRequest)?;
+        let status = response.status();
+        let body = response
+            .into_body()
+            .collect()
+            .await
+            .map_err(Error::ReadResponse)?
+            .to_bytes();
+        Ok((status, body.to_vec()))
+    }
+}
+
+fn parse_response<T: DeserializeOwned>((status, body): (StatusCode, Vec<u8>)) -> Result<T, Error> {
+    if status.is_success() {
+        serde_json::from_slice(&body).map_err(Error::ParseResponse)
+    } else {
+        let error = serde_json::from_slice::<ErrorResponse>(&body).unwrap_or_default();
+        Err(Error::RequestFailed {
+            status,
+            error: error.error,
+            description: error.error_description,
+        })
+    }
+}
+
+/// Token response from Microsoft Entra ID.
+#[derive(Deserialize)]
+struct TokenResponse {
+    access_token: String,
+    refresh_token: Option<String>,
+    #[serde(deserialize_with = "deserialize_seconds")]
+    expires_in: i64,
+}
+
+/// Device authorization response from Microsoft Entra ID.
+#[derive(Deserialize)]
+struct DeviceCodeResponse {
+    device_code: String,
+    /// Instructions for the user, including the code and the URL to sign in at
+    message: String,
+    #[serde(default = "default_interval")]
+    interval: u64,
+}
+
+fn default_interval() -> u64 {
+    5
+}
+
+/// Error response from Microsoft Entra ID.
+#[derive(Deserialize, Default)]
+#[serde(default)]
+struct ErrorResponse {
+    error: String,
+    error_description: String,
+}
+
+/// Deserialize a number of seconds from a JSON number or string, the v1 endpoints use strings.
+fn deserialize_seconds<'de, D: Deserializer<'de>>(deserializer: D) -> core::result::Result<i64, D::Error> {
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Seconds {
+        Number(Number),
+        String(String),
+    }
+
+    match Seconds::deserialize(deserializer)? {
+        Seconds::Number(number) => number.as_i64(),
+        Seconds::String(string) => string.parse().ok(),
+    }
+    .ok_or(serde::de::Error::custom("cannot be casted to i64"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn config(fields: &[(&str, &str)]) -> HashMap<String, String> {
+        fields
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect()
+    }
+
+    #[cfg(any(feature = "openssl-tls", feature = "rustls-tls"))]
+    #[test]
+    fn from_config() {
+        let azure = Azure::from_config(&config(&[
+            (Azure::CONFIG_APISERVER_ID, "apiserver"),
+            (Azure::CONFIG_CLIENT_ID, "client"),
+            (Azure::CONFIG_TENANT_ID, "tenant"),
+            (Azure::CONFIG_ENVIRONMENT, "AzureChinaCloud"),
+            (Azure::CONFIG_ACCESS_TOKEN, "token"),
+            (Azure::CONFIG_EXPIRES_ON, "4843639092"),
+            (Azure::CONFIG_REFRESH_TOKEN, ""),
+        ]))
+        .expect("failed to create azure from config");
+        assert_eq!(azure.authority, "https://login.chinacloudapi.cn/tenant");
+        assert_eq!(azure.scope, "apiserver/.default");
+        let (token, expires_on) = azure.access_token.as_ref().unwrap();
+        assert_eq!(token.expose_secret(), "token");
+        assert_eq!(expires_on.timestamp(), 4843639092);
+        assert!(azure.refresh_token.is_none());
+
+        let public = Azure::from_config(&config(&[
+            (Azure::CONFIG_APISERVER_ID, "apiserver"),
+            (Azure::CONFIG_CLIENT_ID, "client"),
+            (Azure::CONFIG_TENANT_ID, "tenant"),
+        ]))
+        .expect("failed to create azure from config");
+        assert_eq!(public.authority, "https://login.microsoftonline.com/tenant");
+        assert!(public.access_token.is_none());
+    }
+
+    #[cfg(any(feature = "openssl-tls", feature = "rustls-tls"))]
+    #[test]
+    fn from_invalid_config() {
+        assert!(matches!(
+            Azure::from_config(&config(&[(Azure::CONFIG_CLIENT_ID, "client")])),
+            Err(Error::MissingField(Azure::CONFIG_APISERVER_ID))
+        ));
+        assert!(matches!(
+            Azure::from_config(&config(&[
+                (Azure::CONFIG_APISERVER_ID, "apiserver"),
+                (Azure::CONFIG_CLIENT_ID, "client"),
+                (Azure::CONFIG_TENANT_ID, "tenant"),
+                (Azure::CONFIG_ENVIRONMENT, "AzureGermanCloud"),
+            ])),
+            Err(Error::UnknownEnvironment(_))
+        ));
+    }
+
+    #[test]
+    fn parse_token_response() {
+        let v2 = br#"{"token_type":"Bearer","expires_in":3599,"access_token":"a"}"#.to_vec();
+        let response: TokenResponse = parse_response((StatusCode::OK, v2)).unwrap();
+        assert_eq!(response.expires_in, 3599);
+        assert!(response.refresh_token.is_none());
+
+        let v1 = br#"{"expires_in":"3599","access_token":"a","refresh_token":"r"}"#.to_vec();
+        let response: TokenResponse = parse_response((StatusCode::OK, v1)).unwrap();
+        assert_eq!(response.expires_in, 3599);
+
+        let pending = br#"{"error":"authorization_pending","error_description":"waiting"}"#.to_vec();
+        let Err(err) = parse_response::<TokenResponse>((StatusCode::BAD_REQUEST, pending)) else {
+            panic!("a pending authorization should fail");
+        };
+        assert!(matches!(err, Error::RequestFailed { error, .. } if error == "authorization_pending"));
+    }
+}
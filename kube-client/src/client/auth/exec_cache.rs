@@ -0,0 +1,165 @@
+//! Sharing of exec plugin credentials between clients
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+    sync::{LazyLock, Mutex},
+};
+
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+
+use super::{auth_exec, Error, ExecCredential, SIXTY_SEC};
+use crate::{
+    client::hash::stable_hash,
+    config::{ExecConfig, ExecCredentialCache},
+};
+
+static IN_PROCESS: LazyLock<Mutex<HashMap<String, ExecCredential>>> = LazyLock::new(Default::default);
+
+/// A credential stored in a cache directory, along with the full key to detect hash collisions
+#[derive(Serialize, Deserialize)]
+struct CachedCredential {
+    key: String,
+    credential: ExecCredential,
+}
+
+/// Run the exec plugin, unless the cache has an unexpired credential from an identical plugin
+pub(super) fn cached_auth_exec(
+    exec: &ExecConfig,
+    cache: &ExecCredentialCache,
+) -> Result<ExecCredential, Error> {
+    let key = cache_key(exec)?;
+    let cached = match cache {
+        ExecCredentialCache::InProcess => IN_PROCESS.lock().unwrap().get(&key).cloned(),
+        ExecCredentialCache::Directory(dir) => read_cached(&cache_file(dir, &key), &key),
+    };
+    if let Some(credential) = cached.filter(is_fresh) {
+        return Ok(credential);
+    }
+
+    let credential = auth_exec(exec)?;
+    if is_fresh(&credential) {
+        match cache {
+            ExecCredentialCache::InProcess => {
+                IN_PROCESS.lock().unwrap().insert(key, credential.clone());
+            }
+            ExecCredentialCache::Directory(dir) => {
+                let path = cache_file(dir, &key);
+                let cached = CachedCredential {
+                    key,
+                    credential: credential.clone(),
+                };
+                if let Err(err) = write_cached(dir, &path, &cached) {
+                    tracing::warn!("Failed to cache exec credential in {}: {err}", dir.display());
+                }
+            }
+        }
+    }
+    Ok(credential)
+}
+
+/// Identifies a plugin by everything it gets as input, since that determines the credential it returns
+fn cache_key(exec: &ExecConfig) -> Result<String, Error> {
+    let env: Option<Vec<_>> = exec.env.as_ref().map(|env| {
+        env.iter()
+            .map(|var| (var.get("name"), var.get("value")))
+            .collect()
+    });
+    let cluster = exec.cluster.as_ref().filter(|_| exec.provide_cluster_info);
+    serde_json::to_string(&(
+        &exec.api_version,
+        &exec.command,
+        &exec.args,
+        env,
+        &exec.drop_env,
+        cluster,
+    ))
+    .map_err(Error::AuthExecSerialize)
+}
+
+/// Whether a credential expires, and does not need to be refreshed yet
+fn is_fresh(credential: &ExecCredential) -> bool {
+    credential
+        .status
+        .as_ref()
+        .and_then(|status| status.expiration_timestamp.as_ref())
+        .and_then(|ts| ts.parse::<DateTime<Utc>>().ok())
+        .is_some_and(|expiration| Utc::now() + SIXTY_SEC < expiration)
+}
+
+fn cache_file(dir: &Path, key: &str) -> PathBuf {
+    dir.join(format!("{}.json", stable_hash(key.as_bytes())))
+}
+
+fn read_cached(path: &Path, key: &str) -> Option<ExecCredential> {
+    let data = std::fs::read(path).ok()?;
+    let cached: CachedCredential = serde_json::from_slice(&data).ok()?;
+    (cached.key == key).then_some(cached.credential)
+}
+
+/// Write the credential to a temporary file first, so that other processes never read a partial file
+fn write_cached(dir: &Path, path: &Path, cached: &CachedCredential) -> std::io::Result<()> {
+    let mut builder = std::fs::DirBuilder::new();
+    builder.recursive(true);
+    #[cfg(unix)]
+    std::os::unix::fs::DirBuilderExt::mode(&mut builder, 0o700);
+    builder.create(dir)?;
+
+    let tmp_path = path.with_extension(format!("{}.tmp", std::process::id()));
+    let mut options = std::fs::OpenOptions::new();
+    options.write(true).create(true).truncate(true);
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+    let mut file = options.open(&tmp_path)?;
+    file.write_all(&serde_json::to_vec(cached)?)?;
+    drop(file);
+    std::fs::rename(&tmp_path, path)
+}
+
+#[cfg(all(test, unix))]
+mod tests {
+    use super::cached_auth_exec;
+    use crate::config::{ExecConfig, ExecCredentialCache};
+
+    // Counts its runs in `$RUNS`, and returns a token that expires far in the future
+    fn counting_plugin(runs: &std::path::Path) -> ExecConfig {
+        let script = r#"echo run >> "$RUNS"; printf '%s' '{"apiVersion":"client.authentication.k8s.io/v1","kind":"ExecCredential","status":{"token":"cached","expirationTimestamp":"2999-01-01T00:00:00Z"}}'"#;
+        serde_json::from_value(serde_json::json!({
+            "apiVersion": "client.authentication.k8s.io/v1",
+            "command": "sh",
+            "args": ["-c", script],
+            "env": [{ "name": "RUNS", "value": runs }],
+            "interactiveMode": "Never",
+        }))
+        .unwrap()
+    }
+
+    fn runs(path: &std::path::Path) -> usize {
+        std::fs::read_to_string(path).unwrap_or_default().lines().count()
+    }
+
+    #[test]
+    fn exec_credentials_are_shared_by_identical_plugins() {
+        let dir = tempfile::tempdir().unwrap();
+        let caches = [
+            ExecCredentialCache::InProcess,
+            ExecCredentialCache::Directory(dir.path().join("credentials")),
+        ];
+        for (i, cache) in caches.into_iter().enumerate() {
+            let runs_file = dir.path().join(format!("runs-{i}"));
+            let plugin = counting_plugin(&runs_file);
+            for _ in 0..3 {
+                let credential = cached_auth_exec(&plugin, &cache).unwrap();
+                assert_eq!(credential.status.unwrap().token.as_deref(), Some("cached"));
+            }
+            assert_eq!(runs(&runs_file), 1, "{cache:?} did not reuse the credential");
+
+            // A plugin with other inputs can return other credentials
+            let mut other_plugin = plugin.clone();
+            other_plugin.args.as_mut().unwrap().push("other".into());
+            cached_auth_exec(&other_plugin, &cache).unwrap();
+            assert_eq!(runs(&runs_file), 2);
+        }
+    }
+}
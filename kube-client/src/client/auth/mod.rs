@@ -1,6 +1,7 @@
 use std::{
+    io::IsTerminal,
     path::{Path, PathBuf},
-    process::Command,
+    process::{Command, Stdio},
     sync::Arc,
 };
 
@@ -19,10 +20,15 @@ use tower::{filter::AsyncPredicate, BoxError};
 
 use crate::config::{AuthInfo, AuthProviderConfig, ExecAuthCluster, ExecConfig, ExecInteractiveMode};
 
+mod exec_cache;
 #[cfg(feature = "oauth")] mod oauth;
 #[cfg(feature = "oauth")] pub use oauth::Error as OAuthError;
 #[cfg(feature = "oidc")] mod oidc;
 #[cfg(feature = "oidc")] pub use oidc::errors as oidc_errors;
+#[cfg(feature = "aws")] mod aws;
+#[cfg(feature = "azure")] mod azure;
+#[cfg(feature = "aws")] pub use aws::Error as AwsError;
+#[cfg(feature = "azure")] pub use azure::Error as AzureError;
 #[cfg(target_os = "windows")] use std::os::windows::process::CommandExt;
 
 #[derive(Error, Debug)]
@@ -75,6 +81,13 @@ pub enum Error {
     #[error("failed exec auth: {0}")]
     AuthExec(String),
 
+    /// Auth exec command has `interactiveMode: Always`, but there is no terminal to prompt on
+    #[error("auth exec command '{cmd}' requires an interactive terminal, but none is available")]
+    AuthExecInteractiveRequired {
+        /// The command that needs a terminal
+        cmd: String,
+    },
+
     /// Failed to read token file
     #[error("failed to read token file '{1:?}': {0}")]
     ReadTokenFile(#[source] std::io::Error, PathBuf),
@@ -99,6 +112,18 @@ pub enum Error {
     #[error("failed OIDC: {0}")]
     Oidc(#[source] oidc_errors::Error),
 
+    /// AWS EKS token error
+    #[cfg(feature = "aws")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "aws")))]
+    #[error("failed EKS token generation: {0}")]
+    Aws(#[source] AwsError),
+
+    /// Azure error
+    #[cfg(feature = "azure")]
+    #[cfg_attr(docsrs, doc(cfg(feature = "azure")))]
+    #[error("failed Azure: {0}")]
+    Azure(#[source] AzureError),
+
     /// cluster spec missing while `provideClusterInfo` is true
     #[error("Cluster spec must be populated when `provideClusterInfo` is true")]
     ExecMissingClusterInfo,
@@ -185,6 +210,8 @@ const SIXTY_SEC: chrono::TimeDelta = const_unwrap!(Duration::try_seconds(60));
 // - token-file refreshed at least once per minute
 // - gcp: command based token source (exec)
 // - gcp: application credential based token source (requires `oauth` feature)
+// - azure: client credentials, refresh token, and device code flows (requires `azure` feature)
+// - aws: EKS tokens signed in-process instead of running `aws eks get-token` (requires `aws` feature)
 //
 // Note that the visibility must be `pub` for `impl Layer for AuthLayer`, but this is not exported from the crate.
 // It's not accessible from outside and not shown on docs.
@@ -196,6 +223,10 @@ pub enum RefreshableToken {
     GcpOauth(Arc<Mutex<oauth::Gcp>>),
     #[cfg(feature = "oidc")]
     Oidc(Arc<Mutex<oidc::Oidc>>),
+    #[cfg(feature = "aws")]
+    Eks(Arc<Mutex<aws::Eks>>),
+    #[cfg(feature = "azure")]
+    Azure(Arc<Mutex<azure::Azure>>),
 }
 
 // For use with `AsyncFilterLayer` to add `Authorization` header with a refreshed token.
@@ -246,6 +277,10 @@ impl RefreshableToken {
                         Auth::RefreshableToken(RefreshableToken::GcpOauth(_)) => unreachable!(),
                         #[cfg(feature = "oidc")]
                         Auth::RefreshableToken(RefreshableToken::Oidc(_)) => unreachable!(),
+                        #[cfg(feature = "aws")]
+                        Auth::RefreshableToken(RefreshableToken::Eks(_)) => unreachable!(),
+                        #[cfg(feature = "azure")]
+                        Auth::RefreshableToken(RefreshableToken::Azure(_)) => unreachable!(),
                     }
                 }
 
@@ -276,6 +311,18 @@ impl RefreshableToken {
                 let token = oidc.lock().await.id_token().await.map_err(Error::Oidc)?;
                 bearer_header(&token)
             }
+
+            #[cfg(feature = "aws")]
+            RefreshableToken::Eks(eks) => {
+                let token = eks.lock().await.token().await.map_err(Error::Aws)?;
+                bearer_header(token.expose_secret())
+            }
+
+            #[cfg(feature = "azure")]
+            RefreshableToken::Azure(azure) => {
+                let token = azure.lock().await.token().await.map_err(Error::Azure)?;
+                bearer_header(token.expose_secret())
+            }
         }
     }
 }
@@ -298,7 +345,7 @@ impl TryFrom<&AuthInfo> for Auth {
                 #[cfg(feature = "oidc")]
                 ProviderToken::Oidc(oidc) => {
                     return Ok(Self::RefreshableToken(RefreshableToken::Oidc(Arc::new(
-                        Mutex::new(oidc),
+                        Mutex::new(*oidc),
                     ))));
                 }
 
@@ -328,6 +375,13 @@ impl TryFrom<&AuthInfo> for Auth {
                         Mutex::new(gcp),
                     ))));
                 }
+
+                #[cfg(feature = "azure")]
+                ProviderToken::Azure(azure) => {
+                    return Ok(Self::RefreshableToken(RefreshableToken::Azure(Arc::new(
+                        Mutex::new(*azure),
+                    ))));
+                }
             }
         }
 
@@ -347,8 +401,19 @@ impl TryFrom<&AuthInfo> for Auth {
             ))));
         }
 
+        // Sign EKS tokens in-process rather than running the AWS CLI or aws-iam-authenticator
+        #[cfg(feature = "aws")]
+        if let Some(eks) = auth_info.exec.as_ref().and_then(aws::Eks::from_exec_config) {
+            return Ok(Self::RefreshableToken(RefreshableToken::Eks(Arc::new(
+                Mutex::new(eks),
+            ))));
+        }
+
         if let Some(exec) = &auth_info.exec {
-            let creds = auth_exec(exec)?;
+            let creds = match &exec.credential_cache {
+                Some(cache) => exec_cache::cached_auth_exec(exec, cache)?,
+                None => auth_exec(exec)?,
+            };
             let status = creds.status.ok_or(Error::ExecPluginFailed)?;
             if let (Some(client_certificate_data), Some(client_key_data)) =
                 (status.client_certificate_data, status.client_key_data)
@@ -376,7 +441,7 @@ impl TryFrom<&AuthInfo> for Auth {
 // We need to differentiate providers because the keys/formats to store token expiration differs.
 enum ProviderToken {
     #[cfg(feature = "oidc")]
-    Oidc(oidc::Oidc),
+    Oidc(Box<oidc::Oidc>),
     #[cfg(not(feature = "oidc"))]
     Oidc(String),
     // "access-token", "expiry" (RFC3339)
@@ -384,16 +449,15 @@ enum ProviderToken {
     #[cfg(feature = "oauth")]
     GcpOauth(oauth::Gcp),
     // "access-token", "expires-on" (timestamp)
-    // Azure(String, Option<DateTime<Utc>>),
+    #[cfg(feature = "azure")]
+    Azure(Box<azure::Azure>),
 }
 
 fn token_from_provider(provider: &AuthProviderConfig) -> Result<ProviderToken, Error> {
     match provider.name.as_ref() {
         "oidc" => token_from_oidc_provider(provider),
         "gcp" => token_from_gcp_provider(provider),
-        "azure" => Err(Error::AuthExec(
-            "The azure auth plugin is not supported; use https://github.com/Azure/kubelogin instead".into(),
-        )),
+        "azure" => token_from_azure_provider(provider),
         _ => Err(Error::AuthExec(format!(
             "Authentication with provider {:} not supported",
             provider.name
@@ -405,7 +469,7 @@ fn token_from_provider(provider: &AuthProviderConfig) -> Result<ProviderToken, E
 fn token_from_oidc_provider(provider: &AuthProviderConfig) -> Result<ProviderToken, Error> {
     oidc::Oidc::from_config(&provider.config)
         .map_err(Error::Oidc)
-        .map(ProviderToken::Oidc)
+        .map(|oidc| ProviderToken::Oidc(Box::new(oidc)))
 }
 
 #[cfg(not(feature = "oidc"))]
@@ -418,6 +482,21 @@ fn token_from_oidc_provider(provider: &AuthProviderConfig) -> Result<ProviderTok
     }
 }
 
+#[cfg(feature = "azure")]
+fn token_from_azure_provider(provider: &AuthProviderConfig) -> Result<ProviderToken, Error> {
+    azure::Azure::from_config(&provider.config)
+        .map_err(Error::Azure)
+        .map(|azure| ProviderToken::Azure(Box::new(azure)))
+}
+
+#[cfg(not(feature = "azure"))]
+fn token_from_azure_provider(_provider: &AuthProviderConfig) -> Result<ProviderToken, Error> {
+    Err(Error::AuthExec(
+        "The azure auth plugin requires the `azure` feature; or use https://github.com/Azure/kubelogin instead"
+            .into(),
+    ))
+}
+
 fn token_from_gcp_provider(provider: &AuthProviderConfig) -> Result<ProviderToken, Error> {
     if let Some(id_token) = provider.config.get("id-token") {
         return Ok(ProviderToken::GcpCommand(id_token.clone(), None));
@@ -578,11 +657,23 @@ fn auth_exec(auth: &ExecConfig) -> Result<ExecCredential, Error> {
         cmd.envs(envs);
     }
 
-    let interactive = auth.interactive_mode != Some(ExecInteractiveMode::Never);
-    if interactive {
-        cmd.stdin(std::process::Stdio::inherit());
+    // Plugins prompt on stderr, so an interactive plugin gets the terminal for both stdin and stderr,
+    // while stdout is always captured for the credential.
+    let terminal = match auth.interactive_mode {
+        Some(ExecInteractiveMode::Never) => None,
+        Some(ExecInteractiveMode::Always) => {
+            let terminal = stdin_terminal().or_else(controlling_terminal);
+            let cmd = format!("{cmd:?}");
+            Some(terminal.ok_or(Error::AuthExecInteractiveRequired { cmd })?)
+        }
+        Some(ExecInteractiveMode::IfAvailable) | None => stdin_terminal(),
+    };
+    let interactive = terminal.is_some();
+    if let Some((stdin, stderr)) = terminal {
+        cmd.stdin(stdin);
+        cmd.stderr(stderr);
     } else {
-        cmd.stdin(std::process::Stdio::piped());
+        cmd.stdin(Stdio::piped());
     }
 
     let mut exec_credential_spec = ExecCredentialSpec {
@@ -629,6 +720,33 @@ fn auth_exec(auth: &ExecConfig) -> Result<ExecCredential, Error> {
     Ok(creds)
 }
 
+/// Stdin and stderr for an interactive plugin, when our own stdin is a terminal
+fn stdin_terminal() -> Option<(Stdio, Stdio)> {
+    std::io::stdin()
+        .is_terminal()
+        .then(|| (Stdio::inherit(), Stdio::inherit()))
+}
+
+/// Stdin and stderr for an interactive plugin on the controlling terminal of the process
+///
+/// This lets plugins that must prompt work when our stdin is redirected, as long as the process
+/// was started from a terminal.
+#[cfg(unix)]
+fn controlling_terminal() -> Option<(Stdio, Stdio)> {
+    let tty = std::fs::OpenOptions::new()
+        .read(true)
+        .write(true)
+        .open("/dev/tty")
+        .ok()?;
+    let prompt = tty.try_clone().ok()?;
+    Some((tty.into(), prompt.into()))
+}
+
+#[cfg(not(unix))]
+fn controlling_terminal() -> Option<(Stdio, Stdio)> {
+    None
+}
+
 #[cfg(test)]
 mod test {
     use crate::config::Kubeconfig;
@@ -681,6 +799,28 @@ mod test {
         Ok(())
     }
 
+    #[cfg(unix)]
+    #[test]
+    fn exec_auth_reports_non_interactive_mode() {
+        // Echo the exec info back, which is itself a valid `ExecCredential`
+        let auth = ExecConfig {
+            api_version: Some("client.authentication.k8s.io/v1".to_string()),
+            command: Some("sh".to_string()),
+            args: Some(vec![
+                "-c".to_string(),
+                r#"printf '%s' "$KUBERNETES_EXEC_INFO""#.to_string(),
+            ]),
+            env: None,
+            drop_env: None,
+            interactive_mode: Some(ExecInteractiveMode::Never),
+            provide_cluster_info: false,
+            cluster: None,
+            credential_cache: None,
+        };
+        let creds = auth_exec(&auth).unwrap();
+        assert_eq!(creds.spec.unwrap().interactive, Some(false));
+    }
+
     #[test]
     fn token_file() {
         let file = tempfile::NamedTempFile::new().unwrap();
@@ -120,14 +120,19 @@ impl Gcp {
                 // 2. openssl-tls
                 #[cfg(all(feature = "rustls-tls", not(feature = "webpki-roots")))]
                 let https = hyper_rustls::HttpsConnectorBuilder::new()
-                    .with_native_roots()
+                    .with_provider_and_native_roots(crate::client::tls::rustls_tls::crypto_provider())
                     .map_err(Error::NoValidNativeRootCA)?
                     .https_only()
                     .enable_http1()
                     .build();
                 #[cfg(all(feature = "rustls-tls", feature = "webpki-roots"))]
                 let https = hyper_rustls::HttpsConnectorBuilder::new()
-                    .with_webpki_roots()
+                    .with_tls_config(
+                        hyper_rustls::ConfigBuilderExt::with_webpki_roots(
+                            crate::client::tls::rustls_tls::client_config_builder(),
+                        )
+                        .with_no_client_auth(),
+                    )
                     .https_only()
                     .enable_http1()
                     .build();
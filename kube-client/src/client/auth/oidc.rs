@@ -1,7 +1,7 @@
 use std::collections::HashMap;
 
 use super::TEN_SEC;
-use chrono::{TimeZone, Utc};
+use chrono::{DateTime, TimeZone, Utc};
 use form_urlencoded::Serializer;
 use http::{
     header::{HeaderValue, AUTHORIZATION, CONTENT_TYPE},
@@ -65,6 +65,9 @@ pub mod errors {
         /// No valid native root CA certificates found
         #[error("No valid native root CA certificates found")]
         NoValidNativeRootCA,
+        /// The configured certificate authority of the provider could not be loaded.
+        #[error("invalid certificate authority of the provider: {0}")]
+        InvalidCertificateAuthority(String),
     }
 
     /// Possible errors when using the refresh token.
@@ -91,6 +94,14 @@ pub mod errors {
             #[from]
             hyper_util::client::legacy::Error,
         ),
+        /// Failed to fetch the metadata of the provider, which is likely caused by a wrong issuer URL.
+        #[error("failed to fetch the provider metadata from {url}: status code {status}")]
+        Discovery {
+            /// The discovery document URL derived from the issuer URL.
+            url: String,
+            /// The status code returned by the provider.
+            status: StatusCode,
+        },
         /// Failed to parse the metadata received from the provider.
         #[error("invalid metadata received from the provider: {0}")]
         InvalidMetadata(#[source] serde_json::Error),
@@ -113,12 +124,25 @@ pub mod errors {
         /// Token response from the provider did not contain an ID token.
         #[error("no ID token received from the provider")]
         NoIdTokenReceived,
+        /// The refresh token is expired or revoked, so the user has to log in again.
+        #[error("refresh token was rejected by the provider, log in again: {}", .0.as_deref().unwrap_or("invalid_grant"))]
+        InvalidGrant(Option<String>),
+        /// The provider rejected the token request with an OAuth error.
+        #[error("token request rejected by the provider with status code {status}: {error}{}", .description.as_ref().map(|d| format!(" ({d})")).unwrap_or_default())]
+        TokenRequestRejected {
+            /// The status code returned by the provider.
+            status: StatusCode,
+            /// The OAuth error code, such as `invalid_client`.
+            error: String,
+            /// The human readable description of the error, if the provider sent one.
+            description: Option<String>,
+        },
     }
 
     /// Possible errors when dealing with OIDC.
     #[derive(Error, Debug)]
     pub enum Error {
-        /// Config did not contain the ID token.
+        /// Config contained neither the ID token, nor the fields to get one with a refresh token.
         #[error("missing field {}", Oidc::CONFIG_ID_TOKEN)]
         IdTokenMissing,
         /// Failed to retrieve expiration timestamp from the ID token.
@@ -156,7 +180,11 @@ use base64::engine::general_purpose::STANDARD as STANDARD_BASE64_ENGINE;
 
 #[derive(Debug)]
 pub struct Oidc {
-    id_token: SecretString,
+    /// Missing when the config only contains the fields to refresh it.
+    id_token: Option<SecretString>,
+    /// Expiration time of the ID token by the local clock.
+    /// Only known for ID tokens received from the provider, see [`Claims::local_expiry`].
+    expires_at: Option<DateTime<Utc>>,
     refresher: Result<Refresher, errors::RefreshInitError>,
 }
 
@@ -166,20 +194,15 @@ impl Oidc {
 
     /// Check whether the stored ID token can still be used.
     fn token_valid(&self) -> Result<bool, errors::IdTokenError> {
-        let part = self
-            .id_token
-            .expose_secret()
-            .split('.')
-            .nth(1)
-            .ok_or(errors::IdTokenError::InvalidFormat)?;
-        let payload = JWT_BASE64_ENGINE.decode(part)?;
-        let expiry = serde_json::from_slice::<Claims>(&payload)?.expiry;
-        let timestamp = Utc
-            .timestamp_opt(expiry, 0)
-            .earliest()
-            .ok_or(errors::IdTokenError::InvalidExpirationTimestamp)?;
+        let Some(id_token) = &self.id_token else {
+            return Ok(false);
+        };
+        let expiry = match self.expires_at {
+            Some(expiry) => expiry,
+            None => Claims::from_id_token(id_token.expose_secret())?.expiry()?,
+        };
 
-        let valid = Utc::now() + TEN_SEC < timestamp;
+        let valid = Utc::now() + TEN_SEC < expiry;
 
         Ok(valid)
     }
@@ -187,44 +210,95 @@ impl Oidc {
     /// Retrieve the ID token. If the stored ID token is or will soon be expired, try refreshing it first.
     pub async fn id_token(&mut self) -> Result<String, errors::Error> {
         if self.token_valid()? {
-            return Ok(self.id_token.expose_secret().to_string());
+            if let Some(id_token) = &self.id_token {
+                return Ok(id_token.expose_secret().to_string());
+            }
         }
 
+        let requested_at = Utc::now();
         let id_token = self.refresher.as_mut().map_err(|e| e.clone())?.id_token().await?;
+        let expires_at = Claims::from_id_token(&id_token)?.local_expiry(requested_at)?;
 
-        self.id_token = id_token.clone().into();
+        self.id_token = Some(id_token.clone().into());
+        self.expires_at = Some(expires_at);
 
         Ok(id_token)
     }
 
     /// Create an instance of this struct from the auth provider config.
     pub fn from_config(config: &HashMap<String, String>) -> Result<Self, errors::Error> {
-        let id_token = config
-            .get(Self::CONFIG_ID_TOKEN)
-            .ok_or(errors::Error::IdTokenMissing)?
-            .clone()
-            .into();
+        let id_token = config.get(Self::CONFIG_ID_TOKEN).cloned().map(SecretString::from);
         let refresher = Refresher::from_config(config);
+        // Without an ID token, refreshing is the only way to get one
+        if id_token.is_none() && refresher.is_err() {
+            return Err(errors::Error::IdTokenMissing);
+        }
 
-        Ok(Self { id_token, refresher })
+        Ok(Self {
+            id_token,
+            expires_at: None,
+            refresher,
+        })
     }
 }
 
-/// Claims extracted from the ID token. Only expiration time here is important.
+/// Claims extracted from the ID token. Only expiration and issue times here are important.
 #[derive(Deserialize)]
 struct Claims {
     #[serde(rename = "exp", deserialize_with = "deserialize_expiry")]
     expiry: i64,
+    #[serde(rename = "iat", default)]
+    issued_at: Option<Number>,
+}
+
+impl Claims {
+    /// Extract the claims from the payload of an ID token.
+    fn from_id_token(id_token: &str) -> Result<Self, errors::IdTokenError> {
+        let part = id_token
+            .split('.')
+            .nth(1)
+            .ok_or(errors::IdTokenError::InvalidFormat)?;
+        let payload = JWT_BASE64_ENGINE.decode(part)?;
+        Ok(serde_json::from_slice(&payload)?)
+    }
+
+    /// Expiration time of the token by the clock of the provider.
+    fn expiry(&self) -> Result<DateTime<Utc>, errors::IdTokenError> {
+        Utc.timestamp_opt(self.expiry, 0)
+            .earliest()
+            .ok_or(errors::IdTokenError::InvalidExpirationTimestamp)
+    }
+
+    /// Expiration time of a token requested at `requested_at`, by the local clock.
+    ///
+    /// The lifetime of the token is measured from when it was requested, so that a local clock that is
+    /// ahead or behind the clock of the provider neither makes fresh tokens look expired, nor expired ones valid.
+    /// Falls back to the expiration time of the provider if the token has no issue time.
+    fn local_expiry(&self, requested_at: DateTime<Utc>) -> Result<DateTime<Utc>, errors::IdTokenError> {
+        let lifetime = self
+            .issued_at
+            .as_ref()
+            .and_then(number_as_i64)
+            .and_then(|issued_at| chrono::Duration::try_seconds(self.expiry - issued_at));
+        match lifetime {
+            Some(lifetime) => Ok(requested_at + lifetime),
+            None => self.expiry(),
+        }
+    }
 }
 
 /// Deserialize expiration time from a JSON number.
 fn deserialize_expiry<'de, D: Deserializer<'de>>(deserializer: D) -> core::result::Result<i64, D::Error> {
     let json_number = Number::deserialize(deserializer)?;
 
+    number_as_i64(&json_number).ok_or(serde::de::Error::custom("cannot be casted to i64"))
+}
+
+/// Convert a JSON number, which may be a float, to an integer timestamp.
+fn number_as_i64(json_number: &Number) -> Option<i64> {
     json_number
         .as_i64()
         .or_else(|| Some(json_number.as_f64()? as i64))
-        .ok_or(serde::de::Error::custom("cannot be casted to i64"))
 }
 
 /// Metadata retrieved from the provider. Only token endpoint here is important.
@@ -254,6 +328,28 @@ struct TokenResponse {
     id_token: Option<String>,
 }
 
+/// Error response from the token endpoint, as defined by RFC 6749 section 5.2.
+#[derive(Deserialize)]
+struct TokenErrorResponse {
+    error: String,
+    error_description: Option<String>,
+}
+
+/// Turn a failed token response into an error, keeping the OAuth error code if the provider sent one.
+fn token_error(status: http::StatusCode, body: &[u8]) -> errors::RefreshError {
+    match serde_json::from_slice::<TokenErrorResponse>(body) {
+        Ok(response) if response.error == "invalid_grant" => {
+            errors::RefreshError::InvalidGrant(response.error_description)
+        }
+        Ok(response) => errors::RefreshError::TokenRequestRejected {
+            status,
+            error: response.error,
+            description: response.error_description,
+        },
+        Err(_) => errors::RefreshError::RequestFailed(status),
+    }
+}
+
 #[cfg(not(any(feature = "rustls-tls", feature = "openssl-tls")))]
 compile_error!(
     "At least one of rustls-tls or openssl-tls feature must be enabled to use refresh-oidc feature"
@@ -285,6 +381,10 @@ struct Refresher {
 }
 
 impl Refresher {
+    /// Config key for the path to the certificate authority of the provider.
+    const CONFIG_CERTIFICATE_AUTHORITY: &'static str = "idp-certificate-authority";
+    /// Config key for the base64 encoded certificate authority of the provider.
+    const CONFIG_CERTIFICATE_AUTHORITY_DATA: &'static str = "idp-certificate-authority-data";
     /// Config key for the client ID.
     const CONFIG_CLIENT_ID: &'static str = "client-id";
     /// Config key for the client secret.
@@ -307,6 +407,7 @@ impl Refresher {
         let refresh_token = get_field(Self::CONFIG_REFRESH_TOKEN)?.into();
         let client_id = get_field(Self::CONFIG_CLIENT_ID)?.into();
         let client_secret = get_field(Self::CONFIG_CLIENT_SECRET)?.into();
+        let certificate_authority = Self::certificate_authority(config)?;
 
         #[cfg(all(feature = "rustls-tls", feature = "aws-lc-rs"))]
         {
@@ -317,21 +418,54 @@ impl Refresher {
             }
         }
 
-        #[cfg(all(feature = "rustls-tls", not(feature = "webpki-roots")))]
-        let https = hyper_rustls::HttpsConnectorBuilder::new()
-            .with_native_roots()
-            .map_err(|_| errors::RefreshInitError::NoValidNativeRootCA)?
-            .https_only()
-            .enable_http1()
-            .build();
-        #[cfg(all(feature = "rustls-tls", feature = "webpki-roots"))]
-        let https = hyper_rustls::HttpsConnectorBuilder::new()
-            .with_webpki_roots()
-            .https_only()
-            .enable_http1()
-            .build();
+        #[cfg(feature = "rustls-tls")]
+        let https = {
+            use crate::client::tls::rustls_tls;
+
+            let builder = hyper_rustls::HttpsConnectorBuilder::new();
+            let builder = if let Some(certs) = certificate_authority {
+                // Like client-go, only trust the configured certificate authority
+                let mut roots = rustls::RootCertStore::empty();
+                for der in certs {
+                    roots
+                        .add(der.into())
+                        .map_err(|e| errors::RefreshInitError::InvalidCertificateAuthority(e.to_string()))?;
+                }
+                builder.with_tls_config(
+                    rustls_tls::client_config_builder()
+                        .with_root_certificates(roots)
+                        .with_no_client_auth(),
+                )
+            } else {
+                #[cfg(not(feature = "webpki-roots"))]
+                {
+                    builder
+                        .with_provider_and_native_roots(rustls_tls::crypto_provider())
+                        .map_err(|_| errors::RefreshInitError::NoValidNativeRootCA)?
+                }
+                #[cfg(feature = "webpki-roots")]
+                {
+                    builder.with_tls_config(
+                        hyper_rustls::ConfigBuilderExt::with_webpki_roots(rustls_tls::client_config_builder())
+                            .with_no_client_auth(),
+                    )
+                }
+            };
+            builder.https_only().enable_http1().build()
+        };
         #[cfg(all(not(feature = "rustls-tls"), feature = "openssl-tls"))]
-        let https = hyper_openssl::HttpsConnector::new()?;
+        let https = if let Some(certs) = certificate_authority {
+            let mut ssl = openssl::ssl::SslConnector::builder(openssl::ssl::SslMethod::tls())?;
+            for der in certs {
+                ssl.cert_store_mut()
+                    .add_cert(openssl::x509::X509::from_der(&der)?)?;
+            }
+            let mut http = HttpConnector::new();
+            http.enforce_http(false);
+            HttpsConnector::with_connector(http, ssl)?
+        } else {
+            hyper_openssl::HttpsConnector::new()?
+        };
 
         let https_client = hyper_util::client::legacy::Client::builder(TokioExecutor::new()).build(https);
 
@@ -346,6 +480,31 @@ impl Refresher {
         })
     }
 
+    /// Load the DER encoded certificates of the certificate authority of the provider, if one is configured.
+    fn certificate_authority(
+        config: &HashMap<String, String>,
+    ) -> Result<Option<Vec<Vec<u8>>>, errors::RefreshInitError> {
+        let invalid = |field: &str, e: &dyn std::fmt::Display| {
+            errors::RefreshInitError::InvalidCertificateAuthority(format!("{field}: {e}"))
+        };
+
+        let pem = if let Some(data) = config.get(Self::CONFIG_CERTIFICATE_AUTHORITY_DATA) {
+            STANDARD_BASE64_ENGINE
+                .decode(data.trim())
+                .map_err(|e| invalid(Self::CONFIG_CERTIFICATE_AUTHORITY_DATA, &e))?
+        } else if let Some(path) = config.get(Self::CONFIG_CERTIFICATE_AUTHORITY) {
+            std::fs::read(path).map_err(|e| invalid(Self::CONFIG_CERTIFICATE_AUTHORITY, &e))?
+        } else {
+            return Ok(None);
+        };
+
+        let certs = crate::config::certs(&pem).map_err(|e| invalid("certificates", &e))?;
+        if certs.is_empty() {
+            return Err(invalid("certificates", &"no certificate found"));
+        }
+        Ok(Some(certs))
+    }
+
     /// If the token endpoint is not yet cached in this struct, extract it from the provider metadata and store in the cache.
     /// Provider metadata is retrieved from a well-known path.
     async fn token_endpoint(&mut self) -> Result<String, errors::RefreshError> {
@@ -353,8 +512,11 @@ impl Refresher {
             return Ok(endpoint);
         }
 
-        let discovery = format!("{}/.well-known/openid-configuration", self.issuer).parse::<Uri>()?;
-        let response = self.https_client.get(discovery).await?;
+        let url = format!(
+            "{}/.well-known/openid-configuration",
+            self.issuer.trim_end_matches('/')
+        );
+        let response = self.https_client.get(url.parse::<Uri>()?).await?;
 
         if response.status().is_success() {
             let body = response.into_body().collect().await?.to_bytes();
@@ -365,7 +527,10 @@ impl Refresher {
 
             Ok(metadata.token_endpoint)
         } else {
-            Err(errors::RefreshError::RequestFailed(response.status()))
+            Err(errors::RefreshError::Discovery {
+                url,
+                status: response.status(),
+            })
         }
     }
 
@@ -435,17 +600,25 @@ impl Refresher {
                         self.auth_style.replace(style);
                         break;
                     }
+
+                    // The client was authorized, but the refresh token was not accepted
+                    let status = response.status();
+                    let body = response.into_body().collect().await?.to_bytes();
+                    if let err @ errors::RefreshError::InvalidGrant(_) = token_error(status, &body) {
+                        return Err(err);
+                    }
                 }
 
                 ok_response.ok_or(errors::RefreshError::AuthorizationFailure)?
             }
         };
 
-        if !response.status().is_success() {
-            return Err(errors::RefreshError::RequestFailed(response.status()));
+        let status = response.status();
+        let body = response.into_body().collect().await?.to_bytes();
+        if !status.is_success() {
+            return Err(token_error(status, &body));
         }
 
-        let body = response.into_body().collect().await?.to_bytes();
         let token_response = serde_json::from_slice::<TokenResponse>(body.as_ref())
             .map_err(errors::RefreshError::InvalidTokenResponse)?;
 
@@ -466,7 +639,8 @@ mod tests {
     #[test]
     fn token_valid() {
         let mut oidc = Oidc {
-            id_token: String::new().into(),
+            id_token: None,
+            expires_at: None,
             refresher: Err(errors::RefreshInitError::MissingField(
                 Refresher::CONFIG_REFRESH_TOKEN,
             )),
@@ -481,21 +655,21 @@ mod tests {
 .eyJpc3MiOiJPbmxpbmUgSldUIEJ1aWxkZXIiLCJpYXQiOjE2ODc5NjU0NTIsImV4cCI6MTY4Nzk2NTU5MywiYXVkIjoid3d3LmV4YW1wbGUuY29tIiwic3ViIjoianJvY2tldEBleGFtcGxlLmNvbSIsIkVtYWlsIjoiYmVlQGV4YW1wbGUuY29tIn0\
 .zTDnfI_zXIa6yPKY_ZE8r6GoLK7Syj-URcTU5_ryv1M";
 
-        oidc.id_token = token_valid.to_string().into();
+        oidc.id_token = Some(token_valid.to_string().into());
         assert!(oidc.token_valid().expect("proper token failed validation"));
 
-        oidc.id_token = token_expired.to_string().into();
+        oidc.id_token = Some(token_expired.to_string().into());
         assert!(!oidc.token_valid().expect("proper token failed validation"));
 
         let malformed_token = token_expired.split_once('.').unwrap().0.to_string();
-        oidc.id_token = malformed_token.into();
+        oidc.id_token = Some(malformed_token.into());
         oidc.token_valid().expect_err("malformed token passed validation");
 
         let invalid_base64_token = token_valid
             .split_once('.')
             .map(|(prefix, suffix)| format!("{}.?{}", prefix, suffix))
             .unwrap();
-        oidc.id_token = invalid_base64_token.into();
+        oidc.id_token = Some(invalid_base64_token.into());
         oidc.token_valid()
             .expect_err("token with invalid base64 encoding passed validation");
 
@@ -508,7 +682,7 @@ mod tests {
             JWT_BASE64_ENGINE.encode(serde_json::to_string(&invalid_claims).unwrap()),
             token_valid.rsplit_once('.').unwrap().1,
         );
-        oidc.id_token = invalid_claims_token.into();
+        oidc.id_token = Some(invalid_claims_token.into());
         oidc.token_valid()
             .expect_err("token without expiration timestamp passed validation");
     }
@@ -522,7 +696,7 @@ mod tests {
 
         let oidc = Oidc::from_config(&minimal_config)
             .expect("failed to create oidc from minimal config (only id-token)");
-        assert_eq!(oidc.id_token.expose_secret(), "some_id_token");
+        assert_eq!(oidc.id_token.unwrap().expose_secret(), "some_id_token");
         assert!(oidc.refresher.is_err());
     }
 
@@ -546,7 +720,7 @@ mod tests {
         .collect();
 
         let oidc = Oidc::from_config(&full_config).expect("failed to create oidc from full config");
-        assert_eq!(oidc.id_token.expose_secret(), "some_id_token");
+        assert_eq!(oidc.id_token.as_ref().unwrap().expose_secret(), "some_id_token");
         let refresher = oidc
             .refresher
             .as_ref()
@@ -558,4 +732,79 @@ mod tests {
         assert_eq!(refresher.client_secret.expose_secret(), "some_client_secret");
         assert_eq!(refresher.auth_style, None);
     }
+
+    #[cfg(any(feature = "openssl-tls", feature = "rustls-tls"))]
+    #[test]
+    fn from_refresh_only_config() {
+        let refresh_config: HashMap<String, String> = [
+            (Refresher::CONFIG_ISSUER_URL.into(), "some_issuer".into()),
+            (
+                Refresher::CONFIG_REFRESH_TOKEN.into(),
+                "some_refresh_token".into(),
+            ),
+            (Refresher::CONFIG_CLIENT_ID.into(), "some_client_id".into()),
+            (
+                Refresher::CONFIG_CLIENT_SECRET.into(),
+                "some_client_secret".into(),
+            ),
+        ]
+        .into_iter()
+        .collect();
+
+        let oidc = Oidc::from_config(&refresh_config).expect("failed to create oidc from refresh config");
+        assert!(oidc.id_token.is_none());
+        assert!(!oidc.token_valid().unwrap());
+
+        let mut invalid_ca_config = refresh_config.clone();
+        invalid_ca_config.insert(
+            Refresher::CONFIG_CERTIFICATE_AUTHORITY_DATA.into(),
+            STANDARD_BASE64_ENGINE.encode("not a certificate"),
+        );
+        let oidc = Oidc::from_config(&invalid_ca_config);
+        assert!(matches!(oidc, Err(errors::Error::IdTokenMissing)));
+        let refresher = Refresher::from_config(&invalid_ca_config);
+        assert!(matches!(
+            refresher,
+            Err(errors::RefreshInitError::InvalidCertificateAuthority(_))
+        ));
+    }
+
+    #[test]
+    fn local_expiry_tolerates_clock_skew() {
+        // Issued at 2023-06-28T15:17:32Z, expiring 141 seconds later
+        let claims: Claims = serde_json::from_str(r#"{"iat": 1687965452, "exp": 1687965593}"#).unwrap();
+        let requested_at = Utc::now();
+        assert_eq!(
+            claims.local_expiry(requested_at).unwrap(),
+            requested_at + chrono::Duration::try_seconds(141).unwrap()
+        );
+
+        let claims: Claims = serde_json::from_str(r#"{"exp": 1687965593}"#).unwrap();
+        assert_eq!(
+            claims.local_expiry(requested_at).unwrap(),
+            claims.expiry().unwrap()
+        );
+    }
+
+    #[test]
+    fn token_errors_are_actionable() {
+        let status = http::StatusCode::BAD_REQUEST;
+        let body = br#"{"error": "invalid_grant", "error_description": "Token is not active"}"#;
+        assert!(matches!(
+            token_error(status, body),
+            errors::RefreshError::InvalidGrant(Some(description)) if description == "Token is not active"
+        ));
+
+        let body = br#"{"error": "invalid_client"}"#;
+        assert!(matches!(
+            token_error(status, body),
+            errors::RefreshError::TokenRequestRejected { error, description: None, .. } if error == "invalid_client"
+        ));
+
+        let status = http::StatusCode::BAD_GATEWAY;
+        assert!(matches!(
+            token_error(status, b"<html>"),
+            errors::RefreshError::RequestFailed(http::StatusCode::BAD_GATEWAY)
+        ));
+    }
 }
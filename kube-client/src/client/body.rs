@@ -49,6 +49,22 @@ impl Body {
         Body::new(Kind::Wrap(body.map_err(Into::into).boxed_unsync()))
     }
 
+    // Clone the body if it is fully buffered, streaming bodies cannot be replayed
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        match &self.kind {
+            Kind::Once(bytes) => Some(Self::new(Kind::Once(bytes.clone()))),
+            Kind::Wrap(_) => None,
+        }
+    }
+
+    // The bytes of the body if it is fully buffered, streaming bodies cannot be inspected
+    pub(crate) fn buffered(&self) -> Option<&[u8]> {
+        match &self.kind {
+            Kind::Once(bytes) => Some(bytes.as_deref().unwrap_or_default()),
+            Kind::Wrap(_) => None,
+        }
+    }
+
     /// Collect all the data frames and trailers of this request body and return the data frame
     pub async fn collect_bytes(self) -> Result<Bytes, crate::Error> {
         Ok(self.collect().await?.to_bytes())
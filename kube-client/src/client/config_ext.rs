@@ -1,6 +1,5 @@
 use std::sync::Arc;
 
-use http::{header::HeaderName, HeaderValue};
 #[cfg(feature = "openssl-tls")] use hyper::rt::{Read, Write};
 use hyper_util::client::legacy::connect::HttpConnector;
 use secrecy::ExposeSecret;
@@ -9,9 +8,12 @@ use tower::{filter::AsyncFilterLayer, util::Either};
 #[cfg(any(feature = "rustls-tls", feature = "openssl-tls"))] use super::tls;
 use super::{
     auth::Auth,
-    middleware::{AddAuthorizationLayer, AuthLayer, BaseUriLayer, ExtraHeadersLayer},
+    middleware::{
+        AddAuthorizationLayer, AuthLayer, BaseUriLayer, ExtraHeadersLayer, ImpersonateLayer, RateLimitLayer,
+        WarningLayer,
+    },
 };
-use crate::{Config, Error, Result};
+use crate::{config::WarningHandler, Config, Error, Result};
 
 /// Extensions to [`Config`](crate::Config) for custom [`Client`](crate::Client).
 ///
@@ -28,6 +30,15 @@ pub trait ConfigExt: private::Sealed {
     /// Layer to add non-authn HTTP headers depending on the config.
     fn extra_headers_layer(&self) -> Result<ExtraHeadersLayer>;
 
+    /// Optional layer to add impersonation headers depending on the config.
+    fn impersonate_layer(&self) -> Result<Option<ImpersonateLayer>>;
+
+    /// Optional layer to throttle requests depending on the config.
+    fn rate_limit_layer(&self) -> Option<RateLimitLayer>;
+
+    /// Optional layer to handle warnings returned by the apiserver depending on the config.
+    fn warning_layer(&self) -> Option<WarningLayer>;
+
     /// Create [`hyper_rustls::HttpsConnector`] based on config.
     ///
     /// # Example
@@ -181,36 +192,51 @@ impl ConfigExt for Config {
     }
 
     fn extra_headers_layer(&self) -> Result<ExtraHeadersLayer> {
-        let mut headers = self.headers.clone();
-        if let Some(impersonate_user) = &self.auth_info.impersonate {
-            headers.push((
-                HeaderName::from_static("impersonate-user"),
-                HeaderValue::from_str(impersonate_user)
-                    .map_err(http::Error::from)
-                    .map_err(Error::HttpError)?,
-            ));
-        }
-        if let Some(impersonate_groups) = &self.auth_info.impersonate_groups {
-            for group in impersonate_groups {
-                headers.push((
-                    HeaderName::from_static("impersonate-group"),
-                    HeaderValue::from_str(group)
-                        .map_err(http::Error::from)
-                        .map_err(Error::HttpError)?,
-                ));
-            }
-        }
         Ok(ExtraHeadersLayer {
-            headers: Arc::new(headers),
+            headers: Arc::new(self.headers.clone()),
         })
     }
 
+    fn impersonate_layer(&self) -> Result<Option<ImpersonateLayer>> {
+        let Some(user) = &self.auth_info.impersonate else {
+            return Ok(None);
+        };
+        ImpersonateLayer::new(
+            user,
+            self.auth_info.impersonate_groups.as_deref().unwrap_or_default(),
+            &self.auth_info.impersonate_extra.clone().unwrap_or_default(),
+        )
+        .map(Some)
+        .map_err(Error::HttpError)
+    }
+
+    fn rate_limit_layer(&self) -> Option<RateLimitLayer> {
+        self.rate_limit
+            .filter(|rate_limit| rate_limit.qps > 0.0)
+            .map(RateLimitLayer::from)
+    }
+
+    fn warning_layer(&self) -> Option<WarningLayer> {
+        match self.warning_handler {
+            WarningHandler::Ignore => None,
+            _ => Some(WarningLayer::new(self.warning_handler.clone())),
+        }
+    }
+
     #[cfg(feature = "rustls-tls")]
     fn rustls_client_config(&self) -> Result<rustls::ClientConfig> {
-        let identity = self.exec_identity_pem().or_else(|| self.identity_pem());
+        let exec_identity = self.exec_identity_pem();
+        // Identities from exec plugins are refreshed by rebuilding the client instead
+        let identity_files = match exec_identity {
+            Some(_) => None,
+            None => self.auth_info.identity_files(),
+        };
+        let identity = exec_identity.or_else(|| self.identity_pem());
         tls::rustls_tls::rustls_client_config(
             identity.as_deref(),
+            identity_files,
             self.root_cert.as_deref(),
+            self.root_cert_file.as_deref(),
             self.accept_invalid_certs,
         )
         .map_err(Error::RustlsTls)
@@ -244,6 +270,10 @@ impl ConfigExt for Config {
                     .map_err(Error::RustlsTls)?,
             ));
         }
+        #[cfg(feature = "http2")]
+        if self.http2_only {
+            return Ok(builder.enable_http2().wrap_connector(connector));
+        }
         Ok(builder.enable_http1().wrap_connector(connector))
     }
 
@@ -251,8 +281,15 @@ impl ConfigExt for Config {
     fn openssl_ssl_connector_builder(&self) -> Result<openssl::ssl::SslConnectorBuilder> {
         let identity = self.exec_identity_pem().or_else(|| self.identity_pem());
         // TODO: pass self.tls_server_name for openssl
-        tls::openssl_tls::ssl_connector_builder(identity.as_ref(), self.root_cert.as_ref())
-            .map_err(|e| Error::OpensslTls(tls::openssl_tls::Error::CreateSslConnector(e)))
+        let mut builder = tls::openssl_tls::ssl_connector_builder(identity.as_ref(), self.root_cert.as_ref())
+            .map_err(|e| Error::OpensslTls(tls::openssl_tls::Error::CreateSslConnector(e)))?;
+        if self.http2_only {
+            builder
+                .set_alpn_protos(b"\x02h2")
+                .map_err(tls::openssl_tls::SslConnectorError::SetAlpnProtocols)
+                .map_err(|e| Error::OpensslTls(tls::openssl_tls::Error::CreateSslConnector(e)))?;
+        }
+        Ok(builder)
     }
 
     #[cfg(feature = "openssl-tls")]
@@ -0,0 +1,1036 @@
+//! An in-memory apiserver for unit tests, see [`FakeApiServer`].
+use std::{
+    collections::{BTreeMap, HashMap, VecDeque},
+    convert::Infallible,
+    sync::{Arc, Mutex, MutexGuard},
+    task::{Context, Poll},
+};
+
+use bytes::Bytes;
+use chrono::{SecondsFormat, Utc};
+use futures::{future::BoxFuture, FutureExt};
+use http::{header::CONTENT_TYPE, Request, Response, StatusCode};
+use http_body::Frame;
+use http_body_util::StreamBody;
+use kube_core::{ApiResource, DynamicObject, Expression, Resource, Selector, SelectorExt};
+use serde::Serialize;
+use serde_json::{json, Value};
+use tokio::sync::mpsc;
+use tower::Service;
+
+use super::{middleware::trace::ResourceRequest, Body};
+use crate::Client;
+
+/// The group and plural name of a resource
+type ResourceKey = (String, String);
+/// The namespace and name of an object, with an empty namespace for cluster-scoped objects
+type ObjectKey = (String, String);
+
+/// How many changes are kept to resume watches from, like the watch cache of the apiserver
+const MAX_EVENTS: usize = 1000;
+
+/// An in-memory apiserver, to unit test code using a [`Client`] without a cluster.
+///
+/// Serves get, list, watch, create, replace, patch and delete requests, along with the `status` subresource,
+/// for the resources registered with [`FakeApiServer::with_resource`] or [`FakeApiServer::with_object`].
+/// Requests for other resources fail with `404 Not Found`, like they would in a cluster without them.
+///
+/// The semantics of the apiserver are followed where tests commonly rely on them:
+/// - objects get a `uid`, `resourceVersion`, `creationTimestamp` and a `generation` that increases when their `spec` changes
+/// - replacing an object with an outdated `resourceVersion` fails with `409 Conflict`
+/// - deleting an object with finalizers only sets its `deletionTimestamp`, and it is deleted once its finalizers are removed
+/// - the `status` is only changed through the `status` subresource
+/// - lists and watches support label and field selectors, and `dryRun` requests change nothing
+/// - watches resume from the last 1000 changes, and fail with `410 Gone` for older resource versions
+///
+/// Strategic merge patches and server-side apply patches are applied as JSON merge patches,
+/// so lists are replaced rather than merged, and fields are not tracked by field manager.
+///
+/// ```
+/// # #[tokio::main]
+/// # async fn main() -> Result<(), Box<dyn std::error::Error>> {
+/// use k8s_openapi::api::core::v1::ConfigMap;
+/// use kube::{api::{Api, Patch, PatchParams}, client::fake::FakeApiServer};
+/// use serde_json::json;
+///
+/// let existing: ConfigMap = serde_json::from_value(json!({
+///     "metadata": { "name": "settings", "namespace": "default" },
+///     "data": { "mode": "slow" }
+/// }))?;
+/// let client = FakeApiServer::new().with_object(&existing).client();
+///
+/// // The code under test
+/// let configmaps: Api<ConfigMap> = Api::default_namespaced(client);
+/// let patch = json!({ "data": { "mode": "fast" } });
+/// configmaps.patch("settings", &PatchParams::default(), &Patch::Merge(&patch)).await?;
+///
+/// let settings = configmaps.get("settings").await?;
+/// assert_eq!(settings.data.unwrap()["mode"], "fast");
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct FakeApiServer {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeApiServer {
+    /// An apiserver without any resources
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Serve the resource `K`
+    #[must_use]
+    pub fn with_resource<K: Resource<DynamicType = ()>>(self) -> Self {
+        self.with_api_resource(&ApiResource::erase::<K>(&()))
+    }
+
+    /// Serve the dynamic `resource`
+    #[must_use]
+    pub fn with_api_resource(self, resource: &ApiResource) -> Self {
+        self.lock()
+            .resources
+            .insert(resource_key(&resource.group, &resource.plural), resource.clone());
+        self
+    }
+
+    /// Serve the resource `K`, with the existing `object`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `object` cannot be serialized.
+    #[must_use]
+    pub fn with_object<K: Resource<DynamicType = ()> + Serialize>(self, object: &K) -> Self {
+        let resource = ApiResource::erase::<K>(&());
+        let object = serde_json::to_value(object).expect("object can be serialized");
+        self.with_api_resource(&resource).insert(&resource, object)
+    }
+
+    /// Serve the dynamic `resource`, with the existing `object`
+    ///
+    /// # Panics
+    ///
+    /// Panics if `object` cannot be serialized.
+    #[must_use]
+    pub fn with_dynamic_object(self, object: &DynamicObject, resource: &ApiResource) -> Self {
+        let object = serde_json::to_value(object).expect("object can be serialized");
+        self.with_api_resource(resource).insert(resource, object)
+    }
+
+    /// A [`Client`] that sends its requests to this apiserver, with `default` as its default namespace
+    ///
+    /// Clients share the objects of the apiserver they are created from.
+    /// Must be called within a Tokio runtime.
+    pub fn client(&self) -> Client {
+        Client::new(self.clone(), "default")
+    }
+
+    fn insert(self, resource: &ApiResource, mut object: Value) -> Self {
+        {
+            let mut state = self.lock();
+            let resource_version = state.next_resource_version();
+            object["apiVersion"] = json!(resource.api_version);
+            object["kind"] = json!(resource.kind);
+            let metadata = &mut object["metadata"];
+            for (field, value) in [
+                ("uid", json!(uid(resource_version))),
+                ("creationTimestamp", json!(now())),
+                ("generation", json!(1)),
+            ] {
+                if metadata.get(field).is_none() {
+                    metadata[field] = value;
+                }
+            }
+            metadata["resourceVersion"] = json!(resource_version.to_string());
+            let key = resource_key(&resource.group, &resource.plural);
+            state
+                .objects
+                .entry(key.clone())
+                .or_default()
+                .insert(object_key(&object), object.clone());
+            state.emit(&key, "ADDED", &object);
+        }
+        self
+    }
+
+    fn lock(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+
+    fn handle(&self, req: Request<Bytes>) -> Response<Body> {
+        let Some(request) = ResourceRequest::parse(req.method(), req.uri()) else {
+            let message = format!(
+                "the fake apiserver does not serve {} {}",
+                req.method(),
+                req.uri().path()
+            );
+            return status_response(StatusCode::NOT_FOUND, "NotFound", message);
+        };
+        let params = form_urlencoded::parse(req.uri().query().unwrap_or_default().as_bytes())
+            .into_owned()
+            .collect::<HashMap<_, _>>();
+        let key = resource_key(request.group, request.resource);
+        let mut state = self.lock();
+        let Some(resource) = state.resources.get(&key).cloned() else {
+            let message = "the server could not find the requested resource".to_string();
+            return status_response(StatusCode::NOT_FOUND, "NotFound", message);
+        };
+        if let Some(subresource) = request.subresource.filter(|subresource| *subresource != "status") {
+            let message = format!("the fake apiserver does not serve the {subresource} subresource");
+            return status_response(StatusCode::NOT_FOUND, "NotFound", message);
+        }
+        let filter = Filter {
+            namespace: request.namespace.map(String::from),
+            label_selector: params
+                .get("labelSelector")
+                .map(|selector| parse_selector(selector)),
+            field_selector: params.get("fieldSelector").cloned(),
+        };
+        let target = Target {
+            key,
+            resource,
+            object: (
+                request.namespace.unwrap_or_default().to_string(),
+                request.name.unwrap_or_default().to_string(),
+            ),
+            status: request.subresource.is_some(),
+            dry_run: params.contains_key("dryRun"),
+        };
+        let body = || parse_body(req.headers().get(CONTENT_TYPE), req.body());
+        match request.verb {
+            "get" => state.get(&target),
+            "list" => state.list(&target, &filter),
+            "watch" => state.watch(&target, filter, &params),
+            "create" => match body() {
+                Ok(object) => state.create(&target, object),
+                Err(rejected) => rejected.into_response(),
+            },
+            "update" => match body() {
+                Ok(object) => state.update(&target, object),
+                Err(rejected) => rejected.into_response(),
+            },
+            "patch" => state.patch(&target, req.headers().get(CONTENT_TYPE), req.body()),
+            "delete" => state.delete(&target),
+            _ => state.delete_collection(&target, &filter),
+        }
+    }
+}
+
+impl std::fmt::Debug for FakeApiServer {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("FakeApiServer").finish_non_exhaustive()
+    }
+}
+
+impl Service<Request<Body>> for FakeApiServer {
+    type Error = Infallible;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = Response<Body>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let server = self.clone();
+        async move {
+            let (parts, body) = req.into_parts();
+            let response = match body.collect_bytes().await {
+                Ok(body) => server.handle(Request::from_parts(parts, body)),
+                Err(err) => status_response(StatusCode::BAD_REQUEST, "BadRequest", err.to_string()),
+            };
+            Ok(response)
+        }
+        .boxed()
+    }
+}
+
+/// The objects, resources and watches of a [`FakeApiServer`]
+#[derive(Default)]
+struct State {
+    resources: HashMap<ResourceKey, ApiResource>,
+    objects: HashMap<ResourceKey, BTreeMap<ObjectKey, Value>>,
+    resource_version: u64,
+    /// The latest changes, up to [`MAX_EVENTS`]
+    events: VecDeque<Event>,
+    /// The resource version of the latest change that is no longer kept in `events`
+    compacted_version: u64,
+    watchers: Vec<Watcher>,
+}
+
+/// A change of an object, kept to replay watches from earlier resource versions
+struct Event {
+    key: ResourceKey,
+    type_: &'static str,
+    object: Value,
+    resource_version: u64,
+}
+
+struct Watcher {
+    key: ResourceKey,
+    filter: Filter,
+    sender: mpsc::UnboundedSender<Bytes>,
+}
+
+/// The object or collection a request is for
+struct Target {
+    key: ResourceKey,
+    resource: ApiResource,
+    object: ObjectKey,
+    status: bool,
+    dry_run: bool,
+}
+
+impl Target {
+    fn not_found(&self) -> Response<Body> {
+        let message = format!("{} \"{}\" not found", self.qualified_resource(), self.object.1);
+        status_response(StatusCode::NOT_FOUND, "NotFound", message)
+    }
+
+    fn status_not_allowed(&self, verb: &str) -> Response<Body> {
+        let message = format!(
+            "the fake apiserver does not {verb} the status of {}",
+            self.qualified_resource()
+        );
+        status_response(StatusCode::METHOD_NOT_ALLOWED, "MethodNotAllowed", message)
+    }
+
+    fn qualified_resource(&self) -> String {
+        if self.resource.group.is_empty() {
+            self.resource.plural.clone()
+        } else {
+            format!("{}.{}", self.resource.plural, self.resource.group)
+        }
+    }
+}
+
+impl State {
+    fn next_resource_version(&mut self) -> u64 {
+        self.resource_version += 1;
+        self.resource_version
+    }
+
+    fn objects(&mut self, key: &ResourceKey) -> &mut BTreeMap<ObjectKey, Value> {
+        self.objects.entry(key.clone()).or_default()
+    }
+
+    /// Records a change of an object and sends it to the matching watches
+    fn emit(&mut self, key: &ResourceKey, type_: &'static str, object: &Value) {
+        let resource_version = self.resource_version;
+        let line = watch_event(type_, object);
+        self.watchers.retain(|watcher| {
+            watcher.key != *key
+                || !watcher.filter.matches(object)
+                || watcher.sender.send(line.clone()).is_ok()
+        });
+        self.events.push_back(Event {
+            key: key.clone(),
+            type_,
+            object: object.clone(),
+            resource_version,
+        });
+        if self.events.len() > MAX_EVENTS {
+            if let Some(event) = self.events.pop_front() {
+                self.compacted_version = event.resource_version;
+            }
+        }
+    }
+
+    fn get(&mut self, target: &Target) -> Response<Body> {
+        match self.objects(&target.key).get(&target.object) {
+            Some(object) => json_response(StatusCode::OK, object),
+            None => target.not_found(),
+        }
+    }
+
+    fn list(&mut self, target: &Target, filter: &Filter) -> Response<Body> {
+        let items = self
+            .objects(&target.key)
+            .values()
+            .filter(|object| filter.matches(object))
+            .cloned()
+            .collect::<Vec<_>>();
+        let list = json!({
+            "apiVersion": target.resource.api_version,
+            "kind": format!("{}List", target.resource.kind),
+            "metadata": { "resourceVersion": self.resource_version.to_string() },
+            "items": items,
+        });
+        json_response(StatusCode::OK, &list)
+    }
+
+    fn watch(&mut self, target: &Target, filter: Filter, params: &HashMap<String, String>) -> Response<Body> {
+        let (sender, mut receiver) = mpsc::unbounded_channel();
+        let send_initial_events = params.get("sendInitialEvents").is_some_and(|send| send == "true");
+        let resource_version = params
+            .get("resourceVersion")
+            .and_then(|version| version.parse::<u64>().ok())
+            .unwrap_or_default();
+        let expired =
+            !send_initial_events && resource_version != 0 && resource_version < self.compacted_version;
+        if expired {
+            // Like the apiserver, end the watch with an error that makes watchers relist
+            let message = format!(
+                "too old resource version: {resource_version} ({})",
+                self.compacted_version
+            );
+            let error = status_body(StatusCode::GONE, "Expired", message);
+            let _ = sender.send(watch_event("ERROR", &error));
+        } else if send_initial_events || resource_version == 0 {
+            for object in self.objects(&target.key).values() {
+                if filter.matches(object) {
+                    let _ = sender.send(watch_event("ADDED", object));
+                }
+            }
+            if send_initial_events {
+                let bookmark = json!({
+                    "apiVersion": target.resource.api_version,
+                    "kind": target.resource.kind,
+                    "metadata": {
+                        "resourceVersion": self.resource_version.to_string(),
+                        "annotations": { "k8s.io/initial-events-end": "true" },
+                    },
+                });
+                let _ = sender.send(watch_event("BOOKMARK", &bookmark));
+            }
+        } else {
+            for event in &self.events {
+                if event.key == target.key
+                    && event.resource_version > resource_version
+                    && filter.matches(&event.object)
+                {
+                    let _ = sender.send(watch_event(event.type_, &event.object));
+                }
+            }
+        }
+        if !expired {
+            self.watchers.push(Watcher {
+                key: target.key.clone(),
+                filter,
+                sender,
+            });
+        }
+        let events = futures::stream::poll_fn(move |cx| {
+            receiver
+                .poll_recv(cx)
+                .map(|line| line.map(|line| Ok::<_, Infallible>(Frame::data(line))))
+        });
+        Response::builder()
+            .header(CONTENT_TYPE, "application/json")
+            .body(Body::wrap_body(StreamBody::new(events)))
+            .expect("response is valid")
+    }
+
+    fn create(&mut self, target: &Target, mut object: Value) -> Response<Body> {
+        if target.status {
+            return target.status_not_allowed("create");
+        }
+        if let Err(rejected) = validate(&object) {
+            return rejected.into_response();
+        }
+        // Only taken once the object is stored, so that failed and dryRun requests change nothing
+        let resource_version = self.resource_version + 1;
+        let metadata = &mut object["metadata"];
+        if !target.object.0.is_empty() {
+            metadata["namespace"] = json!(target.object.0);
+        }
+        if metadata.get("name").is_none() {
+            let Some(generate_name) = metadata
+                .get("generateName")
+                .and_then(Value::as_str)
+                .map(String::from)
+            else {
+                let message = "name or generateName is required".to_string();
+                return status_response(StatusCode::UNPROCESSABLE_ENTITY, "Invalid", message);
+            };
+            metadata["name"] = json!(format!("{generate_name}{resource_version:05}"));
+        }
+        metadata["uid"] = json!(uid(resource_version));
+        metadata["creationTimestamp"] = json!(now());
+        metadata["generation"] = json!(1);
+        metadata["resourceVersion"] = json!(resource_version.to_string());
+        object["apiVersion"] = json!(target.resource.api_version);
+        object["kind"] = json!(target.resource.kind);
+        let key = object_key(&object);
+        if self.objects(&target.key).contains_key(&key) {
+            let message = format!("{} \"{}\" already exists", target.qualified_resource(), key.1);
+            return status_response(StatusCode::CONFLICT, "AlreadyExists", message);
+        }
+        if !target.dry_run {
+            self.resource_version = resource_version;
+            self.objects(&target.key).insert(key, object.clone());
+            self.emit(&target.key, "ADDED", &object);
+        }
+        json_response(StatusCode::CREATED, &object)
+    }
+
+    fn update(&mut self, target: &Target, object: Value) -> Response<Body> {
+        if let Err(rejected) = validate(&object) {
+            return rejected.into_response();
+        }
+        let Some(existing) = self.objects(&target.key).get(&target.object).cloned() else {
+            return target.not_found();
+        };
+        let expected_version = object
+            .pointer("/metadata/resourceVersion")
+            .and_then(Value::as_str);
+        let current_version = existing
+            .pointer("/metadata/resourceVersion")
+            .and_then(Value::as_str);
+        if expected_version.is_some_and(|expected| !expected.is_empty() && Some(expected) != current_version)
+        {
+            let message = format!(
+                "Operation cannot be fulfilled on {} \"{}\": the object has been modified; \
+                 please apply your changes to the latest version and try again",
+                target.qualified_resource(),
+                target.object.1
+            );
+            return status_response(StatusCode::CONFLICT, "Conflict", message);
+        }
+        self.commit(target, existing, object)
+    }
+
+    fn patch(
+        &mut self,
+        target: &Target,
+        content_type: Option<&http::HeaderValue>,
+        body: &Bytes,
+    ) -> Response<Body> {
+        let content_type = content_type
+            .and_then(|value| value.to_str().ok())
+            .unwrap_or_default();
+        let existing = self.objects(&target.key).get(&target.object).cloned();
+        let mut patched = existing.clone().unwrap_or_else(|| json!({}));
+        match content_type {
+            "application/json-patch+json" => {
+                let patch = match serde_json::from_slice::<json_patch::Patch>(body) {
+                    Ok(patch) => patch,
+                    Err(err) => {
+                        return status_response(StatusCode::BAD_REQUEST, "BadRequest", err.to_string())
+                    }
+                };
+                if let Err(err) = json_patch::patch(&mut patched, &patch) {
+                    return status_response(StatusCode::UNPROCESSABLE_ENTITY, "Invalid", err.to_string());
+                }
+            }
+            "application/merge-patch+json"
+            | "application/strategic-merge-patch+json"
+            | "application/apply-patch+yaml" => {
+                let patch = match parse_body(None, body) {
+                    Ok(patch) => patch,
+                    Err(rejected) => return rejected.into_response(),
+                };
+                json_patch::merge(&mut patched, &patch);
+            }
+            _ => {
+                let message = format!("the fake apiserver does not support patches of type {content_type:?}");
+                return status_response(
+                    StatusCode::UNSUPPORTED_MEDIA_TYPE,
+                    "UnsupportedMediaType",
+                    message,
+                );
+            }
+        }
+        if let Err(rejected) = validate(&patched) {
+            return rejected.into_response();
+        }
+        match existing {
+            Some(existing) => self.commit(target, existing, patched),
+            // Server-side apply creates missing objects
+            None if content_type == "application/apply-patch+yaml" && !target.status => {
+                patched["metadata"]["name"] = json!(target.object.1);
+                self.create(target, patched)
+            }
+            None => target.not_found(),
+        }
+    }
+
+    /// Stores the `updated` object in place of the `existing` object, and deletes it if its finalizers are removed
+    fn commit(&mut self, target: &Target, existing: Value, updated: Value) -> Response<Body> {
+        let mut object = if target.status {
+            let mut object = existing.clone();
+            set_field(&mut object, "status", updated.get("status"));
+            object
+        } else {
+            let mut object = updated;
+            set_field(&mut object, "status", existing.get("status"));
+            object
+        };
+        // Fields that are managed by the apiserver
+        object["apiVersion"] = existing["apiVersion"].clone();
+        object["kind"] = existing["kind"].clone();
+        for field in [
+            "name",
+            "namespace",
+            "uid",
+            "creationTimestamp",
+            "deletionTimestamp",
+        ] {
+            set_field(&mut object["metadata"], field, existing["metadata"].get(field));
+        }
+        let generation = existing["metadata"]["generation"].as_i64().unwrap_or(1);
+        let spec_changed = object.get("spec") != existing.get("spec");
+        object["metadata"]["generation"] = json!(if spec_changed { generation + 1 } else { generation });
+        if object == existing {
+            return json_response(StatusCode::OK, &object);
+        }
+        let resource_version = self.resource_version + 1;
+        object["metadata"]["resourceVersion"] = json!(resource_version.to_string());
+        if target.dry_run {
+            return json_response(StatusCode::OK, &object);
+        }
+        self.resource_version = resource_version;
+        if object["metadata"].get("deletionTimestamp").is_some() && !has_finalizers(&object) {
+            self.objects(&target.key).remove(&target.object);
+            self.emit(&target.key, "DELETED", &object);
+        } else {
+            self.objects(&target.key)
+                .insert(target.object.clone(), object.clone());
+            self.emit(&target.key, "MODIFIED", &object);
+        }
+        json_response(StatusCode::OK, &object)
+    }
+
+    fn delete(&mut self, target: &Target) -> Response<Body> {
+        if target.status {
+            return target.status_not_allowed("delete");
+        }
+        let Some(mut object) = self.objects(&target.key).get(&target.object).cloned() else {
+            return target.not_found();
+        };
+        if target.dry_run {
+            return json_response(StatusCode::OK, &object);
+        }
+        if has_finalizers(&object) {
+            if object["metadata"].get("deletionTimestamp").is_none() {
+                let resource_version = self.next_resource_version();
+                object["metadata"]["deletionTimestamp"] = json!(now());
+                object["metadata"]["resourceVersion"] = json!(resource_version.to_string());
+                self.objects(&target.key)
+                    .insert(target.object.clone(), object.clone());
+                self.emit(&target.key, "MODIFIED", &object);
+            }
+            return json_response(StatusCode::ACCEPTED, &object);
+        }
+        let resource_version = self.next_resource_version();
+        object["metadata"]["resourceVersion"] = json!(resource_version.to_string());
+        self.objects(&target.key).remove(&target.object);
+        self.emit(&target.key, "DELETED", &object);
+        json_response(StatusCode::OK, &object)
+    }
+
+    fn delete_collection(&mut self, target: &Target, filter: &Filter) -> Response<Body> {
+        let keys = self
+            .objects(&target.key)
+            .iter()
+            .filter(|(_, object)| filter.matches(object))
+            .map(|(key, _)| key.clone())
+            .collect::<Vec<_>>();
+        let mut items = Vec::new();
+        for key in keys {
+            let target = Target {
+                key: target.key.clone(),
+                resource: target.resource.clone(),
+                object: key,
+                status: false,
+                dry_run: target.dry_run,
+            };
+            let deleted = self.objects(&target.key).get(&target.object).cloned();
+            self.delete(&target);
+            items.extend(deleted);
+        }
+        let list = json!({
+            "apiVersion": target.resource.api_version,
+            "kind": format!("{}List", target.resource.kind),
+            "metadata": { "resourceVersion": self.resource_version.to_string() },
+            "items": items,
+        });
+        json_response(StatusCode::OK, &list)
+    }
+}
+
+/// The namespace and selectors of a list or watch
+struct Filter {
+    namespace: Option<String>,
+    label_selector: Option<Selector>,
+    field_selector: Option<String>,
+}
+
+impl Filter {
+    fn matches(&self, object: &Value) -> bool {
+        let key = object_key(object);
+        if self
+            .namespace
+            .as_ref()
+            .is_some_and(|namespace| *namespace != key.0)
+        {
+            return false;
+        }
+        if let Some(selector) = &self.label_selector {
+            let labels = object
+                .pointer("/metadata/labels")
+                .and_then(|labels| serde_json::from_value::<BTreeMap<String, String>>(labels.clone()).ok())
+                .unwrap_or_default();
+            if !selector.matches(&labels) {
+                return false;
+            }
+        }
+        let Some(field_selector) = &self.field_selector else {
+            return true;
+        };
+        field_selector
+            .split(',')
+            .filter(|requirement| !requirement.is_empty())
+            .all(|requirement| {
+                let (path, value, equal) = match requirement.split_once("!=") {
+                    Some((path, value)) => (path, value, false),
+                    None => match requirement.split_once('=') {
+                        Some((path, value)) => (path, value.trim_start_matches('='), true),
+                        None => return false,
+                    },
+                };
+                let pointer = format!("/{}", path.replace('.', "/"));
+                let field = match object.pointer(&pointer) {
+                    Some(Value::String(field)) => field.clone(),
+                    Some(Value::Null) | None => String::new(),
+                    Some(field) => field.to_string(),
+                };
+                (field == value) == equal
+            })
+    }
+}
+
+/// Parses a label selector like the apiserver, such as `app=web,tier in (frontend,backend),!canary`
+fn parse_selector(selector: &str) -> Selector {
+    let mut requirements = Vec::new();
+    let mut depth = 0;
+    let mut start = 0;
+    for (i, c) in selector.char_indices() {
+        match c {
+            '(' => depth += 1,
+            ')' => depth -= 1,
+            ',' if depth == 0 => {
+                requirements.push(&selector[start..i]);
+                start = i + 1;
+            }
+            _ => {}
+        }
+    }
+    requirements.push(&selector[start..]);
+    let values = |values: &str| {
+        values
+            .trim()
+            .trim_start_matches('(')
+            .trim_end_matches(')')
+            .split(',')
+            .map(|value| value.trim().to_string())
+            .collect()
+    };
+    requirements
+        .into_iter()
+        .map(str::trim)
+        .filter(|requirement| !requirement.is_empty())
+        .map(|requirement| {
+            if let Some((key, set)) = requirement.split_once(" notin ") {
+                Expression::NotIn(key.trim().to_string(), values(set))
+            } else if let Some((key, set)) = requirement.split_once(" in ") {
+                Expression::In(key.trim().to_string(), values(set))
+            } else if let Some((key, value)) = requirement.split_once("!=") {
+                Expression::NotEqual(key.trim().to_string(), value.trim().to_string())
+            } else if let Some((key, value)) = requirement.split_once('=') {
+                let value = value.trim_start_matches('=');
+                Expression::Equal(key.trim().to_string(), value.trim().to_string())
+            } else if let Some(key) = requirement.strip_prefix('!') {
+                Expression::DoesNotExist(key.trim().to_string())
+            } else {
+                Expression::Exists(requirement.to_string())
+            }
+        })
+        .collect()
+}
+
+/// Parses a JSON or YAML object from a request body
+/// Why the body of a request was rejected
+enum Rejected {
+    /// The body could not be parsed
+    BadRequest(String),
+    /// The body cannot be stored as an object
+    Invalid(String),
+}
+
+impl Rejected {
+    fn into_response(self) -> Response<Body> {
+        match self {
+            Self::BadRequest(message) => status_response(StatusCode::BAD_REQUEST, "BadRequest", message),
+            Self::Invalid(message) => status_response(StatusCode::UNPROCESSABLE_ENTITY, "Invalid", message),
+        }
+    }
+}
+
+fn parse_body(content_type: Option<&http::HeaderValue>, body: &Bytes) -> Result<Value, Rejected> {
+    let is_yaml = content_type
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|content_type| content_type.ends_with("yaml"));
+    let parsed = if is_yaml {
+        serde_yaml::from_slice::<Value>(body).map_err(|err| err.to_string())
+    } else {
+        serde_json::from_slice::<Value>(body)
+            .or_else(|_| serde_yaml::from_slice(body).map_err(|err| err.to_string()))
+    };
+    match parsed {
+        Ok(object @ Value::Object(_)) => Ok(object),
+        Ok(_) => Err(Rejected::BadRequest(
+            "the request body is not an object".to_string(),
+        )),
+        Err(err) => Err(Rejected::BadRequest(err)),
+    }
+}
+
+fn resource_key(group: &str, plural: &str) -> ResourceKey {
+    (group.to_string(), plural.to_string())
+}
+
+fn object_key(object: &Value) -> ObjectKey {
+    let field = |pointer| {
+        object
+            .pointer(pointer)
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string()
+    };
+    (field("/metadata/namespace"), field("/metadata/name"))
+}
+
+/// Rejects objects that the apiserver could not decode, since their fields are set in place
+fn validate(object: &Value) -> Result<(), Rejected> {
+    if object.is_object()
+        && matches!(
+            object.get("metadata"),
+            None | Some(Value::Null | Value::Object(_))
+        )
+    {
+        return Ok(());
+    }
+    let message = "the object and its metadata must be JSON objects".to_string();
+    Err(Rejected::Invalid(message))
+}
+
+/// Sets `field` of `object` to `value`, or removes it if `value` is `None`
+fn set_field(object: &mut Value, field: &str, value: Option<&Value>) {
+    match value {
+        Some(value) => object[field] = value.clone(),
+        None => {
+            if let Some(fields) = object.as_object_mut() {
+                fields.remove(field);
+            }
+        }
+    }
+}
+
+fn has_finalizers(object: &Value) -> bool {
+    object
+        .pointer("/metadata/finalizers")
+        .and_then(Value::as_array)
+        .is_some_and(|finalizers| !finalizers.is_empty())
+}
+
+/// A unique and deterministic uid for the object created at `resource_version`
+fn uid(resource_version: u64) -> String {
+    format!("00000000-0000-4000-8000-{resource_version:012x}")
+}
+
+fn now() -> String {
+    Utc::now().to_rfc3339_opts(SecondsFormat::Secs, true)
+}
+
+fn watch_event(type_: &str, object: &Value) -> Bytes {
+    let mut line = json!({ "type": type_, "object": object }).to_string();
+    line.push('\n');
+    Bytes::from(line)
+}
+
+fn json_response(status: StatusCode, body: &Value) -> Response<Body> {
+    Response::builder()
+        .status(status)
+        .header(CONTENT_TYPE, "application/json")
+        .body(Body::from(body.to_string().into_bytes()))
+        .expect("response is valid")
+}
+
+fn status_response(status: StatusCode, reason: &str, message: String) -> Response<Body> {
+    json_response(status, &status_body(status, reason, message))
+}
+
+fn status_body(status: StatusCode, reason: &str, message: String) -> Value {
+    json!({
+        "apiVersion": "v1",
+        "kind": "Status",
+        "metadata": {},
+        "status": "Failure",
+        "message": message,
+        "reason": reason,
+        "code": status.as_u16(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{FakeApiServer, MAX_EVENTS};
+    use crate::{
+        api::{Api, DeleteParams, ListParams, Patch, PatchParams, PostParams, WatchEvent, WatchParams},
+        Error,
+    };
+    use futures::{StreamExt, TryStreamExt};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use serde_json::json;
+
+    fn configmap(name: &str, labels: serde_json::Value) -> ConfigMap {
+        serde_json::from_value(json!({
+            "metadata": { "name": name, "labels": labels },
+            "data": { "key": "value" },
+        }))
+        .unwrap()
+    }
+
+    #[tokio::test]
+    async fn serves_crud_requests() {
+        let client = FakeApiServer::new().with_resource::<ConfigMap>().client();
+        let api: Api<ConfigMap> = Api::namespaced(client, "apps");
+        let pp = PostParams::default();
+
+        let created = api
+            .create(&pp, &configmap("web", json!({"app": "web"})))
+            .await
+            .unwrap();
+        assert_eq!(created.metadata.namespace.as_deref(), Some("apps"));
+        assert!(created.metadata.uid.is_some());
+        api.create(&pp, &configmap("db", json!({"app": "db"})))
+            .await
+            .unwrap();
+        let err = api.create(&pp, &configmap("db", json!({}))).await.unwrap_err();
+        assert!(matches!(err, Error::Api(ae) if ae.code == 409 && ae.reason == "AlreadyExists"));
+
+        let selected = api.list(&ListParams::default().labels("app=web")).await.unwrap();
+        assert_eq!(selected.items.len(), 1);
+        assert_eq!(selected.items[0].metadata.name.as_deref(), Some("web"));
+        assert_eq!(api.list(&ListParams::default()).await.unwrap().items.len(), 2);
+
+        let patch = json!({"data": {"key": "patched"}});
+        let patched = api
+            .patch("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert_eq!(patched.data.unwrap()["key"], "patched");
+
+        // Replacing with an outdated resourceVersion conflicts
+        let err = api.replace("web", &pp, &created).await.unwrap_err();
+        assert!(matches!(err, Error::Api(ae) if ae.code == 409 && ae.reason == "Conflict"));
+
+        api.delete("web", &DeleteParams::default()).await.unwrap();
+        let err = api.get("web").await.unwrap_err();
+        assert!(matches!(err, Error::Api(ae) if ae.code == 404 && ae.reason == "NotFound"));
+        assert!(api.get_opt("db").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn watches_changes_and_finalizers() {
+        let mut existing = configmap("web", json!({}));
+        existing.metadata.namespace = Some("default".into());
+        existing.metadata.finalizers = Some(vec!["example.com/cleanup".into()]);
+        let client = FakeApiServer::new().with_object(&existing).client();
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+
+        let mut events = api.watch(&WatchParams::default(), "0").await.unwrap().boxed();
+        assert!(matches!(
+            events.try_next().await.unwrap(),
+            Some(WatchEvent::Added(_))
+        ));
+
+        // Deletion waits for the finalizers to be removed
+        api.delete("web", &DeleteParams::default()).await.unwrap();
+        let Some(WatchEvent::Modified(deleting)) = events.try_next().await.unwrap() else {
+            panic!("expected the deletion to be pending");
+        };
+        assert!(deleting.metadata.deletion_timestamp.is_some());
+        let patch = json!({"metadata": {"finalizers": null}});
+        api.patch("web", &PatchParams::default(), &Patch::Merge(&patch))
+            .await
+            .unwrap();
+        assert!(matches!(
+            events.try_next().await.unwrap(),
+            Some(WatchEvent::Deleted(_))
+        ));
+        assert!(api.get_opt("web").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn rejects_invalid_requests() {
+        let mut existing = configmap("web", json!({}));
+        existing.metadata.namespace = Some("default".into());
+        let client = FakeApiServer::new().with_object(&existing).client();
+        let path = "/api/v1/namespaces/default/configmaps/web";
+
+        // A JSON patch that replaces the object with a string
+        let patch = http::Request::patch(path)
+            .header(http::header::CONTENT_TYPE, "application/json-patch+json")
+            .body(br#"[{"op": "replace", "path": "", "value": "web"}]"#.to_vec())
+            .unwrap();
+        let err = client.request::<ConfigMap>(patch).await.unwrap_err();
+        assert!(matches!(err, Error::Api(ae) if ae.code == 422 && ae.reason == "Invalid"));
+
+        let delete = http::Request::delete(format!("{path}/status"))
+            .body(vec![])
+            .unwrap();
+        let err = client.request::<ConfigMap>(delete).await.unwrap_err();
+        assert!(matches!(err, Error::Api(ae) if ae.code == 405 && ae.reason == "MethodNotAllowed"));
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+        assert!(api.get_opt("web").await.unwrap().is_some());
+    }
+
+    #[tokio::test]
+    async fn dry_run_creates_change_nothing() {
+        let client = FakeApiServer::new().with_resource::<ConfigMap>().client();
+        let api: Api<ConfigMap> = Api::default_namespaced(client);
+        let list_version = || async {
+            api.list(&ListParams::default())
+                .await
+                .unwrap()
+                .metadata
+                .resource_version
+        };
+
+        let before = list_version().await;
+        let pp = PostParams {
+            dry_run: true,
+            ..PostParams::default()
+        };
+        api.create(&pp, &configmap("web", json!({}))).await.unwrap();
+        assert_eq!(list_version().await, before);
+        assert!(api.get_opt("web").await.unwrap().is_none());
+    }
+
+    #[tokio::test]
+    async fn watches_from_compacted_versions_expire() {
+        let server = (0..MAX_EVENTS + 2).fold(FakeApiServer::new(), |server, i| {
+            let mut cm = configmap(&format!("cm-{i}"), json!({}));
+            cm.metadata.namespace = Some("default".into());
+            server.with_object(&cm)
+        });
+        let api: Api<ConfigMap> = Api::default_namespaced(server.client());
+
+        let mut events = api.watch(&WatchParams::default(), "1").await.unwrap().boxed();
+        assert!(matches!(
+            events.try_next().await.unwrap(),
+            Some(WatchEvent::Error(err)) if err.code == 410
+        ));
+        assert!(events.try_next().await.unwrap().is_none());
+
+        // Recent versions can still be resumed from
+        let mut events = api.watch(&WatchParams::default(), "2").await.unwrap().boxed();
+        assert!(matches!(
+            events.try_next().await.unwrap(),
+            Some(WatchEvent::Added(cm)) if cm.metadata.name.as_deref() == Some("cm-2")
+        ));
+    }
+}
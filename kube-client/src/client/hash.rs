@@ -0,0 +1,23 @@
+//! Hashing for values that are persisted outside of the process
+
+/// A stable FNV-1a hash, which does not change between Rust versions or platforms
+///
+/// Unlike [`std::hash::DefaultHasher`], this is safe to use for file names and fixtures.
+pub(crate) fn stable_hash(bytes: &[u8]) -> String {
+    let hash = bytes.iter().fold(0xcbf2_9ce4_8422_2325_u64, |hash, byte| {
+        (hash ^ u64::from(*byte)).wrapping_mul(0x0100_0000_01b3)
+    });
+    format!("{hash:016x}")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::stable_hash;
+
+    #[test]
+    fn matches_fnv1a_reference_values() {
+        assert_eq!(stable_hash(b""), "cbf29ce484222325");
+        assert_eq!(stable_hash(b"a"), "af63dc4c8601ec8c");
+        assert_eq!(stable_hash(b"foobar"), "85944171f73967e8");
+    }
+}
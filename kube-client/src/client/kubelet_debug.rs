@@ -3,7 +3,11 @@ use crate::{
     client::AsyncBufRead,
     Client, Error, Result,
 };
-use kube_core::{kubelet_debug::KubeletDebugParams, Request};
+use k8s_openapi::api::core::v1::Pod;
+use kube_core::{
+    kubelet_debug::{KubeletDebugParams, Summary},
+    ObjectList, Request,
+};
 use std::fmt::Debug;
 
 /// Methods to access debug endpoints directly on `kubelet`
@@ -70,7 +74,7 @@ impl Client {
             Request::kubelet_node_portforward(kubelet_params, ports).map_err(Error::BuildRequest)?;
         req.extensions_mut().insert("kubelet_node_portforward");
         let stream = self.connect(req).await?;
-        Ok(Portforwarder::new(stream, ports))
+        Ok(Portforwarder::new(stream, ports, Default::default()))
     }
 
     /// Stream logs directly from node
@@ -88,4 +92,39 @@ impl Client {
         req.extensions_mut().insert("kubelet_node_log");
         self.request_stream(req).await
     }
+
+    /// Get the resource usage of the node and its pods from `/stats/summary`
+    ///
+    /// With `only_cpu_and_memory`, the kubelet skips the more expensive filesystem and network stats.
+    ///
+    /// ## Warning
+    /// This method uses the insecure `kubelet_debug` interface.
+    pub async fn kubelet_node_stats_summary(&self, only_cpu_and_memory: bool) -> Result<Summary> {
+        let mut req =
+            Request::kubelet_node_stats_summary(only_cpu_and_memory).map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("kubelet_node_stats_summary");
+        self.request(req).await
+    }
+
+    /// List the pods running on the node from `/pods`
+    ///
+    /// This includes static pods, and pods that the apiserver no longer knows about but are still running.
+    ///
+    /// ## Warning
+    /// This method uses the insecure `kubelet_debug` interface. See [`Api::list`](crate::Api::list) for the normal interface.
+    pub async fn kubelet_node_pods(&self) -> Result<ObjectList<Pod>> {
+        let mut req = Request::kubelet_node_pods().map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("kubelet_node_pods");
+        self.request(req).await
+    }
+
+    /// Get the resource metrics of the node from `/metrics/resource`, in the Prometheus text format
+    ///
+    /// ## Warning
+    /// This method uses the insecure `kubelet_debug` interface.
+    pub async fn kubelet_node_resource_metrics(&self) -> Result<String> {
+        let mut req = Request::kubelet_node_resource_metrics().map_err(Error::BuildRequest)?;
+        req.extensions_mut().insert("kubelet_node_resource_metrics");
+        self.request_text(req).await
+    }
 }
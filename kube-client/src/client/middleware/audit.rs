@@ -0,0 +1,329 @@
+//! Recording of the mutations made by a client.
+use std::{
+    collections::BTreeSet,
+    io::Write,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use chrono::Utc;
+use futures::{future::BoxFuture, FutureExt};
+use http::{Request, Response};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use serde::Serialize;
+use serde_json::Value;
+use tower::{Layer, Service};
+
+use super::trace::ResourceRequest;
+use crate::client::Body;
+
+/// Paths below this depth are summarized by their parent in [`AuditRecord::changes`]
+const MAX_CHANGE_DEPTH: usize = 3;
+
+/// Fields that identify an object rather than change it
+const IDENTIFYING_FIELDS: &[&str] = &[
+    "apiVersion",
+    "kind",
+    "metadata.name",
+    "metadata.namespace",
+    "metadata.resourceVersion",
+    "metadata.uid",
+    "metadata.managedFields",
+];
+
+/// A mutating request made by a client, see [`AuditLayer`]
+#[derive(Clone, Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    /// When the request was made
+    pub time: Time,
+    /// The Kubernetes verb of the request, such as `create`, `patch` or `delete`
+    pub verb: String,
+    /// The group of the resource, empty for the core group
+    pub group: String,
+    /// The version of the resource
+    pub version: String,
+    /// The plural name of the resource, such as `deployments`
+    pub resource: String,
+    /// The subresource, such as `status` or `scale`
+    pub subresource: Option<String>,
+    /// The kind of the object, if the request body names it
+    pub kind: Option<String>,
+    /// The namespace of the object, or `None` for cluster-scoped objects
+    pub namespace: Option<String>,
+    /// The name of the object, or `None` for requests on collections
+    pub name: Option<String>,
+    /// The field manager of the request
+    pub field_manager: Option<String>,
+    /// Whether the request was a dry run, which changes nothing
+    pub dry_run: bool,
+    /// The status code of the response, or `None` if the request failed without a response
+    pub status: Option<u16>,
+    /// A summary of the fields set by the request, such as `spec.replicas`
+    ///
+    /// JSON patches are summarized by their operations, such as `replace /spec/replicas`.
+    /// Empty for requests without a body, such as deletions, and for streamed bodies.
+    pub changes: Vec<String>,
+}
+
+impl AuditRecord {
+    /// Records a mutating request, or returns `None` for other requests
+    fn from_request(req: &Request<Body>) -> Option<Self> {
+        let request = ResourceRequest::parse(req.method(), req.uri()).filter(ResourceRequest::is_mutating)?;
+        let query = req.uri().query().unwrap_or_default();
+        let param = |key: &str| {
+            query
+                .split('&')
+                .filter_map(|param| param.split_once('='))
+                .find(|(k, _)| *k == key)
+                .map(|(_, value)| value.to_string())
+        };
+        let body = req
+            .body()
+            .buffered()
+            .and_then(|body| serde_json::from_slice::<Value>(body).ok());
+        let body_field = |pointer: &str| {
+            body.as_ref()
+                .and_then(|body| body.pointer(pointer))
+                .and_then(Value::as_str)
+                .map(String::from)
+        };
+        Some(Self {
+            time: Time(Utc::now()),
+            verb: request.verb.to_string(),
+            group: request.group.to_string(),
+            version: request.version.to_string(),
+            resource: request.resource.to_string(),
+            subresource: request.subresource.map(String::from),
+            kind: body_field("/kind"),
+            namespace: request.namespace.map(String::from),
+            name: request
+                .name
+                .map(String::from)
+                .or_else(|| body_field("/metadata/name")),
+            field_manager: param("fieldManager"),
+            dry_run: param("dryRun").is_some(),
+            status: None,
+            changes: body.as_ref().map(summarize_changes).unwrap_or_default(),
+        })
+    }
+}
+
+/// Summarizes the fields set by a request body
+fn summarize_changes(body: &Value) -> Vec<String> {
+    match body {
+        Value::Array(operations) => operations
+            .iter()
+            .filter_map(|operation| {
+                let op = operation.get("op")?.as_str()?;
+                let path = operation.get("path")?.as_str()?;
+                Some(format!("{op} {path}"))
+            })
+            .collect(),
+        _ => {
+            let mut paths = BTreeSet::new();
+            collect_paths(body, String::new(), 0, &mut paths);
+            paths.into_iter().collect()
+        }
+    }
+}
+
+fn collect_paths(value: &Value, path: String, depth: usize, paths: &mut BTreeSet<String>) {
+    if IDENTIFYING_FIELDS.contains(&path.as_str()) {
+        return;
+    }
+    match value {
+        Value::Object(fields) if depth < MAX_CHANGE_DEPTH && !fields.is_empty() => {
+            for (key, value) in fields {
+                let path = if path.is_empty() {
+                    key.clone()
+                } else {
+                    format!("{path}.{key}")
+                };
+                collect_paths(value, path, depth + 1, paths);
+            }
+        }
+        _ if path.is_empty() => {}
+        _ => {
+            paths.insert(path);
+        }
+    }
+}
+
+/// Receives the [`AuditRecord`] of every mutating request, see [`AuditLayer`]
+#[derive(Clone)]
+pub struct AuditSink(Arc<dyn Fn(&AuditRecord) + Send + Sync>);
+
+impl AuditSink {
+    /// Call `f` with every record
+    pub fn callback(f: impl Fn(&AuditRecord) + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+
+    /// Send every record into a channel
+    ///
+    /// Records are dropped once the receiver is closed.
+    pub fn channel(sender: tokio::sync::mpsc::UnboundedSender<AuditRecord>) -> Self {
+        Self::callback(move |record| {
+            let _ = sender.send(record.clone());
+        })
+    }
+
+    /// Write every record to `writer` as a line of JSON, such as to a [`File`](std::fs::File)
+    ///
+    /// Records that fail to be written are logged and dropped.
+    pub fn writer(writer: impl Write + Send + 'static) -> Self {
+        let writer = Mutex::new(writer);
+        Self::callback(move |record| {
+            let mut writer = writer.lock().unwrap_or_else(|poisoned| poisoned.into_inner());
+            let written = serde_json::to_writer(&mut *writer, record)
+                .map_err(std::io::Error::from)
+                .and_then(|()| writer.write_all(b"\n"))
+                .and_then(|()| writer.flush());
+            if let Err(err) = written {
+                tracing::warn!("failed to write audit record: {err}");
+            }
+        })
+    }
+}
+
+impl std::fmt::Debug for AuditSink {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("AuditSink")
+    }
+}
+
+/// Layer that records every mutating request of a client into an [`AuditSink`]
+///
+/// Records are made once the response arrives, giving an audit trail of what a controller changed and when.
+///
+/// ```no_run
+/// # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+/// use kube::{client::{middleware::{AuditLayer, AuditSink}, ClientBuilder}, Config};
+///
+/// let audit_log = std::fs::File::create("audit.jsonl")?;
+/// let config = Config::infer().await?;
+/// let client = ClientBuilder::try_from(config)?
+///     .with_layer(&AuditLayer::new(AuditSink::writer(audit_log)))
+///     .build();
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct AuditLayer {
+    sink: AuditSink,
+}
+
+impl AuditLayer {
+    /// Record mutating requests into `sink`
+    pub fn new(sink: AuditSink) -> Self {
+        Self { sink }
+    }
+}
+
+impl<S> Layer<S> for AuditLayer {
+    type Service = Audit<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Audit {
+            sink: self.sink.clone(),
+            inner,
+        }
+    }
+}
+
+/// Service that records every mutating request into an [`AuditSink`]
+#[derive(Debug, Clone)]
+pub struct Audit<S> {
+    sink: AuditSink,
+    inner: S,
+}
+
+impl<S, ResBody> Service<Request<Body>> for Audit<S>
+where
+    S: Service<Request<Body>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let record = AuditRecord::from_request(&req);
+        let res = self.inner.call(req);
+        let sink = self.sink.clone();
+        async move {
+            let res = res.await;
+            if let Some(mut record) = record {
+                record.status = res.as_ref().ok().map(|res| res.status().as_u16());
+                (sink.0)(&record);
+            }
+            res
+        }
+        .boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::Method;
+    use serde_json::json;
+    use tower::ServiceExt;
+    use tower_test::mock;
+
+    #[test]
+    fn summarizes_changes() {
+        let patch = json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": {"name": "web", "labels": {"app": "web"}},
+            "spec": {"replicas": 3, "template": {"spec": {"containers": []}}},
+        });
+        let changes = ["metadata.labels.app", "spec.replicas", "spec.template.spec"];
+        assert_eq!(summarize_changes(&patch), changes);
+        let json_patch = json!([{"op": "replace", "path": "/spec/replicas", "value": 3}]);
+        assert_eq!(summarize_changes(&json_patch), vec!["replace /spec/replicas"]);
+    }
+
+    #[tokio::test]
+    async fn records_mutating_requests() {
+        let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<()>>();
+        let service = AuditLayer::new(AuditSink::channel(sender)).layer(mock_service);
+        let spawned = tokio::spawn(async move {
+            let mut handle = std::pin::pin!(handle);
+            for _ in 0..2 {
+                let (_request, send) = handle.next_request().await.expect("service not called");
+                send.send_response(Response::new(()));
+            }
+        });
+        let get = Request::get("/apis/apps/v1/namespaces/prod/deployments/web")
+            .body(Body::empty())
+            .unwrap();
+        service.clone().oneshot(get).await.unwrap();
+        let patch = Request::builder()
+            .method(Method::PATCH)
+            .uri("/apis/apps/v1/namespaces/prod/deployments/web?fieldManager=my-controller&dryRun=All")
+            .body(Body::from(br#"{"spec":{"replicas":3}}"#.to_vec()))
+            .unwrap();
+        service.oneshot(patch).await.unwrap();
+        spawned.await.unwrap();
+
+        let record = receiver.recv().await.unwrap();
+        assert_eq!(record.verb, "patch");
+        assert_eq!(record.resource, "deployments");
+        assert_eq!(record.namespace.as_deref(), Some("prod"));
+        assert_eq!(record.name.as_deref(), Some("web"));
+        assert_eq!(record.field_manager.as_deref(), Some("my-controller"));
+        assert!(record.dry_run);
+        assert_eq!(record.status, Some(200));
+        assert_eq!(record.changes, vec!["spec.replicas"]);
+        assert!(receiver.try_recv().is_err(), "only mutations are recorded");
+    }
+}
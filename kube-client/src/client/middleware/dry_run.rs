@@ -0,0 +1,138 @@
+//! Dry runs of every request of a client.
+use std::task::{Context, Poll};
+
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
+use http::{uri::PathAndQuery, Method, Request, Uri};
+use tower::{BoxError, Layer, Service};
+
+use super::trace::ResourceRequest;
+use crate::Error;
+
+/// Subresources that act on running workloads, which the apiserver cannot dry run
+const UNSUPPORTED_SUBRESOURCES: &[&str] = &["exec", "attach", "portforward", "proxy"];
+
+/// Layer that applies [`DryRun`] to every request, see [`Client::dry_run`](crate::Client::dry_run).
+#[derive(Debug, Clone, Copy, Default)]
+pub struct DryRunLayer;
+
+impl<S> Layer<S> for DryRunLayer {
+    type Service = DryRun<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        DryRun { inner }
+    }
+}
+
+/// Middleware that sends every mutating request with `dryRun=All`, so that nothing is persisted.
+///
+/// Reads are passed through unchanged. Requests that cannot be dry run, such as `exec` into pods
+/// or mutations outside of the resource API, fail with [`Error::DryRunUnsupported`] without being sent.
+#[derive(Debug, Clone)]
+pub struct DryRun<S> {
+    inner: S,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for DryRun<S>
+where
+    S: Service<Request<ReqBody>>,
+    S::Error: Into<BoxError> + 'static,
+    S::Future: Send + 'static,
+    S::Response: Send + 'static,
+{
+    type Error = BoxError;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx).map_err(Into::into)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        match dry_run_uri(req.method(), req.uri()) {
+            Ok(Some(uri)) => *req.uri_mut() = uri,
+            Ok(None) => {}
+            Err(err) => return futures::future::err(err.into()).boxed(),
+        }
+        self.inner.call(req).map_err(Into::into).boxed()
+    }
+}
+
+/// The URI of a request with `dryRun=All`, or `None` if the request changes nothing
+fn dry_run_uri(method: &Method, uri: &Uri) -> Result<Option<Uri>, Error> {
+    let unsupported = || Error::DryRunUnsupported(format!("{method} {}", uri.path()));
+    let Some(request) = ResourceRequest::parse(method, uri) else {
+        return match *method {
+            Method::GET | Method::HEAD | Method::OPTIONS => Ok(None),
+            _ => Err(unsupported()),
+        };
+    };
+    if request
+        .subresource
+        .is_some_and(|subresource| UNSUPPORTED_SUBRESOURCES.contains(&subresource))
+    {
+        return Err(unsupported());
+    }
+    if !request.is_mutating() {
+        return Ok(None);
+    }
+    let query = uri.query().unwrap_or_default();
+    if query.split('&').any(|param| param.starts_with("dryRun=")) {
+        return Ok(None);
+    }
+    let path_and_query = if query.is_empty() {
+        format!("{}?dryRun=All", uri.path())
+    } else {
+        format!("{}?{query}&dryRun=All", uri.path())
+    };
+    let mut parts = uri.clone().into_parts();
+    parts.path_and_query =
+        Some(PathAndQuery::try_from(path_and_query).map_err(|err| Error::HttpError(err.into()))?);
+    Uri::from_parts(parts)
+        .map(Some)
+        .map_err(|err| Error::HttpError(err.into()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::dry_run_uri;
+    use crate::Error;
+    use http::{Method, Uri};
+
+    fn dry_run(method: Method, uri: &'static str) -> Result<Option<String>, Error> {
+        dry_run_uri(&method, &Uri::from_static(uri)).map(|uri| uri.map(|uri| uri.to_string()))
+    }
+
+    #[test]
+    fn forces_dry_runs_of_mutations() {
+        assert_eq!(
+            dry_run(
+                Method::PATCH,
+                "/apis/apps/v1/namespaces/ns/deployments/web?fieldManager=me"
+            )
+            .unwrap(),
+            Some("/apis/apps/v1/namespaces/ns/deployments/web?fieldManager=me&dryRun=All".into())
+        );
+        assert_eq!(
+            dry_run(Method::DELETE, "/api/v1/namespaces/ns/pods/web").unwrap(),
+            Some("/api/v1/namespaces/ns/pods/web?dryRun=All".into())
+        );
+        assert_eq!(
+            dry_run(Method::POST, "/api/v1/namespaces/ns/pods?dryRun=All").unwrap(),
+            None
+        );
+        assert_eq!(dry_run(Method::GET, "/api/v1/namespaces/ns/pods").unwrap(), None);
+        assert_eq!(dry_run(Method::GET, "/version").unwrap(), None);
+    }
+
+    #[test]
+    fn rejects_requests_that_cannot_be_dry_run() {
+        assert!(matches!(
+            dry_run(Method::GET, "/api/v1/namespaces/ns/pods/web/exec?command=ls"),
+            Err(Error::DryRunUnsupported(_))
+        ));
+        assert!(matches!(
+            dry_run(Method::POST, "/api/v1/namespaces/ns/pods/web/portforward"),
+            Err(Error::DryRunUnsupported(_))
+        ));
+    }
+}
@@ -0,0 +1,145 @@
+use std::{collections::HashMap, sync::Arc};
+
+use http::{header::HeaderName, request::Request, HeaderValue};
+use tower::{Layer, Service};
+
+const IMPERSONATE_USER: &str = "impersonate-user";
+const IMPERSONATE_GROUP: &str = "impersonate-group";
+const IMPERSONATE_EXTRA_PREFIX: &str = "impersonate-extra-";
+
+#[derive(Clone)]
+/// Layer that adds [impersonation](https://kubernetes.io/docs/reference/access-authn-authz/authentication/#user-impersonation)
+/// headers to each request
+///
+/// Requests that already impersonate a user are passed through unchanged,
+/// so the outermost impersonation of a client wins.
+pub struct ImpersonateLayer {
+    headers: Arc<Vec<(HeaderName, HeaderValue)>>,
+}
+
+impl ImpersonateLayer {
+    /// Impersonate `user`, with its `groups` and `extra` information
+    ///
+    /// Keys of `extra` are percent-encoded into the header names, as required by the apiserver.
+    ///
+    /// # Errors
+    ///
+    /// Fails if any of the values are not valid header values.
+    pub fn new(
+        user: &str,
+        groups: &[String],
+        extra: &HashMap<String, Vec<String>>,
+    ) -> Result<Self, http::Error> {
+        let mut headers = vec![(
+            HeaderName::from_static(IMPERSONATE_USER),
+            HeaderValue::from_str(user)?,
+        )];
+        for group in groups {
+            headers.push((
+                HeaderName::from_static(IMPERSONATE_GROUP),
+                HeaderValue::from_str(group)?,
+            ));
+        }
+        for (key, values) in extra {
+            let name =
+                HeaderName::from_bytes(format!("{IMPERSONATE_EXTRA_PREFIX}{}", escape_key(key)).as_bytes())?;
+            for value in values {
+                headers.push((name.clone(), HeaderValue::from_str(value)?));
+            }
+        }
+        Ok(Self {
+            headers: Arc::new(headers),
+        })
+    }
+}
+
+/// Percent-encodes an extra key like `url.PathEscape` in Go, and lowercases it since header names are case-insensitive
+fn escape_key(key: &str) -> String {
+    let mut escaped = String::with_capacity(key.len());
+    for byte in key.to_lowercase().bytes() {
+        if byte.is_ascii_alphanumeric() || b"-._~".contains(&byte) {
+            escaped.push(char::from(byte));
+        } else {
+            escaped.push_str(&format!("%{byte:02X}"));
+        }
+    }
+    escaped
+}
+
+impl<S> Layer<S> for ImpersonateLayer {
+    type Service = Impersonate<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Impersonate {
+            inner,
+            headers: self.headers.clone(),
+        }
+    }
+}
+
+#[derive(Clone)]
+/// Service that adds impersonation headers to each request
+pub struct Impersonate<S> {
+    inner: S,
+    headers: Arc<Vec<(HeaderName, HeaderValue)>>,
+}
+
+impl<S, ReqBody> Service<Request<ReqBody>> for Impersonate<S>
+where
+    S: Service<Request<ReqBody>>,
+{
+    type Error = S::Error;
+    type Future = S::Future;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut std::task::Context<'_>) -> std::task::Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+        if !req.headers().contains_key(IMPERSONATE_USER) {
+            req.headers_mut().extend(self.headers.iter().cloned());
+        }
+        self.inner.call(req)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::{Request, Response};
+    use tower::ServiceExt;
+    use tower_test::mock;
+
+    #[tokio::test]
+    async fn adds_impersonation_headers() {
+        let extra = HashMap::from([("Scopes.example.com/Tenant".to_string(), vec!["a".to_string()])]);
+        let outer = ImpersonateLayer::new("jane", &["devs".into(), "ops".into()], &extra).unwrap();
+        let inner = ImpersonateLayer::new("system:admin", &[], &HashMap::new()).unwrap();
+        let (mock_service, handle) = mock::pair::<Request<()>, Response<()>>();
+        let service = outer.layer(inner.layer(mock_service));
+        let spawned = tokio::spawn(async move {
+            let mut handle = std::pin::pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            let headers = request.headers();
+            assert_eq!(
+                headers.get_all("impersonate-user").iter().collect::<Vec<_>>(),
+                vec!["jane"]
+            );
+            assert_eq!(
+                headers.get_all("impersonate-group").iter().collect::<Vec<_>>(),
+                vec!["devs", "ops"]
+            );
+            assert_eq!(
+                headers
+                    .get("impersonate-extra-scopes.example.com%2Ftenant")
+                    .unwrap(),
+                "a"
+            );
+            send.send_response(Response::new(()));
+        });
+        service.oneshot(Request::new(())).await.unwrap();
+        spawned.await.unwrap();
+    }
+}
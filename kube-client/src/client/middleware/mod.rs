@@ -2,11 +2,27 @@
 use tower::{filter::AsyncFilterLayer, util::Either, Layer};
 pub(crate) use tower_http::auth::AddAuthorizationLayer;
 
+mod audit;
 mod base_uri;
+mod dry_run;
 mod extra_headers;
+mod impersonate;
+mod rate_limit;
+mod retry;
+pub(crate) mod trace;
+mod warnings;
 
+pub use audit::{Audit, AuditLayer, AuditRecord, AuditSink};
 pub use base_uri::{BaseUri, BaseUriLayer};
+pub use dry_run::{DryRun, DryRunLayer};
 pub use extra_headers::{ExtraHeaders, ExtraHeadersLayer};
+pub use impersonate::{Impersonate, ImpersonateLayer};
+pub use rate_limit::{RateLimitLayer, RateLimited};
+pub use retry::{RetryLayer, RetryPolicy};
+#[cfg(feature = "otel")]
+#[cfg_attr(docsrs, doc(cfg(feature = "otel")))]
+pub use trace::{PropagateContext, PropagateContextLayer};
+pub use warnings::{WarningLayer, Warnings};
 
 use super::auth::RefreshableToken;
 /// Layer to set up `Authorization` header depending on the config.
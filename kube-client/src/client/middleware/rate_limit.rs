@@ -0,0 +1,236 @@
+//! Client side throttling of requests.
+use std::{
+    sync::Arc,
+    task::{Context, Poll},
+    time::Duration,
+};
+
+use futures::{future::BoxFuture, FutureExt};
+use http::{header::RETRY_AFTER, Request, Response, StatusCode};
+use tokio::{sync::Mutex, time::Instant};
+use tower::{Layer, Service};
+
+use crate::config::RateLimit;
+
+/// Layer that applies [`RateLimited`] to throttle requests to the apiserver.
+///
+/// The token bucket is shared between all services created by the same layer.
+#[derive(Debug, Clone)]
+pub struct RateLimitLayer {
+    bucket: Arc<Mutex<TokenBucket>>,
+}
+
+impl RateLimitLayer {
+    /// Throttle requests to `qps` per second on average, with bursts of up to `burst` requests.
+    ///
+    /// A non-positive `qps` disables throttling.
+    pub fn new(qps: f32, burst: u32) -> Self {
+        Self {
+            bucket: Arc::new(Mutex::new(TokenBucket::new(qps, burst))),
+        }
+    }
+}
+
+impl From<RateLimit> for RateLimitLayer {
+    fn from(rate_limit: RateLimit) -> Self {
+        Self::new(rate_limit.qps, rate_limit.burst)
+    }
+}
+
+impl<S> Layer<S> for RateLimitLayer {
+    type Service = RateLimited<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        RateLimited {
+            bucket: self.bucket.clone(),
+            inner,
+        }
+    }
+}
+
+/// Middleware that delays requests until the rate limit allows them to be sent.
+///
+/// A `429 Too Many Requests` response with a `Retry-After` header holds back all requests
+/// for the requested duration. The response itself is still passed on to the caller.
+#[derive(Debug, Clone)]
+pub struct RateLimited<S> {
+    bucket: Arc<Mutex<TokenBucket>>,
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for RateLimited<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>> + Clone + Send + 'static,
+    S::Future: Send + 'static,
+    ReqBody: Send + 'static,
+    ResBody: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        // Use the service that was polled ready, and leave a fresh clone in its place
+        let clone = self.inner.clone();
+        let mut inner = std::mem::replace(&mut self.inner, clone);
+        let bucket = self.bucket.clone();
+        async move {
+            let ready_at = bucket.lock().await.reserve(Instant::now());
+            tokio::time::sleep_until(ready_at).await;
+
+            let res = inner.call(req).await?;
+            if res.status() == StatusCode::TOO_MANY_REQUESTS {
+                if let Some(retry_after) = retry_after(&res) {
+                    bucket.lock().await.pause_until(Instant::now() + retry_after);
+                }
+            }
+            Ok(res)
+        }
+        .boxed()
+    }
+}
+
+/// Parses the `Retry-After` header of a response, only the delay-seconds form is used by the apiserver.
+pub(crate) fn retry_after<B>(res: &Response<B>) -> Option<Duration> {
+    let seconds = res
+        .headers()
+        .get(RETRY_AFTER)?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()?;
+    Some(Duration::from_secs(seconds))
+}
+
+/// A token bucket where requests reserve their token up front, queueing in order of arrival.
+#[derive(Debug)]
+struct TokenBucket {
+    qps: f64,
+    burst: f64,
+    /// Available tokens as of `last_update`, negative when requests are queued for future tokens
+    tokens: f64,
+    last_update: Instant,
+    paused_until: Option<Instant>,
+}
+
+impl TokenBucket {
+    fn new(qps: f32, burst: u32) -> Self {
+        let burst = f64::from(burst.max(1));
+        Self {
+            qps: f64::from(qps),
+            burst,
+            tokens: burst,
+            last_update: Instant::now(),
+            paused_until: None,
+        }
+    }
+
+    /// Takes a token, and returns the instant at which the request may be sent
+    fn reserve(&mut self, now: Instant) -> Instant {
+        if self.qps <= 0.0 {
+            return now;
+        }
+        let start = match self.paused_until {
+            Some(paused_until) if paused_until > now => paused_until,
+            _ => now,
+        };
+        if start > self.last_update {
+            let elapsed = start.duration_since(self.last_update).as_secs_f64();
+            self.tokens = (self.tokens + elapsed * self.qps).min(self.burst);
+            self.last_update = start;
+        }
+        self.tokens -= 1.0;
+        if self.tokens >= 0.0 {
+            self.last_update
+        } else {
+            self.last_update + Duration::from_secs_f64(-self.tokens / self.qps)
+        }
+    }
+
+    /// Holds back all requests until `until`
+    fn pause_until(&mut self, until: Instant) {
+        if self
+            .paused_until
+            .map_or(true, |paused_until| paused_until < until)
+        {
+            self.paused_until = Some(until);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::pin::pin;
+
+    use tower_test::mock;
+
+    use crate::client::Body;
+
+    #[tokio::test(start_paused = true)]
+    async fn throttles_after_burst() {
+        let (service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let mut service = RateLimitLayer::new(2.0, 2).layer(service);
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            while let Some((_request, send)) = handle.next_request().await {
+                send.send_response(Response::builder().body(Body::empty()).unwrap());
+            }
+        });
+
+        let start = Instant::now();
+        for _ in 0..4 {
+            futures::future::poll_fn(|cx| service.poll_ready(cx))
+                .await
+                .unwrap();
+            service
+                .call(Request::builder().uri("/").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+        }
+        // Two requests are sent immediately, the other two are spaced by 1/qps
+        assert_eq!(start.elapsed(), Duration::from_secs(1));
+
+        drop(service);
+        spawned.await.unwrap();
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn respects_retry_after() {
+        let (service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let mut service = RateLimitLayer::new(100.0, 100).layer(service);
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (_request, send) = handle.next_request().await.expect("service not called");
+            send.send_response(
+                Response::builder()
+                    .status(StatusCode::TOO_MANY_REQUESTS)
+                    .header(RETRY_AFTER, "3")
+                    .body(Body::empty())
+                    .unwrap(),
+            );
+            let (_request, send) = handle.next_request().await.expect("service not called");
+            send.send_response(Response::builder().body(Body::empty()).unwrap());
+        });
+
+        let start = Instant::now();
+        for status in [StatusCode::TOO_MANY_REQUESTS, StatusCode::OK] {
+            futures::future::poll_fn(|cx| service.poll_ready(cx))
+                .await
+                .unwrap();
+            let res = service
+                .call(Request::builder().uri("/").body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            assert_eq!(res.status(), status);
+        }
+        assert_eq!(start.elapsed(), Duration::from_secs(3));
+        spawned.await.unwrap();
+    }
+}
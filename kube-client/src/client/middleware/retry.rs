@@ -0,0 +1,248 @@
+//! Retry requests that failed for transient reasons.
+use std::{fmt, sync::Arc, time::Duration};
+
+use http::{Request, Response, StatusCode};
+use tower::{
+    retry::{
+        budget::{Budget, TpsBudget},
+        Policy, Retry,
+    },
+    Layer,
+};
+
+use super::rate_limit::retry_after;
+use crate::client::Body;
+
+/// Layer that applies a [`RetryPolicy`] to requests, see [`ClientBuilder::with_retries`](crate::client::ClientBuilder::with_retries).
+#[derive(Debug, Clone)]
+pub struct RetryLayer(tower::retry::RetryLayer<RetryPolicy>);
+
+impl RetryLayer {
+    /// Retry requests according to `policy`.
+    pub fn new(policy: RetryPolicy) -> Self {
+        Self(tower::retry::RetryLayer::new(policy))
+    }
+}
+
+impl<S> Layer<S> for RetryLayer {
+    type Service = Retry<RetryPolicy, S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        self.0.layer(inner)
+    }
+}
+
+/// Policy for retrying requests that failed for transient reasons.
+///
+/// The following failures are retried with exponential backoff:
+///
+/// - `429 Too Many Requests`, for any request since the apiserver did not process it.
+///   A `Retry-After` header from the apiserver takes precedence over the backoff.
+/// - `502 Bad Gateway`, `503 Service Unavailable` and `504 Gateway Timeout`, for idempotent requests.
+/// - Connection failures such as resets and timeouts, for idempotent requests.
+///
+/// Retries are limited per request, and across all requests by a [`TpsBudget`] so that
+/// an unavailable apiserver does not get flooded with retries.
+/// Requests with a streaming body cannot be replayed, and are never retried.
+#[derive(Clone)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    min_delay: Duration,
+    max_delay: Duration,
+    budget: Arc<TpsBudget>,
+    /// Number of retries of the current request, the policy is cloned for every request
+    attempts: u32,
+}
+
+impl Default for RetryPolicy {
+    /// Retry up to 3 times, with a backoff from 500ms up to 10s
+    ///
+    /// Retries are budgeted to 20% of the requests over the last 10s, with a floor of 10 retries per second.
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            min_delay: Duration::from_millis(500),
+            max_delay: Duration::from_secs(10),
+            budget: Arc::new(TpsBudget::new(Duration::from_secs(10), 10, 0.2)),
+            attempts: 0,
+        }
+    }
+}
+
+impl fmt::Debug for RetryPolicy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("RetryPolicy")
+            .field("max_retries", &self.max_retries)
+            .field("min_delay", &self.min_delay)
+            .field("max_delay", &self.max_delay)
+            .finish_non_exhaustive()
+    }
+}
+
+impl RetryPolicy {
+    /// Maximum number of times a single request is retried
+    #[must_use]
+    pub fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+        self
+    }
+
+    /// Exponential backoff between retries, doubling from `min_delay` up to `max_delay`
+    #[must_use]
+    pub fn backoff(mut self, min_delay: Duration, max_delay: Duration) -> Self {
+        self.min_delay = min_delay;
+        self.max_delay = max_delay;
+        self
+    }
+
+    /// Budget of retries shared by all requests of the client
+    #[must_use]
+    pub fn budget(mut self, budget: TpsBudget) -> Self {
+        self.budget = Arc::new(budget);
+        self
+    }
+
+    fn backoff_delay(&self) -> Duration {
+        self.min_delay
+            .saturating_mul(2u32.saturating_pow(self.attempts))
+            .min(self.max_delay)
+    }
+}
+
+impl<B, E> Policy<Request<Body>, Response<B>, E> for RetryPolicy {
+    type Future = tokio::time::Sleep;
+
+    fn retry(
+        &mut self,
+        req: &mut Request<Body>,
+        result: &mut Result<Response<B>, E>,
+    ) -> Option<Self::Future> {
+        let idempotent = req.method().is_idempotent();
+        let delay = match result {
+            Ok(res) if res.status() == StatusCode::TOO_MANY_REQUESTS => {
+                Some(retry_after(res).unwrap_or_else(|| self.backoff_delay()))
+            }
+            Ok(res)
+                if idempotent
+                    && matches!(
+                        res.status(),
+                        StatusCode::BAD_GATEWAY
+                            | StatusCode::SERVICE_UNAVAILABLE
+                            | StatusCode::GATEWAY_TIMEOUT
+                    ) =>
+            {
+                Some(retry_after(res).unwrap_or_else(|| self.backoff_delay()))
+            }
+            Err(_) if idempotent => Some(self.backoff_delay()),
+            _ => None,
+        };
+
+        match delay {
+            Some(delay) if self.attempts < self.max_retries && self.budget.withdraw() => {
+                self.attempts += 1;
+                tracing::debug!(attempt = self.attempts, ?delay, "retrying request");
+                Some(tokio::time::sleep(delay))
+            }
+            _ => {
+                if self.attempts == 0 {
+                    self.budget.deposit();
+                }
+                None
+            }
+        }
+    }
+
+    fn clone_request(&mut self, req: &Request<Body>) -> Option<Request<Body>> {
+        let mut clone = Request::new(req.body().try_clone()?);
+        *clone.method_mut() = req.method().clone();
+        *clone.uri_mut() = req.uri().clone();
+        *clone.version_mut() = req.version();
+        *clone.headers_mut() = req.headers().clone();
+        *clone.extensions_mut() = req.extensions().clone();
+        Some(clone)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::pin::pin;
+
+    use http::Method;
+    use tower::{Service, ServiceExt};
+    use tower_test::mock;
+
+    fn status(status: StatusCode) -> Response<Body> {
+        Response::builder().status(status).body(Body::empty()).unwrap()
+    }
+
+    async fn call_with_responses(method: Method, responses: Vec<StatusCode>) -> (StatusCode, usize) {
+        let (service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let mut service = RetryLayer::new(RetryPolicy::default()).layer(service);
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let mut calls = 0;
+            for response in responses {
+                let Some((request, send)) = handle.next_request().await else {
+                    break;
+                };
+                assert_eq!(request.into_body().collect_bytes().await.unwrap(), "body");
+                calls += 1;
+                send.send_response(status(response));
+            }
+            calls
+        });
+
+        let res = service
+            .ready()
+            .await
+            .unwrap()
+            .call(
+                Request::builder()
+                    .method(method)
+                    .uri("/")
+                    .body(b"body".to_vec().into())
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        drop(service);
+        (res.status(), spawned.await.unwrap())
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn retries_transient_failures() {
+        let responses = vec![
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::OK,
+        ];
+        assert_eq!(
+            call_with_responses(Method::GET, responses).await,
+            (StatusCode::OK, 3)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn gives_up_after_max_retries() {
+        let responses = vec![StatusCode::SERVICE_UNAVAILABLE; 5];
+        assert_eq!(
+            call_with_responses(Method::GET, responses).await,
+            (StatusCode::SERVICE_UNAVAILABLE, 4)
+        );
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn only_retries_throttled_non_idempotent_requests() {
+        let responses = vec![
+            StatusCode::TOO_MANY_REQUESTS,
+            StatusCode::SERVICE_UNAVAILABLE,
+            StatusCode::OK,
+        ];
+        assert_eq!(
+            call_with_responses(Method::POST, responses).await,
+            (StatusCode::SERVICE_UNAVAILABLE, 2)
+        );
+    }
+}
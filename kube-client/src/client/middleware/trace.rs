@@ -0,0 +1,255 @@
+//! Tracing of requests, with spans named by the Kubernetes verb and resource of the request.
+use http::{Method, Request, StatusCode, Uri};
+use tracing::Span;
+
+/// Creates the span of a request
+///
+/// With the `otel` feature, the span records the attributes of the
+/// [OpenTelemetry HTTP semantic conventions](https://opentelemetry.io/docs/specs/semconv/http/http-spans/).
+pub(crate) fn make_span<B>(req: &Request<B>) -> Span {
+    let name = ResourceRequest::parse(req.method(), req.uri())
+        .map(|request| request.to_string())
+        .or_else(|| req.extensions().get::<&'static str>().map(ToString::to_string))
+        .unwrap_or_else(|| req.method().to_string());
+    #[cfg(not(feature = "otel"))]
+    let span = tracing::debug_span!(
+        "HTTP",
+        http.method = %req.method(),
+        http.url = %req.uri(),
+        http.status_code = tracing::field::Empty,
+        otel.name = name.as_str(),
+        otel.kind = "client",
+        otel.status_code = tracing::field::Empty,
+    );
+    #[cfg(feature = "otel")]
+    let span = tracing::debug_span!(
+        "HTTP",
+        http.request.method = %req.method(),
+        url.full = %req.uri(),
+        server.address = req.uri().host(),
+        server.port = req.uri().port_u16().or_else(|| match req.uri().scheme_str() {
+            Some("http") => Some(80),
+            Some("https") => Some(443),
+            _ => None,
+        }),
+        http.response.status_code = tracing::field::Empty,
+        error.type = tracing::field::Empty,
+        otel.name = name.as_str(),
+        otel.kind = "client",
+        otel.status_code = tracing::field::Empty,
+    );
+    span
+}
+
+/// Records the response `status` on the span of a request
+pub(crate) fn record_status(span: &Span, status: StatusCode) {
+    #[cfg(not(feature = "otel"))]
+    span.record("http.status_code", status.as_u16());
+    #[cfg(feature = "otel")]
+    span.record("http.response.status_code", status.as_u16());
+    if status.is_client_error() || status.is_server_error() {
+        record_error(span, Some(status));
+    }
+}
+
+/// Marks the span of a request as failed, either with an error `status` or an error of the request itself
+#[cfg_attr(not(feature = "otel"), allow(unused_variables))]
+pub(crate) fn record_error(span: &Span, status: Option<StatusCode>) {
+    span.record("otel.status_code", "ERROR");
+    #[cfg(feature = "otel")]
+    span.record("error.type", status.as_ref().map_or("_OTHER", StatusCode::as_str));
+}
+
+/// The Kubernetes verb and resource of a request, parsed from its method and path
+pub(crate) struct ResourceRequest<'a> {
+    pub verb: &'static str,
+    pub group: &'a str,
+    pub version: &'a str,
+    pub resource: &'a str,
+    pub subresource: Option<&'a str>,
+    pub namespace: Option<&'a str>,
+    pub name: Option<&'a str>,
+}
+
+impl<'a> ResourceRequest<'a> {
+    /// Parses a request like the apiserver
+    ///
+    /// Returns `None` for requests that are not for a Kubernetes resource, such as `/version`.
+    pub fn parse(method: &Method, uri: &'a Uri) -> Option<Self> {
+        let segments = uri.path().trim_matches('/').split('/').collect::<Vec<_>>();
+        let (group, version, rest) = match segments.as_slice() {
+            ["api", version, rest @ ..] => ("", *version, rest),
+            ["apis", group, version, rest @ ..] => (*group, *version, rest),
+            _ => return None,
+        };
+        // Namespaced resources are nested in their namespace, unlike the subresources of namespaces themselves
+        let (namespace, rest) = match rest {
+            ["namespaces", _name, "status" | "finalize"] => (None, rest),
+            ["namespaces", namespace, rest @ ..] if !rest.is_empty() => (Some(*namespace), rest),
+            rest => (None, rest),
+        };
+        let (resource, name, subresource) = match rest {
+            [resource] => (*resource, None, None),
+            [resource, name] => (*resource, Some(*name), None),
+            [resource, name, subresource, ..] => (*resource, Some(*name), Some(*subresource)),
+            [] => return None,
+        };
+        let watch = uri.query().is_some_and(|query| {
+            query
+                .split('&')
+                .any(|param| param == "watch=true" || param == "watch=1")
+        });
+        let verb = match (method, name) {
+            (&Method::GET, _) if watch => "watch",
+            (&Method::GET, Some(_)) => "get",
+            (&Method::GET, None) => "list",
+            (&Method::POST, _) => "create",
+            (&Method::PUT, _) => "update",
+            (&Method::PATCH, _) => "patch",
+            (&Method::DELETE, Some(_)) => "delete",
+            (&Method::DELETE, None) => "deletecollection",
+            _ => return None,
+        };
+        Some(Self {
+            verb,
+            group,
+            version,
+            resource,
+            subresource,
+            namespace,
+            name,
+        })
+    }
+
+    /// Whether the request changes the cluster
+    pub fn is_mutating(&self) -> bool {
+        !matches!(self.verb, "get" | "list" | "watch")
+    }
+}
+
+impl std::fmt::Display for ResourceRequest<'_> {
+    /// Names the request like `kubectl`, such as `list pods`, `watch deployments.apps` or `get pods/log`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{} {}", self.verb, self.resource)?;
+        if !self.group.is_empty() {
+            write!(f, ".{}", self.group)?;
+        }
+        if let Some(subresource) = self.subresource {
+            write!(f, "/{subresource}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(feature = "otel")]
+pub use propagate::{PropagateContext, PropagateContextLayer};
+
+#[cfg(feature = "otel")]
+mod propagate {
+    use http::{header::HeaderName, HeaderMap, HeaderValue, Request};
+    use opentelemetry::propagation::Injector;
+    use tower::{Layer, Service};
+    use tracing_opentelemetry::OpenTelemetrySpanExt;
+
+    /// Layer that propagates the OpenTelemetry context of the current span in the headers of each request
+    ///
+    /// Headers are injected by the globally configured text map propagator,
+    /// such as a `TraceContextPropagator` for `traceparent` headers.
+    #[derive(Debug, Clone, Copy, Default)]
+    pub struct PropagateContextLayer;
+
+    impl<S> Layer<S> for PropagateContextLayer {
+        type Service = PropagateContext<S>;
+
+        fn layer(&self, inner: S) -> Self::Service {
+            PropagateContext { inner }
+        }
+    }
+
+    /// Service that propagates the OpenTelemetry context of the current span in the headers of each request
+    #[derive(Debug, Clone)]
+    pub struct PropagateContext<S> {
+        inner: S,
+    }
+
+    impl<S, ReqBody> Service<Request<ReqBody>> for PropagateContext<S>
+    where
+        S: Service<Request<ReqBody>>,
+    {
+        type Error = S::Error;
+        type Future = S::Future;
+        type Response = S::Response;
+
+        fn poll_ready(
+            &mut self,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), Self::Error>> {
+            self.inner.poll_ready(cx)
+        }
+
+        fn call(&mut self, mut req: Request<ReqBody>) -> Self::Future {
+            let context = tracing::Span::current().context();
+            opentelemetry::global::get_text_map_propagator(|propagator| {
+                propagator.inject_context(&context, &mut HeaderInjector(req.headers_mut()));
+            });
+            self.inner.call(req)
+        }
+    }
+
+    struct HeaderInjector<'a>(&'a mut HeaderMap);
+
+    impl Injector for HeaderInjector<'_> {
+        fn set(&mut self, key: &str, value: String) {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(key.as_bytes()),
+                HeaderValue::from_str(&value),
+            ) {
+                self.0.insert(name, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceRequest;
+    use http::{Method, Uri};
+
+    fn name(method: Method, uri: &'static str) -> Option<String> {
+        let uri = Uri::from_static(uri);
+        ResourceRequest::parse(&method, &uri).map(|request| request.to_string())
+    }
+
+    #[test]
+    fn names_requests_by_verb_and_resource() {
+        assert_eq!(
+            name(Method::GET, "/api/v1/namespaces/default/pods").unwrap(),
+            "list pods"
+        );
+        assert_eq!(
+            name(Method::GET, "/api/v1/pods?watch=true&resourceVersion=1").unwrap(),
+            "watch pods"
+        );
+        assert_eq!(
+            name(Method::GET, "/api/v1/namespaces/default/pods/web/log").unwrap(),
+            "get pods/log"
+        );
+        assert_eq!(
+            name(Method::PATCH, "/apis/apps/v1/namespaces/prod/deployments/web").unwrap(),
+            "patch deployments.apps"
+        );
+        assert_eq!(
+            name(Method::DELETE, "/apis/apps/v1/namespaces/prod/deployments").unwrap(),
+            "deletecollection deployments.apps"
+        );
+        assert_eq!(
+            name(Method::GET, "/api/v1/namespaces/kube-system").unwrap(),
+            "get namespaces"
+        );
+        assert_eq!(
+            name(Method::PUT, "/api/v1/namespaces/kube-system/finalize").unwrap(),
+            "update namespaces/finalize"
+        );
+        assert_eq!(name(Method::GET, "/version"), None);
+    }
+}
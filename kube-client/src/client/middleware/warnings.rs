@@ -0,0 +1,190 @@
+//! Handling of warnings returned by the apiserver.
+use std::task::{Context, Poll};
+
+use futures::{future::BoxFuture, FutureExt};
+use http::{header::WARNING, HeaderMap, Request, Response};
+use tower::{Layer, Service};
+
+use crate::config::{Warning, WarningHandler};
+
+/// Layer that passes the `Warning` headers of responses to a [`WarningHandler`].
+#[derive(Debug, Clone)]
+pub struct WarningLayer {
+    handler: WarningHandler,
+}
+
+impl WarningLayer {
+    /// Pass warnings to `handler`
+    pub fn new(handler: WarningHandler) -> Self {
+        Self { handler }
+    }
+}
+
+impl<S> Layer<S> for WarningLayer {
+    type Service = Warnings<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Warnings {
+            handler: self.handler.clone(),
+            inner,
+        }
+    }
+}
+
+/// Middleware that passes the `Warning` headers of responses to a [`WarningHandler`].
+///
+/// Like client-go, only warnings with the code `299` and a non-empty text are handled.
+#[derive(Debug, Clone)]
+pub struct Warnings<S> {
+    handler: WarningHandler,
+    inner: S,
+}
+
+impl<S, ReqBody, ResBody> Service<Request<ReqBody>> for Warnings<S>
+where
+    S: Service<Request<ReqBody>, Response = Response<ResBody>>,
+    S::Future: Send + 'static,
+{
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = S::Response;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<ReqBody>) -> Self::Future {
+        let res = self.inner.call(req);
+        let handler = self.handler.clone();
+        async move {
+            let res = res.await?;
+            for warning in parse_warnings(res.headers()) {
+                if warning.code == 299 && !warning.text.is_empty() {
+                    handler.handle(&warning);
+                }
+            }
+            Ok(res)
+        }
+        .boxed()
+    }
+}
+
+/// Parses `Warning` headers of the form `299 - "text"`, with an optional date after the text
+///
+/// Malformed warnings are skipped, along with any warnings after them in the same header value.
+fn parse_warnings(headers: &HeaderMap) -> Vec<Warning> {
+    let mut warnings = Vec::new();
+    for value in headers.get_all(WARNING) {
+        let Ok(mut rest) = value.to_str() else {
+            continue;
+        };
+        while let Some((warning, remaining)) = parse_warning(rest) {
+            warnings.push(warning);
+            match remaining.trim_start().strip_prefix(',') {
+                Some(remaining) => rest = remaining,
+                None => break,
+            }
+        }
+    }
+    warnings
+}
+
+/// Parses a single warning from the start of `value`, and returns it along with the rest of `value`
+fn parse_warning(value: &str) -> Option<(Warning, &str)> {
+    let (code, rest) = value.trim_start().split_once(' ')?;
+    let code = code.parse().ok()?;
+    let (agent, rest) = rest.split_once(' ')?;
+    let (text, mut rest) = parse_quoted(rest)?;
+    // Skip the optional date
+    if let Some(date) = rest.strip_prefix(' ').filter(|date| date.starts_with('"')) {
+        rest = parse_quoted(date)?.1;
+    }
+    Some((
+        Warning {
+            code,
+            agent: agent.to_string(),
+            text,
+        },
+        rest,
+    ))
+}
+
+/// Parses a quoted string with `\` escapes from the start of `value`, and returns it along with the rest of `value`
+fn parse_quoted(value: &str) -> Option<(String, &str)> {
+    let mut chars = value.strip_prefix('"')?.char_indices();
+    let mut text = String::new();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '\\' => text.push(chars.next()?.1),
+            // The index is within the value without its opening quote
+            '"' => return Some((text, &value[i + 2..])),
+            c => text.push(c),
+        }
+    }
+    None
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use std::sync::{Arc, Mutex};
+
+    use http::HeaderValue;
+    use tower::ServiceExt;
+    use tower_test::mock;
+
+    #[test]
+    fn parses_warning_headers() {
+        let mut headers = HeaderMap::new();
+        headers.append(
+            WARNING,
+            HeaderValue::from_static(r#"299 - "v1beta1 Foo is deprecated, use v1 Foo""#),
+        );
+        headers.append(
+            WARNING,
+            HeaderValue::from_static(
+                r#"299 - "unknown field \"spec.foo\"" "Tue, 15 Nov 1994 08:12:31 GMT", 299 - "second""#,
+            ),
+        );
+        headers.append(WARNING, HeaderValue::from_static("malformed"));
+        let texts = parse_warnings(&headers)
+            .into_iter()
+            .map(|warning| warning.text)
+            .collect::<Vec<_>>();
+        assert_eq!(texts, vec![
+            "v1beta1 Foo is deprecated, use v1 Foo",
+            r#"unknown field "spec.foo""#,
+            "second",
+        ]);
+    }
+
+    #[tokio::test]
+    async fn passes_warnings_to_handler() {
+        let warnings = Arc::new(Mutex::new(Vec::new()));
+        let handler = WarningHandler::callback({
+            let warnings = warnings.clone();
+            move |warning| warnings.lock().unwrap().push(warning.clone())
+        });
+        let (mock_service, handle) = mock::pair::<Request<()>, Response<()>>();
+        let service = WarningLayer::new(handler).layer(mock_service);
+        let spawned = tokio::spawn(async move {
+            let mut handle = std::pin::pin!(handle);
+            let (_request, send) = handle.next_request().await.expect("service not called");
+            send.send_response(
+                Response::builder()
+                    .header(WARNING, r#"299 - "deprecated""#)
+                    .header(WARNING, r#"199 - "not from the apiserver""#)
+                    .body(())
+                    .unwrap(),
+            );
+        });
+        service.oneshot(Request::new(())).await.unwrap();
+        spawned.await.unwrap();
+        assert_eq!(*warnings.lock().unwrap(), vec![Warning {
+            code: 299,
+            agent: "-".into(),
+            text: "deprecated".into(),
+        }]);
+    }
+}
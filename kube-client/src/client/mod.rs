@@ -7,6 +7,8 @@
 //!
 //! The [`Client`] can also be used with [`Discovery`](crate::Discovery) to dynamically
 //! retrieve the resources served by the kubernetes API.
+use std::collections::HashMap;
+
 use either::{Either, Left, Right};
 use futures::{future::BoxFuture, AsyncBufRead, StreamExt, TryStream, TryStreamExt};
 use http::{self, Request, Response};
@@ -28,9 +30,11 @@ use tower_http::map_response_body::MapResponseBodyLayer;
 pub use self::body::Body;
 use crate::{api::WatchEvent, error::ErrorResponse, Config, Error, Result};
 
+mod access_review;
 mod auth;
 mod body;
 mod builder;
+mod hash;
 #[cfg_attr(docsrs, doc(cfg(feature = "unstable-client")))]
 #[cfg(feature = "unstable-client")]
 mod client_ext;
@@ -38,15 +42,25 @@ mod client_ext;
 #[cfg(feature = "unstable-client")]
 pub use client_ext::scope;
 mod config_ext;
+#[cfg_attr(docsrs, doc(cfg(feature = "fake")))]
+#[cfg(feature = "fake")]
+pub mod fake;
 pub use auth::Error as AuthError;
 pub use config_ext::ConfigExt;
 pub mod middleware;
+mod pool;
+pub use pool::{ClientPool, ClusterSource, Error as PoolError};
+#[cfg_attr(docsrs, doc(cfg(feature = "replay")))]
+#[cfg(feature = "replay")]
+pub mod replay;
 
 #[cfg(any(feature = "rustls-tls", feature = "openssl-tls"))] mod tls;
 
 #[cfg(feature = "openssl-tls")]
 pub use tls::openssl_tls::Error as OpensslTlsError;
 #[cfg(feature = "rustls-tls")] pub use tls::rustls_tls::Error as RustlsTlsError;
+#[cfg(feature = "rustls-tls")] pub use tls::rustls_tls::TlsInfo;
+#[cfg(unix)] mod unix_socket;
 #[cfg(feature = "ws")] mod upgrade;
 
 #[cfg(feature = "oauth")]
@@ -57,6 +71,14 @@ pub use auth::OAuthError;
 #[cfg_attr(docsrs, doc(cfg(feature = "oidc")))]
 pub use auth::oidc_errors;
 
+#[cfg(feature = "aws")]
+#[cfg_attr(docsrs, doc(cfg(feature = "aws")))]
+pub use auth::AwsError;
+
+#[cfg(feature = "azure")]
+#[cfg_attr(docsrs, doc(cfg(feature = "azure")))]
+pub use auth::AzureError;
+
 #[cfg(feature = "ws")] pub use upgrade::UpgradeConnectionError;
 
 #[cfg(feature = "kubelet-debug")]
@@ -164,6 +186,62 @@ impl Client {
         &self.default_ns
     }
 
+    /// Create a clone of this [`Client`] that impersonates `user`
+    ///
+    /// Requests are authorized as if they were sent by `user`, in `groups` and with `extra` information
+    /// such as scopes, which requires the credentials of this client to be allowed to `impersonate` them.
+    /// Overrides any impersonation of this client, while sharing its connections.
+    ///
+    /// ```no_run
+    /// # async fn doc(client: kube::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use std::collections::HashMap;
+    /// let tenant = client.impersonated("jane", vec!["tenant-a".into()], HashMap::new())?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if any of the values are not valid HTTP header values.
+    pub fn impersonated(
+        &self,
+        user: &str,
+        groups: Vec<String>,
+        extra: HashMap<String, Vec<String>>,
+    ) -> Result<Self> {
+        let layer = middleware::ImpersonateLayer::new(user, &groups, &extra).map_err(Error::HttpError)?;
+        Ok(Self::new(
+            layer.layer(self.inner.clone()),
+            self.default_ns.clone(),
+        ))
+    }
+
+    /// Create a clone of this [`Client`] that dry runs every mutating request
+    ///
+    /// Mutating requests are sent with `dryRun=All`, so the apiserver validates and admits them
+    /// without persisting any changes. Requests that cannot be dry run, such as `exec` into pods,
+    /// fail with [`Error::DryRunUnsupported`] without being sent. Reads are unaffected.
+    ///
+    /// This allows integration tests and "plan" modes to swap the client rather than configure every request.
+    ///
+    /// ```no_run
+    /// # async fn doc(client: kube::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use k8s_openapi::api::core::v1::ConfigMap;
+    /// use kube::{api::{Api, DeleteParams}, Client};
+    ///
+    /// let configmaps: Api<ConfigMap> = Api::default_namespaced(client.dry_run());
+    /// // Validated and admitted, but not deleted
+    /// configmaps.delete("settings", &DeleteParams::default()).await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn dry_run(&self) -> Self {
+        Self::new(
+            middleware::DryRunLayer.layer(self.inner.clone()),
+            self.default_ns.clone(),
+        )
+    }
+
     /// Perform a raw HTTP request against the API and return the raw response back.
     /// This method can be used to get raw access to the API which may be used to, for example,
     /// create a proxy server or application-level gateway between localhost and the API server.
@@ -453,7 +531,7 @@ impl Client {
 ///
 /// In either case, present an ApiError upstream.
 /// The latter is probably a bug if encountered.
-async fn handle_api_errors(res: Response<Body>) -> Result<Response<Body>> {
+pub(crate) async fn handle_api_errors(res: Response<Body>) -> Result<Response<Body>> {
     let status = res.status();
     if status.is_client_error() || status.is_server_error() {
         // trace!("Status = {:?} for {}", status, res.url());
@@ -471,6 +549,7 @@ async fn handle_api_errors(res: Response<Body>) -> Result<Response<Body>> {
                 code: status.as_u16(),
                 message: format!("{text:?}"),
                 reason: "Failed to parse error data".into(),
+                details: None,
             };
             tracing::debug!("Unsuccessful: {error_response:?} (reconstruct)");
             Err(Error::Api(error_response))
@@ -0,0 +1,289 @@
+//! A pool of clients for multiple clusters
+use std::{
+    collections::HashMap,
+    fmt,
+    sync::{Arc, RwLock},
+};
+
+use http::Request;
+use thiserror::Error;
+use tokio::sync::OnceCell;
+
+use crate::{
+    config::{InClusterError, KubeConfigOptions, Kubeconfig, KubeconfigError},
+    Client, Config,
+};
+
+/// Possible errors when using a [`ClientPool`]
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The cluster has not been added to the pool
+    #[error("cluster {0:?} is not part of the pool")]
+    UnknownCluster(String),
+
+    /// The kubeconfig of the cluster could not be loaded
+    #[error("failed to load the kubeconfig of cluster {0:?}: {1}")]
+    Kubeconfig(String, #[source] KubeconfigError),
+
+    /// The in-cluster config could not be loaded
+    #[error("failed to load the in-cluster config of cluster {0:?}: {1}")]
+    InCluster(String, #[source] InClusterError),
+
+    /// The client of the cluster could not be built, or a request to the cluster failed
+    #[error("failed to reach cluster {0:?}: {1}")]
+    Client(String, #[source] crate::Error),
+}
+
+/// Where the client of a cluster in a [`ClientPool`] connects to
+#[derive(Clone)]
+#[allow(clippy::large_enum_variant)]
+pub enum ClusterSource {
+    /// A context of the default kubeconfig, see [`Config::from_kubeconfig_context`]
+    KubeconfigContext(String),
+    /// A custom kubeconfig, such as one generated for a service account, see [`Config::from_custom_kubeconfig`]
+    Kubeconfig {
+        /// The kubeconfig to load
+        kubeconfig: Kubeconfig,
+        /// The context, cluster and user to load from the kubeconfig
+        options: KubeConfigOptions,
+    },
+    /// The cluster the process runs in, see [`Config::incluster`]
+    InCluster,
+    /// An explicit config
+    Config(Config),
+    /// An already built client
+    Client(Client),
+}
+
+impl fmt::Debug for ClusterSource {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::KubeconfigContext(context) => f.debug_tuple("KubeconfigContext").field(context).finish(),
+            Self::Kubeconfig { options, .. } => f
+                .debug_struct("Kubeconfig")
+                .field("context", &options.context)
+                .field("cluster", &options.cluster)
+                .field("user", &options.user)
+                .finish_non_exhaustive(),
+            Self::InCluster => f.write_str("InCluster"),
+            Self::Config(config) => f.debug_tuple("Config").field(&config.cluster_url).finish(),
+            Self::Client(_) => f.write_str("Client"),
+        }
+    }
+}
+
+impl ClusterSource {
+    async fn connect(&self, name: &str) -> Result<Client, Error> {
+        let config = match self {
+            Self::KubeconfigContext(context) => Config::from_kubeconfig_context(context)
+                .await
+                .map_err(|err| Error::Kubeconfig(name.to_owned(), err))?,
+            Self::Kubeconfig { kubeconfig, options } => {
+                Config::from_custom_kubeconfig(kubeconfig.clone(), options)
+                    .await
+                    .map_err(|err| Error::Kubeconfig(name.to_owned(), err))?
+            }
+            Self::InCluster => Config::incluster().map_err(|err| Error::InCluster(name.to_owned(), err))?,
+            Self::Config(config) => config.clone(),
+            Self::Client(client) => return Ok(client.clone()),
+        };
+        Client::try_from(config).map_err(|err| Error::Client(name.to_owned(), err))
+    }
+}
+
+struct Member {
+    source: ClusterSource,
+    client: OnceCell<Client>,
+}
+
+/// A set of clients for multiple clusters, looked up by name
+///
+/// Clients are built lazily when they are first requested, so clusters that are never used
+/// do not need to be reachable, and a client that failed to build is retried on the next lookup.
+/// The pool is cheap to clone, and clusters can be added and removed while it is shared.
+///
+/// ```no_run
+/// use kube::{client::ClientPool, config::Kubeconfig, Api};
+/// use k8s_openapi::api::core::v1::Namespace;
+///
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let pool = ClientPool::from_kubeconfig(&Kubeconfig::read()?);
+/// for (cluster, health) in pool.check_health().await {
+///     if let Err(err) = health {
+///         println!("skipping {cluster}: {err}");
+///         continue;
+///     }
+///     let namespaces: Api<Namespace> = Api::all(pool.get(&cluster).await?);
+///     println!("{cluster} has {} namespaces", namespaces.list(&Default::default()).await?.items.len());
+/// }
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Default)]
+pub struct ClientPool {
+    members: Arc<RwLock<HashMap<String, Arc<Member>>>>,
+}
+
+impl fmt::Debug for ClientPool {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let members = self.members.read().unwrap();
+        f.debug_map()
+            .entries(members.iter().map(|(name, member)| (name, &member.source)))
+            .finish()
+    }
+}
+
+impl ClientPool {
+    /// Create an empty pool
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a pool with a cluster for every context of a kubeconfig, named after the context
+    pub fn from_kubeconfig(kubeconfig: &Kubeconfig) -> Self {
+        let pool = Self::new();
+        for context in &kubeconfig.contexts {
+            pool.insert(&context.name, ClusterSource::Kubeconfig {
+                kubeconfig: kubeconfig.clone(),
+                options: KubeConfigOptions {
+                    context: Some(context.name.clone()),
+                    ..KubeConfigOptions::default()
+                },
+            });
+        }
+        pool
+    }
+
+    /// Add a cluster to the pool
+    #[must_use]
+    pub fn with_cluster(self, name: &str, source: ClusterSource) -> Self {
+        self.insert(name, source);
+        self
+    }
+
+    /// Add a cluster, replacing any cluster with the same name and its client
+    pub fn insert(&self, name: &str, source: ClusterSource) {
+        let member = Arc::new(Member {
+            source,
+            client: OnceCell::new(),
+        });
+        self.members.write().unwrap().insert(name.to_owned(), member);
+    }
+
+    /// Remove a cluster, returning whether it was part of the pool
+    pub fn remove(&self, name: &str) -> bool {
+        self.members.write().unwrap().remove(name).is_some()
+    }
+
+    /// Whether a cluster is part of the pool
+    pub fn contains(&self, name: &str) -> bool {
+        self.members.read().unwrap().contains_key(name)
+    }
+
+    /// The names of the clusters in the pool, sorted
+    pub fn names(&self) -> Vec<String> {
+        let mut names: Vec<_> = self.members.read().unwrap().keys().cloned().collect();
+        names.sort();
+        names
+    }
+
+    /// Get the client of a cluster, building it on first use
+    pub async fn get(&self, name: &str) -> Result<Client, Error> {
+        let member = self
+            .members
+            .read()
+            .unwrap()
+            .get(name)
+            .cloned()
+            .ok_or_else(|| Error::UnknownCluster(name.to_owned()))?;
+        member
+            .client
+            .get_or_try_init(|| member.source.connect(name))
+            .await
+            .cloned()
+    }
+
+    /// Check that the apiserver of a cluster is ready to serve requests, using its `/readyz` endpoint
+    pub async fn health(&self, name: &str) -> Result<(), Error> {
+        let client = self.get(name).await?;
+        let req = Request::get("/readyz")
+            .body(vec![])
+            .map_err(|err| Error::Client(name.to_owned(), crate::Error::HttpError(err)))?;
+        client
+            .request_text(req)
+            .await
+            .map_err(|err| Error::Client(name.to_owned(), err))?;
+        Ok(())
+    }
+
+    /// Check the health of every cluster in the pool concurrently, see [`ClientPool::health`]
+    pub async fn check_health(&self) -> Vec<(String, Result<(), Error>)> {
+        let checks = self.names().into_iter().map(|name| async move {
+            let health = self.health(&name).await;
+            (name, health)
+        });
+        futures::future::join_all(checks).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::pin::pin;
+
+    use super::{ClientPool, ClusterSource, Error};
+    use crate::{client::Body, Client};
+
+    use http::{Request, Response, StatusCode};
+    use tower_test::mock;
+
+    #[tokio::test]
+    async fn pool_looks_up_clients_by_name() {
+        let (mock_service, _) = mock::pair::<Request<Body>, Response<Body>>();
+        let client = Client::new(mock_service, "east-namespace");
+        let pool = ClientPool::new().with_cluster("east", ClusterSource::Client(client));
+
+        assert!(pool.contains("east"));
+        assert_eq!(pool.names(), ["east"]);
+        let client = pool.get("east").await.unwrap();
+        assert_eq!(client.default_namespace(), "east-namespace");
+        assert!(matches!(pool.get("west").await, Err(Error::UnknownCluster(_))));
+
+        assert!(pool.remove("east"));
+        assert!(!pool.contains("east"));
+    }
+
+    #[tokio::test]
+    async fn pool_checks_readiness_of_every_cluster() {
+        let (ready_service, ready_handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let (broken_service, broken_handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut ready_handle = pin!(ready_handle);
+            let (request, send) = ready_handle.next_request().await.expect("service not called");
+            assert_eq!(request.uri().to_string(), "/readyz");
+            send.send_response(Response::builder().body(Body::from(b"ok".to_vec())).unwrap());
+
+            let mut broken_handle = pin!(broken_handle);
+            let (_, send) = broken_handle.next_request().await.expect("service not called");
+            send.send_response(
+                Response::builder()
+                    .status(StatusCode::INTERNAL_SERVER_ERROR)
+                    .body(Body::from(b"[-]etcd failed".to_vec()))
+                    .unwrap(),
+            );
+        });
+
+        let ready = Client::new(ready_service, "default");
+        let broken = Client::new(broken_service, "default");
+        let pool = ClientPool::new()
+            .with_cluster("ready", ClusterSource::Client(ready))
+            .with_cluster("broken", ClusterSource::Client(broken));
+        let health = pool.check_health().await;
+        spawned.await.unwrap();
+
+        assert_eq!(health.len(), 2);
+        assert_eq!(health[0].0, "broken");
+        assert!(matches!(health[0].1, Err(Error::Client(..))));
+        assert_eq!(health[1].0, "ready");
+        assert!(health[1].1.is_ok());
+    }
+}
@@ -0,0 +1,477 @@
+//! Recording of API interactions into fixtures, and their deterministic replay in tests.
+//!
+//! Record the interactions of a test against a real cluster once with a [`RecordLayer`],
+//! then replay them from the fixture with [`Replay`] to run the test without a cluster:
+//!
+//! ```no_run
+//! # async fn doc() -> Result<(), Box<dyn std::error::Error>> {
+//! use kube::{client::{replay::{RecordLayer, Replay}, ClientBuilder}, Client, Config};
+//!
+//! let fixture = "tests/fixtures/list_pods.json";
+//! let client = if std::env::var("RECORD").is_ok() {
+//!     ClientBuilder::try_from(Config::infer().await?)?
+//!         .with_layer(&RecordLayer::new(fixture))
+//!         .build()
+//! } else {
+//!     Client::new(Replay::from_file(fixture)?, "default")
+//! };
+//! # Ok(())
+//! # }
+//! ```
+use std::{
+    collections::BTreeMap,
+    path::{Path, PathBuf},
+    pin::Pin,
+    sync::{Arc, Mutex},
+    task::{Context, Poll},
+};
+
+use base64::Engine;
+use bytes::{Bytes, BytesMut};
+use futures::{future::BoxFuture, FutureExt};
+use http::{HeaderMap, HeaderValue, Request, Response, StatusCode};
+use http_body::{Body as HttpBody, Frame, SizeHint};
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use tower::{Layer, Service};
+
+use super::{hash::stable_hash, Body};
+
+/// Headers of responses that are recorded, other headers are not needed to replay responses
+const RECORDED_HEADERS: &[&str] = &["content-type", "warning"];
+
+/// Possible errors when replaying interactions
+#[derive(Error, Debug)]
+pub enum Error {
+    /// Failed to read the fixture
+    #[error("failed to read fixture {0:?}: {1}")]
+    ReadFixture(PathBuf, #[source] std::io::Error),
+
+    /// Failed to parse the fixture
+    #[error("failed to parse fixture {0:?}: {1}")]
+    ParseFixture(PathBuf, #[source] serde_json::Error),
+
+    /// A request does not match any remaining recorded interaction
+    #[error("no recorded interaction matches {method} {uri}")]
+    Unmatched {
+        /// The method of the request
+        method: String,
+        /// The path and query of the request
+        uri: String,
+    },
+}
+
+/// The recorded interactions of a client, stored as a JSON fixture
+#[derive(Clone, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Cassette {
+    /// The interactions, in the order their responses completed
+    pub interactions: Vec<Interaction>,
+}
+
+impl Cassette {
+    /// Reads a cassette from the fixture at `path`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the fixture cannot be read or parsed.
+    pub fn load(path: impl AsRef<Path>) -> Result<Self, Error> {
+        let path = path.as_ref();
+        let fixture = std::fs::read(path).map_err(|err| Error::ReadFixture(path.to_owned(), err))?;
+        serde_json::from_slice(&fixture).map_err(|err| Error::ParseFixture(path.to_owned(), err))
+    }
+
+    /// Writes the cassette to the fixture at `path`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the fixture cannot be written.
+    pub fn save(&self, path: impl AsRef<Path>) -> std::io::Result<()> {
+        let fixture = serde_json::to_vec_pretty(self)?;
+        std::fs::write(path, fixture)
+    }
+}
+
+/// A request and the response it received
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Interaction {
+    /// The request
+    pub request: RecordedRequest,
+    /// The response
+    pub response: RecordedResponse,
+}
+
+/// A recorded request, with the fields that are matched on replay
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct RecordedRequest {
+    /// The method, such as `GET`
+    pub method: String,
+    /// The path and query, such as `/api/v1/namespaces/default/pods?limit=500`
+    pub uri: String,
+    /// A stable hash of the body, or `None` for requests without a body
+    pub body_hash: Option<String>,
+}
+
+impl RecordedRequest {
+    fn from_request(req: &Request<Body>) -> Self {
+        Self {
+            method: req.method().to_string(),
+            uri: req
+                .uri()
+                .path_and_query()
+                .map_or_else(|| req.uri().path().to_string(), ToString::to_string),
+            body_hash: req
+                .body()
+                .buffered()
+                .filter(|body| !body.is_empty())
+                .map(stable_hash),
+        }
+    }
+}
+
+/// A recorded response
+///
+/// Streamed responses, such as those of watches, are recorded in full once their stream ends,
+/// or once the client stops reading them.
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RecordedResponse {
+    /// The status code
+    pub status: u16,
+    /// The headers that are needed to replay the response, such as `content-type`
+    #[serde(default)]
+    pub headers: BTreeMap<String, String>,
+    /// The body, as text if it is valid UTF-8 and otherwise base64 encoded
+    pub body: String,
+    /// Whether the body is base64 encoded
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub base64: bool,
+}
+
+impl RecordedResponse {
+    fn new(status: StatusCode, headers: &HeaderMap, body: Vec<u8>) -> Self {
+        let headers = RECORDED_HEADERS
+            .iter()
+            .filter_map(|name| {
+                let value = headers.get(*name)?.to_str().ok()?;
+                Some(((*name).to_string(), value.to_string()))
+            })
+            .collect();
+        let (body, base64) = match String::from_utf8(body) {
+            Ok(body) => (body, false),
+            Err(err) => (
+                base64::engine::general_purpose::STANDARD.encode(err.into_bytes()),
+                true,
+            ),
+        };
+        Self {
+            status: status.as_u16(),
+            headers,
+            body,
+            base64,
+        }
+    }
+
+    fn to_response(&self) -> Response<Body> {
+        let body = if self.base64 {
+            base64::engine::general_purpose::STANDARD
+                .decode(&self.body)
+                .unwrap_or_default()
+        } else {
+            self.body.clone().into_bytes()
+        };
+        let mut response = Response::new(Body::from(body));
+        *response.status_mut() =
+            StatusCode::from_u16(self.status).unwrap_or(StatusCode::INTERNAL_SERVER_ERROR);
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (name.parse::<http::HeaderName>(), HeaderValue::from_str(value)) {
+                response.headers_mut().append(name, value);
+            }
+        }
+        response
+    }
+}
+
+/// Layer that records every interaction of a client into a fixture, see [`Record`]
+#[derive(Debug, Clone)]
+pub struct RecordLayer {
+    recorder: Arc<Recorder>,
+}
+
+impl RecordLayer {
+    /// Record interactions into the fixture at `path`, replacing any existing fixture
+    pub fn new(path: impl Into<PathBuf>) -> Self {
+        Self {
+            recorder: Arc::new(Recorder {
+                path: path.into(),
+                cassette: Mutex::new(Cassette::default()),
+            }),
+        }
+    }
+}
+
+impl<S> Layer<S> for RecordLayer {
+    type Service = Record<S>;
+
+    fn layer(&self, inner: S) -> Self::Service {
+        Record {
+            recorder: self.recorder.clone(),
+            inner,
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Recorder {
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+}
+
+impl Recorder {
+    /// Adds an interaction, and saves the fixture so that it is complete even if the test is aborted
+    fn record(&self, interaction: Interaction) {
+        let mut cassette = self
+            .cassette
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        cassette.interactions.push(interaction);
+        if let Err(err) = cassette.save(&self.path) {
+            tracing::warn!("failed to save fixture {:?}: {err}", self.path);
+        }
+    }
+}
+
+/// Middleware that records every interaction into a fixture, to be replayed by [`Replay`]
+#[derive(Debug, Clone)]
+pub struct Record<S> {
+    recorder: Arc<Recorder>,
+    inner: S,
+}
+
+impl<S, B> Service<Request<Body>> for Record<S>
+where
+    S: Service<Request<Body>, Response = Response<B>>,
+    S::Future: Send + 'static,
+    B: HttpBody<Data = Bytes> + Unpin,
+{
+    type Error = S::Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = Response<RecordingBody<B>>;
+
+    fn poll_ready(&mut self, cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        self.inner.poll_ready(cx)
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let request = RecordedRequest::from_request(&req);
+        let recorder = self.recorder.clone();
+        self.inner
+            .call(req)
+            .map(move |res| {
+                res.map(|res| {
+                    let status = res.status();
+                    let headers = res.headers().clone();
+                    res.map(|body| RecordingBody {
+                        inner: body,
+                        recorded: BytesMut::new(),
+                        pending: Some(PendingInteraction {
+                            recorder,
+                            request,
+                            status,
+                            headers,
+                        }),
+                    })
+                })
+            })
+            .boxed()
+    }
+}
+
+struct PendingInteraction {
+    recorder: Arc<Recorder>,
+    request: RecordedRequest,
+    status: StatusCode,
+    headers: HeaderMap,
+}
+
+/// The body of a response that is recorded by [`Record`] once it ends or is dropped
+pub struct RecordingBody<B> {
+    inner: B,
+    recorded: BytesMut,
+    pending: Option<PendingInteraction>,
+}
+
+impl<B> RecordingBody<B> {
+    fn finish(&mut self) {
+        if let Some(pending) = self.pending.take() {
+            let body = std::mem::take(&mut self.recorded).to_vec();
+            pending.recorder.record(Interaction {
+                request: pending.request,
+                response: RecordedResponse::new(pending.status, &pending.headers, body),
+            });
+        }
+    }
+}
+
+impl<B> HttpBody for RecordingBody<B>
+where
+    B: HttpBody<Data = Bytes> + Unpin,
+{
+    type Data = Bytes;
+    type Error = B::Error;
+
+    fn poll_frame(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+    ) -> Poll<Option<Result<Frame<Self::Data>, Self::Error>>> {
+        let frame = std::task::ready!(Pin::new(&mut self.inner).poll_frame(cx));
+        match &frame {
+            Some(Ok(frame)) => {
+                if let Some(data) = frame.data_ref() {
+                    self.recorded.extend_from_slice(data);
+                }
+            }
+            Some(Err(_)) | None => self.finish(),
+        }
+        Poll::Ready(frame)
+    }
+
+    fn is_end_stream(&self) -> bool {
+        self.inner.is_end_stream()
+    }
+
+    fn size_hint(&self) -> SizeHint {
+        self.inner.size_hint()
+    }
+}
+
+impl<B> Drop for RecordingBody<B> {
+    fn drop(&mut self) {
+        self.finish();
+    }
+}
+
+/// A service that replays the recorded interactions of a fixture, see the [module docs](self)
+///
+/// Requests are matched on their method, path and query and a hash of their body.
+/// Each recorded interaction is replayed once, in the order it was recorded, so repeated requests
+/// like relists receive their successive responses. Requests that do not match any remaining
+/// interaction fail with [`Error::Unmatched`].
+#[derive(Debug, Clone)]
+pub struct Replay {
+    remaining: Arc<Mutex<Vec<Interaction>>>,
+}
+
+impl Replay {
+    /// Replays the interactions of `cassette`
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            remaining: Arc::new(Mutex::new(cassette.interactions)),
+        }
+    }
+
+    /// Replays the interactions of the fixture at `path`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the fixture cannot be read or parsed.
+    pub fn from_file(path: impl AsRef<Path>) -> Result<Self, Error> {
+        Cassette::load(path).map(Self::new)
+    }
+
+    /// Whether every recorded interaction has been replayed
+    ///
+    /// Useful to assert that the code under test made all the requests it made when it was recorded.
+    pub fn is_exhausted(&self) -> bool {
+        self.remaining
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .is_empty()
+    }
+
+    fn replay(&self, request: &RecordedRequest) -> Result<Response<Body>, Error> {
+        let mut remaining = self
+            .remaining
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner());
+        let index = remaining
+            .iter()
+            .position(|interaction| interaction.request == *request)
+            .ok_or_else(|| Error::Unmatched {
+                method: request.method.clone(),
+                uri: request.uri.clone(),
+            })?;
+        Ok(remaining.remove(index).response.to_response())
+    }
+}
+
+impl Service<Request<Body>> for Replay {
+    type Error = Error;
+    type Future = BoxFuture<'static, Result<Self::Response, Self::Error>>;
+    type Response = Response<Body>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, req: Request<Body>) -> Self::Future {
+        let response = self.replay(&RecordedRequest::from_request(&req));
+        async move { response }.boxed()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use http::Method;
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use tower::ServiceExt;
+    use tower_test::mock;
+
+    use crate::{api::PostParams, Api, Client};
+
+    #[tokio::test]
+    async fn replays_recorded_interactions() {
+        let fixture = tempfile::NamedTempFile::new().unwrap();
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = std::pin::pin!(handle);
+            let (request, send) = handle.next_request().await.expect("service not called");
+            assert_eq!(request.method(), Method::POST);
+            send.send_response(
+                Response::builder()
+                    .status(201)
+                    .header("content-type", "application/json")
+                    .body(Body::from(
+                        br#"{"apiVersion":"v1","kind":"ConfigMap","metadata":{"name":"settings"}}"#.to_vec(),
+                    ))
+                    .unwrap(),
+            );
+        });
+        let configmap = ConfigMap {
+            metadata: kube_core::ObjectMeta {
+                name: Some("settings".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+
+        let recording = Client::new(RecordLayer::new(fixture.path()).layer(mock_service), "default");
+        let api: Api<ConfigMap> = Api::default_namespaced(recording);
+        api.create(&PostParams::default(), &configmap).await.unwrap();
+        spawned.await.unwrap();
+
+        let replay = Replay::from_file(fixture.path()).unwrap();
+        let api: Api<ConfigMap> = Api::default_namespaced(Client::new(replay.clone(), "default"));
+        let created = api.create(&PostParams::default(), &configmap).await.unwrap();
+        assert_eq!(created.metadata.name.as_deref(), Some("settings"));
+        assert!(replay.is_exhausted());
+
+        // A different body does not match, and each interaction is only replayed once
+        let request = Request::post("/api/v1/namespaces/default/configmaps?")
+            .body(Body::from(b"{}".to_vec()))
+            .unwrap();
+        let err = replay.oneshot(request).await.unwrap_err();
+        assert!(matches!(err, Error::Unmatched { .. }));
+    }
+}
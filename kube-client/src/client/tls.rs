@@ -1,14 +1,33 @@
 #[cfg(feature = "rustls-tls")]
 pub mod rustls_tls {
+    use std::{
+        path::{Path, PathBuf},
+        sync::{Arc, RwLock},
+        time::{Duration, Instant, SystemTime},
+    };
+
     use hyper_rustls::ConfigBuilderExt;
     use rustls::{
         self,
-        client::danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+        client::{
+            danger::{HandshakeSignatureValid, ServerCertVerified, ServerCertVerifier},
+            ResolvesClientCert, WebPkiServerVerifier,
+        },
+        crypto::{CryptoProvider, KeyProvider},
         pki_types::{CertificateDer, InvalidDnsNameError, PrivateKeyDer, ServerName},
-        ClientConfig, DigitallySignedStruct,
+        sign::CertifiedKey,
+        CertificateError, ClientConfig, ConfigBuilder, DigitallySignedStruct, SignatureScheme, WantsVerifier,
     };
     use thiserror::Error;
 
+    /// How long a root certificate bundle loaded from a file is used before it is read again
+    const ROOT_CERT_RELOAD_INTERVAL: Duration = Duration::from_secs(60);
+
+    /// How often an unknown issuer may force the root certificate bundle to be read again
+    ///
+    /// This keeps a server with an untrusted certificate from causing a file read on every handshake.
+    const ROOT_CERT_FORCED_RELOAD_INTERVAL: Duration = Duration::from_secs(5);
+
     /// Errors from Rustls
     #[derive(Debug, Error)]
     pub enum Error {
@@ -44,32 +63,83 @@ pub mod rustls_tls {
         /// Invalid server name
         #[error("invalid server name: {0}")]
         InvalidServerName(#[source] InvalidDnsNameError),
+
+        /// Failed to read the root certificate file
+        #[error("failed to read the root certificate file {1:?}: {0}")]
+        ReadRootCertificates(#[source] std::io::Error, PathBuf),
+
+        // Using type-erased error to avoid depending on webpki
+        /// Failed to build a certificate verifier from the root certificates
+        #[error("failed to build a certificate verifier: {0}")]
+        BuildCertificateVerifier(#[source] Box<dyn std::error::Error + Send + Sync>),
+
+        /// Failed to read the client certificate or key file
+        #[error("failed to read the client identity file {1:?}: {0}")]
+        ReadIdentity(#[source] std::io::Error, PathBuf),
+    }
+
+    /// The [`CryptoProvider`] of the rustls configs built by kube
+    ///
+    /// The process-level default is used when one is installed. Otherwise the provider of the enabled
+    /// features is picked explicitly, since dependencies like `aws-config` can enable both `ring` and
+    /// `aws-lc-rs`, and rustls then cannot determine a default by itself.
+    pub(crate) fn crypto_provider() -> Arc<CryptoProvider> {
+        if let Some(provider) = CryptoProvider::get_default() {
+            return provider.clone();
+        }
+        #[cfg(feature = "aws-lc-rs")]
+        {
+            Arc::new(rustls::crypto::aws_lc_rs::default_provider())
+        }
+        #[cfg(not(feature = "aws-lc-rs"))]
+        {
+            Arc::new(rustls::crypto::ring::default_provider())
+        }
+    }
+
+    /// Starts a [`ClientConfig`] with the [`crypto_provider`] and the safe default protocol versions
+    pub(crate) fn client_config_builder() -> ConfigBuilder<ClientConfig, WantsVerifier> {
+        ClientConfig::builder_with_provider(crypto_provider())
+            .with_safe_default_protocol_versions()
+            // `ClientConfig::builder` unwraps this for the default provider as well
+            .expect("crypto provider supports the default protocol versions")
     }
 
     /// Create `rustls::ClientConfig`.
+    ///
+    /// When `identity_files` (the client certificate and key files) are set, they are used instead
+    /// of `identity_pem`, and read again whenever they change.
+    /// When `root_cert_file` is set, the root certificates are loaded from it instead of `root_certs`,
+    /// and reloaded when they rotate.
     pub fn rustls_client_config(
         identity_pem: Option<&[u8]>,
+        identity_files: Option<(&Path, &Path)>,
         root_certs: Option<&[Vec<u8>]>,
+        root_cert_file: Option<&Path>,
         accept_invalid: bool,
     ) -> Result<ClientConfig, Error> {
         let config_builder = if let Some(certs) = root_certs {
-            ClientConfig::builder().with_root_certificates(root_store(certs)?)
+            client_config_builder().with_root_certificates(root_store(certs)?)
         } else {
             #[cfg(feature = "webpki-roots")]
             {
                 // Use WebPKI roots.
-                ClientConfig::builder().with_webpki_roots()
+                client_config_builder().with_webpki_roots()
             }
             #[cfg(not(feature = "webpki-roots"))]
             {
                 // Use native roots. This will panic on Android and iOS.
-                ClientConfig::builder()
+                client_config_builder()
                     .with_native_roots()
                     .map_err(Error::NoValidNativeRootCA)?
             }
         };
 
-        let mut client_config = if let Some((chain, pkey)) = identity_pem.map(client_auth).transpose()? {
+        let mut client_config = if let Some((cert_file, key_file)) = identity_files {
+            let key_provider = config_builder.crypto_provider().key_provider;
+            let resolver = ReloadingClientCert::new(cert_file, key_file, key_provider)?;
+            config_builder.with_client_cert_resolver(Arc::new(resolver))
+        } else if let Some((chain, pkey)) = identity_pem.map(client_auth).transpose()? {
             config_builder
                 .with_client_auth_cert(chain, pkey)
                 .map_err(Error::InvalidPrivateKey)?
@@ -81,6 +151,10 @@ pub mod rustls_tls {
             client_config
                 .dangerous()
                 .set_certificate_verifier(std::sync::Arc::new(NoCertificateVerification {}));
+        } else if let Some(path) = root_cert_file {
+            client_config
+                .dangerous()
+                .set_certificate_verifier(Arc::new(ReloadingRootsVerifier::new(path)?));
         }
         Ok(client_config)
     }
@@ -126,6 +200,320 @@ pub mod rustls_tls {
         Ok((cert_chain, private_key))
     }
 
+    fn load_verifier(path: &Path) -> Result<Arc<WebPkiServerVerifier>, Error> {
+        let pem = std::fs::read(path).map_err(|err| Error::ReadRootCertificates(err, path.to_owned()))?;
+        let mut root_store = rustls::RootCertStore::empty();
+        for cert in rustls_pemfile::certs(&mut pem.as_slice()) {
+            let cert = cert.map_err(|err| Error::ReadRootCertificates(err, path.to_owned()))?;
+            root_store
+                .add(cert)
+                .map_err(|e| Error::AddRootCertificate(Box::new(e)))?;
+        }
+        WebPkiServerVerifier::builder_with_provider(Arc::new(root_store), crypto_provider())
+            .build()
+            .map_err(|e| Error::BuildCertificateVerifier(Box::new(e)))
+    }
+
+    /// Verifies server certificates against a root certificate bundle that is reloaded from disk
+    ///
+    /// The bundle is reloaded once it is older than [`ROOT_CERT_RELOAD_INTERVAL`], and early when a certificate
+    /// has an unknown issuer, which is what a rotated cluster CA looks like. Such forced reloads happen at most
+    /// once every [`ROOT_CERT_FORCED_RELOAD_INTERVAL`]. If the file cannot be loaded, the previous bundle is kept.
+    #[derive(Debug)]
+    struct ReloadingRootsVerifier {
+        path: PathBuf,
+        current: RwLock<(Arc<WebPkiServerVerifier>, Instant)>,
+    }
+
+    impl ReloadingRootsVerifier {
+        fn new(path: &Path) -> Result<Self, Error> {
+            Ok(Self {
+                path: path.to_owned(),
+                current: RwLock::new((load_verifier(path)?, Instant::now())),
+            })
+        }
+
+        fn verifier(&self, force_reload: bool) -> Arc<WebPkiServerVerifier> {
+            let (verifier, loaded_at) = self.current.read().unwrap().clone();
+            let reload_interval = if force_reload {
+                ROOT_CERT_FORCED_RELOAD_INTERVAL
+            } else {
+                ROOT_CERT_RELOAD_INTERVAL
+            };
+            if loaded_at.elapsed() < reload_interval {
+                return verifier;
+            }
+            let verifier = match load_verifier(&self.path) {
+                Ok(reloaded) => reloaded,
+                Err(err) => {
+                    tracing::warn!("Failed to reload root certificates, keeping the previous ones: {err}");
+                    verifier
+                }
+            };
+            *self.current.write().unwrap() = (verifier.clone(), Instant::now());
+            verifier
+        }
+    }
+
+    impl ServerCertVerifier for ReloadingRootsVerifier {
+        fn verify_server_cert(
+            &self,
+            end_entity: &CertificateDer,
+            intermediates: &[CertificateDer],
+            server_name: &ServerName,
+            ocsp_response: &[u8],
+            now: rustls::pki_types::UnixTime,
+        ) -> Result<ServerCertVerified, rustls::Error> {
+            let verify = |verifier: Arc<WebPkiServerVerifier>| {
+                verifier.verify_server_cert(end_entity, intermediates, server_name, ocsp_response, now)
+            };
+            match verify(self.verifier(false)) {
+                Err(rustls::Error::InvalidCertificate(CertificateError::UnknownIssuer)) => {
+                    verify(self.verifier(true))
+                }
+                result => result,
+            }
+        }
+
+        fn verify_tls12_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            self.verifier(false).verify_tls12_signature(message, cert, dss)
+        }
+
+        fn verify_tls13_signature(
+            &self,
+            message: &[u8],
+            cert: &CertificateDer,
+            dss: &DigitallySignedStruct,
+        ) -> Result<HandshakeSignatureValid, rustls::Error> {
+            self.verifier(false).verify_tls13_signature(message, cert, dss)
+        }
+
+        fn supported_verify_schemes(&self) -> Vec<rustls::SignatureScheme> {
+            self.verifier(false).supported_verify_schemes()
+        }
+    }
+
+    /// The modification times of the client certificate and key files
+    type IdentityVersion = (Option<SystemTime>, Option<SystemTime>);
+
+    fn identity_version(cert_file: &Path, key_file: &Path) -> IdentityVersion {
+        let modified = |path: &Path| std::fs::metadata(path).and_then(|meta| meta.modified()).ok();
+        (modified(cert_file), modified(key_file))
+    }
+
+    fn load_identity(
+        cert_file: &Path,
+        key_file: &Path,
+        key_provider: &dyn KeyProvider,
+    ) -> Result<CertifiedKey, Error> {
+        let read = |path: &Path| std::fs::read(path).map_err(|err| Error::ReadIdentity(err, path.to_owned()));
+        let mut pem = read(key_file)?;
+        pem.push(b'\n');
+        pem.extend(read(cert_file)?);
+        let (chain, key) = client_auth(&pem)?;
+        let key = key_provider
+            .load_private_key(key)
+            .map_err(Error::InvalidPrivateKey)?;
+        Ok(CertifiedKey::new(chain, key))
+    }
+
+    /// Provides the client certificate for every handshake from files that may be rotated
+    ///
+    /// The files are read again whenever their modification time changes, as kubelets do when their
+    /// certificates are renewed. If they cannot be loaded, the previous certificate is kept.
+    #[derive(Debug)]
+    struct ReloadingClientCert {
+        cert_file: PathBuf,
+        key_file: PathBuf,
+        key_provider: &'static dyn KeyProvider,
+        current: RwLock<(Arc<CertifiedKey>, IdentityVersion)>,
+    }
+
+    impl ReloadingClientCert {
+        fn new(
+            cert_file: &Path,
+            key_file: &Path,
+            key_provider: &'static dyn KeyProvider,
+        ) -> Result<Self, Error> {
+            let version = identity_version(cert_file, key_file);
+            let key = load_identity(cert_file, key_file, key_provider)?;
+            Ok(Self {
+                cert_file: cert_file.to_owned(),
+                key_file: key_file.to_owned(),
+                key_provider,
+                current: RwLock::new((Arc::new(key), version)),
+            })
+        }
+
+        fn certified_key(&self) -> Arc<CertifiedKey> {
+            let (key, version) = self.current.read().unwrap().clone();
+            let current_version = identity_version(&self.cert_file, &self.key_file);
+            if current_version == version {
+                return key;
+            }
+            match load_identity(&self.cert_file, &self.key_file, self.key_provider) {
+                Ok(reloaded) => {
+                    let reloaded = Arc::new(reloaded);
+                    *self.current.write().unwrap() = (reloaded.clone(), current_version);
+                    reloaded
+                }
+                Err(err) => {
+                    // Retried on the next handshake, since the files may be in the middle of being replaced
+                    tracing::warn!(
+                        "Failed to reload the client certificate, keeping the previous one: {err}"
+                    );
+                    key
+                }
+            }
+        }
+    }
+
+    impl ResolvesClientCert for ReloadingClientCert {
+        fn resolve(
+            &self,
+            _root_hint_subjects: &[&[u8]],
+            _sigschemes: &[SignatureScheme],
+        ) -> Option<Arc<CertifiedKey>> {
+            Some(self.certified_key())
+        }
+
+        fn has_certs(&self) -> bool {
+            true
+        }
+    }
+
+    /// TLS parameters negotiated with the apiserver
+    ///
+    /// Clients built from a [`Config`](crate::Config) add this to the extensions of every response
+    /// that was received over TLS, which helps to debug connections through intercepting proxies.
+    ///
+    /// ```no_run
+    /// # async fn doc(client: kube::Client) -> Result<(), Box<dyn std::error::Error>> {
+    /// use kube::client::{Body, TlsInfo};
+    /// let response = client.send(http::Request::get("/version").body(Body::empty())?).await?;
+    /// if let Some(tls) = response.extensions().get::<TlsInfo>() {
+    ///     println!("{:?} with {:?}", tls.protocol_version, tls.cipher_suite);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    #[derive(Clone, Debug)]
+    pub struct TlsInfo {
+        /// The negotiated protocol version
+        pub protocol_version: Option<rustls::ProtocolVersion>,
+        /// The negotiated cipher suite
+        pub cipher_suite: Option<rustls::CipherSuite>,
+        /// The protocol negotiated with ALPN, such as `h2`
+        pub alpn_protocol: Option<Vec<u8>>,
+        /// The DER encoded certificates presented by the server, starting with its own certificate
+        pub peer_certificates: Vec<Vec<u8>>,
+    }
+
+    impl TlsInfo {
+        fn new(connection: &rustls::ClientConnection) -> Self {
+            Self {
+                protocol_version: connection.protocol_version(),
+                cipher_suite: connection.negotiated_cipher_suite().map(|suite| suite.suite()),
+                alpn_protocol: connection.alpn_protocol().map(<[u8]>::to_vec),
+                peer_certificates: connection
+                    .peer_certificates()
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|cert| cert.to_vec())
+                    .collect(),
+            }
+        }
+    }
+
+    /// Wraps a [`hyper_rustls::HttpsConnector`] to add [`TlsInfo`] to the extensions of responses
+    #[derive(Clone, Debug)]
+    pub(crate) struct TlsInfoConnector<C>(pub(crate) C);
+
+    impl<C, T> tower::Service<http::Uri> for TlsInfoConnector<C>
+    where
+        C: tower::Service<http::Uri, Response = hyper_rustls::MaybeHttpsStream<T>>,
+        C::Future: Send + 'static,
+    {
+        type Error = C::Error;
+        type Future = futures::future::BoxFuture<'static, Result<TlsInfoStream<T>, C::Error>>;
+        type Response = TlsInfoStream<T>;
+
+        fn poll_ready(
+            &mut self,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<Result<(), Self::Error>> {
+            self.0.poll_ready(cx)
+        }
+
+        fn call(&mut self, uri: http::Uri) -> Self::Future {
+            let connecting = self.0.call(uri);
+            Box::pin(async move { connecting.await.map(TlsInfoStream) })
+        }
+    }
+
+    /// A possibly encrypted connection that reports its [`TlsInfo`] as connection extra
+    pub(crate) struct TlsInfoStream<T>(hyper_rustls::MaybeHttpsStream<T>);
+
+    impl<T> hyper_util::client::legacy::connect::Connection for TlsInfoStream<T>
+    where
+        T: hyper_util::client::legacy::connect::Connection + hyper::rt::Read + hyper::rt::Write + Unpin,
+    {
+        fn connected(&self) -> hyper_util::client::legacy::connect::Connected {
+            let connected = self.0.connected();
+            match &self.0 {
+                hyper_rustls::MaybeHttpsStream::Https(tls) => {
+                    connected.extra(TlsInfo::new(tls.inner().get_ref().1))
+                }
+                hyper_rustls::MaybeHttpsStream::Http(_) => connected,
+            }
+        }
+    }
+
+    impl<T> hyper::rt::Read for TlsInfoStream<T>
+    where
+        T: hyper::rt::Read + hyper::rt::Write + Unpin,
+    {
+        fn poll_read(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: hyper::rt::ReadBufCursor<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::pin::Pin::new(&mut self.0).poll_read(cx, buf)
+        }
+    }
+
+    impl<T> hyper::rt::Write for TlsInfoStream<T>
+    where
+        T: hyper::rt::Read + hyper::rt::Write + Unpin,
+    {
+        fn poll_write(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+            buf: &[u8],
+        ) -> std::task::Poll<std::io::Result<usize>> {
+            std::pin::Pin::new(&mut self.0).poll_write(cx, buf)
+        }
+
+        fn poll_flush(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::pin::Pin::new(&mut self.0).poll_flush(cx)
+        }
+
+        fn poll_shutdown(
+            mut self: std::pin::Pin<&mut Self>,
+            cx: &mut std::task::Context<'_>,
+        ) -> std::task::Poll<std::io::Result<()>> {
+            std::pin::Pin::new(&mut self.0).poll_shutdown(cx)
+        }
+    }
+
     #[derive(Debug)]
     struct NoCertificateVerification {}
 
@@ -179,6 +567,61 @@ pub mod rustls_tls {
             ]
         }
     }
+    #[cfg(test)]
+    mod tests {
+        use super::{ReloadingRootsVerifier, ROOT_CERT_FORCED_RELOAD_INTERVAL};
+        use rcgen::{BasicConstraints, Certificate, CertificateParams, DnType, IsCa, KeyPair};
+        use rustls::{
+            client::danger::ServerCertVerifier,
+            pki_types::{ServerName, UnixTime},
+            CertificateError,
+        };
+        use std::time::Instant;
+
+        /// Generates a CA called `name`, along with a serving certificate for `localhost` that it signed
+        fn generate_ca(name: &str) -> (String, Certificate) {
+            let ca_key = KeyPair::generate().unwrap();
+            let mut ca_params = CertificateParams::new(Vec::new()).unwrap();
+            ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+            ca_params.distinguished_name.push(DnType::CommonName, name);
+            let ca = ca_params.self_signed(&ca_key).unwrap();
+            let serving = CertificateParams::new(vec!["localhost".to_owned()])
+                .unwrap()
+                .signed_by(&KeyPair::generate().unwrap(), &ca, &ca_key)
+                .unwrap();
+            (ca.pem(), serving)
+        }
+
+        fn verify(verifier: &ReloadingRootsVerifier, serving: &Certificate) -> Result<(), rustls::Error> {
+            let server_name = ServerName::try_from("localhost").unwrap();
+            verifier
+                .verify_server_cert(serving.der(), &[], &server_name, &[], UnixTime::now())
+                .map(|_| ())
+        }
+
+        #[test]
+        fn rotated_roots_are_reloaded_at_most_once_per_interval() {
+            let (old_ca, old_serving) = generate_ca("old-ca");
+            let (new_ca, new_serving) = generate_ca("new-ca");
+            let dir = tempfile::tempdir().unwrap();
+            let path = dir.path().join("ca.crt");
+            std::fs::write(&path, old_ca).unwrap();
+            let verifier = ReloadingRootsVerifier::new(&path).unwrap();
+            verify(&verifier, &old_serving).unwrap();
+
+            // The bundle was just loaded, so the rotated CA is not picked up yet
+            std::fs::write(&path, new_ca).unwrap();
+            assert!(matches!(
+                verify(&verifier, &new_serving),
+                Err(rustls::Error::InvalidCertificate(CertificateError::UnknownIssuer))
+            ));
+
+            // Once the forced reload interval has passed, the unknown issuer reloads the bundle
+            verifier.current.write().unwrap().1 = Instant::now() - ROOT_CERT_FORCED_RELOAD_INTERVAL;
+            verify(&verifier, &new_serving).unwrap();
+            assert!(verify(&verifier, &old_serving).is_err());
+        }
+    }
 }
 
 #[cfg(feature = "openssl-tls")]
@@ -240,6 +683,10 @@ pub mod openssl_tls {
         /// Failed to add a root certificate
         #[error("failed to add a root certificate: {0}")]
         AddRootCertificate(#[source] openssl::error::ErrorStack),
+
+        /// Failed to set the ALPN protocols
+        #[error("failed to set the ALPN protocols: {0}")]
+        SetAlpnProtocols(#[source] openssl::error::ErrorStack),
     }
 
     /// Create `openssl::ssl::SslConnectorBuilder` required for `hyper_openssl::HttpsConnector`.
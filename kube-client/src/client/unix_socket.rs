@@ -0,0 +1,118 @@
+//! Connecting to an apiserver listening on a unix domain socket
+use std::{
+    io,
+    path::PathBuf,
+    pin::Pin,
+    sync::Arc,
+    task::{Context, Poll},
+};
+
+use futures::future::BoxFuture;
+use hyper::rt::{Read, ReadBufCursor, Write};
+use hyper_util::{
+    client::legacy::connect::{Connected, Connection},
+    rt::TokioIo,
+};
+use tokio::net::UnixStream;
+
+/// Connects every request to the same unix domain socket, regardless of the request URI
+#[derive(Clone, Debug)]
+pub(crate) struct UnixSocketConnector {
+    path: Arc<PathBuf>,
+}
+
+impl UnixSocketConnector {
+    pub(crate) fn new(path: PathBuf) -> Self {
+        Self { path: Arc::new(path) }
+    }
+}
+
+impl tower::Service<http::Uri> for UnixSocketConnector {
+    type Error = io::Error;
+    type Future = BoxFuture<'static, io::Result<UnixConnection>>;
+    type Response = UnixConnection;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, _uri: http::Uri) -> Self::Future {
+        let path = self.path.clone();
+        Box::pin(async move {
+            let stream = UnixStream::connect(path.as_path()).await?;
+            Ok(UnixConnection(TokioIo::new(stream)))
+        })
+    }
+}
+
+/// A connection to a unix domain socket
+#[derive(Debug)]
+pub(crate) struct UnixConnection(TokioIo<UnixStream>);
+
+impl Connection for UnixConnection {
+    fn connected(&self) -> Connected {
+        Connected::new()
+    }
+}
+
+impl Read for UnixConnection {
+    fn poll_read(
+        mut self: Pin<&mut Self>,
+        cx: &mut Context<'_>,
+        buf: ReadBufCursor<'_>,
+    ) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_read(cx, buf)
+    }
+}
+
+impl Write for UnixConnection {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.0).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_flush(cx)
+    }
+
+    fn poll_shutdown(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.0).poll_shutdown(cx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::{Api, Client, Config};
+    use bytes::Bytes;
+    use http::{Request, Response};
+    use http_body_util::Full;
+    use hyper::body::Incoming;
+    use hyper_util::rt::TokioIo;
+    use k8s_openapi::api::core::v1::Namespace;
+
+    async fn get_namespace(
+        req: Request<Incoming>,
+    ) -> Result<Response<Full<Bytes>>, std::convert::Infallible> {
+        assert_eq!(req.uri().path(), "/api/v1/namespaces/default");
+        let body = r#"{"apiVersion": "v1", "kind": "Namespace", "metadata": {"name": "default"}}"#;
+        Ok(Response::new(Full::new(Bytes::from(body))))
+    }
+
+    #[tokio::test]
+    async fn client_talks_to_a_unix_socket() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("kube.sock");
+        let listener = tokio::net::UnixListener::bind(&path).unwrap();
+        let server = tokio::spawn(async move {
+            let (stream, _) = listener.accept().await.unwrap();
+            hyper::server::conn::http1::Builder::new()
+                .serve_connection(TokioIo::new(stream), hyper::service::service_fn(get_namespace))
+                .await
+                .unwrap();
+        });
+
+        let client = Client::try_from(Config::from_unix_socket(&path)).unwrap();
+        let namespace = Api::<Namespace>::all(client).get("default").await.unwrap();
+        assert_eq!(namespace.metadata.name.as_deref(), Some("default"));
+        server.abort();
+    }
+}
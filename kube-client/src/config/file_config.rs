@@ -199,6 +199,9 @@ pub struct AuthInfo {
     pub token_file: Option<String>,
 
     /// Path to a client cert file for TLS.
+    ///
+    /// With the `rustls-tls` stack, the certificate and key files are read again when they change,
+    /// so that rotated certificates are picked up by new connections.
     #[serde(rename = "client-certificate")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub client_certificate: Option<String>,
@@ -230,6 +233,10 @@ pub struct AuthInfo {
     #[serde(rename = "as-groups")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub impersonate_groups: Option<Vec<String>>,
+    /// The extra information to impersonate, such as scopes.
+    #[serde(rename = "as-user-extra")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub impersonate_extra: Option<HashMap<String, Vec<String>>>,
 
     /// Specifies a custom authentication plugin for the kubernetes cluster.
     #[serde(rename = "auth-provider")]
@@ -288,6 +295,10 @@ pub struct ExecConfig {
     pub drop_env: Option<Vec<String>>,
 
     /// Interative mode of the auth plugins
+    ///
+    /// Interactive plugins share the terminal for stdin and stderr, so they can prompt the user.
+    /// `IfAvailable` plugins are only interactive when stdin is a terminal, while `Always` plugins
+    /// fall back to the controlling terminal on unix, and fail if there is none.
     #[serde(rename = "interactiveMode")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub interactive_mode: Option<ExecInteractiveMode>,
@@ -304,6 +315,28 @@ pub struct ExecConfig {
     /// Should be used only when `provide_cluster_info` is True.
     #[serde(skip)]
     pub cluster: Option<ExecAuthCluster>,
+
+    /// Where to share the credentials returned by the plugin, so that other clients can reuse them until they expire
+    ///
+    /// Credentials are shared between plugins that are run with the same command, arguments, environment
+    /// and cluster information. Only credentials with an `expirationTimestamp` are cached.
+    ///
+    /// This does currently not exist upstream and cannot be specified on disk,
+    /// see [`Config::exec_credential_cache`](crate::Config::exec_credential_cache).
+    #[serde(skip)]
+    pub credential_cache: Option<ExecCredentialCache>,
+}
+
+/// Where the credentials of exec plugins are cached, see [`ExecConfig::credential_cache`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ExecCredentialCache {
+    /// Share credentials between the clients of the current process
+    InProcess,
+    /// Share credentials between processes through files in a directory
+    ///
+    /// The files contain the credentials, so the directory should only be accessible by the current user.
+    /// It is created with restricted permissions if it does not exist.
+    Directory(PathBuf),
 }
 
 /// ExecInteractiveMode define the interactity of the child process
@@ -345,6 +378,41 @@ pub struct Context {
     pub extensions: Option<Vec<NamedExtension>>,
 }
 
+/// A context of a [`Kubeconfig`], as listed by [`Kubeconfig::contexts`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ContextInfo<'a> {
+    /// Name of the context
+    pub name: &'a str,
+    /// Whether this is the `current-context`
+    pub current: bool,
+    /// Name of the cluster of the context
+    pub cluster: Option<&'a str>,
+    /// Name of the user of the context
+    pub user: Option<&'a str>,
+    /// The default namespace of the context
+    pub namespace: Option<&'a str>,
+}
+
+/// A cluster of a [`Kubeconfig`], as listed by [`Kubeconfig::clusters`]
+#[derive(Clone, Debug)]
+pub struct ClusterInfo<'a> {
+    /// Name of the cluster
+    pub name: &'a str,
+    /// The address of the cluster
+    pub server: Option<&'a str>,
+    /// The full cluster config
+    pub cluster: Option<&'a Cluster>,
+}
+
+/// A user of a [`Kubeconfig`], as listed by [`Kubeconfig::users`]
+#[derive(Clone, Debug)]
+pub struct UserInfo<'a> {
+    /// Name of the user
+    pub name: &'a str,
+    /// The full user config
+    pub auth_info: Option<&'a AuthInfo>,
+}
+
 const KUBECONFIG: &str = "KUBECONFIG";
 
 /// Some helpers on the raw Config object are exposed for people needing to parse it
@@ -473,6 +541,174 @@ impl Kubeconfig {
     }
 }
 
+/// Listing of the entries of a kubeconfig, like `kubectl config get-contexts`
+impl Kubeconfig {
+    /// The contexts of the kubeconfig, in file order
+    ///
+    /// ```no_run
+    /// # fn wrapper() -> Result<(), kube::config::KubeconfigError> {
+    /// use kube::config::Kubeconfig;
+    ///
+    /// let kubeconfig = Kubeconfig::read()?;
+    /// for context in kubeconfig.contexts() {
+    ///     let marker = if context.current { "*" } else { " " };
+    ///     println!("{marker} {} ({})", context.name, context.cluster.unwrap_or_default());
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn contexts(&self) -> impl Iterator<Item = ContextInfo<'_>> {
+        self.contexts.iter().map(|named| {
+            let context = named.context.as_ref();
+            ContextInfo {
+                name: &named.name,
+                current: self.current_context.as_deref() == Some(named.name.as_str()),
+                cluster: context.map(|ctx| ctx.cluster.as_str()).filter(|c| !c.is_empty()),
+                user: context.and_then(|ctx| ctx.user.as_deref()),
+                namespace: context.and_then(|ctx| ctx.namespace.as_deref()),
+            }
+        })
+    }
+
+    /// The clusters of the kubeconfig, in file order
+    pub fn clusters(&self) -> impl Iterator<Item = ClusterInfo<'_>> {
+        self.clusters.iter().map(|named| ClusterInfo {
+            name: &named.name,
+            server: named.cluster.as_ref().and_then(|c| c.server.as_deref()),
+            cluster: named.cluster.as_ref(),
+        })
+    }
+
+    /// The users of the kubeconfig, in file order
+    pub fn users(&self) -> impl Iterator<Item = UserInfo<'_>> {
+        self.auth_infos.iter().map(|named| UserInfo {
+            name: &named.name,
+            auth_info: named.auth_info.as_ref(),
+        })
+    }
+}
+
+/// Helpers for managing a kubeconfig, like `kubectl config`
+impl Kubeconfig {
+    /// Add a cluster, replacing any existing cluster with the same name
+    pub fn set_cluster(&mut self, name: impl Into<String>, cluster: Cluster) -> &mut Self {
+        let named = NamedCluster {
+            name: name.into(),
+            cluster: Some(cluster),
+        };
+        upsert_named(&mut self.clusters, named, |x| &x.name);
+        self
+    }
+
+    /// Add a user, replacing any existing user with the same name
+    pub fn set_auth_info(&mut self, name: impl Into<String>, auth_info: AuthInfo) -> &mut Self {
+        let named = NamedAuthInfo {
+            name: name.into(),
+            auth_info: Some(auth_info),
+        };
+        upsert_named(&mut self.auth_infos, named, |x| &x.name);
+        self
+    }
+
+    /// Add a context, replacing any existing context with the same name
+    pub fn set_context(&mut self, name: impl Into<String>, context: Context) -> &mut Self {
+        let named = NamedContext {
+            name: name.into(),
+            context: Some(context),
+        };
+        upsert_named(&mut self.contexts, named, |x| &x.name);
+        self
+    }
+
+    /// Set `current-context` to an existing context
+    pub fn set_current_context(&mut self, name: &str) -> Result<&mut Self, KubeconfigError> {
+        if !self.contexts.iter().any(|x| x.name == name) {
+            return Err(KubeconfigError::UnknownContext(name.to_owned()));
+        }
+        self.current_context = Some(name.to_owned());
+        Ok(self)
+    }
+
+    /// Remove a cluster, returning it if it existed
+    pub fn remove_cluster(&mut self, name: &str) -> Option<NamedCluster> {
+        remove_named(&mut self.clusters, name, |x| &x.name)
+    }
+
+    /// Remove a user, returning it if it existed
+    pub fn remove_auth_info(&mut self, name: &str) -> Option<NamedAuthInfo> {
+        remove_named(&mut self.auth_infos, name, |x| &x.name)
+    }
+
+    /// Remove a context, returning it if it existed
+    ///
+    /// Like `kubectl config delete-context`, this leaves `current-context` untouched.
+    pub fn remove_context(&mut self, name: &str) -> Option<NamedContext> {
+        remove_named(&mut self.contexts, name, |x| &x.name)
+    }
+
+    /// Serialize the kubeconfig to YAML
+    pub fn to_yaml(&self) -> Result<String, KubeconfigError> {
+        serde_yaml::to_string(self).map_err(KubeconfigError::Serialize)
+    }
+
+    /// Write the kubeconfig to `path`, replacing the file atomically
+    ///
+    /// The file is locked while writing with a `<path>.lock` file, the same convention as `kubectl`,
+    /// and writing fails if another process holds the lock. New files are only readable by the owner.
+    pub fn write_to<P: AsRef<Path>>(&self, path: P) -> Result<(), KubeconfigError> {
+        let path = path.as_ref();
+        let _lock = FileLock::acquire(path).map_err(|err| KubeconfigError::LockConfig(err, path.into()))?;
+        self.write_locked(path)
+    }
+
+    /// Update the kubeconfig at `path` with `f`, holding the lock between reading and writing
+    ///
+    /// A missing file is treated as an empty kubeconfig and created. Unlike [`Kubeconfig::read_from`],
+    /// relative paths to certificates and keys are left as is, so that they are written back unchanged.
+    ///
+    /// ```no_run
+    /// # fn wrapper() -> Result<(), kube::config::KubeconfigError> {
+    /// use kube::config::{Cluster, Context, Kubeconfig};
+    ///
+    /// Kubeconfig::modify("/tmp/kubeconfig", |config| {
+    ///     config.set_cluster("kind", Cluster {
+    ///         server: Some("https://127.0.0.1:6443".into()),
+    ///         ..Cluster::default()
+    ///     });
+    ///     config.set_context("kind", Context {
+    ///         cluster: "kind".into(),
+    ///         user: Some("kind-admin".into()),
+    ///         ..Context::default()
+    ///     });
+    ///     config.set_current_context("kind")?;
+    ///     Ok(())
+    /// })?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub fn modify<P, F>(path: P, f: F) -> Result<Kubeconfig, KubeconfigError>
+    where
+        P: AsRef<Path>,
+        F: FnOnce(&mut Kubeconfig) -> Result<(), KubeconfigError>,
+    {
+        let path = path.as_ref();
+        let _lock = FileLock::acquire(path).map_err(|err| KubeconfigError::LockConfig(err, path.into()))?;
+        let mut config = match read_path(path) {
+            Ok(data) => Kubeconfig::from_yaml(&data)?,
+            Err(err) if err.kind() == io::ErrorKind::NotFound => Kubeconfig::default(),
+            Err(err) => return Err(KubeconfigError::ReadConfig(err, path.into())),
+        };
+        f(&mut config)?;
+        config.write_locked(path)?;
+        Ok(config)
+    }
+
+    fn write_locked(&self, path: &Path) -> Result<(), KubeconfigError> {
+        let data = self.to_yaml()?;
+        write_atomic(path, data.as_bytes()).map_err(|err| KubeconfigError::WriteConfig(err, path.into()))
+    }
+}
+
 fn kubeconfig_from_yaml(text: &str) -> Result<Vec<Kubeconfig>, KubeconfigError> {
     let mut documents = vec![];
     for doc in serde_yaml::Deserializer::from_str(text) {
@@ -497,6 +733,79 @@ where
     });
 }
 
+fn upsert_named<T, F>(base: &mut Vec<T>, item: T, f: F)
+where
+    F: Fn(&T) -> &String,
+{
+    match base.iter_mut().find(|x| f(x) == f(&item)) {
+        Some(existing) => *existing = item,
+        None => base.push(item),
+    }
+}
+
+fn remove_named<T, F>(base: &mut Vec<T>, name: &str, f: F) -> Option<T>
+where
+    F: Fn(&T) -> &String,
+{
+    let index = base.iter().position(|x| f(x) == name)?;
+    Some(base.remove(index))
+}
+
+/// Lock file next to a kubeconfig, removed when dropped
+struct FileLock(PathBuf);
+
+impl FileLock {
+    fn acquire(path: &Path) -> io::Result<Self> {
+        if let Some(dir) = path.parent().filter(|dir| !dir.as_os_str().is_empty()) {
+            fs::create_dir_all(dir)?;
+        }
+        let mut lock_path = path.as_os_str().to_owned();
+        lock_path.push(".lock");
+        let lock_path = PathBuf::from(lock_path);
+        fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&lock_path)?;
+        Ok(Self(lock_path))
+    }
+}
+
+impl Drop for FileLock {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.0);
+    }
+}
+
+/// Write to a temporary file in the same directory and rename it over `path`,
+/// so that readers never see a partially written kubeconfig.
+fn write_atomic(path: &Path, data: &[u8]) -> io::Result<()> {
+    use std::io::Write;
+
+    let mut tmp_path = path.as_os_str().to_owned();
+    tmp_path.push(".tmp");
+    let tmp_path = PathBuf::from(tmp_path);
+    // Keep the permissions of the existing file
+    let permissions = fs::metadata(path).ok().map(|metadata| metadata.permissions());
+
+    let result = (|| {
+        let mut options = fs::OpenOptions::new();
+        options.write(true).create(true).truncate(true);
+        #[cfg(unix)]
+        std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+        let mut file = options.open(&tmp_path)?;
+        if let Some(permissions) = permissions {
+            file.set_permissions(permissions)?;
+        }
+        file.write_all(data)?;
+        file.sync_all()?;
+        fs::rename(&tmp_path, path)
+    })();
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp_path);
+    }
+    result
+}
+
 fn read_path<P: AsRef<Path>>(path: P) -> io::Result<String> {
     let bytes = fs::read(&path)?;
     match bytes.as_slice() {
@@ -557,6 +866,17 @@ impl AuthInfo {
         Ok(buffer)
     }
 
+    /// The client certificate and key files, unless either of them is given inline
+    pub(crate) fn identity_files(&self) -> Option<(&Path, &Path)> {
+        if self.client_certificate_data.is_some() || self.client_key_data.is_some() {
+            return None;
+        }
+        Some((
+            Path::new(self.client_certificate.as_deref()?),
+            Path::new(self.client_key.as_deref()?),
+        ))
+    }
+
     pub(crate) fn load_client_certificate(&self) -> Result<Vec<u8>, KubeconfigError> {
         // TODO Shouldn't error when `self.client_certificate_data.is_none() && self.client_certificate.is_none()`
 
@@ -753,6 +1073,107 @@ mod tests {
         assert_eq!(merged.auth_infos[1].name, "green-user");
     }
 
+    #[test]
+    fn identity_files_are_only_used_without_inline_data() {
+        let mut auth_info = AuthInfo {
+            client_certificate: Some("/var/lib/kubelet/pki/kubelet-client-current.pem".into()),
+            client_key: Some("/var/lib/kubelet/pki/kubelet-client-current.pem".into()),
+            ..Default::default()
+        };
+        let pem = Path::new("/var/lib/kubelet/pki/kubelet-client-current.pem");
+        assert_eq!(auth_info.identity_files(), Some((pem, pem)));
+
+        auth_info.client_certificate_data = Some("aGVsbG8K".into());
+        assert_eq!(auth_info.identity_files(), None);
+    }
+
+    #[test]
+    fn kubeconfig_set_and_remove() {
+        let mut config = Kubeconfig::default();
+        config
+            .set_cluster("kind", Cluster {
+                server: Some("https://127.0.0.1:6443".into()),
+                ..Default::default()
+            })
+            .set_context("kind", Context {
+                cluster: "kind".into(),
+                ..Default::default()
+            });
+        assert!(matches!(
+            config.set_current_context("missing"),
+            Err(KubeconfigError::UnknownContext(_))
+        ));
+        config.set_current_context("kind").unwrap();
+        assert_eq!(config.current_context.as_deref(), Some("kind"));
+
+        // Replaces the existing cluster in place
+        config.set_cluster("kind", Cluster {
+            server: Some("https://127.0.0.1:7443".into()),
+            ..Default::default()
+        });
+        assert_eq!(config.clusters.len(), 1);
+        assert_eq!(
+            config.clusters[0].cluster.as_ref().unwrap().server.as_deref(),
+            Some("https://127.0.0.1:7443")
+        );
+
+        assert_eq!(config.remove_context("kind").unwrap().name, "kind");
+        assert!(config.remove_context("kind").is_none());
+        assert!(config.contexts.is_empty());
+    }
+
+    #[test]
+    fn kubeconfig_write_and_modify() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("nested").join("config");
+
+        let created = Kubeconfig::modify(&path, |config| {
+            config
+                .set_cluster("kind", Cluster {
+                    server: Some("https://127.0.0.1:6443".into()),
+                    ..Default::default()
+                })
+                .set_auth_info("admin", AuthInfo {
+                    token: Some(SecretString::new("token".into())),
+                    ..Default::default()
+                })
+                .set_context("kind", Context {
+                    cluster: "kind".into(),
+                    user: Some("admin".into()),
+                    ..Default::default()
+                })
+                .set_current_context("kind")?;
+            Ok(())
+        })?;
+        assert_eq!(Kubeconfig::read_from(&path)?, created);
+
+        let mut updated = created.clone();
+        updated.remove_auth_info("admin");
+        updated.write_to(&path)?;
+        assert_eq!(Kubeconfig::read_from(&path)?, updated);
+
+        // Lock files and temporary files are cleaned up
+        let mut files = fs::read_dir(path.parent().unwrap())?
+            .map(|entry| entry.map(|entry| entry.file_name()))
+            .collect::<Result<Vec<_>, _>>()?;
+        files.sort();
+        assert_eq!(files, vec!["config"]);
+        Ok(())
+    }
+
+    #[test]
+    fn kubeconfig_write_fails_when_locked() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let path = dir.path().join("config");
+        let _lock = FileLock::acquire(&path)?;
+        assert!(matches!(
+            Kubeconfig::default().write_to(&path),
+            Err(KubeconfigError::LockConfig(..))
+        ));
+        assert!(!path.exists());
+        Ok(())
+    }
+
     #[test]
     fn kubeconfig_deserialize() {
         let config_yaml = "apiVersion: v1
@@ -913,6 +1334,56 @@ users:
         Ok(())
     }
 
+    #[test]
+    fn kubeconfig_lists_its_entries() {
+        let config = Kubeconfig::from_yaml(
+            r#"
+clusters:
+- name: prod
+  cluster:
+    server: https://prod:6443
+- name: kind
+contexts:
+- name: prod-admin
+  context:
+    cluster: prod
+    user: admin
+    namespace: web
+- name: kind
+  context:
+    cluster: kind
+current-context: kind
+users:
+- name: admin
+  user:
+    token: secret
+"#,
+        )
+        .unwrap();
+
+        let contexts: Vec<_> = config.contexts().collect();
+        assert_eq!(contexts, [
+            ContextInfo {
+                name: "prod-admin",
+                current: false,
+                cluster: Some("prod"),
+                user: Some("admin"),
+                namespace: Some("web"),
+            },
+            ContextInfo {
+                name: "kind",
+                current: true,
+                cluster: Some("kind"),
+                user: None,
+                namespace: None,
+            },
+        ]);
+        let clusters: Vec<_> = config.clusters().map(|c| (c.name, c.server)).collect();
+        assert_eq!(clusters, [("prod", Some("https://prod:6443")), ("kind", None)]);
+        let users: Vec<_> = config.users().map(|u| (u.name, u.auth_info.is_some())).collect();
+        assert_eq!(users, [("admin", true)]);
+    }
+
     #[test]
     fn kubeconfig_from_empty_string() {
         let cfg = Kubeconfig::from_yaml("").unwrap();
@@ -945,7 +1416,7 @@ password: kube_rs
         token: None, token_file: None, client_certificate: None, \
         client_certificate_data: None, client_key: None, \
         client_key_data: None, impersonate: None, \
-        impersonate_groups: None, \
+        impersonate_groups: None, impersonate_extra: None, \
         auth_provider: None, \
         exec: None \
         }";
@@ -120,12 +120,14 @@ impl ConfigLoader {
         }
     }
 
-    pub fn proxy_url(&self) -> Result<Option<http::Uri>, KubeconfigError> {
-        let nonempty = |o: Option<String>| o.filter(|s| !s.is_empty());
-
-        if let Some(proxy) = nonempty(self.cluster.proxy_url.clone())
-            .or_else(|| nonempty(std::env::var("HTTPS_PROXY").ok()))
-            .or_else(|| nonempty(std::env::var("https_proxy").ok()))
+    /// The `proxy-url` of the cluster, or else the proxy from the environment for `cluster_url`
+    pub fn proxy_url(&self, cluster_url: &http::Uri) -> Result<Option<http::Uri>, KubeconfigError> {
+        if let Some(proxy) = self
+            .cluster
+            .proxy_url
+            .clone()
+            .filter(|s| !s.is_empty())
+            .or_else(|| super::proxy::from_env(cluster_url))
         {
             Ok(Some(
                 proxy
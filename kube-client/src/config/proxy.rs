@@ -0,0 +1,170 @@
+//! Proxy settings from the environment, following `http.ProxyFromEnvironment` of Go
+use std::net::IpAddr;
+
+use http::uri::{Scheme, Uri};
+
+/// The proxy for `cluster_url` from `HTTPS_PROXY`, or `HTTP_PROXY` for plain http clusters
+///
+/// Returns `None` for clusters on localhost, such as a `kubectl proxy`, and when `NO_PROXY` excludes the cluster.
+/// Proxies without a scheme are http proxies.
+pub(crate) fn from_env(cluster_url: &Uri) -> Option<String> {
+    proxy_for(cluster_url, |name| std::env::var(name).ok())
+}
+
+fn proxy_for(cluster_url: &Uri, env: impl Fn(&str) -> Option<String>) -> Option<String> {
+    if is_localhost(cluster_url) {
+        return None;
+    }
+    let var = |names: [&str; 2]| {
+        names
+            .into_iter()
+            .find_map(|name| env(name).filter(|v| !v.is_empty()))
+    };
+
+    let proxy = if cluster_url.scheme() == Some(&Scheme::HTTP) {
+        var(["HTTP_PROXY", "http_proxy"])
+    } else {
+        var(["HTTPS_PROXY", "https_proxy"])
+    }?;
+    if let Some(no_proxy) = var(["NO_PROXY", "no_proxy"]) {
+        if excluded(&no_proxy, cluster_url) {
+            return None;
+        }
+    }
+    if proxy.contains("://") {
+        Some(proxy)
+    } else {
+        Some(format!("http://{proxy}"))
+    }
+}
+
+fn is_localhost(url: &Uri) -> bool {
+    let host = url.host().unwrap_or_default();
+    let host = host.trim_start_matches('[').trim_end_matches(']');
+    host.eq_ignore_ascii_case("localhost") || host.parse::<IpAddr>().is_ok_and(|ip| ip.is_loopback())
+}
+
+/// Whether a `NO_PROXY` list excludes `url` from proxying
+///
+/// Entries are `*`, IP addresses, CIDR ranges, or domains that also match their subdomains,
+/// optionally with a port.
+fn excluded(no_proxy: &str, url: &Uri) -> bool {
+    let Some(host) = url.host() else {
+        return false;
+    };
+    let host = host
+        .trim_start_matches('[')
+        .trim_end_matches(']')
+        .to_ascii_lowercase();
+    let host_ip = host.parse::<IpAddr>().ok();
+    let default_port = if url.scheme() == Some(&Scheme::HTTP) {
+        80
+    } else {
+        443
+    };
+    let port = url.port_u16().unwrap_or(default_port);
+
+    no_proxy.split(',').map(str::trim).any(|entry| {
+        if entry == "*" {
+            return true;
+        }
+        if let Some((network, prefix)) = entry.split_once('/') {
+            return match (host_ip, network.parse::<IpAddr>(), prefix.parse::<u8>()) {
+                (Some(ip), Ok(network), Ok(prefix)) => in_network(ip, network, prefix),
+                _ => false,
+            };
+        }
+
+        let (entry_host, entry_port) = split_port(entry);
+        if entry_port.is_some_and(|entry_port| entry_port != port) {
+            return false;
+        }
+        if let Ok(entry_ip) = entry_host.parse::<IpAddr>() {
+            return host_ip == Some(entry_ip);
+        }
+        let domain = entry_host
+            .trim_start_matches("*.")
+            .trim_start_matches('.')
+            .to_ascii_lowercase();
+        !domain.is_empty()
+            && (host == domain || host.strip_suffix(&domain).is_some_and(|sub| sub.ends_with('.')))
+    })
+}
+
+/// Split an entry into its host and port, leaving bare IPv6 addresses intact
+fn split_port(entry: &str) -> (&str, Option<u16>) {
+    if let Some(rest) = entry.strip_prefix('[') {
+        return match rest.split_once(']') {
+            Some((ip, port)) => (ip, port.strip_prefix(':').and_then(|p| p.parse().ok())),
+            None => (rest, None),
+        };
+    }
+    match entry.split_once(':') {
+        Some((host, port)) if !port.contains(':') => (host, port.parse().ok()),
+        _ => (entry, None),
+    }
+}
+
+fn in_network(ip: IpAddr, network: IpAddr, prefix: u8) -> bool {
+    match (ip, network) {
+        (IpAddr::V4(ip), IpAddr::V4(network)) if prefix <= 32 => {
+            let mask = u32::MAX.checked_shl(32 - u32::from(prefix)).unwrap_or(0);
+            u32::from(ip) & mask == u32::from(network) & mask
+        }
+        (IpAddr::V6(ip), IpAddr::V6(network)) if prefix <= 128 => {
+            let mask = u128::MAX.checked_shl(128 - u32::from(prefix)).unwrap_or(0);
+            u128::from(ip) & mask == u128::from(network) & mask
+        }
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{excluded, proxy_for};
+
+    #[test]
+    fn proxy_is_picked_by_cluster_scheme() {
+        let env = |name: &str| match name {
+            "https_proxy" => Some("proxy.corp:3128".to_string()),
+            "HTTP_PROXY" => Some("socks5://socks.corp:1080".to_string()),
+            "NO_PROXY" => Some(".internal".to_string()),
+            _ => None,
+        };
+        let proxy = |url: &str| proxy_for(&url.parse().unwrap(), env);
+
+        assert_eq!(
+            proxy("https://k8s.example.com").as_deref(),
+            Some("http://proxy.corp:3128")
+        );
+        assert_eq!(
+            proxy("http://k8s.example.com").as_deref(),
+            Some("socks5://socks.corp:1080")
+        );
+        assert_eq!(proxy("https://api.k8s.internal"), None);
+        assert_eq!(proxy("http://127.0.0.1:8001"), None);
+        assert_eq!(proxy("https://[::1]:6443"), None);
+    }
+
+    #[test]
+    fn no_proxy_matches_like_go() {
+        let excludes = |no_proxy: &str, url: &str| excluded(no_proxy, &url.parse().unwrap());
+
+        assert!(excludes("*", "https://anything:6443"));
+        assert!(excludes("example.com", "https://example.com"));
+        assert!(excludes("example.com", "https://api.example.com"));
+        assert!(excludes(".example.com", "https://api.example.com"));
+        assert!(excludes("*.example.com", "https://api.example.com"));
+        assert!(!excludes("example.com", "https://notexample.com"));
+        assert!(excludes("example.com:6443", "https://example.com:6443"));
+        assert!(!excludes("example.com:6443", "https://example.com"));
+
+        assert!(excludes("10.0.0.1", "https://10.0.0.1:6443"));
+        assert!(excludes("10.96.0.0/12", "https://10.96.0.1"));
+        assert!(!excludes("10.96.0.0/12", "https://10.112.0.1"));
+        assert!(excludes("::1", "https://[::1]:6443"));
+        assert!(excludes("[::1]:6443", "https://[::1]:6443"));
+        assert!(excludes("fd00::/8", "https://[fd00::1]"));
+        assert!(!excludes("10.0.0.0/8", "https://example.com"));
+    }
+}
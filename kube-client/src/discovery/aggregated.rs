@@ -0,0 +1,249 @@
+//! Aggregated discovery through `apidiscovery.k8s.io/v2`
+//!
+//! Aggregated discovery returns every group, version, and resource in one response per root path,
+//! instead of one response per group version.
+//! These types mirror the subset of `apidiscovery.k8s.io/v2` that discovery needs,
+//! since they are not available in every version of `k8s-openapi`.
+use super::parse::GroupVersionData;
+use crate::{Client, Error, Result};
+use http::{header::ACCEPT, Request};
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIGroupList, APIVersions, ObjectMeta};
+use kube_core::{
+    discovery::{ApiCapabilities, ApiResource, Scope},
+    gvk::GroupVersion,
+};
+use serde::Deserialize;
+
+/// Asks for aggregated discovery, while allowing servers without it to answer with the legacy types
+const ACCEPT_AGGREGATED: &str =
+    "application/json;g=apidiscovery.k8s.io;v=v2;as=APIGroupDiscoveryList,application/json";
+
+/// The kind of a response to an aggregated discovery request
+const AGGREGATED_KIND: &str = "APIGroupDiscoveryList";
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct APIGroupDiscoveryList {
+    #[serde(default)]
+    pub(crate) items: Vec<APIGroupDiscovery>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct APIGroupDiscovery {
+    #[serde(default)]
+    pub(crate) metadata: ObjectMeta,
+    /// Versions in order of preference
+    #[serde(default)]
+    pub(crate) versions: Vec<APIVersionDiscovery>,
+}
+
+#[derive(Deserialize, Debug)]
+pub(crate) struct APIVersionDiscovery {
+    pub(crate) version: String,
+    #[serde(default)]
+    pub(crate) resources: Vec<APIResourceDiscovery>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+pub(crate) struct APIResourceDiscovery {
+    resource: String,
+    response_kind: Option<ResponseKind>,
+    scope: String,
+    #[serde(default)]
+    verbs: Vec<String>,
+    #[serde(default)]
+    subresources: Vec<APISubresourceDiscovery>,
+}
+
+#[derive(Deserialize, Debug)]
+#[serde(rename_all = "camelCase")]
+struct APISubresourceDiscovery {
+    subresource: String,
+    response_kind: Option<ResponseKind>,
+    #[serde(default)]
+    verbs: Vec<String>,
+}
+
+#[derive(Deserialize, Debug)]
+struct ResponseKind {
+    #[serde(default)]
+    group: String,
+    #[serde(default)]
+    version: String,
+    kind: String,
+}
+
+/// The response to a discovery request on a root path
+pub(crate) enum Discovered<T> {
+    /// The server supports aggregated discovery
+    Aggregated(APIGroupDiscoveryList),
+    /// The server only supports legacy discovery, and answered with the legacy type for the path
+    Legacy(T),
+}
+
+/// Runs aggregated discovery on `/apis`, or returns the [`APIGroupList`] if it is not supported
+pub(crate) async fn apis(client: &Client) -> Result<Discovered<APIGroupList>> {
+    discover(client, "/apis").await
+}
+
+/// Runs aggregated discovery on `/api`, or returns the core [`APIVersions`] if it is not supported
+pub(crate) async fn core(client: &Client) -> Result<Discovered<APIVersions>> {
+    discover(client, "/api").await
+}
+
+async fn discover<T>(client: &Client, path: &str) -> Result<Discovered<T>>
+where
+    T: serde::de::DeserializeOwned,
+{
+    let req = Request::builder()
+        .uri(path)
+        .header(ACCEPT, ACCEPT_AGGREGATED)
+        .body(vec![])
+        .map_err(Error::HttpError)?;
+    let value: serde_json::Value = client.request(req).await?;
+    if value.get("kind").and_then(serde_json::Value::as_str) == Some(AGGREGATED_KIND) {
+        serde_json::from_value(value)
+            .map(Discovered::Aggregated)
+            .map_err(Error::SerdeError)
+    } else {
+        serde_json::from_value(value)
+            .map(Discovered::Legacy)
+            .map_err(Error::SerdeError)
+    }
+}
+
+impl GroupVersionData {
+    /// Extracts all resources of a version of an aggregated group
+    pub(crate) fn from_aggregated(group: &str, discovered: APIVersionDiscovery) -> Self {
+        let gv = GroupVersion::gv(group, &discovered.version);
+        let mut resources = vec![];
+        for res in discovered.resources {
+            let Some(kind) = res.response_kind else {
+                tracing::debug!(
+                    resource = res.resource.as_str(),
+                    "skipping resource without a kind"
+                );
+                continue;
+            };
+            let scope = if res.scope == "Cluster" {
+                Scope::Cluster
+            } else {
+                Scope::Namespaced
+            };
+            let subresources = res
+                .subresources
+                .into_iter()
+                .map(|sub| {
+                    // An empty version means that the subresource shares the group version of its resource
+                    let (group, version, kind) = match sub.response_kind {
+                        Some(sub_kind) if !sub_kind.version.is_empty() => {
+                            (sub_kind.group, sub_kind.version, sub_kind.kind)
+                        }
+                        Some(sub_kind) => (gv.group.clone(), gv.version.clone(), sub_kind.kind),
+                        None => (gv.group.clone(), gv.version.clone(), kind.kind.clone()),
+                    };
+                    let ar = ApiResource {
+                        group,
+                        version,
+                        api_version: gv.api_version(),
+                        kind,
+                        plural: sub.subresource,
+                    };
+                    let caps = ApiCapabilities {
+                        scope: scope.clone(),
+                        subresources: vec![],
+                        operations: sub.verbs,
+                    };
+                    (ar, caps)
+                })
+                .collect();
+            let ar = ApiResource {
+                group: gv.group.clone(),
+                version: gv.version.clone(),
+                api_version: gv.api_version(),
+                kind: kind.kind,
+                plural: res.resource,
+            };
+            let caps = ApiCapabilities {
+                scope,
+                subresources,
+                operations: res.verbs,
+            };
+            resources.push((ar, caps));
+        }
+        GroupVersionData {
+            version: discovered.version,
+            resources,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{APIGroupDiscoveryList, GroupVersionData};
+    use crate::discovery::{verbs, ApiGroup, Scope};
+
+    #[test]
+    fn parses_aggregated_groups() {
+        let list: APIGroupDiscoveryList = serde_json::from_value(serde_json::json!({
+            "kind": "APIGroupDiscoveryList",
+            "apiVersion": "apidiscovery.k8s.io/v2",
+            "items": [{
+                "metadata": { "name": "apps" },
+                "versions": [{
+                    "version": "v1",
+                    "resources": [{
+                        "resource": "deployments",
+                        "responseKind": { "group": "", "version": "", "kind": "Deployment" },
+                        "scope": "Namespaced",
+                        "singularResource": "deployment",
+                        "verbs": ["create", "get", "list", "watch"],
+                        "subresources": [{
+                            "subresource": "scale",
+                            "responseKind": { "group": "autoscaling", "version": "v1", "kind": "Scale" },
+                            "verbs": ["get", "patch", "update"]
+                        }, {
+                            "subresource": "status",
+                            "responseKind": { "group": "", "version": "", "kind": "Deployment" },
+                            "verbs": ["get"]
+                        }]
+                    }],
+                    "freshness": "Current"
+                }]
+            }]
+        }))
+        .unwrap();
+
+        let group = list.items.into_iter().next().unwrap();
+        let name = group.metadata.name.clone().unwrap();
+        let group = ApiGroup::from_aggregated(name, group.versions).unwrap();
+        assert_eq!(group.name(), "apps");
+        assert_eq!(group.preferred_version_or_latest(), "v1");
+
+        let (ar, caps) = group.recommended_kind("Deployment").unwrap();
+        assert_eq!(ar.api_version, "apps/v1");
+        assert_eq!(ar.plural, "deployments");
+        assert_eq!(caps.scope, Scope::Namespaced);
+        assert!(caps.supports_operation(verbs::LIST));
+
+        let (scale, scale_caps) = caps
+            .subresources
+            .iter()
+            .find(|(ar, _)| ar.plural == "scale")
+            .unwrap();
+        assert_eq!(scale.group, "autoscaling");
+        assert_eq!(scale.kind, "Scale");
+        assert!(scale_caps.supports_operation(verbs::PATCH));
+    }
+
+    #[test]
+    fn skips_resources_without_kind() {
+        let version = serde_json::from_value(serde_json::json!({
+            "version": "v1",
+            "resources": [{ "resource": "bindings", "scope": "Namespaced", "verbs": ["create"] }]
+        }))
+        .unwrap();
+        let data = GroupVersionData::from_aggregated("", version);
+        assert!(data.resources.is_empty());
+    }
+}
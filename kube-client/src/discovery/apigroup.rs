@@ -1,4 +1,7 @@
-use super::parse::{self, GroupVersionData};
+use super::{
+    aggregated::APIVersionDiscovery,
+    parse::{self, GroupVersionData},
+};
 use crate::{error::DiscoveryError, Client, Error, Result};
 use k8s_openapi::apimachinery::pkg::apis::meta::v1::{APIGroup, APIVersions};
 pub use kube_core::discovery::{ApiCapabilities, ApiResource};
@@ -72,11 +75,11 @@ use std::{cmp::Reverse, collections::HashMap, iter::Iterator};
 /// [`ApiGroup::recommended_kind`]: crate::discovery::ApiGroup::recommended_kind
 pub struct ApiGroup {
     /// Name of the group e.g. apiregistration.k8s.io
-    name: String,
+    pub(crate) name: String,
     /// List of resource information, capabilities at particular versions
-    data: Vec<GroupVersionData>,
+    pub(crate) data: Vec<GroupVersionData>,
     /// Preferred version if exported by the `APIGroup`
-    preferred: Option<String>,
+    pub(crate) preferred: Option<String>,
 }
 
 /// Internal queriers to convert from an APIGroup (or APIVersions for core) to our ApiGroup
@@ -124,6 +127,25 @@ impl ApiGroup {
         Ok(group)
     }
 
+    pub(crate) fn from_aggregated(name: String, versions: Vec<APIVersionDiscovery>) -> Result<Self> {
+        if versions.is_empty() {
+            return Err(Error::Discovery(DiscoveryError::EmptyApiGroup(name)));
+        }
+        // Versions are listed in order of preference
+        let preferred = versions.first().map(|v| v.version.clone());
+        let data = versions
+            .into_iter()
+            .map(|v| GroupVersionData::from_aggregated(&name, v))
+            .collect();
+        let mut group = ApiGroup {
+            name,
+            data,
+            preferred,
+        };
+        group.sort_versions();
+        Ok(group)
+    }
+
     fn sort_versions(&mut self) {
         self.data
             .sort_by_cached_key(|gvd| Reverse(Version::parse(gvd.version.as_str()).priority()))
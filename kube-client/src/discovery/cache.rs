@@ -0,0 +1,300 @@
+//! On-disk cache of discovery results, in the spirit of kubectl's `~/.kube/cache/discovery`
+use super::{parse::GroupVersionData, ApiGroup, DiscoveryMode};
+use kube_core::discovery::{ApiCapabilities, ApiResource, Scope};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    io::Write,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+/// Same as the default of kubectl
+pub(crate) const DEFAULT_TTL: Duration = Duration::from_secs(6 * 60 * 60);
+
+const CACHE_FILE: &str = "kube-rs-discovery.json";
+
+// The cache format is private, so that it does not constrain the public discovery types
+
+#[derive(Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct CachedDiscovery {
+    /// The `gitVersion` of the apiserver that was discovered
+    server_version: String,
+    /// Seconds since the UNIX epoch
+    cached_at: u64,
+    mode: DiscoveryMode,
+    groups: Vec<CachedGroup>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedGroup {
+    name: String,
+    preferred: Option<String>,
+    versions: Vec<CachedVersion>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedVersion {
+    version: String,
+    resources: Vec<CachedResource>,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CachedResource {
+    #[serde(flatten)]
+    resource: ApiResource,
+    namespaced: bool,
+    subresources: Vec<CachedResource>,
+    operations: Vec<String>,
+}
+
+impl From<&ApiGroup> for CachedGroup {
+    fn from(group: &ApiGroup) -> Self {
+        let versions = group
+            .data
+            .iter()
+            .map(|data| CachedVersion {
+                version: data.version.clone(),
+                resources: data.resources.iter().map(CachedResource::from).collect(),
+            })
+            .collect();
+        Self {
+            name: group.name.clone(),
+            preferred: group.preferred.clone(),
+            versions,
+        }
+    }
+}
+
+impl From<CachedGroup> for ApiGroup {
+    fn from(group: CachedGroup) -> Self {
+        let data = group
+            .versions
+            .into_iter()
+            .map(|version| GroupVersionData {
+                version: version.version,
+                resources: version.resources.into_iter().map(Into::into).collect(),
+            })
+            .collect();
+        Self {
+            name: group.name,
+            data,
+            preferred: group.preferred,
+        }
+    }
+}
+
+impl From<&(ApiResource, ApiCapabilities)> for CachedResource {
+    fn from((resource, caps): &(ApiResource, ApiCapabilities)) -> Self {
+        Self {
+            resource: resource.clone(),
+            namespaced: caps.scope == Scope::Namespaced,
+            subresources: caps.subresources.iter().map(CachedResource::from).collect(),
+            operations: caps.operations.clone(),
+        }
+    }
+}
+
+impl From<CachedResource> for (ApiResource, ApiCapabilities) {
+    fn from(cached: CachedResource) -> Self {
+        let caps = ApiCapabilities {
+            scope: if cached.namespaced {
+                Scope::Namespaced
+            } else {
+                Scope::Cluster
+            },
+            subresources: cached.subresources.into_iter().map(Into::into).collect(),
+            operations: cached.operations,
+        };
+        (cached.resource, caps)
+    }
+}
+
+/// Returns the directory that kubectl would cache discovery for `cluster_url` in
+///
+/// This is `~/.kube/cache/discovery/<host>_<port>`, and is meant to be passed to
+/// [`Discovery::cache_dir`](super::Discovery::cache_dir).
+pub fn default_cache_dir(cluster_url: &http::Uri) -> Option<PathBuf> {
+    let dir = home::home_dir()?.join(".kube").join("cache").join("discovery");
+    Some(dir.join(cache_dir_name(cluster_url)))
+}
+
+fn cache_dir_name(cluster_url: &http::Uri) -> String {
+    let authority = cluster_url.authority().map_or("", |a| a.as_str());
+    let path = cluster_url.path().trim_end_matches('/');
+    format!("{authority}{path}")
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || "_/.()".contains(c) {
+                c
+            } else {
+                '_'
+            }
+        })
+        .collect()
+}
+
+fn unix_secs(time: SystemTime) -> u64 {
+    time.duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+/// Reads the cached groups, unless they are missing, expired at `now`, or were discovered differently
+pub(crate) fn read(
+    dir: &Path,
+    now: SystemTime,
+    ttl: Duration,
+    server_version: &str,
+    mode: &DiscoveryMode,
+) -> Option<HashMap<String, ApiGroup>> {
+    let path = dir.join(CACHE_FILE);
+    let data = match std::fs::read(&path) {
+        Ok(data) => data,
+        Err(err) => {
+            tracing::debug!(path = %path.display(), error = %err, "no cached discovery");
+            return None;
+        }
+    };
+    let cached: CachedDiscovery = match serde_json::from_slice(&data) {
+        Ok(cached) => cached,
+        Err(err) => {
+            tracing::debug!(path = %path.display(), error = %err, "ignoring invalid cached discovery");
+            return None;
+        }
+    };
+    let age = Duration::from_secs(unix_secs(now).saturating_sub(cached.cached_at));
+    if age > ttl || cached.server_version != server_version || cached.mode != *mode {
+        tracing::debug!(path = %path.display(), "cached discovery is outdated");
+        return None;
+    }
+    let groups = cached.groups.into_iter().map(ApiGroup::from);
+    Some(groups.map(|group| (group.name.clone(), group)).collect())
+}
+
+/// Writes the discovered groups, logging failures since the cache is only an optimization
+pub(crate) fn write(
+    dir: &Path,
+    now: SystemTime,
+    server_version: &str,
+    mode: &DiscoveryMode,
+    groups: &HashMap<String, ApiGroup>,
+) {
+    let cached = CachedDiscovery {
+        server_version: server_version.to_string(),
+        cached_at: unix_secs(now),
+        mode: mode.clone(),
+        groups: groups.values().map(CachedGroup::from).collect(),
+    };
+    let path = dir.join(CACHE_FILE);
+    let result = serde_json::to_vec(&cached)
+        .map_err(std::io::Error::from)
+        .and_then(|data| {
+            std::fs::create_dir_all(dir)?;
+            // Write to a unique temporary file first, so that concurrent readers never see a partial cache
+            let mut file = tempfile::NamedTempFile::new_in(dir)?;
+            file.write_all(&data)?;
+            file.persist(&path)?;
+            Ok(())
+        });
+    if let Err(err) = result {
+        tracing::warn!(path = %path.display(), error = %err, "failed to cache discovery");
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{cache_dir_name, read, write, DiscoveryMode, GroupVersionData};
+    use crate::discovery::ApiGroup;
+    use kube_core::discovery::{verbs, ApiCapabilities, ApiResource, Scope};
+    use std::{
+        collections::HashMap,
+        time::{Duration, SystemTime},
+    };
+
+    #[test]
+    fn cache_dir_names_match_kubectl() {
+        let url = "https://127.0.0.1:6443".parse().unwrap();
+        assert_eq!(cache_dir_name(&url), "127.0.0.1_6443");
+        let url = "https://example.com/k8s/clusters/c-1/".parse().unwrap();
+        assert_eq!(cache_dir_name(&url), "example.com/k8s/clusters/c_1");
+    }
+
+    #[test]
+    fn cache_is_invalidated() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let mode = DiscoveryMode::Block(vec![]);
+        let ttl = Duration::from_secs(60);
+        let now = SystemTime::now();
+        assert!(read(dir.path(), now, ttl, "v1.30.0", &mode).is_none());
+
+        write(dir.path(), now, "v1.30.0", &mode, &HashMap::new());
+        assert!(read(dir.path(), now, ttl, "v1.30.0", &mode).is_some());
+        // Server was upgraded
+        assert!(read(dir.path(), now, ttl, "v1.31.0", &mode).is_none());
+        // Different groups were discovered
+        assert!(read(
+            dir.path(),
+            now,
+            ttl,
+            "v1.30.0",
+            &DiscoveryMode::Allow(vec!["apps".into()])
+        )
+        .is_none());
+        // Cache has expired
+        let later = now + ttl + Duration::from_secs(1);
+        assert!(read(dir.path(), later, ttl, "v1.30.0", &mode).is_none());
+        Ok(())
+    }
+
+    #[test]
+    fn cache_roundtrips_groups() -> Result<(), Box<dyn std::error::Error>> {
+        let dir = tempfile::tempdir()?;
+        let mode = DiscoveryMode::Block(vec![]);
+        let now = SystemTime::now();
+        let ar = ApiResource {
+            group: "apps".into(),
+            version: "v1".into(),
+            api_version: "apps/v1".into(),
+            kind: "Deployment".into(),
+            plural: "deployments".into(),
+        };
+        let scale = ApiResource {
+            kind: "Scale".into(),
+            plural: "scale".into(),
+            ..ar.clone()
+        };
+        let caps = ApiCapabilities {
+            scope: Scope::Namespaced,
+            subresources: vec![(scale, ApiCapabilities {
+                scope: Scope::Namespaced,
+                subresources: vec![],
+                operations: vec![verbs::GET.into()],
+            })],
+            operations: vec![verbs::LIST.into(), verbs::WATCH.into()],
+        };
+        let group = ApiGroup {
+            name: "apps".into(),
+            data: vec![GroupVersionData {
+                version: "v1".into(),
+                resources: vec![(ar.clone(), caps)],
+            }],
+            preferred: Some("v1".into()),
+        };
+        write(
+            dir.path(),
+            now,
+            "v1.30.0",
+            &mode,
+            &HashMap::from([("apps".into(), group)]),
+        );
+
+        let groups = read(dir.path(), now, Duration::from_secs(60), "v1.30.0", &mode).unwrap();
+        let (res, caps) = groups["apps"].recommended_kind("Deployment").unwrap();
+        assert_eq!(res, ar);
+        assert_eq!(caps.scope, Scope::Namespaced);
+        assert!(caps.supports_operation(verbs::WATCH));
+        assert_eq!(caps.subresources[0].0.plural, "scale");
+        assert!(caps.subresources[0].1.supports_operation(verbs::GET));
+        Ok(())
+    }
+}
@@ -1,10 +1,23 @@
 //! High-level utilities for runtime API discovery.
 
-use crate::{Client, Result};
+use crate::{
+    api::{Api, DynamicObject, ListParams},
+    Client, Result,
+};
+use futures::{stream, Stream, StreamExt, TryStreamExt};
 pub use kube_core::discovery::{verbs, ApiCapabilities, ApiResource, Scope};
-use kube_core::gvk::GroupVersionKind;
-use std::collections::HashMap;
+use kube_core::{gvk::GroupVersionKind, TypeMeta};
+use serde::{Deserialize, Serialize};
+use std::{
+    collections::HashMap,
+    path::PathBuf,
+    time::{Duration, SystemTime},
+};
+mod aggregated;
+use aggregated::{APIGroupDiscoveryList, Discovered};
 mod apigroup;
+mod cache;
+pub use cache::default_cache_dir;
 pub mod oneshot;
 pub use apigroup::ApiGroup;
 mod parse;
@@ -13,6 +26,7 @@ mod parse;
 pub use oneshot::{group, pinned_group, pinned_kind};
 
 /// How the Discovery client decides what api groups to scan
+#[derive(Clone, PartialEq, Serialize, Deserialize)]
 enum DiscoveryMode {
     /// Only allow explicitly listed apigroups
     Allow(Vec<String>),
@@ -53,6 +67,8 @@ pub struct Discovery {
     client: Client,
     groups: HashMap<String, ApiGroup>,
     mode: DiscoveryMode,
+    cache_dir: Option<PathBuf>,
+    cache_ttl: Duration,
 }
 
 /// Caching discovery interface
@@ -64,7 +80,13 @@ impl Discovery {
     pub fn new(client: Client) -> Self {
         let groups = HashMap::new();
         let mode = DiscoveryMode::Block(vec![]);
-        Self { client, groups, mode }
+        Self {
+            client,
+            groups,
+            mode,
+            cache_dir: None,
+            cache_ttl: cache::DEFAULT_TTL,
+        }
     }
 
     /// Configure the discovery client to only look for the listed apigroups
@@ -81,10 +103,46 @@ impl Discovery {
         self
     }
 
+    /// Configure the discovery client to cache its results in `dir`
+    ///
+    /// [`Discovery::run`] then reuses the cached results, as long as they are younger than the
+    /// [`Discovery::cache_ttl`] and the apiserver version has not changed since.
+    /// Checking the version costs a single request, instead of a full discovery.
+    ///
+    /// The directory must be specific to the cluster; see [`default_cache_dir`] for the directory used by kubectl.
+    ///
+    /// ```no_run
+    /// use kube::{Client, Config, discovery::{self, Discovery}};
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let config = Config::infer().await?;
+    ///     let cache_dir = discovery::default_cache_dir(&config.cluster_url).ok_or("no home directory")?;
+    ///     let client = Client::try_from(config)?;
+    ///     let discovery = Discovery::new(client).cache_dir(cache_dir).run().await?;
+    ///     Ok(())
+    /// }
+    /// ```
+    #[must_use]
+    pub fn cache_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.cache_dir = Some(dir.into());
+        self
+    }
+
+    /// Configure how long cached results are reused for
+    ///
+    /// Defaults to 6 hours, like kubectl. Only has an effect together with [`Discovery::cache_dir`].
+    #[must_use]
+    pub fn cache_ttl(mut self, ttl: Duration) -> Self {
+        self.cache_ttl = ttl;
+        self
+    }
+
     /// Runs or re-runs the configured discovery algorithm and updates/populates the cache
     ///
-    /// The cache is empty cleared when this is started. By default, every api group found is checked,
-    /// causing `N+2` queries to the api server (where `N` is number of api groups).
+    /// The cache is empty cleared when this is started. Servers that support aggregated discovery
+    /// (`apidiscovery.k8s.io/v2`, Kubernetes 1.30+) are queried with 2 requests in total.
+    /// Otherwise every api group found is checked, causing `N+2` queries to the api server
+    /// (where `N` is number of api groups).
     ///
     /// ```no_run
     /// use kube::{Client, api::{Api, DynamicObject}, discovery::{Discovery, verbs, Scope}, ResourceExt};
@@ -110,23 +168,63 @@ impl Discovery {
     /// See a bigger example in [examples/dynamic.api](https://github.com/kube-rs/kube/blob/main/examples/dynamic_api.rs)
     pub async fn run(mut self) -> Result<Self> {
         self.groups.clear();
-        let api_groups = self.client.list_api_groups().await?;
+        let Some(dir) = self.cache_dir.clone() else {
+            self.discover().await?;
+            return Ok(self);
+        };
+        let server_version = self.client.apiserver_version().await?.git_version;
+        if let Some(groups) = cache::read(
+            &dir,
+            SystemTime::now(),
+            self.cache_ttl,
+            &server_version,
+            &self.mode,
+        ) {
+            self.groups = groups;
+            return Ok(self);
+        }
+        self.discover().await?;
+        cache::write(&dir, SystemTime::now(), &server_version, &self.mode, &self.groups);
+        Ok(self)
+    }
+
+    async fn discover(&mut self) -> Result<()> {
         // query regular groups + crds under /apis
-        for g in api_groups.groups {
-            let key = g.name.clone();
-            if self.mode.is_queryable(&key) {
-                let apigroup = ApiGroup::query_apis(&self.client, g).await?;
-                self.groups.insert(key, apigroup);
+        match aggregated::apis(&self.client).await? {
+            Discovered::Aggregated(list) => self.insert_aggregated(list)?,
+            Discovered::Legacy(api_groups) => {
+                for g in api_groups.groups {
+                    let key = g.name.clone();
+                    if self.mode.is_queryable(&key) {
+                        let apigroup = ApiGroup::query_apis(&self.client, g).await?;
+                        self.groups.insert(key, apigroup);
+                    }
+                }
             }
         }
         // query core versions under /api
         let corekey = ApiGroup::CORE_GROUP.to_string();
         if self.mode.is_queryable(&corekey) {
-            let coreapis = self.client.list_core_api_versions().await?;
-            let apigroup = ApiGroup::query_core(&self.client, coreapis).await?;
-            self.groups.insert(corekey, apigroup);
+            match aggregated::core(&self.client).await? {
+                Discovered::Aggregated(list) => self.insert_aggregated(list)?,
+                Discovered::Legacy(coreapis) => {
+                    let apigroup = ApiGroup::query_core(&self.client, coreapis).await?;
+                    self.groups.insert(corekey, apigroup);
+                }
+            }
         }
-        Ok(self)
+        Ok(())
+    }
+
+    fn insert_aggregated(&mut self, list: APIGroupDiscoveryList) -> Result<()> {
+        for g in list.items {
+            let key = g.metadata.name.unwrap_or_default();
+            if self.mode.is_queryable(&key) {
+                let apigroup = ApiGroup::from_aggregated(key.clone(), g.versions)?;
+                self.groups.insert(key, apigroup);
+            }
+        }
+        Ok(())
     }
 }
 
@@ -167,4 +265,143 @@ impl Discovery {
             .into_iter()
             .find(|res| res.0.kind == gvk.kind)
     }
+
+    /// Lists the objects of every discovered resource that supports [`LIST`](verbs::LIST)
+    ///
+    /// This emulates `kubectl get all`, but across every recommended resource rather than a fixed category.
+    /// Resources are listed one at a time, in alphabetical group order, and all pages of a resource are
+    /// fetched when `lp` sets a `limit`. Every object has its `types` set to the `apiVersion` and `kind`
+    /// of its resource, since these are not included in list responses.
+    ///
+    /// When `namespace` is set, only namespaced resources are listed in that namespace.
+    /// Otherwise, every resource is listed across all namespaces.
+    ///
+    /// Failing to list a resource (for instance when RBAC does not permit it) yields an error,
+    /// after which the stream moves on to the next resource.
+    ///
+    /// ```no_run
+    /// use kube::{Client, api::ListParams, discovery::Discovery, ResourceExt};
+    /// use futures::TryStreamExt;
+    /// #[tokio::main]
+    /// async fn main() -> Result<(), Box<dyn std::error::Error>> {
+    ///     let client = Client::try_default().await?;
+    ///     let discovery = Discovery::new(client).run().await?;
+    ///     let lp = ListParams::default().labels("app=blog");
+    ///     let mut objects = std::pin::pin!(discovery.list_all(Some("default"), &lp));
+    ///     while let Some(obj) = objects.try_next().await? {
+    ///         let types = obj.types.as_ref().unwrap();
+    ///         println!("{} {}: {}", types.api_version, types.kind, obj.name_any());
+    ///     }
+    ///     Ok(())
+    /// }
+    /// ```
+    pub fn list_all(
+        &self,
+        namespace: Option<&str>,
+        lp: &ListParams,
+    ) -> impl Stream<Item = Result<DynamicObject>> + Send + 'static {
+        let apis = self
+            .groups_alphabetical()
+            .into_iter()
+            .flat_map(ApiGroup::recommended_resources)
+            .filter(|(_, caps)| caps.supports_operation(verbs::LIST))
+            .filter_map(|(ar, caps)| {
+                let api = match (namespace, caps.scope) {
+                    (Some(ns), Scope::Namespaced) => Api::namespaced_with(self.client.clone(), ns, &ar),
+                    (Some(_), Scope::Cluster) => return None,
+                    (None, _) => Api::all_with(self.client.clone(), &ar),
+                };
+                Some((ar, api))
+            })
+            .collect::<Vec<_>>();
+        let lp = lp.clone();
+        stream::iter(apis).flat_map(move |(ar, api)| list_pages(ar, api, lp.clone()))
+    }
+}
+
+/// Lists all pages of a resource one at a time, setting the `types` of its objects
+fn list_pages(
+    ar: ApiResource,
+    api: Api<DynamicObject>,
+    lp: ListParams,
+) -> impl Stream<Item = Result<DynamicObject>> + Send + 'static {
+    let types = TypeMeta {
+        api_version: ar.api_version,
+        kind: ar.kind,
+    };
+    stream::try_unfold(Some(lp), move |lp| {
+        let api = api.clone();
+        async move {
+            let Some(lp) = lp else { return Ok(None) };
+            let page = api.list(&lp).await?;
+            let next = page.continue_token().map(|token| lp.continue_token(token));
+            Ok(Some((stream::iter(page.items.into_iter().map(Ok)), next)))
+        }
+    })
+    .try_flatten()
+    .map_ok(move |mut obj| {
+        obj.types = Some(types.clone());
+        obj
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::{list_pages, ApiResource};
+    use crate::{
+        api::{Api, DynamicObject, ListParams},
+        client::Body,
+        Client,
+    };
+    use futures::TryStreamExt;
+    use http::{Request, Response};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use std::pin::pin;
+    use tower_test::mock;
+
+    #[tokio::test]
+    async fn list_pages_follows_continue_tokens() {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let spawned = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            for (continue_token, names, next) in [
+                (None, vec!["a", "b"], "page-2"),
+                (Some("page-2"), vec!["c", "d"], "page-3"),
+                (Some("page-3"), vec!["e"], ""),
+            ] {
+                let (request, send) = handle.next_request().await.expect("service not called");
+                let query = request.uri().query().unwrap_or_default().to_string();
+                assert!(query.contains("limit=2"));
+                match continue_token {
+                    Some(token) => assert!(query.contains(&format!("continue={token}"))),
+                    None => assert!(!query.contains("continue=")),
+                }
+                let items = names
+                    .into_iter()
+                    .map(|name| serde_json::json!({ "metadata": { "name": name } }))
+                    .collect::<Vec<_>>();
+                let list = serde_json::json!({
+                    "metadata": { "continue": next },
+                    "items": items,
+                });
+                send.send_response(
+                    Response::builder()
+                        .body(Body::from(list.to_string().into_bytes()))
+                        .unwrap(),
+                );
+            }
+        });
+
+        let ar = ApiResource::erase::<ConfigMap>(&());
+        let api: Api<DynamicObject> =
+            Api::namespaced_with(Client::new(mock_service, "default"), "default", &ar);
+        let mut objects = pin!(list_pages(ar, api, ListParams::default().limit(2)));
+        let mut names = vec![];
+        while let Some(obj) = objects.try_next().await.unwrap() {
+            assert_eq!(obj.types.unwrap().kind, "ConfigMap");
+            names.push(obj.metadata.name.unwrap());
+        }
+        assert_eq!(names, ["a", "b", "c", "d", "e"]);
+        spawned.await.unwrap();
+    }
 }
@@ -14,9 +14,39 @@ pub enum Error {
     /// It's also used in `WatchEvent` from watch calls.
     ///
     /// It's quite common to get a `410 Gone` when the `resourceVersion` is too old.
+    /// Validation failures list the rejected fields in [`ErrorResponse::causes`].
     #[error("ApiError: {0} ({0:?})")]
     Api(#[source] ErrorResponse),
 
+    /// A server-side apply was rejected because it conflicts with the fields of other field managers
+    ///
+    /// Only returned by [`Api::apply`](crate::Api::apply) and [`Api::apply_forcing`](crate::Api::apply_forcing),
+    /// other methods return these conflicts as an [`Error::Api`].
+    #[error("ApplyConflict: {0}")]
+    ApplyConflict(#[source] kube_core::apply::ApplyConflict),
+
+    /// A deletion was rejected because its preconditions no longer hold (`409 Conflict`)
+    ///
+    /// The object was recreated with another UID, or modified since the resource version of the preconditions.
+    /// Only returned by [`Api::delete`](crate::Api::delete) with preconditions, other conflicts are returned
+    /// as an [`Error::Api`].
+    #[error("PreconditionFailed: {0}")]
+    PreconditionFailed(#[source] ErrorResponse),
+
+    /// An eviction was blocked by a `PodDisruptionBudget` (`429 Too Many Requests`)
+    ///
+    /// The eviction can be retried later, once the disruption budget allows it.
+    /// Only returned by [`Api::evict`](crate::Api::evict), other failures of evictions are returned as an [`Error::Api`].
+    #[error("EvictionBlocked: {0}")]
+    EvictionBlocked(#[source] ErrorResponse),
+
+    /// A request of a dry run [`Client`](crate::Client) cannot be dry run by the apiserver, such as `exec` into pods
+    ///
+    /// The request was not sent, see [`Client::dry_run`](crate::Client::dry_run).
+    #[cfg(feature = "client")]
+    #[error("request cannot be dry run: {0}")]
+    DryRunUnsupported(String),
+
     /// Hyper error
     #[cfg(feature = "client")]
     #[error("HyperError: {0}")]
@@ -41,6 +71,14 @@ pub enum Error {
         protocol_feature: &'static str,
     },
 
+    /// Returned when the config points at a unix socket on a platform without unix sockets
+    #[error("unix sockets are not supported on this platform")]
+    UnixSocketUnsupported,
+
+    /// Returned when the config requires HTTP/2, but the `http2` feature is disabled
+    #[error("HTTP/2 requires the disabled feature \"kube/http2\"")]
+    Http2Disabled,
+
     /// UTF-8 Error
     #[error("UTF-8 Error: {0}")]
     FromUtf8(#[source] std::string::FromUtf8Error),
@@ -0,0 +1,277 @@
+//! Checks of whether requests are authorized, like `kubectl auth can-i`
+use std::fmt::{self, Display};
+
+use k8s_openapi::api::authorization::v1::{
+    NonResourceAttributes, ResourceAttributes, SelfSubjectAccessReview, SelfSubjectAccessReviewSpec,
+    SubjectAccessReview, SubjectAccessReviewSpec, SubjectAccessReviewStatus,
+};
+use thiserror::Error;
+
+use crate::Resource;
+
+/// The request to check in an access review
+///
+/// Converts into a [`SelfSubjectAccessReview`] to check the permissions of the client itself,
+/// or a [`SubjectAccessReview`] to check the permissions of another user.
+///
+/// ```
+/// use k8s_openapi::api::apps::v1::Deployment;
+/// use kube_core::access_review::AccessCheck;
+///
+/// let check = AccessCheck::new("list", "pods/log").namespace("default");
+/// assert_eq!(check.to_string(), "list pods/log in namespace default");
+///
+/// let check = AccessCheck::of::<Deployment>("patch").name("web");
+/// assert_eq!(check.to_string(), "patch deployments.apps/web in all namespaces");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct AccessCheck {
+    resource: Option<ResourceAttributes>,
+    non_resource: Option<NonResourceAttributes>,
+}
+
+impl AccessCheck {
+    /// Check whether `verb` is allowed on `resource` in all namespaces
+    ///
+    /// The `resource` is given like to `kubectl auth can-i`, as `resource[.group][/subresource]`,
+    /// such as `pods`, `deployments.apps` or `pods/log`.
+    pub fn new(verb: &str, resource: &str) -> Self {
+        let (resource, subresource) = match resource.split_once('/') {
+            Some((resource, subresource)) => (resource, Some(subresource.to_string())),
+            None => (resource, None),
+        };
+        let (resource, group) = match resource.split_once('.') {
+            Some((resource, group)) => (resource, group),
+            None => (resource, ""),
+        };
+        Self {
+            resource: Some(ResourceAttributes {
+                verb: Some(verb.to_string()),
+                group: Some(group.to_string()),
+                resource: Some(resource.to_string()),
+                subresource,
+                ..ResourceAttributes::default()
+            }),
+            non_resource: None,
+        }
+    }
+
+    /// Check whether `verb` is allowed on the resource `K` in all namespaces
+    pub fn of<K: Resource<DynamicType = ()>>(verb: &str) -> Self {
+        Self {
+            resource: Some(ResourceAttributes {
+                verb: Some(verb.to_string()),
+                group: Some(K::group(&()).to_string()),
+                version: Some(K::version(&()).to_string()),
+                resource: Some(K::plural(&()).to_string()),
+                ..ResourceAttributes::default()
+            }),
+            non_resource: None,
+        }
+    }
+
+    /// Check whether `verb` is allowed on a non-resource URL `path`, such as `/healthz`
+    pub fn non_resource(verb: &str, path: &str) -> Self {
+        Self {
+            resource: None,
+            non_resource: Some(NonResourceAttributes {
+                verb: Some(verb.to_string()),
+                path: Some(path.to_string()),
+            }),
+        }
+    }
+
+    /// Only check the namespace `namespace`, rather than all namespaces
+    ///
+    /// Has no effect on checks of non-resource URLs.
+    #[must_use]
+    pub fn namespace(mut self, namespace: &str) -> Self {
+        if let Some(resource) = &mut self.resource {
+            resource.namespace = Some(namespace.to_string());
+        }
+        self
+    }
+
+    /// Only check the object named `name`, rather than all objects
+    ///
+    /// Has no effect on checks of non-resource URLs.
+    #[must_use]
+    pub fn name(mut self, name: &str) -> Self {
+        if let Some(resource) = &mut self.resource {
+            resource.name = Some(name.to_string());
+        }
+        self
+    }
+
+    /// Check the subresource `subresource`, such as `status` or `scale`
+    ///
+    /// Has no effect on checks of non-resource URLs.
+    #[must_use]
+    pub fn subresource(mut self, subresource: &str) -> Self {
+        if let Some(resource) = &mut self.resource {
+            resource.subresource = Some(subresource.to_string());
+        }
+        self
+    }
+
+    /// A review of whether the requester itself is allowed to make the request
+    pub fn self_review(&self) -> SelfSubjectAccessReview {
+        SelfSubjectAccessReview {
+            spec: SelfSubjectAccessReviewSpec {
+                resource_attributes: self.resource.clone(),
+                non_resource_attributes: self.non_resource.clone(),
+            },
+            ..SelfSubjectAccessReview::default()
+        }
+    }
+
+    /// A review of whether `user` in `groups` is allowed to make the request
+    pub fn subject_review(&self, user: &str, groups: Vec<String>) -> SubjectAccessReview {
+        SubjectAccessReview {
+            spec: SubjectAccessReviewSpec {
+                user: Some(user.to_string()),
+                groups: Some(groups),
+                resource_attributes: self.resource.clone(),
+                non_resource_attributes: self.non_resource.clone(),
+                ..SubjectAccessReviewSpec::default()
+            },
+            ..SubjectAccessReview::default()
+        }
+    }
+}
+
+impl Display for AccessCheck {
+    /// Describes the request, such as `get deployments.apps/web in namespace default`
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(non_resource) = &self.non_resource {
+            let verb = non_resource.verb.as_deref().unwrap_or_default();
+            let path = non_resource.path.as_deref().unwrap_or_default();
+            return write!(f, "{verb} {path}");
+        }
+        let Some(attrs) = &self.resource else {
+            return Ok(());
+        };
+        write!(
+            f,
+            "{} {}",
+            attrs.verb.as_deref().unwrap_or_default(),
+            attrs.resource.as_deref().unwrap_or_default()
+        )?;
+        if let Some(group) = attrs.group.as_deref().filter(|group| !group.is_empty()) {
+            write!(f, ".{group}")?;
+        }
+        if let Some(subresource) = &attrs.subresource {
+            write!(f, "/{subresource}")?;
+        }
+        if let Some(name) = &attrs.name {
+            write!(f, "/{name}")?;
+        }
+        match &attrs.namespace {
+            Some(namespace) => write!(f, " in namespace {namespace}"),
+            None => write!(f, " in all namespaces"),
+        }
+    }
+}
+
+/// The decision of an access review
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct AccessDecision {
+    /// Whether the request is allowed
+    pub allowed: bool,
+    /// Whether the request is explicitly denied, rather than not allowed by any authorizer
+    pub denied: bool,
+    /// Why the request is allowed or denied, if the authorizer explained it
+    pub reason: Option<String>,
+    /// An error that occurred during authorization, while the decision may still be valid
+    pub evaluation_error: Option<String>,
+}
+
+impl AccessDecision {
+    /// Fails with an actionable [`AccessDenied`] error unless the `check` was allowed
+    ///
+    /// # Errors
+    ///
+    /// Fails if the request is not allowed.
+    pub fn require(&self, check: &AccessCheck) -> Result<(), AccessDenied> {
+        if self.allowed {
+            Ok(())
+        } else {
+            Err(AccessDenied {
+                request: check.to_string(),
+                reason: self.reason.clone().filter(|reason| !reason.is_empty()),
+            })
+        }
+    }
+}
+
+impl From<SubjectAccessReviewStatus> for AccessDecision {
+    fn from(status: SubjectAccessReviewStatus) -> Self {
+        Self {
+            allowed: status.allowed,
+            denied: status.denied.unwrap_or_default(),
+            reason: status.reason,
+            evaluation_error: status.evaluation_error,
+        }
+    }
+}
+
+/// A request is not allowed, see [`AccessDecision::require`]
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub struct AccessDenied {
+    /// The request that is not allowed, such as `get pods in namespace default`
+    pub request: String,
+    /// Why the request is not allowed, if the authorizer explained it
+    pub reason: Option<String>,
+}
+
+impl Display for AccessDenied {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "not allowed to {}", self.request)?;
+        if let Some(reason) = &self.reason {
+            write!(f, ": {reason}")?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{AccessCheck, AccessDecision};
+    use k8s_openapi::api::authorization::v1::SubjectAccessReviewStatus;
+
+    #[test]
+    fn parses_kubectl_resources() {
+        let check = AccessCheck::new("get", "deployments.apps/scale").namespace("prod");
+        let attrs = check.self_review().spec.resource_attributes.unwrap();
+        assert_eq!(attrs.resource.as_deref(), Some("deployments"));
+        assert_eq!(attrs.group.as_deref(), Some("apps"));
+        assert_eq!(attrs.subresource.as_deref(), Some("scale"));
+        assert_eq!(attrs.namespace.as_deref(), Some("prod"));
+        assert_eq!(check.to_string(), "get deployments.apps/scale in namespace prod");
+
+        let check = AccessCheck::non_resource("get", "/metrics");
+        let review = check.subject_review("jane", vec!["devs".into()]);
+        assert!(review.spec.resource_attributes.is_none());
+        assert_eq!(review.spec.user.as_deref(), Some("jane"));
+        assert_eq!(check.to_string(), "get /metrics");
+    }
+
+    #[test]
+    fn denied_decisions_are_actionable() {
+        let check = AccessCheck::new("delete", "secrets").namespace("kube-system");
+        let decision = AccessDecision::from(SubjectAccessReviewStatus {
+            allowed: false,
+            reason: Some("no RBAC policy matched".into()),
+            ..SubjectAccessReviewStatus::default()
+        });
+        assert_eq!(
+            decision.require(&check).unwrap_err().to_string(),
+            "not allowed to delete secrets in namespace kube-system: no RBAC policy matched"
+        );
+        let allowed = AccessDecision {
+            allowed: true,
+            ..AccessDecision::default()
+        };
+        assert!(allowed.require(&check).is_ok());
+    }
+}
@@ -16,7 +16,7 @@ use crate::{
 use std::collections::HashMap;
 
 use k8s_openapi::{api::authentication::v1::UserInfo, apimachinery::pkg::runtime::RawExtension};
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use thiserror::Error;
 
 #[derive(Debug, Error)]
@@ -36,6 +36,67 @@ pub const META_API_VERSION_V1: &str = "admission.k8s.io/v1";
 /// The `api_version` field in [`TypeMeta`] on the v1beta1 version.
 pub const META_API_VERSION_V1BETA1: &str = "admission.k8s.io/v1beta1";
 
+/// A version of the [`AdmissionReview`] API.
+///
+/// Both versions have the same fields, so the admission types handle either of them.
+/// The version of a request is kept, so that the response is sent with the version that the apiserver expects.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum AdmissionVersion {
+    /// `admission.k8s.io/v1`
+    V1,
+    /// `admission.k8s.io/v1beta1`
+    V1Beta1,
+}
+
+impl AdmissionVersion {
+    /// Returns the version with the `apiVersion` `api_version`, if it is supported.
+    pub fn from_api_version(api_version: &str) -> Option<Self> {
+        match api_version {
+            META_API_VERSION_V1 => Some(Self::V1),
+            META_API_VERSION_V1BETA1 => Some(Self::V1Beta1),
+            _ => None,
+        }
+    }
+
+    /// The `apiVersion` of the version.
+    pub fn api_version(self) -> &'static str {
+        match self {
+            Self::V1 => META_API_VERSION_V1,
+            Self::V1Beta1 => META_API_VERSION_V1BETA1,
+        }
+    }
+
+    /// The [`TypeMeta`] of an [`AdmissionReview`] of this version.
+    pub fn types(self) -> TypeMeta {
+        TypeMeta {
+            api_version: self.api_version().to_owned(),
+            kind: META_KIND.to_owned(),
+        }
+    }
+}
+
+/// An [`AdmissionReview`] that could not be read by [`AdmissionReview::from_slice`].
+///
+/// This keeps the version and uid of the review when they could be read, so that
+/// [`InvalidAdmissionReview::into_response`] can still respond in the version the apiserver expects.
+#[derive(Debug, Error)]
+#[error("invalid AdmissionReview: {reason}")]
+pub struct InvalidAdmissionReview {
+    reason: String,
+    types: TypeMeta,
+    uid: String,
+}
+
+impl InvalidAdmissionReview {
+    /// Constructs an invalid [`AdmissionResponse`] for the review, with its version and uid.
+    pub fn into_response(self) -> AdmissionResponse {
+        let mut response = AdmissionResponse::invalid(&self.reason);
+        response.types = self.types;
+        response.uid = self.uid;
+        response
+    }
+}
+
 /// The top level struct used for Serializing and Deserializing AdmissionReview
 /// requests and responses.
 ///
@@ -58,6 +119,57 @@ pub struct AdmissionReview<T: Resource> {
     pub response: Option<AdmissionResponse>,
 }
 
+impl<T: Resource + DeserializeOwned> AdmissionReview<T> {
+    /// Reads the request of an [`AdmissionReview`] of either [`AdmissionVersion`] from a request body.
+    ///
+    /// ```no_run
+    /// use kube::core::{admission::{AdmissionResponse, AdmissionReview}, DynamicObject};
+    ///
+    /// # let body: &[u8] = todo!();
+    /// let response = match AdmissionReview::<DynamicObject>::from_slice(body) {
+    ///     Ok(req) => AdmissionResponse::from(&req),
+    ///     Err(invalid) => invalid.into_response(),
+    /// };
+    /// let review = response.into_review();
+    /// ```
+    pub fn from_slice(body: &[u8]) -> Result<AdmissionRequest<T>, InvalidAdmissionReview> {
+        let value: serde_json::Value =
+            serde_json::from_slice(body).map_err(|err| InvalidAdmissionReview {
+                reason: err.to_string(),
+                types: AdmissionVersion::V1.types(),
+                uid: String::new(),
+            })?;
+        let api_version = value
+            .get("apiVersion")
+            .and_then(|v| v.as_str())
+            .unwrap_or_default();
+        let invalid = |reason: String| InvalidAdmissionReview {
+            reason,
+            types: AdmissionVersion::from_api_version(api_version)
+                .unwrap_or(AdmissionVersion::V1)
+                .types(),
+            uid: value
+                .pointer("/request/uid")
+                .and_then(|uid| uid.as_str())
+                .unwrap_or_default()
+                .to_owned(),
+        };
+
+        if AdmissionVersion::from_api_version(api_version).is_none() {
+            return Err(invalid(format!("unsupported apiVersion {api_version:?}")));
+        }
+        let review: Self = serde_json::from_value(value.clone()).map_err(|err| invalid(err.to_string()))?;
+        review
+            .try_into()
+            .map_err(|err: ConvertAdmissionReviewError| invalid(err.to_string()))
+    }
+
+    /// The version of the review, if it is supported.
+    pub fn version(&self) -> Option<AdmissionVersion> {
+        AdmissionVersion::from_api_version(&self.types.api_version)
+    }
+}
+
 impl<T: Resource> TryInto<AdmissionRequest<T>> for AdmissionReview<T> {
     type Error = ConvertAdmissionReviewError;
 
@@ -330,6 +442,55 @@ impl AdmissionResponse {
         Ok(self)
     }
 
+    /// Add the JSON patch that turns `before` into `after`, such as the object from the request
+    /// and a mutated copy of it.
+    ///
+    /// The patch is left out when the objects are equal.
+    ///
+    /// ```
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::core::{
+    ///     admission::{AdmissionRequest, AdmissionResponse, SerializePatchError},
+    ///     ResourceExt,
+    /// };
+    ///
+    /// fn mutate(req: &AdmissionRequest<Pod>) -> Result<AdmissionResponse, SerializePatchError> {
+    ///     let pod = req.object.clone().unwrap_or_default();
+    ///     let mut mutated = pod.clone();
+    ///     mutated.labels_mut().insert("injected".into(), "true".into());
+    ///     Ok(AdmissionResponse::from(req)
+    ///         .with_patch_from(&pod, &mutated)?
+    ///         .with_warning("the pod was labeled"))
+    /// }
+    /// ```
+    pub fn with_patch_from<T: Serialize>(self, before: &T, after: &T) -> Result<Self, SerializePatchError> {
+        let before = serde_json::to_value(before).map_err(SerializePatchError)?;
+        let after = serde_json::to_value(after).map_err(SerializePatchError)?;
+        let patch = json_patch::diff(&before, &after);
+        if patch.0.is_empty() {
+            return Ok(self);
+        }
+        self.with_patch(patch)
+    }
+
+    /// Add a warning for the client that made the request.
+    ///
+    /// Warnings should be shorter than 120 characters, and the apiserver may truncate long or many warnings.
+    #[must_use]
+    pub fn with_warning(mut self, warning: impl Into<String>) -> Self {
+        self.warnings.get_or_insert_with(Vec::new).push(warning.into());
+        self
+    }
+
+    /// Add an annotation to the audit log entry of the request.
+    ///
+    /// The apiserver prefixes `key` with the name of the webhook.
+    #[must_use]
+    pub fn with_audit_annotation(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.audit_annotations.insert(key.into(), value.into());
+        self
+    }
+
     /// Converts an [`AdmissionResponse`] into a generic [`AdmissionReview`] that
     /// can be used as a webhook response.
     pub fn into_review(self) -> AdmissionReview<DynamicObject> {
@@ -354,8 +515,11 @@ mod test {
     const WEBHOOK_BODY: &str = r#"{"kind":"AdmissionReview","apiVersion":"admission.k8s.io/v1","request":{"uid":"0c9a8d74-9cb7-44dd-b98e-09fd62def2f4","kind":{"group":"","version":"v1","kind":"Pod"},"resource":{"group":"","version":"v1","resource":"pods"},"requestKind":{"group":"","version":"v1","kind":"Pod"},"requestResource":{"group":"","version":"v1","resource":"pods"},"name":"echo-pod","namespace":"colin-coder","operation":"CREATE","userInfo":{"username":"colin@coder.com","groups":["system:authenticated"],"extra":{"iam.gke.io/user-assertion":["REDACTED"],"user-assertion.cloud.google.com":["REDACTED"]}},"object":{"kind":"Pod","apiVersion":"v1","metadata":{"name":"echo-pod","namespace":"colin-coder","creationTimestamp":null,"labels":{"app":"echo-server"},"annotations":{"kubectl.kubernetes.io/last-applied-configuration":"{\"apiVersion\":\"v1\",\"kind\":\"Pod\",\"metadata\":{\"annotations\":{},\"labels\":{\"app\":\"echo-server\"},\"name\":\"echo-pod\",\"namespace\":\"colin-coder\"},\"spec\":{\"containers\":[{\"image\":\"jmalloc/echo-server\",\"name\":\"echo-server\",\"ports\":[{\"containerPort\":8080,\"name\":\"http-port\"}]}]}}\n"},"managedFields":[{"manager":"kubectl","operation":"Update","apiVersion":"v1","time":"2021-03-29T23:02:16Z","fieldsType":"FieldsV1","fieldsV1":{"f:metadata":{"f:annotations":{".":{},"f:kubectl.kubernetes.io/last-applied-configuration":{}},"f:labels":{".":{},"f:app":{}}},"f:spec":{"f:containers":{"k:{\"name\":\"echo-server\"}":{".":{},"f:image":{},"f:imagePullPolicy":{},"f:name":{},"f:ports":{".":{},"k:{\"containerPort\":8080,\"protocol\":\"TCP\"}":{".":{},"f:containerPort":{},"f:name":{},"f:protocol":{}}},"f:resources":{},"f:terminationMessagePath":{},"f:terminationMessagePolicy":{}}},"f:dnsPolicy":{},"f:enableServiceLinks":{},"f:restartPolicy":{},"f:schedulerName":{},"f:securityContext":{},"f:terminationGracePeriodSeconds":{}}}}]},"spec":{"volumes":[{"name":"default-token-rxbqq","secret":{"secretName":"default-token-rxbqq"}}],"containers":[{"name":"echo-server","image":"jmalloc/echo-server","ports":[{"name":"http-port","containerPort":8080,"protocol":"TCP"}],"resources":{},"volumeMounts":[{"name":"default-token-rxbqq","readOnly":true,"mountPath":"/var/run/secrets/kubernetes.io/serviceaccount"}],"terminationMessagePath":"/dev/termination-log","terminationMessagePolicy":"File","imagePullPolicy":"Always"}],"restartPolicy":"Always","terminationGracePeriodSeconds":30,"dnsPolicy":"ClusterFirst","serviceAccountName":"default","serviceAccount":"default","securityContext":{},"schedulerName":"default-scheduler","tolerations":[{"key":"node.kubernetes.io/not-ready","operator":"Exists","effect":"NoExecute","tolerationSeconds":300},{"key":"node.kubernetes.io/unreachable","operator":"Exists","effect":"NoExecute","tolerationSeconds":300}],"priority":0,"enableServiceLinks":true},"status":{}},"oldObject":null,"dryRun":false,"options":{"kind":"CreateOptions","apiVersion":"meta.k8s.io/v1"}}}"#;
 
     use crate::{
-        admission::{AdmissionResponse, AdmissionReview, ConvertAdmissionReviewError},
-        DynamicObject,
+        admission::{
+            AdmissionRequest, AdmissionResponse, AdmissionReview, AdmissionVersion,
+            ConvertAdmissionReviewError,
+        },
+        DynamicObject, ResourceExt,
     };
 
     #[test]
@@ -376,4 +540,56 @@ mod test {
         assert_eq!(&rev_typ, &res.types);
         Ok(())
     }
+
+    #[test]
+    fn review_versions_are_echoed() {
+        let v1beta1 = WEBHOOK_BODY.replace("admission.k8s.io/v1", "admission.k8s.io/v1beta1");
+        let req = AdmissionReview::<DynamicObject>::from_slice(v1beta1.as_bytes()).unwrap();
+        let review = AdmissionResponse::from(&req).into_review();
+        assert_eq!(review.version(), Some(AdmissionVersion::V1Beta1));
+        assert_eq!(
+            review.response.unwrap().uid,
+            "0c9a8d74-9cb7-44dd-b98e-09fd62def2f4"
+        );
+
+        let malformed = br#"{"apiVersion": "admission.k8s.io/v1", "kind": "AdmissionReview",
+            "request": {"uid": "1234", "operation": "CREATE"}}"#;
+        let invalid = AdmissionReview::<DynamicObject>::from_slice(malformed).unwrap_err();
+        let res = invalid.into_response();
+        assert!(!res.allowed);
+        assert_eq!(res.uid, "1234");
+        assert_eq!(res.types, AdmissionVersion::V1.types());
+
+        let unsupported = br#"{"apiVersion": "admission.k8s.io/v2", "kind": "AdmissionReview"}"#;
+        let invalid = AdmissionReview::<DynamicObject>::from_slice(unsupported).unwrap_err();
+        assert_eq!(
+            invalid.to_string(),
+            r#"invalid AdmissionReview: unsupported apiVersion "admission.k8s.io/v2""#
+        );
+    }
+
+    #[test]
+    fn patch_is_computed_from_mutation() {
+        let rev = serde_json::from_str::<AdmissionReview<DynamicObject>>(WEBHOOK_BODY).unwrap();
+        let req: AdmissionRequest<DynamicObject> = rev.try_into().unwrap();
+        let pod = req.object.clone().unwrap();
+        let mut mutated = pod.clone();
+        mutated.labels_mut().insert("injected".into(), "true".into());
+
+        let res = AdmissionResponse::from(&req)
+            .with_patch_from(&pod, &mutated)
+            .unwrap()
+            .with_warning("labeled")
+            .with_audit_annotation("mutation", "label");
+        let patch: serde_json::Value = serde_json::from_slice(res.patch.as_deref().unwrap()).unwrap();
+        assert_eq!(
+            patch,
+            serde_json::json!([{ "op": "add", "path": "/metadata/labels/injected", "value": "true" }])
+        );
+        assert_eq!(res.warnings, Some(vec!["labeled".to_owned()]));
+        assert_eq!(res.audit_annotations["mutation"], "label");
+
+        let unchanged = AdmissionResponse::from(&req).with_patch_from(&pod, &pod).unwrap();
+        assert_eq!(unchanged.patch, None);
+    }
 }
@@ -0,0 +1,333 @@
+//! Typed partial objects and conflict errors for server-side apply.
+use std::{fmt, marker::PhantomData};
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    error::ErrorResponse,
+    metadata::{ObjectMeta, TypeMeta},
+    object::NotUsed,
+    resource::Resource,
+};
+
+/// A partial object of kind `K`, used as the body of a server-side apply patch
+///
+/// Server-side apply takes ownership of every field that is sent, so an apply patch should only contain
+/// the fields that the field manager has opinions about. This is a typed alternative to building
+/// such patches with `serde_json::json!`, where the `spec` and `status` are partial types whose
+/// unset fields are left out, such as the types generated by the
+/// [`ApplyConfiguration`](https://docs.rs/kube/*/kube/derive.ApplyConfiguration.html) derive.
+///
+/// ```
+/// # use k8s_openapi::api::core::v1::ConfigMap;
+/// use kube::core::ApplyConfiguration;
+///
+/// let apply = ApplyConfiguration::<ConfigMap>::new("my-config")
+///     .within("default")
+///     .label("app.kubernetes.io/managed-by", "my-controller");
+/// assert_eq!(serde_json::to_value(&apply).unwrap(), serde_json::json!({
+///     "apiVersion": "v1",
+///     "kind": "ConfigMap",
+///     "metadata": {
+///         "name": "my-config",
+///         "namespace": "default",
+///         "labels": { "app.kubernetes.io/managed-by": "my-controller" },
+///     },
+/// }));
+/// ```
+///
+/// Note that the types of `k8s-openapi` have required fields that would always be sent,
+/// and are therefore not suitable as partial specs.
+#[derive(Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase")]
+pub struct ApplyConfiguration<K, P = NotUsed, U = NotUsed> {
+    /// The type fields, always present
+    #[serde(flatten)]
+    pub types: TypeMeta,
+
+    /// Metadata to apply, only the fields that are set are sent
+    pub metadata: ObjectMeta,
+
+    /// The partial spec to apply, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub spec: Option<P>,
+
+    /// The partial status to apply, if any
+    ///
+    /// Only used when applying to the `status` subresource.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<U>,
+
+    /// Type information for static dispatch
+    #[serde(skip)]
+    pub _phantom: PhantomData<K>,
+}
+
+impl<K, P, U> ApplyConfiguration<K, P, U>
+where
+    K: Resource<DynamicType = ()>,
+{
+    /// Create an apply configuration for the object named `name`, with nothing else set
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            types: TypeMeta::resource::<K>(),
+            metadata: ObjectMeta {
+                name: Some(name.into()),
+                ..ObjectMeta::default()
+            },
+            spec: None,
+            status: None,
+            _phantom: PhantomData,
+        }
+    }
+}
+
+impl<K, P, U> ApplyConfiguration<K, P, U> {
+    /// Set the namespace of the object
+    #[must_use]
+    pub fn within(mut self, namespace: impl Into<String>) -> Self {
+        self.metadata.namespace = Some(namespace.into());
+        self
+    }
+
+    /// Add a label to the object
+    #[must_use]
+    pub fn label(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata
+            .labels
+            .get_or_insert_with(Default::default)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Add an annotation to the object
+    #[must_use]
+    pub fn annotation(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.metadata
+            .annotations
+            .get_or_insert_with(Default::default)
+            .insert(key.into(), value.into());
+        self
+    }
+
+    /// Add an owner reference to the object
+    ///
+    /// See [`Resource::controller_owner_ref`] for creating one from an owning object.
+    #[must_use]
+    pub fn owner_reference(mut self, owner: OwnerReference) -> Self {
+        self.metadata
+            .owner_references
+            .get_or_insert_with(Default::default)
+            .push(owner);
+        self
+    }
+
+    /// Set the partial spec to apply
+    #[must_use]
+    pub fn spec(mut self, spec: P) -> Self {
+        self.spec = Some(spec);
+        self
+    }
+
+    /// Set the partial status to apply
+    #[must_use]
+    pub fn status(mut self, status: U) -> Self {
+        self.status = Some(status);
+        self
+    }
+}
+
+/// A field that is already owned by another field manager, see [`ApplyConflict`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct FieldConflict {
+    /// The field manager that owns the field
+    pub manager: String,
+    /// The path of the field, such as `.spec.replicas`
+    pub field: String,
+}
+
+/// A server-side apply that was rejected because it changes fields owned by other field managers
+///
+/// The apiserver only reports these conflicts as a message in a `409 Conflict` response,
+/// which [`ApplyConflict::from_response`] parses.
+///
+/// ```
+/// use kube_core::{apply::ApplyConflict, ErrorResponse};
+/// let response = ErrorResponse {
+///     status: "Failure".into(),
+///     message: "Apply failed with 1 conflict: conflict with \"kubectl\" using apps/v1: .spec.replicas".into(),
+///     reason: "Conflict".into(),
+///     code: 409,
+///     details: None,
+/// };
+/// let conflict = ApplyConflict::from_response(&response).unwrap();
+/// assert_eq!(conflict.managers(), vec!["kubectl"]);
+/// assert!(conflict.is_within(&[".spec.replicas"]));
+/// ```
+#[derive(Error, Clone, Debug, PartialEq, Eq)]
+pub struct ApplyConflict {
+    /// The conflicting fields, along with their current managers
+    pub conflicts: Vec<FieldConflict>,
+}
+
+impl ApplyConflict {
+    /// Parses the conflicts of a rejected server-side apply, if `response` is one
+    pub fn from_response(response: &ErrorResponse) -> Option<Self> {
+        if response.code != 409 {
+            return None;
+        }
+        let (_, conflicts) = response
+            .message
+            .strip_prefix("Apply failed with ")?
+            .split_once(": ")?;
+
+        let mut manager = None;
+        let mut parsed = Vec::new();
+        for line in conflicts.lines() {
+            if let Some(field) = line.strip_prefix("- ") {
+                parsed.push(FieldConflict {
+                    manager: manager.clone()?,
+                    field: field.to_string(),
+                });
+                continue;
+            }
+            // Either `conflict with "manager" using v1: .field` or `conflicts with "manager" using v1:`
+            let rest = line
+                .strip_prefix("conflict with \"")
+                .or_else(|| line.strip_prefix("conflicts with \""))?;
+            let (name, rest) = rest.split_once('"')?;
+            manager = Some(name.to_string());
+            let (_, field) = rest.split_once(':')?;
+            let field = field.trim();
+            if !field.is_empty() {
+                parsed.push(FieldConflict {
+                    manager: name.to_string(),
+                    field: field.to_string(),
+                });
+            }
+        }
+        (!parsed.is_empty()).then_some(Self { conflicts: parsed })
+    }
+
+    /// The distinct managers of the conflicting fields
+    pub fn managers(&self) -> Vec<&str> {
+        let mut managers = self
+            .conflicts
+            .iter()
+            .map(|conflict| conflict.manager.as_str())
+            .collect::<Vec<_>>();
+        managers.sort_unstable();
+        managers.dedup();
+        managers
+    }
+
+    /// Whether every conflicting field is one of `fields`, or nested within one of them
+    pub fn is_within(&self, fields: &[&str]) -> bool {
+        self.conflicts.iter().all(|conflict| {
+            fields.iter().any(|field| {
+                conflict.field.strip_prefix(field).is_some_and(|nested| {
+                    nested.is_empty() || nested.starts_with('.') || nested.starts_with('[')
+                })
+            })
+        })
+    }
+}
+
+impl fmt::Display for ApplyConflict {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "apply conflicts with fields of {}", self.managers().join(", "))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{ApplyConfiguration, ApplyConflict, FieldConflict};
+    use crate::ErrorResponse;
+    use k8s_openapi::api::apps::v1::Deployment;
+    use serde::Serialize;
+    use serde_json::json;
+
+    #[derive(Serialize, Clone, Debug, Default)]
+    #[serde(rename_all = "camelCase")]
+    struct DeploymentSpecApply {
+        #[serde(skip_serializing_if = "Option::is_none")]
+        replicas: Option<i32>,
+        #[serde(skip_serializing_if = "Option::is_none")]
+        min_ready_seconds: Option<i32>,
+    }
+
+    #[test]
+    fn serializes_only_set_fields() {
+        let apply = ApplyConfiguration::<Deployment, _>::new("web")
+            .within("apps")
+            .annotation("a", "b")
+            .spec(DeploymentSpecApply {
+                replicas: Some(3),
+                ..Default::default()
+            });
+        assert_eq!(
+            serde_json::to_value(&apply).unwrap(),
+            json!({
+                "apiVersion": "apps/v1",
+                "kind": "Deployment",
+                "metadata": {
+                    "name": "web",
+                    "namespace": "apps",
+                    "annotations": { "a": "b" },
+                },
+                "spec": { "replicas": 3 },
+            })
+        );
+    }
+
+    fn conflict_response(message: &str) -> ErrorResponse {
+        ErrorResponse {
+            status: "Failure".into(),
+            message: message.into(),
+            reason: "Conflict".into(),
+            code: 409,
+            details: None,
+        }
+    }
+
+    #[test]
+    fn parses_apply_conflicts() {
+        let single = conflict_response(
+            r#"Apply failed with 1 conflict: conflict with "kubectl-client-side-apply" using apps/v1: .spec.replicas"#,
+        );
+        assert_eq!(ApplyConflict::from_response(&single).unwrap().conflicts, vec![
+            FieldConflict {
+                manager: "kubectl-client-side-apply".into(),
+                field: ".spec.replicas".into(),
+            }
+        ]);
+
+        let multiple = conflict_response(
+            "Apply failed with 3 conflicts: conflicts with \"helm\" using apps/v1:\n- .spec.replicas\n\
+             - .spec.template.spec.containers[name=\"web\"].image\nconflicts with \"kubectl\":\n- .metadata.labels.app",
+        );
+        let conflict = ApplyConflict::from_response(&multiple).unwrap();
+        assert_eq!(conflict.conflicts.len(), 3);
+        assert_eq!(conflict.managers(), vec!["helm", "kubectl"]);
+        assert_eq!(
+            conflict.to_string(),
+            "apply conflicts with fields of helm, kubectl"
+        );
+        assert!(conflict.is_within(&[".spec", ".metadata.labels"]));
+        assert!(!conflict.is_within(&[".spec.replicas", ".metadata.labels"]));
+        assert!(!conflict.is_within(&[".spec.rep", ".spec.template", ".metadata"]));
+    }
+
+    #[test]
+    fn ignores_other_conflicts() {
+        let modified = conflict_response(
+            "Operation cannot be fulfilled on deployments.apps \"web\": the object has been modified",
+        );
+        assert_eq!(ApplyConflict::from_response(&modified), None);
+        let mut not_found = conflict_response("Apply failed with 1 conflict: conflict with \"a\": .spec");
+        not_found.code = 404;
+        assert_eq!(ApplyConflict::from_response(&not_found), None);
+    }
+}
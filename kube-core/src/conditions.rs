@@ -0,0 +1,371 @@
+//! Helpers for maintaining `.status.conditions`. See [`Conditions`].
+use std::{fmt, ops::Deref};
+
+use chrono::Utc;
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{Condition, Time};
+use serde::{Deserialize, Serialize};
+
+use crate::Resource;
+
+/// The status of a [`Condition`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum ConditionStatus {
+    /// The condition holds
+    True,
+    /// The condition does not hold
+    False,
+    /// It is not known whether the condition holds
+    Unknown,
+}
+
+impl ConditionStatus {
+    /// The string representation used by [`Condition::status`]
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ConditionStatus::True => "True",
+            ConditionStatus::False => "False",
+            ConditionStatus::Unknown => "Unknown",
+        }
+    }
+
+    /// Parses [`Condition::status`], treating unrecognized values as [`ConditionStatus::Unknown`]
+    pub fn parse(status: &str) -> Self {
+        match status {
+            "True" => ConditionStatus::True,
+            "False" => ConditionStatus::False,
+            _ => ConditionStatus::Unknown,
+        }
+    }
+}
+
+impl From<bool> for ConditionStatus {
+    fn from(status: bool) -> Self {
+        if status {
+            ConditionStatus::True
+        } else {
+            ConditionStatus::False
+        }
+    }
+}
+
+impl fmt::Display for ConditionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A fixed set of condition types, usually derived with `#[derive(ConditionSet)]` on a fieldless enum
+///
+/// The derive also generates an extension trait for [`Conditions`] with typed accessors,
+/// such as `is_ready()` and `set_ready(status, reason, message)` for a `Ready` variant.
+pub trait ConditionSet: Copy + 'static {
+    /// Every condition type in the set
+    const ALL: &'static [Self];
+
+    /// The `type` of the condition
+    fn as_str(&self) -> &'static str;
+
+    /// Creates a [`NewCondition`] of this type
+    fn condition(self, status: impl Into<ConditionStatus>, reason: impl Into<String>) -> NewCondition {
+        NewCondition::new(self.as_str(), status, reason)
+    }
+}
+
+/// The desired state of a condition, to be applied with [`Conditions::set`]
+///
+/// ```
+/// use kube_core::conditions::NewCondition;
+/// let ready = NewCondition::new("Ready", true, "AllReplicasReady").message("3/3 replicas are ready");
+/// ```
+#[derive(Clone, Debug, PartialEq)]
+pub struct NewCondition {
+    type_: String,
+    status: ConditionStatus,
+    reason: String,
+    message: String,
+    observed_generation: Option<i64>,
+}
+
+impl NewCondition {
+    /// Creates a condition of type `type_`
+    ///
+    /// The `reason` should be a short `CamelCase` identifier, since the apiserver rejects empty reasons.
+    pub fn new(
+        type_: impl Into<String>,
+        status: impl Into<ConditionStatus>,
+        reason: impl Into<String>,
+    ) -> Self {
+        Self {
+            type_: type_.into(),
+            status: status.into(),
+            reason: reason.into(),
+            message: String::new(),
+            observed_generation: None,
+        }
+    }
+
+    /// Sets a human readable message with details about the condition
+    #[must_use]
+    pub fn message(mut self, message: impl Into<String>) -> Self {
+        self.message = message.into();
+        self
+    }
+
+    /// Sets the `.metadata.generation` that the condition was computed from
+    #[must_use]
+    pub fn observed_generation(mut self, generation: Option<i64>) -> Self {
+        self.observed_generation = generation;
+        self
+    }
+
+    /// Stamps the current `.metadata.generation` of `obj` as the observed generation
+    #[must_use]
+    pub fn for_object<K: Resource>(self, obj: &K) -> Self {
+        let generation = obj.meta().generation;
+        self.observed_generation(generation)
+    }
+}
+
+/// A list of [`Condition`]s with set and update semantics
+///
+/// This (de)serializes as a plain list, so it can be used for the `conditions` of a status directly.
+/// Like `meta.SetStatusCondition` in apimachinery, [`Conditions::set`] only bumps the
+/// `lastTransitionTime` of a condition when its status changes, and reports whether anything changed,
+/// so that controllers only need to patch the status when it is out of date.
+///
+/// ```
+/// use kube_core::conditions::{Conditions, NewCondition};
+/// let mut conditions = Conditions::default();
+/// assert!(conditions.set(NewCondition::new("Ready", false, "Pending")));
+/// assert!(conditions.set(NewCondition::new("Ready", true, "Available")));
+/// // Setting the same condition again is a no-op
+/// assert!(!conditions.set(NewCondition::new("Ready", true, "Available")));
+/// assert!(conditions.is_true("Ready"));
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct Conditions(Vec<Condition>);
+
+impl Conditions {
+    /// Returns the condition of type `type_`, if any
+    pub fn get(&self, type_: &str) -> Option<&Condition> {
+        self.0.iter().find(|c| c.type_ == type_)
+    }
+
+    /// Returns the status of the condition of type `type_`, if any
+    pub fn status(&self, type_: &str) -> Option<ConditionStatus> {
+        self.get(type_).map(|c| ConditionStatus::parse(&c.status))
+    }
+
+    /// Whether the condition of type `type_` is present and `True`
+    pub fn is_true(&self, type_: &str) -> bool {
+        self.status(type_) == Some(ConditionStatus::True)
+    }
+
+    /// Whether the condition of type `type_` is present and `False`
+    pub fn is_false(&self, type_: &str) -> bool {
+        self.status(type_) == Some(ConditionStatus::False)
+    }
+
+    /// Adds or updates the condition of the same type, returning whether anything changed
+    ///
+    /// The `lastTransitionTime` is set to now when the condition is added or its status changes,
+    /// while the reason, message, and observed generation are only overwritten when they differ.
+    pub fn set(&mut self, condition: NewCondition) -> bool {
+        self.set_at(condition, Time(Utc::now()))
+    }
+
+    fn set_at(&mut self, condition: NewCondition, now: Time) -> bool {
+        let NewCondition {
+            type_,
+            status,
+            reason,
+            message,
+            observed_generation,
+        } = condition;
+        let Some(existing) = self.0.iter_mut().find(|c| c.type_ == type_) else {
+            self.0.push(Condition {
+                last_transition_time: now,
+                message,
+                observed_generation,
+                reason,
+                status: status.as_str().to_string(),
+                type_,
+            });
+            return true;
+        };
+
+        let mut changed = false;
+        if existing.status != status.as_str() {
+            existing.status = status.as_str().to_string();
+            existing.last_transition_time = now;
+            changed = true;
+        }
+        if existing.reason != reason {
+            existing.reason = reason;
+            changed = true;
+        }
+        if existing.message != message {
+            existing.message = message;
+            changed = true;
+        }
+        if existing.observed_generation != observed_generation {
+            existing.observed_generation = observed_generation;
+            changed = true;
+        }
+        changed
+    }
+
+    /// Removes the condition of type `type_`, returning it if it was present
+    pub fn remove(&mut self, type_: &str) -> Option<Condition> {
+        let index = self.0.iter().position(|c| c.type_ == type_)?;
+        Some(self.0.remove(index))
+    }
+
+    /// Returns the underlying list of conditions
+    pub fn into_inner(self) -> Vec<Condition> {
+        self.0
+    }
+}
+
+impl Deref for Conditions {
+    type Target = [Condition];
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl From<Vec<Condition>> for Conditions {
+    fn from(conditions: Vec<Condition>) -> Self {
+        Self(conditions)
+    }
+}
+
+impl From<Conditions> for Vec<Condition> {
+    fn from(conditions: Conditions) -> Self {
+        conditions.0
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Conditions {
+    fn schema_name() -> String {
+        "Conditions".to_owned()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        conditions_schema(None)
+    }
+}
+
+/// Generates the schema of [`Conditions`] that only allows the condition types of `C`
+///
+/// This is meant to be used as `#[schemars(schema_with = "schema_for::<MyCondition>")]`.
+#[cfg_attr(docsrs, doc(cfg(feature = "schema")))]
+#[cfg(feature = "schema")]
+pub fn schema_for<C: ConditionSet>(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+    conditions_schema(Some(C::ALL.iter().map(ConditionSet::as_str).collect()))
+}
+
+// Mirrors the schema of `meta/v1.Condition`, since `k8s-openapi` only implements `JsonSchema` behind a feature
+#[cfg(feature = "schema")]
+fn conditions_schema(types: Option<Vec<&str>>) -> schemars::schema::Schema {
+    let mut type_ = serde_json::json!({ "type": "string" });
+    if let Some(types) = types {
+        type_["enum"] = types.into();
+    }
+    serde_json::from_value(serde_json::json!({
+        "type": "array",
+        "x-kubernetes-list-type": "map",
+        "x-kubernetes-list-map-keys": ["type"],
+        "items": {
+            "type": "object",
+            "properties": {
+                "lastTransitionTime": { "type": "string", "format": "date-time" },
+                "message": { "type": "string" },
+                "observedGeneration": { "type": "integer", "format": "int64" },
+                "reason": { "type": "string" },
+                "status": { "type": "string", "enum": ["True", "False", "Unknown"] },
+                "type": type_,
+            },
+            "required": ["lastTransitionTime", "message", "reason", "status", "type"],
+        },
+    }))
+    .expect("valid condition schema")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConditionStatus, Conditions, NewCondition};
+    use chrono::{TimeZone, Utc};
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+
+    fn time(secs: i64) -> Time {
+        Time(Utc.timestamp_opt(secs, 0).unwrap())
+    }
+
+    #[test]
+    fn set_only_transitions_on_status_changes() {
+        let mut conditions = Conditions::default();
+        let ready = NewCondition::new("Ready", false, "Pending").observed_generation(Some(1));
+        assert!(conditions.set_at(ready.clone(), time(1)));
+        assert!(!conditions.set_at(ready, time(2)));
+        assert_eq!(conditions.get("Ready").unwrap().last_transition_time, time(1));
+
+        // Message and generation changes are applied without a transition
+        let ready = NewCondition::new("Ready", false, "Pending")
+            .message("waiting for replicas")
+            .observed_generation(Some(2));
+        assert!(conditions.set_at(ready, time(3)));
+        let condition = conditions.get("Ready").unwrap();
+        assert_eq!(condition.last_transition_time, time(1));
+        assert_eq!(condition.message, "waiting for replicas");
+        assert_eq!(condition.observed_generation, Some(2));
+
+        assert!(conditions.set_at(NewCondition::new("Ready", true, "Available"), time(4)));
+        let condition = conditions.get("Ready").unwrap();
+        assert_eq!(condition.last_transition_time, time(4));
+        assert_eq!(condition.status, "True");
+        assert_eq!(condition.reason, "Available");
+        assert!(conditions.is_true("Ready"));
+        assert_eq!(conditions.len(), 1);
+    }
+
+    #[test]
+    fn conditions_are_kept_per_type() {
+        let mut conditions = Conditions::default();
+        conditions.set(NewCondition::new("Ready", true, "Available"));
+        conditions.set(NewCondition::new("Degraded", ConditionStatus::Unknown, "Probing"));
+        assert_eq!(conditions.status("Degraded"), Some(ConditionStatus::Unknown));
+        assert_eq!(conditions.status("Missing"), None);
+        assert!(!conditions.is_false("Ready"));
+
+        assert_eq!(conditions.remove("Degraded").unwrap().reason, "Probing");
+        assert!(conditions.remove("Degraded").is_none());
+        assert_eq!(conditions.len(), 1);
+    }
+
+    #[test]
+    fn serializes_as_list() {
+        let mut conditions = Conditions::default();
+        conditions.set_at(NewCondition::new("Ready", true, "Available"), time(0));
+        let json = serde_json::to_value(&conditions).unwrap();
+        assert_eq!(
+            json,
+            serde_json::json!([{
+                "lastTransitionTime": "1970-01-01T00:00:00Z",
+                "message": "",
+                "reason": "Available",
+                "status": "True",
+                "type": "Ready",
+            }])
+        );
+        let parsed: Conditions = serde_json::from_value(json).unwrap();
+        assert_eq!(parsed, conditions);
+    }
+}
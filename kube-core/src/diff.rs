@@ -0,0 +1,479 @@
+//! Comparing desired and live objects to detect drift. See [`Diff`].
+use std::fmt;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::ManagedFieldsEntry;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{
+    managed_fields::{is_within, ManagedFields},
+    Quantity,
+};
+
+/// Fields that the apiserver populates, which are ignored by the [`Default`] [`DiffParams`]
+pub const DEFAULT_IGNORED_FIELDS: &[&str] = &[
+    ".status",
+    ".metadata.creationTimestamp",
+    ".metadata.generation",
+    ".metadata.managedFields",
+    ".metadata.resourceVersion",
+    ".metadata.selfLink",
+    ".metadata.uid",
+];
+
+/// Options for computing a [`Diff`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct DiffParams {
+    /// Fields that are not compared, along with the fields nested within them
+    ///
+    /// Paths use the same format as [`ManagedFields`], such as `.spec.replicas` or
+    /// `.spec.containers[name="web"].image`.
+    pub ignored_fields: Vec<String>,
+
+    /// The field manager that applies the desired object
+    ///
+    /// Fields that this manager owns in the live object, but that are not in the desired object,
+    /// are reported as [`Change::Extra`], because applying the desired object would remove them.
+    pub field_manager: Option<String>,
+}
+
+impl Default for DiffParams {
+    fn default() -> Self {
+        Self {
+            ignored_fields: DEFAULT_IGNORED_FIELDS
+                .iter()
+                .map(|&field| field.to_owned())
+                .collect(),
+            field_manager: None,
+        }
+    }
+}
+
+impl DiffParams {
+    /// Ignore a field, along with the fields nested within it
+    #[must_use]
+    pub fn ignore(mut self, field: impl Into<String>) -> Self {
+        self.ignored_fields.push(field.into());
+        self
+    }
+
+    /// Report the fields that `manager` owns but no longer desires as [`Change::Extra`]
+    #[must_use]
+    pub fn field_manager(mut self, manager: impl Into<String>) -> Self {
+        self.field_manager = Some(manager.into());
+        self
+    }
+}
+
+/// A single difference between a desired and a live object
+#[derive(Clone, Debug, PartialEq)]
+pub enum Change {
+    /// A desired field that the live object does not have
+    Missing {
+        /// The path of the field
+        path: String,
+        /// The desired value
+        desired: Value,
+    },
+
+    /// A field with a different value in the live object
+    Changed {
+        /// The path of the field
+        path: String,
+        /// The desired value
+        desired: Value,
+        /// The live value
+        live: Value,
+    },
+
+    /// A field of the live object that is owned by the [`DiffParams::field_manager`], but no longer desired
+    Extra {
+        /// The path of the field
+        path: String,
+        /// The live value
+        live: Value,
+    },
+}
+
+impl Change {
+    /// The path of the changed field, such as `.spec.containers[name="web"].image`
+    pub fn path(&self) -> &str {
+        match self {
+            Self::Missing { path, .. } | Self::Changed { path, .. } | Self::Extra { path, .. } => path,
+        }
+    }
+}
+
+/// The semantic differences between a desired object and the live object in the cluster
+///
+/// Only the fields of the desired object are compared, so fields that the apiserver or other
+/// controllers fill in, such as defaults, are not reported as drift. Fields that are only in the
+/// live object are reported when they are owned by the [`DiffParams::field_manager`].
+///
+/// Lists of objects with a `name` are compared by name, other lists by index. Quantities in
+/// `requests`, `limits` and `hard`, such as `0.5` and `500m`, are compared by value.
+///
+/// ```
+/// use kube_core::diff::{Diff, DiffParams};
+/// use serde_json::json;
+///
+/// let desired = json!({ "spec": { "replicas": 3 } });
+/// let live = json!({ "spec": { "replicas": 2, "revisionHistoryLimit": 10 }, "status": { "replicas": 2 } });
+/// let diff = Diff::new(&desired, &live, &DiffParams::default())?;
+/// assert_eq!(diff.to_string(), "~ .spec.replicas: 2 -> 3\n");
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct Diff {
+    changes: Vec<Change>,
+}
+
+impl Diff {
+    /// Compares the `desired` object with the `live` object
+    ///
+    /// Fails if either object cannot be serialized, or if the managed fields of the `live` object are invalid.
+    pub fn new<K: Serialize>(desired: &K, live: &K, params: &DiffParams) -> Result<Self, serde_json::Error> {
+        let desired = serde_json::to_value(desired)?;
+        let live = serde_json::to_value(live)?;
+        let mut differ = Differ {
+            params,
+            changes: Vec::new(),
+        };
+        differ.compare("", &desired, &live);
+
+        if let Some(manager) = &params.field_manager {
+            let entries: Vec<ManagedFieldsEntry> = match live.pointer("/metadata/managedFields") {
+                Some(entries) => serde_json::from_value(entries.clone())?,
+                None => Vec::new(),
+            };
+            let managed_fields = ManagedFields::from_entries(&entries);
+            let mut removed: Vec<&str> = Vec::new();
+            for field in managed_fields.fields_of(manager) {
+                if field.is_empty()
+                    || differ.ignored(field)
+                    || removed.iter().any(|parent| is_within(field, parent))
+                    || lookup(&desired, field).is_some_and(|desired| !desired.is_null())
+                {
+                    continue;
+                }
+                if let Some(live) = lookup(&live, field) {
+                    differ.changes.push(Change::Extra {
+                        path: field.to_owned(),
+                        live: live.clone(),
+                    });
+                    removed.push(field);
+                }
+            }
+        }
+        Ok(Self {
+            changes: differ.changes,
+        })
+    }
+
+    /// The changes, in the order of the fields
+    pub fn changes(&self) -> &[Change] {
+        &self.changes
+    }
+
+    /// Whether the live object matches the desired object
+    pub fn is_empty(&self) -> bool {
+        self.changes.is_empty()
+    }
+}
+
+/// Formats the changes like a plan, with one line per change
+///
+/// Missing fields start with `+`, changed fields with `~` and extra fields with `-`.
+impl fmt::Display for Diff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for change in &self.changes {
+            match change {
+                Change::Missing { path, desired } => writeln!(f, "+ {path}: {desired}")?,
+                Change::Changed { path, desired, live } => writeln!(f, "~ {path}: {live} -> {desired}")?,
+                Change::Extra { path, live } => writeln!(f, "- {path}: {live}")?,
+            }
+        }
+        Ok(())
+    }
+}
+
+struct Differ<'a> {
+    params: &'a DiffParams,
+    changes: Vec<Change>,
+}
+
+impl Differ<'_> {
+    fn ignored(&self, path: &str) -> bool {
+        self.params
+            .ignored_fields
+            .iter()
+            .any(|ignored| is_within(path, ignored))
+    }
+
+    fn compare(&mut self, path: &str, desired: &Value, live: &Value) {
+        if self.ignored(path) {
+            return;
+        }
+        match (desired, live) {
+            (Value::Object(desired), Value::Object(live)) => {
+                for (key, desired) in desired {
+                    let path = format!("{path}.{key}");
+                    match live.get(key) {
+                        _ if desired.is_null() => {}
+                        Some(live) if !live.is_null() => self.compare(&path, desired, live),
+                        _ => self.missing(path, desired),
+                    }
+                }
+            }
+            (Value::Array(desired_items), Value::Array(live_items)) => {
+                if let (Some(desired_names), Some(live_names)) = (names(desired_items), names(live_items)) {
+                    for (name, desired) in desired_names.into_iter().zip(desired_items) {
+                        let path = format!("{path}[name={}]", Value::from(name));
+                        match live_names.iter().position(|live_name| *live_name == name) {
+                            Some(index) => self.compare(&path, desired, &live_items[index]),
+                            None => self.missing(path, desired),
+                        }
+                    }
+                } else if desired_items.len() == live_items.len() {
+                    for (index, (desired, live)) in desired_items.iter().zip(live_items).enumerate() {
+                        self.compare(&format!("{path}[{index}]"), desired, live);
+                    }
+                } else {
+                    self.changed(path, desired, live);
+                }
+            }
+            _ if scalars_equal(path, desired, live) => {}
+            _ => self.changed(path, desired, live),
+        }
+    }
+
+    fn missing(&mut self, path: String, desired: &Value) {
+        let is_empty = match desired {
+            Value::Null => true,
+            Value::Object(object) => object.is_empty(),
+            Value::Array(items) => items.is_empty(),
+            _ => false,
+        };
+        if !is_empty && !self.ignored(&path) {
+            self.changes.push(Change::Missing {
+                path,
+                desired: desired.clone(),
+            });
+        }
+    }
+
+    fn changed(&mut self, path: &str, desired: &Value, live: &Value) {
+        self.changes.push(Change::Changed {
+            path: path.to_owned(),
+            desired: desired.clone(),
+            live: live.clone(),
+        });
+    }
+}
+
+/// The names of all items, if every item is an object with a `name`
+fn names(items: &[Value]) -> Option<Vec<&str>> {
+    items.iter().map(|item| item.get("name")?.as_str()).collect()
+}
+
+fn scalars_equal(path: &str, desired: &Value, live: &Value) -> bool {
+    match (desired, live) {
+        (Value::Number(desired), Value::Number(live)) => desired.as_f64() == live.as_f64(),
+        (Value::String(desired), Value::String(live))
+            if [".requests.", ".limits.", ".hard."]
+                .iter()
+                .any(|parent| path.contains(parent)) =>
+        {
+            match (desired.parse::<Quantity>(), live.parse::<Quantity>()) {
+                (Ok(desired), Ok(live)) => desired == live,
+                _ => desired == live,
+            }
+        }
+        _ => desired == live,
+    }
+}
+
+/// The value of a field, with a path in the format of [`ManagedFields`]
+fn lookup<'v>(value: &'v Value, path: &str) -> Option<&'v Value> {
+    if path.is_empty() {
+        return Some(value);
+    }
+    if let Some(path) = path.strip_prefix('.') {
+        // field names can contain dots, such as label keys, so the longest matching name wins
+        let (key, rest) = value
+            .as_object()?
+            .keys()
+            .filter_map(|key| {
+                let rest = path.strip_prefix(key.as_str())?;
+                (rest.is_empty() || rest.starts_with(['.', '['])).then_some((key, rest))
+            })
+            .max_by_key(|(key, _)| key.len())?;
+        return lookup(&value[key], rest);
+    }
+
+    let path = path.strip_prefix('[')?;
+    let items = value.as_array()?;
+    if let Some(path) = path.strip_prefix('=') {
+        // set items, such as `[="example.com/cleanup"]`
+        let (item, rest) = json_prefix(path)?;
+        let rest = rest.strip_prefix(']')?;
+        return lookup(items.iter().find(|candidate| **candidate == item)?, rest);
+    }
+    if let Some((index, rest)) = path.split_once(']') {
+        if let Ok(index) = index.parse::<usize>() {
+            return lookup(items.get(index)?, rest);
+        }
+    }
+
+    // list items identified by their keys, such as `[containerPort=80,protocol="TCP"]`
+    let mut keys = Vec::new();
+    let mut path = path;
+    let rest = loop {
+        let (name, rest) = path.split_once('=')?;
+        let (key, rest) = json_prefix(rest)?;
+        keys.push((name, key));
+        match rest.strip_prefix(',') {
+            Some(rest) => path = rest,
+            None => break rest.strip_prefix(']')?,
+        }
+    };
+    let item = items
+        .iter()
+        .find(|item| keys.iter().all(|(name, key)| item.get(*name) == Some(key)))?;
+    lookup(item, rest)
+}
+
+/// Splits the JSON value at the start of `s` from the rest of it
+fn json_prefix(s: &str) -> Option<(Value, &str)> {
+    let mut values = serde_json::Deserializer::from_str(s).into_iter::<Value>();
+    let value = values.next()?.ok()?;
+    Some((value, &s[values.byte_offset()..]))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Change, Diff, DiffParams};
+    use serde_json::{json, Value};
+
+    fn desired() -> Value {
+        json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": { "name": "web", "labels": { "app": "web", "tier": "frontend" } },
+            "spec": {
+                "replicas": 3,
+                "template": { "spec": { "containers": [{
+                    "name": "web",
+                    "image": "nginx:1.25",
+                    "resources": { "requests": { "cpu": "0.5" } },
+                }] } },
+            },
+        })
+    }
+
+    fn live() -> Value {
+        json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": {
+                "name": "web",
+                "uid": "1234",
+                "resourceVersion": "7",
+                "labels": { "app": "web", "app.kubernetes.io/part-of": "shop" },
+                "managedFields": [{
+                    "manager": "deployer",
+                    "operation": "Apply",
+                    "apiVersion": "apps/v1",
+                    "fieldsType": "FieldsV1",
+                    "fieldsV1": {
+                        "f:metadata": { "f:labels": { "f:app": {}, "f:app.kubernetes.io/part-of": {} } },
+                        "f:spec": {
+                            "f:paused": {},
+                            "f:replicas": {},
+                            "f:template": { "f:spec": { "f:containers": {
+                                "k:{\"name\":\"web\"}": { ".": {}, "f:image": {}, "f:name": {} },
+                            } } },
+                        },
+                    },
+                }],
+            },
+            "spec": {
+                "replicas": 2,
+                "paused": true,
+                "progressDeadlineSeconds": 600,
+                "template": { "spec": { "containers": [
+                    { "name": "sidecar", "image": "envoy" },
+                    {
+                        "name": "web",
+                        "image": "nginx:1.24",
+                        "terminationMessagePath": "/dev/termination-log",
+                        "resources": { "requests": { "cpu": "500m" } },
+                    },
+                ] } },
+            },
+            "status": { "replicas": 2 },
+        })
+    }
+
+    #[test]
+    fn only_desired_fields_are_compared() {
+        let diff = Diff::new(&desired(), &live(), &DiffParams::default()).unwrap();
+        assert_eq!(diff.changes(), [
+            Change::Missing {
+                path: ".metadata.labels.tier".into(),
+                desired: json!("frontend"),
+            },
+            Change::Changed {
+                path: ".spec.replicas".into(),
+                desired: json!(3),
+                live: json!(2),
+            },
+            Change::Changed {
+                path: ".spec.template.spec.containers[name=\"web\"].image".into(),
+                desired: json!("nginx:1.25"),
+                live: json!("nginx:1.24"),
+            },
+        ]);
+
+        let params = DiffParams::default()
+            .ignore(".spec.template")
+            .ignore(".metadata.labels");
+        let diff = Diff::new(&desired(), &live(), &params).unwrap();
+        assert_eq!(diff.to_string(), "~ .spec.replicas: 2 -> 3\n");
+        assert!(Diff::new(&live(), &live(), &params).unwrap().is_empty());
+    }
+
+    #[test]
+    fn fields_owned_by_the_manager_are_extra() {
+        let params = DiffParams::default()
+            .field_manager("deployer")
+            .ignore(".spec.template");
+        let diff = Diff::new(&desired(), &live(), &params).unwrap();
+        assert_eq!(
+            diff.to_string(),
+            "+ .metadata.labels.tier: \"frontend\"\n\
+             ~ .spec.replicas: 2 -> 3\n\
+             - .metadata.labels.app.kubernetes.io/part-of: \"shop\"\n\
+             - .spec.paused: true\n"
+        );
+    }
+
+    #[test]
+    fn lookup_follows_managed_field_paths() {
+        let live = live();
+        let containers = ".spec.template.spec.containers";
+        assert_eq!(
+            super::lookup(&live, &format!("{containers}[name=\"web\"].image")),
+            Some(&json!("nginx:1.24"))
+        );
+        assert_eq!(
+            super::lookup(&live, &format!("{containers}[0].name")),
+            Some(&json!("sidecar"))
+        );
+        assert_eq!(super::lookup(&live, &format!("{containers}[name=\"db\"]")), None);
+        assert_eq!(
+            super::lookup(&live, ".metadata.labels.app.kubernetes.io/part-of"),
+            Some(&json!("shop"))
+        );
+    }
+}
@@ -97,14 +97,56 @@ impl fmt::Debug for Duration {
     }
 }
 
+/// Formats the duration the same as Go's [`time.Duration.String()`], such as `1h2m3.5s` or `1.5ms`
+///
+/// [`time.Duration.String()`]: https://pkg.go.dev/time#Duration.String
 impl fmt::Display for Duration {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
-        use std::fmt::Write;
+        // implements the same format as
+        // https://cs.opensource.google/go/go/+/refs/tags/go1.20.4:src/time/time.go;l=648
+        let nanos = self.duration.as_nanos();
+        if nanos == 0 {
+            return f.write_str("0s");
+        }
         if self.is_negative {
-            f.write_char('-')?;
+            f.write_str("-")?;
         }
-        fmt::Debug::fmt(&self.duration, f)
+        match nanos {
+            0..=999 => write!(f, "{nanos}ns"),
+            1_000..=999_999 => {
+                write_fraction(f, nanos, 3)?;
+                f.write_str("\u{00b5}s")
+            }
+            1_000_000..=999_999_999 => {
+                write_fraction(f, nanos, 6)?;
+                f.write_str("ms")
+            }
+            _ => {
+                let secs = nanos / 1_000_000_000;
+                let (hours, minutes) = (secs / 3600, secs / 60 % 60);
+                if hours > 0 {
+                    write!(f, "{hours}h")?;
+                }
+                if hours > 0 || minutes > 0 {
+                    write!(f, "{minutes}m")?;
+                }
+                write_fraction(f, nanos % 60_000_000_000, 9)?;
+                f.write_str("s")
+            }
+        }
+    }
+}
+
+/// Writes `value / 10^digits`, with the fraction but without its trailing zeros
+fn write_fraction(f: &mut fmt::Formatter<'_>, value: u128, digits: u32) -> fmt::Result {
+    let unit = 10u128.pow(digits);
+    write!(f, "{}", value / unit)?;
+    let fraction = value % unit;
+    if fraction == 0 {
+        return Ok(());
     }
+    let fraction = format!("{fraction:0width$}", width = digits as usize);
+    write!(f, ".{}", fraction.trim_end_matches('0'))
 }
 
 impl FromStr for Duration {
@@ -454,4 +496,44 @@ mod tests {
             assert_eq!(&dbg!(parsed), expected);
         }
     }
+
+    #[test]
+    fn formats_the_same_as_go() {
+        const MINUTE: time::Duration = time::Duration::from_secs(60);
+        const HOUR: time::Duration = time::Duration::from_secs(60 * 60);
+        // from Go:
+        // https://cs.opensource.google/go/go/+/refs/tags/go1.20.4:src/time/time_test.go;l=822-846
+        let cases: &[(&str, Duration)] = &[
+            ("0s", time::Duration::from_secs(0).into()),
+            ("1ns", time::Duration::from_nanos(1).into()),
+            ("1.1\u{00b5}s", time::Duration::from_nanos(1100).into()),
+            ("2.2ms", time::Duration::from_micros(2200).into()),
+            ("3.3s", time::Duration::from_millis(3300).into()),
+            ("4m5s", (4 * MINUTE + time::Duration::from_secs(5)).into()),
+            (
+                "4m5.001s",
+                (4 * MINUTE + time::Duration::from_millis(5001)).into(),
+            ),
+            (
+                "5h6m7.001s",
+                (5 * HOUR + 6 * MINUTE + time::Duration::from_millis(7001)).into(),
+            ),
+            (
+                "8m0.000000001s",
+                (8 * MINUTE + time::Duration::from_nanos(1)).into(),
+            ),
+            ("1h0m0s", HOUR.into()),
+            ("-2m3.4s", Duration {
+                duration: 2 * MINUTE + time::Duration::from_millis(3400),
+                is_negative: true,
+            }),
+        ];
+
+        for (expected, duration) in cases {
+            let formatted = duration.to_string();
+            assert_eq!(&formatted, expected);
+            assert_eq!(&formatted.parse::<Duration>().unwrap(), duration);
+        }
+        assert_eq!("-0".parse::<Duration>().unwrap().to_string(), "0s");
+    }
 }
@@ -1,6 +1,10 @@
+use std::time::Duration;
+
 use serde::{Deserialize, Serialize};
 use thiserror::Error;
 
+use crate::response::{StatusCause, StatusDetails};
+
 /// An error response from the API.
 #[derive(Error, Deserialize, Serialize, Debug, Clone, Eq, PartialEq)]
 #[error("{message}: {reason}")]
@@ -15,4 +19,57 @@ pub struct ErrorResponse {
     pub reason: String,
     /// The error code
     pub code: u16,
+    /// Extended data associated with the reason, such as the resource and the fields that were rejected
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub details: Option<Box<StatusDetails>>,
+}
+
+impl ErrorResponse {
+    /// The individual causes of the error, if the apiserver reported any
+    ///
+    /// Validation errors (reason `Invalid`) have one cause per rejected field:
+    ///
+    /// ```
+    /// # fn report(err: &kube_core::ErrorResponse) {
+    /// for cause in err.causes() {
+    ///     eprintln!("{}: {}", cause.field, cause.message);
+    /// }
+    /// # }
+    /// ```
+    pub fn causes(&self) -> &[StatusCause] {
+        self.details.as_ref().map_or(&[], |details| &details.causes)
+    }
+
+    /// How long the apiserver asked to wait before retrying, if it did
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.details
+            .as_ref()
+            .map(|details| details.retry_after_seconds)
+            .filter(|&seconds| seconds > 0)
+            .map(|seconds| Duration::from_secs(seconds.into()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::ErrorResponse;
+    use std::time::Duration;
+
+    #[test]
+    fn error_response_deserializes_details() {
+        let invalid = r#"{"kind":"Status","apiVersion":"v1","metadata":{},"status":"Failure","message":"Deployment.apps \"blog\" is invalid: spec.replicas: Invalid value: -1: must be greater than or equal to 0","reason":"Invalid","details":{"name":"blog","group":"apps","kind":"Deployment","causes":[{"reason":"FieldValueInvalid","message":"Invalid value: -1: must be greater than or equal to 0","field":"spec.replicas"}]},"code":422}"#;
+        let err: ErrorResponse = serde_json::from_str(invalid).unwrap();
+        let details = err.details.as_ref().unwrap();
+        assert_eq!(details.group, "apps");
+        assert_eq!(details.kind, "Deployment");
+        assert_eq!(details.name, "blog");
+        assert_eq!(err.causes().len(), 1);
+        assert_eq!(err.causes()[0].field, "spec.replicas");
+        assert_eq!(err.retry_after(), None);
+
+        let throttled = r#"{"status":"Failure","message":"too many requests","reason":"TooManyRequests","details":{"retryAfterSeconds":3},"code":429}"#;
+        let err: ErrorResponse = serde_json::from_str(throttled).unwrap();
+        assert!(err.causes().is_empty());
+        assert_eq!(err.retry_after(), Some(Duration::from_secs(3)));
+    }
 }
@@ -0,0 +1,121 @@
+//! Type safe field selector logic
+use std::fmt::{self, Display};
+
+/// The `metadata.name` field, supported by all resources
+pub const METADATA_NAME: &str = "metadata.name";
+/// The `metadata.namespace` field, supported by all namespaced resources
+pub const METADATA_NAMESPACE: &str = "metadata.namespace";
+/// The `spec.nodeName` field of pods
+pub const SPEC_NODE_NAME: &str = "spec.nodeName";
+/// The `spec.restartPolicy` field of pods
+pub const SPEC_RESTART_POLICY: &str = "spec.restartPolicy";
+/// The `spec.schedulerName` field of pods
+pub const SPEC_SCHEDULER_NAME: &str = "spec.schedulerName";
+/// The `spec.serviceAccountName` field of pods
+pub const SPEC_SERVICE_ACCOUNT_NAME: &str = "spec.serviceAccountName";
+/// The `status.phase` field of pods and namespaces
+pub const STATUS_PHASE: &str = "status.phase";
+/// The `status.podIP` field of pods
+pub const STATUS_POD_IP: &str = "status.podIP";
+/// The `involvedObject.kind` field of `core/v1` events
+pub const INVOLVED_OBJECT_KIND: &str = "involvedObject.kind";
+/// The `involvedObject.name` field of `core/v1` events
+pub const INVOLVED_OBJECT_NAME: &str = "involvedObject.name";
+/// The `regarding.kind` field of `events.k8s.io/v1` events
+pub const REGARDING_KIND: &str = "regarding.kind";
+/// The `regarding.name` field of `events.k8s.io/v1` events
+pub const REGARDING_NAME: &str = "regarding.name";
+
+/// A field selector requirement
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum Requirement {
+    Equal(String, String),
+    NotEqual(String, String),
+}
+
+/// Selects objects by the values of their fields
+///
+/// Values are escaped when converted to the wire format, so they may safely contain `,`, `=` or `\`.
+/// Can be injected into [`WatchParams`](crate::params::WatchParams::fields_from) or [`ListParams`](crate::params::ListParams::fields_from).
+///
+/// ```
+/// use kube::core::{fields, FieldSelector};
+/// let selector = FieldSelector::default()
+///     .equal(fields::SPEC_NODE_NAME, "node-1")
+///     .not_equal(fields::STATUS_PHASE, "Succeeded");
+/// assert_eq!(selector.to_string(), "spec.nodeName=node-1,status.phase!=Succeeded");
+/// ```
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FieldSelector(Vec<Requirement>);
+
+impl FieldSelector {
+    /// Require the field at `path` to equal `value`
+    #[must_use]
+    pub fn equal(mut self, path: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.push(Requirement::Equal(path.into(), value.into()));
+        self
+    }
+
+    /// Require the field at `path` to not equal `value`
+    #[must_use]
+    pub fn not_equal(mut self, path: impl Into<String>, value: impl Into<String>) -> Self {
+        self.0.push(Requirement::NotEqual(path.into(), value.into()));
+        self
+    }
+
+    /// Indicates whether this field selector matches everything
+    pub fn selects_all(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+/// Escapes the characters that have a meaning in field selectors, like `fields.EscapeValue` in apimachinery
+fn escape_value(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for c in value.chars() {
+        if matches!(c, '\\' | ',' | '=') {
+            escaped.push('\\');
+        }
+        escaped.push(c);
+    }
+    escaped
+}
+
+impl Display for FieldSelector {
+    /// Convert a selector to a string for the API
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, requirement) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(",")?;
+            }
+            match requirement {
+                Requirement::Equal(path, value) => write!(f, "{path}={}", escape_value(value))?,
+                Requirement::NotEqual(path, value) => write!(f, "{path}!={}", escape_value(value))?,
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_to_string() {
+        assert_eq!(FieldSelector::default().to_string(), "");
+        let selector = FieldSelector::default()
+            .equal(METADATA_NAME, "foo")
+            .not_equal(METADATA_NAMESPACE, "kube-system");
+        assert_eq!(
+            selector.to_string(),
+            "metadata.name=foo,metadata.namespace!=kube-system"
+        );
+    }
+
+    #[test]
+    fn test_values_are_escaped() {
+        let selector = FieldSelector::default().equal("spec.value", r"a,b=c\d");
+        assert_eq!(selector.to_string(), r"spec.value=a\,b\=c\\d");
+    }
+}
@@ -0,0 +1,253 @@
+//! Kubernetes [`IntOrString`]s.
+use k8s_openapi::apimachinery::pkg::util::intstr;
+use serde::{Deserialize, Serialize};
+use std::{convert::Infallible, fmt, num::ParseIntError, str::FromStr};
+
+/// A value that is either an integer or a string, such as `3` or `"50%"`.
+///
+/// This is a wrapper around [`k8s_openapi`]'s `IntOrString` that can be
+/// converted from integers and strings, scales percentages like the Go
+/// [`intstr`] package, and implements `JsonSchema` with the
+/// `x-kubernetes-int-or-string` extension that the apiserver requires for
+/// these fields in custom resources.
+///
+/// ```
+/// use kube::core::IntOrString;
+///
+/// let max_unavailable = IntOrString::from("25%");
+/// assert_eq!(max_unavailable.scaled_value(10, true)?, 3);
+/// assert_eq!(IntOrString::from(2).scaled_value(10, true)?, 2);
+/// # Ok::<(), kube::core::int_or_string::ParseError>(())
+/// ```
+///
+/// [`intstr`]: https://pkg.go.dev/k8s.io/apimachinery/pkg/util/intstr
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct IntOrString(pub intstr::IntOrString);
+
+/// Errors returned by [`IntOrString::scaled_value`].
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The string does not end with `%`.
+    #[error("string is not a percentage: {0:?}")]
+    NotAPercentage(String),
+
+    /// The number before the `%` is not an integer.
+    #[error("invalid percentage {0:?}: {1}")]
+    InvalidPercentage(String, #[source] ParseIntError),
+}
+
+impl IntOrString {
+    /// Creates a percentage, such as `"50%"`.
+    #[must_use]
+    pub fn percent(percent: i32) -> Self {
+        Self(intstr::IntOrString::String(format!("{percent}%")))
+    }
+
+    /// Returns the integer, if this is one.
+    #[must_use]
+    pub fn as_int(&self) -> Option<i32> {
+        match &self.0 {
+            intstr::IntOrString::Int(value) => Some(*value),
+            intstr::IntOrString::String(_) => None,
+        }
+    }
+
+    /// Returns the string, if this is one.
+    #[must_use]
+    pub fn as_str(&self) -> Option<&str> {
+        match &self.0 {
+            intstr::IntOrString::Int(_) => None,
+            intstr::IntOrString::String(value) => Some(value),
+        }
+    }
+
+    /// Returns the percentage, if this is a string such as `"50%"`.
+    pub fn as_percent(&self) -> Option<Result<i32, ParseError>> {
+        let value = self.as_str()?;
+        let percent = value.strip_suffix('%')?;
+        Some(
+            percent
+                .parse()
+                .map_err(|err| ParseError::InvalidPercentage(value.to_owned(), err)),
+        )
+    }
+
+    /// Returns the integer, or the percentage of `total`, like `GetScaledValueFromIntOrPercent()` in Go.
+    ///
+    /// Percentages are rounded up if `round_up` is `true`, and rounded down otherwise.
+    /// Strings that are not percentages are an error.
+    pub fn scaled_value(&self, total: i32, round_up: bool) -> Result<i32, ParseError> {
+        let percent = match &self.0 {
+            intstr::IntOrString::Int(value) => return Ok(*value),
+            intstr::IntOrString::String(value) => self
+                .as_percent()
+                .ok_or_else(|| ParseError::NotAPercentage(value.clone()))??,
+        };
+        let scaled = f64::from(percent) * f64::from(total) / 100.0;
+        let scaled = if round_up { scaled.ceil() } else { scaled.floor() };
+        // saturates for percentages that scale beyond the range of an i32
+        Ok(scaled as i32)
+    }
+}
+
+impl Default for IntOrString {
+    fn default() -> Self {
+        Self(intstr::IntOrString::Int(0))
+    }
+}
+
+impl From<i32> for IntOrString {
+    fn from(value: i32) -> Self {
+        Self(intstr::IntOrString::Int(value))
+    }
+}
+
+impl From<&str> for IntOrString {
+    fn from(value: &str) -> Self {
+        Self(intstr::IntOrString::String(value.to_owned()))
+    }
+}
+
+impl From<String> for IntOrString {
+    fn from(value: String) -> Self {
+        Self(intstr::IntOrString::String(value))
+    }
+}
+
+impl From<intstr::IntOrString> for IntOrString {
+    fn from(value: intstr::IntOrString) -> Self {
+        Self(value)
+    }
+}
+
+impl From<IntOrString> for intstr::IntOrString {
+    fn from(value: IntOrString) -> Self {
+        value.0
+    }
+}
+
+/// Parses integers as integers and everything else as strings, like `intstr.Parse()` in Go
+impl FromStr for IntOrString {
+    type Err = Infallible;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        Ok(match s.parse::<i32>() {
+            Ok(value) => value.into(),
+            Err(_) => s.into(),
+        })
+    }
+}
+
+impl fmt::Display for IntOrString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.0 {
+            intstr::IntOrString::Int(value) => fmt::Display::fmt(value, f),
+            intstr::IntOrString::String(value) => f.write_str(value),
+        }
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for IntOrString {
+    // see
+    // https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definitions/#int-or-string
+    fn schema_name() -> String {
+        "IntOrString".to_owned()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        int_or_string_schema()
+    }
+}
+
+/// The schema of fields that are either an integer or a string
+///
+/// The apiserver only accepts `anyOf` integer and string when `x-kubernetes-int-or-string` is set.
+#[cfg(feature = "schema")]
+pub(crate) fn int_or_string_schema() -> schemars::schema::Schema {
+    use schemars::schema::{InstanceType, SchemaObject, SubschemaValidation};
+
+    let mut schema = SchemaObject {
+        subschemas: Some(Box::new(SubschemaValidation {
+            any_of: Some(vec![
+                SchemaObject {
+                    instance_type: Some(InstanceType::Integer.into()),
+                    ..Default::default()
+                }
+                .into(),
+                SchemaObject {
+                    instance_type: Some(InstanceType::String.into()),
+                    ..Default::default()
+                }
+                .into(),
+            ]),
+            ..Default::default()
+        })),
+        ..Default::default()
+    };
+    schema
+        .extensions
+        .insert("x-kubernetes-int-or-string".into(), true.into());
+    schema.into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn percentages_are_scaled_like_go() {
+        let cases = [
+            (IntOrString::from(5), 10, 5, 5),
+            (IntOrString::percent(25), 10, 3, 2),
+            (IntOrString::from("50%"), 5, 3, 2),
+            (IntOrString::from("100%"), 7, 7, 7),
+            (IntOrString::from("0%"), 7, 0, 0),
+        ];
+        for (value, total, rounded_up, rounded_down) in cases {
+            assert_eq!(value.scaled_value(total, true), Ok(rounded_up));
+            assert_eq!(value.scaled_value(total, false), Ok(rounded_down));
+        }
+
+        assert_eq!(
+            IntOrString::from("5").scaled_value(10, true),
+            Err(ParseError::NotAPercentage("5".into()))
+        );
+        assert!(matches!(
+            IntOrString::from("half%").scaled_value(10, true),
+            Err(ParseError::InvalidPercentage(value, _)) if value == "half%"
+        ));
+    }
+
+    #[test]
+    fn parses_and_serializes_like_go() {
+        assert_eq!("3".parse(), Ok(IntOrString::from(3)));
+        assert_eq!("50%".parse(), Ok(IntOrString::percent(50)));
+        assert_eq!(IntOrString::percent(50).as_percent(), Some(Ok(50)));
+        assert_eq!(IntOrString::from(50).as_percent(), None);
+        assert_eq!(IntOrString::from("http").to_string(), "http");
+
+        let values: Vec<IntOrString> = serde_json::from_str(r#"[8080, "http"]"#).unwrap();
+        assert_eq!(values, [IntOrString::from(8080), IntOrString::from("http")]);
+        assert_eq!(serde_json::to_string(&values).unwrap(), r#"[8080,"http"]"#);
+    }
+
+    #[cfg(feature = "schema")]
+    #[test]
+    fn schema_is_int_or_string() {
+        let schema = schemars::gen::SchemaGenerator::default().subschema_for::<IntOrString>();
+        assert_eq!(
+            serde_json::to_value(schema).unwrap(),
+            serde_json::json!({
+                "x-kubernetes-int-or-string": true,
+                "anyOf": [{"type": "integer"}, {"type": "string"}],
+            })
+        );
+    }
+}
@@ -4,6 +4,8 @@ use crate::{
     subresource::{AttachParams, LogParams},
     Request,
 };
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::Time;
+use serde::{Deserialize, Serialize};
 use std::fmt::Debug;
 
 /// Struct that hold all required parameters to call specific pod methods from node
@@ -158,12 +160,225 @@ impl Request {
         let req = http::Request::get(urlstr);
         req.body(vec![]).map_err(Error::BuildRequest)
     }
+
+    /// Get the resource usage of the node and its pods
+    ///
+    /// With `only_cpu_and_memory`, the kubelet skips the more expensive filesystem and network stats.
+    pub fn kubelet_node_stats_summary(only_cpu_and_memory: bool) -> Result<http::Request<Vec<u8>>, Error> {
+        let target = if only_cpu_and_memory {
+            "/stats/summary?only_cpu_and_memory=true"
+        } else {
+            "/stats/summary"
+        };
+        http::Request::get(target)
+            .body(vec![])
+            .map_err(Error::BuildRequest)
+    }
+
+    /// List the pods running on the node, as seen by the kubelet
+    pub fn kubelet_node_pods() -> Result<http::Request<Vec<u8>>, Error> {
+        http::Request::get("/pods")
+            .body(vec![])
+            .map_err(Error::BuildRequest)
+    }
+
+    /// Get the resource metrics of the node in the Prometheus text format
+    pub fn kubelet_node_resource_metrics() -> Result<http::Request<Vec<u8>>, Error> {
+        http::Request::get("/metrics/resource")
+            .body(vec![])
+            .map_err(Error::BuildRequest)
+    }
+}
+
+/// Resource usage of a node and its pods, from the kubelet `/stats/summary` endpoint
+///
+/// This mirrors the `stats/v1alpha1` API of the kubelet, which is not part of the Kubernetes API.
+/// Fields that are not reported by every container runtime are optional.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Summary {
+    /// Stats of the node itself
+    pub node: NodeStats,
+    /// Stats of the pods on the node
+    #[serde(default)]
+    pub pods: Vec<PodStats>,
+}
+
+/// Resource usage of a node, see [`Summary`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NodeStats {
+    /// Name of the node
+    pub node_name: String,
+    /// Stats of the system daemons, such as the kubelet and the container runtime
+    #[serde(default)]
+    pub system_containers: Vec<ContainerStats>,
+    /// When the stats of the node started being collected
+    pub start_time: Option<Time>,
+    /// CPU usage of the node
+    pub cpu: Option<CpuStats>,
+    /// Memory usage of the node
+    pub memory: Option<MemoryStats>,
+    /// Network usage of the node
+    pub network: Option<NetworkStats>,
+    /// Usage of the filesystem of the node
+    pub fs: Option<FsStats>,
+}
+
+/// Resource usage of a pod, see [`Summary`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PodStats {
+    /// The pod that the stats are for
+    pub pod_ref: PodReference,
+    /// When the stats of the pod started being collected
+    pub start_time: Option<Time>,
+    /// Stats of the containers of the pod
+    #[serde(default)]
+    pub containers: Vec<ContainerStats>,
+    /// CPU usage of the pod
+    pub cpu: Option<CpuStats>,
+    /// Memory usage of the pod
+    pub memory: Option<MemoryStats>,
+    /// Network usage of the pod
+    pub network: Option<NetworkStats>,
+    /// Usage of the volumes of the pod
+    #[serde(default)]
+    pub volume: Vec<VolumeStats>,
+    /// Usage of the local ephemeral storage of the pod
+    #[serde(rename = "ephemeral-storage")]
+    pub ephemeral_storage: Option<FsStats>,
+}
+
+/// Identifies the pod of [`PodStats`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+pub struct PodReference {
+    /// Name of the pod
+    pub name: String,
+    /// Namespace of the pod
+    pub namespace: String,
+    /// Uid of the pod
+    pub uid: String,
+}
+
+/// Resource usage of a container, see [`Summary`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerStats {
+    /// Name of the container
+    pub name: String,
+    /// When the container started
+    pub start_time: Option<Time>,
+    /// CPU usage of the container
+    pub cpu: Option<CpuStats>,
+    /// Memory usage of the container
+    pub memory: Option<MemoryStats>,
+    /// Usage of the writable layer of the container
+    pub rootfs: Option<FsStats>,
+    /// Usage of the logs of the container
+    pub logs: Option<FsStats>,
+}
+
+/// CPU usage, see [`Summary`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CpuStats {
+    /// When the usage was sampled
+    pub time: Option<Time>,
+    /// CPU usage over the sample window, in nanocores
+    pub usage_nano_cores: Option<u64>,
+    /// Cumulative CPU usage since the start of the container, in nanoseconds of a single core
+    pub usage_core_nano_seconds: Option<u64>,
+}
+
+/// Memory usage, see [`Summary`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct MemoryStats {
+    /// When the usage was sampled
+    pub time: Option<Time>,
+    /// Memory available for use, in bytes
+    pub available_bytes: Option<u64>,
+    /// Total memory in use, including caches that can be freed, in bytes
+    pub usage_bytes: Option<u64>,
+    /// Memory in use that cannot be freed, in bytes, which is what the kubelet evicts pods for
+    pub working_set_bytes: Option<u64>,
+    /// Anonymous and swap cache memory, in bytes
+    pub rss_bytes: Option<u64>,
+    /// Cumulative number of minor page faults
+    pub page_faults: Option<u64>,
+    /// Cumulative number of major page faults
+    pub major_page_faults: Option<u64>,
+}
+
+/// Network usage, see [`Summary`]
+///
+/// The counters are those of the default interface, see [`NetworkStats::interfaces`] for all of them.
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStats {
+    /// When the usage was sampled
+    pub time: Option<Time>,
+    /// Usage of the default interface
+    #[serde(flatten)]
+    pub default: InterfaceStats,
+    /// Usage of every interface
+    #[serde(default)]
+    pub interfaces: Vec<InterfaceStats>,
+}
+
+/// Usage of a network interface, see [`NetworkStats`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct InterfaceStats {
+    /// Name of the interface
+    #[serde(default)]
+    pub name: String,
+    /// Cumulative bytes received
+    pub rx_bytes: Option<u64>,
+    /// Cumulative errors while receiving
+    pub rx_errors: Option<u64>,
+    /// Cumulative bytes transmitted
+    pub tx_bytes: Option<u64>,
+    /// Cumulative errors while transmitting
+    pub tx_errors: Option<u64>,
+}
+
+/// Filesystem usage, see [`Summary`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct FsStats {
+    /// When the usage was sampled
+    pub time: Option<Time>,
+    /// Storage available for use, in bytes
+    pub available_bytes: Option<u64>,
+    /// Total storage, in bytes
+    pub capacity_bytes: Option<u64>,
+    /// Storage in use, in bytes
+    pub used_bytes: Option<u64>,
+    /// Free inodes
+    pub inodes_free: Option<u64>,
+    /// Total inodes
+    pub inodes: Option<u64>,
+    /// Inodes in use
+    pub inodes_used: Option<u64>,
+}
+
+/// Usage of a volume of a pod, see [`PodStats`]
+#[derive(Clone, Debug, Default, PartialEq, Deserialize, Serialize)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeStats {
+    /// Name of the volume in the pod
+    pub name: String,
+    /// Usage of the volume
+    #[serde(flatten)]
+    pub fs: FsStats,
 }
 
 #[cfg(test)]
 mod test {
     use crate::{
-        kubelet_debug::KubeletDebugParams,
+        kubelet_debug::{KubeletDebugParams, Summary},
         subresource::{AttachParams, LogParams},
         Request,
     };
@@ -241,4 +456,41 @@ mod test {
         .unwrap();
         assert_eq!(req.uri(), "/portForward/some-namespace/some-name?&port=1204");
     }
+
+    #[test]
+    fn node_stats_test() {
+        let req = Request::kubelet_node_stats_summary(true).unwrap();
+        assert_eq!(req.uri(), "/stats/summary?only_cpu_and_memory=true");
+        assert_eq!(Request::kubelet_node_pods().unwrap().uri(), "/pods");
+        assert_eq!(
+            Request::kubelet_node_resource_metrics().unwrap().uri(),
+            "/metrics/resource"
+        );
+
+        let summary: Summary = serde_json::from_value(serde_json::json!({
+            "node": {
+                "nodeName": "node-1",
+                "cpu": {"time": "2024-01-01T00:00:00Z", "usageNanoCores": 250000000},
+                "network": {"name": "eth0", "rxBytes": 10, "interfaces": [{"name": "eth0", "rxBytes": 10}]},
+            },
+            "pods": [{
+                "podRef": {"name": "web", "namespace": "default", "uid": "1234"},
+                "containers": [{"name": "app", "memory": {"workingSetBytes": 1024}}],
+                "volume": [{"name": "data", "usedBytes": 42}],
+                "ephemeral-storage": {"usedBytes": 7},
+            }],
+        }))
+        .unwrap();
+        assert_eq!(summary.node.node_name, "node-1");
+        assert_eq!(summary.node.cpu.unwrap().usage_nano_cores, Some(250_000_000));
+        assert_eq!(summary.node.network.unwrap().default.rx_bytes, Some(10));
+        let pod = &summary.pods[0];
+        assert_eq!(pod.pod_ref.name, "web");
+        assert_eq!(
+            pod.containers[0].memory.as_ref().unwrap().working_set_bytes,
+            Some(1024)
+        );
+        assert_eq!(pod.volume[0].fs.used_bytes, Some(42));
+        assert_eq!(pod.ephemeral_storage.as_ref().unwrap().used_bytes, Some(7));
+    }
 }
@@ -22,6 +22,11 @@ mod private {
 /// Indicates failure of conversion to Expression
 pub struct ParseExpressionError(pub String);
 
+#[derive(Debug, Error, PartialEq, Eq)]
+#[error("invalid label selector: {0}")]
+/// Indicates that a selector would be rejected by the apiserver
+pub struct InvalidSelectorError(pub String);
+
 // local type aliases
 type Expressions = Vec<Expression>;
 
@@ -150,6 +155,141 @@ impl Selector {
         self.0.extend(exprs);
         self
     }
+
+    /// Require the label `key` to equal `value`
+    ///
+    /// ```
+    /// use kube::core::Selector;
+    /// let selector = Selector::default()
+    ///     .equal("app", "web")
+    ///     .is_in("environment", ["production", "staging"])
+    ///     .does_not_exist("canary");
+    /// assert_eq!(selector.to_string(), "app=web,environment in (production,staging),!canary");
+    /// selector.validate()?;
+    /// # Ok::<(), kube_core::labels::InvalidSelectorError>(())
+    /// ```
+    #[must_use]
+    pub fn equal(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.with(Expression::Equal(key.into(), value.into()))
+    }
+
+    /// Require the label `key` to be missing or not equal `value`
+    #[must_use]
+    pub fn not_equal(self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.with(Expression::NotEqual(key.into(), value.into()))
+    }
+
+    /// Require the label `key` to be one of `values`
+    #[must_use]
+    pub fn is_in(self, key: impl Into<String>, values: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let values = values.into_iter().map(Into::into).collect();
+        self.with(Expression::In(key.into(), values))
+    }
+
+    /// Require the label `key` to be missing or not one of `values`
+    #[must_use]
+    pub fn not_in(self, key: impl Into<String>, values: impl IntoIterator<Item = impl Into<String>>) -> Self {
+        let values = values.into_iter().map(Into::into).collect();
+        self.with(Expression::NotIn(key.into(), values))
+    }
+
+    /// Require the label `key` to be set
+    #[must_use]
+    pub fn exists(self, key: impl Into<String>) -> Self {
+        self.with(Expression::Exists(key.into()))
+    }
+
+    /// Require the label `key` to be missing
+    #[must_use]
+    pub fn does_not_exist(self, key: impl Into<String>) -> Self {
+        self.with(Expression::DoesNotExist(key.into()))
+    }
+
+    fn with(mut self, expr: Expression) -> Self {
+        self.0.push(expr);
+        self
+    }
+
+    /// Checks that the apiserver would accept the selector
+    ///
+    /// Keys must be (optionally prefixed) label names, values must be valid label values,
+    /// and set based expressions must have at least one value.
+    pub fn validate(&self) -> Result<(), InvalidSelectorError> {
+        self.0.iter().try_for_each(Expression::validate)
+    }
+}
+
+impl Expression {
+    /// Checks that the apiserver would accept the expression
+    pub fn validate(&self) -> Result<(), InvalidSelectorError> {
+        match self {
+            Expression::In(key, values) | Expression::NotIn(key, values) => {
+                validate_key(key)?;
+                if values.is_empty() {
+                    return Err(InvalidSelectorError(format!(
+                        "no values given for set based key {key:?}"
+                    )));
+                }
+                values.iter().try_for_each(|value| validate_value(value))
+            }
+            Expression::Equal(key, value) | Expression::NotEqual(key, value) => {
+                validate_key(key)?;
+                validate_value(value)
+            }
+            Expression::Exists(key) | Expression::DoesNotExist(key) => validate_key(key),
+        }
+    }
+}
+
+/// Validates a label key, which is a name with an optional DNS subdomain prefix
+fn validate_key(key: &str) -> Result<(), InvalidSelectorError> {
+    let (prefix, name) = match key.split_once('/') {
+        Some((prefix, name)) => (Some(prefix), name),
+        None => (None, key),
+    };
+    if let Some(prefix) = prefix {
+        let valid_prefix = !prefix.is_empty()
+            && prefix.len() <= 253
+            && prefix.split('.').all(|label| {
+                !label.is_empty()
+                    && label
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+                    && !label.starts_with('-')
+                    && !label.ends_with('-')
+            });
+        if !valid_prefix {
+            return Err(InvalidSelectorError(format!(
+                "prefix of key {key:?} must be a DNS subdomain"
+            )));
+        }
+    }
+    if name.is_empty() || !is_label_value(name) {
+        return Err(InvalidSelectorError(format!(
+            "name of key {key:?} must be at most 63 alphanumeric characters, '-', '_' or '.'"
+        )));
+    }
+    Ok(())
+}
+
+fn validate_value(value: &str) -> Result<(), InvalidSelectorError> {
+    if !is_label_value(value) {
+        return Err(InvalidSelectorError(format!(
+            "value {value:?} must be at most 63 alphanumeric characters, '-', '_' or '.'"
+        )));
+    }
+    Ok(())
+}
+
+/// Whether `value` matches `(([A-Za-z0-9][-A-Za-z0-9_.]*)?[A-Za-z0-9])?` and is at most 63 characters
+fn is_label_value(value: &str) -> bool {
+    let edges_alphanumeric = |c: Option<char>| c.map_or(true, |c| c.is_ascii_alphanumeric());
+    value.len() <= 63
+        && value
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || matches!(c, '-' | '_' | '.'))
+        && edges_alphanumeric(value.chars().next())
+        && edges_alphanumeric(value.chars().last())
 }
 
 impl SelectorExt for Selector {
@@ -610,6 +750,44 @@ mod tests {
         assert!(!selector.matches(&Default::default()));
     }
 
+    #[test]
+    fn test_builder() {
+        let selector = Selector::default()
+            .equal("app", "web")
+            .not_equal("tier", "cache")
+            .is_in("env", ["prod", "staging"])
+            .not_in("zone", ["a"])
+            .exists("kubernetes.io/team")
+            .does_not_exist("canary");
+        assert_eq!(
+            selector.to_string(),
+            "app=web,tier!=cache,env in (prod,staging),zone notin (a),kubernetes.io/team,!canary"
+        );
+        assert_eq!(selector.validate(), Ok(()));
+    }
+
+    #[test]
+    fn test_validate() {
+        for (selector, valid) in [
+            (Selector::default().equal("app", ""), true),
+            (
+                Selector::default().equal("app.kubernetes.io/name", "my-app_1.0"),
+                true,
+            ),
+            (Selector::default().equal("app", "web,tier=db"), false),
+            (Selector::default().equal("app", "-web"), false),
+            (Selector::default().equal("app", "x".repeat(64)), false),
+            (Selector::default().exists("Example.com/app"), false),
+            (Selector::default().exists("example.com/"), false),
+            (Selector::default().exists("/app"), false),
+            (Selector::default().exists("app name"), false),
+            (Selector::default().is_in("app", Vec::<String>::new()), false),
+            (Selector::default().not_in("app", ["web", "db)"]), false),
+        ] {
+            assert_eq!(selector.validate().is_ok(), valid, "{selector}");
+        }
+    }
+
     #[test]
     fn test_to_string() {
         let selector = Selector(vec![
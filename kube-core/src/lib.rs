@@ -8,12 +8,22 @@
 //! (even with zero features) under [`kube::core`]((https://docs.rs/kube/*/kube/core/index.html)).
 #![cfg_attr(docsrs, feature(doc_cfg))]
 
+pub mod access_review;
+
 #[cfg_attr(docsrs, doc(cfg(feature = "admission")))]
 #[cfg(feature = "admission")]
 pub mod admission;
 
+pub mod apply;
+pub use apply::ApplyConfiguration;
+
+pub mod conditions;
+
 pub mod conversion;
 
+pub mod diff;
+pub use diff::Diff;
+
 pub mod discovery;
 
 pub mod duration;
@@ -31,9 +41,20 @@ pub use cel::{Message, Reason, Rule};
 #[cfg(feature = "schema")]
 pub use cel::{merge_properties, validate, validate_property};
 
+pub mod fields;
+pub use fields::FieldSelector;
+
 pub mod gvk;
 pub use gvk::{GroupVersion, GroupVersionKind, GroupVersionResource};
 
+pub mod int_or_string;
+pub use int_or_string::IntOrString;
+
+pub mod managed_fields;
+pub use managed_fields::ManagedFields;
+
+pub mod merge;
+
 pub mod metadata;
 pub use metadata::{ListMeta, ObjectMeta, PartialObjectMeta, PartialObjectMetaExt, TypeMeta};
 
@@ -46,6 +67,9 @@ pub use object::{NotUsed, Object, ObjectList};
 
 pub mod params;
 
+pub mod quantity;
+pub use quantity::Quantity;
+
 pub mod request;
 pub use request::Request;
 
@@ -58,7 +82,7 @@ pub use resource::{
 pub mod response;
 pub use response::Status;
 
-pub use labels::{Expression, ParseExpressionError, Selector, SelectorExt};
+pub use labels::{Expression, InvalidSelectorError, ParseExpressionError, Selector, SelectorExt};
 
 #[cfg_attr(docsrs, doc(cfg(feature = "schema")))]
 #[cfg(feature = "schema")]
@@ -66,6 +90,9 @@ pub mod schema;
 
 pub mod subresource;
 
+pub mod table;
+pub use table::Table;
+
 pub mod util;
 
 pub mod watch;
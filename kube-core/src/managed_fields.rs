@@ -0,0 +1,448 @@
+//! Inspection of server-side apply field ownership. See [`ManagedFields`].
+use std::collections::BTreeSet;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::{ManagedFieldsEntry, Time};
+use serde::Serialize;
+use serde_json::{Map, Value};
+
+use crate::{DynamicObject, Resource, ResourceExt};
+
+/// The fields owned by a single field manager, parsed from a [`ManagedFieldsEntry`]
+#[derive(Clone, Debug, PartialEq)]
+pub struct FieldOwnership {
+    /// The name of the field manager
+    pub manager: String,
+    /// The operation that took ownership of the fields, either `Apply` or `Update`
+    pub operation: Option<String>,
+    /// The subresource that the fields were written through, such as `status`
+    pub subresource: Option<String>,
+    /// The API version of the object when the fields were written
+    pub api_version: Option<String>,
+    /// When the fields were last written
+    pub time: Option<Time>,
+    /// The owned fields, as paths like `.spec.replicas` or `.spec.containers[name="web"].image`
+    ///
+    /// Paths use the same format as the conflicts reported by server-side apply,
+    /// see [`ApplyConflict`](crate::apply::ApplyConflict).
+    pub fields: BTreeSet<String>,
+}
+
+impl FieldOwnership {
+    /// Parses the fields that `entry` owns
+    ///
+    /// Entries without `fieldsV1` own no fields.
+    pub fn from_entry(entry: &ManagedFieldsEntry) -> Self {
+        let mut fields = BTreeSet::new();
+        if let Some(fields_v1) = &entry.fields_v1 {
+            collect_fields(&fields_v1.0, "", &mut fields);
+        }
+        Self {
+            manager: entry.manager.clone().unwrap_or_default(),
+            operation: entry.operation.clone(),
+            subresource: entry
+                .subresource
+                .clone()
+                .filter(|subresource| !subresource.is_empty()),
+            api_version: entry.api_version.clone(),
+            time: entry.time.clone(),
+            fields,
+        }
+    }
+
+    /// Whether the manager owns `field`
+    pub fn owns(&self, field: &str) -> bool {
+        self.fields.contains(field)
+    }
+
+    /// Whether the manager owns `field`, or any field nested within it
+    pub fn owns_within(&self, field: &str) -> bool {
+        self.fields.iter().any(|owned| is_within(owned, field))
+    }
+}
+
+/// The server-side apply field ownership of an object, parsed from `.metadata.managedFields`
+///
+/// This answers questions like "who owns `.spec.replicas`?", to debug conflicts,
+/// or to decide whether a controller can adopt a field from another manager.
+///
+/// ```
+/// use k8s_openapi::api::apps::v1::Deployment;
+/// use kube_core::managed_fields::ManagedFields;
+/// # let deployment: Deployment = serde_json::from_value(serde_json::json!({
+/// #     "metadata": { "name": "web", "managedFields": [{
+/// #         "manager": "kubectl", "operation": "Apply", "apiVersion": "apps/v1", "fieldsType": "FieldsV1",
+/// #         "fieldsV1": { "f:spec": { "f:replicas": {} } },
+/// #     }] },
+/// # })).unwrap();
+/// let managed_fields = ManagedFields::of(&deployment);
+/// assert_eq!(managed_fields.managers_of(".spec.replicas"), vec!["kubectl"]);
+/// ```
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct ManagedFields(Vec<FieldOwnership>);
+
+impl ManagedFields {
+    /// Parses the `.metadata.managedFields` of `obj`
+    pub fn of<K: Resource>(obj: &K) -> Self {
+        Self::from_entries(obj.managed_fields())
+    }
+
+    /// Parses a list of [`ManagedFieldsEntry`]
+    pub fn from_entries(entries: &[ManagedFieldsEntry]) -> Self {
+        Self(entries.iter().map(FieldOwnership::from_entry).collect())
+    }
+
+    /// The ownership of every manager, in the order of `.metadata.managedFields`
+    ///
+    /// A manager can occur multiple times, such as for different operations or subresources.
+    pub fn entries(&self) -> &[FieldOwnership] {
+        &self.0
+    }
+
+    /// The managers that own `field`
+    pub fn managers_of(&self, field: &str) -> Vec<&str> {
+        self.managers_matching(|ownership| ownership.owns(field))
+    }
+
+    /// The managers that own `field`, or any field nested within it
+    pub fn managers_within(&self, field: &str) -> Vec<&str> {
+        self.managers_matching(|ownership| ownership.owns_within(field))
+    }
+
+    /// All fields owned by `manager`, across its operations and subresources
+    pub fn fields_of(&self, manager: &str) -> BTreeSet<&str> {
+        self.0
+            .iter()
+            .filter(|ownership| ownership.manager == manager)
+            .flat_map(|ownership| ownership.fields.iter().map(String::as_str))
+            .collect()
+    }
+
+    fn managers_matching(&self, mut predicate: impl FnMut(&FieldOwnership) -> bool) -> Vec<&str> {
+        let mut managers = Vec::new();
+        for ownership in &self.0 {
+            if predicate(ownership) && !managers.contains(&ownership.manager.as_str()) {
+                managers.push(ownership.manager.as_str());
+            }
+        }
+        managers
+    }
+}
+
+/// Extracts the fields that `field_manager` has applied to `obj`, like the `Extract` functions of client-go
+///
+/// The result has the fields of the `Apply` operation of the manager, along with the type, name and namespace
+/// of the object. Modifying the result and applying it again with the same field manager only changes the
+/// fields of that manager, without taking ownership of the fields that other managers have set.
+///
+/// ```
+/// use k8s_openapi::api::core::v1::ConfigMap;
+/// use kube_core::managed_fields::extract;
+/// # let config_map: ConfigMap = serde_json::from_value(serde_json::json!({
+/// #     "metadata": { "name": "settings", "managedFields": [{
+/// #         "manager": "installer", "operation": "Apply", "apiVersion": "v1", "fieldsType": "FieldsV1",
+/// #         "fieldsV1": { "f:data": { "f:mode": {} } },
+/// #     }] },
+/// #     "data": { "mode": "fast", "added-by-hand": "true" },
+/// # })).unwrap();
+/// let applied = extract(&config_map, "installer")?;
+/// assert_eq!(applied.data, serde_json::json!({ "data": { "mode": "fast" } }));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+pub fn extract<K: Resource + Serialize>(
+    obj: &K,
+    field_manager: &str,
+) -> Result<DynamicObject, serde_json::Error> {
+    extract_applied(obj, field_manager, None)
+}
+
+/// Extracts the fields that `field_manager` has applied to the `status` subresource of `obj`
+///
+/// See [`extract`].
+pub fn extract_status<K: Resource + Serialize>(
+    obj: &K,
+    field_manager: &str,
+) -> Result<DynamicObject, serde_json::Error> {
+    extract_applied(obj, field_manager, Some("status"))
+}
+
+fn extract_applied<K: Resource + Serialize>(
+    obj: &K,
+    field_manager: &str,
+    subresource: Option<&str>,
+) -> Result<DynamicObject, serde_json::Error> {
+    let value = serde_json::to_value(obj)?;
+    let fields = obj
+        .managed_fields()
+        .iter()
+        .find(|entry| {
+            entry.manager.as_deref() == Some(field_manager)
+                && entry.operation.as_deref() == Some("Apply")
+                && entry.subresource.as_deref().filter(|s| !s.is_empty()) == subresource
+        })
+        .and_then(|entry| entry.fields_v1.as_ref());
+    let mut extracted = match fields.map(|fields| extract_fields(&fields.0, &value)) {
+        Some(Value::Object(extracted)) => extracted,
+        _ => Map::new(),
+    };
+
+    for key in ["apiVersion", "kind"] {
+        if let Some(types) = value.get(key) {
+            extracted.insert(key.to_owned(), types.clone());
+        }
+    }
+    let metadata = extracted
+        .entry("metadata")
+        .or_insert_with(|| Value::Object(Map::new()));
+    if let Some(metadata) = metadata.as_object_mut() {
+        for key in ["name", "namespace"] {
+            if let Some(id) = value.pointer(&format!("/metadata/{key}")) {
+                metadata.insert(key.to_owned(), id.clone());
+            }
+        }
+    }
+    serde_json::from_value(Value::Object(extracted))
+}
+
+/// The parts of `value` that are in a `FieldsV1` trie, see [`collect_fields`] for its format
+fn extract_fields(trie: &Value, value: &Value) -> Value {
+    let trie = match trie.as_object() {
+        Some(trie) if !trie.is_empty() => trie,
+        _ => return value.clone(),
+    };
+    match value {
+        Value::Object(object) => Value::Object(
+            trie.iter()
+                .filter_map(|(key, child)| {
+                    let name = key.strip_prefix("f:")?;
+                    let field = object.get(name)?;
+                    Some((name.to_owned(), extract_fields(child, field)))
+                })
+                .collect(),
+        ),
+        Value::Array(items) => Value::Array(
+            items
+                .iter()
+                .enumerate()
+                .filter_map(|(index, item)| {
+                    trie.iter()
+                        .find_map(|(key, child)| extract_item(key, child, index, item))
+                })
+                .collect(),
+        ),
+        _ => value.clone(),
+    }
+}
+
+/// The parts of a list item, if the trie `key` identifies the item
+fn extract_item(key: &str, child: &Value, index: usize, item: &Value) -> Option<Value> {
+    if let Some(keys) = key.strip_prefix("k:") {
+        let keys = serde_json::from_str::<Map<String, Value>>(keys).ok()?;
+        if !keys.iter().all(|(name, key)| item.get(name) == Some(key)) {
+            return None;
+        }
+        // list items are identified by their keys, so these are always kept
+        let mut extracted = extract_fields(child, item);
+        if let Some(extracted) = extracted.as_object_mut() {
+            extracted.extend(keys);
+        }
+        Some(extracted)
+    } else if let Some(value) = key.strip_prefix("v:") {
+        (serde_json::from_str::<Value>(value).ok()? == *item).then(|| item.clone())
+    } else if let Some(i) = key.strip_prefix("i:") {
+        (i.parse::<usize>().ok()? == index).then(|| extract_fields(child, item))
+    } else {
+        None
+    }
+}
+
+/// Whether `path` is `parent`, or nested within it
+pub(crate) fn is_within(path: &str, parent: &str) -> bool {
+    path.strip_prefix(parent)
+        .is_some_and(|nested| nested.is_empty() || nested.starts_with('.') || nested.starts_with('['))
+}
+
+/// Flattens a `FieldsV1` trie into the paths of the fields that it contains
+///
+/// Every key is a path element, where `f:` is a field name, `k:` identifies a list item by its keys,
+/// `v:` identifies a set item by its value, and `i:` identifies a list item by its index.
+/// Empty objects are leaves, and a `.` key marks that the object itself is owned along with some of its fields.
+fn collect_fields(trie: &Value, prefix: &str, fields: &mut BTreeSet<String>) {
+    let Some(trie) = trie.as_object() else {
+        return;
+    };
+    for (key, child) in trie {
+        if key == "." {
+            fields.insert(prefix.to_string());
+            continue;
+        }
+        let element = if let Some(name) = key.strip_prefix("f:") {
+            format!(".{name}")
+        } else if let Some(keys) = key.strip_prefix("k:") {
+            match serde_json::from_str::<serde_json::Map<String, Value>>(keys) {
+                Ok(keys) => {
+                    let keys = keys
+                        .iter()
+                        .map(|(name, value)| format!("{name}={value}"))
+                        .collect::<Vec<_>>();
+                    format!("[{}]", keys.join(","))
+                }
+                Err(_) => format!("[{keys}]"),
+            }
+        } else if let Some(value) = key.strip_prefix("v:") {
+            format!("[={value}]")
+        } else if let Some(index) = key.strip_prefix("i:") {
+            format!("[{index}]")
+        } else {
+            continue;
+        };
+        let path = format!("{prefix}{element}");
+        if child.as_object().is_some_and(|child| !child.is_empty()) {
+            collect_fields(child, &path, fields);
+        } else {
+            fields.insert(path);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{extract, extract_status, ManagedFields};
+    use crate::DynamicObject;
+    use k8s_openapi::apimachinery::pkg::apis::meta::v1::ManagedFieldsEntry;
+    use serde_json::json;
+
+    fn entries() -> Vec<ManagedFieldsEntry> {
+        serde_json::from_value(json!([
+            {
+                "manager": "kubectl",
+                "operation": "Apply",
+                "apiVersion": "apps/v1",
+                "fieldsType": "FieldsV1",
+                "fieldsV1": {
+                    "f:metadata": { "f:labels": { "f:app": {} } },
+                    "f:spec": {
+                        "f:replicas": {},
+                        "f:template": { "f:spec": { "f:containers": {
+                            "k:{\"name\":\"web\"}": { ".": {}, "f:image": {}, "f:name": {} },
+                        } } },
+                    },
+                },
+            },
+            {
+                "manager": "hpa",
+                "operation": "Update",
+                "apiVersion": "apps/v1",
+                "fieldsType": "FieldsV1",
+                "fieldsV1": { "f:spec": { "f:replicas": {} } },
+            },
+            {
+                "manager": "kube-controller-manager",
+                "operation": "Update",
+                "apiVersion": "apps/v1",
+                "subresource": "status",
+                "fieldsType": "FieldsV1",
+                "fieldsV1": {
+                    "f:metadata": { "f:finalizers": { "v:\"example.com/cleanup\"": {} } },
+                    "f:status": { "f:conditions": { "i:0": {} } },
+                },
+            },
+        ]))
+        .unwrap()
+    }
+
+    #[test]
+    fn parses_field_paths() {
+        let managed_fields = ManagedFields::from_entries(&entries());
+        let kubectl = &managed_fields.entries()[0];
+        assert_eq!(
+            kubectl.fields.iter().map(String::as_str).collect::<Vec<_>>(),
+            vec![
+                ".metadata.labels.app",
+                ".spec.replicas",
+                ".spec.template.spec.containers[name=\"web\"]",
+                ".spec.template.spec.containers[name=\"web\"].image",
+                ".spec.template.spec.containers[name=\"web\"].name",
+            ]
+        );
+        let status = &managed_fields.entries()[2];
+        assert_eq!(status.subresource.as_deref(), Some("status"));
+        assert!(status.owns(".metadata.finalizers[=\"example.com/cleanup\"]"));
+        assert!(status.owns(".status.conditions[0]"));
+    }
+
+    #[test]
+    fn extracts_applied_fields() {
+        let mut deployment: DynamicObject = serde_json::from_value(json!({
+            "apiVersion": "apps/v1",
+            "kind": "Deployment",
+            "metadata": {
+                "name": "web",
+                "namespace": "blog",
+                "labels": { "app": "web", "team": "blog" },
+                "finalizers": ["example.com/cleanup", "other.com/cleanup"],
+            },
+            "spec": {
+                "replicas": 5,
+                "template": { "spec": { "containers": [
+                    { "name": "proxy", "image": "envoy" },
+                    { "name": "web", "image": "nginx", "imagePullPolicy": "Always" },
+                ] } },
+            },
+            "status": { "replicas": 5, "conditions": [{ "type": "Available" }] },
+        }))
+        .unwrap();
+        deployment.metadata.managed_fields = Some(entries());
+
+        assert_eq!(
+            serde_json::to_value(extract(&deployment, "kubectl").unwrap()).unwrap(),
+            json!({
+                "apiVersion": "apps/v1",
+                "kind": "Deployment",
+                "metadata": { "name": "web", "namespace": "blog", "labels": { "app": "web" } },
+                "spec": {
+                    "replicas": 5,
+                    "template": { "spec": { "containers": [{ "name": "web", "image": "nginx" }] } },
+                },
+            })
+        );
+        // only applied fields are extracted
+        assert_eq!(
+            serde_json::to_value(extract(&deployment, "hpa").unwrap()).unwrap(),
+            json!({
+                "apiVersion": "apps/v1",
+                "kind": "Deployment",
+                "metadata": { "name": "web", "namespace": "blog" },
+            })
+        );
+
+        let entry = &mut deployment.metadata.managed_fields.as_mut().unwrap()[2];
+        entry.operation = Some("Apply".into());
+        assert_eq!(
+            serde_json::to_value(extract_status(&deployment, "kube-controller-manager").unwrap()).unwrap(),
+            json!({
+                "apiVersion": "apps/v1",
+                "kind": "Deployment",
+                "metadata": { "name": "web", "namespace": "blog", "finalizers": ["example.com/cleanup"] },
+                "status": { "conditions": [{ "type": "Available" }] },
+            })
+        );
+    }
+
+    #[test]
+    fn finds_owners() {
+        let managed_fields = ManagedFields::from_entries(&entries());
+        assert_eq!(managed_fields.managers_of(".spec.replicas"), vec![
+            "kubectl", "hpa"
+        ]);
+        assert!(managed_fields.managers_of(".spec").is_empty());
+        assert_eq!(managed_fields.managers_within(".spec.template"), vec!["kubectl"]);
+        assert_eq!(managed_fields.managers_within(".status"), vec![
+            "kube-controller-manager"
+        ]);
+        assert!(managed_fields.managers_within(".spec.rep").is_empty());
+        assert_eq!(
+            managed_fields.fields_of("hpa").into_iter().collect::<Vec<_>>(),
+            vec![".spec.replicas"]
+        );
+    }
+}
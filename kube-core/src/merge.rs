@@ -0,0 +1,127 @@
+//! Merging of objects that respects the merge keys of lists, like strategic merge patches.
+use serde_json::Value;
+
+/// Merges the set fields of `patch` into `target`
+///
+/// This follows the rules of a [strategic merge patch], without its directives:
+///
+/// - objects are merged field by field, recursively
+/// - `null` fields of `patch` are left unset, rather than deleting the field from `target`
+/// - lists listed in `merge_keys` are merged item by item, matching the items by their merge key,
+///   and appending the items of `patch` that are not in `target`
+/// - all other values, including other lists, are replaced
+///
+/// `merge_keys` are `(path, key)` pairs, where `path` is the dotted path of object fields from the root,
+/// such as `.spec.containers`. Lists are not part of the path, so `.spec.containers.ports` are the ports
+/// of each container.
+///
+/// ```
+/// use kube::core::merge::merge;
+/// use serde_json::json;
+///
+/// let mut defaults = json!({"spec": {"replicas": 1, "ports": [{"name": "http", "port": 80}]}});
+/// let overrides = json!({"spec": {"replicas": null, "ports": [{"name": "http", "port": 8080}, {"name": "metrics", "port": 9090}]}});
+/// merge(&mut defaults, &overrides, &[(".spec.ports", "name")]);
+/// assert_eq!(
+///     defaults,
+///     json!({"spec": {"replicas": 1, "ports": [{"name": "http", "port": 8080}, {"name": "metrics", "port": 9090}]}})
+/// );
+/// ```
+///
+/// [strategic merge patch]: https://kubernetes.io/docs/tasks/manage-kubernetes-objects/update-api-object-kubectl-patch/#notes-on-the-strategic-merge-patch
+pub fn merge(target: &mut Value, patch: &Value, merge_keys: &[(&str, &str)]) {
+    merge_at(target, patch, &mut String::new(), merge_keys);
+}
+
+fn merge_at(target: &mut Value, patch: &Value, path: &mut String, merge_keys: &[(&str, &str)]) {
+    match (target, patch) {
+        (_, Value::Null) => {}
+        (Value::Object(target), Value::Object(patch)) => {
+            for (field, value) in patch {
+                if value.is_null() {
+                    continue;
+                }
+                let len = path.len();
+                path.push('.');
+                path.push_str(field);
+                match target.get_mut(field) {
+                    Some(existing) => merge_at(existing, value, path, merge_keys),
+                    None => {
+                        target.insert(field.clone(), value.clone());
+                    }
+                }
+                path.truncate(len);
+            }
+        }
+        (Value::Array(target), Value::Array(patch)) => {
+            let Some((_, key)) = merge_keys.iter().find(|(keyed, _)| *keyed == path.as_str()) else {
+                target.clone_from(patch);
+                return;
+            };
+            for item in patch {
+                let existing = item
+                    .get(key)
+                    .filter(|id| !id.is_null())
+                    .and_then(|id| target.iter().position(|other| other.get(key) == Some(id)));
+                match existing {
+                    Some(index) => merge_at(&mut target[index], item, path, merge_keys),
+                    None => target.push(item.clone()),
+                }
+            }
+        }
+        (target, patch) => target.clone_from(patch),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::merge;
+    use serde_json::json;
+
+    #[test]
+    fn nested_lists_are_merged_by_key() {
+        let mut target = json!({
+            "metadata": {"labels": {"app": "blog"}},
+            "spec": {
+                "args": ["--verbose"],
+                "containers": [
+                    {"name": "app", "image": "blog:1", "ports": [{"name": "http", "port": 80}]},
+                    {"name": "proxy", "image": "envoy"},
+                ],
+            },
+        });
+        let patch = json!({
+            "metadata": {"labels": {"tier": "web"}},
+            "spec": {
+                "args": ["--quiet"],
+                "containers": [
+                    {"name": "app", "image": null, "ports": [{"name": "metrics", "port": 9090}]},
+                    {"name": "sidecar", "image": "logger"},
+                    {"image": "unnamed"},
+                ],
+            },
+        });
+        merge(&mut target, &patch, &[
+            (".spec.containers", "name"),
+            (".spec.containers.ports", "name"),
+        ]);
+        assert_eq!(
+            target,
+            json!({
+                "metadata": {"labels": {"app": "blog", "tier": "web"}},
+                "spec": {
+                    "args": ["--quiet"],
+                    "containers": [
+                        {"name": "app", "image": "blog:1", "ports": [
+                            {"name": "http", "port": 80},
+                            {"name": "metrics", "port": 9090},
+                        ]},
+                        {"name": "proxy", "image": "envoy"},
+                        {"name": "sidecar", "image": "logger"},
+                        {"image": "unnamed"},
+                    ],
+                },
+            })
+        );
+    }
+}
@@ -50,7 +50,7 @@ where
     }))
 }
 
-fn deserialize_null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
+pub(crate) fn deserialize_null_as_default<'de, D, T>(deserializer: D) -> Result<T, D::Error>
 where
     T: Default + Deserialize<'de>,
     D: Deserializer<'de>,
@@ -103,6 +103,35 @@ impl<T: Clone> ObjectList<T> {
     pub fn iter_mut(&mut self) -> impl Iterator<Item = &mut T> {
         self.items.iter_mut()
     }
+
+    /// The resourceVersion of the collection as a whole
+    ///
+    /// This can be passed to [`ListParams::at`](crate::params::ListParams::at) together with
+    /// [`VersionMatch::Exact`](crate::params::VersionMatch::Exact) to list the same snapshot again.
+    pub fn resource_version(&self) -> Option<&str> {
+        self.metadata
+            .resource_version
+            .as_deref()
+            .filter(|rv| !rv.is_empty())
+    }
+
+    /// The token to pass to [`ListParams::continue_token`](crate::params::ListParams::continue_token) to fetch the next page
+    ///
+    /// This is `None` when the list was not limited, or when this is the last page.
+    pub fn continue_token(&self) -> Option<&str> {
+        self.metadata
+            .continue_
+            .as_deref()
+            .filter(|token| !token.is_empty())
+    }
+
+    /// The number of items remaining after this page, if the apiserver could estimate it
+    ///
+    /// This is only set on limited lists that have more pages, and is not set when the list
+    /// contains a label or field selector. It may be an estimate when served from a continue token.
+    pub fn remaining_item_count(&self) -> Option<i64> {
+        self.metadata.remaining_item_count
+    }
 }
 
 impl<T: Clone> IntoIterator for ObjectList<T> {
@@ -425,4 +454,30 @@ mod test {
             pod_list.types,
         );
     }
+
+    #[test]
+    fn k8s_object_list_pagination() {
+        use k8s_openapi::api::core::v1::Pod;
+
+        let page: ObjectList<Pod> = serde_json::from_value(serde_json::json!({
+            "metadata": {
+                "resourceVersion": "1234",
+                "continue": "eyJ2IjoibWV0YS5rOHMuaW8vdjEifQ",
+                "remainingItemCount": 50
+            },
+            "items": []
+        }))
+        .unwrap();
+        assert_eq!(page.resource_version(), Some("1234"));
+        assert_eq!(page.continue_token(), Some("eyJ2IjoibWV0YS5rOHMuaW8vdjEifQ"));
+        assert_eq!(page.remaining_item_count(), Some(50));
+
+        let last: ObjectList<Pod> = serde_json::from_value(serde_json::json!({
+            "metadata": { "resourceVersion": "1234", "continue": "" },
+            "items": []
+        }))
+        .unwrap();
+        assert_eq!(last.continue_token(), None);
+        assert_eq!(last.remaining_item_count(), None);
+    }
 }
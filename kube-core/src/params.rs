@@ -1,5 +1,5 @@
 //! A port of request parameter *Optionals from apimachinery/types.go
-use crate::{request::Error, Selector};
+use crate::{request::Error, FieldSelector, Selector};
 use serde::Serialize;
 
 /// Controls how the resource version parameter is applied for list calls
@@ -191,6 +191,19 @@ impl ListParams {
         self
     }
 
+    /// Configure typed field selectors
+    ///
+    /// ```
+    /// use kube::core::{fields, params::ListParams, FieldSelector};
+    /// let selector = FieldSelector::default().equal(fields::METADATA_NAME, "my-pod");
+    /// let lp = ListParams::default().fields_from(&selector);
+    /// ```
+    #[must_use]
+    pub fn fields_from(mut self, selector: &FieldSelector) -> Self {
+        self.field_selector = Some(selector.to_string());
+        self
+    }
+
     /// Sets a result limit.
     #[must_use]
     pub fn limit(mut self, limit: u32) -> Self {
@@ -199,6 +212,25 @@ impl ListParams {
     }
 
     /// Sets a continue token.
+    ///
+    /// Every page fetched with the tokens of a limited list is served from the same snapshot as the first page,
+    /// so a manual pagination loop can follow [`ObjectList::continue_token`](crate::ObjectList::continue_token)
+    /// and report progress with [`ObjectList::remaining_item_count`](crate::ObjectList::remaining_item_count).
+    ///
+    /// ```
+    /// use kube::api::{ListParams, ObjectList};
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # let page: ObjectList<Pod> = serde_json::from_value(serde_json::json!({
+    /// #     "metadata": { "resourceVersion": "1234", "continue": "next", "remainingItemCount": 50 },
+    /// #     "items": []
+    /// # })).unwrap();
+    /// let lp = ListParams::default().limit(100);
+    /// // let page = api.list(&lp).await?;
+    /// if let Some(token) = page.continue_token() {
+    ///     println!("{} items left", page.remaining_item_count().unwrap_or_default());
+    ///     let next = lp.continue_token(token);
+    /// }
+    /// ```
     #[must_use]
     pub fn continue_token(mut self, token: &str) -> Self {
         self.continue_token = Some(token.to_string());
@@ -479,6 +511,19 @@ impl WatchParams {
         self
     }
 
+    /// Configure typed field selectors
+    ///
+    /// ```
+    /// use kube::core::{fields, params::WatchParams, FieldSelector};
+    /// let selector = FieldSelector::default().equal(fields::METADATA_NAME, "my-pod");
+    /// let wp = WatchParams::default().fields_from(&selector);
+    /// ```
+    #[must_use]
+    pub fn fields_from(mut self, selector: &FieldSelector) -> Self {
+        self.field_selector = Some(selector.to_string());
+        self
+    }
+
     /// Disables watch bookmarks to simplify watch handling
     ///
     /// This is not recommended to use with production watchers as it can cause desyncs.
@@ -845,6 +890,26 @@ impl DeleteParams {
         self
     }
 
+    /// Only delete the object if it still has the UID `uid`
+    ///
+    /// This guards against deleting an object that was recreated under the same name.
+    #[must_use]
+    pub fn with_uid_precondition(mut self, uid: impl Into<String>) -> Self {
+        self.preconditions.get_or_insert_with(Preconditions::default).uid = Some(uid.into());
+        self
+    }
+
+    /// Only delete the object if it is still at the resource version `resource_version`
+    ///
+    /// This guards against deleting an object that was modified since it was last read.
+    #[must_use]
+    pub fn with_resource_version_precondition(mut self, resource_version: impl Into<String>) -> Self {
+        self.preconditions
+            .get_or_insert_with(Preconditions::default)
+            .resource_version = Some(resource_version.into());
+        self
+    }
+
     pub(crate) fn is_default(&self) -> bool {
         !self.dry_run
             && self.grace_period_seconds.is_none()
@@ -907,6 +972,21 @@ mod test {
         assert_eq!(ser, serde_json::json!({"propagationPolicy": "Orphan"}));
     }
 
+    #[test]
+    fn delete_param_preconditions() {
+        let dp = DeleteParams::foreground()
+            .with_uid_precondition("5c1c4b2e")
+            .with_resource_version_precondition("42");
+        let ser = serde_json::to_value(dp).unwrap();
+        assert_eq!(
+            ser,
+            serde_json::json!({
+                "propagationPolicy": "Foreground",
+                "preconditions": {"uid": "5c1c4b2e", "resourceVersion": "42"},
+            })
+        );
+    }
+
     #[test]
     fn patch_param_serializes_field_validation() {
         let pp = PatchParams::default().validation_ignore();
@@ -0,0 +1,682 @@
+//! Kubernetes resource [`Quantity`]s.
+use k8s_openapi::apimachinery::pkg::api::resource::Quantity as QuantityString;
+use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
+use std::{
+    cmp::Ordering,
+    fmt,
+    hash::{Hash, Hasher},
+    iter,
+    num::IntErrorKind,
+    ops,
+    str::FromStr,
+};
+
+/// A Kubernetes resource quantity, such as `500m` of CPU or `1.5Gi` of memory.
+///
+/// This is equivalent to the [`resource.Quantity`] type in the Go Kubernetes
+/// apimachinery package. Unlike [`k8s_openapi`]'s `Quantity`, which only wraps
+/// the string, this type holds the parsed value, so quantities can be compared
+/// and added up, and it serializes to the same canonical form as the apiserver.
+///
+/// ```
+/// use kube::core::Quantity;
+///
+/// let memory: Quantity = "1.5Gi".parse()?;
+/// assert_eq!(memory.to_string(), "1536Mi");
+///
+/// let cpu: Quantity = "0.5".parse()?;
+/// let total = cpu + "250m".parse::<Quantity>()?;
+/// assert_eq!(total.to_string(), "750m");
+/// assert!(total < "1".parse::<Quantity>()?);
+/// # Ok::<(), kube::core::quantity::ParseError>(())
+/// ```
+///
+/// # Precision
+///
+/// Like in Go, values are exact decimals. Values more precise than `1n` are
+/// rounded up to it, away from zero. Values with more than 38 significant
+/// digits are out of range.
+///
+/// # Format
+///
+/// A [`Quantity`] remembers whether it was written with a binary suffix
+/// (`Ki`), a decimal suffix (`k`) or an exponent (`e3`), and is formatted with
+/// the largest suffix of the same [`Format`] that keeps the number an integer.
+/// The format is ignored when comparing quantities.
+///
+/// [`resource.Quantity`]: https://pkg.go.dev/k8s.io/apimachinery/pkg/api/resource#Quantity
+#[derive(Copy, Clone, Default)]
+pub struct Quantity {
+    /// Never a multiple of 10, unless it is zero
+    mantissa: i128,
+    /// The power of 10 of the `mantissa`, at least [`MIN_EXPONENT`], and zero for zero
+    exponent: i32,
+    format: Format,
+}
+
+/// The suffixes a [`Quantity`] is formatted with.
+#[derive(Copy, Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub enum Format {
+    /// Powers of 2, such as `Ki` or `Mi`.
+    ///
+    /// Numbers that are not integers, or are smaller than `1Ki`, are formatted as [`Format::DecimalSI`].
+    BinarySI,
+    /// Powers of 10, such as `m` or `k`.
+    #[default]
+    DecimalSI,
+    /// Exponents that are multiples of 3, such as `e3` or `e-3`.
+    DecimalExponent,
+}
+
+/// Errors returned by the [`FromStr`] implementation for [`Quantity`].
+#[derive(Debug, thiserror::Error, Eq, PartialEq)]
+#[non_exhaustive]
+pub enum ParseError {
+    /// The quantity does not start with a number.
+    #[error("quantities must start with a number")]
+    InvalidNumber,
+
+    /// The suffix is not one of the suffixes of a [`Format`].
+    #[error("invalid suffix: {}", EXPECTED_SUFFIXES)]
+    InvalidSuffix,
+
+    /// The quantity has more than 38 significant digits, or its exponent is too large.
+    #[error("quantity is out of range")]
+    OutOfRange,
+}
+
+const EXPECTED_SUFFIXES: &str =
+    "expected one of 'n', 'u', 'm', 'k', 'M', 'G', 'T', 'P', 'E', 'Ki', 'Mi', 'Gi', 'Ti', 'Pi', 'Ei', or an exponent";
+
+const DECIMAL_SUFFIXES: [(i32, &str); 10] = [
+    (-9, "n"),
+    (-6, "u"),
+    (-3, "m"),
+    (0, ""),
+    (3, "k"),
+    (6, "M"),
+    (9, "G"),
+    (12, "T"),
+    (15, "P"),
+    (18, "E"),
+];
+
+const BINARY_SUFFIXES: [(u32, &str); 6] = [
+    (10, "Ki"),
+    (20, "Mi"),
+    (30, "Gi"),
+    (40, "Ti"),
+    (50, "Pi"),
+    (60, "Ei"),
+];
+
+/// The exponent of `1n`, the smallest quantity
+const MIN_EXPONENT: i32 = -9;
+const MAX_MANTISSA: u128 = 10u128.pow(38);
+
+impl Quantity {
+    /// Normalizes `mantissa * 10^exponent`, rounding it up to [`MIN_EXPONENT`]
+    fn new(mantissa: i128, exponent: i64, format: Format) -> Option<Self> {
+        let (mut mantissa, mut exponent) = if exponent < i64::from(MIN_EXPONENT) {
+            let shift = i64::from(MIN_EXPONENT) - exponent;
+            (round_up(mantissa, shift), i64::from(MIN_EXPONENT))
+        } else {
+            (mantissa, exponent)
+        };
+        if mantissa == 0 {
+            exponent = 0;
+        }
+        while mantissa != 0 && mantissa % 10 == 0 {
+            mantissa /= 10;
+            exponent += 1;
+        }
+        (mantissa.unsigned_abs() < MAX_MANTISSA).then_some(Self {
+            mantissa,
+            exponent: i32::try_from(exponent).ok()?,
+            format,
+        })
+    }
+
+    /// Creates a quantity of `value` thousandths, such as `500m` of CPU.
+    #[must_use]
+    pub fn from_milli(value: i64) -> Self {
+        Self::new(value.into(), -3, Format::DecimalSI).expect("i64 is in range")
+    }
+
+    /// Returns the [`Format`] the quantity is formatted with.
+    #[must_use]
+    pub fn format(&self) -> Format {
+        self.format
+    }
+
+    /// Returns the quantity formatted with `format` instead.
+    #[must_use]
+    pub fn with_format(mut self, format: Format) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Returns `true` if the quantity is zero.
+    #[must_use]
+    pub fn is_zero(&self) -> bool {
+        self.mantissa == 0
+    }
+
+    /// Returns `true` if the quantity is negative.
+    #[must_use]
+    pub fn is_negative(&self) -> bool {
+        self.mantissa < 0
+    }
+
+    /// Returns the quantity rounded up to an integer, away from zero, like `Value()` in Go.
+    ///
+    /// Returns `None` if the value does not fit in an `i64`.
+    #[must_use]
+    pub fn value(&self) -> Option<i64> {
+        self.scaled_value(0)
+    }
+
+    /// Returns the quantity in thousandths, rounded up away from zero, like `MilliValue()` in Go.
+    ///
+    /// Returns `None` if the value does not fit in an `i64`.
+    #[must_use]
+    pub fn milli_value(&self) -> Option<i64> {
+        self.scaled_value(-3)
+    }
+
+    /// Returns the quantity in units of `10^exponent`, rounded up away from zero, like `ScaledValue()` in Go.
+    ///
+    /// Returns `None` if the value does not fit in an `i64`.
+    #[must_use]
+    pub fn scaled_value(&self, exponent: i32) -> Option<i64> {
+        let shift = i64::from(self.exponent) - i64::from(exponent);
+        let value = if shift >= 0 {
+            scale(self.mantissa, shift)?
+        } else {
+            round_up(self.mantissa, -shift)
+        };
+        i64::try_from(value).ok()
+    }
+
+    /// Returns the closest `f64` to the quantity.
+    #[must_use]
+    pub fn to_f64(&self) -> f64 {
+        format!("{}e{}", self.mantissa, self.exponent)
+            .parse()
+            .expect("quantity is a valid float")
+    }
+
+    /// Adds `other`, returning `None` if the sum is out of range.
+    ///
+    /// The sum keeps the [`Format`] of `self`, unless `self` is zero.
+    #[must_use]
+    pub fn checked_add(self, other: Self) -> Option<Self> {
+        let (lhs, rhs, exponent) = align(&self, &other)?;
+        Self::new(lhs.checked_add(rhs)?, exponent.into(), self.format_with(&other))
+    }
+
+    /// Subtracts `other`, returning `None` if the difference is out of range.
+    ///
+    /// The difference keeps the [`Format`] of `self`, unless `self` is zero.
+    #[must_use]
+    pub fn checked_sub(self, other: Self) -> Option<Self> {
+        let (lhs, rhs, exponent) = align(&self, &other)?;
+        Self::new(lhs.checked_sub(rhs)?, exponent.into(), self.format_with(&other))
+    }
+
+    /// The format of the result of adding `other` to `self`, which is the format of `other` for a zero `self`
+    fn format_with(&self, other: &Self) -> Format {
+        if self.is_zero() {
+            other.format
+        } else {
+            self.format
+        }
+    }
+
+    /// The integer value with the largest binary suffix that keeps it an integer
+    ///
+    /// Returns `None` for values that are not integers, or are smaller than `1Ki`.
+    fn binary_si(&self) -> Option<(i128, &'static str)> {
+        let mut value = scale(self.mantissa, self.exponent.into())?;
+        if value.unsigned_abs() < 1024 {
+            return None;
+        }
+        let mut suffix = "";
+        for (_, next) in BINARY_SUFFIXES {
+            if value % 1024 != 0 {
+                break;
+            }
+            value /= 1024;
+            suffix = next;
+        }
+        Some((value, suffix))
+    }
+}
+
+/// `mantissa * 10^shift`, or `None` if it overflows
+fn scale(mantissa: i128, shift: i64) -> Option<i128> {
+    if mantissa == 0 {
+        return Some(0);
+    }
+    mantissa.checked_mul(10i128.checked_pow(u32::try_from(shift).ok()?)?)
+}
+
+/// `mantissa / 10^shift`, rounded up away from zero
+fn round_up(mantissa: i128, shift: i64) -> i128 {
+    match u32::try_from(shift)
+        .ok()
+        .and_then(|shift| 10i128.checked_pow(shift))
+    {
+        Some(divisor) if mantissa % divisor != 0 => mantissa / divisor + mantissa.signum(),
+        Some(divisor) => mantissa / divisor,
+        // the divisor is larger than any mantissa
+        None => mantissa.signum(),
+    }
+}
+
+/// The mantissas of both quantities scaled to the smaller of their exponents, or `None` if that overflows
+fn align(lhs: &Quantity, rhs: &Quantity) -> Option<(i128, i128, i32)> {
+    let exponent = lhs.exponent.min(rhs.exponent);
+    let scaled = |q: &Quantity| scale(q.mantissa, i64::from(q.exponent) - i64::from(exponent));
+    Some((scaled(lhs)?, scaled(rhs)?, exponent))
+}
+
+impl From<i64> for Quantity {
+    fn from(value: i64) -> Self {
+        Self::new(value.into(), 0, Format::DecimalSI).expect("i64 is in range")
+    }
+}
+
+impl From<Quantity> for QuantityString {
+    fn from(quantity: Quantity) -> Self {
+        QuantityString(quantity.to_string())
+    }
+}
+
+impl TryFrom<&QuantityString> for Quantity {
+    type Error = ParseError;
+
+    fn try_from(quantity: &QuantityString) -> Result<Self, Self::Error> {
+        quantity.0.parse()
+    }
+}
+
+impl TryFrom<QuantityString> for Quantity {
+    type Error = ParseError;
+
+    fn try_from(quantity: QuantityString) -> Result<Self, Self::Error> {
+        quantity.0.parse()
+    }
+}
+
+impl FromStr for Quantity {
+    type Err = ParseError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        // implements the same format as
+        // https://github.com/kubernetes/apimachinery/blob/v0.30.0/pkg/api/resource/quantity.go#L141
+        let (is_negative, s) = match s.strip_prefix('-') {
+            Some(rest) => (true, rest),
+            None => (false, s.strip_prefix('+').unwrap_or(s)),
+        };
+        let number_end = s
+            .find(|c: char| !c.is_ascii_digit() && c != '.')
+            .unwrap_or(s.len());
+        let (number, suffix) = s.split_at(number_end);
+        let (integer, fraction) = number.split_once('.').unwrap_or((number, ""));
+        if (integer.is_empty() && fraction.is_empty()) || fraction.contains('.') {
+            return Err(ParseError::InvalidNumber);
+        }
+
+        let (exponent, binary_shift, format) =
+            if let Some((exponent, _)) = DECIMAL_SUFFIXES.iter().find(|(_, decimal)| *decimal == suffix) {
+                (i64::from(*exponent), 0, Format::DecimalSI)
+            } else if let Some((shift, _)) = BINARY_SUFFIXES.iter().find(|(_, binary)| *binary == suffix) {
+                (0, *shift, Format::BinarySI)
+            } else if let Some(exponent) = suffix.strip_prefix(['e', 'E']) {
+                let exponent = exponent.parse::<i32>().map_err(|err| match err.kind() {
+                    IntErrorKind::PosOverflow | IntErrorKind::NegOverflow => ParseError::OutOfRange,
+                    _ => ParseError::InvalidSuffix,
+                })?;
+                (i64::from(exponent), 0, Format::DecimalExponent)
+            } else {
+                return Err(ParseError::InvalidSuffix);
+            };
+
+        let digits = format!("{integer}{fraction}");
+        let significant = digits.trim_start_matches('0').trim_end_matches('0');
+        let trailing_zeros = digits.len() - digits.trim_end_matches('0').len();
+        if significant.len() > 38 {
+            return Err(ParseError::OutOfRange);
+        }
+        let mut mantissa = if significant.is_empty() {
+            0
+        } else {
+            significant
+                .parse::<i128>()
+                .map_err(|_| ParseError::InvalidNumber)?
+        };
+        if is_negative {
+            mantissa = -mantissa;
+        }
+        let mantissa = mantissa
+            .checked_mul(1 << binary_shift)
+            .ok_or(ParseError::OutOfRange)?;
+        // both lengths are bounded by the length of the string
+        let exponent = exponent + trailing_zeros as i64 - fraction.len() as i64;
+        Self::new(mantissa, exponent, format).ok_or(ParseError::OutOfRange)
+    }
+}
+
+/// Formats the quantity in canonical form, the same as the apiserver
+///
+/// The number is an integer with the largest suffix of its [`Format`] that keeps it one, such as `1536Mi`, `1500m` or `15e2`.
+impl fmt::Display for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        // implements the same format as
+        // https://github.com/kubernetes/apimachinery/blob/v0.30.0/pkg/api/resource/quantity.go#L362
+        if self.format == Format::BinarySI {
+            if let Some((value, suffix)) = self.binary_si() {
+                return write!(f, "{value}{suffix}");
+            }
+        }
+
+        // the largest multiple of 3 that keeps the number an integer
+        let exponent = self.exponent.div_euclid(3) * 3;
+        let exponent = match self.format {
+            Format::DecimalExponent => exponent,
+            Format::BinarySI | Format::DecimalSI => exponent.min(18),
+        };
+        write!(f, "{}", self.mantissa)?;
+        for _ in exponent..self.exponent {
+            f.write_str("0")?;
+        }
+        match self.format {
+            Format::DecimalExponent if exponent == 0 => Ok(()),
+            Format::DecimalExponent => write!(f, "e{exponent}"),
+            Format::BinarySI | Format::DecimalSI => {
+                let (_, suffix) = DECIMAL_SUFFIXES
+                    .iter()
+                    .find(|(decimal, _)| *decimal == exponent)
+                    .expect("exponent is a multiple of 3 between -9 and 18");
+                f.write_str(suffix)
+            }
+        }
+    }
+}
+
+impl fmt::Debug for Quantity {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        fmt::Display::fmt(self, f)
+    }
+}
+
+impl PartialEq for Quantity {
+    fn eq(&self, other: &Self) -> bool {
+        self.mantissa == other.mantissa && self.exponent == other.exponent
+    }
+}
+
+impl Eq for Quantity {}
+
+impl Hash for Quantity {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        self.mantissa.hash(state);
+        self.exponent.hash(state);
+    }
+}
+
+impl PartialOrd for Quantity {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Quantity {
+    fn cmp(&self, other: &Self) -> Ordering {
+        match align(self, other) {
+            Some((lhs, rhs, _)) => lhs.cmp(&rhs),
+            // the quantity that could not be scaled down is larger than any mantissa,
+            // so its sign decides the order
+            None if self.exponent > other.exponent => self.mantissa.cmp(&0),
+            None => 0.cmp(&other.mantissa),
+        }
+    }
+}
+
+impl ops::Add for Quantity {
+    type Output = Self;
+
+    fn add(self, other: Self) -> Self {
+        self.checked_add(other)
+            .expect("attempt to add quantities out of range")
+    }
+}
+
+impl ops::AddAssign for Quantity {
+    fn add_assign(&mut self, other: Self) {
+        *self = *self + other;
+    }
+}
+
+impl ops::Sub for Quantity {
+    type Output = Self;
+
+    fn sub(self, other: Self) -> Self {
+        self.checked_sub(other)
+            .expect("attempt to subtract quantities out of range")
+    }
+}
+
+impl ops::SubAssign for Quantity {
+    fn sub_assign(&mut self, other: Self) {
+        *self = *self - other;
+    }
+}
+
+impl ops::Neg for Quantity {
+    type Output = Self;
+
+    fn neg(mut self) -> Self {
+        self.mantissa = -self.mantissa;
+        self
+    }
+}
+
+impl iter::Sum for Quantity {
+    fn sum<I: Iterator<Item = Self>>(iter: I) -> Self {
+        iter.fold(Self::default(), ops::Add::add)
+    }
+}
+
+impl<'a> iter::Sum<&'a Quantity> for Quantity {
+    fn sum<I: Iterator<Item = &'a Self>>(iter: I) -> Self {
+        iter.copied().sum()
+    }
+}
+
+impl Serialize for Quantity {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
+    where
+        S: Serializer,
+    {
+        serializer.collect_str(self)
+    }
+}
+
+impl<'de> Deserialize<'de> for Quantity {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        struct Visitor;
+        impl de::Visitor<'_> for Visitor {
+            type Value = Quantity;
+
+            fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                f.write_str("a number, or a string in Kubernetes quantity format")
+            }
+
+            fn visit_str<E>(self, value: &str) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                value.parse().map_err(de::Error::custom)
+            }
+
+            fn visit_i64<E>(self, value: i64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Ok(value.into())
+            }
+
+            fn visit_u64<E>(self, value: u64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                Quantity::new(value.into(), 0, Format::DecimalSI)
+                    .ok_or_else(|| de::Error::custom(ParseError::OutOfRange))
+            }
+
+            fn visit_f64<E>(self, value: f64) -> Result<Self::Value, E>
+            where
+                E: de::Error,
+            {
+                value.to_string().parse().map_err(de::Error::custom)
+            }
+        }
+        deserializer.deserialize_any(Visitor)
+    }
+}
+
+#[cfg(feature = "schema")]
+impl schemars::JsonSchema for Quantity {
+    // see
+    // https://github.com/kubernetes/apimachinery/blob/v0.30.0/pkg/api/resource/quantity.go#L401
+    fn schema_name() -> String {
+        "Quantity".to_owned()
+    }
+
+    fn is_referenceable() -> bool {
+        false
+    }
+
+    fn json_schema(_: &mut schemars::gen::SchemaGenerator) -> schemars::schema::Schema {
+        crate::int_or_string::int_or_string_schema()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_and_canonicalizes_the_same_as_go() {
+        // from Go:
+        // https://github.com/kubernetes/apimachinery/blob/v0.30.0/pkg/api/resource/quantity_test.go
+        let cases = [
+            ("0", "0"),
+            ("-0", "0"),
+            ("0Ki", "0"),
+            ("+1", "1"),
+            ("1.5", "1500m"),
+            (".5", "500m"),
+            ("5.", "5"),
+            ("1000k", "1M"),
+            ("0.000000001", "1n"),
+            ("0.1m", "100u"),
+            // rounded up to the nearest nano
+            ("0.0000000001", "1n"),
+            ("-0.0000000001", "-1n"),
+            ("1.0000000001", "1000000001n"),
+            ("100Ki", "100Ki"),
+            ("1024Ki", "1Mi"),
+            ("1.5Gi", "1536Mi"),
+            ("1.5Ki", "1536"),
+            ("0.5Ki", "512"),
+            ("-1Ei", "-1Ei"),
+            ("8Ei", "8Ei"),
+            ("1e3", "1e3"),
+            ("1E3", "1e3"),
+            ("1.5e3", "1500"),
+            ("1000e-3", "1"),
+            ("12e-1", "1200e-3"),
+            ("1e21", "1e21"),
+            ("2E", "2E"),
+            ("2000E", "2000E"),
+        ];
+        for (input, canonical) in cases {
+            let parsed = input.parse::<Quantity>().unwrap();
+            assert_eq!(parsed.to_string(), canonical);
+            assert_eq!(canonical.parse::<Quantity>().unwrap(), parsed);
+        }
+    }
+
+    #[test]
+    fn rejects_invalid_quantities() {
+        let cases = [
+            ("", ParseError::InvalidNumber),
+            ("-", ParseError::InvalidNumber),
+            ("Ki", ParseError::InvalidNumber),
+            ("1.2.3", ParseError::InvalidNumber),
+            ("1Kb", ParseError::InvalidSuffix),
+            ("1 ", ParseError::InvalidSuffix),
+            ("1e", ParseError::InvalidSuffix),
+            ("1e1.5", ParseError::InvalidSuffix),
+            ("1e99999999999", ParseError::OutOfRange),
+            ("100000000000000000000000000000000000001", ParseError::OutOfRange),
+        ];
+        for (input, error) in cases {
+            assert_eq!(input.parse::<Quantity>(), Err(error), "{input}");
+        }
+    }
+
+    #[test]
+    fn arithmetic_and_comparison() {
+        let q = |s: &str| s.parse::<Quantity>().unwrap();
+
+        assert_eq!(q("1Gi"), q("1024Mi"));
+        assert_eq!(q("1k"), q("1e3"));
+        assert!(q("999m") < q("1"));
+        assert!(q("-1Ki") < q("1m"));
+        assert!(q("1e40") > q("1"));
+        assert!(q("-1e40") < q("-1"));
+        assert_eq!(q("1e40").cmp(&q("1e40")), Ordering::Equal);
+
+        assert_eq!((q("1Gi") + q("512Mi")).to_string(), "1536Mi");
+        assert_eq!((q("1") - q("250m")).to_string(), "750m");
+        assert_eq!((-q("1.5Gi")).to_string(), "-1536Mi");
+        let requests = [q("100m"), q("0.5"), q("1")];
+        assert_eq!(requests.iter().sum::<Quantity>().to_string(), "1600m");
+        // zero takes the format of the quantity added to it
+        assert_eq!(
+            [q("1Gi"), q("1Gi")].into_iter().sum::<Quantity>().to_string(),
+            "2Gi"
+        );
+        assert_eq!(q("1e40").checked_add(q("1")), None);
+
+        assert_eq!(q("1.5").value(), Some(2));
+        assert_eq!(q("-1.5").value(), Some(-2));
+        assert_eq!(q("1.5").milli_value(), Some(1500));
+        assert_eq!(q("0.1m").milli_value(), Some(1));
+        assert_eq!(q("1Ki").scaled_value(3), Some(2));
+        assert_eq!(q("1e30").value(), None);
+        assert_eq!(Quantity::from_milli(250), q("250m"));
+        assert_eq!(q("1.5Ki").to_f64(), 1536.0);
+    }
+
+    #[test]
+    fn serializes_in_canonical_form() {
+        let q: Quantity = serde_json::from_str(r#""1.5Gi""#).unwrap();
+        assert_eq!(serde_json::to_string(&q).unwrap(), r#""1536Mi""#);
+        let q: Quantity = serde_json::from_str("1.5").unwrap();
+        assert_eq!(serde_json::to_string(&q).unwrap(), r#""1500m""#);
+        let q: Quantity = serde_json::from_str("2048").unwrap();
+        assert_eq!(serde_json::to_string(&q).unwrap(), r#""2048""#);
+
+        let string = QuantityString("2000m".into());
+        let q = Quantity::try_from(&string).unwrap();
+        assert_eq!(QuantityString::from(q), QuantityString("2".into()));
+    }
+}
@@ -30,6 +30,9 @@ pub enum Error {
     /// Failed to validate request.
     #[error("failed to validate request: {0}")]
     Validation(String),
+    /// The kind of workload does not support restarts.
+    #[error("{0} does not support restarts")]
+    UnsupportedRestart(String),
 }
 
 /// A Kubernetes request builder
@@ -4,8 +4,11 @@ use k8s_openapi::{
     apimachinery::pkg::apis::meta::v1::{ManagedFieldsEntry, OwnerReference, Time},
 };
 
+use serde_json::json;
 use std::{borrow::Cow, collections::BTreeMap};
 
+use crate::params::Patch;
+
 pub use k8s_openapi::{ClusterResourceScope, NamespaceResourceScope, ResourceScope, SubResourceScope};
 
 /// Indicates that a [`Resource`] is of an indeterminate dynamic scope.
@@ -260,6 +263,33 @@ pub trait ResourceExt: Resource {
     fn managed_fields(&self) -> &[ManagedFieldsEntry];
     /// Provides mutable access to managed fields
     fn managed_fields_mut(&mut self) -> &mut Vec<ManagedFieldsEntry>;
+
+    /// Returns a merge patch that sets a single label, leaving the other labels alone
+    ///
+    /// This is a merge patch rather than a server-side apply patch, because applying only the label
+    /// would remove every other field owned by the field manager.
+    ///
+    /// ```
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::ResourceExt;
+    /// let patch = Pod::default().patch_for_label("app", "blog");
+    /// ```
+    fn patch_for_label(&self, key: &str, value: &str) -> Patch<serde_json::Value>;
+    /// Returns a merge patch that removes a single label, leaving the other labels alone
+    fn patch_removing_label(&self, key: &str) -> Patch<serde_json::Value>;
+    /// Returns a merge patch that sets a single annotation, leaving the other annotations alone
+    fn patch_for_annotation(&self, key: &str, value: &str) -> Patch<serde_json::Value>;
+    /// Returns a merge patch that removes a single annotation, leaving the other annotations alone
+    fn patch_removing_annotation(&self, key: &str) -> Patch<serde_json::Value>;
+    /// Returns a merge patch that adds a finalizer to the finalizers of this object
+    ///
+    /// Merge patches replace lists, so the patch includes the resource version to fail with a conflict
+    /// when the finalizers have changed since this object was read.
+    fn patch_for_finalizer(&self, finalizer: &str) -> Patch<serde_json::Value>;
+    /// Returns a merge patch that removes a finalizer from the finalizers of this object
+    ///
+    /// Like [`patch_for_finalizer`](ResourceExt::patch_for_finalizer), the patch includes the resource version.
+    fn patch_removing_finalizer(&self, finalizer: &str) -> Patch<serde_json::Value>;
 }
 
 static EMPTY_MAP: BTreeMap<String, String> = BTreeMap::new();
@@ -332,4 +362,83 @@ impl<K: Resource> ResourceExt for K {
     fn managed_fields_mut(&mut self) -> &mut Vec<ManagedFieldsEntry> {
         self.meta_mut().managed_fields.get_or_insert_with(Vec::new)
     }
+
+    fn patch_for_label(&self, key: &str, value: &str) -> Patch<serde_json::Value> {
+        metadata_patch(json!({ "labels": { key: value } }))
+    }
+
+    fn patch_removing_label(&self, key: &str) -> Patch<serde_json::Value> {
+        metadata_patch(json!({ "labels": { key: null } }))
+    }
+
+    fn patch_for_annotation(&self, key: &str, value: &str) -> Patch<serde_json::Value> {
+        metadata_patch(json!({ "annotations": { key: value } }))
+    }
+
+    fn patch_removing_annotation(&self, key: &str) -> Patch<serde_json::Value> {
+        metadata_patch(json!({ "annotations": { key: null } }))
+    }
+
+    fn patch_for_finalizer(&self, finalizer: &str) -> Patch<serde_json::Value> {
+        let mut finalizers = self.finalizers().to_vec();
+        if !finalizers.iter().any(|f| f == finalizer) {
+            finalizers.push(finalizer.to_owned());
+        }
+        metadata_patch(json!({
+            "finalizers": finalizers,
+            "resourceVersion": self.resource_version(),
+        }))
+    }
+
+    fn patch_removing_finalizer(&self, finalizer: &str) -> Patch<serde_json::Value> {
+        let finalizers: Vec<_> = self.finalizers().iter().filter(|f| *f != finalizer).collect();
+        metadata_patch(json!({
+            "finalizers": finalizers,
+            "resourceVersion": self.resource_version(),
+        }))
+    }
+}
+
+fn metadata_patch(metadata: serde_json::Value) -> Patch<serde_json::Value> {
+    Patch::Merge(json!({ "metadata": metadata }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ResourceExt;
+    use crate::{params::Patch, ObjectMeta};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use serde_json::json;
+
+    #[test]
+    fn metadata_patches_only_touch_one_key() {
+        let cm = ConfigMap {
+            metadata: ObjectMeta {
+                finalizers: Some(vec!["a".into(), "b".into()]),
+                resource_version: Some("7".into()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        assert_eq!(
+            cm.patch_for_label("app", "blog"),
+            Patch::Merge(json!({ "metadata": { "labels": { "app": "blog" } } }))
+        );
+        assert_eq!(
+            cm.patch_removing_annotation("note"),
+            Patch::Merge(json!({ "metadata": { "annotations": { "note": null } } }))
+        );
+        assert_eq!(
+            cm.patch_for_finalizer("c"),
+            Patch::Merge(json!({ "metadata": { "finalizers": ["a", "b", "c"], "resourceVersion": "7" } }))
+        );
+        assert_eq!(
+            cm.patch_for_finalizer("a"),
+            Patch::Merge(json!({ "metadata": { "finalizers": ["a", "b"], "resourceVersion": "7" } }))
+        );
+        assert_eq!(
+            cm.patch_removing_finalizer("a"),
+            Patch::Merge(json!({ "metadata": { "finalizers": ["b"], "resourceVersion": "7" } }))
+        );
+    }
 }
@@ -75,6 +75,134 @@ impl Visitor for StructuralSchemaRewriter {
     }
 }
 
+/// Sets how server-side apply merges the property under `property_index` of the schema
+///
+/// `list_type` and `list_map_keys` become `x-kubernetes-list-type` and `x-kubernetes-list-map-keys`,
+/// and `map_type` becomes `x-kubernetes-map-type`, see [merge strategy]. Unset values are left out.
+///
+/// This is used by the `#[x_kube(..)]` field attributes of `#[derive(CELSchema)]`.
+///
+/// ```rust
+/// use schemars::JsonSchema;
+/// use kube::core::schema::set_property_merge_strategy;
+///
+/// #[derive(JsonSchema)]
+/// struct Port {
+///     name: String,
+/// }
+///
+/// #[derive(JsonSchema)]
+/// struct MyStruct {
+///     ports: Vec<Port>,
+/// }
+///
+/// let gen = &mut schemars::gen::SchemaSettings::openapi3().into_generator();
+/// let mut schema = MyStruct::json_schema(gen);
+/// set_property_merge_strategy(&mut schema, 0, Some("map"), &["name"], None);
+/// let ports = &serde_json::to_value(&schema)?["properties"]["ports"];
+/// assert_eq!(ports["x-kubernetes-list-type"], "map");
+/// assert_eq!(ports["x-kubernetes-list-map-keys"], serde_json::json!(["name"]));
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+///
+/// [merge strategy]: https://kubernetes.io/docs/reference/using-api/server-side-apply/#merge-strategy
+pub fn set_property_merge_strategy(
+    s: &mut Schema,
+    property_index: usize,
+    list_type: Option<&str>,
+    list_map_keys: &[&str],
+    map_type: Option<&str>,
+) {
+    let Some(property) = property_mut(s, property_index) else {
+        return;
+    };
+    if let Some(list_type) = list_type {
+        property
+            .extensions
+            .insert("x-kubernetes-list-type".into(), list_type.into());
+    }
+    if !list_map_keys.is_empty() {
+        property
+            .extensions
+            .insert("x-kubernetes-list-map-keys".into(), list_map_keys.into());
+    }
+    if let Some(map_type) = map_type {
+        property
+            .extensions
+            .insert("x-kubernetes-map-type".into(), map_type.into());
+    }
+}
+
+/// Marks the property under `property_index` of the schema to keep unknown fields, or as an embedded resource
+///
+/// `preserve_unknown_fields` sets `x-kubernetes-preserve-unknown-fields`, so that the apiserver keeps the fields
+/// that are not in the schema, such as those of a `serde_json::Value` or a `RawExtension`.
+/// `embedded_resource` sets `x-kubernetes-embedded-resource` and the `object` type, so that the apiserver
+/// validates the `apiVersion`, `kind` and `metadata` of the property like those of a top level object.
+///
+/// This is used by the `#[x_kube(..)]` field attributes of `#[derive(CELSchema)]`.
+///
+/// ```rust
+/// use schemars::JsonSchema;
+/// use kube::core::schema::set_property_markers;
+///
+/// #[derive(JsonSchema)]
+/// struct MyStruct {
+///     template: serde_json::Value,
+/// }
+///
+/// let gen = &mut schemars::gen::SchemaSettings::openapi3().into_generator();
+/// let mut schema = MyStruct::json_schema(gen);
+/// set_property_markers(&mut schema, 0, true, true);
+/// assert_eq!(
+///     serde_json::to_value(&schema)?["properties"]["template"],
+///     serde_json::json!({
+///         "type": "object",
+///         "x-kubernetes-preserve-unknown-fields": true,
+///         "x-kubernetes-embedded-resource": true,
+///     })
+/// );
+/// # Ok::<(), serde_json::Error>(())
+/// ```
+pub fn set_property_markers(
+    s: &mut Schema,
+    property_index: usize,
+    preserve_unknown_fields: bool,
+    embedded_resource: bool,
+) {
+    let Some(property) = property_mut(s, property_index) else {
+        return;
+    };
+    if preserve_unknown_fields {
+        property
+            .extensions
+            .insert("x-kubernetes-preserve-unknown-fields".into(), true.into());
+    }
+    if embedded_resource {
+        property
+            .extensions
+            .insert("x-kubernetes-embedded-resource".into(), true.into());
+        property
+            .instance_type
+            .get_or_insert_with(|| InstanceType::Object.into());
+    }
+}
+
+/// The property under `property_index` of the schema, turning the `true` schema of any value into an object
+fn property_mut(s: &mut Schema, property_index: usize) -> Option<&mut SchemaObject> {
+    let Schema::Object(schema_object) = s else {
+        return None;
+    };
+    let (_, property) = schema_object.object().properties.iter_mut().nth(property_index)?;
+    if let Schema::Bool(true) = property {
+        *property = SchemaObject::default().into();
+    }
+    match property {
+        Schema::Object(property) => Some(property),
+        Schema::Bool(_) => None,
+    }
+}
+
 /// Bring all plain enum values up to the root schema,
 /// since Kubernetes doesn't allow subschemas to define enum options.
 ///
@@ -94,7 +94,13 @@ impl Request {
 // ----------------------------------------------------------------------------
 
 /// Params for evictable objects
-#[derive(Default, Clone)]
+///
+/// ```
+/// use kube_core::subresource::EvictParams;
+/// let ep = EvictParams::default().grace_period(30);
+/// assert_eq!(ep.delete_options.unwrap().grace_period_seconds, Some(30));
+/// ```
+#[derive(Default, Clone, Debug)]
 pub struct EvictParams {
     /// How the eviction should occur
     pub delete_options: Option<DeleteParams>,
@@ -102,8 +108,26 @@ pub struct EvictParams {
     pub post_options: PostParams,
 }
 
+impl EvictParams {
+    /// Set how the evicted object should be deleted
+    #[must_use]
+    pub fn delete_options(mut self, delete_options: DeleteParams) -> Self {
+        self.delete_options = Some(delete_options);
+        self
+    }
+
+    /// Set the duration in seconds before the evicted object should be deleted
+    ///
+    /// Overrides the `terminationGracePeriodSeconds` of the pod.
+    #[must_use]
+    pub fn grace_period(mut self, secs: u32) -> Self {
+        self.delete_options = Some(self.delete_options.unwrap_or_default().grace_period(secs));
+        self
+    }
+}
+
 impl Request {
-    /// Create an eviction
+    /// Create a `policy/v1` eviction
     pub fn evict(&self, name: &str, ep: &EvictParams) -> Result<http::Request<Vec<u8>>, Error> {
         let target = format!("{}/{}/eviction?", self.url_path, name);
         // This is technically identical to Request::create, but different url
@@ -112,12 +136,15 @@ impl Request {
         let mut qp = form_urlencoded::Serializer::new(target);
         pp.populate_qp(&mut qp);
         let urlstr = qp.finish();
-        // eviction body parameters are awkward, need metadata with name
-        let data = serde_json::to_vec(&serde_json::json!({
-            "delete_options": ep.delete_options,
+        let mut eviction = serde_json::json!({
+            "apiVersion": "policy/v1",
+            "kind": "Eviction",
             "metadata": { "name": name }
-        }))
-        .map_err(Error::SerializeBody)?;
+        });
+        if let Some(delete_options) = &ep.delete_options {
+            eviction["deleteOptions"] = serde_json::to_value(delete_options).map_err(Error::SerializeBody)?;
+        }
+        let data = serde_json::to_vec(&eviction).map_err(Error::SerializeBody)?;
         let req = http::Request::post(urlstr).header(http::header::CONTENT_TYPE, JSON_MIME);
         req.body(data).map_err(Error::BuildRequest)
     }
@@ -126,6 +153,52 @@ impl Request {
 // ----------------------------------------------------------------------------
 // Attach subresource
 // ----------------------------------------------------------------------------
+/// Keepalive of the WebSocket connection of an attach, exec or portforward
+///
+/// Load balancers in front of the apiserver often drop connections that have been idle for a while,
+/// which ends long interactive sessions without notice. Pings keep such connections busy,
+/// and the idle timeout detects connections that were dropped anyway.
+///
+/// Sessions cannot be resumed once their connection is lost: the process of an exec is not reattached,
+/// and forwarded connections are closed. Callers need to start a new session instead.
+///
+/// ```
+/// use kube_core::subresource::KeepaliveParams;
+/// use std::time::Duration;
+/// let keepalive = KeepaliveParams::default()
+///     .ping_interval(Duration::from_secs(30))
+///     .idle_timeout(Duration::from_secs(90));
+/// ```
+#[cfg(feature = "ws")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct KeepaliveParams {
+    /// How often to ping the server. Defaults to never.
+    pub ping_interval: Option<std::time::Duration>,
+    /// How long the server can stay silent before the session fails. Defaults to forever.
+    ///
+    /// Any message counts, including the replies to pings, so this should be longer than `ping_interval`.
+    pub idle_timeout: Option<std::time::Duration>,
+}
+
+#[cfg(feature = "ws")]
+#[cfg_attr(docsrs, doc(cfg(feature = "ws")))]
+impl KeepaliveParams {
+    /// Set `ping_interval` field.
+    #[must_use]
+    pub fn ping_interval(mut self, interval: std::time::Duration) -> Self {
+        self.ping_interval = Some(interval);
+        self
+    }
+
+    /// Set `idle_timeout` field.
+    #[must_use]
+    pub fn idle_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+}
+
 /// Parameters for attaching to a container in a Pod.
 ///
 /// - One of `stdin`, `stdout`, or `stderr` must be `true`.
@@ -150,8 +223,6 @@ pub struct AttachParams {
     /// Call [`AttachedProcess::stderr`](https://docs.rs/kube/*/kube/api/struct.AttachedProcess.html#method.stderr) to obtain a reader.
     pub stderr: bool,
     /// Allocate TTY. Defaults to `false`.
-    ///
-    /// NOTE: Terminal resizing is not implemented yet.
     pub tty: bool,
 
     /// The maximum amount of bytes that can be written to the internal `stdin`
@@ -172,6 +243,11 @@ pub struct AttachParams {
     ///
     /// This is not sent to the server.
     pub max_stderr_buf_size: Option<usize>,
+
+    /// Keepalive of the WebSocket connection. Defaults to none.
+    ///
+    /// This is not sent to the server.
+    pub keepalive: KeepaliveParams,
 }
 
 #[cfg(feature = "ws")]
@@ -188,6 +264,7 @@ impl Default for AttachParams {
             max_stdin_buf_size: None,
             max_stdout_buf_size: None,
             max_stderr_buf_size: None,
+            keepalive: KeepaliveParams::default(),
         }
     }
 }
@@ -263,6 +340,13 @@ impl AttachParams {
         self
     }
 
+    /// Set `keepalive` field.
+    #[must_use]
+    pub fn keepalive(mut self, keepalive: KeepaliveParams) -> Self {
+        self.keepalive = keepalive;
+        self
+    }
+
     pub(crate) fn validate(&self) -> Result<(), Error> {
         if !self.stdin && !self.stdout && !self.stderr {
             return Err(Error::Validation(
@@ -405,6 +489,80 @@ impl Request {
     }
 }
 
+// ----------------------------------------------------------------------------
+// Proxy subresource
+// ----------------------------------------------------------------------------
+
+impl Request {
+    /// Send a request through the apiserver proxy of an object, to `path` on the object
+    ///
+    /// The `name` can include a port, like `my-node:10250` or `my-service:http`, and services can also
+    /// be prefixed by a scheme, like `https:my-service:443`. The method, headers, body and query of
+    /// `req` are kept, only its path is replaced.
+    pub fn proxy(
+        &self,
+        name: &str,
+        path: &str,
+        req: http::Request<Vec<u8>>,
+    ) -> Result<http::Request<Vec<u8>>, Error> {
+        if name.is_empty() {
+            return Err(Error::Validation("proxy target name cannot be empty".into()));
+        }
+        let path = path.trim_start_matches('/');
+        let mut target = format!("{}/{name}/proxy/{path}", self.url_path);
+        if let Some(query) = req.uri().query() {
+            target = format!("{target}?{query}");
+        }
+        let (mut parts, body) = req.into_parts();
+        parts.uri = target
+            .parse()
+            .map_err(|err: http::uri::InvalidUri| Error::BuildRequest(err.into()))?;
+        Ok(http::Request::from_parts(parts, body))
+    }
+
+    /// Get `path` on a port of an object through the apiserver proxy
+    ///
+    /// The `path` can include a query, like `stats?format=json`.
+    pub fn proxy_get(&self, name: &str, port: u16, path: &str) -> Result<http::Request<Vec<u8>>, Error> {
+        self.proxy_port(http::Method::GET, name, port, path, vec![])
+    }
+
+    /// Post JSON `data` to `path` on a port of an object through the apiserver proxy
+    ///
+    /// The `path` can include a query, like `drain?timeout=30s`.
+    pub fn proxy_post(
+        &self,
+        name: &str,
+        port: u16,
+        path: &str,
+        data: Vec<u8>,
+    ) -> Result<http::Request<Vec<u8>>, Error> {
+        self.proxy_port(http::Method::POST, name, port, path, data)
+    }
+
+    fn proxy_port(
+        &self,
+        method: http::Method,
+        name: &str,
+        port: u16,
+        path: &str,
+        data: Vec<u8>,
+    ) -> Result<http::Request<Vec<u8>>, Error> {
+        let (path, query) = path.split_once('?').unwrap_or((path, ""));
+        let uri = if query.is_empty() {
+            "/".to_string()
+        } else {
+            format!("/?{query}")
+        };
+        let mut req = http::Request::builder().method(method).uri(uri);
+        if !data.is_empty() {
+            req = req.header(http::header::CONTENT_TYPE, JSON_MIME);
+        }
+        let req = req.body(data).map_err(Error::BuildRequest)?;
+        self.proxy(&format!("{name}:{port}"), path, req)
+    }
+}
+
 // ----------------------------------------------------------------------------
 // tests
 // ----------------------------------------------------------------------------
@@ -452,4 +610,37 @@ mod test {
             "/api/v1/namespaces/ns/pods/mypod/log?&sinceTime=2023-10-19T13%3A14%3A26Z" // cross-referenced with kubectl
         );
     }
+
+    #[test]
+    fn proxy_path() {
+        let url = corev1::Node::url_path(&(), None);
+        let req = http::Request::post("/healthz?verbose=1").body(vec![1]).unwrap();
+        let req = Request::new(url).proxy("mynode:10250", "/metrics", req).unwrap();
+        assert_eq!(req.uri(), "/api/v1/nodes/mynode:10250/proxy/metrics?verbose=1");
+        assert_eq!(req.method(), http::Method::POST);
+        assert_eq!(req.body(), &vec![1]);
+
+        let url = corev1::Service::url_path(&(), Some("ns"));
+        let req = http::Request::get("/").body(vec![]).unwrap();
+        let req = Request::new(url).proxy("https:mysvc:443", "", req).unwrap();
+        assert_eq!(req.uri(), "/api/v1/namespaces/ns/services/https:mysvc:443/proxy/");
+
+        let url = corev1::Pod::url_path(&(), Some("ns"));
+        let req = Request::new(url.clone())
+            .proxy_get("mypod", 15000, "stats?format=json")
+            .unwrap();
+        assert_eq!(
+            req.uri(),
+            "/api/v1/namespaces/ns/pods/mypod:15000/proxy/stats?format=json"
+        );
+        let req = Request::new(url)
+            .proxy_post("mypod", 15000, "/drain_listeners", b"{}".to_vec())
+            .unwrap();
+        assert_eq!(
+            req.uri(),
+            "/api/v1/namespaces/ns/pods/mypod:15000/proxy/drain_listeners"
+        );
+        assert_eq!(req.method(), http::Method::POST);
+        assert_eq!(req.headers()[http::header::CONTENT_TYPE], "application/json");
+    }
 }
@@ -0,0 +1,196 @@
+//! Server-side printing of resources as tables
+use crate::{
+    metadata::{ListMeta, PartialObjectMeta, TypeMeta},
+    object::deserialize_null_as_default,
+    params::ListParams,
+    request::{Error, JSON_MIME},
+    DynamicObject, Request,
+};
+use serde::{Deserialize, Serialize};
+
+/// Extended Accept Header
+///
+/// Requests a meta.k8s.io/v1 Table, the same representation `kubectl get` prints from.
+pub(crate) const JSON_TABLE_MIME: &str = "application/json;as=Table;g=meta.k8s.io;v=v1";
+
+/// A tabular representation of a list of resources, as printed by `kubectl get`
+///
+/// The columns are chosen by the apiserver, so they include the `additionalPrinterColumns` of custom resources.
+/// See <https://kubernetes.io/docs/reference/using-api/api-concepts/#receiving-resources-as-tables> for details.
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", bound(deserialize = ""))]
+pub struct Table<K = DynamicObject> {
+    /// The type fields, always `meta.k8s.io/v1` `Table`
+    #[serde(flatten)]
+    pub types: TypeMeta,
+
+    /// ListMeta of the listed collection, including any continue token
+    #[serde(default)]
+    pub metadata: ListMeta,
+
+    /// The columns of every row, in order
+    #[serde(default, deserialize_with = "deserialize_null_as_default")]
+    pub column_definitions: Vec<TableColumnDefinition>,
+
+    /// One row per listed object
+    #[serde(default, deserialize_with = "deserialize_null_as_default")]
+    pub rows: Vec<TableRow<K>>,
+}
+
+/// Describes a column of a [`Table`]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableColumnDefinition {
+    /// Human readable name of the column
+    pub name: String,
+
+    /// OpenAPI type of the column, such as `string`, `integer` or `number`
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// Optional OpenAPI format modifier of the type, such as `name` or `date`
+    #[serde(default)]
+    pub format: String,
+
+    /// Human readable description of the column
+    #[serde(default)]
+    pub description: String,
+
+    /// Relative importance of the column
+    ///
+    /// Columns with a priority greater than 0 are only shown by `kubectl get -o wide`.
+    #[serde(default)]
+    pub priority: i32,
+}
+
+/// A single row of a [`Table`]
+#[derive(Deserialize, Serialize, Clone, Debug)]
+#[serde(rename_all = "camelCase", bound(deserialize = ""))]
+pub struct TableRow<K = DynamicObject> {
+    /// The values of the row, one per column definition
+    #[serde(default, deserialize_with = "deserialize_null_as_default")]
+    pub cells: Vec<serde_json::Value>,
+
+    /// Extra information about the row, such as whether it has completed
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub conditions: Vec<TableRowCondition>,
+
+    /// The metadata of the object the row was printed from
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub object: Option<PartialObjectMeta<K>>,
+}
+
+/// A condition of a [`TableRow`]
+#[derive(Deserialize, Serialize, Clone, Debug, Default, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct TableRowCondition {
+    /// Type of the condition, currently only `Completed`
+    #[serde(rename = "type")]
+    pub type_: String,
+
+    /// Status of the condition, one of `True`, `False` or `Unknown`
+    pub status: String,
+
+    /// Machine readable reason for the last transition
+    pub reason: Option<String>,
+
+    /// Human readable message for the last transition
+    pub message: Option<String>,
+}
+
+impl<K> Table<K> {
+    /// The uppercased column headers, as printed by `kubectl get`
+    ///
+    /// Columns with a non-zero priority are only included when `wide` is set.
+    pub fn headers(&self, wide: bool) -> Vec<String> {
+        self.column_definitions
+            .iter()
+            .filter(|col| wide || col.priority == 0)
+            .map(|col| col.name.to_uppercase())
+            .collect()
+    }
+
+    /// The cells of every row rendered as text, matching [`headers`](Self::headers)
+    ///
+    /// Missing values are rendered as `<none>`.
+    pub fn cells(&self, wide: bool) -> Vec<Vec<String>> {
+        self.rows
+            .iter()
+            .map(|row| {
+                self.column_definitions
+                    .iter()
+                    .zip(row.cells.iter().map(Some).chain(std::iter::repeat(None)))
+                    .filter(|(col, _)| wide || col.priority == 0)
+                    .map(|(_, cell)| match cell {
+                        Some(serde_json::Value::String(s)) => s.clone(),
+                        None | Some(serde_json::Value::Null) => "<none>".to_string(),
+                        Some(value) => value.to_string(),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+impl Request {
+    /// List a collection of a resource as a [`Table`]
+    pub fn list_table(&self, lp: &ListParams) -> Result<http::Request<Vec<u8>>, Error> {
+        let target = format!("{}?", self.url_path);
+        let mut qp = form_urlencoded::Serializer::new(target);
+        lp.validate()?;
+        lp.populate_qp(&mut qp);
+        let urlstr = qp.finish();
+        let req = http::Request::get(urlstr)
+            .header(http::header::ACCEPT, JSON_TABLE_MIME)
+            .header(http::header::CONTENT_TYPE, JSON_MIME);
+
+        req.body(vec![]).map_err(Error::BuildRequest)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::{Table, JSON_TABLE_MIME};
+    use crate::{params::ListParams, request::Request, Resource};
+    use k8s_openapi::api::core::v1 as corev1;
+
+    #[test]
+    fn list_table_path() {
+        let url = corev1::Pod::url_path(&(), Some("ns"));
+        let lp = ListParams::default().labels("app=blog");
+        let req = Request::new(url).list_table(&lp).unwrap();
+        assert_eq!(req.uri(), "/api/v1/namespaces/ns/pods?&labelSelector=app%3Dblog");
+        assert_eq!(req.headers().get(http::header::ACCEPT).unwrap(), JSON_TABLE_MIME);
+    }
+
+    #[test]
+    fn table_renders_like_kubectl() {
+        let table: Table<corev1::Pod> = serde_json::from_value(serde_json::json!({
+            "kind": "Table",
+            "apiVersion": "meta.k8s.io/v1",
+            "metadata": { "resourceVersion": "1234" },
+            "columnDefinitions": [
+                { "name": "Name", "type": "string", "format": "name", "description": "", "priority": 0 },
+                { "name": "Restarts", "type": "string", "format": "", "description": "", "priority": 0 },
+                { "name": "IP", "type": "string", "format": "", "description": "", "priority": 1 },
+                { "name": "Ready", "type": "integer", "format": "", "description": "", "priority": 0 }
+            ],
+            "rows": [{
+                "cells": ["blog", "0", null, 1],
+                "object": {
+                    "kind": "PartialObjectMetadata",
+                    "apiVersion": "meta.k8s.io/v1",
+                    "metadata": { "name": "blog", "namespace": "ns" }
+                }
+            }]
+        }))
+        .unwrap();
+
+        assert_eq!(table.headers(false), ["NAME", "RESTARTS", "READY"]);
+        assert_eq!(table.headers(true), ["NAME", "RESTARTS", "IP", "READY"]);
+        assert_eq!(table.cells(false), [["blog", "0", "1"]]);
+        assert_eq!(table.cells(true), [["blog", "0", "<none>", "1"]]);
+        let object = table.rows[0].object.as_ref().unwrap();
+        assert_eq!(object.metadata.name.as_deref(), Some("blog"));
+    }
+}
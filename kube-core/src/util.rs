@@ -2,36 +2,66 @@
 
 use crate::{
     params::{Patch, PatchParams},
-    request, Request,
+    request, Request, TypeMeta,
 };
 use chrono::Utc;
-use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, ReplicaSet, StatefulSet};
+use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+
+/// The pod template annotation that `kubectl rollout restart` sets to roll out new pods
+pub const RESTARTED_AT_ANNOTATION: &str = "kubectl.kubernetes.io/restartedAt";
+
+/// The field manager that [`Request::restart`] applies the [`RESTARTED_AT_ANNOTATION`] with
+pub const RESTART_FIELD_MANAGER: &str = "kube-rollout-restart";
+
+/// The `(group, kind)` of the workloads that support restarts
+const RESTARTABLE_KINDS: [(&str, &str); 3] = [
+    ("apps", "Deployment"),
+    ("apps", "DaemonSet"),
+    ("apps", "StatefulSet"),
+];
 
 /// Restartable Resource marker trait
+///
+/// Implemented for the workloads that `kubectl rollout restart` supports.
 pub trait Restart {}
 
 impl Restart for Deployment {}
 impl Restart for DaemonSet {}
 impl Restart for StatefulSet {}
-impl Restart for ReplicaSet {}
 
 impl Request {
-    /// Restart a resource
-    pub fn restart(&self, name: &str) -> Result<http::Request<Vec<u8>>, request::Error> {
+    /// Restart a workload, like `kubectl rollout restart`
+    ///
+    /// Sets the [`RESTARTED_AT_ANNOTATION`] of the pod template to the current time with a forced server-side apply,
+    /// which makes the workload controller roll out new pods.
+    /// Fails with [`request::Error::UnsupportedRestart`] unless `types` is a Deployment, DaemonSet or StatefulSet.
+    pub fn restart(&self, name: &str, types: &TypeMeta) -> Result<http::Request<Vec<u8>>, request::Error> {
+        let group = types.api_version.rsplit_once('/').map_or("", |(group, _)| group);
+        if !RESTARTABLE_KINDS.contains(&(group, types.kind.as_str())) {
+            return Err(request::Error::UnsupportedRestart(format!(
+                "{}/{}",
+                types.api_version, types.kind
+            )));
+        }
         let patch = serde_json::json!({
+          "apiVersion": types.api_version,
+          "kind": types.kind,
+          "metadata": {
+            "name": name
+          },
           "spec": {
             "template": {
               "metadata": {
                 "annotations": {
-                  "kube.kubernetes.io/restartedAt": Utc::now().to_rfc3339()
+                  RESTARTED_AT_ANNOTATION: Utc::now().to_rfc3339()
                 }
               }
             }
           }
         });
 
-        let pparams = PatchParams::default();
-        self.patch(name, &pparams, &Patch::Merge(patch))
+        let pparams = PatchParams::apply(RESTART_FIELD_MANAGER).force();
+        self.patch(name, &pparams, &Patch::Apply(patch))
     }
 }
 
@@ -65,16 +95,39 @@ mod test {
 
     #[test]
     fn restart_patch_is_correct() {
+        use crate::TypeMeta;
         use k8s_openapi::api::apps::v1 as appsv1;
 
         let url = appsv1::Deployment::url_path(&(), Some("ns"));
-        let req = Request::new(url).restart("mydeploy").unwrap();
-        assert_eq!(req.uri(), "/apis/apps/v1/namespaces/ns/deployments/mydeploy?");
+        let req = Request::new(url)
+            .restart("mydeploy", &TypeMeta::resource::<appsv1::Deployment>())
+            .unwrap();
+        assert_eq!(
+            req.uri(),
+            "/apis/apps/v1/namespaces/ns/deployments/mydeploy?&force=true&fieldManager=kube-rollout-restart"
+        );
         assert_eq!(req.method(), "PATCH");
         assert_eq!(
             req.headers().get("Content-Type").unwrap().to_str().unwrap(),
-            Patch::Merge(()).content_type()
+            Patch::Apply(()).content_type()
         );
+        let body: serde_json::Value = serde_json::from_slice(req.body()).unwrap();
+        assert_eq!(body["kind"], "Deployment");
+        assert!(
+            body["spec"]["template"]["metadata"]["annotations"][super::RESTARTED_AT_ANNOTATION].is_string()
+        );
+    }
+
+    #[test]
+    fn restart_rejects_unsupported_kinds() {
+        use crate::{request::Error, TypeMeta};
+        use k8s_openapi::api::apps::v1::ReplicaSet;
+
+        let url = ReplicaSet::url_path(&(), Some("ns"));
+        let err = Request::new(url)
+            .restart("myrs", &TypeMeta::resource::<ReplicaSet>())
+            .unwrap_err();
+        assert!(matches!(err, Error::UnsupportedRestart(kind) if kind == "apps/v1/ReplicaSet"));
     }
 
     #[test]
@@ -0,0 +1,276 @@
+// Generated by darling macros, out of our control
+#![allow(clippy::manual_unwrap_or_default)]
+
+use darling::{util::Flag, FromDeriveInput, FromField, FromMeta};
+use proc_macro2::{Ident, TokenStream};
+use syn::{parse_quote, Data, DeriveInput, Fields, GenericArgument, LitStr, Path, PathArguments, Type};
+
+/// Values we can parse from #[apply(attrs)] on the struct
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(apply))]
+struct ApplyAttrs {
+    #[darling(default)]
+    crates: Crates,
+}
+
+/// Values we can parse from #[apply(attrs)] on fields
+#[derive(Debug, FromField)]
+#[darling(attributes(apply))]
+struct ApplyFieldAttrs {
+    /// Use the apply configuration of the field type, rather than the full type
+    nested: Flag,
+}
+
+#[derive(Debug, FromMeta)]
+struct Crates {
+    #[darling(default = "Self::default_serde")]
+    serde: Path,
+    #[darling(default = "Self::default_std")]
+    std: Path,
+}
+
+// Default is required when the subattribute isn't mentioned at all
+// Delegate to darling rather than deriving, so that we can piggyback off the `#[darling(default)]` clauses
+impl Default for Crates {
+    fn default() -> Self {
+        Self::from_list(&[]).unwrap()
+    }
+}
+
+impl Crates {
+    fn default_serde() -> Path {
+        parse_quote! { ::serde }
+    }
+
+    fn default_std() -> Path {
+        parse_quote! { ::std }
+    }
+}
+
+/// The serde attributes that carry over to the apply configuration
+#[derive(Debug, Default)]
+pub(crate) struct SerdeAttrs {
+    pub(crate) rename: Option<LitStr>,
+    pub(crate) rename_all: Option<LitStr>,
+    pub(crate) flatten: bool,
+    pub(crate) skip: bool,
+}
+
+impl SerdeAttrs {
+    pub(crate) fn parse(attrs: &[syn::Attribute]) -> syn::Result<Self> {
+        let mut serde_attrs = Self::default();
+        for attr in attrs.iter().filter(|attr| attr.path().is_ident("serde")) {
+            attr.parse_nested_meta(|meta| {
+                if meta.path.is_ident("rename") {
+                    serde_attrs.rename = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("rename_all") {
+                    serde_attrs.rename_all = Some(meta.value()?.parse()?);
+                } else if meta.path.is_ident("flatten") {
+                    serde_attrs.flatten = true;
+                } else if meta.path.is_ident("skip") || meta.path.is_ident("skip_serializing") {
+                    serde_attrs.skip = true;
+                } else if meta.input.peek(syn::Token![=]) {
+                    // Irrelevant for serialization of the apply configuration, such as `default` or `alias`
+                    meta.value()?.parse::<syn::Expr>()?;
+                } else if meta.input.peek(syn::token::Paren) {
+                    let content;
+                    syn::parenthesized!(content in meta.input);
+                    content.parse::<TokenStream>()?;
+                }
+                Ok(())
+            })?;
+        }
+        Ok(serde_attrs)
+    }
+}
+
+pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let derive_input: DeriveInput = match syn::parse2(input) {
+        Err(err) => return err.to_compile_error(),
+        Ok(di) => di,
+    };
+    let fields = match &derive_input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return syn::Error::new_spanned(
+                    &derive_input.ident,
+                    r#"#[derive(ApplyConfiguration)] requires a struct with named fields"#,
+                )
+                .to_compile_error()
+            }
+        },
+        _ => {
+            return syn::Error::new_spanned(
+                &derive_input.ident,
+                r#"Enums or Unions can not #[derive(ApplyConfiguration)]"#,
+            )
+            .to_compile_error()
+        }
+    };
+    let ApplyAttrs {
+        crates: Crates { serde, std },
+    } = match ApplyAttrs::from_derive_input(&derive_input) {
+        Err(err) => return err.write_errors(),
+        Ok(attrs) => attrs,
+    };
+    let container_serde = match SerdeAttrs::parse(&derive_input.attrs) {
+        Err(err) => return err.to_compile_error(),
+        Ok(attrs) => attrs,
+    };
+
+    let visibility = &derive_input.vis;
+    let ident = &derive_input.ident;
+    let apply_ident = apply_ident(ident);
+    let (impl_generics, ty_generics, where_clause) = derive_input.generics.split_for_impl();
+    let serde_str = quote! { #serde }.to_string().replace(' ', "");
+
+    let mut apply_fields = Vec::new();
+    let mut setters = Vec::new();
+    for field in fields {
+        let field_attrs = match ApplyFieldAttrs::from_field(field) {
+            Err(err) => return err.write_errors(),
+            Ok(attrs) => attrs,
+        };
+        let field_serde = match SerdeAttrs::parse(&field.attrs) {
+            Err(err) => return err.to_compile_error(),
+            Ok(attrs) => attrs,
+        };
+        if field_serde.skip {
+            continue;
+        }
+
+        let field_vis = &field.vis;
+        let field_ident = field.ident.as_ref().expect("named fields have idents");
+        let inner_ty = option_inner(&field.ty).unwrap_or(&field.ty);
+        let ty = if field_attrs.nested.is_present() {
+            match nested_apply_type(inner_ty) {
+                Some(ty) => ty,
+                None => {
+                    return syn::Error::new_spanned(
+                        &field.ty,
+                        r#"#[apply(nested)] requires a field type that also derives ApplyConfiguration"#,
+                    )
+                    .to_compile_error()
+                }
+            }
+        } else {
+            inner_ty.clone()
+        };
+
+        let rename = field_serde.rename.map(|rename| quote! { rename = #rename, });
+        let serde_attr = if field_serde.flatten {
+            quote! { #[serde(#rename flatten)] }
+        } else {
+            quote! { #[serde(#rename skip_serializing_if = "Option::is_none")] }
+        };
+        let docs = field.attrs.iter().filter(|attr| attr.path().is_ident("doc"));
+        apply_fields.push(quote! {
+            #(#docs)*
+            #serde_attr
+            #field_vis #field_ident: #std::option::Option<#ty>,
+        });
+
+        let setter_doc = format!("Set `{field_ident}` in the apply configuration");
+        setters.push(quote! {
+            #[doc = #setter_doc]
+            #[must_use]
+            #field_vis fn #field_ident(mut self, #field_ident: impl #std::convert::Into<#ty>) -> Self {
+                self.#field_ident = #std::option::Option::Some(#field_ident.into());
+                self
+            }
+        });
+    }
+
+    let rename_all = container_serde
+        .rename_all
+        .map(|rename_all| quote! { #[serde(rename_all = #rename_all)] });
+    let doc = format!(
+        "Partial [`{ident}`] for server-side apply, generated by `#[derive(ApplyConfiguration)]`\n\n\
+         Only the fields that are set are serialized."
+    );
+    let generics = &derive_input.generics;
+
+    quote! {
+        #[doc = #doc]
+        #[derive(#std::clone::Clone, #std::fmt::Debug, #std::default::Default, #serde::Serialize)]
+        #[serde(crate = #serde_str)]
+        #rename_all
+        #visibility struct #apply_ident #generics #where_clause {
+            #(#apply_fields)*
+        }
+
+        impl #impl_generics #apply_ident #ty_generics #where_clause {
+            #(#setters)*
+        }
+    }
+}
+
+fn apply_ident(ident: &Ident) -> Ident {
+    format_ident!("{}ApplyConfiguration", ident)
+}
+
+/// Extracts `T` from `Option<T>`
+fn option_inner(ty: &Type) -> Option<&Type> {
+    let Type::Path(path) = ty else { return None };
+    let segment = path.path.segments.last()?;
+    if segment.ident != "Option" {
+        return None;
+    }
+    let PathArguments::AngleBracketed(args) = &segment.arguments else {
+        return None;
+    };
+    match args.args.first()? {
+        GenericArgument::Type(inner) if args.args.len() == 1 => Some(inner),
+        _ => None,
+    }
+}
+
+/// Maps `path::Foo<T>` to `path::FooApplyConfiguration<T>`
+fn nested_apply_type(ty: &Type) -> Option<Type> {
+    let Type::Path(path) = ty else { return None };
+    let mut path = path.clone();
+    let segment = path.path.segments.last_mut()?;
+    segment.ident = apply_ident(&segment.ident);
+    Some(Type::Path(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_derive_apply_configuration() {
+        let input = quote! {
+            #[serde(rename_all = "camelCase")]
+            pub struct FooSpec {
+                /// Number of replicas
+                pub replicas: i32,
+                #[serde(rename = "nodeName")]
+                node: Option<String>,
+                #[apply(nested)]
+                template: Option<BarSpec>,
+                #[serde(skip)]
+                cache: String,
+            }
+        };
+        let output = derive(input).to_string();
+        let expected = quote! {
+            #[doc = "Partial [`FooSpec`] for server-side apply, generated by `#[derive(ApplyConfiguration)]`\n\nOnly the fields that are set are serialized."]
+            #[derive(::std::clone::Clone, ::std::fmt::Debug, ::std::default::Default, ::serde::Serialize)]
+            #[serde(crate = "::serde")]
+            #[serde(rename_all = "camelCase")]
+            pub struct FooSpecApplyConfiguration {
+                /// Number of replicas
+                #[serde(skip_serializing_if = "Option::is_none")]
+                pub replicas: ::std::option::Option<i32>,
+                #[serde(rename = "nodeName", skip_serializing_if = "Option::is_none")]
+                node: ::std::option::Option<String>,
+                #[serde(skip_serializing_if = "Option::is_none")]
+                template: ::std::option::Option<BarSpecApplyConfiguration>,
+            }
+        }
+        .to_string();
+        assert!(output.starts_with(&expected), "{output}");
+    }
+}
@@ -1,4 +1,4 @@
-use darling::{FromDeriveInput, FromField, FromMeta};
+use darling::{util::SpannedValue, FromDeriveInput, FromField, FromMeta};
 use proc_macro2::TokenStream;
 use syn::{parse_quote, Attribute, DeriveInput, Expr, Ident, Path};
 
@@ -9,6 +9,57 @@ struct Rule {
     rules: Vec<Expr>,
 }
 
+/// The schema extensions from `#[x_kube(..)]`, such as `#[x_kube(list_type = "map", list_map_key = "name")]`
+#[derive(FromField)]
+#[darling(attributes(x_kube))]
+struct Extensions {
+    list_type: Option<SpannedValue<String>>,
+    #[darling(multiple, rename = "list_map_key")]
+    list_map_keys: Vec<String>,
+    map_type: Option<SpannedValue<String>>,
+    #[darling(default)]
+    preserve_unknown_fields: bool,
+    #[darling(default)]
+    embedded_resource: bool,
+}
+
+impl Extensions {
+    fn has_merge_strategy(&self) -> bool {
+        self.list_type.is_some() || !self.list_map_keys.is_empty() || self.map_type.is_some()
+    }
+
+    fn has_markers(&self) -> bool {
+        self.preserve_unknown_fields || self.embedded_resource
+    }
+
+    /// Rejects merge strategies that the apiserver would refuse
+    fn validate(self, field: &syn::Field) -> darling::Result<Self> {
+        let mut errors = darling::Error::accumulator();
+        let known = [
+            (&self.list_type, &["atomic", "set", "map"][..]),
+            (&self.map_type, &["atomic", "granular"][..]),
+        ];
+        for (value, alternates) in known {
+            if let Some(value) = value
+                .as_ref()
+                .filter(|value| !alternates.contains(&value.as_str()))
+            {
+                let msg = format!("expected one of `{}`", alternates.join("`, `"));
+                errors.push(syn::Error::new(value.span(), msg).into());
+            }
+        }
+        let is_map = self
+            .list_type
+            .as_ref()
+            .is_some_and(|list_type| list_type.as_str() == "map");
+        if is_map == self.list_map_keys.is_empty() {
+            let msg = "`list_map_key` is required with, and only allowed with `list_type = \"map\"`";
+            errors.push(darling::Error::custom(msg).with_span(field));
+        }
+        errors.finish_with(self)
+    }
+}
+
 #[derive(FromDeriveInput)]
 #[darling(attributes(cel_validate), supports(struct_named))]
 struct CELSchema {
@@ -93,16 +144,52 @@ pub(crate) fn derive_validated_schema(input: TokenStream) -> TokenStream {
                 Ok(rule) => rule,
                 Err(err) => return err.write_errors(),
             };
+            let extensions = match Extensions::from_field(field).and_then(|e| e.validate(field)) {
+                Ok(extensions) => extensions,
+                Err(err) => return err.write_errors(),
+            };
 
             // Remove all unknown attributes from each field
             // Has to happen on the original definition at all times, as we don't have #[derive] stanzes.
             field.attrs = remove_attributes(&field.attrs, &attribute_whitelist);
 
-            if rules.is_empty() {
+            if rules.is_empty() && !extensions.has_merge_strategy() && !extensions.has_markers() {
                 continue;
             }
 
-            let rules: Vec<TokenStream> = rules.iter().map(|r| quote! {#r,}).collect();
+            let validate = (!rules.is_empty()).then(|| {
+                let rules: Vec<TokenStream> = rules.iter().map(|r| quote! {#r,}).collect();
+                quote! { #kube_core::validate_property(merge, 0, &[#(#rules)*]).unwrap(); }
+            });
+            let set_merge_strategy = extensions.has_merge_strategy().then(|| {
+                let Extensions {
+                    list_type,
+                    list_map_keys,
+                    map_type,
+                    ..
+                } = &extensions;
+                let list_type = match list_type.as_ref().map(|list_type| list_type.as_str()) {
+                    Some(list_type) => quote! { Some(#list_type) },
+                    None => quote! { None },
+                };
+                let map_type = match map_type.as_ref().map(|map_type| map_type.as_str()) {
+                    Some(map_type) => quote! { Some(#map_type) },
+                    None => quote! { None },
+                };
+                quote! {
+                    #kube_core::schema::set_property_merge_strategy(merge, 0, #list_type, &[#(#list_map_keys),*], #map_type);
+                }
+            });
+            let set_markers = extensions.has_markers().then(|| {
+                let Extensions {
+                    preserve_unknown_fields,
+                    embedded_resource,
+                    ..
+                } = &extensions;
+                quote! {
+                    #kube_core::schema::set_property_markers(merge, 0, #preserve_unknown_fields, #embedded_resource);
+                }
+            });
 
             // We need to prepend derive macros, as they were consumed by this macro processing, being a derive by itself.
             property_modifications.push(quote! {
@@ -116,7 +203,9 @@ pub(crate) fn derive_validated_schema(input: TokenStream) -> TokenStream {
                     }
 
                     let merge = &mut Validated::json_schema(gen);
-                    #kube_core::validate_property(merge, 0, &[#(#rules)*]).unwrap();
+                    #validate
+                    #set_merge_strategy
+                    #set_markers
                     #kube_core::merge_properties(s, merge);
                 }
             });
@@ -232,4 +321,31 @@ mod tests {
         let expected = unparse(&syn::File::parse.parse2(expected).unwrap());
         assert_eq!(output, expected);
     }
+
+    #[test]
+    fn test_extensions_validation() {
+        let valid = [
+            quote! { #[x_kube(list_type = "map", list_map_key = "name")] ports: Vec<Port> },
+            quote! { #[x_kube(list_type = "set")] hosts: Vec<String> },
+            quote! { #[x_kube(map_type = "granular")] labels: BTreeMap<String, String> },
+            quote! { #[x_kube(preserve_unknown_fields, embedded_resource)] template: Value },
+        ];
+        for field in valid {
+            let field = syn::Field::parse_named.parse2(field).unwrap();
+            let extensions = Extensions::from_field(&field).unwrap();
+            assert!(extensions.validate(&field).is_ok());
+        }
+
+        let invalid = [
+            quote! { #[x_kube(list_type = "map")] ports: Vec<Port> },
+            quote! { #[x_kube(list_type = "set", list_map_key = "name")] ports: Vec<Port> },
+            quote! { #[x_kube(list_type = "unique")] hosts: Vec<String> },
+            quote! { #[x_kube(map_type = "merge")] labels: BTreeMap<String, String> },
+        ];
+        for field in invalid {
+            let field = syn::Field::parse_named.parse2(field).unwrap();
+            let extensions = Extensions::from_field(&field).unwrap();
+            assert!(extensions.validate(&field).is_err());
+        }
+    }
 }
@@ -0,0 +1,154 @@
+// Generated by darling macros, out of our control
+#![allow(clippy::manual_unwrap_or_default)]
+
+use darling::{ast, util::Ignored, FromDeriveInput, FromMeta, FromVariant};
+use proc_macro2::{Ident, TokenStream};
+use syn::{parse_quote, DeriveInput, Path, Visibility};
+
+/// Values we can parse from #[condition(attrs)] on the enum
+#[derive(Debug, FromDeriveInput)]
+#[darling(attributes(condition), supports(enum_unit))]
+struct ConditionSetAttrs {
+    ident: Ident,
+    vis: Visibility,
+    data: ast::Data<ConditionVariant, Ignored>,
+    /// Name of the generated extension trait for `Conditions` (defaults to `{Enum}Ext`)
+    trait_name: Option<Ident>,
+    #[darling(default)]
+    crates: Crates,
+}
+
+/// Values we can parse from #[condition(attrs)] on variants
+#[derive(Debug, FromVariant)]
+#[darling(attributes(condition))]
+struct ConditionVariant {
+    ident: Ident,
+    /// The condition `type` (defaults to the variant name)
+    rename: Option<String>,
+}
+
+#[derive(Debug, FromMeta)]
+struct Crates {
+    #[darling(default = "Self::default_kube_core")]
+    kube_core: Path,
+}
+
+// Default is required when the subattribute isn't mentioned at all
+// Delegate to darling rather than deriving, so that we can piggyback off the `#[darling(default)]` clauses
+impl Default for Crates {
+    fn default() -> Self {
+        Self::from_list(&[]).unwrap()
+    }
+}
+
+impl Crates {
+    fn default_kube_core() -> Path {
+        parse_quote! { ::kube::core } // by default must work well with people using facade crate
+    }
+}
+
+pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    let derive_input: DeriveInput = match syn::parse2(input) {
+        Err(err) => return err.to_compile_error(),
+        Ok(di) => di,
+    };
+    let ConditionSetAttrs {
+        ident,
+        vis,
+        data,
+        trait_name,
+        crates: Crates { kube_core },
+    } = match ConditionSetAttrs::from_derive_input(&derive_input) {
+        Err(err) => return err.write_errors(),
+        Ok(attrs) => attrs,
+    };
+    let variants = data.take_enum().expect("supports(enum_unit) only accepts enums");
+    let trait_name = trait_name.unwrap_or_else(|| format_ident!("{}Ext", ident));
+
+    let variant_idents = variants.iter().map(|v| &v.ident).collect::<Vec<_>>();
+    let types = variants
+        .iter()
+        .map(|v| v.rename.clone().unwrap_or_else(|| v.ident.to_string()))
+        .collect::<Vec<_>>();
+
+    let mut trait_fns = TokenStream::new();
+    let mut impl_fns = TokenStream::new();
+    for (variant, type_) in variants.iter().zip(&types) {
+        let name = to_snake_case(&variant.ident.to_string());
+        let is_fn = format_ident!("is_{}", name);
+        let set_fn = format_ident!("set_{}", name);
+        let is_doc = format!(" Whether the `{type_}` condition is present and `True`");
+        let set_doc = format!(" Adds or updates the `{type_}` condition, returning whether anything changed");
+        let set_args = quote! {
+            status: impl ::std::convert::Into<#kube_core::conditions::ConditionStatus>,
+            reason: impl ::std::convert::Into<::std::string::String>,
+            message: impl ::std::convert::Into<::std::string::String>
+        };
+        trait_fns.extend(quote! {
+            #[doc = #is_doc]
+            fn #is_fn(&self) -> bool;
+            #[doc = #set_doc]
+            fn #set_fn(&mut self, #set_args) -> bool;
+        });
+        impl_fns.extend(quote! {
+            fn #is_fn(&self) -> bool {
+                self.is_true(#type_)
+            }
+            fn #set_fn(&mut self, #set_args) -> bool {
+                self.set(#kube_core::conditions::NewCondition::new(#type_, status, reason).message(message))
+            }
+        });
+    }
+
+    let trait_doc = format!(" Typed accessors for the conditions of [`{ident}`]");
+    quote! {
+        impl #kube_core::conditions::ConditionSet for #ident {
+            const ALL: &'static [Self] = &[#(Self::#variant_idents),*];
+
+            fn as_str(&self) -> &'static str {
+                match self {
+                    #(Self::#variant_idents => #types,)*
+                }
+            }
+        }
+
+        #[doc = #trait_doc]
+        #vis trait #trait_name {
+            #trait_fns
+        }
+
+        impl #trait_name for #kube_core::conditions::Conditions {
+            #impl_fns
+        }
+    }
+}
+
+/// Converts a `PascalCase` variant name into a `snake_case` method name, keeping acronyms together
+fn to_snake_case(name: &str) -> String {
+    let chars = name.chars().collect::<Vec<_>>();
+    let mut snake = String::new();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_lowercase());
+            if prev.is_lowercase() || prev.is_ascii_digit() || (prev.is_uppercase() && next_lower) {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+    snake
+}
+
+#[cfg(test)]
+mod tests {
+    use super::to_snake_case;
+
+    #[test]
+    fn test_to_snake_case() {
+        assert_eq!(to_snake_case("Ready"), "ready");
+        assert_eq!(to_snake_case("PodScheduled"), "pod_scheduled");
+        assert_eq!(to_snake_case("TLSReady"), "tls_ready");
+        assert_eq!(to_snake_case("Http2Enabled"), "http2_enabled");
+    }
+}
@@ -1,9 +1,11 @@
 // Generated by darling macros, out of our control
 #![allow(clippy::manual_unwrap_or_default)]
-use darling::{FromDeriveInput, FromMeta};
+use darling::{util::SpannedValue, FromDeriveInput, FromField, FromMeta};
 use proc_macro2::{Ident, Literal, Span, TokenStream};
 use quote::{ToTokens, TokenStreamExt as _};
-use syn::{parse_quote, Data, DeriveInput, Expr, Path, Visibility};
+use syn::{parse_quote, Data, DeriveInput, Expr, LitStr, Path, Visibility};
+
+use crate::apply_configuration::SerdeAttrs;
 
 /// Values we can parse from #[kube(attrs)]
 #[derive(Debug, FromDeriveInput)]
@@ -25,15 +27,21 @@ struct KubeAttrs {
     derives: Vec<String>,
     schema: Option<SchemaMode>,
     status: Option<String>,
+    /// Defaults an omitted spec to `Default::default()`, also in the schema
+    #[darling(default, rename = "default")]
+    default_spec: bool,
     #[darling(multiple, rename = "category")]
     categories: Vec<String>,
+    /// More categories at once, as `categories = ["all", "apps"]`
+    #[darling(default, rename = "categories")]
+    category_list: StringList,
     #[darling(multiple, rename = "shortname")]
     shortnames: Vec<String>,
     #[darling(multiple, rename = "printcolumn")]
-    printcolums: Vec<String>,
+    printcolums: Vec<PrintColumn>,
     #[darling(multiple)]
     selectable: Vec<String>,
-    scale: Option<String>,
+    scale: Option<Scale>,
     #[darling(default)]
     crates: Crates,
     #[darling(multiple, rename = "annotation")]
@@ -42,6 +50,9 @@ struct KubeAttrs {
     labels: Vec<KVTuple>,
     #[darling(multiple, rename = "rule")]
     rules: Vec<Expr>,
+    /// Generates a `merge_from` method that respects the `merge_key`s of spec fields
+    #[darling(default)]
+    merge: bool,
 
     /// Sets the `storage` property to `true` or `false`.
     ///
@@ -54,6 +65,163 @@ struct KubeAttrs {
     /// Defaults to `true`.
     #[darling(default = default_served_arg)]
     served: bool,
+
+    /// Marks the version as deprecated, optionally with a `deprecationWarning` for clients.
+    deprecated: Option<Deprecated>,
+}
+
+/// Values we can parse from #[kube(attrs)] on fields of the spec
+#[derive(Debug, FromField)]
+#[darling(attributes(kube))]
+struct KubeFieldAttrs {
+    #[darling(multiple, rename = "printcolumn")]
+    printcolumns: Vec<PrinterColumn>,
+    merge_key: Option<String>,
+}
+
+/// A printer column, either as raw json or as `printcolumn(name = "..", type_ = "..")`
+#[derive(Debug)]
+enum PrintColumn {
+    Json(String),
+    Column(PrinterColumn),
+}
+
+impl FromMeta for PrintColumn {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(PrintColumn::Json(value.to_string()))
+    }
+
+    fn from_list(items: &[darling::ast::NestedMeta]) -> darling::Result<Self> {
+        PrinterColumn::from_list(items).map(PrintColumn::Column)
+    }
+}
+
+#[derive(Debug, FromMeta)]
+struct PrinterColumn {
+    name: String,
+    /// `type` is a keyword, which cannot be used as a key in attributes
+    type_: String,
+    /// Required on the struct, inferred from the field name on spec fields
+    json_path: Option<String>,
+    description: Option<String>,
+    format: Option<String>,
+    priority: Option<i32>,
+}
+
+impl PrinterColumn {
+    fn to_json(&self, json_path: &str) -> String {
+        let mut column = serde_json::json!({
+            "name": self.name,
+            "type": self.type_,
+            "jsonPath": json_path,
+        });
+        if let Some(description) = &self.description {
+            column["description"] = description.as_str().into();
+        }
+        if let Some(format) = &self.format {
+            column["format"] = format.as_str().into();
+        }
+        if let Some(priority) = self.priority {
+            column["priority"] = priority.into();
+        }
+        column.to_string()
+    }
+}
+
+/// The scale subresource, either as raw json or as `scale(spec_replicas = "..", ..)`
+#[derive(Debug)]
+enum Scale {
+    Json(String),
+    Paths(ScalePaths),
+}
+
+impl FromMeta for Scale {
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Scale::Json(value.to_string()))
+    }
+
+    fn from_list(items: &[darling::ast::NestedMeta]) -> darling::Result<Self> {
+        ScalePaths::from_list(items).map(Scale::Paths)
+    }
+}
+
+#[derive(Debug, FromMeta)]
+#[darling(and_then = Self::validate)]
+struct ScalePaths {
+    spec_replicas: SpannedValue<String>,
+    status_replicas: SpannedValue<String>,
+    label_selector: Option<SpannedValue<String>>,
+}
+
+impl ScalePaths {
+    /// Rejects paths that the apiserver would refuse, since it only allows `.spec` and `.status` paths
+    fn validate(self) -> darling::Result<Self> {
+        let mut errors = darling::Error::accumulator();
+        let paths = [
+            ("spec_replicas", Some(&self.spec_replicas), &[".spec."][..]),
+            ("status_replicas", Some(&self.status_replicas), &[".status."][..]),
+            (
+                "label_selector",
+                self.label_selector.as_ref(),
+                &[".spec.", ".status."][..],
+            ),
+        ];
+        for (name, path, prefixes) in paths {
+            let invalid = |path: &&SpannedValue<String>| !prefixes.iter().any(|p| path.starts_with(p));
+            if let Some(path) = path.filter(invalid) {
+                let msg = format!(
+                    "`{name}` must be a JSON path starting with `{}`",
+                    prefixes.join("` or `")
+                );
+                errors.push(syn::Error::new(path.span(), msg).into());
+            }
+        }
+        errors.finish_with(self)
+    }
+
+    fn to_json(&self) -> String {
+        let mut scale = serde_json::json!({
+            "specReplicasPath": self.spec_replicas.as_str(),
+            "statusReplicasPath": self.status_replicas.as_str(),
+        });
+        if let Some(label_selector) = &self.label_selector {
+            scale["labelSelectorPath"] = label_selector.as_str().into();
+        }
+        scale.to_string()
+    }
+}
+
+/// A list of strings, as `["a", "b"]`
+#[derive(Debug, Default)]
+struct StringList(Vec<String>);
+
+impl FromMeta for StringList {
+    fn from_expr(expr: &Expr) -> darling::Result<Self> {
+        let Expr::Array(array) = expr else {
+            return Err(darling::Error::unexpected_expr_type(expr));
+        };
+        let mut errors = darling::Error::accumulator();
+        let strings = array
+            .elems
+            .iter()
+            .filter_map(|elem| errors.handle(String::from_expr(elem).map_err(|err| err.with_span(elem))))
+            .collect();
+        errors.finish_with(StringList(strings))
+    }
+}
+
+/// A deprecated version, either as `deprecated` or with a warning as `deprecated = "use v2"`
+#[derive(Debug)]
+struct Deprecated(Option<String>);
+
+impl FromMeta for Deprecated {
+    fn from_word() -> darling::Result<Self> {
+        Ok(Deprecated(None))
+    }
+
+    fn from_string(value: &str) -> darling::Result<Self> {
+        Ok(Deprecated(Some(value.to_string())))
+    }
 }
 
 #[derive(Debug)]
@@ -224,16 +392,20 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         derives,
         schema: schema_mode,
         status,
+        default_spec,
         plural,
         singular,
-        categories,
+        mut categories,
+        category_list,
         shortnames,
         printcolums,
         selectable,
         scale,
         rules,
+        merge,
         storage,
         served,
+        deprecated,
         crates:
             Crates {
                 kube_core,
@@ -256,6 +428,14 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         )
         .to_compile_error();
     }
+    let printcolums = match process_printcolumns(printcolums, &derive_input) {
+        Err(err) => return err.write_errors(),
+        Ok(columns) => columns,
+    };
+    let merge_keys = match process_merge_keys(merge, &derive_input) {
+        Err(err) => return err.write_errors(),
+        Ok(keys) => keys,
+    };
     let visibility = derive_input.vis;
     let ident = derive_input.ident;
 
@@ -335,6 +515,13 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         quote! { None }
     };
 
+    // Makes schemars emit the serialized `Default` of the spec as the schema default
+    let spec_default = if default_spec {
+        quote! { #[serde(default)] }
+    } else {
+        quote! {}
+    };
+
     let docstr =
         doc.unwrap_or_else(|| format!(" Auto-generated derived type for {ident} via `CustomResource`"));
     let quoted_serde = Literal::string(&serde.to_token_stream().to_string());
@@ -349,6 +536,7 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         #visibility struct #rootident {
             #schemars_skip
             #visibility metadata: #k8s_openapi::apimachinery::pkg::apis::meta::v1::ObjectMeta,
+            #spec_default
             #visibility spec: #ident,
             #status_field
         }
@@ -452,7 +640,11 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         .map(|s| format!(r#"{{ "jsonPath": "{s}" }}"#))
         .collect();
     let fields = format!("[ {} ]", fields.join(","));
-    let scale_code = if let Some(s) = scale { s } else { "".to_string() };
+    let scale_code = match scale {
+        Some(Scale::Json(json)) => json,
+        Some(Scale::Paths(paths)) => paths.to_json(),
+        None => "".to_string(),
+    };
 
     // Ensure it generates for the correct CRD version (only v1 supported now)
     let apiext = quote! {
@@ -470,6 +662,7 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         quote! { &[#names] }
     };
 
+    categories.extend(category_list.0);
     let categories_json = serde_json::to_string(&categories).unwrap();
     let short_json = serde_json::to_string(&shortnames).unwrap();
     let crd_meta_name = format!("{plural}.{group}");
@@ -512,6 +705,12 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         quote! {}
     };
 
+    let deprecation = match deprecated {
+        Some(Deprecated(Some(warning))) => quote! { "deprecated": true, "deprecationWarning": #warning, },
+        Some(Deprecated(None)) => quote! { "deprecated": true, },
+        None => quote! {},
+    };
+
     // Known constraints that are hard to enforce elsewhere
     let compile_constraints = if !selectable.is_empty() {
         quote! {
@@ -544,6 +743,7 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
                     "name": #version,
                     "served": #served,
                     "storage": #storage,
+                    #deprecation
                     "schema": {
                         "openAPIV3Schema": schema,
                     },
@@ -603,6 +803,31 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         }
     };
 
+    // 5. Implement merge_from if requested
+    let impl_merge = if merge {
+        let (paths, keys): (Vec<_>, Vec<_>) = merge_keys.into_iter().unzip();
+        quote! {
+            impl #rootident {
+                /// Merges the set fields of `other` into this object, see `kube::core::merge::merge`
+                ///
+                /// Lists of the spec are merged by their `#[kube(merge_key)]`, owner references by their uid,
+                /// and all other lists are replaced.
+                pub fn merge_from(&mut self, other: &Self) -> #std::result::Result<(), #serde_json::Error> {
+                    let mut merged = #serde_json::to_value(&*self)?;
+                    let other = #serde_json::to_value(other)?;
+                    #kube_core::merge::merge(&mut merged, &other, &[
+                        (".metadata.ownerReferences", "uid"),
+                        #((#paths, #keys),)*
+                    ]);
+                    *self = #serde_json::from_value(merged)?;
+                    Ok(())
+                }
+            }
+        }
+    } else {
+        quote! {}
+    };
+
     let impl_hasspec = generate_hasspec(&ident, &rootident, &kube_core);
 
     // Concat output
@@ -612,6 +837,7 @@ pub(crate) fn derive(input: proc_macro2::TokenStream) -> proc_macro2::TokenStrea
         #impl_resource
         #impl_default
         #impl_crd
+        #impl_merge
         #impl_hasspec
         #impl_hasstatus
     }
@@ -705,6 +931,142 @@ fn process_status(
 // Simple pluralizer.
 // Duplicating the code from kube (without special casing) because it's simple enough.
 // Irregular plurals must be explicitly specified.
+/// Serializes the struct level printer columns, followed by those on fields of the spec
+fn process_printcolumns(columns: Vec<PrintColumn>, input: &DeriveInput) -> darling::Result<Vec<String>> {
+    let mut errors = darling::Error::accumulator();
+    let mut printcolumns = vec![];
+    for column in columns {
+        match column {
+            PrintColumn::Json(json) => printcolumns.push(json),
+            PrintColumn::Column(column) => match &column.json_path {
+                Some(json_path) => printcolumns.push(column.to_json(json_path)),
+                None => errors.push(
+                    darling::Error::custom("`json_path` is required for printcolumns on the struct")
+                        .with_span(&input.ident),
+                ),
+            },
+        }
+    }
+
+    let Data::Struct(data) = &input.data else {
+        return errors.finish_with(printcolumns);
+    };
+    let rename_all = errors
+        .handle(SerdeAttrs::parse(&input.attrs).map_err(darling::Error::from))
+        .and_then(|attrs| attrs.rename_all);
+    for field in &data.fields {
+        let Some(attrs) = errors.handle(KubeFieldAttrs::from_field(field)) else {
+            continue;
+        };
+        if attrs.printcolumns.is_empty() {
+            continue;
+        }
+        let Some(name) = errors.handle(serialized_name(
+            field,
+            rename_all.as_ref(),
+            "printcolumns are only supported on named and serialized fields",
+        )) else {
+            continue;
+        };
+        for column in attrs.printcolumns {
+            let json_path = column
+                .json_path
+                .clone()
+                .unwrap_or_else(|| format!(".spec.{name}"));
+            printcolumns.push(column.to_json(&json_path));
+        }
+    }
+    errors.finish_with(printcolumns)
+}
+
+/// Collects the `.spec` paths and merge keys of spec fields with a `merge_key`
+fn process_merge_keys(merge: bool, input: &DeriveInput) -> darling::Result<Vec<(String, String)>> {
+    let mut errors = darling::Error::accumulator();
+    let mut merge_keys = vec![];
+    let Data::Struct(data) = &input.data else {
+        return errors.finish_with(merge_keys);
+    };
+    let rename_all = errors
+        .handle(SerdeAttrs::parse(&input.attrs).map_err(darling::Error::from))
+        .and_then(|attrs| attrs.rename_all);
+    for field in &data.fields {
+        let Some(KubeFieldAttrs {
+            merge_key: Some(key), ..
+        }) = errors.handle(KubeFieldAttrs::from_field(field))
+        else {
+            continue;
+        };
+        if !merge {
+            errors.push(darling::Error::custom("`merge_key` requires `#[kube(merge)]`").with_span(field));
+            continue;
+        }
+        if let Some(name) = errors.handle(serialized_name(
+            field,
+            rename_all.as_ref(),
+            "`merge_key` is only supported on named and serialized fields",
+        )) {
+            merge_keys.push((format!(".spec.{name}"), key));
+        }
+    }
+    errors.finish_with(merge_keys)
+}
+
+/// The serialized name of a field, or an error with `unsupported` for unnamed, flattened and skipped fields
+fn serialized_name(
+    field: &syn::Field,
+    rename_all: Option<&LitStr>,
+    unsupported: &str,
+) -> darling::Result<String> {
+    let serde_attrs = SerdeAttrs::parse(&field.attrs)?;
+    let Some(ident) = field
+        .ident
+        .as_ref()
+        .filter(|_| !serde_attrs.flatten && !serde_attrs.skip)
+    else {
+        return Err(darling::Error::custom(unsupported).with_span(field));
+    };
+    match serde_attrs.rename {
+        Some(rename) => Ok(rename.value()),
+        None => rename_field(&ident.to_string(), rename_all),
+    }
+}
+
+/// Applies a serde `rename_all` rule to a field name
+fn rename_field(field: &str, rename_all: Option<&LitStr>) -> darling::Result<String> {
+    let field = field.trim_start_matches("r#");
+    let Some(rule) = rename_all else {
+        return Ok(field.to_string());
+    };
+    let pascal_case = || -> String {
+        field
+            .split('_')
+            .map(|word| {
+                let mut chars = word.chars();
+                chars
+                    .next()
+                    .map(|first| first.to_ascii_uppercase().to_string() + chars.as_str())
+                    .unwrap_or_default()
+            })
+            .collect()
+    };
+    Ok(match rule.value().as_str() {
+        "lowercase" | "snake_case" => field.to_string(),
+        "UPPERCASE" | "SCREAMING_SNAKE_CASE" => field.to_ascii_uppercase(),
+        "PascalCase" => pascal_case(),
+        "camelCase" => {
+            let pascal = pascal_case();
+            let mut chars = pascal.chars();
+            chars
+                .next()
+                .map(|first| first.to_ascii_lowercase().to_string() + chars.as_str())
+                .unwrap_or_default()
+        }
+        "kebab-case" => field.replace('_', "-"),
+        "SCREAMING-KEBAB-CASE" => field.to_ascii_uppercase().replace('_', "-"),
+        other => return Err(darling::Error::unknown_value(other).with_span(rule)),
+    })
+}
+
 fn to_plural(word: &str) -> String {
     // Words ending in s, x, z, ch, sh will be pluralized with -es (eg. foxes).
     if word.ends_with('s')
@@ -754,6 +1116,146 @@ mod tests {
         assert!(kube_attrs.namespaced);
     }
 
+    #[test]
+    fn test_categories_and_deprecation() {
+        let input = quote! {
+            #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+            #[kube(group = "clux.dev", version = "v1", kind = "Foo", category = "clux")]
+            #[kube(categories = ["all", "apps"], deprecated = "use v2")]
+            struct FooSpec { foo: String }
+        };
+        let input = syn::parse2(input).unwrap();
+        let kube_attrs = KubeAttrs::from_derive_input(&input).unwrap();
+        assert_eq!(kube_attrs.categories, ["clux"]);
+        assert_eq!(kube_attrs.category_list.0, ["all", "apps"]);
+        assert_eq!(kube_attrs.deprecated.unwrap().0.as_deref(), Some("use v2"));
+
+        let input = quote! {
+            #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+            #[kube(group = "clux.dev", version = "v1", kind = "Foo", deprecated)]
+            struct FooSpec { foo: String }
+        };
+        let input = syn::parse2(input).unwrap();
+        let kube_attrs = KubeAttrs::from_derive_input(&input).unwrap();
+        assert_eq!(kube_attrs.deprecated.unwrap().0, None);
+
+        let input = quote! {
+            #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+            #[kube(group = "clux.dev", version = "v1", kind = "Foo", categories = ["all", 1])]
+            struct FooSpec { foo: String }
+        };
+        let input = syn::parse2(input).unwrap();
+        assert!(KubeAttrs::from_derive_input(&input).is_err());
+    }
+
+    #[test]
+    fn test_field_printcolumns() {
+        let input = quote! {
+            #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+            #[kube(group = "clux.dev", version = "v1", kind = "Foo", status = "FooStatus")]
+            #[kube(printcolumn = r#"{"name":"Spec", "type":"string", "jsonPath":".spec"}"#)]
+            #[kube(printcolumn(name = "Ready", type_ = "boolean", json_path = ".status.ready"))]
+            #[serde(rename_all = "camelCase")]
+            struct FooSpec {
+                #[kube(printcolumn(name = "Replicas", type_ = "integer", priority = 1))]
+                min_replicas: i32,
+                #[serde(rename = "image")]
+                #[kube(printcolumn(name = "Image", type_ = "string", description = "Container image"))]
+                image_name: String,
+            }
+        };
+        let input: DeriveInput = syn::parse2(input).unwrap();
+        let kube_attrs = KubeAttrs::from_derive_input(&input).unwrap();
+        let columns = process_printcolumns(kube_attrs.printcolums, &input).unwrap();
+        let columns: Vec<serde_json::Value> =
+            columns.iter().map(|c| serde_json::from_str(c).unwrap()).collect();
+        assert_eq!(columns, vec![
+            serde_json::json!({ "name": "Spec", "type": "string", "jsonPath": ".spec" }),
+            serde_json::json!({ "name": "Ready", "type": "boolean", "jsonPath": ".status.ready" }),
+            serde_json::json!({ "name": "Replicas", "type": "integer", "jsonPath": ".spec.minReplicas", "priority": 1 }),
+            serde_json::json!({ "name": "Image", "type": "string", "jsonPath": ".spec.image", "description": "Container image" }),
+        ]);
+    }
+
+    #[test]
+    fn test_struct_printcolumn_requires_json_path() {
+        let input = quote! {
+            #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+            #[kube(group = "clux.dev", version = "v1", kind = "Foo")]
+            #[kube(printcolumn(name = "Foo", type_ = "string"))]
+            struct FooSpec { foo: String }
+        };
+        let input: DeriveInput = syn::parse2(input).unwrap();
+        let kube_attrs = KubeAttrs::from_derive_input(&input).unwrap();
+        assert!(process_printcolumns(kube_attrs.printcolums, &input).is_err());
+    }
+
+    #[test]
+    fn test_scale_paths() {
+        let input = quote! {
+            #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+            #[kube(group = "clux.dev", version = "v1", kind = "Foo", status = "FooStatus")]
+            #[kube(scale(spec_replicas = ".spec.replicas", status_replicas = ".status.replicas", label_selector = ".status.selector"))]
+            struct FooSpec { replicas: i32 }
+        };
+        let input = syn::parse2(input).unwrap();
+        let Some(Scale::Paths(scale)) = KubeAttrs::from_derive_input(&input).unwrap().scale else {
+            panic!("expected scale paths");
+        };
+        let scale: serde_json::Value = serde_json::from_str(&scale.to_json()).unwrap();
+        assert_eq!(
+            scale,
+            serde_json::json!({
+                "specReplicasPath": ".spec.replicas",
+                "statusReplicasPath": ".status.replicas",
+                "labelSelectorPath": ".status.selector",
+            })
+        );
+
+        let input = quote! {
+            #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+            #[kube(group = "clux.dev", version = "v1", kind = "Foo", status = "FooStatus")]
+            #[kube(scale(spec_replicas = ".spec.replicas", status_replica = ".status.replicas"))]
+            struct FooSpec { replicas: i32 }
+        };
+        let input = syn::parse2(input).unwrap();
+        let err = KubeAttrs::from_derive_input(&input).unwrap_err().to_string();
+        assert!(err.contains("status_replica"), "{err}");
+
+        let input = quote! {
+            #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, JsonSchema)]
+            #[kube(group = "clux.dev", version = "v1", kind = "Foo", status = "FooStatus")]
+            #[kube(scale(spec_replicas = "spec.replicas", status_replicas = ".status.replicas"))]
+            struct FooSpec { replicas: i32 }
+        };
+        let input = syn::parse2(input).unwrap();
+        let err = KubeAttrs::from_derive_input(&input).unwrap_err().to_string();
+        assert!(err.contains("`spec_replicas` must be a JSON path"), "{err}");
+    }
+
+    #[test]
+    fn test_rename_field() {
+        let rule = |rule: &str| LitStr::new(rule, Span::call_site());
+        assert_eq!(rename_field("r#type", None).unwrap(), "type");
+        assert_eq!(
+            rename_field("min_replicas", Some(&rule("camelCase"))).unwrap(),
+            "minReplicas"
+        );
+        assert_eq!(
+            rename_field("min_replicas", Some(&rule("PascalCase"))).unwrap(),
+            "MinReplicas"
+        );
+        assert_eq!(
+            rename_field("min_replicas", Some(&rule("kebab-case"))).unwrap(),
+            "min-replicas"
+        );
+        assert_eq!(
+            rename_field("min_replicas", Some(&rule("SCREAMING_SNAKE_CASE"))).unwrap(),
+            "MIN_REPLICAS"
+        );
+        assert!(rename_field("min_replicas", Some(&rule("nope"))).is_err());
+    }
+
     #[test]
     fn test_derive_crd() {
         let path = env::current_dir().unwrap().join("tests").join("crd_enum_test.rs");
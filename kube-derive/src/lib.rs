@@ -3,7 +3,9 @@
 extern crate proc_macro;
 #[macro_use] extern crate quote;
 
+mod apply_configuration;
 mod cel_schema;
+mod condition_set;
 mod custom_resource;
 mod resource;
 
@@ -113,6 +115,15 @@ mod resource;
 /// Adding `#[kube(derive = "PartialEq")]` is required if you want your generated
 /// top level type to be able to `#[derive(PartialEq)]`
 ///
+/// ## `#[kube(default)]`
+/// Makes the spec optional, defaulting it to `Default::default()` when it is omitted.
+/// The serialized default spec is also emitted as the `default` of `spec` in the derived schema,
+/// so that the apiserver fills in an omitted spec (and its nested defaults) on admission.
+/// Requires the spec struct to implement `Default`.
+///
+/// Field level `#[serde(default)]` and `#[serde(default = "path")]` are already emitted as
+/// `default` values in the derived schema, but the apiserver only applies them to objects that are present.
+///
 /// ## `#[kube(schema = "mode")]`
 /// Defines whether the `JsonSchema` of the top level generated type should be used when generating a `CustomResourceDefinition`.
 ///
@@ -129,18 +140,65 @@ mod resource;
 /// NOTE: `CustomResourceDefinition`s require a schema. If `schema = "disabled"` then
 /// `Self::crd()` will not be installable into the cluster as-is.
 ///
-/// ## `#[kube(scale = r#"json"#)]`
+/// ## `#[kube(scale(spec_replicas = ".spec.replicas", status_replicas = ".status.replicas"))]`
 /// Allow customizing the scale struct for the [scale subresource](https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definitions/#subresources).
+/// Optionally takes a `label_selector` path to the serialized label selector.
+///
+/// The paths are checked at compile time: `spec_replicas` must start with `.spec.`,
+/// `status_replicas` with `.status.`, and `label_selector` with either.
+///
+/// ## `#[kube(scale = r#"json"#)]`
+/// Sets the scale subresource from straight json, without any compile time validation.
 ///
 /// ## `#[kube(printcolumn = r#"json"#)]`
 /// Allows adding straight json to [printcolumns](https://kubernetes.io/docs/tasks/extend-kubernetes/custom-resources/custom-resource-definitions/#additional-printer-columns).
 ///
+/// ## `#[kube(printcolumn(name = "Ready", type_ = "boolean", json_path = ".status.ready"))]`
+/// Adds a printcolumn without writing json. Optionally takes `description`, `format`, and `priority`.
+/// The `type_` key sets the `type` of the column, since `type` is a keyword.
+///
+/// This can also be put on fields of the spec struct, where the `json_path` is inferred from the
+/// (serde renamed) field name:
+///
+/// ```rust,ignore
+/// #[derive(CustomResource, Deserialize, Serialize, Clone, Debug, JsonSchema)]
+/// #[kube(group = "example.com", version = "v1", kind = "App")]
+/// #[serde(rename_all = "camelCase")]
+/// struct AppSpec {
+///     // Becomes a column with `jsonPath: .spec.minReplicas`
+///     #[kube(printcolumn(name = "Replicas", type_ = "integer"))]
+///     min_replicas: i32,
+/// }
+/// ```
+///
+/// Fields of the status struct are not visible to the derive, so status columns need a struct level `json_path`.
+///
+/// ## `#[kube(merge)]`
+/// Generates a `merge_from(&mut self, other: &Self)` method on the root struct, which merges the set fields of
+/// `other` into `self`, such as when applying defaults in a webhook. Lists are replaced, unless the spec field
+/// has a `#[kube(merge_key = "field")]`, in which case their items are merged by that field like in a strategic
+/// merge patch:
+///
+/// ```rust,ignore
+/// #[derive(CustomResource, Deserialize, Serialize, Clone, Debug, JsonSchema)]
+/// #[kube(group = "example.com", version = "v1", kind = "App", merge)]
+/// struct AppSpec {
+///     #[kube(merge_key = "name")]
+///     ports: Vec<Port>,
+/// }
+/// ```
+///
+/// Nested lists are replaced; use [`kube::core::merge::merge`] to merge them by key.
+///
 /// ## `#[kube(shortname = "sn")]`
 /// Add a single shortname to the generated crd.
 ///
 /// ## `#[kube(category = "apps")]`
 /// Add a single category to `crd.spec.names.categories`.
 ///
+/// ## `#[kube(categories = ["all", "apps"])]`
+/// Add several categories to `crd.spec.names.categories` at once.
+///
 /// ## `#[kube(selectable = "fieldSelectorPath")]`
 /// Adds a Kubernetes >=1.30 `selectableFields` property ([KEP-4358](https://github.com/kubernetes/enhancements/blob/master/keps/sig-api-machinery/4358-custom-resource-field-selectors/README.md)) to the schema.
 /// Unlocks `kubectl get kind --field-selector fieldSelectorPath`.
@@ -161,6 +219,10 @@ mod resource;
 /// ## `#[kube(served = true)]`
 /// Sets the `served` property to `true` or `false`.
 ///
+/// ## `#[kube(deprecated)]` or `#[kube(deprecated = "use v2")]`
+/// Marks the version as `deprecated`, optionally with a `deprecationWarning` that the apiserver
+/// returns to clients using it.
+///
 /// ## `#[kube(rule = Rule::new("self == oldSelf").message("field is immutable"))]`
 /// Inject a top level CEL validation rule for the top level generated struct.
 /// This attribute is for resources deriving [`CELSchema`] instead of [`schemars::JsonSchema`].
@@ -185,7 +247,7 @@ mod resource;
 ///     singular = "foot",
 ///     plural = "feetz",
 ///     shortname = "f",
-///     scale = r#"{"specReplicasPath":".spec.replicas", "statusReplicasPath":".status.replicas"}"#,
+///     scale(spec_replicas = ".spec.replicas", status_replicas = ".status.replicas"),
 ///     printcolumn = r#"{"name":"Spec", "type":"string", "description":"name of foo", "jsonPath":".spec.name"}"#,
 ///     selectable = "spec.replicasCount"
 /// )]
@@ -327,6 +389,7 @@ mod resource;
 /// [`kube::Resource`]: https://docs.rs/kube/*/kube/trait.Resource.html
 /// [`kube::core::ApiResource`]: https://docs.rs/kube/*/kube/core/struct.ApiResource.html
 /// [`kube::CustomResourceExt`]: https://docs.rs/kube/*/kube/trait.CustomResourceExt.html
+/// [`kube::core::merge::merge`]: https://docs.rs/kube/*/kube/core/merge/fn.merge.html
 #[proc_macro_derive(CustomResource, attributes(kube))]
 pub fn derive_custom_resource(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     custom_resource::derive(proc_macro2::TokenStream::from(input)).into()
@@ -366,7 +429,45 @@ pub fn derive_custom_resource(input: proc_macro::TokenStream) -> proc_macro::Tok
 /// assert!(serde_json::to_string(&Struct::crd()).unwrap().contains(r#""default":"value""#));
 /// assert!(serde_json::to_string(&Struct::crd()).unwrap().contains(r#""rule":"self.matadata.name == 'singleton'""#));
 /// ```
-#[proc_macro_derive(CELSchema, attributes(cel_validate, schemars))]
+///
+/// ## `#[x_kube(list_type = "map", list_map_key = "name")]`
+/// Sets how server-side apply merges a field, through the `x-kubernetes-list-type`, `x-kubernetes-list-map-keys`
+/// and `x-kubernetes-map-type` schema extensions, see [merge strategy]:
+///
+/// - `list_type` is one of `"atomic"`, `"set"` or `"map"`, where `"map"` requires at least one `list_map_key`
+/// - `map_type` is one of `"atomic"` or `"granular"`
+///
+/// ```rust,ignore
+/// #[derive(CustomResource, CELSchema, Serialize, Deserialize, Clone, Debug)]
+/// #[kube(group = "kube.rs", version = "v1", kind = "App")]
+/// struct AppSpec {
+///     #[x_kube(list_type = "map", list_map_key = "name", list_map_key = "protocol")]
+///     ports: Vec<Port>,
+///     #[x_kube(map_type = "atomic")]
+///     selector: BTreeMap<String, String>,
+/// }
+/// ```
+///
+/// [merge strategy]: https://kubernetes.io/docs/reference/using-api/server-side-apply/#merge-strategy
+///
+/// ## `#[x_kube(preserve_unknown_fields)]`
+/// Sets `x-kubernetes-preserve-unknown-fields` on a field, so that the apiserver keeps the fields that are not
+/// in its schema, such as those of a `serde_json::Value`.
+///
+/// ## `#[x_kube(embedded_resource)]`
+/// Sets `x-kubernetes-embedded-resource` on a field, so that the apiserver validates its `apiVersion`, `kind`
+/// and `metadata` like those of a top level object. Combined with `preserve_unknown_fields`, this allows
+/// `RawExtension` style fields of any kind:
+///
+/// ```rust,ignore
+/// #[derive(CustomResource, CELSchema, Serialize, Deserialize, Clone, Debug)]
+/// #[kube(group = "kube.rs", version = "v1", kind = "Template")]
+/// struct TemplateSpec {
+///     #[x_kube(preserve_unknown_fields, embedded_resource)]
+///     object: serde_json::Value,
+/// }
+/// ```
+#[proc_macro_derive(CELSchema, attributes(cel_validate, schemars, x_kube))]
 pub fn derive_schema_validation(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     cel_schema::derive_validated_schema(input.into()).into()
 }
@@ -421,3 +522,109 @@ pub fn derive_schema_validation(input: proc_macro::TokenStream) -> proc_macro::T
 pub fn derive_resource(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
     resource::derive(proc_macro2::TokenStream::from(input)).into()
 }
+
+/// A custom derive for partial types used in server-side apply patches.
+///
+/// This generates a `{Name}ApplyConfiguration` struct with the same fields as the deriving struct,
+/// but where every field is optional and only serialized when set, along with a setter for each field.
+/// Renames from `#[serde(rename)]` and `#[serde(rename_all)]` are carried over.
+///
+/// Fields are kept as their full type by default. Fields marked with `#[apply(nested)]` instead use
+/// the apply configuration of their type, which must also derive `ApplyConfiguration`.
+///
+/// The generated types can be used as the partial spec and status of a
+/// [`kube::core::ApplyConfiguration`] to build typed server-side apply patches.
+///
+/// # Example
+///
+/// ```rust
+/// use kube::{core::ApplyConfiguration, ApplyConfiguration, CustomResource};
+/// use schemars::JsonSchema;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(CustomResource, ApplyConfiguration, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+/// #[kube(group = "clux.dev", version = "v1", kind = "Foo", namespaced)]
+/// #[serde(rename_all = "camelCase")]
+/// pub struct FooSpec {
+///     pub replica_count: i32,
+///     pub info: String,
+///     #[apply(nested)]
+///     pub backup: Option<BackupSpec>,
+/// }
+///
+/// #[derive(ApplyConfiguration, Clone, Debug, Deserialize, Serialize, JsonSchema)]
+/// pub struct BackupSpec {
+///     pub schedule: String,
+///     pub retention: u32,
+/// }
+///
+/// let patch = ApplyConfiguration::<Foo, _>::new("foo-1")
+///     .within("default")
+///     .spec(
+///         FooSpecApplyConfiguration::default()
+///             .replica_count(3)
+///             .backup(BackupSpecApplyConfiguration::default().schedule("@daily")),
+///     );
+/// assert_eq!(serde_json::to_value(&patch).unwrap(), serde_json::json!({
+///     "apiVersion": "clux.dev/v1",
+///     "kind": "Foo",
+///     "metadata": { "name": "foo-1", "namespace": "default" },
+///     "spec": { "replicaCount": 3, "backup": { "schedule": "@daily" } },
+/// }));
+/// // api.patch("foo-1", &PatchParams::apply("my-controller"), &Patch::Apply(&patch)).await?;
+/// ```
+///
+/// [`kube::core::ApplyConfiguration`]: https://docs.rs/kube/*/kube/core/apply/struct.ApplyConfiguration.html
+#[proc_macro_derive(ApplyConfiguration, attributes(apply))]
+pub fn derive_apply_configuration(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    apply_configuration::derive(proc_macro2::TokenStream::from(input)).into()
+}
+
+/// A custom derive for typed status conditions.
+///
+/// Derive this on a fieldless enum of condition types to implement [`kube::core::conditions::ConditionSet`],
+/// and to generate an extension trait (named `{Enum}Ext` by default) for [`kube::core::conditions::Conditions`]
+/// with an `is_{variant}` and a `set_{variant}` method for every variant.
+///
+/// ```rust
+/// use kube::core::conditions::{schema_for, Conditions};
+/// use kube::ConditionSet;
+/// use schemars::JsonSchema;
+/// use serde::{Deserialize, Serialize};
+///
+/// #[derive(ConditionSet, Clone, Copy, Debug)]
+/// enum FooCondition {
+///     Ready,
+///     #[condition(rename = "Degraded")]
+///     Unhealthy,
+/// }
+///
+/// #[derive(Deserialize, Serialize, Clone, Debug, Default, JsonSchema)]
+/// struct FooStatus {
+///     // Only allows the condition types of `FooCondition` in the schema
+///     #[schemars(schema_with = "schema_for::<FooCondition>")]
+///     conditions: Conditions,
+/// }
+///
+/// let mut status = FooStatus::default();
+/// assert!(status.conditions.set_ready(true, "Available", "all replicas are ready"));
+/// assert!(status.conditions.set_unhealthy(false, "AsExpected", ""));
+/// assert!(status.conditions.is_ready());
+/// assert!(status.conditions.is_false("Degraded"));
+/// ```
+///
+/// ## `#[condition(rename = "Type")]`
+/// Sets the condition `type` of a variant (defaults to the variant name).
+///
+/// ## `#[condition(trait_name = "FooConditionsExt")]`
+/// Customize the name of the generated extension trait.
+///
+/// ## `#[condition(crates(kube_core = "::kube::core"))]`
+/// Customize the crate name the generated code will reach into (defaults to `::kube::core`).
+///
+/// [`kube::core::conditions::ConditionSet`]: https://docs.rs/kube/*/kube/core/conditions/trait.ConditionSet.html
+/// [`kube::core::conditions::Conditions`]: https://docs.rs/kube/*/kube/core/conditions/struct.Conditions.html
+#[proc_macro_derive(ConditionSet, attributes(condition))]
+pub fn derive_condition_set(input: proc_macro::TokenStream) -> proc_macro::TokenStream {
+    condition_set::derive(proc_macro2::TokenStream::from(input)).into()
+}
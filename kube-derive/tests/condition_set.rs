@@ -0,0 +1,54 @@
+use kube::core::conditions::{schema_for, ConditionSet, ConditionStatus, Conditions};
+use kube_derive::ConditionSet;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(ConditionSet, Clone, Copy, Debug, PartialEq)]
+#[condition(trait_name = "AppConditionsExt")]
+enum AppCondition {
+    Ready,
+    PodScheduled,
+    #[condition(rename = "Degraded")]
+    Unhealthy,
+}
+
+#[derive(Serialize, Deserialize, Default, JsonSchema)]
+struct AppStatus {
+    #[schemars(schema_with = "schema_for::<AppCondition>")]
+    conditions: Conditions,
+}
+
+#[test]
+fn test_condition_types() {
+    assert_eq!(AppCondition::ALL, &[
+        AppCondition::Ready,
+        AppCondition::PodScheduled,
+        AppCondition::Unhealthy
+    ]);
+    assert_eq!(AppCondition::Unhealthy.as_str(), "Degraded");
+}
+
+#[test]
+fn test_typed_accessors() {
+    let mut conditions = Conditions::default();
+    assert!(!conditions.is_pod_scheduled());
+    assert!(conditions.set_pod_scheduled(true, "Scheduled", ""));
+    assert!(!conditions.set_pod_scheduled(true, "Scheduled", ""));
+    assert!(conditions.set_unhealthy(ConditionStatus::Unknown, "Probing", "waiting for probes"));
+    assert!(conditions.is_pod_scheduled());
+    assert_eq!(conditions.status("Degraded"), Some(ConditionStatus::Unknown));
+    assert_eq!(conditions.get("Degraded").unwrap().message, "waiting for probes");
+    assert!(conditions.set(AppCondition::Ready.condition(false, "Pending")));
+    assert!(!conditions.is_ready());
+}
+
+#[test]
+fn test_schema_restricts_types() {
+    let schema = serde_json::to_value(schemars::schema_for!(AppStatus)).unwrap();
+    let conditions = &schema["properties"]["conditions"];
+    assert_eq!(conditions["x-kubernetes-list-type"], "map");
+    assert_eq!(
+        conditions["items"]["properties"]["type"]["enum"],
+        serde_json::json!(["Ready", "PodScheduled", "Degraded"])
+    );
+}
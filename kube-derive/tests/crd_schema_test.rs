@@ -6,7 +6,7 @@ use kube::CELSchema;
 use kube_derive::CustomResource;
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use std::collections::{HashMap, HashSet};
+use std::collections::{BTreeMap, HashMap, HashSet};
 
 // See `crd_derive_schema` example for how the schema generated from this struct affects defaulting and validation.
 #[derive(CustomResource, Serialize, Deserialize, Debug, PartialEq, Clone, CELSchema)]
@@ -392,3 +392,160 @@ fn flattening() {
     assert_eq!(spec.x_kubernetes_preserve_unknown_fields, Some(true));
     assert_eq!(spec.additional_properties, None);
 }
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[kube(group = "clux.dev", version = "v1", kind = "Defaulted", default)]
+pub struct DefaultedSpec {
+    #[serde(default = "default_replicas")]
+    replicas: i32,
+}
+
+fn default_replicas() -> i32 {
+    1
+}
+
+impl Default for DefaultedSpec {
+    fn default() -> Self {
+        Self {
+            replicas: default_replicas(),
+        }
+    }
+}
+
+#[test]
+fn default_spec() {
+    use kube::core::CustomResourceExt;
+    let schema = Defaulted::crd().spec.versions[0]
+        .schema
+        .clone()
+        .unwrap()
+        .open_api_v3_schema
+        .unwrap();
+    assert!(!schema.required.unwrap_or_default().contains(&"spec".to_string()));
+    let spec = &schema.properties.unwrap()["spec"];
+    assert_eq!(
+        spec.default.as_ref().unwrap().0,
+        serde_json::json!({ "replicas": 1 })
+    );
+    assert_eq!(
+        spec.properties.as_ref().unwrap()["replicas"]
+            .default
+            .as_ref()
+            .unwrap()
+            .0,
+        serde_json::json!(1)
+    );
+
+    let defaulted: Defaulted = serde_json::from_value(serde_json::json!({
+        "apiVersion": "clux.dev/v1",
+        "kind": "Defaulted",
+        "metadata": { "name": "test" }
+    }))
+    .unwrap();
+    assert_eq!(defaulted.spec.replicas, 1);
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, CELSchema)]
+#[kube(group = "clux.dev", version = "v1", kind = "Merged")]
+#[serde(rename_all = "camelCase")]
+pub struct MergedSpec {
+    #[x_kube(list_type = "map", list_map_key = "name", list_map_key = "protocol")]
+    ports: Vec<MergedPort>,
+    #[x_kube(list_type = "set")]
+    hosts: Vec<String>,
+    #[x_kube(map_type = "atomic")]
+    selector: BTreeMap<String, String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone, JsonSchema)]
+pub struct MergedPort {
+    name: String,
+    protocol: String,
+}
+
+#[test]
+fn merge_strategies() {
+    use kube::core::CustomResourceExt;
+    let schema = Merged::crd().spec.versions[0]
+        .schema
+        .clone()
+        .unwrap()
+        .open_api_v3_schema
+        .unwrap();
+    let spec = &schema.properties.unwrap()["spec"];
+    let properties = spec.properties.as_ref().unwrap();
+    assert_eq!(properties["ports"].x_kubernetes_list_type.as_deref(), Some("map"));
+    assert_eq!(
+        properties["ports"].x_kubernetes_list_map_keys,
+        Some(vec!["name".to_string(), "protocol".to_string()])
+    );
+    assert_eq!(properties["hosts"].x_kubernetes_list_type.as_deref(), Some("set"));
+    assert_eq!(
+        properties["selector"].x_kubernetes_map_type.as_deref(),
+        Some("atomic")
+    );
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, CELSchema)]
+#[kube(group = "clux.dev", version = "v1", kind = "Embedding")]
+pub struct EmbeddingSpec {
+    #[x_kube(preserve_unknown_fields, embedded_resource)]
+    object: serde_json::Value,
+    #[x_kube(preserve_unknown_fields)]
+    values: Option<serde_json::Value>,
+}
+
+#[test]
+fn preserve_unknown_fields_and_embedded_resources() {
+    use kube::core::CustomResourceExt;
+    let schema = Embedding::crd().spec.versions[0]
+        .schema
+        .clone()
+        .unwrap()
+        .open_api_v3_schema
+        .unwrap();
+    let spec = &schema.properties.unwrap()["spec"];
+    let properties = spec.properties.as_ref().unwrap();
+    assert_eq!(properties["object"].type_.as_deref(), Some("object"));
+    assert_eq!(properties["object"].x_kubernetes_embedded_resource, Some(true));
+    assert_eq!(
+        properties["object"].x_kubernetes_preserve_unknown_fields,
+        Some(true)
+    );
+    assert_eq!(properties["values"].x_kubernetes_embedded_resource, None);
+    assert_eq!(
+        properties["values"].x_kubernetes_preserve_unknown_fields,
+        Some(true)
+    );
+}
+
+#[derive(CustomResource, Serialize, Deserialize, Debug, Clone, JsonSchema)]
+#[kube(
+    group = "clux.dev",
+    version = "v1beta1",
+    kind = "Legacy",
+    category = "clux",
+    categories = ["all", "legacy"],
+    deprecated = "clux.dev/v1beta1 Legacy is deprecated, use clux.dev/v1 Modern",
+    served = false
+)]
+pub struct LegacySpec {
+    name: String,
+}
+
+#[test]
+fn categories_and_deprecation() {
+    use kube::core::CustomResourceExt;
+    let crd = Legacy::crd();
+    assert_eq!(
+        crd.spec.names.categories,
+        Some(vec!["clux".to_string(), "all".to_string(), "legacy".to_string()])
+    );
+    let version = &crd.spec.versions[0];
+    assert!(!version.served);
+    assert_eq!(version.deprecated, Some(true));
+    assert_eq!(
+        version.deprecation_warning.as_deref(),
+        Some("clux.dev/v1beta1 Legacy is deprecated, use clux.dev/v1 Modern")
+    );
+}
@@ -0,0 +1,59 @@
+use kube::CustomResource;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(CustomResource, Serialize, Deserialize, Clone, Debug, Default, JsonSchema)]
+#[kube(group = "example.com", version = "v1", kind = "App", merge)]
+#[serde(rename_all = "camelCase")]
+struct AppSpec {
+    image: Option<String>,
+    args: Vec<String>,
+    #[kube(merge_key = "name")]
+    service_ports: Vec<Port>,
+}
+
+#[derive(Serialize, Deserialize, Clone, Debug, PartialEq, JsonSchema)]
+struct Port {
+    name: String,
+    port: Option<u16>,
+}
+
+#[test]
+fn test_merge_from_merges_lists_by_key() {
+    let mut app = App::new("blog", AppSpec {
+        image: Some("blog:1".into()),
+        args: vec!["--verbose".into()],
+        service_ports: vec![Port {
+            name: "http".into(),
+            port: Some(80),
+        }],
+    });
+    app.merge_from(&App::new("blog", AppSpec {
+        image: None,
+        args: vec!["--quiet".into()],
+        service_ports: vec![
+            Port {
+                name: "http".into(),
+                port: None,
+            },
+            Port {
+                name: "metrics".into(),
+                port: Some(9090),
+            },
+        ],
+    }))
+    .unwrap();
+
+    assert_eq!(app.spec.image.as_deref(), Some("blog:1"));
+    assert_eq!(app.spec.args, ["--quiet"]);
+    assert_eq!(app.spec.service_ports, [
+        Port {
+            name: "http".into(),
+            port: Some(80),
+        },
+        Port {
+            name: "metrics".into(),
+            port: Some(9090),
+        },
+    ]);
+}
@@ -0,0 +1,408 @@
+//! Serves validating and mutating admission webhooks
+//!
+//! The [`WebhookServer`] takes care of terminating TLS, decoding incoming [`AdmissionReview`]s,
+//! dispatching them to the handler registered for the request path, and wrapping the outcome back
+//! into an [`AdmissionReview`] response. Mutating handlers return the desired object, and the
+//! server generates the JSON patch that the apiserver expects.
+//!
+//! CRD conversion webhooks can be served alongside, see [`WebhookServer::convert`].
+//!
+//! ```no_run
+//! use k8s_openapi::api::core::v1::Pod;
+//! use kube::runtime::admission::WebhookServer;
+//! use kube::ResourceExt;
+//! use std::{convert::Infallible, sync::Arc};
+//!
+//! # async fn wrapper(tls: tokio_rustls::rustls::ServerConfig) -> Result<(), Box<dyn std::error::Error>> {
+//! WebhookServer::new()
+//!     .validate("/validate", |req: kube::core::admission::AdmissionRequest<Pod>| async move {
+//!         match req.object {
+//!             Some(pod) if pod.labels().contains_key("illegal") => Err("illegal label"),
+//!             _ => Ok(()),
+//!         }
+//!     })
+//!     .mutate("/mutate", |req: kube::core::admission::AdmissionRequest<Pod>| async move {
+//!         let mut pod = req.object;
+//!         if let Some(pod) = &mut pod {
+//!             pod.labels_mut().insert("admission".into(), "modified".into());
+//!         }
+//!         Ok::<_, Infallible>(pod)
+//!     })
+//!     .tls(Arc::new(tls))
+//!     .serve(([0, 0, 0, 0], 8443).into(), std::future::pending())
+//!     .await?;
+//! # Ok(())
+//! # }
+//! ```
+use crate::conversion::ConversionHandler;
+use bytes::Bytes;
+use futures::{
+    future::{self, BoxFuture},
+    FutureExt,
+};
+use http::{header, HeaderValue, Method, Request, Response, StatusCode};
+use http_body_util::{BodyExt, Full, LengthLimitError, Limited};
+use hyper::{body::Body, server::conn::http1, service::service_fn};
+use hyper_util::rt::TokioIo;
+use kube_client::{
+    core::{
+        admission::{AdmissionRequest, AdmissionResponse, AdmissionReview},
+        conversion::{ConversionResponse, ConversionReview},
+        DynamicObject, Status,
+    },
+    Resource,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{
+    collections::HashMap, convert::Infallible, fmt::Display, future::Future, net::SocketAddr, pin::pin,
+    sync::Arc,
+};
+use thiserror::Error;
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::TcpListener,
+};
+use tokio_rustls::{rustls::ServerConfig, TlsAcceptor};
+
+/// Errors returned by [`WebhookServer::serve`]
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The listening socket could not be bound
+    #[error("failed to bind webhook server: {0}")]
+    Bind(#[source] std::io::Error),
+}
+
+/// The largest request body that the [`WebhookServer`] will accept
+///
+/// A review carries at most an object and its previous version, each of which etcd limits to 1.5 MiB.
+const MAX_BODY_BYTES: usize = 3 * 1024 * 1024;
+
+/// Takes an encoded request review, and returns the encoded response review
+type Handler = Box<dyn Fn(Bytes) -> BoxFuture<'static, serde_json::Result<Vec<u8>>> + Send + Sync>;
+
+fn encode(review: &AdmissionReview<DynamicObject>) -> serde_json::Result<Vec<u8>> {
+    serde_json::to_vec(review)
+}
+
+/// Decodes an [`AdmissionRequest`], or produces the [`AdmissionResponse`] explaining why it couldn't be
+#[allow(clippy::result_large_err)] // the response is sent back as is
+fn decode<K>(body: &[u8]) -> Result<AdmissionRequest<K>, AdmissionResponse>
+where
+    K: Resource + DeserializeOwned,
+{
+    let review: AdmissionReview<K> = serde_json::from_slice(body).map_err(AdmissionResponse::invalid)?;
+    review.try_into().map_err(AdmissionResponse::invalid)
+}
+
+/// Generates the JSON patch required to turn `original` into `desired`
+fn patch_for<K: Serialize>(
+    original: Option<&K>,
+    desired: &K,
+) -> Result<json_patch::Patch, serde_json::Error> {
+    let original = original
+        .map(serde_json::to_value)
+        .transpose()?
+        .unwrap_or_default();
+    Ok(json_patch::diff(&original, &serde_json::to_value(desired)?))
+}
+
+/// An HTTPS server for admission webhooks
+///
+/// Handlers are registered per request path with [`WebhookServer::validate`] and [`WebhookServer::mutate`],
+/// and should match the `clientConfig` of your `ValidatingWebhookConfiguration`
+/// and `MutatingWebhookConfiguration` respectively.
+#[derive(Default)]
+pub struct WebhookServer {
+    handlers: HashMap<String, Handler>,
+    tls: Option<Arc<ServerConfig>>,
+}
+
+impl WebhookServer {
+    /// Creates a server without any handlers
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Terminates TLS using the given configuration
+    ///
+    /// The Kubernetes apiserver only calls webhooks over HTTPS, so this should only be skipped
+    /// if TLS is terminated by something else in front of the server.
+    #[must_use]
+    pub fn tls(mut self, config: Arc<ServerConfig>) -> Self {
+        self.tls = Some(config);
+        self
+    }
+
+    /// Registers a validating handler for requests to `path`
+    ///
+    /// The request is allowed if `handler` succeeds, and denied with the error message otherwise.
+    #[must_use]
+    pub fn validate<K, F, Fut, E>(mut self, path: &str, handler: F) -> Self
+    where
+        K: Resource + DeserializeOwned + Send + 'static,
+        F: Fn(AdmissionRequest<K>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<(), E>> + Send + 'static,
+        E: Display,
+    {
+        let handler: Handler = Box::new(move |body| match decode::<K>(&body) {
+            Err(res) => std::future::ready(encode(&res.into_review())).boxed(),
+            Ok(req) => {
+                let res = AdmissionResponse::from(&req);
+                handler(req)
+                    .map(move |outcome| match outcome {
+                        Ok(()) => res,
+                        Err(err) => res.deny(err),
+                    })
+                    .map(|res| encode(&res.into_review()))
+                    .boxed()
+            }
+        });
+        self.handlers.insert(path.to_string(), handler);
+        self
+    }
+
+    /// Registers a mutating handler for requests to `path`
+    ///
+    /// `handler` returns the desired state of the object, or `None` to leave it unchanged.
+    /// The request is denied with the error message if `handler` fails.
+    #[must_use]
+    pub fn mutate<K, F, Fut, E>(mut self, path: &str, handler: F) -> Self
+    where
+        K: Resource + DeserializeOwned + Serialize + Clone + Send + 'static,
+        F: Fn(AdmissionRequest<K>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = Result<Option<K>, E>> + Send + 'static,
+        E: Display,
+    {
+        let handler: Handler = Box::new(move |body| match decode::<K>(&body) {
+            Err(res) => std::future::ready(encode(&res.into_review())).boxed(),
+            Ok(req) => {
+                let res = AdmissionResponse::from(&req);
+                let original = req.object.clone();
+                handler(req)
+                    .map(move |outcome| match outcome {
+                        Ok(None) => res,
+                        Ok(Some(desired)) => match patch_for(original.as_ref(), &desired) {
+                            Ok(json_patch) if json_patch.0.is_empty() => res,
+                            Ok(json_patch) => match res.clone().with_patch(json_patch) {
+                                Ok(res) => res,
+                                Err(err) => res.deny(err),
+                            },
+                            Err(err) => res.deny(err),
+                        },
+                        Err(err) => res.deny(err),
+                    })
+                    .map(|res| encode(&res.into_review()))
+                    .boxed()
+            }
+        });
+        self.handlers.insert(path.to_string(), handler);
+        self
+    }
+
+    /// Registers a CRD conversion handler for requests to `path`
+    ///
+    /// `path` should match the `conversion.webhook.clientConfig` of your `CustomResourceDefinition`.
+    #[must_use]
+    pub fn convert(mut self, path: &str, handler: ConversionHandler) -> Self {
+        let handler: Handler = Box::new(move |body| {
+            let review = match serde_json::from_slice::<ConversionReview>(&body) {
+                Ok(review) => handler.review(review),
+                Err(err) => ConversionResponse::invalid(Status::failure(&err.to_string(), "InvalidRequest"))
+                    .into_review(),
+            };
+            std::future::ready(serde_json::to_vec(&review)).boxed()
+        });
+        self.handlers.insert(path.to_string(), handler);
+        self
+    }
+
+    /// Runs the handler registered for `path` against an encoded review
+    ///
+    /// Returns `None` if no handler is registered for `path`.
+    async fn review(&self, path: &str, body: Bytes) -> Option<serde_json::Result<Vec<u8>>> {
+        let handler = self.handlers.get(path)?;
+        Some(handler(body).await)
+    }
+
+    async fn respond<B>(&self, req: Request<B>) -> Response<Full<Bytes>>
+    where
+        B: Body,
+        B::Error: Into<Box<dyn std::error::Error + Send + Sync>>,
+    {
+        let reply = |status: StatusCode, body: Bytes| {
+            let mut res = Response::new(Full::new(body));
+            *res.status_mut() = status;
+            res
+        };
+        if req.method() != Method::POST {
+            return reply(StatusCode::METHOD_NOT_ALLOWED, Bytes::new());
+        }
+        let path = req.uri().path().to_string();
+        let body = match Limited::new(req.into_body(), MAX_BODY_BYTES).collect().await {
+            Ok(body) => body.to_bytes(),
+            Err(err) if err.is::<LengthLimitError>() => {
+                return reply(StatusCode::PAYLOAD_TOO_LARGE, err.to_string().into())
+            }
+            Err(err) => return reply(StatusCode::BAD_REQUEST, err.to_string().into()),
+        };
+        let Some(review) = self.review(&path, body).await else {
+            return reply(StatusCode::NOT_FOUND, Bytes::new());
+        };
+        match review {
+            Ok(json) => {
+                let mut res = reply(StatusCode::OK, json.into());
+                res.headers_mut()
+                    .insert(header::CONTENT_TYPE, HeaderValue::from_static("application/json"));
+                res
+            }
+            Err(err) => reply(StatusCode::INTERNAL_SERVER_ERROR, err.to_string().into()),
+        }
+    }
+
+    async fn serve_connection<IO>(self: Arc<Self>, io: IO)
+    where
+        IO: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+    {
+        let service = service_fn(move |req| {
+            let server = self.clone();
+            async move { Ok::<_, Infallible>(server.respond(req).await) }
+        });
+        if let Err(err) = http1::Builder::new()
+            .serve_connection(TokioIo::new(io), service)
+            .await
+        {
+            tracing::debug!(error = %err, "webhook connection failed");
+        }
+    }
+
+    /// Serves webhook requests on `addr` until `shutdown` resolves
+    ///
+    /// Requests that are already being processed are allowed to finish after `shutdown` resolves.
+    ///
+    /// # Errors
+    ///
+    /// Fails if `addr` could not be bound. Errors on individual connections are logged and otherwise ignored.
+    pub async fn serve(self, addr: SocketAddr, shutdown: impl Future<Output = ()>) -> Result<(), Error> {
+        let listener = TcpListener::bind(addr).await.map_err(Error::Bind)?;
+        let acceptor = self.tls.clone().map(TlsAcceptor::from);
+        let server = Arc::new(self);
+        let mut shutdown = pin!(shutdown);
+        loop {
+            let (stream, peer) = match future::select(pin!(listener.accept()), shutdown.as_mut()).await {
+                future::Either::Left((Ok(conn), _)) => conn,
+                future::Either::Left((Err(err), _)) => {
+                    tracing::warn!(error = %err, "failed to accept webhook connection");
+                    continue;
+                }
+                future::Either::Right(_) => return Ok(()),
+            };
+            let server = server.clone();
+            let acceptor = acceptor.clone();
+            tokio::spawn(async move {
+                match acceptor {
+                    Some(acceptor) => match acceptor.accept(stream).await {
+                        Ok(stream) => server.serve_connection(stream).await,
+                        Err(err) => tracing::debug!(%peer, error = %err, "webhook TLS handshake failed"),
+                    },
+                    None => server.serve_connection(stream).await,
+                }
+            });
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{WebhookServer, MAX_BODY_BYTES};
+    use bytes::Bytes;
+    use http::{Request, StatusCode};
+    use http_body_util::Full;
+    use json_patch::{Patch, PatchOperation};
+    use k8s_openapi::api::core::v1::ConfigMap;
+    use kube_client::core::{
+        admission::{AdmissionRequest, AdmissionResponse, AdmissionReview},
+        DynamicObject,
+    };
+    use std::convert::Infallible;
+
+    const REVIEW: &str = r#"{"kind":"AdmissionReview","apiVersion":"admission.k8s.io/v1","request":{"uid":"8d1bc3d5-7b2c-4a5e-9a6f-5a9d1b4c3e21","kind":{"group":"","version":"v1","kind":"ConfigMap"},"resource":{"group":"","version":"v1","resource":"configmaps"},"name":"cm","namespace":"default","operation":"CREATE","userInfo":{"username":"admin"},"object":{"apiVersion":"v1","kind":"ConfigMap","metadata":{"name":"cm","namespace":"default","labels":{"app":"test"}},"data":{"key":"value"}},"oldObject":null,"dryRun":false}}"#;
+
+    fn server() -> WebhookServer {
+        WebhookServer::new()
+            .validate("/validate", |req: AdmissionRequest<ConfigMap>| async move {
+                match req
+                    .object
+                    .and_then(|cm| cm.data)
+                    .and_then(|data| data.get("key").cloned())
+                {
+                    Some(value) if value == "forbidden" => Err("forbidden value"),
+                    _ => Ok(()),
+                }
+            })
+            .mutate("/mutate", |req: AdmissionRequest<ConfigMap>| async move {
+                let mut cm = req.object;
+                if let Some(cm) = &mut cm {
+                    cm.data
+                        .get_or_insert_with(Default::default)
+                        .insert("injected".into(), "true".into());
+                }
+                Ok::<_, Infallible>(cm)
+            })
+    }
+
+    async fn response(server: &WebhookServer, path: &str, body: String) -> AdmissionResponse {
+        let json = server.review(path, body.into()).await.unwrap().unwrap();
+        let review: AdmissionReview<DynamicObject> = serde_json::from_slice(&json).unwrap();
+        review.response.unwrap()
+    }
+
+    #[tokio::test]
+    async fn validating_handler_allows_and_denies() {
+        let server = server();
+        let res = response(&server, "/validate", REVIEW.into()).await;
+        assert!(res.allowed);
+        assert_eq!(res.uid, "8d1bc3d5-7b2c-4a5e-9a6f-5a9d1b4c3e21");
+        assert!(res.patch.is_none());
+
+        let forbidden = REVIEW.replace(r#""key":"value""#, r#""key":"forbidden""#);
+        let res = response(&server, "/validate", forbidden).await;
+        assert!(!res.allowed);
+        assert_eq!(res.result.message, "forbidden value");
+    }
+
+    #[tokio::test]
+    async fn mutating_handler_generates_patch() {
+        let res = response(&server(), "/mutate", REVIEW.into()).await;
+        assert!(res.allowed);
+        let patch: Patch = serde_json::from_slice(&res.patch.unwrap()).unwrap();
+        assert!(matches!(&patch.0[..], [PatchOperation::Add(add)] if add.path.as_str() == "/data/injected"));
+    }
+
+    #[tokio::test]
+    async fn invalid_and_unknown_requests() {
+        let server = server();
+        let res = response(&server, "/validate", "{}".into()).await;
+        assert!(!res.allowed);
+        assert!(server.review("/unknown", REVIEW.into()).await.is_none());
+    }
+
+    #[tokio::test]
+    async fn oversized_requests_are_rejected() {
+        let server = server();
+        let res = server
+            .respond(
+                Request::post("/validate")
+                    .body(Full::new(Bytes::from(REVIEW)))
+                    .unwrap(),
+            )
+            .await;
+        assert_eq!(res.status(), StatusCode::OK);
+
+        let body = Bytes::from(vec![b' '; MAX_BODY_BYTES + 1]);
+        let res = server
+            .respond(Request::post("/validate").body(Full::new(body)).unwrap())
+            .await;
+        assert_eq!(res.status(), StatusCode::PAYLOAD_TOO_LARGE);
+    }
+}
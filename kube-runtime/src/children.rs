@@ -0,0 +1,345 @@
+//! Server-side apply of the children of an object, pruning children that are no longer desired
+//!
+//! This is similar to `kubectl apply --prune`, scoped to the children of a single parent object.
+use std::collections::HashSet;
+
+use k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference;
+use kube_client::{
+    api::{Api, DeleteParams, ListParams, PatchParams},
+    core::{ApiResource, DynamicObject, Resource, ResourceExt},
+    Client,
+};
+use serde::Serialize;
+use thiserror::Error;
+
+/// Label that marks the children applied by a [`ChildApplier`] with the uid of their parent
+pub const PARENT_UID_LABEL: &str = "kube.rs/parent-uid";
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("parent object has no uid, it must be fetched from the apiserver first")]
+    MissingParentUid,
+    #[error("child object has no name")]
+    UnnamedChild,
+    #[error("child {name:?} has kind {kind:?} of {api_version:?}, which is not one of the child kinds")]
+    UnknownKind {
+        name: String,
+        api_version: String,
+        kind: String,
+    },
+    #[error("child {name:?} is not in the namespace of its parent")]
+    NamespaceMismatch { name: String },
+    #[error("failed to serialize child: {0}")]
+    SerializeChild(#[source] serde_json::Error),
+    #[error("failed to apply child {name:?}: {source}")]
+    Apply {
+        name: String,
+        #[source]
+        source: Box<kube_client::Error>,
+    },
+    #[error("failed to list children: {0}")]
+    List(#[source] kube_client::Error),
+    #[error("failed to prune child {name:?}: {source}")]
+    Prune {
+        name: String,
+        #[source]
+        source: Box<kube_client::Error>,
+    },
+}
+
+/// Converts a typed object, such as a `Deployment`, into a child for [`ChildApplier::apply`]
+///
+/// # Errors
+///
+/// Fails if the object cannot be serialized, or does not serialize its `apiVersion` and `kind`.
+pub fn to_child<K: Serialize>(object: &K) -> Result<DynamicObject, Error> {
+    serde_json::to_value(object)
+        .and_then(serde_json::from_value)
+        .map_err(Error::SerializeChild)
+}
+
+/// The children that were applied and pruned by [`ChildApplier::apply`]
+#[derive(Clone, Debug, Default)]
+pub struct Applied {
+    /// The desired children, as returned by the apiserver after applying them
+    pub applied: Vec<DynamicObject>,
+    /// The children that were no longer desired, and have been deleted
+    pub pruned: Vec<DynamicObject>,
+}
+
+/// Server-side applies the desired children of a parent object, and deletes the ones that are no longer desired
+///
+/// Every child gets a controller owner reference to the parent, and the [`PARENT_UID_LABEL`] label.
+/// A child is pruned when it has that label, is of one of the child kinds, was applied with the same
+/// field manager, and is not part of the desired children anymore. Objects applied by other
+/// field managers are never pruned, so several controllers can manage children of the same parent.
+///
+/// Children of namespaced parents default to, and must be in, the namespace of the parent.
+///
+/// ```no_run
+/// use k8s_openapi::api::{apps::v1::Deployment, core::v1::{ConfigMap, Service}};
+/// use kube::runtime::children::{to_child, ChildApplier};
+///
+/// # async fn reconcile(client: kube::Client, parent: ConfigMap, deployment: Deployment) -> Result<(), Box<dyn std::error::Error>> {
+/// let applier = ChildApplier::new(client, "my-controller", &parent)?
+///     .with_kind::<Deployment>()
+///     .with_kind::<Service>();
+/// // The service is pruned if it was applied before
+/// let applied = applier.apply(vec![to_child(&deployment)?]).await?;
+/// println!("pruned {} children", applied.pruned.len());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone)]
+pub struct ChildApplier {
+    client: Client,
+    manager: String,
+    namespace: Option<String>,
+    parent_uid: String,
+    owner_ref: OwnerReference,
+    kinds: Vec<ApiResource>,
+}
+
+impl ChildApplier {
+    /// Creates an applier for the children of `parent`, which are applied with the field manager `manager`
+    ///
+    /// # Errors
+    ///
+    /// Fails if the parent has no uid, which is the case for objects that were not fetched from the apiserver.
+    pub fn new<P>(client: Client, manager: &str, parent: &P) -> Result<Self, Error>
+    where
+        P: Resource<DynamicType = ()>,
+    {
+        Self::new_with(client, manager, parent, &())
+    }
+
+    /// Creates an applier for the children of a `parent` with a dynamic type, see [`ChildApplier::new`]
+    ///
+    /// # Errors
+    ///
+    /// Fails if the parent has no uid, which is the case for objects that were not fetched from the apiserver.
+    pub fn new_with<P: Resource>(
+        client: Client,
+        manager: &str,
+        parent: &P,
+        dt: &P::DynamicType,
+    ) -> Result<Self, Error> {
+        let owner_ref = parent.controller_owner_ref(dt).ok_or(Error::MissingParentUid)?;
+        Ok(Self {
+            client,
+            manager: manager.to_string(),
+            namespace: parent.namespace(),
+            parent_uid: owner_ref.uid.clone(),
+            owner_ref,
+            kinds: Vec::new(),
+        })
+    }
+
+    /// Adds a kind that children may have, and which is checked for children to prune
+    #[must_use]
+    pub fn with_kind<K: Resource<DynamicType = ()>>(self) -> Self {
+        self.with_dynamic_kind(ApiResource::erase::<K>(&()))
+    }
+
+    /// Adds a dynamic kind that children may have, and which is checked for children to prune
+    #[must_use]
+    pub fn with_dynamic_kind(mut self, resource: ApiResource) -> Self {
+        self.kinds.push(resource);
+        self
+    }
+
+    /// Applies the desired `children`, and then prunes the children that are no longer desired
+    ///
+    /// Conflicts with other field managers are forced, as the children are owned by the parent.
+    ///
+    /// # Errors
+    ///
+    /// Fails if a child is invalid, or a request fails. Nothing is pruned unless all children were applied.
+    pub async fn apply(&self, children: Vec<DynamicObject>) -> Result<Applied, Error> {
+        let children = children
+            .into_iter()
+            .map(|child| self.prepare(child))
+            .collect::<Result<Vec<_>, _>>()?;
+
+        let pp = PatchParams::apply(&self.manager).force();
+        let mut result = Applied::default();
+        let mut desired = HashSet::new();
+        for (kind, child) in children {
+            let name = child.name_any();
+            let applied = self
+                .api(kind, child.namespace().as_deref())
+                .apply(&name, &pp, &child)
+                .await
+                .map_err(|source| Error::Apply {
+                    name,
+                    source: Box::new(source),
+                })?;
+            desired.insert(child_key(kind, &applied));
+            result.applied.push(applied);
+        }
+
+        let lp = ListParams::default().labels(&format!("{PARENT_UID_LABEL}={}", self.parent_uid));
+        for (kind, resource) in self.kinds.iter().enumerate() {
+            let api = self.api(kind, self.namespace.as_deref());
+            for child in api.list(&lp).await.map_err(Error::List)? {
+                if !self.is_pruned(kind, &child, &desired) {
+                    continue;
+                }
+                let name = child.name_any();
+                tracing::debug!(kind = %resource.kind, %name, "pruning child that is no longer desired");
+                match self
+                    .api(kind, child.namespace().as_deref())
+                    .delete(&name, &DeleteParams::background())
+                    .await
+                {
+                    Ok(_) => result.pruned.push(child),
+                    Err(kube_client::Error::Api(err)) if err.code == 404 => {}
+                    Err(source) => {
+                        return Err(Error::Prune {
+                            name,
+                            source: Box::new(source),
+                        })
+                    }
+                }
+            }
+        }
+        Ok(result)
+    }
+
+    /// Validates a child, and adds the owner reference and label that mark it as a child of the parent
+    fn prepare(&self, mut child: DynamicObject) -> Result<(usize, DynamicObject), Error> {
+        let name = child.metadata.name.clone().ok_or(Error::UnnamedChild)?;
+        let (api_version, kind) = child
+            .types
+            .as_ref()
+            .map(|types| (types.api_version.clone(), types.kind.clone()))
+            .unwrap_or_default();
+        let kind_index = self
+            .kinds
+            .iter()
+            .position(|resource| resource.api_version == api_version && resource.kind == kind)
+            .ok_or_else(|| Error::UnknownKind {
+                name: name.clone(),
+                api_version,
+                kind,
+            })?;
+
+        if let Some(parent_ns) = &self.namespace {
+            match &child.metadata.namespace {
+                None => child.metadata.namespace = Some(parent_ns.clone()),
+                Some(ns) if ns == parent_ns => {}
+                Some(_) => return Err(Error::NamespaceMismatch { name }),
+            }
+        }
+        child
+            .labels_mut()
+            .insert(PARENT_UID_LABEL.to_string(), self.parent_uid.clone());
+        let owner_refs = child.owner_references_mut();
+        if !owner_refs.iter().any(|owner| owner.uid == self.parent_uid) {
+            owner_refs.push(self.owner_ref.clone());
+        }
+        Ok((kind_index, child))
+    }
+
+    /// Whether an existing child should be deleted, because it is no longer desired
+    fn is_pruned(&self, kind: usize, child: &DynamicObject, desired: &HashSet<ChildKey>) -> bool {
+        let applied_by_manager = child.managed_fields().iter().any(|fields| {
+            fields.manager.as_deref() == Some(self.manager.as_str())
+                && fields.operation.as_deref() == Some("Apply")
+        });
+        applied_by_manager
+            && child.meta().deletion_timestamp.is_none()
+            && child.labels().get(PARENT_UID_LABEL) == Some(&self.parent_uid)
+            && !desired.contains(&child_key(kind, child))
+    }
+
+    fn api(&self, kind: usize, namespace: Option<&str>) -> Api<DynamicObject> {
+        let resource = &self.kinds[kind];
+        match namespace {
+            Some(ns) => Api::namespaced_with(self.client.clone(), ns, resource),
+            None => Api::all_with(self.client.clone(), resource),
+        }
+    }
+}
+
+/// Identifies a child by its kind, namespace and name
+type ChildKey = (usize, Option<String>, String);
+
+fn child_key(kind: usize, child: &DynamicObject) -> ChildKey {
+    (kind, child.namespace(), child.name_any())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{child_key, to_child, ChildApplier, Error, PARENT_UID_LABEL};
+    use k8s_openapi::api::{
+        apps::v1::Deployment,
+        core::v1::{ConfigMap, Service},
+    };
+    use kube::{core::ResourceExt, Client, Config};
+    use serde_json::json;
+    use std::collections::HashSet;
+
+    fn applier() -> ChildApplier {
+        let client = Client::try_from(Config::new("http://localhost:8001".parse().unwrap())).unwrap();
+        let parent: ConfigMap = serde_json::from_value(json!({
+            "metadata": { "name": "parent", "namespace": "apps", "uid": "1234" }
+        }))
+        .unwrap();
+        ChildApplier::new(client, "test-manager", &parent)
+            .unwrap()
+            .with_kind::<Deployment>()
+            .with_kind::<Service>()
+    }
+
+    fn service(name: &str) -> Service {
+        serde_json::from_value(json!({ "metadata": { "name": name } })).unwrap()
+    }
+
+    #[tokio::test]
+    async fn children_are_marked_with_their_parent() {
+        let applier = applier();
+        let (kind, child) = applier.prepare(to_child(&service("web")).unwrap()).unwrap();
+        assert_eq!(kind, 1);
+        assert_eq!(child.namespace().as_deref(), Some("apps"));
+        assert_eq!(child.labels()[PARENT_UID_LABEL], "1234");
+        assert_eq!(child.owner_references()[0].uid, "1234");
+        assert_eq!(child.owner_references()[0].controller, Some(true));
+
+        let mut elsewhere = service("web");
+        elsewhere.metadata.namespace = Some("other".into());
+        assert!(matches!(
+            applier.prepare(to_child(&elsewhere).unwrap()),
+            Err(Error::NamespaceMismatch { .. })
+        ));
+        let unnamed = to_child(&ConfigMap::default()).unwrap();
+        assert!(matches!(applier.prepare(unnamed), Err(Error::UnnamedChild)));
+        let mut configmap = ConfigMap::default();
+        configmap.metadata.name = Some("settings".into());
+        let unknown = to_child(&configmap).unwrap();
+        assert!(matches!(applier.prepare(unknown), Err(Error::UnknownKind { .. })));
+    }
+
+    #[tokio::test]
+    async fn only_undesired_children_of_the_manager_are_pruned() {
+        let applier = applier();
+        let applied_by = |name: &str, manager: &str| {
+            let mut child = to_child(&service(name)).unwrap();
+            child.metadata.namespace = Some("apps".into());
+            child.labels_mut().insert(PARENT_UID_LABEL.into(), "1234".into());
+            child.metadata.managed_fields = Some(vec![serde_json::from_value(json!({
+                "manager": manager,
+                "operation": "Apply",
+            }))
+            .unwrap()]);
+            child
+        };
+        let desired = HashSet::from([child_key(1, &applied_by("web", "test-manager"))]);
+
+        assert!(!applier.is_pruned(1, &applied_by("web", "test-manager"), &desired));
+        assert!(applier.is_pruned(1, &applied_by("old", "test-manager"), &desired));
+        assert!(!applier.is_pruned(1, &applied_by("old", "someone-else"), &desired));
+        // The same name of another kind is a different child
+        assert!(applier.is_pruned(0, &applied_by("web", "test-manager"), &desired));
+    }
+}
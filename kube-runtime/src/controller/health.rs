@@ -0,0 +1,200 @@
+//! Readiness and liveness probes for a [`Controller`](super::Controller)
+use std::{sync::Arc, time::Duration};
+
+use parking_lot::Mutex;
+use tokio::{sync::watch, time::Instant};
+
+/// The default for [`Controller::trigger_unhealthy_after`](super::Controller::trigger_unhealthy_after)
+const DEFAULT_UNHEALTHY_AFTER: Duration = Duration::from_secs(5 * 60);
+
+/// A Kubernetes probe that can be answered by [`Health`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum Probe {
+    /// Whether the controller has synced its stores, and can reconcile with a complete view
+    Readiness,
+    /// Whether the controller is making progress, rather than being stuck backing off from trigger errors
+    Liveness,
+}
+
+impl Probe {
+    /// The conventional HTTP path of the probe
+    #[must_use]
+    pub fn path(self) -> &'static str {
+        match self {
+            Probe::Readiness => "/readyz",
+            Probe::Liveness => "/livez",
+        }
+    }
+
+    /// Finds the probe served at an HTTP `path`
+    #[must_use]
+    pub fn from_path(path: &str) -> Option<Self> {
+        [Probe::Readiness, Probe::Liveness]
+            .into_iter()
+            .find(|probe| probe.path() == path)
+    }
+
+    /// The HTTP status code and body for a probe that `passed` or not
+    pub(crate) fn response(passed: bool) -> (u16, &'static str) {
+        if passed {
+            (200, "ok")
+        } else {
+            (503, "unavailable")
+        }
+    }
+}
+
+#[derive(Debug)]
+struct Liveness {
+    unhealthy_after: Duration,
+    /// When the current streak of trigger errors started
+    first_error: Option<Instant>,
+    last_error: Option<Instant>,
+}
+
+/// A handle to the readiness and liveness of a [`Controller`](super::Controller), retrieved with
+/// [`Controller::health`](super::Controller::health)
+///
+/// The controller becomes ready once all of its stores have completed their initial list,
+/// and stops being live once its trigger watches have kept failing for
+/// [`trigger_unhealthy_after`](super::Controller::trigger_unhealthy_after).
+///
+/// This does not depend on an HTTP server, but [`Health::respond`] can back the probe endpoints of any server:
+///
+/// ```
+/// # use kube::runtime::controller::Health;
+/// fn handle(health: &Health, path: &str) -> (u16, &'static str) {
+///     health.respond(path).unwrap_or((404, "not found"))
+/// }
+/// ```
+#[derive(Clone, Debug)]
+pub struct Health {
+    ready: watch::Receiver<bool>,
+    liveness: Arc<Mutex<Liveness>>,
+}
+
+impl Health {
+    pub(super) fn new() -> (watch::Sender<bool>, Self) {
+        let (ready_tx, ready) = watch::channel(false);
+        (ready_tx, Self {
+            ready,
+            liveness: Arc::new(Mutex::new(Liveness {
+                unhealthy_after: DEFAULT_UNHEALTHY_AFTER,
+                first_error: None,
+                last_error: None,
+            })),
+        })
+    }
+
+    /// Whether all stores of the controller have completed their initial list
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        *self.ready.borrow()
+    }
+
+    /// Waits until all stores of the controller have completed their initial list
+    ///
+    /// Never completes if the controller is dropped before it became ready.
+    pub async fn ready(&self) {
+        let mut ready = self.ready.clone();
+        while !*ready.borrow() {
+            if ready.changed().await.is_err() {
+                std::future::pending::<()>().await;
+            }
+        }
+    }
+
+    /// Whether the trigger watches of the controller are making progress
+    ///
+    /// This is `false` while the trigger watches have been failing (and backing off) for longer than the
+    /// configured threshold. Watches that recover without emitting any objects are considered live again once
+    /// they have stopped failing for the same duration.
+    #[must_use]
+    pub fn is_live(&self) -> bool {
+        let liveness = self.liveness.lock();
+        match (liveness.first_error, liveness.last_error) {
+            (Some(first), Some(last)) => {
+                last.duration_since(first) < liveness.unhealthy_after
+                    || last.elapsed() >= liveness.unhealthy_after
+            }
+            _ => true,
+        }
+    }
+
+    /// Whether `probe` currently passes
+    #[must_use]
+    pub fn check(&self, probe: Probe) -> bool {
+        match probe {
+            Probe::Readiness => self.is_ready(),
+            Probe::Liveness => self.is_live(),
+        }
+    }
+
+    /// The HTTP status code and body to respond to a request for `path` with, if it is a [`Probe::path`]
+    #[must_use]
+    pub fn respond(&self, path: &str) -> Option<(u16, &'static str)> {
+        Probe::from_path(path).map(|probe| Probe::response(self.check(probe)))
+    }
+
+    pub(super) fn set_unhealthy_after(&self, unhealthy_after: Duration) {
+        self.liveness.lock().unhealthy_after = unhealthy_after;
+    }
+
+    /// Records the outcome of an item from the trigger watches
+    pub(super) fn observe_trigger(&self, ok: bool) {
+        let mut liveness = self.liveness.lock();
+        if ok {
+            liveness.first_error = None;
+            liveness.last_error = None;
+        } else {
+            let now = Instant::now();
+            liveness.first_error.get_or_insert(now);
+            liveness.last_error = Some(now);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Health, Probe};
+    use std::time::Duration;
+
+    #[tokio::test(start_paused = true)]
+    async fn liveness_fails_when_trigger_errors_persist() {
+        let (_ready_tx, health) = Health::new();
+        health.set_unhealthy_after(Duration::from_secs(60));
+        assert!(health.is_live());
+
+        health.observe_trigger(false);
+        tokio::time::advance(Duration::from_secs(30)).await;
+        health.observe_trigger(false);
+        assert!(health.is_live());
+        tokio::time::advance(Duration::from_secs(30)).await;
+        health.observe_trigger(false);
+        assert!(!health.is_live());
+        assert_eq!(health.respond("/livez"), Some((503, "unavailable")));
+
+        // Recovers on success, or once errors stop for long enough
+        health.observe_trigger(true);
+        assert!(health.is_live());
+        for _ in 0..3 {
+            health.observe_trigger(false);
+            tokio::time::advance(Duration::from_secs(30)).await;
+        }
+        assert!(!health.is_live());
+        tokio::time::advance(Duration::from_secs(60)).await;
+        assert!(health.is_live());
+    }
+
+    #[tokio::test]
+    async fn readiness_follows_sender() {
+        let (ready_tx, health) = Health::new();
+        assert!(!health.check(Probe::Readiness));
+        assert_eq!(health.respond("/readyz"), Some((503, "unavailable")));
+        assert_eq!(health.respond("/metrics"), None);
+
+        ready_tx.send(true).unwrap();
+        health.ready().await;
+        assert_eq!(health.respond(Probe::Readiness.path()), Some((200, "ok")));
+    }
+}
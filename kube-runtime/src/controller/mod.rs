@@ -1,11 +1,13 @@
 //! Runs a user-supplied reconciler function on objects when they (or related objects) are updated
 
 use self::runner::Runner;
+#[cfg(feature = "metrics")] use crate::metrics::Metrics;
 use crate::{
+    events::{Event, EventType, Recorder},
     reflector::{
         self, reflector,
         store::{Store, Writer},
-        ObjectRef,
+        ObjectRef, ReflectHandle,
     },
     scheduler::{debounced_scheduler, ScheduleRequest},
     utils::{trystream_try_via, CancelableJoinHandle, KubeRuntimeStreamExt, StreamBackoff, WatchStreamExt},
@@ -18,10 +20,14 @@ use futures::{
     future::{self, BoxFuture},
     stream, FutureExt, Stream, StreamExt, TryFuture, TryFutureExt, TryStream, TryStreamExt,
 };
-use kube_client::api::{Api, DynamicObject, Resource};
+use kube_client::{
+    api::{Api, DynamicObject, Resource},
+    core::ObjectMeta,
+};
 use pin_project::pin_project;
 use serde::de::DeserializeOwned;
 use std::{
+    collections::HashMap,
     fmt::{Debug, Display},
     future::Future,
     hash::Hash,
@@ -31,12 +37,19 @@ use std::{
 };
 use stream::BoxStream;
 use thiserror::Error;
-use tokio::{runtime::Handle, time::Instant};
+use tokio::{
+    runtime::Handle,
+    sync::{watch, Semaphore},
+    time::Instant,
+};
 use tracing::{info_span, Instrument};
 
 mod future_hash_map;
+mod health;
 mod runner;
 
+pub use health::{Health, Probe};
+
 pub type RunnerError = runner::Error<reflector::store::WriterDropped>;
 
 #[derive(Debug, Error)]
@@ -59,6 +72,8 @@ pub struct Action {
     /// For example, use this to query external systems for updates, expire time-limited resources, or
     /// (in your `error_policy`) retry after errors.
     requeue_after: Option<Duration>,
+    /// Why the reconciliation was given up, see [`Action::give_up`]
+    give_up_reason: Option<String>,
 }
 
 impl Action {
@@ -72,6 +87,7 @@ impl Action {
     pub fn requeue(duration: Duration) -> Self {
         Self {
             requeue_after: Some(duration),
+            give_up_reason: None,
         }
     }
 
@@ -85,7 +101,117 @@ impl Action {
     /// frequent changes to the underlying object, or some other hook to retain eventual consistency.
     #[must_use]
     pub fn await_change() -> Self {
-        Self { requeue_after: None }
+        Self {
+            requeue_after: None,
+            give_up_reason: None,
+        }
+    }
+
+    /// Stop retrying the object until a change is detected, because the error will not resolve itself
+    ///
+    /// This is meant to be returned from an `error_policy` for permanent errors, such as an invalid spec.
+    /// Like [`Action::await_change`], the object is not requeued, but the `reason` is passed on to the
+    /// [`Controller::on_reconcile_error`] hook, for example to publish it as a Kubernetes Event on the object.
+    #[must_use]
+    pub fn give_up(reason: impl Into<String>) -> Self {
+        Self {
+            requeue_after: None,
+            give_up_reason: Some(reason.into()),
+        }
+    }
+
+    /// When the object is reconciled again, if no change is detected before then
+    #[must_use]
+    pub fn requeue_after(&self) -> Option<Duration> {
+        self.requeue_after
+    }
+
+    /// Why the object was given up on, if the action was created by [`Action::give_up`]
+    #[must_use]
+    pub fn give_up_reason(&self) -> Option<&str> {
+        self.give_up_reason.as_deref()
+    }
+}
+
+/// Called with every object that was reconciled successfully, see [`Controller::on_reconcile_success`]
+type SuccessHook<K> = Arc<dyn Fn(&K, &Action) + Send + Sync>;
+
+/// Called with every object that failed to reconcile, see [`Controller::on_reconcile_error`]
+type ErrorHook<K> = Arc<dyn Fn(&K, &(dyn std::error::Error + 'static), &Action) + Send + Sync>;
+
+/// The maximum number of error events that may be waiting to be published at any time
+///
+/// Further errors are not published until the apiserver catches up, rather than piling up tasks.
+const MAX_PENDING_ERROR_EVENTS: usize = 32;
+
+/// Publishes a Warning [`Event`] on objects that failed to reconcile, see [`Controller::publish_error_events`]
+struct ErrorEvents<K: Resource> {
+    recorder: Recorder,
+    min_interval: Duration,
+    dyntype: K::DynamicType,
+    last_published: parking_lot::Mutex<HashMap<ObjectRef<K>, Instant>>,
+    pending: Arc<Semaphore>,
+}
+
+impl<K> ErrorEvents<K>
+where
+    K: Resource + 'static,
+    K::DynamicType: Eq + Hash + Clone,
+{
+    /// Whether an event may be published for `obj_ref`, recording it as published if so
+    fn acquire(&self, obj_ref: ObjectRef<K>) -> bool {
+        let now = Instant::now();
+        let mut last_published = self.last_published.lock();
+        last_published.retain(|_, published| now.duration_since(*published) < self.min_interval);
+        if last_published.contains_key(&obj_ref) {
+            return false;
+        }
+        last_published.insert(obj_ref, now);
+        true
+    }
+
+    fn new(recorder: Recorder, min_interval: Duration, dyntype: K::DynamicType) -> Self {
+        Self {
+            recorder,
+            min_interval,
+            dyntype,
+            last_published: parking_lot::Mutex::default(),
+            pending: Arc::new(Semaphore::new(MAX_PENDING_ERROR_EVENTS)),
+        }
+    }
+
+    fn publish(&self, obj: &K, error: &(dyn std::error::Error + 'static), action: &Action) {
+        let obj_ref = ObjectRef::from_obj_with(obj, self.dyntype.clone());
+        let Ok(permit) = self.pending.clone().try_acquire_owned() else {
+            tracing::debug!(object.ref = %obj_ref, "too many pending error events, skipping: {error}");
+            return;
+        };
+        if !self.acquire(obj_ref.clone()) {
+            return;
+        }
+        let note = match action.give_up_reason() {
+            Some(reason) => format!("{reason}: {error}"),
+            None => error.to_string(),
+        };
+        let event = Event {
+            type_: EventType::Warning,
+            reason: if action.give_up_reason().is_some() {
+                "ReconcileGaveUp".into()
+            } else {
+                "ReconcileFailed".into()
+            },
+            note: Some(note),
+            action: "Reconcile".into(),
+            secondary: None,
+        };
+        let reference = obj.object_ref(&self.dyntype);
+        let (recorder, obj_ref) = (self.recorder.clone(), obj_ref.erase());
+        tokio::spawn(async move {
+            if let Err(err) = recorder.publish(&event, &reference).await {
+                tracing::warn!(object.ref = %obj_ref, "failed to publish reconcile error event: {err}");
+            }
+            drop(permit);
+        });
     }
 }
 
@@ -119,13 +245,14 @@ where
         Some(ReconcileRequest {
             obj_ref: ObjectRef::from_obj_with(&obj, dyntype.clone()),
             reason: ReconcileReason::ObjectUpdated,
+            priority: 0,
+            cluster: None,
         })
     })
 }
 
 /// Enqueues the object itself for reconciliation when the object is behind a
 /// shared pointer
-#[cfg(feature = "unstable-runtime-subscribe")]
 fn trigger_self_shared<K, S>(
     stream: S,
     dyntype: K::DynamicType,
@@ -141,6 +268,8 @@ where
         Some(ReconcileRequest {
             obj_ref: ObjectRef::from_obj_with(obj.as_ref(), dyntype.clone()),
             reason: ReconcileReason::ObjectUpdated,
+            priority: 0,
+            cluster: None,
         })
     })
 }
@@ -172,12 +301,13 @@ where
                 reason: ReconcileReason::RelatedObjectUpdated {
                     obj_ref: Box::new(watch_ref.clone()),
                 },
+                priority: 0,
+                cluster: None,
             })
     })
 }
 
 /// Enqueues any mapper returned `Arc<K>` types for reconciliation
-#[cfg(feature = "unstable-runtime-subscribe")]
 fn trigger_others_shared<S, O, K, I>(
     stream: S,
     mapper: impl Fn(S::Ok) -> I + Sync + Send + 'static,
@@ -204,6 +334,8 @@ where
                 reason: ReconcileReason::RelatedObjectUpdated {
                     obj_ref: Box::new(watch_ref.clone()),
                 },
+                priority: 0,
+                cluster: None,
             })
     })
 }
@@ -233,11 +365,190 @@ where
     trigger_others(stream, mapper, child_type)
 }
 
+/// Enqueues any owners of type `KOwner` for reconciliation, in the namespace resolved by `owner_namespace`
+pub fn trigger_owners_in<KOwner, S>(
+    stream: S,
+    owner_namespace: OwnerNamespace,
+    owner_type: KOwner::DynamicType,
+    child_type: <S::Ok as Resource>::DynamicType,
+) -> impl Stream<Item = Result<ReconcileRequest<KOwner>, S::Error>>
+where
+    S: TryStream,
+    S::Ok: Resource,
+    <S::Ok as Resource>::DynamicType: Clone,
+    KOwner: Resource,
+    KOwner::DynamicType: Clone,
+{
+    let mapper = move |obj: S::Ok| {
+        let ns = (owner_namespace.0)(obj.meta());
+        let owner_type = owner_type.clone();
+        obj.meta()
+            .owner_references
+            .clone()
+            .into_iter()
+            .flatten()
+            .filter_map(move |owner| ObjectRef::from_owner_ref(ns.as_deref(), &owner, owner_type.clone()))
+    };
+    trigger_others(stream, mapper, child_type)
+}
+
+/// Resolves the namespace of the owners in the ownerReferences of an object
+///
+/// By default, owners are assumed to be in the same namespace as the object that they own, like the garbage
+/// collector does. Controllers that keep their parent objects in another namespace, such as a fixed control
+/// namespace, can resolve it with [`Controller::owns_cross_namespace`] instead.
+#[derive(Clone)]
+pub struct OwnerNamespace(Arc<ResolveOwnerNamespace>);
+
+type ResolveOwnerNamespace = dyn Fn(&ObjectMeta) -> Option<String> + Send + Sync;
+
+impl OwnerNamespace {
+    /// Owners are in the namespace stored in the annotation `key` of the objects they own
+    ///
+    /// Falls back to the namespace of the owned object if the annotation is missing.
+    #[must_use]
+    pub fn from_annotation(key: impl Into<String>) -> Self {
+        let key = key.into();
+        Self::mapper(move |meta| {
+            meta.annotations
+                .as_ref()
+                .and_then(|annotations| annotations.get(&key))
+                .or(meta.namespace.as_ref())
+                .cloned()
+        })
+    }
+
+    /// Owners are all in `namespace`
+    #[must_use]
+    pub fn fixed(namespace: impl Into<String>) -> Self {
+        let namespace = namespace.into();
+        Self::mapper(move |_| Some(namespace.clone()))
+    }
+
+    /// Owners are in the namespace returned by `f` for the metadata of the objects they own
+    ///
+    /// Returning `None` refers to cluster-scoped owners.
+    #[must_use]
+    pub fn mapper(f: impl Fn(&ObjectMeta) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self(Arc::new(f))
+    }
+}
+
+impl Debug for OwnerNamespace {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("OwnerNamespace")
+    }
+}
+
+/// Enqueues the owners of type `KOwner` named by the [`OwnerLabels`] of each object for reconciliation
+pub fn trigger_owners_by_label<KOwner, S>(
+    stream: S,
+    owner_labels: OwnerLabels,
+    owner_type: KOwner::DynamicType,
+    child_type: <S::Ok as Resource>::DynamicType,
+) -> impl Stream<Item = Result<ReconcileRequest<KOwner>, S::Error>>
+where
+    S: TryStream,
+    S::Ok: Resource,
+    <S::Ok as Resource>::DynamicType: Clone,
+    KOwner: Resource + 'static,
+    KOwner::DynamicType: Clone,
+{
+    let mapper = move |obj: S::Ok| owner_labels.owner_ref(obj.meta(), owner_type.clone());
+    trigger_others(stream, mapper, child_type)
+}
+
+/// Labels (or annotations) naming the owner of an object, for relations that cannot use ownerReferences
+///
+/// `ownerReferences` can only point to owners in the same namespace as their child, or to cluster-scoped
+/// owners. Children in other namespaces, or cluster-scoped children of namespaced owners, can instead carry
+/// the name and namespace of their owner in labels, see [`Controller::owns_by_label`].
+///
+/// ```
+/// use kube::runtime::controller::OwnerLabels;
+/// // children labelled with `example.com/owner-name` and `example.com/owner-namespace`
+/// let owner_labels = OwnerLabels::new("example.com/owner-name")
+///     .namespace_from("example.com/owner-namespace");
+/// ```
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct OwnerLabels {
+    name: String,
+    namespace: LabelledNamespace,
+    annotations: bool,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+enum LabelledNamespace {
+    Child,
+    Key(String),
+    Cluster,
+}
+
+impl OwnerLabels {
+    /// Owners named by the label `name_key` of their children, in the same namespace as their children
+    #[must_use]
+    pub fn new(name_key: impl Into<String>) -> Self {
+        Self {
+            name: name_key.into(),
+            namespace: LabelledNamespace::Child,
+            annotations: false,
+        }
+    }
+
+    /// Reads the namespace of owners from the label `namespace_key` of their children
+    ///
+    /// Children without this label are ignored.
+    #[must_use]
+    pub fn namespace_from(mut self, namespace_key: impl Into<String>) -> Self {
+        self.namespace = LabelledNamespace::Key(namespace_key.into());
+        self
+    }
+
+    /// Owners are cluster-scoped, so children name them without a namespace
+    #[must_use]
+    pub fn cluster_scoped(mut self) -> Self {
+        self.namespace = LabelledNamespace::Cluster;
+        self
+    }
+
+    /// Reads the keys from annotations instead of labels
+    ///
+    /// Label values are limited to 63 characters, so this is needed for owners with longer names.
+    #[must_use]
+    pub fn from_annotations(mut self) -> Self {
+        self.annotations = true;
+        self
+    }
+
+    /// A label selector for the children that name an owner, or `None` when reading annotations
+    fn selector(&self) -> Option<&str> {
+        (!self.annotations).then_some(self.name.as_str())
+    }
+
+    /// The owner named by the metadata of a child
+    fn owner_ref<K: Resource>(&self, meta: &ObjectMeta, dyntype: K::DynamicType) -> Option<ObjectRef<K>> {
+        let values = if self.annotations {
+            meta.annotations.as_ref()
+        } else {
+            meta.labels.as_ref()
+        }?;
+        let owner = ObjectRef::new_with(values.get(&self.name)?, dyntype);
+        let namespace = match &self.namespace {
+            LabelledNamespace::Child => meta.namespace.as_ref(),
+            LabelledNamespace::Key(key) => Some(values.get(key)?),
+            LabelledNamespace::Cluster => None,
+        };
+        Some(match namespace {
+            Some(namespace) => owner.within(namespace),
+            None => owner,
+        })
+    }
+}
+
 // TODO: do we really need to deal with a trystream? can we simplify this at
 // all?
 /// Enqueues any owners of type `KOwner` for reconciliation based on a stream of
 /// owned `K` objects
-#[cfg(feature = "unstable-runtime-subscribe")]
 fn trigger_owners_shared<KOwner, S, K>(
     stream: S,
     owner_type: KOwner::DynamicType,
@@ -267,6 +578,8 @@ where
 /// NOTE: The reason is ignored for comparison purposes. This means that, for example,
 /// an object can only occupy one scheduler slot, even if it has been scheduled for multiple reasons.
 /// In this case, only *the first* reason is stored.
+///
+/// The same goes for the priority, except that the highest priority is kept when an object is scheduled again.
 #[derive(Educe)]
 #[educe(
     Debug(bound("K::DynamicType: Debug")),
@@ -278,6 +591,38 @@ pub struct ReconcileRequest<K: Resource> {
     pub obj_ref: ObjectRef<K>,
     #[educe(PartialEq(ignore), Hash(ignore))]
     pub reason: ReconcileReason,
+    #[educe(PartialEq(ignore), Hash(ignore))]
+    priority: i32,
+    /// The cluster of the object that triggered the request, see [`Controller::watches_in_cluster`]
+    ///
+    /// Like the reason, only *the first* cluster is kept when an object is scheduled again.
+    /// The reconciler can read it with [`reconcile_cluster`].
+    #[educe(PartialEq(ignore), Hash(ignore))]
+    pub cluster: Option<String>,
+}
+
+impl<K: Resource> ReconcileRequest<K> {
+    /// Requests with a higher priority are reconciled first when the controller is at its concurrency limit
+    ///
+    /// Defaults to 0, and is overridden by [`Config::with_priority_fn`] if set.
+    #[must_use]
+    pub fn priority(&self) -> i32 {
+        self.priority
+    }
+
+    /// Sets the [`priority`](Self::priority) of the request
+    #[must_use]
+    pub fn with_priority(mut self, priority: i32) -> Self {
+        self.priority = priority;
+        self
+    }
+
+    /// Sets the [`cluster`](Self::cluster) that the request was triggered from
+    #[must_use]
+    pub fn with_cluster(mut self, cluster: impl Into<String>) -> Self {
+        self.cluster = Some(cluster.into());
+        self
+    }
 }
 
 impl<K: Resource> Eq for ReconcileRequest<K> where K::DynamicType: Eq {}
@@ -287,6 +632,8 @@ impl<K: Resource> From<ObjectRef<K>> for ReconcileRequest<K> {
         ReconcileRequest {
             obj_ref,
             reason: ReconcileReason::Unknown,
+            priority: 0,
+            cluster: None,
         }
     }
 }
@@ -318,6 +665,19 @@ impl Display for ReconcileReason {
     }
 }
 
+tokio::task_local! {
+    static RECONCILE_CLUSTER: Option<String>;
+}
+
+/// The cluster that triggered the running reconciliation, see [`Controller::watches_in_cluster`]
+///
+/// Returns `None` when the reconciliation was not triggered by another cluster, or when called
+/// outside of the future returned by the reconciler.
+#[must_use]
+pub fn reconcile_cluster() -> Option<String> {
+    RECONCILE_CLUSTER.try_with(Clone::clone).ok().flatten()
+}
+
 const APPLIER_REQUEUE_BUF_SIZE: usize = 100;
 
 /// Apply a reconciler to an input stream, with a given retry policy
@@ -333,6 +693,7 @@ const APPLIER_REQUEUE_BUF_SIZE: usize = 100;
 /// (such as triggering from arbitrary [`Stream`]s), at the cost of being a bit more verbose.
 #[allow(clippy::needless_pass_by_value)]
 #[allow(clippy::type_complexity)]
+#[allow(clippy::too_many_lines)]
 pub fn applier<K, QueueStream, ReconcilerFut, Ctx>(
     mut reconciler: impl FnMut(Arc<K>, Arc<Ctx>) -> ReconcilerFut,
     error_policy: impl Fn(Arc<K>, &ReconcilerFut::Error, Arc<Ctx>) -> Action,
@@ -355,76 +716,111 @@ where
         channel::mpsc::channel::<ScheduleRequest<ReconcileRequest<K>>>(APPLIER_REQUEUE_BUF_SIZE);
     let error_policy = Arc::new(error_policy);
     let delay_store = store.clone();
+    let priority_fn = config.priority_fn.clone();
+    let mut generation_filter = config
+        .skip_unchanged_generation
+        .then(|| GenerationFilter::new(store.clone()));
     // Create a stream of ObjectRefs that need to be reconciled
     trystream_try_via(
         // input: stream combining scheduled tasks and user specified inputs event
-        Box::pin(stream::select(
-            // 1. inputs from users queue stream
-            queue
-                .map_err(Error::QueueError)
-                .map_ok(|request| ScheduleRequest {
-                    message: request.into(),
-                    run_at: Instant::now(),
-                })
-                .on_complete(async move {
-                    // On error: scheduler has already been shut down and there is nothing for us to do
-                    let _ = scheduler_shutdown_tx.send(());
-                    tracing::debug!("applier queue terminated, starting graceful shutdown")
-                }),
-            // 2. requests sent to scheduler_tx
-            scheduler_rx
-                .map(Ok)
-                .take_until(scheduler_shutdown_rx)
-                .on_complete(async { tracing::debug!("applier scheduler consumer terminated") }),
-        )),
+        Box::pin(
+            stream::select(
+                // 1. inputs from users queue stream
+                queue
+                    .map_err(Error::QueueError)
+                    .map_ok(Into::<ReconcileRequest<K>>::into)
+                    .try_filter(move |request| {
+                        std::future::ready(match &mut generation_filter {
+                            Some(generation_filter) => generation_filter.is_changed(request),
+                            None => true,
+                        })
+                    })
+                    .map_ok(|request| ScheduleRequest {
+                        message: request,
+                        run_at: Instant::now(),
+                    })
+                    .on_complete(async move {
+                        // On error: scheduler has already been shut down and there is nothing for us to do
+                        let _ = scheduler_shutdown_tx.send(());
+                        tracing::debug!("applier queue terminated, starting graceful shutdown")
+                    }),
+                // 2. requests sent to scheduler_tx
+                scheduler_rx
+                    .map(Ok)
+                    .take_until(scheduler_shutdown_rx)
+                    .on_complete(async { tracing::debug!("applier scheduler consumer terminated") }),
+            )
+            .map_ok(move |mut request| {
+                if let Some(priority_fn) = &priority_fn {
+                    let reconcile = &mut request.message;
+                    reconcile.priority = priority_fn(&reconcile.obj_ref.clone().erase(), &reconcile.reason);
+                }
+                request
+            }),
+        ),
         // all the Oks from the select gets passed through the scheduler stream, and are then executed
         move |s| {
-            Runner::new(
-                debounced_scheduler(s, config.debounce),
-                config.concurrency,
-                move |request| {
-                    let request = request.clone();
-                    match store.get(&request.obj_ref) {
-                        Some(obj) => {
-                            let scheduler_tx = scheduler_tx.clone();
-                            let error_policy_ctx = context.clone();
-                            let error_policy = error_policy.clone();
-                            let reconciler_span = info_span!(
-                                "reconciling object",
-                                "object.ref" = %request.obj_ref,
-                                object.reason = %request.reason
-                            );
-                            reconciler_span
-                                .in_scope(|| reconciler(Arc::clone(&obj), context.clone()))
-                                .into_future()
-                                .then(move |res| {
-                                    let error_policy = error_policy;
-                                    RescheduleReconciliation::new(
-                                        res,
-                                        |err| error_policy(obj, err, error_policy_ctx),
-                                        request.obj_ref.clone(),
-                                        scheduler_tx,
-                                    )
-                                    // Reconciler errors are OK from the applier's PoV, we need to apply the error policy
-                                    // to them separately
-                                    .map(|res| Ok((request.obj_ref, res)))
-                                })
-                                .instrument(reconciler_span)
-                                .left_future()
-                        }
-                        None => std::future::ready(Err(Error::ObjectNotFound(request.obj_ref.erase())))
-                            .right_future(),
+            let mut scheduler = debounced_scheduler(s, config.debounce);
+            if config.priority_fn.is_some() {
+                scheduler = scheduler.with_priority(ReconcileRequest::priority);
+            }
+            #[cfg(feature = "metrics")]
+            let scheduler = scheduler.with_metrics(config.metrics.clone());
+            #[cfg(feature = "metrics")]
+            let reconcile_metrics = config.metrics.clone();
+            let runner = Runner::new(scheduler, config.concurrency, move |request| {
+                let request = request.clone();
+                match store.get(&request.obj_ref) {
+                    Some(obj) => {
+                        let scheduler_tx = scheduler_tx.clone();
+                        let error_policy_ctx = context.clone();
+                        let error_policy = error_policy.clone();
+                        #[cfg(feature = "metrics")]
+                        let (metrics, reconcile_started_at) = (reconcile_metrics.clone(), Instant::now());
+                        let reconciler_span = info_span!(
+                            "reconciling object",
+                            "object.ref" = %request.obj_ref,
+                            object.reason = %request.reason
+                        );
+                        let reconciliation = reconciler_span
+                            .in_scope(|| reconciler(Arc::clone(&obj), context.clone()))
+                            .into_future();
+                        Box::pin(RECONCILE_CLUSTER.scope(request.cluster.clone(), reconciliation))
+                            .then(move |res| {
+                                let error_policy = error_policy;
+                                #[cfg(feature = "metrics")]
+                                metrics.reconciled(reconcile_started_at.elapsed(), res.is_err());
+                                RescheduleReconciliation::new(
+                                    res,
+                                    |err| error_policy(obj, err, error_policy_ctx),
+                                    request.obj_ref.clone(),
+                                    request.priority,
+                                    request.cluster,
+                                    scheduler_tx,
+                                )
+                                // Reconciler errors are OK from the applier's PoV, we need to apply the error policy
+                                // to them separately
+                                .map(|res| Ok((request.obj_ref, res)))
+                            })
+                            .instrument(reconciler_span)
+                            .left_future()
                     }
-                },
-            )
-            .delay_tasks_until(async move {
-                tracing::debug!("applier runner held until store is ready");
-                let res = delay_store.wait_until_ready().await;
-                tracing::debug!("store is ready, starting runner");
-                res
-            })
-            .map(|runner_res| runner_res.unwrap_or_else(|err| Err(Error::RunnerError(err))))
-            .on_complete(async { tracing::debug!("applier runner terminated") })
+                    None => {
+                        std::future::ready(Err(Error::ObjectNotFound(request.obj_ref.erase()))).right_future()
+                    }
+                }
+            });
+            #[cfg(feature = "metrics")]
+            let runner = runner.with_metrics(config.metrics.clone());
+            runner
+                .delay_tasks_until(async move {
+                    tracing::debug!("applier runner held until store is ready");
+                    let res = delay_store.wait_until_ready().await;
+                    tracing::debug!("store is ready, starting runner");
+                    res
+                })
+                .map(|runner_res| runner_res.unwrap_or_else(|err| Err(Error::RunnerError(err))))
+                .on_complete(async { tracing::debug!("applier runner terminated") })
         },
     )
     .on_complete(async { tracing::debug!("applier runner-merge terminated") })
@@ -438,6 +834,47 @@ where
     .on_complete(async { tracing::debug!("applier terminated") })
 }
 
+/// Drops reconcile requests for objects whose generation has not changed, see [`Config::skip_unchanged_generation`]
+struct GenerationFilter<K: Resource + 'static>
+where
+    K::DynamicType: Eq + Hash,
+{
+    store: Store<K>,
+    generations: HashMap<ObjectRef<K>, i64>,
+}
+
+impl<K: Resource + Clone + 'static> GenerationFilter<K>
+where
+    K::DynamicType: Eq + Hash + Clone,
+{
+    fn new(store: Store<K>) -> Self {
+        Self {
+            store,
+            generations: HashMap::new(),
+        }
+    }
+
+    /// Whether the request should be reconciled
+    ///
+    /// Only requests triggered by the object itself are filtered, and objects without a generation always
+    /// pass through.
+    fn is_changed(&mut self, request: &ReconcileRequest<K>) -> bool {
+        if !matches!(request.reason, ReconcileReason::ObjectUpdated) {
+            return true;
+        }
+        let generation = self
+            .store
+            .get(&request.obj_ref)
+            .and_then(|obj| obj.meta().generation);
+        if let Some(generation) = generation {
+            self.generations.insert(request.obj_ref.clone(), generation) != Some(generation)
+        } else {
+            self.generations.remove(&request.obj_ref);
+            true
+        }
+    }
+}
+
 /// Internal helper [`Future`] that reschedules reconciliation of objects (if required), in the scheduled context of the reconciler
 ///
 /// This could be an `async fn`, but isn't because we want it to be [`Unpin`]
@@ -458,6 +895,8 @@ where
         result: Result<Action, ReconcilerErr>,
         error_policy: impl FnOnce(&ReconcilerErr) -> Action,
         obj_ref: ObjectRef<K>,
+        priority: i32,
+        cluster: Option<String>,
         reschedule_tx: channel::mpsc::Sender<ScheduleRequest<ReconcileRequest<K>>>,
     ) -> Self {
         let reconciler_finished_at = Instant::now();
@@ -473,6 +912,8 @@ where
                 message: ReconcileRequest {
                     obj_ref,
                     reason: reschedule_reason,
+                    priority,
+                    cluster,
                 },
                 run_at: reconciler_finished_at
                     .checked_add(requeue_after)
@@ -512,11 +953,20 @@ where
     }
 }
 
+/// Computes the priority of a [`ReconcileRequest`], see [`Config::with_priority_fn`]
+type PriorityFn = Arc<dyn Fn(&ObjectRef<DynamicObject>, &ReconcileReason) -> i32 + Send + Sync>;
+
 /// Accumulates all options that can be used on a [`Controller`] invocation.
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Default, Educe)]
+#[educe(Debug)]
 pub struct Config {
     debounce: Duration,
     concurrency: u16,
+    #[educe(Debug(ignore))]
+    priority_fn: Option<PriorityFn>,
+    skip_unchanged_generation: bool,
+    #[cfg(feature = "metrics")]
+    metrics: Metrics,
 }
 
 impl Config {
@@ -550,6 +1000,62 @@ impl Config {
         self.concurrency = concurrency;
         self
     }
+
+    /// Computes the [`priority`](ReconcileRequest::priority) of every reconcile request.
+    ///
+    /// Requests with a higher priority are reconciled first when more objects are due than the
+    /// [`concurrency`](Self::concurrency) allows, for example to handle user-designated critical objects,
+    /// or retries of failed reconciles, before periodic resyncs. Requests of the same priority are reconciled
+    /// in the order they were due.
+    ///
+    /// Without a priority function, requests are reconciled in the order they were due, and the scheduler
+    /// does not need to rank the requests that are due at the same time.
+    ///
+    /// ```
+    /// # use kube::runtime::controller::{Config, ReconcileReason};
+    /// let config = Config::default().concurrency(4).with_priority_fn(|obj_ref, reason| {
+    ///     match reason {
+    ///         _ if obj_ref.namespace.as_deref() == Some("kube-system") => 10,
+    ///         ReconcileReason::ReconcilerRequestedRetry => -1,
+    ///         _ => 0,
+    ///     }
+    /// });
+    /// ```
+    #[must_use]
+    pub fn with_priority_fn(
+        mut self,
+        priority_fn: impl Fn(&ObjectRef<DynamicObject>, &ReconcileReason) -> i32 + Send + Sync + 'static,
+    ) -> Self {
+        self.priority_fn = Some(Arc::new(priority_fn));
+        self
+    }
+
+    /// Skips reconciles of objects whose changes did not bump their generation.
+    ///
+    /// The generation of an object is only bumped by changes to its spec (and deletions), so this skips
+    /// reconciles triggered by changes to the status or metadata of the object, such as the status updates
+    /// of the reconciler itself. Reconciles triggered by related objects, requeues, and
+    /// [`Controller::reconcile_on`] are not affected, and neither are objects without a generation.
+    ///
+    /// This is similar to filtering the watch stream with
+    /// [`predicates::generation`](crate::predicates::generation), without having to construct the stream.
+    /// Note that relists do not trigger reconciles for unchanged objects either.
+    #[must_use]
+    pub fn skip_unchanged_generation(mut self) -> Self {
+        self.skip_unchanged_generation = true;
+        self
+    }
+
+    /// The [`Metrics`] handle that reconciliation and scheduling metrics are recorded into.
+    ///
+    /// Every [`Config`] starts out with its own set of metrics, this can be used to share
+    /// them between multiple [`applier`]s.
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
 }
 
 /// Controller for a Resource `K`
@@ -641,6 +1147,10 @@ where
     dyntype: K::DynamicType,
     reader: Store<K>,
     config: Config,
+    health: Health,
+    ready_tx: watch::Sender<bool>,
+    on_success: Option<SuccessHook<K>>,
+    on_error: Option<ErrorHook<K>>,
 }
 
 impl<K> Controller<K>
@@ -687,6 +1197,7 @@ where
         )
         .boxed();
         trigger_selector.push(self_watcher);
+        let (ready_tx, health) = Health::new();
         Self {
             trigger_selector,
             trigger_backoff: Box::<DefaultBackoff>::default(),
@@ -701,6 +1212,10 @@ where
             dyntype,
             reader,
             config: Default::default(),
+            health,
+            ready_tx,
+            on_success: None,
+            on_error: None,
         }
     }
 
@@ -773,6 +1288,7 @@ where
         let mut trigger_selector = stream::SelectAll::new();
         let self_watcher = trigger_self(trigger, dyntype.clone()).boxed();
         trigger_selector.push(self_watcher);
+        let (ready_tx, health) = Health::new();
         Self {
             trigger_selector,
             trigger_backoff: Box::<DefaultBackoff>::default(),
@@ -787,6 +1303,10 @@ where
             dyntype,
             reader,
             config: Default::default(),
+            health,
+            ready_tx,
+            on_success: None,
+            on_error: None,
         }
     }
 
@@ -796,10 +1316,6 @@ where
     /// Through this interface, multiple controllers can use the same root
     /// (shared) input stream of resources to keep memory overheads smaller.
     ///
-    /// **N.B**: This constructor requires an
-    /// [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21)
-    /// feature.
-    ///
     /// Prefer [`Controller::new`] or [`Controller::for_stream`] if you do not
     /// need to share the stream.
     ///
@@ -849,7 +1365,6 @@ where
     ///   _ = controller => {},
     /// }
     /// # }
-    #[cfg(feature = "unstable-runtime-subscribe")]
     pub fn for_shared_stream(trigger: impl Stream<Item = Arc<K>> + Send + 'static, reader: Store<K>) -> Self
     where
         K::DynamicType: Default,
@@ -863,10 +1378,6 @@ where
     /// Through this interface, multiple controllers can use the same root
     /// (shared) input stream of resources to keep memory overheads smaller.
     ///
-    /// **N.B**: This constructor requires an
-    /// [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21)
-    /// feature.
-    ///
     /// Prefer [`Controller::new`] or [`Controller::for_stream`] if you do not
     /// need to share the stream.
     ///
@@ -875,7 +1386,6 @@ where
     /// known at compile time).
     ///
     /// [`dynamic`]: kube_client::core::dynamic
-    #[cfg(feature = "unstable-runtime-subscribe")]
     pub fn for_shared_stream_with(
         trigger: impl Stream<Item = Arc<K>> + Send + 'static,
         reader: Store<K>,
@@ -884,6 +1394,7 @@ where
         let mut trigger_selector = stream::SelectAll::new();
         let self_watcher = trigger_self_shared(trigger.map(Ok), dyntype.clone()).boxed();
         trigger_selector.push(self_watcher);
+        let (ready_tx, health) = Health::new();
         Self {
             trigger_selector,
             trigger_backoff: Box::<DefaultBackoff>::default(),
@@ -898,9 +1409,70 @@ where
             dyntype,
             reader,
             config: Default::default(),
+            health,
+            ready_tx,
+            on_success: None,
+            on_error: None,
         }
     }
 
+    /// Creates a new controller from a [`ReflectHandle`] of a shared store
+    ///
+    /// This is the same as [`Controller::for_shared_stream`], with the [`Store`] taken
+    /// from the subscriber itself, so that the controller and the root stream are
+    /// guaranteed to share the same cache.
+    ///
+    /// ## Warning:
+    ///
+    /// You **must** ensure the root stream (i.e. stream created through a `reflector()`)
+    /// is driven to readiness independently of this controller to ensure the
+    /// watcher never deadlocks.
+    ///
+    /// # Example:
+    ///
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use k8s_openapi::api::apps::v1::Deployment;
+    /// # use kube::runtime::controller::{Action, Controller};
+    /// # use kube::runtime::{watcher, reflector, WatchStreamExt};
+    /// # use kube::{Api, Client, Error};
+    /// # use std::sync::Arc;
+    /// # async fn reconcile(_: Arc<Deployment>, _: Arc<()>) -> Result<Action, Error> { Ok(Action::await_change()) }
+    /// # fn error_policy(_: Arc<Deployment>, _: &kube::Error, _: Arc<()>) -> Action { Action::await_change() }
+    /// # async fn doc(client: kube::Client) {
+    /// let api: Api<Deployment> = Api::default_namespaced(client);
+    /// let (_, writer) = reflector::store_shared(128);
+    /// let subscriber = writer
+    ///     .subscribe()
+    ///     .expect("subscribers can only be created from shared stores");
+    /// let deploys = watcher(api, watcher::Config::default())
+    ///     .default_backoff()
+    ///     .reflect_shared(writer)
+    ///     .for_each(|_| async {});
+    ///
+    /// // Each controller gets its own subscriber, all backed by the same store
+    /// let first = Controller::for_subscriber(subscriber.clone())
+    ///     .run(reconcile, error_policy, Arc::new(()))
+    ///     .for_each(|_| async {});
+    /// let second = Controller::for_subscriber(subscriber)
+    ///     .run(reconcile, error_policy, Arc::new(()))
+    ///     .for_each(|_| async {});
+    ///
+    /// tokio::select! {
+    ///   _ = deploys => {},
+    ///   _ = futures::future::join(first, second) => {},
+    /// }
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn for_subscriber(subscriber: ReflectHandle<K>) -> Self
+    where
+        K::DynamicType: Default,
+    {
+        let reader = subscriber.reader();
+        Self::for_shared_stream(subscriber, reader)
+    }
+
     /// Specify the configuration for the controller's behavior.
     #[must_use]
     pub fn with_config(mut self, config: Config) -> Self {
@@ -908,23 +1480,147 @@ where
         self
     }
 
-    /// Specify the backoff policy for "trigger" watches
+    /// Specify the backoff policy for "trigger" watches
+    ///
+    /// This includes the core watch, as well as auxilary watches introduced by [`Self::owns`] and [`Self::watches`].
+    ///
+    /// The [`default_backoff`](crate::watcher::default_backoff) follows client-go conventions,
+    /// but can be overridden by calling this method.
+    #[must_use]
+    pub fn trigger_backoff(mut self, backoff: impl Backoff + Send + 'static) -> Self {
+        self.trigger_backoff = Box::new(backoff);
+        self
+    }
+
+    /// Mark the controller as not live once its trigger watches have kept failing for `unhealthy_after`
+    ///
+    /// Failing watches are retried according to [`Controller::trigger_backoff`], so this detects watches that
+    /// are stuck in backoff, for example because of missing RBAC permissions.
+    /// Defaults to 5 minutes. See [`Controller::health`].
+    #[must_use]
+    pub fn trigger_unhealthy_after(self, unhealthy_after: Duration) -> Self {
+        self.health.set_unhealthy_after(unhealthy_after);
+        self
+    }
+
+    /// Retrieve a copy of the reader before starting the controller
+    pub fn store(&self) -> Store<K> {
+        self.reader.clone()
+    }
+
+    /// Retrieve a handle to the readiness and liveness of the controller, for serving Kubernetes probes
+    ///
+    /// The controller becomes ready once [`Controller::run`] is polled and its store has completed its initial list.
+    pub fn health(&self) -> Health {
+        self.health.clone()
+    }
+
+    /// Returns a [`Future`] that resolves once the controller is ready
+    ///
+    /// See [`Controller::health`].
+    pub fn ready(&self) -> impl Future<Output = ()> + Send + 'static {
+        let health = self.health.clone();
+        async move { health.ready().await }
+    }
+
+    /// Retrieve a handle to the controller's metrics, for registering into a [`prometheus_client::registry::Registry`]
+    ///
+    /// Note that [`Controller::with_config`] replaces the metrics along with the rest of the [`Config`],
+    /// so this should be called afterwards.
+    #[cfg(feature = "metrics")]
+    pub fn metrics(&self) -> Metrics {
+        self.config.metrics.clone()
+    }
+
+    /// Calls `hook` with every object that was reconciled successfully, and the [`Action`] of the reconciler
+    ///
+    /// This can be used to publish Kubernetes Events or record metrics without wrapping the reconciler.
+    /// The hook is called on the reconciler task, so it should not block.
+    #[must_use]
+    pub fn on_reconcile_success(mut self, hook: impl Fn(&K, &Action) + Send + Sync + 'static) -> Self {
+        self.on_success = Some(Arc::new(hook));
+        self
+    }
+
+    /// Calls `hook` with every object that failed to reconcile, its error, and the [`Action`] of the `error_policy`
+    ///
+    /// This can be used to publish Kubernetes Events or record metrics without wrapping the reconciler.
+    /// Errors that the `error_policy` [gave up](Action::give_up) on can be told apart by their
+    /// [`Action::give_up_reason`].
+    ///
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use k8s_openapi::api::core::v1::ConfigMap;
+    /// # use kube::runtime::controller::{Action, Controller};
+    /// # use kube::runtime::watcher;
+    /// # use kube::{Api, Error, ResourceExt};
+    /// # use std::sync::Arc;
+    /// # async fn reconcile(_: Arc<ConfigMap>, _: Arc<()>) -> Result<Action, Error> { Ok(Action::await_change()) }
+    /// fn error_policy(_: Arc<ConfigMap>, error: &Error, _: Arc<()>) -> Action {
+    ///     match error {
+    ///         Error::Api(response) if response.code == 422 => Action::give_up(response.message.clone()),
+    ///         _ => Action::requeue(std::time::Duration::from_secs(5)),
+    ///     }
+    /// }
+    /// # async fn doc(client: kube::Client) {
+    /// Controller::new(Api::<ConfigMap>::all(client), watcher::Config::default())
+    ///     .on_reconcile_error(|obj, error, action| match action.give_up_reason() {
+    ///         Some(reason) => tracing::error!(name = obj.name_any(), reason, "giving up: {error}"),
+    ///         None => tracing::warn!(name = obj.name_any(), "reconcile failed: {error}"),
+    ///     })
+    ///     .run(reconcile, error_policy, Arc::new(()))
+    ///     .for_each(|_| std::future::ready(()))
+    ///     .await;
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn on_reconcile_error(
+        mut self,
+        hook: impl Fn(&K, &(dyn std::error::Error + 'static), &Action) + Send + Sync + 'static,
+    ) -> Self {
+        self.on_error = Some(Arc::new(hook));
+        self
+    }
+
+    /// Publishes a Warning [`Event`] on each object that failed to reconcile, using `recorder`
     ///
-    /// This includes the core watch, as well as auxilary watches introduced by [`Self::owns`] and [`Self::watches`].
+    /// This makes reconcile errors visible to cluster operators in `kubectl describe`.
+    /// Events use the reason `ReconcileFailed`, or `ReconcileGaveUp` when the `error_policy`
+    /// [gave up](Action::give_up), and carry the error message as their note.
     ///
-    /// The [`default_backoff`](crate::watcher::default_backoff) follows client-go conventions,
-    /// but can be overridden by calling this method.
+    /// At most one event is published per object every `min_interval`. Repeats of the same error are also
+    /// aggregated into an event series by the [`Recorder`].
+    ///
+    /// This is added on top of any hook set by [`Controller::on_reconcile_error`], as long as that is called first.
+    ///
+    /// ```no_run
+    /// # use k8s_openapi::api::core::v1::ConfigMap;
+    /// # use kube::runtime::{controller::Controller, events::Recorder, watcher};
+    /// # use kube::Api;
+    /// # use std::time::Duration;
+    /// # async fn doc(client: kube::Client) {
+    /// let recorder = Recorder::new(client.clone(), "configmap-controller".into());
+    /// let controller = Controller::new(Api::<ConfigMap>::all(client), watcher::Config::default())
+    ///     .publish_error_events(recorder, Duration::from_secs(60));
+    /// # }
+    /// ```
+    ///
+    /// # RBAC
+    ///
+    /// Requires `create` and `patch` permissions for `events` in the `events.k8s.io` API group.
     #[must_use]
-    pub fn trigger_backoff(mut self, backoff: impl Backoff + Send + 'static) -> Self {
-        self.trigger_backoff = Box::new(backoff);
+    pub fn publish_error_events(mut self, recorder: Recorder, min_interval: Duration) -> Self {
+        let events = ErrorEvents::new(recorder, min_interval, self.dyntype.clone());
+        let previous = self.on_error.take();
+        self.on_error = Some(Arc::new(move |obj, error, action| {
+            if let Some(previous) = &previous {
+                previous(obj, error, action);
+            }
+            events.publish(obj, error, action);
+        }));
         self
     }
 
-    /// Retrieve a copy of the reader before starting the controller
-    pub fn store(&self) -> Store<K> {
-        self.reader.clone()
-    }
-
     /// Specify `Child` objects which `K` owns and should be watched
     ///
     /// Takes an [`Api`] object that determines how the `Controller` listens for changes to the `Child`.
@@ -967,6 +1663,145 @@ where
         self
     }
 
+    /// Specify `Child` objects which `K` owns, where `K` may be in another namespace than its `Child` objects
+    ///
+    /// Same as [`Controller::owns`], but the namespace of the owners in each [`OwnerReference`] is resolved
+    /// by an [`OwnerNamespace`] instead of assuming that owners share the namespace of their `Child`.
+    ///
+    /// **NB**: The garbage collector deletes namespaced objects whose ownerReferences cannot be found in their own
+    /// namespace, and never deletes cluster-scoped objects with namespaced owners. This is mostly useful for
+    /// cluster-scoped `Child` objects, which have to be cleaned up by the controller, such as with a
+    /// [`finalizer`](crate::finalizer()).
+    ///
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use k8s_openapi::api::core::v1::{ConfigMap, Namespace};
+    /// # use kube::runtime::controller::{Action, OwnerNamespace};
+    /// # use kube::runtime::{watcher, Controller};
+    /// # use kube::{Api, Error};
+    /// # use std::sync::Arc;
+    /// # async fn reconcile(_: Arc<ConfigMap>, _: Arc<()>) -> Result<Action, Error> { Ok(Action::await_change()) }
+    /// # fn error_policy(_: Arc<ConfigMap>, _: &Error, _: Arc<()>) -> Action { Action::await_change() }
+    /// # async fn doc(client: kube::Client) {
+    /// // parents are all kept in the `control` namespace, and own the namespaces they create
+    /// Controller::new(Api::<ConfigMap>::namespaced(client.clone(), "control"), watcher::Config::default())
+    ///     .owns_cross_namespace(
+    ///         Api::<Namespace>::all(client),
+    ///         watcher::Config::default(),
+    ///         OwnerNamespace::fixed("control"),
+    ///     )
+    ///     .run(reconcile, error_policy, Arc::new(()))
+    ///     .for_each(|_| std::future::ready(()))
+    ///     .await;
+    /// # }
+    /// ```
+    ///
+    /// [`OwnerReference`]: k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference
+    #[must_use]
+    pub fn owns_cross_namespace<Child>(
+        self,
+        api: Api<Child>,
+        wc: watcher::Config,
+        owner_namespace: OwnerNamespace,
+    ) -> Self
+    where
+        Child: Clone + Resource<DynamicType = ()> + DeserializeOwned + Debug + Send + 'static,
+    {
+        self.owns_cross_namespace_with(api, (), wc, owner_namespace)
+    }
+
+    /// Specify `Child` objects which `K` owns, where `K` may be in another namespace than its `Child` objects
+    ///
+    /// Same as [`Controller::owns_cross_namespace`], but accepts a `DynamicType` so it can be used with dynamic resources.
+    #[must_use]
+    pub fn owns_cross_namespace_with<Child: Clone + Resource + DeserializeOwned + Debug + Send + 'static>(
+        mut self,
+        api: Api<Child>,
+        dyntype: Child::DynamicType,
+        wc: watcher::Config,
+        owner_namespace: OwnerNamespace,
+    ) -> Self
+    where
+        Child::DynamicType: Debug + Eq + Hash + Clone,
+    {
+        let child_watcher = trigger_owners_in(
+            metadata_watcher(api, wc).touched_objects(),
+            owner_namespace,
+            self.dyntype.clone(),
+            dyntype,
+        );
+        self.trigger_selector.push(child_watcher.boxed());
+        self
+    }
+
+    /// Specify `Child` objects which `K` owns, and which name their owner in labels
+    ///
+    /// Same as [`Controller::owns`], but `Child` objects are mapped to their owner by [`OwnerLabels`]
+    /// instead of an [`OwnerReference`]. This allows owning objects that cannot have an ownerReference
+    /// to `K`, such as objects in another namespace, or cluster-scoped objects of a namespaced `K`.
+    ///
+    /// When reading labels, only `Child` objects with the name label are watched.
+    ///
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use k8s_openapi::api::core::v1::{ConfigMap, Namespace};
+    /// # use kube::runtime::controller::{Action, OwnerLabels};
+    /// # use kube::runtime::{watcher, Controller};
+    /// # use kube::{Api, Error};
+    /// # use std::sync::Arc;
+    /// # async fn reconcile(_: Arc<ConfigMap>, _: Arc<()>) -> Result<Action, Error> { Ok(Action::await_change()) }
+    /// # fn error_policy(_: Arc<ConfigMap>, _: &Error, _: Arc<()>) -> Action { Action::await_change() }
+    /// # async fn doc(client: kube::Client) {
+    /// // namespaces created for a ConfigMap carry its name and namespace in their labels
+    /// let owner_labels = OwnerLabels::new("example.com/owner-name")
+    ///     .namespace_from("example.com/owner-namespace");
+    /// Controller::new(Api::<ConfigMap>::all(client.clone()), watcher::Config::default())
+    ///     .owns_by_label(Api::<Namespace>::all(client), watcher::Config::default(), owner_labels)
+    ///     .run(reconcile, error_policy, Arc::new(()))
+    ///     .for_each(|_| std::future::ready(()))
+    ///     .await;
+    /// # }
+    /// ```
+    ///
+    /// [`OwnerReference`]: k8s_openapi::apimachinery::pkg::apis::meta::v1::OwnerReference
+    #[must_use]
+    pub fn owns_by_label<Child>(self, api: Api<Child>, wc: watcher::Config, owner_labels: OwnerLabels) -> Self
+    where
+        Child: Clone + Resource<DynamicType = ()> + DeserializeOwned + Debug + Send + 'static,
+    {
+        self.owns_by_label_with(api, (), wc, owner_labels)
+    }
+
+    /// Specify `Child` objects which `K` owns, and which name their owner in labels
+    ///
+    /// Same as [`Controller::owns_by_label`], but accepts a `DynamicType` so it can be used with dynamic resources.
+    #[must_use]
+    pub fn owns_by_label_with<Child: Clone + Resource + DeserializeOwned + Debug + Send + 'static>(
+        mut self,
+        api: Api<Child>,
+        dyntype: Child::DynamicType,
+        mut wc: watcher::Config,
+        owner_labels: OwnerLabels,
+    ) -> Self
+    where
+        Child::DynamicType: Debug + Eq + Hash + Clone,
+    {
+        if let Some(name_key) = owner_labels.selector() {
+            wc.label_selector = Some(match wc.label_selector {
+                Some(selector) => format!("{selector},{name_key}"),
+                None => name_key.to_string(),
+            });
+        }
+        let child_watcher = trigger_owners_by_label(
+            metadata_watcher(api, wc).touched_objects(),
+            owner_labels,
+            self.dyntype.clone(),
+            dyntype,
+        );
+        self.trigger_selector.push(child_watcher.boxed());
+        self
+    }
+
     /// Trigger the reconciliation process for a stream of `Child` objects of the owner `K`
     ///
     /// Same as [`Controller::owns`], but instead of an `Api`, a stream of resources is used.
@@ -1035,26 +1870,6 @@ where
         self
     }
 
-    /// This is the same as [`Controller::for_stream`]. Instead of taking an
-    /// `Api` (e.g. [`Controller::new`]), a stream of resources is used. Shared
-    /// streams can be created out-of-band by subscribing on a store `Writer`.
-    /// Through this interface, multiple controllers can use the same root
-    /// (shared) input stream of resources to keep memory overheads smaller.
-    ///
-    /// **N.B**: This constructor requires an
-    /// [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21)
-    /// feature.
-    ///
-    /// Prefer [`Controller::new`] or [`Controller::for_stream`] if you do not
-    /// need to share the stream.
-    ///
-    /// ## Warning:
-    ///
-    /// You **must** ensure the root stream (i.e. stream created through a `reflector()`)
-    /// is driven to readiness independently of this controller to ensure the
-    /// watcher never deadlocks.
-    ///
-    ///
     /// Trigger the reconciliation process for a shared stream of `Child`
     /// objects of the owner `K`
     ///
@@ -1062,8 +1877,6 @@ where
     /// instead of an `Api`. This interface behaves similarly to its non-shared
     /// counterpart [`Controller::owns_stream`].
     ///
-    /// **NB**: This is constructor requires an [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21) feature.
-    ///
     /// # Example:
     ///
     /// ```no_run
@@ -1107,7 +1920,6 @@ where
     ///   _ = controller => {},
     /// }
     /// # }
-    #[cfg(feature = "unstable-runtime-subscribe")]
     #[must_use]
     pub fn owns_shared_stream<Child: Resource<DynamicType = ()> + Send + 'static>(
         self,
@@ -1122,10 +1934,7 @@ where
     /// The source stream can be shared between multiple controllers, optimising
     /// resource usage.
     ///
-    /// **NB**: This is constructor requires an [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21) feature.
-    ///
     /// Same as [`Controller::owns_shared_stream`], but accepts a `DynamicType` so it can be used with dynamic resources.
-    #[cfg(feature = "unstable-runtime-subscribe")]
     #[must_use]
     pub fn owns_shared_stream_with<Child: Resource<DynamicType = ()> + Send + 'static>(
         mut self,
@@ -1242,6 +2051,63 @@ where
         self
     }
 
+    /// Specify `Watched` objects in another cluster which `K` has a custom relation to and should be watched
+    ///
+    /// Same as [`Controller::watches`], but the `api` is typically created from the [`Client`](kube_client::Client)
+    /// of another cluster, such as one from a [`ClientPool`](kube_client::client::ClientPool).
+    /// The requests are tagged with the `cluster` name, which the reconciler can read with [`reconcile_cluster`].
+    ///
+    /// Ownership cannot be tracked across clusters, since the garbage collector of the other cluster
+    /// would delete objects whose owners it cannot find, so the `mapper` has to relate the objects
+    /// some other way, such as with labels.
+    ///
+    /// ```no_run
+    /// # use futures::StreamExt;
+    /// # use k8s_openapi::api::core::v1::ConfigMap;
+    /// # use kube::runtime::controller::{reconcile_cluster, Action};
+    /// # use kube::runtime::{reflector::ObjectRef, watcher, Controller};
+    /// # use kube::{Api, Client, Error, ResourceExt};
+    /// # use std::sync::Arc;
+    /// # type Fleet = ConfigMap;
+    /// async fn reconcile(fleet: Arc<Fleet>, _: Arc<()>) -> Result<Action, Error> {
+    ///     if let Some(cluster) = reconcile_cluster() {
+    ///         println!("{} triggered by a change in {cluster}", fleet.name_any());
+    ///     }
+    ///     Ok(Action::await_change())
+    /// }
+    /// # fn error_policy(_: Arc<Fleet>, _: &Error, _: Arc<()>) -> Action { Action::await_change() }
+    /// # async fn doc(hub: Client, east: Client) {
+    /// Controller::new(Api::<Fleet>::all(hub), watcher::Config::default())
+    ///     .watches_in_cluster("east", Api::<ConfigMap>::all(east), watcher::Config::default(), |cm| {
+    ///         let fleet = cm.labels().get("example.com/fleet")?;
+    ///         Some(ObjectRef::new(fleet).within("fleets"))
+    ///     })
+    ///     .run(reconcile, error_policy, Arc::new(()))
+    ///     .for_each(|_| futures::future::ready(()))
+    ///     .await;
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn watches_in_cluster<Other, I>(
+        mut self,
+        cluster: &str,
+        api: Api<Other>,
+        wc: watcher::Config,
+        mapper: impl Fn(Other) -> I + Sync + Send + 'static,
+    ) -> Self
+    where
+        Other: Clone + Resource + DeserializeOwned + Debug + Send + 'static,
+        Other::DynamicType: Default + Debug + Clone + Eq + Hash,
+        I: 'static + IntoIterator<Item = ObjectRef<K>>,
+        I::IntoIter: Send,
+    {
+        let cluster = cluster.to_string();
+        let other_watcher = trigger_others(watcher(api, wc).touched_objects(), mapper, Default::default())
+            .map_ok(move |request| request.with_cluster(cluster.clone()));
+        self.trigger_selector.push(other_watcher.boxed());
+        self
+    }
+
     /// Trigger the reconciliation process for a stream of `Other` objects related to a `K`
     ///
     /// Same as [`Controller::watches`], but instead of an `Api`, a stream of resources is used.
@@ -1331,8 +2197,6 @@ where
     /// stream of resources is used. This allows for sharing input streams
     /// between multiple controllers.
     ///
-    /// **NB**: This is constructor requires an [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21) feature.
-    ///
     /// Watcher streams passed in here should be filtered first through `touched_objects`.
     ///
     /// # Example:
@@ -1378,7 +2242,6 @@ where
     /// }
     /// # }
     /// ```
-    #[cfg(feature = "unstable-runtime-subscribe")]
     #[must_use]
     pub fn watches_shared_stream<Other, I>(
         self,
@@ -1400,10 +2263,7 @@ where
     /// stream of resources is used. This allows for sharing of streams between
     /// multiple controllers.
     ///
-    /// **NB**: This is constructor requires an [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21) feature.
-    ///
     /// Same as [`Controller::watches_shared_stream`], but accepts a `DynamicType` so it can be used with dynamic resources.
-    #[cfg(feature = "unstable-runtime-subscribe")]
     #[must_use]
     pub fn watches_shared_stream_with<Other, I>(
         mut self,
@@ -1480,6 +2340,8 @@ where
                         Ok(ReconcileRequest {
                             obj_ref: ObjectRef::from_obj_with(&*obj, dyntype.clone()),
                             reason: ReconcileReason::BulkReconcile,
+                            priority: 0,
+                            cluster: None,
                         })
                     }))
                 })
@@ -1535,6 +2397,8 @@ where
                     Ok(ReconcileRequest {
                         obj_ref: obj,
                         reason: ReconcileReason::Unknown,
+                        priority: 0,
+                        cluster: None,
                     })
                 })
                 .boxed(),
@@ -1657,17 +2521,40 @@ where
         ReconcilerFut: TryFuture<Ok = Action> + Send + 'static,
         ReconcilerFut::Error: std::error::Error + Send + 'static,
     {
+        let (reader, ready_tx) = (self.reader.clone(), self.ready_tx);
+        Handle::current().spawn(async move {
+            if reader.wait_until_ready().await.is_ok() {
+                let _ = ready_tx.send(true);
+            }
+        });
+        let health = self.health;
+        let trigger_selector = self
+            .trigger_selector
+            .inspect(move |request| health.observe_trigger(request.is_ok()));
+        let (on_success, on_error) = (self.on_success, self.on_error);
         applier(
             move |obj, ctx| {
-                CancelableJoinHandle::spawn(
-                    reconciler(obj, ctx).into_future().in_current_span(),
-                    &Handle::current(),
-                )
+                let on_success = on_success.clone();
+                let reconciliation =
+                    reconciler(Arc::clone(&obj), ctx)
+                        .into_future()
+                        .inspect_ok(move |action| {
+                            if let Some(on_success) = on_success {
+                                on_success(&obj, action);
+                            }
+                        });
+                CancelableJoinHandle::spawn(reconciliation.in_current_span(), &Handle::current())
+            },
+            move |obj, err, ctx| {
+                let action = error_policy(Arc::clone(&obj), err, ctx);
+                if let Some(on_error) = &on_error {
+                    on_error(&obj, err, &action);
+                }
+                action
             },
-            error_policy,
             context,
             self.reader,
-            StreamBackoff::new(self.trigger_selector, self.trigger_backoff)
+            StreamBackoff::new(trigger_selector, self.trigger_backoff)
                 .take_until(future::select_all(self.graceful_shutdown_selector)),
             self.config,
         )
@@ -1679,16 +2566,21 @@ where
 mod tests {
     use std::{convert::Infallible, pin::pin, sync::Arc, time::Duration};
 
-    use super::{Action, APPLIER_REQUEUE_BUF_SIZE};
+    use super::{
+        reconcile_cluster, Action, Error, ErrorEvents, OwnerLabels, OwnerNamespace, ReconcileReason,
+        ReconcileRequest, APPLIER_REQUEUE_BUF_SIZE, MAX_PENDING_ERROR_EVENTS,
+    };
     use crate::{
         applier,
+        events::Recorder,
         reflector::{self, ObjectRef},
         watcher::{self, metadata_watcher, watcher, Event},
         Config, Controller,
     };
     use futures::{Stream, StreamExt, TryStreamExt};
-    use k8s_openapi::api::core::v1::ConfigMap;
-    use kube_client::{core::ObjectMeta, Api, Resource};
+    use k8s_openapi::api::{core::v1::ConfigMap, events::v1::Event as K8sEvent};
+    use kube::client::fake::FakeApiServer;
+    use kube_client::{core::ObjectMeta, Api, Resource, ResourceExt};
     use serde::de::DeserializeOwned;
     use tokio::time::timeout;
 
@@ -1800,4 +2692,335 @@ mod tests {
         .expect("applier cleanup timeout expired, individual reconciler likely deadlocked?")
         .unwrap();
     }
+
+    #[test]
+    fn owner_labels_name_owners() {
+        let child = ObjectMeta {
+            name: Some("child".into()),
+            namespace: Some("apps".into()),
+            labels: Some(
+                [
+                    ("example.com/owner-name".to_string(), "parent".to_string()),
+                    ("example.com/owner-namespace".to_string(), "control".to_string()),
+                ]
+                .into(),
+            ),
+            ..ObjectMeta::default()
+        };
+        let owner_labels = OwnerLabels::new("example.com/owner-name");
+        assert_eq!(
+            owner_labels.owner_ref::<ConfigMap>(&child, ()),
+            Some(ObjectRef::new("parent").within("apps"))
+        );
+        assert_eq!(
+            owner_labels
+                .clone()
+                .namespace_from("example.com/owner-namespace")
+                .owner_ref::<ConfigMap>(&child, ()),
+            Some(ObjectRef::new("parent").within("control"))
+        );
+        assert_eq!(
+            owner_labels
+                .clone()
+                .cluster_scoped()
+                .owner_ref::<ConfigMap>(&child, ()),
+            Some(ObjectRef::new("parent"))
+        );
+        assert_eq!(
+            owner_labels.from_annotations().owner_ref::<ConfigMap>(&child, ()),
+            None
+        );
+    }
+
+    #[test]
+    fn owner_namespace_resolves_namespaces() {
+        let child = ObjectMeta {
+            name: Some("child".into()),
+            namespace: Some("apps".into()),
+            annotations: Some([("example.com/owner-namespace".to_string(), "control".to_string())].into()),
+            ..ObjectMeta::default()
+        };
+        let unannotated = ObjectMeta {
+            annotations: None,
+            ..child.clone()
+        };
+        let from_annotation = OwnerNamespace::from_annotation("example.com/owner-namespace");
+        assert_eq!((from_annotation.0)(&child).as_deref(), Some("control"));
+        assert_eq!((from_annotation.0)(&unannotated).as_deref(), Some("apps"));
+        assert_eq!(
+            (OwnerNamespace::fixed("control").0)(&unannotated).as_deref(),
+            Some("control")
+        );
+    }
+
+    #[tokio::test]
+    async fn applier_must_skip_unchanged_generations() {
+        async fn next_reconciled<S, E>(applier: &mut S) -> String
+        where
+            S: Stream<Item = Result<(ObjectRef<ConfigMap>, Action), E>> + Unpin,
+            E: std::fmt::Debug,
+        {
+            let (obj_ref, _) = timeout(Duration::from_secs(10), applier.next())
+                .await
+                .expect("test timeout expired")
+                .unwrap()
+                .unwrap();
+            obj_ref.name
+        }
+
+        let (queue_tx, queue_rx) = futures::channel::mpsc::unbounded::<ReconcileRequest<ConfigMap>>();
+        let (store_rx, mut store_tx) = reflector::store();
+        let mut applier = pin!(applier(
+            |_obj, _| Box::pin(async move { Ok::<_, Infallible>(Action::await_change()) }),
+            |_: Arc<ConfigMap>, _: &Infallible, _| todo!(),
+            Arc::new(()),
+            store_rx,
+            queue_rx.map(Result::<_, Infallible>::Ok),
+            Config::default().skip_unchanged_generation(),
+        ));
+        let object = |name: &str, generation: Option<i64>| ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("default".to_string()),
+                generation,
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        let updated = |obj: &ConfigMap| ReconcileRequest {
+            obj_ref: ObjectRef::from_obj(obj),
+            reason: ReconcileReason::ObjectUpdated,
+            priority: 0,
+            cluster: None,
+        };
+        store_tx.apply_watcher_event(&watcher::Event::InitDone);
+
+        let generation_1 = object("cm", Some(1));
+        store_tx.apply_watcher_event(&watcher::Event::Apply(generation_1.clone()));
+        queue_tx.unbounded_send(updated(&generation_1)).unwrap();
+        assert_eq!(next_reconciled(&mut applier).await, "cm");
+
+        // status changes keep the generation, so only the object without generation is reconciled
+        let without_generation = object("other", None);
+        store_tx.apply_watcher_event(&watcher::Event::Apply(without_generation.clone()));
+        queue_tx.unbounded_send(updated(&generation_1)).unwrap();
+        queue_tx.unbounded_send(updated(&without_generation)).unwrap();
+        assert_eq!(next_reconciled(&mut applier).await, "other");
+
+        let generation_2 = object("cm", Some(2));
+        store_tx.apply_watcher_event(&watcher::Event::Apply(generation_2.clone()));
+        queue_tx.unbounded_send(updated(&generation_2)).unwrap();
+        assert_eq!(next_reconciled(&mut applier).await, "cm");
+    }
+
+    #[tokio::test]
+    async fn reconcilers_see_the_cluster_of_their_trigger() {
+        let (queue_tx, queue_rx) = futures::channel::mpsc::unbounded::<ReconcileRequest<ConfigMap>>();
+        let (clusters_tx, mut clusters_rx) = futures::channel::mpsc::unbounded();
+        let (store_rx, mut store_tx) = reflector::store();
+        let mut applier = pin!(applier(
+            move |_obj, _| {
+                let clusters_tx = clusters_tx.clone();
+                Box::pin(async move {
+                    clusters_tx.unbounded_send(reconcile_cluster()).unwrap();
+                    Ok::<_, Infallible>(Action::await_change())
+                })
+            },
+            |_: Arc<ConfigMap>, _: &Infallible, _| todo!(),
+            Arc::new(()),
+            store_rx,
+            queue_rx.map(Result::<_, Infallible>::Ok),
+            Config::default(),
+        ));
+        let cm = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("cm".to_string()),
+                namespace: Some("default".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        store_tx.apply_watcher_event(&watcher::Event::InitDone);
+        store_tx.apply_watcher_event(&watcher::Event::Apply(cm.clone()));
+
+        queue_tx
+            .unbounded_send(ReconcileRequest::from(ObjectRef::from_obj(&cm)).with_cluster("east"))
+            .unwrap();
+        timeout(Duration::from_secs(10), applier.next()).await.unwrap();
+        queue_tx.unbounded_send(ObjectRef::from_obj(&cm).into()).unwrap();
+        timeout(Duration::from_secs(10), applier.next()).await.unwrap();
+
+        assert_eq!(clusters_rx.next().await, Some(Some("east".to_string())));
+        assert_eq!(clusters_rx.next().await, Some(None));
+        assert_eq!(reconcile_cluster(), None);
+    }
+
+    fn named_cm(name: &str) -> ConfigMap {
+        ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("default".to_string()),
+                ..Default::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Waits for all error events that are being published to be written
+    async fn published_events(events: &ErrorEvents<ConfigMap>, api: &Api<K8sEvent>) -> Vec<K8sEvent> {
+        #[allow(clippy::cast_possible_truncation)]
+        let _permits = events
+            .pending
+            .acquire_many(MAX_PENDING_ERROR_EVENTS as u32)
+            .await
+            .unwrap();
+        api.list(&Default::default()).await.unwrap().items
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn error_events_are_rate_limited_per_object() {
+        let client = FakeApiServer::new().with_resource::<K8sEvent>().client();
+        let api = Api::<K8sEvent>::namespaced(client.clone(), "default");
+        let events =
+            ErrorEvents::<ConfigMap>::new(Recorder::new(client, "kube".into()), Duration::from_secs(60), ());
+        let error = std::io::Error::other("boom");
+        let (first, second) = (named_cm("first"), named_cm("second"));
+
+        events.publish(&first, &error, &Action::await_change());
+        events.publish(&first, &error, &Action::await_change());
+        events.publish(&second, &error, &Action::await_change());
+        let published = published_events(&events, &api).await;
+        assert_eq!(published.len(), 2);
+        assert!(published.iter().all(|event| event.series.is_none()));
+
+        tokio::time::advance(Duration::from_secs(61)).await;
+        events.publish(&first, &error, &Action::await_change());
+        let published = published_events(&events, &api).await;
+        let counts = published
+            .iter()
+            .map(|event| event.series.as_ref().map(|series| series.count))
+            .collect::<Vec<_>>();
+        assert_eq!(counts.len(), 2);
+        assert!(counts.contains(&Some(2)));
+    }
+
+    #[tokio::test]
+    async fn error_events_report_failures_and_give_ups() {
+        let client = FakeApiServer::new().with_resource::<K8sEvent>().client();
+        let api = Api::<K8sEvent>::namespaced(client.clone(), "default");
+        let events =
+            ErrorEvents::<ConfigMap>::new(Recorder::new(client, "kube".into()), Duration::from_secs(60), ());
+        let error = std::io::Error::other("boom");
+
+        events.publish(
+            &named_cm("failed"),
+            &error,
+            &Action::requeue(Duration::from_secs(5)),
+        );
+        events.publish(&named_cm("gave-up"), &error, &Action::give_up("too many retries"));
+        let published = published_events(&events, &api).await;
+        let reasons = published
+            .iter()
+            .map(|event| {
+                let name = event.regarding.as_ref().and_then(|r| r.name.clone()).unwrap();
+                (name, (event.reason.clone().unwrap(), event.note.clone().unwrap()))
+            })
+            .collect::<std::collections::BTreeMap<_, _>>();
+        assert_eq!(
+            reasons["failed"],
+            ("ReconcileFailed".to_string(), "boom".to_string())
+        );
+        assert_eq!(
+            reasons["gave-up"],
+            (
+                "ReconcileGaveUp".to_string(),
+                "too many retries: boom".to_string()
+            )
+        );
+        assert!(published
+            .iter()
+            .all(|event| event.type_.as_deref() == Some("Warning")));
+    }
+
+    #[tokio::test]
+    async fn reconcile_hooks_fire_once_with_the_action() {
+        let client = FakeApiServer::new().with_resource::<ConfigMap>().client();
+        let api = Api::<ConfigMap>::namespaced(client.clone(), "default");
+        for name in ["ok", "fail", "give-up"] {
+            api.create(&Default::default(), &named_cm(name)).await.unwrap();
+        }
+        let calls = Arc::new(parking_lot::Mutex::new(Vec::new()));
+        let (success_calls, error_calls) = (calls.clone(), calls.clone());
+        let controller = Controller::new(Api::<ConfigMap>::all(client), watcher::Config::default())
+            .on_reconcile_success(move |cm, action| {
+                success_calls
+                    .lock()
+                    .push(("success", cm.name_any(), action.clone()));
+            })
+            .on_reconcile_error(move |cm, _, action| {
+                error_calls.lock().push(("error", cm.name_any(), action.clone()));
+            });
+        let requeue = Action::requeue(Duration::from_secs(300));
+        let mut reconciled = pin!(controller.run(
+            |cm, _| async move {
+                match cm.name_any().as_str() {
+                    "ok" => Ok(Action::requeue(Duration::from_secs(300))),
+                    _ => Err(std::io::Error::other("boom")),
+                }
+            },
+            |cm, _, _| match cm.name_any().as_str() {
+                "give-up" => Action::give_up("invalid spec"),
+                _ => Action::requeue(Duration::from_secs(300)),
+            },
+            Arc::new(()),
+        ));
+        for _ in 0..3 {
+            let result = timeout(Duration::from_secs(10), reconciled.next())
+                .await
+                .expect("test timeout expired")
+                .expect("controller stream ended");
+            assert!(matches!(result, Ok(_) | Err(Error::ReconcilerFailed(..))));
+        }
+
+        let mut calls = calls.lock().clone();
+        calls.sort_by(|a, b| (a.0, &a.1).cmp(&(b.0, &b.1)));
+        assert_eq!(calls, [
+            ("error", "fail".to_string(), requeue.clone()),
+            ("error", "give-up".to_string(), Action::give_up("invalid spec")),
+            ("success", "ok".to_string(), requeue),
+        ]);
+    }
+
+    #[tokio::test(start_paused = true)]
+    async fn applier_must_not_requeue_given_up_objects() {
+        let (queue_tx, queue_rx) = futures::channel::mpsc::unbounded::<ReconcileRequest<ConfigMap>>();
+        let (store_rx, mut store_tx) = reflector::store();
+        let mut applier = pin!(applier(
+            |_obj, _| Box::pin(async move { Err::<Action, _>(std::io::Error::other("boom")) }),
+            |cm: Arc<ConfigMap>, _: &std::io::Error, _| match cm.name_any().as_str() {
+                "give-up" => Action::give_up("invalid spec"),
+                _ => Action::requeue(Duration::from_secs(1)),
+            },
+            Arc::new(()),
+            store_rx,
+            queue_rx.map(Result::<_, Infallible>::Ok),
+            Config::default(),
+        ));
+        store_tx.apply_watcher_event(&watcher::Event::InitDone);
+        for name in ["give-up", "retry"] {
+            let cm = named_cm(name);
+            store_tx.apply_watcher_event(&watcher::Event::Apply(cm.clone()));
+            queue_tx.unbounded_send(ObjectRef::from_obj(&cm).into()).unwrap();
+        }
+
+        let mut failed = Vec::new();
+        for _ in 0..5 {
+            match timeout(Duration::from_secs(60), applier.next()).await {
+                Ok(Some(Err(Error::ReconcilerFailed(_, obj_ref)))) => failed.push(obj_ref.name),
+                other => panic!("expected a failed reconciliation, got {other:?}"),
+            }
+        }
+        failed.sort();
+        assert_eq!(failed, ["give-up", "retry", "retry", "retry", "retry"]);
+    }
 }
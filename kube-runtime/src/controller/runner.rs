@@ -1,4 +1,5 @@
 use super::future_hash_map::FutureHashMap;
+#[cfg(feature = "metrics")] use crate::metrics::Metrics;
 use crate::scheduler::{ScheduleRequest, Scheduler};
 use futures::{FutureExt, Stream, StreamExt};
 use pin_project::pin_project;
@@ -34,6 +35,8 @@ pub struct Runner<T, R, F, MkF, Ready = future::Ready<Result<(), Infallible>>> {
     is_ready_to_execute: bool,
     stopped: bool,
     max_concurrent_executions: u16,
+    #[cfg(feature = "metrics")]
+    metrics: Option<Metrics>,
 }
 
 impl<T, R, F, MkF> Runner<T, R, F, MkF>
@@ -53,9 +56,19 @@ where
             is_ready_to_execute: false,
             stopped: false,
             max_concurrent_executions,
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
 
+    /// Records the number of running tasks into `metrics`
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
+
     /// Wait for `ready_to_execute_after` to complete before starting to run any scheduled tasks.
     ///
     /// `scheduler` will still be polled in the meantime.
@@ -74,6 +87,8 @@ where
             is_ready_to_execute: false,
             stopped: false,
             max_concurrent_executions: self.max_concurrent_executions,
+            #[cfg(feature = "metrics")]
+            metrics: self.metrics,
         }
     }
 }
@@ -96,8 +111,16 @@ where
         }
         let slots = this.slots;
         let scheduler = &mut this.scheduler;
+        #[cfg(feature = "metrics")]
+        let metrics = this.metrics.as_ref();
         let has_active_slots = match slots.poll_next_unpin(cx) {
-            Poll::Ready(Some(result)) => return Poll::Ready(Some(Ok(result))),
+            Poll::Ready(Some(result)) => {
+                #[cfg(feature = "metrics")]
+                if let Some(metrics) = metrics {
+                    metrics.set_active_reconciliations(slots.len());
+                }
+                return Poll::Ready(Some(Ok(result)));
+            }
             Poll::Ready(None) => false,
             Poll::Pending => true,
         };
@@ -138,6 +161,10 @@ where
                         slots.insert(msg, msg_fut).is_none(),
                         "Runner tried to replace a running future.. please report this as a kube-rs bug!"
                     );
+                    #[cfg(feature = "metrics")]
+                    if let Some(metrics) = metrics {
+                        metrics.set_active_reconciliations(slots.len());
+                    }
                     cx.waker().wake_by_ref();
                 }
                 Poll::Ready(None) => {
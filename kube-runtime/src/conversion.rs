@@ -0,0 +1,276 @@
+//! Dispatches CRD conversion webhook requests to typed converters
+//!
+//! A [`ConversionHandler`] holds one converter per (source, destination) version pair, and uses them
+//! to answer [`ConversionReview`]s sent by the apiserver for custom resources with multiple versions.
+//!
+//! ```no_run
+//! use kube::{core::conversion::ConversionReview, runtime::conversion::ConversionHandler};
+//! use std::convert::Infallible;
+//!
+//! mod v1 {
+//!     # use kube::CustomResource;
+//!     # use schemars::JsonSchema;
+//!     # use serde::{Deserialize, Serialize};
+//!     #[derive(CustomResource, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+//!     #[kube(group = "example.com", version = "v1", kind = "Foo", namespaced)]
+//!     pub struct FooSpec { pub name: String }
+//! }
+//! mod v2 {
+//!     # use kube::CustomResource;
+//!     # use schemars::JsonSchema;
+//!     # use serde::{Deserialize, Serialize};
+//!     #[derive(CustomResource, Serialize, Deserialize, Clone, Debug, JsonSchema)]
+//!     #[kube(group = "example.com", version = "v2", kind = "Foo", namespaced)]
+//!     pub struct FooSpec { pub names: Vec<String> }
+//! }
+//!
+//! let handler = ConversionHandler::new()
+//!     .converter(|old: v1::Foo| {
+//!         let spec = v2::FooSpec { names: vec![old.spec.name] };
+//!         Ok::<_, Infallible>(v2::Foo { metadata: old.metadata, spec })
+//!     })
+//!     .converter(|new: v2::Foo| {
+//!         let spec = v1::FooSpec { name: new.spec.names.join(",") };
+//!         Ok::<_, Infallible>(v1::Foo { metadata: new.metadata, spec })
+//!     });
+//! # let review: ConversionReview = todo!();
+//! let response: ConversionReview = handler.review(review);
+//! ```
+//!
+//! With the `admission-server` feature enabled, the handler can be served with `WebhookServer::convert`
+//! from the `admission` module.
+use kube_client::{
+    core::{
+        conversion::{ConversionRequest, ConversionResponse, ConversionReview},
+        Status,
+    },
+    Resource,
+};
+use serde::{de::DeserializeOwned, Serialize};
+use std::{collections::HashMap, fmt::Display};
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("object has no apiVersion")]
+    MissingApiVersion,
+    #[error("no converter registered from {from} to {to}")]
+    NoConverter { from: String, to: String },
+    #[error("failed to deserialize {0}: {1}")]
+    Deserialize(String, #[source] serde_json::Error),
+    #[error("failed to serialize {0}: {1}")]
+    Serialize(String, #[source] serde_json::Error),
+    #[error("failed to convert from {from} to {to}: {message}")]
+    ConversionFailed {
+        from: String,
+        to: String,
+        message: String,
+    },
+}
+
+type Converter = Box<dyn Fn(serde_json::Value) -> Result<serde_json::Value, Error> + Send + Sync>;
+
+/// Converts objects between the versions of a custom resource
+///
+/// Converters are registered per version pair with [`ConversionHandler::converter`], using the
+/// [`Resource::api_version`] of each type. Objects that are already in the desired version are passed through
+/// as-is, and every other conversion must have been registered explicitly.
+#[derive(Default)]
+#[allow(clippy::module_name_repetitions)]
+pub struct ConversionHandler {
+    converters: HashMap<(String, String), Converter>,
+}
+
+impl ConversionHandler {
+    /// Creates a handler without any converters
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers a converter from `Old` to `New`
+    ///
+    /// Registering another converter for the same version pair replaces the previous one.
+    #[must_use]
+    pub fn converter<Old, New, E>(
+        mut self,
+        converter: impl Fn(Old) -> Result<New, E> + Send + Sync + 'static,
+    ) -> Self
+    where
+        Old: Resource<DynamicType = ()> + DeserializeOwned,
+        New: Resource<DynamicType = ()> + Serialize,
+        E: Display,
+    {
+        let from = Old::api_version(&()).into_owned();
+        let to = New::api_version(&()).into_owned();
+        let key = (from.clone(), to.clone());
+        let converter: Converter = Box::new(move |obj| {
+            let old: Old =
+                serde_json::from_value(obj).map_err(|err| Error::Deserialize(from.clone(), err))?;
+            let new = converter(old).map_err(|err| Error::ConversionFailed {
+                from: from.clone(),
+                to: to.clone(),
+                message: err.to_string(),
+            })?;
+            let mut obj = serde_json::to_value(new).map_err(|err| Error::Serialize(to.clone(), err))?;
+            // Typed objects do not always serialize their TypeMeta, but the apiserver requires it
+            if let Some(obj) = obj.as_object_mut() {
+                obj.insert("apiVersion".into(), New::api_version(&()).into());
+                obj.insert("kind".into(), New::kind(&()).into());
+            }
+            Ok(obj)
+        });
+        self.converters.insert(key, converter);
+        self
+    }
+
+    /// Converts a single object into `desired_api_version`
+    ///
+    /// # Errors
+    ///
+    /// Fails if no converter has been registered for the object's version, or if the conversion itself fails.
+    pub fn convert(
+        &self,
+        obj: serde_json::Value,
+        desired_api_version: &str,
+    ) -> Result<serde_json::Value, Error> {
+        let from = obj
+            .get("apiVersion")
+            .and_then(serde_json::Value::as_str)
+            .ok_or(Error::MissingApiVersion)?;
+        if from == desired_api_version {
+            return Ok(obj);
+        }
+        let key = (from.to_string(), desired_api_version.to_string());
+        let converter = self.converters.get(&key).ok_or_else(|| Error::NoConverter {
+            from: key.0.clone(),
+            to: key.1.clone(),
+        })?;
+        converter(obj)
+    }
+
+    /// Converts all objects in a [`ConversionRequest`]
+    ///
+    /// The whole request fails if any of its objects fail to convert.
+    #[must_use]
+    pub fn handle(&self, req: ConversionRequest) -> ConversionResponse {
+        let converted = req
+            .objects
+            .iter()
+            .map(|obj| self.convert(obj.clone(), &req.desired_api_version))
+            .collect::<Result<Vec<_>, _>>();
+        let res = ConversionResponse::for_request(req);
+        match converted {
+            Ok(objects) => res.success(objects),
+            Err(err) => {
+                tracing::warn!(error = %err, "conversion failed");
+                res.failure(Status::failure(&err.to_string(), "ConversionFailed"))
+            }
+        }
+    }
+
+    /// Answers a [`ConversionReview`] sent by the apiserver
+    #[must_use]
+    pub fn review(&self, review: ConversionReview) -> ConversionReview {
+        match ConversionRequest::from_review(review) {
+            Ok(req) => self.handle(req).into_review(),
+            Err(err) => {
+                ConversionResponse::invalid(Status::failure(&err.to_string(), "InvalidRequest")).into_review()
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ConversionHandler;
+    use kube::CustomResource;
+    use kube_client::core::conversion::{ConversionRequest, ConversionReview};
+    use schemars::JsonSchema;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    mod v1 {
+        use super::*;
+        #[derive(CustomResource, Deserialize, Serialize, Clone, Debug, JsonSchema)]
+        #[kube(group = "example.com", version = "v1", kind = "Foo", namespaced)]
+        pub struct FooSpec {
+            pub name: String,
+        }
+    }
+
+    mod v2 {
+        use super::*;
+        #[derive(CustomResource, Deserialize, Serialize, Clone, Debug, JsonSchema)]
+        #[kube(group = "example.com", version = "v2", kind = "Foo", namespaced)]
+        pub struct FooSpec {
+            pub names: Vec<String>,
+        }
+    }
+
+    fn handler() -> ConversionHandler {
+        ConversionHandler::new().converter(|old: v1::Foo| {
+            if old.spec.name.is_empty() {
+                return Err("name must not be empty");
+            }
+            Ok(v2::Foo {
+                metadata: old.metadata,
+                spec: v2::FooSpec {
+                    names: vec![old.spec.name],
+                },
+            })
+        })
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn request(objects: Vec<serde_json::Value>) -> ConversionRequest {
+        let review: ConversionReview = serde_json::from_value(json!({
+            "apiVersion": "apiextensions.k8s.io/v1",
+            "kind": "ConversionReview",
+            "request": {
+                "uid": "705ab4f5-6393-11e8-b7cc-42010a800002",
+                "desiredAPIVersion": "example.com/v2",
+                "objects": objects,
+            }
+        }))
+        .unwrap();
+        ConversionRequest::from_review(review).unwrap()
+    }
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn foo(version: &str, spec: serde_json::Value) -> serde_json::Value {
+        json!({
+            "apiVersion": format!("example.com/{version}"),
+            "kind": "Foo",
+            "metadata": { "name": "foo", "namespace": "default" },
+            "spec": spec,
+        })
+    }
+
+    #[test]
+    fn converts_registered_versions_and_passes_through_desired() {
+        let res = handler().handle(request(vec![
+            foo("v1", json!({ "name": "a" })),
+            foo("v2", json!({ "names": ["b"] })),
+        ]));
+        assert_eq!(res.uid, "705ab4f5-6393-11e8-b7cc-42010a800002");
+        assert_eq!(res.converted_objects, vec![
+            foo("v2", json!({ "names": ["a"] })),
+            foo("v2", json!({ "names": ["b"] })),
+        ]);
+    }
+
+    #[test]
+    fn fails_whole_request_on_any_failure() {
+        let res = handler().handle(request(vec![
+            foo("v1", json!({ "name": "a" })),
+            foo("v1", json!({ "name": "" })),
+        ]));
+        assert!(res.converted_objects.is_empty());
+        assert!(res.result.message.contains("name must not be empty"));
+
+        let res = handler().handle(request(vec![foo("v3", json!({}))]));
+        assert_eq!(res.result.reason, "ConversionFailed");
+        assert!(res.result.message.contains("no converter registered"));
+    }
+}
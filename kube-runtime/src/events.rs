@@ -3,6 +3,7 @@ use std::{
     collections::HashMap,
     hash::{Hash, Hasher},
     sync::Arc,
+    time::Duration as StdDuration,
 };
 
 use k8s_openapi::{
@@ -11,7 +12,7 @@ use k8s_openapi::{
         events::v1::{Event as K8sEvent, EventSeries},
     },
     apimachinery::pkg::apis::meta::v1::{MicroTime, ObjectMeta},
-    chrono::{Duration, Utc},
+    chrono::{DateTime, Duration, Utc},
 };
 use kube_client::{
     api::{Api, Patch, PatchParams, PostParams},
@@ -19,7 +20,86 @@ use kube_client::{
 };
 use tokio::sync::RwLock;
 
-const CACHE_TTL: Duration = Duration::minutes(6);
+/// Configuration for the event correlation cache of a [`Recorder`]
+///
+/// Repeated events with the same [`EventKey`](Event) (everything but the `note`) are aggregated into an
+/// [`EventSeries`] on the first published event, rather than creating a new event each time.
+#[derive(Clone, Debug)]
+pub struct CacheConfig {
+    /// How long an event is kept for aggregation after it was last observed
+    ///
+    /// Defaults to 6 minutes, as recommended for `events.k8s.io/v1`.
+    pub ttl: StdDuration,
+
+    /// Maximum number of distinct events kept in the cache
+    ///
+    /// The least recently observed events are evicted first when the cache is full.
+    pub max_entries: usize,
+
+    /// Minimum time between two updates of the same [`EventSeries`]
+    ///
+    /// Repeats within this period only bump the series count locally. They are sent by the first
+    /// [`Recorder::publish`] after the period, or by [`Recorder::flush`], and are lost if neither is called.
+    /// Defaults to zero, updating the series on every repeat.
+    pub series_update_interval: StdDuration,
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self {
+            ttl: StdDuration::from_secs(6 * 60),
+            max_entries: 4096,
+            series_update_interval: StdDuration::ZERO,
+        }
+    }
+}
+
+/// An event that has been published, along with its not-yet-published repeats
+#[derive(Clone, Debug)]
+struct CachedEvent {
+    event: K8sEvent,
+    last_published: DateTime<Utc>,
+    /// Whether the series has repeats that were not published yet
+    pending: bool,
+}
+
+impl CachedEvent {
+    fn last_observed(&self) -> Option<DateTime<Utc>> {
+        if let Some(series) = self.event.series.as_ref() {
+            Some(series.last_observed_time.0)
+        } else {
+            self.event.event_time.as_ref().map(|time| time.0)
+        }
+    }
+}
+
+fn chrono_duration(duration: StdDuration) -> Duration {
+    Duration::from_std(duration).unwrap_or(Duration::MAX)
+}
+
+/// Maximum size of the note of an event, as enforced by the apiserver
+const MAX_NOTE_BYTES: usize = 1024;
+
+/// Namespace that events about the object `regarding` are published in
+///
+/// Events live next to the object they are about, so that `kubectl describe` can find them.
+/// Cluster scoped objects have no namespace, and get their events in the `default` namespace.
+fn event_namespace(regarding: &ObjectReference) -> &str {
+    regarding
+        .namespace
+        .as_deref()
+        .filter(|namespace| !namespace.is_empty())
+        .unwrap_or("default")
+}
+
+/// Cuts `note` down to the size accepted by the apiserver, on a character boundary
+fn truncate_note(note: &str) -> String {
+    let mut end = note.len().min(MAX_NOTE_BYTES);
+    while !note.is_char_boundary(end) {
+        end -= 1;
+    }
+    note[..end].to_string()
+}
 
 /// Minimal event type for publishing through [`Recorder::publish`].
 ///
@@ -37,7 +117,7 @@ pub struct Event {
 
     /// A optional description of the status of the `action`.
     ///
-    /// This must be at most 1kB in size. Shows up in `kubectl describe` as `Message`.
+    /// This must be at most 1kB in size, longer notes are truncated. Shows up in `kubectl describe` as `Message`.
     pub note: Option<String>,
 
     /// The action that was taken (either successfully or unsuccessfully) against main object
@@ -58,6 +138,8 @@ pub struct Event {
     /// Set `secondary` to `None`, instead, if the event affects only the object whose reference
     /// you passed to [`Recorder::new`].
     ///
+    /// The `secondary` object can be in any namespace, the event is always published next to the main object.
+    ///
     /// # Naming note
     ///
     /// `secondary` is mapped to `related` in
@@ -222,7 +304,8 @@ impl From<&str> for Reporter {
 pub struct Recorder {
     client: Client,
     reporter: Reporter,
-    cache: Arc<RwLock<HashMap<EventKey, K8sEvent>>>,
+    cache: Arc<RwLock<HashMap<EventKey, CachedEvent>>>,
+    cache_config: CacheConfig,
 }
 
 impl Recorder {
@@ -238,9 +321,19 @@ impl Recorder {
             client,
             reporter,
             cache,
+            cache_config: CacheConfig::default(),
         }
     }
 
+    /// Configure how repeated events are aggregated
+    ///
+    /// Recorders that are cloned after this share both the configuration and the cache.
+    #[must_use]
+    pub fn with_cache_config(mut self, cache_config: CacheConfig) -> Self {
+        self.cache_config = cache_config;
+        self
+    }
+
     /// Builds unique event key based on reportingController, reportingInstance, regarding, reason
     ///  and note
     fn get_event_key(&self, ev: &Event, regarding: &ObjectReference) -> EventKey {
@@ -269,9 +362,9 @@ impl Recorder {
             deprecated_source: None,
             event_time: Some(MicroTime(now)),
             regarding: Some(reference.clone()),
-            note: ev.note.clone().map(Into::into),
+            note: ev.note.as_deref().map(truncate_note),
             metadata: ObjectMeta {
-                namespace: reference.namespace.clone(),
+                namespace: Some(event_namespace(reference).to_string()),
                 name: Some(format!(
                     "{}.{:x}",
                     reference.name.as_ref().unwrap_or(&self.reporter.controller),
@@ -299,75 +392,291 @@ impl Recorder {
     ///
     /// # Access control
     ///
-    /// The event object is created in the same namespace of the [`ObjectReference`].
-    /// Make sure that your controller has `create` permissions in the required namespaces
+    /// The event object is created in the same namespace of the [`ObjectReference`],
+    /// or in the `default` namespace for cluster scoped objects.
+    /// Make sure that your controller has `create` and `patch` permissions in the required namespaces
     /// for the `event` resource in the API group `events.k8s.io`.
     ///
+    /// # Series
+    ///
+    /// Repeats of an event are published as an [`EventSeries`] on the first event, as required by
+    /// `events.k8s.io/v1`. The series keeps the `note` of the first event, see [`CacheConfig`].
+    ///
     /// # Errors
     ///
     /// Returns an [`Error`](`kube_client::Error`) if the event is rejected by Kubernetes.
     pub async fn publish(&self, ev: &Event, reference: &ObjectReference) -> Result<(), kube_client::Error> {
         let now = Utc::now();
+        let ttl = chrono_duration(self.cache_config.ttl);
 
-        // gc past events older than now + CACHE_TTL
-        self.cache.write().await.retain(|_, v| {
-            if let Some(series) = v.series.as_ref() {
-                series.last_observed_time.0 + CACHE_TTL > now
-            } else if let Some(event_time) = v.event_time.as_ref() {
-                event_time.0 + CACHE_TTL > now
-            } else {
-                true
-            }
-        });
+        // gc past events older than now + ttl
+        self.cache
+            .write()
+            .await
+            .retain(|_, v| v.last_observed().map_or(true, |observed| observed + ttl > now));
 
         let key = self.get_event_key(ev, reference);
-        let event = match self.cache.read().await.get(&key) {
-            Some(e) => {
-                let count = if let Some(s) = &e.series { s.count + 1 } else { 2 };
-                let series = EventSeries {
+        let cached = self.cache.read().await.get(&key).cloned();
+        let (event, last_published) = match cached {
+            Some(CachedEvent {
+                mut event,
+                last_published,
+                ..
+            }) => {
+                let count = if let Some(s) = &event.series {
+                    s.count + 1
+                } else {
+                    2
+                };
+                event.series = Some(EventSeries {
                     count,
                     last_observed_time: MicroTime(now),
-                };
-                let mut event = e.clone();
-                event.series = Some(series);
-                event
+                });
+                // Repeated too quickly, just count it for now and send it along with the next update
+                if last_published + chrono_duration(self.cache_config.series_update_interval) > now {
+                    self.cache_event(key, CachedEvent {
+                        event,
+                        last_published,
+                        pending: true,
+                    })
+                    .await;
+                    self.flush_due(now).await;
+                    return Ok(());
+                }
+                (event, now)
             }
-            None => self.generate_event(ev, reference),
+            None => (self.generate_event(ev, reference), now),
         };
 
-        let events = Api::namespaced(
-            self.client.clone(),
-            reference.namespace.as_ref().unwrap_or(&"default".to_string()),
-        );
         if event.series.is_some() {
-            events
-                .patch(&event.name_any(), &PatchParams::default(), &Patch::Merge(&event))
-                .await?;
+            self.publish_series(&event).await?;
         } else {
+            let events = Api::<K8sEvent>::namespaced(self.client.clone(), event_namespace(reference));
             events.create(&PostParams::default(), &event).await?;
         };
 
+        self.cache_event(key, CachedEvent {
+            event,
+            last_published,
+            pending: false,
+        })
+        .await;
+        self.flush_due(now).await;
+        Ok(())
+    }
+
+    /// Publish the repeats of events that are held back by [`CacheConfig::series_update_interval`]
+    ///
+    /// Held back repeats are otherwise only published by later calls to [`Recorder::publish`],
+    /// so call this periodically or before shutting down to not lose them.
+    ///
+    /// # Errors
+    ///
+    /// Returns an [`Error`](`kube_client::Error`) if a series update is rejected by Kubernetes.
+    /// The remaining repeats are kept, and published by the next call.
+    pub async fn flush(&self) -> Result<(), kube_client::Error> {
+        self.flush_pending(|_| true).await
+    }
+
+    /// Publishes the held back repeats whose [`CacheConfig::series_update_interval`] has passed at `now`
+    async fn flush_due(&self, now: DateTime<Utc>) {
+        let interval = chrono_duration(self.cache_config.series_update_interval);
+        if let Err(err) = self
+            .flush_pending(|cached| cached.last_published + interval <= now)
+            .await
         {
-            let mut cache = self.cache.write().await;
-            cache.insert(key, event);
+            tracing::warn!(error = %err, "failed to publish repeated events");
+        }
+    }
+
+    async fn flush_pending(&self, due: impl Fn(&CachedEvent) -> bool) -> Result<(), kube_client::Error> {
+        let pending = self
+            .cache
+            .read()
+            .await
+            .iter()
+            .filter(|(_, cached)| cached.pending && due(cached))
+            .map(|(key, cached)| (key.clone(), cached.event.clone()))
+            .collect::<Vec<_>>();
+        for (key, event) in pending {
+            self.publish_series(&event).await?;
+            if let Some(cached) = self.cache.write().await.get_mut(&key) {
+                // Repeats may have been counted while publishing, which still need to be published
+                if cached.event.series == event.series {
+                    cached.pending = false;
+                    cached.last_published = Utc::now();
+                }
+            }
         }
         Ok(())
     }
+
+    /// Updates the series of a published event
+    async fn publish_series(&self, event: &K8sEvent) -> Result<(), kube_client::Error> {
+        let namespace = event.namespace().unwrap_or_default();
+        let events = Api::<K8sEvent>::namespaced(self.client.clone(), &namespace);
+        // Only the series changes on repeats, the rest of the event is immutable
+        let patch = serde_json::json!({ "series": event.series });
+        events
+            .patch(&event.name_any(), &PatchParams::default(), &Patch::Merge(&patch))
+            .await?;
+        Ok(())
+    }
+
+    /// Inserts an event into the cache, evicting the least recently observed events if it is full
+    async fn cache_event(&self, key: EventKey, event: CachedEvent) {
+        let mut cache = self.cache.write().await;
+        cache.insert(key, event);
+        while cache.len() > self.cache_config.max_entries.max(1) {
+            let oldest = cache
+                .iter()
+                .min_by_key(|(_, v)| v.last_observed())
+                .map(|(k, _)| k.clone());
+            match oldest {
+                Some(oldest) => cache.remove(&oldest),
+                None => break,
+            };
+        }
+    }
 }
 
 #[cfg(test)]
 mod test {
-    use super::{Event, EventKey, EventType, Recorder, Reference, Reporter};
+    use super::{
+        event_namespace, truncate_note, CacheConfig, Event, EventKey, EventType, Recorder, Reference,
+        Reporter,
+    };
 
     use k8s_openapi::{
         api::{
-            core::v1::{ComponentStatus, Service},
+            core::v1::{ComponentStatus, ObjectReference, Service},
             events::v1::Event as K8sEvent,
         },
         apimachinery::pkg::apis::meta::v1::MicroTime,
         chrono::{Duration, Utc},
     };
-    use kube::{Api, Client, Resource};
+    use kube::{client::fake::FakeApiServer, Api, Client, Resource};
+    use std::time::Duration as StdDuration;
+
+    fn fake_recorder(cache_config: CacheConfig) -> (Recorder, Api<K8sEvent>) {
+        let client = FakeApiServer::new().with_resource::<K8sEvent>().client();
+        let recorder = Recorder::new(client.clone(), "kube".into()).with_cache_config(cache_config);
+        (recorder, Api::namespaced(client, "default"))
+    }
+
+    fn reconciled(reason: &str) -> Event {
+        Event {
+            type_: EventType::Normal,
+            reason: reason.into(),
+            note: None,
+            action: "Reconcile".into(),
+            secondary: None,
+        }
+    }
+
+    fn web_service() -> ObjectReference {
+        let mut service = Service::default();
+        service.metadata.name = Some("web".into());
+        service.metadata.namespace = Some("default".into());
+        service.object_ref(&())
+    }
+
+    async fn series_counts(events: &Api<K8sEvent>) -> Vec<Option<i32>> {
+        let list = events.list(&Default::default()).await.unwrap();
+        list.items
+            .into_iter()
+            .map(|event| event.series.map(|series| series.count))
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn repeats_are_aggregated_into_a_series() -> Result<(), Box<dyn std::error::Error>> {
+        let (recorder, events) = fake_recorder(CacheConfig::default());
+        for _ in 0..3 {
+            recorder
+                .publish(&reconciled("Reconciled"), &web_service())
+                .await?;
+        }
+        assert_eq!(series_counts(&events).await, [Some(3)]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn held_back_repeats_are_flushed() -> Result<(), Box<dyn std::error::Error>> {
+        let (recorder, events) = fake_recorder(CacheConfig {
+            series_update_interval: StdDuration::from_secs(60 * 60),
+            ..CacheConfig::default()
+        });
+        for _ in 0..3 {
+            recorder
+                .publish(&reconciled("Reconciled"), &web_service())
+                .await?;
+        }
+        assert_eq!(series_counts(&events).await, [None]);
+
+        recorder.flush().await?;
+        assert_eq!(series_counts(&events).await, [Some(3)]);
+        assert!(recorder.cache.read().await.values().all(|cached| !cached.pending));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn expired_events_start_a_new_series() -> Result<(), Box<dyn std::error::Error>> {
+        let (recorder, events) = fake_recorder(CacheConfig {
+            ttl: StdDuration::ZERO,
+            ..CacheConfig::default()
+        });
+        recorder
+            .publish(&reconciled("Reconciled"), &web_service())
+            .await?;
+        recorder
+            .publish(&reconciled("Reconciled"), &web_service())
+            .await?;
+        assert_eq!(series_counts(&events).await, [None, None]);
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn least_recently_observed_events_are_evicted() -> Result<(), Box<dyn std::error::Error>> {
+        let (recorder, events) = fake_recorder(CacheConfig {
+            max_entries: 1,
+            ..CacheConfig::default()
+        });
+        recorder.publish(&reconciled("Created"), &web_service()).await?;
+        recorder
+            .publish(&reconciled("Reconciled"), &web_service())
+            .await?;
+        recorder
+            .publish(&reconciled("Reconciled"), &web_service())
+            .await?;
+        // The first event was evicted, so its repeat is published as a new event
+        recorder.publish(&reconciled("Created"), &web_service()).await?;
+
+        let cache = recorder.cache.read().await;
+        assert_eq!(cache.len(), 1);
+        assert!(cache.keys().all(|key| key.reason == "Created"));
+        let mut counts = series_counts(&events).await;
+        counts.sort();
+        assert_eq!(counts, [None, None, Some(2)]);
+        Ok(())
+    }
+
+    #[test]
+    fn events_are_published_next_to_their_object() {
+        let mut service = Service::default();
+        service.metadata.namespace = Some("apps".into());
+        assert_eq!(event_namespace(&service.object_ref(&())), "apps");
+        assert_eq!(
+            event_namespace(&ComponentStatus::default().object_ref(&())),
+            "default"
+        );
+
+        assert_eq!(truncate_note("short"), "short");
+        let long = "é".repeat(1000);
+        let truncated = truncate_note(&long);
+        assert_eq!(truncated.len(), 1024);
+        assert!(long.starts_with(&truncated));
+    }
 
     #[tokio::test]
     #[ignore = "needs cluster (creates an event for the default kubernetes service)"]
@@ -512,7 +821,7 @@ mod test {
         let now = Utc::now();
         let past = now - Duration::minutes(10);
         recorder.cache.write().await.entry(key).and_modify(|e| {
-            e.event_time = Some(MicroTime(past));
+            e.event.event_time = Some(MicroTime(past));
         });
 
         recorder.publish(&ev, &s.object_ref(&())).await?;
@@ -211,6 +211,180 @@ where
     }
 }
 
+/// Reconcile an object that requires several independent cleanups before it can be deleted.
+///
+/// Same as [`finalizer`], but manages one [`ObjectMeta::finalizers`] entry for each of `finalizer_names`,
+/// for example one per external system that the object is mirrored into. Each entry tracks whether its cleanup
+/// is still outstanding, so a cleanup that has succeeded is never retried even if other cleanups keep failing.
+///
+/// # Expected Flow
+///
+/// 1. `finalizers` adds any missing `finalizer_names` to [`ObjectMeta::finalizers`], in a single patch
+/// 2. Once all of them are present, `finalizers` runs [`NamedEvent::Apply`]
+/// 3. When the object is deleted, `finalizers` runs [`NamedEvent::Cleanup`] for every finalizer in `finalizer_names`
+///    that is still present, in order
+/// 4. `finalizers` removes the finalizers whose cleanup succeeded, in a single patch
+///
+/// # Errors
+///
+/// If any cleanup fails then the finalizers of the other, successful, cleanups are still removed, and the first
+/// error is returned as [`Error::CleanupFailed`]. The failed cleanups are retried by the next reconciliation.
+///
+/// Otherwise, errors are the same as for [`finalizer`].
+///
+/// ```no_run
+/// # use kube::runtime::{controller::Action, finalizer::{finalizers, NamedEvent}};
+/// # use k8s_openapi::api::core::v1::ConfigMap;
+/// # use std::sync::Arc;
+/// # async fn doc(api: kube::Api<ConfigMap>, obj: Arc<ConfigMap>) -> Result<Action, Box<dyn std::error::Error>> {
+/// finalizers(&api, &["example.com/dns", "example.com/storage"], obj, |event| async move {
+///     match event {
+///         NamedEvent::Apply(_obj) => { /* create the record and the bucket */ }
+///         NamedEvent::Cleanup { finalizer, obj: _ } if finalizer == "example.com/dns" => { /* remove the record */ }
+///         NamedEvent::Cleanup { .. } => { /* remove the bucket */ }
+///     }
+///     Ok::<_, kube::Error>(Action::await_change())
+/// })
+/// .await?;
+/// # Ok(Action::await_change())
+/// # }
+/// ```
+///
+/// [`ObjectMeta::finalizers`]: kube_client::api::ObjectMeta#structfield.finalizers
+pub async fn finalizers<K, ReconcileFut>(
+    api: &Api<K>,
+    finalizer_names: &[&str],
+    obj: Arc<K>,
+    mut reconcile: impl FnMut(NamedEvent<K>) -> ReconcileFut,
+) -> Result<Action, Error<ReconcileFut::Error>>
+where
+    K: Resource + Clone + DeserializeOwned + Serialize + Debug,
+    ReconcileFut: TryFuture<Ok = Action>,
+    ReconcileFut::Error: StdError + 'static,
+{
+    let name = obj.meta().name.clone().ok_or(Error::UnnamedObject)?;
+    let present = obj.finalizers();
+    let finalizers_path =
+        || PointerBuf::from_str("/metadata/finalizers").map_err(|_err| Error::InvalidFinalizer);
+
+    if obj.meta().deletion_timestamp.is_none() {
+        let missing = finalizer_names
+            .iter()
+            .copied()
+            .filter(|name| !present.iter().any(|fin| fin == name))
+            .collect::<Vec<_>>();
+        if missing.is_empty() {
+            return reconcile(NamedEvent::Apply(obj))
+                .into_future()
+                .await
+                .map_err(Error::ApplyFailed);
+        }
+
+        // Finalizers must be added before it's safe to run an `Apply` reconciliation
+        let mut patch = vec![PatchOperation::Test(TestOperation {
+            path: finalizers_path()?,
+            value: if present.is_empty() {
+                serde_json::Value::Null
+            } else {
+                present.into()
+            },
+        })];
+        if present.is_empty() {
+            patch.push(PatchOperation::Add(AddOperation {
+                path: finalizers_path()?,
+                value: missing.into(),
+            }));
+        } else {
+            for finalizer_name in missing {
+                patch.push(PatchOperation::Add(AddOperation {
+                    path: PointerBuf::from_str("/metadata/finalizers/-")
+                        .map_err(|_err| Error::InvalidFinalizer)?,
+                    value: finalizer_name.into(),
+                }));
+            }
+        }
+        api.patch::<K>(
+            &name,
+            &PatchParams::default(),
+            &Patch::Json(json_patch::Patch(patch)),
+        )
+        .await
+        .map_err(Error::AddFinalizer)?;
+        // No point applying here, since the patch will cause a new reconciliation
+        return Ok(Action::await_change());
+    }
+
+    // Cleanup of each finalizer must succeed before it's safe to remove that finalizer
+    let mut action = Action::await_change();
+    let mut first_error = None;
+    let mut cleaned_up = Vec::new();
+    for finalizer_name in finalizer_names {
+        let Some(finalizer_i) = present.iter().position(|fin| fin == finalizer_name) else {
+            continue;
+        };
+        let cleanup = reconcile(NamedEvent::Cleanup {
+            finalizer: (*finalizer_name).to_string(),
+            obj: obj.clone(),
+        });
+        match cleanup.into_future().await {
+            Ok(cleanup_action) => {
+                action = cleanup_action;
+                cleaned_up.push(finalizer_i);
+            }
+            Err(err) => {
+                first_error.get_or_insert(err);
+            }
+        }
+    }
+
+    if !cleaned_up.is_empty() {
+        // Remove from the back, so that the remaining indices stay valid
+        cleaned_up.sort_unstable_by(|a, b| b.cmp(a));
+        // `Test` ensures that we fail instead of deleting someone else's finalizer
+        // if the finalizers were changed in the meantime (in which case new `Cleanup` events will be sent)
+        let mut patch = vec![PatchOperation::Test(TestOperation {
+            path: finalizers_path()?,
+            value: present.into(),
+        })];
+        for finalizer_i in cleaned_up {
+            patch.push(PatchOperation::Remove(RemoveOperation {
+                path: PointerBuf::from_str(&format!("/metadata/finalizers/{finalizer_i}"))
+                    .map_err(|_err| Error::InvalidFinalizer)?,
+            }));
+        }
+        api.patch::<K>(
+            &name,
+            &PatchParams::default(),
+            &Patch::Json(json_patch::Patch(patch)),
+        )
+        .await
+        .map_err(Error::RemoveFinalizer)?;
+    }
+
+    match first_error {
+        Some(err) => Err(Error::CleanupFailed(err)),
+        None => Ok(action),
+    }
+}
+
+/// A representation of an action that should be taken by a reconciler managing several finalizers,
+/// see [`finalizers`].
+pub enum NamedEvent<K> {
+    /// The reconciler should ensure that the actual state matches the state desired in the object.
+    ///
+    /// This is the same as [`Event::Apply`], and runs once all finalizers have been added.
+    Apply(Arc<K>),
+    /// The object is being deleted, and the reconciler should remove the resources that `finalizer` guards.
+    ///
+    /// This must be idempotent like [`Event::Cleanup`]. It is sent once for each finalizer that is still present.
+    Cleanup {
+        /// The finalizer whose cleanup should run
+        finalizer: String,
+        /// The object being deleted
+        obj: Arc<K>,
+    },
+}
+
 /// A representation of an action that should be taken by a reconciler.
 pub enum Event<K> {
     /// The reconciler should ensure that the actual state matches the state desired in the object.
@@ -232,3 +406,128 @@ pub enum Event<K> {
     /// - The grinch's heart grows a size or two
     Cleanup(Arc<K>),
 }
+
+#[cfg(test)]
+mod tests {
+    use super::{finalizers, Error, NamedEvent};
+    use crate::controller::Action;
+    use http::{Method, Request, Response};
+    use k8s_openapi::{api::core::v1::ConfigMap, apimachinery::pkg::apis::meta::v1::Time, chrono::Utc};
+    use kube::{client::Body, core::ObjectMeta, Api, Client};
+    use serde_json::{json, Value};
+    use std::{pin::pin, sync::Arc};
+    use tower_test::mock;
+
+    fn cm(finalizers: &[&str], deleting: bool) -> ConfigMap {
+        ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("cm".into()),
+                namespace: Some("default".into()),
+                finalizers: Some(finalizers.iter().map(ToString::to_string).collect()),
+                deletion_timestamp: deleting.then(|| Time(Utc::now())),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        }
+    }
+
+    /// Runs `finalizers` for `names` against a mock apiserver, failing the cleanups of `failing`
+    ///
+    /// Returns the result, the events that were reconciled, and the JSON patches that were sent.
+    async fn run(
+        obj: ConfigMap,
+        names: &[&str],
+        failing: &[&str],
+    ) -> (Result<Action, Error<std::io::Error>>, Vec<String>, Vec<Value>) {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let response = serde_json::to_vec(&obj).unwrap();
+        let apiserver = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let mut patches = Vec::new();
+            while let Some((request, send)) = handle.next_request().await {
+                assert_eq!(request.method(), Method::PATCH);
+                assert_eq!(request.uri().path(), "/api/v1/namespaces/default/configmaps/cm");
+                let body = request.into_body().collect_bytes().await.unwrap();
+                patches.push(serde_json::from_slice(&body).unwrap());
+                send.send_response(Response::builder().body(Body::from(response.clone())).unwrap());
+            }
+            patches
+        });
+
+        let api = Api::<ConfigMap>::namespaced(Client::new(mock_service, "default"), "default");
+        let mut events = Vec::new();
+        let result = finalizers(&api, names, Arc::new(obj), |event| {
+            let result = match event {
+                NamedEvent::Apply(_) => {
+                    events.push("apply".to_string());
+                    Ok(Action::await_change())
+                }
+                NamedEvent::Cleanup { finalizer, .. } => {
+                    events.push(format!("cleanup {finalizer}"));
+                    if failing.contains(&finalizer.as_str()) {
+                        Err(std::io::Error::other(format!("{finalizer} failed")))
+                    } else {
+                        Ok(Action::await_change())
+                    }
+                }
+            };
+            std::future::ready(result)
+        })
+        .await;
+        drop(api);
+        (result, events, apiserver.await.unwrap())
+    }
+
+    #[tokio::test]
+    async fn adds_missing_finalizers_before_applying() {
+        let (result, events, patches) = run(cm(&[], false), &["a", "b"], &[]).await;
+        assert_eq!(result.unwrap(), Action::await_change());
+        assert!(events.is_empty());
+        assert_eq!(patches, [json!([
+            { "op": "test", "path": "/metadata/finalizers", "value": null },
+            { "op": "add", "path": "/metadata/finalizers", "value": ["a", "b"] },
+        ])]);
+
+        let (result, events, patches) = run(cm(&["other", "a"], false), &["a", "b"], &[]).await;
+        assert_eq!(result.unwrap(), Action::await_change());
+        assert!(events.is_empty());
+        assert_eq!(patches, [json!([
+            { "op": "test", "path": "/metadata/finalizers", "value": ["other", "a"] },
+            { "op": "add", "path": "/metadata/finalizers/-", "value": "b" },
+        ])]);
+
+        let (result, events, patches) = run(cm(&["b", "a"], false), &["a", "b"], &[]).await;
+        assert_eq!(result.unwrap(), Action::await_change());
+        assert_eq!(events, ["apply"]);
+        assert!(patches.is_empty());
+    }
+
+    #[tokio::test]
+    async fn removes_cleaned_up_finalizers_by_index() {
+        let (result, events, patches) = run(cm(&["a", "other", "b"], true), &["a", "b"], &[]).await;
+        assert_eq!(result.unwrap(), Action::await_change());
+        assert_eq!(events, ["cleanup a", "cleanup b"]);
+        assert_eq!(patches, [json!([
+            { "op": "test", "path": "/metadata/finalizers", "value": ["a", "other", "b"] },
+            { "op": "remove", "path": "/metadata/finalizers/2" },
+            { "op": "remove", "path": "/metadata/finalizers/0" },
+        ])]);
+    }
+
+    #[tokio::test]
+    async fn failed_cleanups_keep_their_finalizer() {
+        let (result, events, patches) = run(cm(&["a", "b"], true), &["a", "b"], &["b"]).await;
+        assert!(matches!(result, Err(Error::CleanupFailed(err)) if err.to_string() == "b failed"));
+        assert_eq!(events, ["cleanup a", "cleanup b"]);
+        assert_eq!(patches, [json!([
+            { "op": "test", "path": "/metadata/finalizers", "value": ["a", "b"] },
+            { "op": "remove", "path": "/metadata/finalizers/0" },
+        ])]);
+
+        // The retry only cleans up what is left, and fails again without sending a patch
+        let (result, events, patches) = run(cm(&["b"], true), &["a", "b"], &["b"]).await;
+        assert!(matches!(result, Err(Error::CleanupFailed(_))));
+        assert_eq!(events, ["cleanup b"]);
+        assert!(patches.is_empty());
+    }
+}
@@ -0,0 +1,602 @@
+//! Lease based leader election for running highly available controllers
+//!
+//! Only one replica holding a given [`Lease`] is considered the leader at any point in time.
+//! The [`LeaderElector`] continuously tries to acquire (or renew) the lease, and publishes
+//! the current [`Leadership`] state so that the [`Controller`](crate::Controller) can be started once
+//! the lease is acquired, and stopped (through [`Controller::graceful_shutdown_on`]) once it is lost.
+//!
+//! ```no_run
+//! use k8s_openapi::api::{coordination::v1::Lease, core::v1::ConfigMap};
+//! use kube::{Api, Client};
+//! use kube::runtime::{
+//!     controller::{Action, Controller},
+//!     leader::{self, LeaderElector},
+//!     watcher,
+//! };
+//! use futures::{FutureExt, StreamExt};
+//! use std::{convert::Infallible, sync::Arc};
+//!
+//! # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::try_default().await?;
+//! let leases = Api::<Lease>::namespaced(client.clone(), "default");
+//! let identity = std::env::var("CONTROLLER_POD_NAME")?;
+//! let elector = LeaderElector::new(leases, "my-controller-lock", identity, leader::Config::default());
+//! let mut leadership = elector.leadership();
+//! tokio::spawn(elector.run(tokio::signal::ctrl_c().map(|_| ())));
+//!
+//! // Wait until we are elected before starting the controller
+//! leadership.acquired().await;
+//! Controller::new(Api::<ConfigMap>::all(client), watcher::Config::default())
+//!     .graceful_shutdown_on(leadership.lost())
+//!     .run(
+//!         |_, _| async { Ok(Action::await_change()) },
+//!         |_, _: &Infallible, _| Action::await_change(),
+//!         Arc::new(()),
+//!     )
+//!     .for_each(|_| async {})
+//!     .await;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Controller::graceful_shutdown_on`]: crate::Controller::graceful_shutdown_on
+use futures::{future, Future};
+use k8s_openapi::{
+    api::coordination::v1::{Lease, LeaseSpec},
+    apimachinery::pkg::apis::meta::v1::MicroTime,
+    chrono::{DateTime, Duration, Utc},
+};
+use kube_client::{
+    api::{ObjectMeta, PostParams},
+    core::ErrorResponse,
+    Api, Error as ClientErr,
+};
+use rand::Rng;
+use std::{pin::pin, time::Duration as StdDuration};
+use thiserror::Error;
+use tokio::sync::watch;
+
+/// Errors from reading or writing the [`Lease`] of a [`LeaderElector`]
+#[derive(Debug, Error)]
+pub enum Error {
+    /// The [`Lease`] could not be read
+    #[error("failed to get lease: {0}")]
+    GetLease(#[source] kube_client::Error),
+    /// The [`Lease`] did not exist yet, and could not be created
+    #[error("failed to create lease: {0}")]
+    CreateLease(#[source] kube_client::Error),
+    /// The [`Lease`] could not be acquired, renewed, or released
+    #[error("failed to update lease: {0}")]
+    UpdateLease(#[source] kube_client::Error),
+}
+
+/// Results of leader election operations, see [`Error`]
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Timing configuration for the [`LeaderElector`]
+///
+/// The defaults match those used by `client-go` and most of the Kubernetes control plane.
+#[derive(Clone, Debug)]
+pub struct Config {
+    /// How long a lease is valid for after its last renewal
+    ///
+    /// Non-leaders will wait this long after the last observed renewal before taking over the lease.
+    pub lease_duration: StdDuration,
+    /// How long the leader keeps retrying a failed renewal before giving up leadership
+    ///
+    /// This must be shorter than `lease_duration`, so that the old leader has stopped by the time
+    /// a new leader may take over.
+    pub renew_deadline: StdDuration,
+    /// How long to wait between attempts to acquire or renew the lease
+    pub retry_period: StdDuration,
+    /// Maximum factor of `retry_period` that is randomly added to each wait, to avoid replicas racing each other
+    pub jitter: f64,
+}
+
+impl Default for Config {
+    fn default() -> Self {
+        Self {
+            lease_duration: StdDuration::from_secs(15),
+            renew_deadline: StdDuration::from_secs(10),
+            retry_period: StdDuration::from_secs(2),
+            jitter: 1.2,
+        }
+    }
+}
+
+impl Config {
+    /// Sets how long a lease is valid for after its last renewal
+    #[must_use]
+    pub fn lease_duration(mut self, lease_duration: StdDuration) -> Self {
+        self.lease_duration = lease_duration;
+        self
+    }
+
+    /// Sets how long a leader keeps retrying failed renewals before giving up leadership
+    #[must_use]
+    pub fn renew_deadline(mut self, renew_deadline: StdDuration) -> Self {
+        self.renew_deadline = renew_deadline;
+        self
+    }
+
+    /// Sets how long to wait between attempts to acquire or renew the lease
+    #[must_use]
+    pub fn retry_period(mut self, retry_period: StdDuration) -> Self {
+        self.retry_period = retry_period;
+        self
+    }
+
+    /// Sets the maximum jitter factor applied to `retry_period`
+    #[must_use]
+    pub fn jitter(mut self, jitter: f64) -> Self {
+        self.jitter = jitter;
+        self
+    }
+
+    fn jittered_retry_period(&self) -> StdDuration {
+        if self.jitter <= 0.0 {
+            return self.retry_period;
+        }
+        let factor = rand::thread_rng().gen_range(0.0..self.jitter);
+        self.retry_period + self.retry_period.mul_f64(factor)
+    }
+}
+
+/// The observed state of a leader election
+#[derive(Clone, Debug, PartialEq, Eq)]
+#[allow(clippy::module_name_repetitions)]
+pub enum LeaderState {
+    /// The lease has not been observed yet
+    Unknown,
+    /// We are holding the lease
+    Leading,
+    /// Someone else is holding the lease
+    Following {
+        /// The identity of the current leader
+        holder: String,
+    },
+    /// We have stepped down and will not try to reacquire the lease
+    Released,
+}
+
+/// A cloneable view into the state of a [`LeaderElector`]
+#[derive(Clone, Debug)]
+pub struct Leadership {
+    rx: watch::Receiver<LeaderState>,
+}
+
+impl Leadership {
+    /// The most recently observed [`LeaderState`]
+    #[must_use]
+    pub fn state(&self) -> LeaderState {
+        self.rx.borrow().clone()
+    }
+
+    /// Whether we are currently holding the lease
+    #[must_use]
+    pub fn is_leader(&self) -> bool {
+        *self.rx.borrow() == LeaderState::Leading
+    }
+
+    /// Waits until the lease has been acquired
+    ///
+    /// Never resolves if the [`LeaderElector`] is released or dropped before acquiring the lease.
+    pub async fn acquired(&mut self) {
+        loop {
+            if self.is_leader() {
+                return;
+            }
+            if self.rx.changed().await.is_err() {
+                future::pending::<()>().await;
+            }
+        }
+    }
+
+    /// Returns a [`Future`] that resolves once we are no longer holding the lease
+    ///
+    /// This resolves immediately if we are not currently the leader, and is intended to be passed
+    /// to [`Controller::graceful_shutdown_on`](crate::Controller::graceful_shutdown_on) after [`Leadership::acquired`].
+    /// The [`Future`] also resolves if the [`LeaderElector`] is dropped, since we can no longer guarantee that
+    /// the lease is being renewed.
+    pub fn lost(&self) -> impl Future<Output = ()> + Send + Sync + 'static {
+        let mut rx = self.rx.clone();
+        async move {
+            loop {
+                if *rx.borrow_and_update() != LeaderState::Leading {
+                    return;
+                }
+                if rx.changed().await.is_err() {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// The outcome of a single [`LeaderElector::try_acquire_or_renew`] attempt
+#[derive(Debug)]
+enum Decision {
+    /// Nobody holds the lease, or it has expired, so we should take it over
+    Acquire,
+    /// We already hold the lease and should renew it
+    Renew,
+    /// Someone else holds a valid lease
+    Follow(String),
+}
+
+fn decide(spec: &LeaseSpec, identity: &str, now: DateTime<Utc>) -> Decision {
+    match spec
+        .holder_identity
+        .as_deref()
+        .filter(|holder| !holder.is_empty())
+    {
+        None => Decision::Acquire,
+        Some(holder) if holder == identity => Decision::Renew,
+        Some(holder) => {
+            let last_renewal = spec.renew_time.as_ref().or(spec.acquire_time.as_ref());
+            let duration = Duration::seconds(spec.lease_duration_seconds.unwrap_or_default().into());
+            if last_renewal.map_or(true, |MicroTime(renewed)| *renewed + duration < now) {
+                Decision::Acquire
+            } else {
+                Decision::Follow(holder.to_string())
+            }
+        }
+    }
+}
+
+/// Acquires and renews a [`Lease`] on behalf of a single replica
+///
+/// Create one with [`LeaderElector::new`], grab a [`Leadership`] handle with [`LeaderElector::leadership`],
+/// and drive the election with [`LeaderElector::run`].
+#[allow(clippy::module_name_repetitions)]
+pub struct LeaderElector {
+    api: Api<Lease>,
+    lease_name: String,
+    identity: String,
+    config: Config,
+    state: watch::Sender<LeaderState>,
+}
+
+impl LeaderElector {
+    /// Creates a new elector for the [`Lease`] called `lease_name`
+    ///
+    /// `identity` must be unique for every replica taking part in the election, and is typically the pod name.
+    #[must_use]
+    pub fn new(api: Api<Lease>, lease_name: &str, identity: impl Into<String>, config: Config) -> Self {
+        let (state, _) = watch::channel(LeaderState::Unknown);
+        Self {
+            api,
+            lease_name: lease_name.to_string(),
+            identity: identity.into(),
+            config,
+            state,
+        }
+    }
+
+    /// The identity that this elector holds the lease under
+    #[must_use]
+    pub fn identity(&self) -> &str {
+        &self.identity
+    }
+
+    /// Returns a handle for observing the leadership state
+    #[must_use]
+    pub fn leadership(&self) -> Leadership {
+        Leadership {
+            rx: self.state.subscribe(),
+        }
+    }
+
+    fn set_state(&self, state: LeaderState) {
+        self.state.send_if_modified(|current| {
+            if *current == state {
+                false
+            } else {
+                tracing::debug!(lease = %self.lease_name, ?state, "leadership changed");
+                *current = state;
+                true
+            }
+        });
+    }
+
+    fn lease_spec(&self, now: DateTime<Utc>) -> LeaseSpec {
+        LeaseSpec {
+            holder_identity: Some(self.identity.clone()),
+            lease_duration_seconds: Some(
+                self.config
+                    .lease_duration
+                    .as_secs()
+                    .try_into()
+                    .unwrap_or(i32::MAX),
+            ),
+            acquire_time: Some(MicroTime(now)),
+            renew_time: Some(MicroTime(now)),
+            lease_transitions: Some(0),
+            ..LeaseSpec::default()
+        }
+    }
+
+    /// Makes a single attempt to acquire or renew the lease
+    ///
+    /// Returns the resulting [`LeaderState`], which is also published to all [`Leadership`] handles.
+    /// Losing a race against another replica (a conflicting write) is not considered an error.
+    /// After a conflict the lease is read again, and the state only changes if the lease is now held by someone else.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the [`Lease`] could not be read or written for any other reason than a conflict.
+    pub async fn try_acquire_or_renew(&self) -> Result<LeaderState> {
+        if let Some(state) = self.acquire_or_renew().await? {
+            self.set_state(state);
+        }
+        Ok(self.state.borrow().clone())
+    }
+
+    /// Makes a single attempt to acquire or renew the lease, without publishing the outcome
+    ///
+    /// Returns `None` if the write conflicted but we are still holding the lease, since the [`LeaderState`] is
+    /// unchanged, but the lease was not renewed either.
+    async fn acquire_or_renew(&self) -> Result<Option<LeaderState>> {
+        let now = Utc::now();
+        let Some(mut lease) = self
+            .api
+            .get_opt(&self.lease_name)
+            .await
+            .map_err(Error::GetLease)?
+        else {
+            let lease = Lease {
+                metadata: ObjectMeta {
+                    name: Some(self.lease_name.clone()),
+                    ..ObjectMeta::default()
+                },
+                spec: Some(self.lease_spec(now)),
+            };
+            return match self.api.create(&PostParams::default(), &lease).await {
+                Ok(_) => Ok(Some(LeaderState::Leading)),
+                // Someone else created it first
+                Err(ClientErr::Api(ErrorResponse { code: 409, .. })) => self.holder_after_conflict().await,
+                Err(err) => Err(Error::CreateLease(err)),
+            };
+        };
+
+        let spec = lease.spec.get_or_insert_with(LeaseSpec::default);
+        match decide(spec, &self.identity, now) {
+            Decision::Follow(holder) => return Ok(Some(LeaderState::Following { holder })),
+            Decision::Renew => {
+                spec.renew_time = Some(MicroTime(now));
+                spec.lease_duration_seconds = self.lease_spec(now).lease_duration_seconds;
+            }
+            Decision::Acquire => {
+                let transitions = spec.lease_transitions.unwrap_or_default();
+                *spec = LeaseSpec {
+                    lease_transitions: Some(transitions + 1),
+                    ..self.lease_spec(now)
+                };
+            }
+        }
+        // The lease's resourceVersion ensures that we fail rather than override a competing write
+        match self
+            .api
+            .replace(&self.lease_name, &PostParams::default(), &lease)
+            .await
+        {
+            Ok(_) => Ok(Some(LeaderState::Leading)),
+            Err(ClientErr::Api(ErrorResponse { code: 409, .. })) => self.holder_after_conflict().await,
+            Err(err) => Err(Error::UpdateLease(err)),
+        }
+    }
+
+    /// Reads who holds the lease after a conflicting write
+    ///
+    /// A conflict does not necessarily mean that the lease was taken over, it may for example also be caused by
+    /// someone updating its labels. Returns `None` if we are still holding the lease.
+    async fn holder_after_conflict(&self) -> Result<Option<LeaderState>> {
+        let lease = self
+            .api
+            .get_opt(&self.lease_name)
+            .await
+            .map_err(Error::GetLease)?;
+        let holder = lease
+            .and_then(|lease| lease.spec)
+            .and_then(|spec| spec.holder_identity)
+            .filter(|holder| !holder.is_empty());
+        Ok(match holder {
+            Some(holder) if holder == self.identity => None,
+            Some(holder) => Some(LeaderState::Following { holder }),
+            None => Some(LeaderState::Unknown),
+        })
+    }
+
+    /// Gives up the lease, if we are currently holding it
+    ///
+    /// The lease is marked as expired, so that other replicas can take over immediately rather than
+    /// having to wait for the full `lease_duration`.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the [`Lease`] could not be read or written for any other reason than a conflict.
+    pub async fn step_down(&self) -> Result<()> {
+        let lease = self
+            .api
+            .get_opt(&self.lease_name)
+            .await
+            .map_err(Error::GetLease)?;
+        if let Some(mut lease) = lease {
+            let spec = lease.spec.get_or_insert_with(LeaseSpec::default);
+            if spec.holder_identity.as_deref() == Some(self.identity.as_str()) {
+                spec.holder_identity = None;
+                spec.lease_duration_seconds = Some(1);
+                spec.renew_time = Some(MicroTime(Utc::now()));
+                match self
+                    .api
+                    .replace(&self.lease_name, &PostParams::default(), &lease)
+                    .await
+                {
+                    // Someone else has already taken over
+                    Ok(_) | Err(ClientErr::Api(ErrorResponse { code: 409, .. })) => {}
+                    Err(err) => return Err(Error::UpdateLease(err)),
+                }
+            }
+        }
+        self.set_state(LeaderState::Released);
+        Ok(())
+    }
+
+    /// Runs the election until `shutdown` resolves
+    ///
+    /// While leading, the lease is renewed every `retry_period`. If renewals keep failing for longer than
+    /// `renew_deadline` then leadership is considered lost, even if the lease might technically still be valid.
+    ///
+    /// Once `shutdown` resolves the lease is released (see [`LeaderElector::step_down`]) so that another
+    /// replica can take over straight away.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the lease could not be released after `shutdown` resolves.
+    /// Errors while acquiring or renewing the lease are logged and retried.
+    pub async fn run(self, shutdown: impl Future<Output = ()>) -> Result<()> {
+        let mut shutdown = pin!(shutdown);
+        let mut last_renewal = tokio::time::Instant::now();
+        loop {
+            let renewed = match self.acquire_or_renew().await {
+                Ok(Some(state)) => {
+                    let leading = state == LeaderState::Leading;
+                    self.set_state(state);
+                    leading
+                }
+                // Conflicted, but still holding the lease
+                Ok(None) => false,
+                Err(err) => {
+                    tracing::warn!(lease = %self.lease_name, error = %err, "failed to acquire or renew lease");
+                    false
+                }
+            };
+            if renewed {
+                last_renewal = tokio::time::Instant::now();
+            } else if *self.state.borrow() == LeaderState::Leading
+                && last_renewal.elapsed() > self.config.renew_deadline
+            {
+                tracing::warn!(lease = %self.lease_name, "renew deadline exceeded, giving up leadership");
+                self.set_state(LeaderState::Unknown);
+            }
+            let retry = pin!(tokio::time::sleep(self.config.jittered_retry_period()));
+            if let future::Either::Right(_) = future::select(retry, shutdown.as_mut()).await {
+                break;
+            }
+        }
+        self.step_down().await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decide, Config, Decision, LeaderElector, LeaderState};
+    use http::{Method, Request, Response, StatusCode};
+    use k8s_openapi::{
+        api::coordination::v1::{Lease, LeaseSpec},
+        apimachinery::pkg::apis::meta::v1::MicroTime,
+        chrono::{Duration, Utc},
+    };
+    use kube::{client::Body, core::ObjectMeta, Api, Client};
+    use std::pin::pin;
+    use tower_test::mock;
+
+    fn lease(holder: Option<&str>, renewed_ago: Duration, duration: i32) -> LeaseSpec {
+        LeaseSpec {
+            holder_identity: holder.map(String::from),
+            renew_time: Some(MicroTime(Utc::now() - renewed_ago)),
+            lease_duration_seconds: Some(duration),
+            ..LeaseSpec::default()
+        }
+    }
+
+    #[test]
+    fn renews_own_lease() {
+        let lease = lease(Some("me"), Duration::seconds(1), 15);
+        assert!(matches!(decide(&lease, "me", Utc::now()), Decision::Renew));
+    }
+
+    #[test]
+    fn follows_valid_foreign_lease() {
+        let lease = lease(Some("other"), Duration::seconds(1), 15);
+        assert!(matches!(decide(&lease, "me", Utc::now()), Decision::Follow(holder) if holder == "other"));
+    }
+
+    #[test]
+    fn acquires_expired_or_released_lease() {
+        let expired = lease(Some("other"), Duration::seconds(30), 15);
+        assert!(matches!(decide(&expired, "me", Utc::now()), Decision::Acquire));
+        let released = lease(None, Duration::seconds(0), 1);
+        assert!(matches!(decide(&released, "me", Utc::now()), Decision::Acquire));
+        let empty = lease(Some(""), Duration::seconds(0), 15);
+        assert!(matches!(decide(&empty, "me", Utc::now()), Decision::Acquire));
+    }
+
+    #[test]
+    fn jitter_stays_within_bounds() {
+        let config = Config::default();
+        for _ in 0..100 {
+            let wait = config.jittered_retry_period();
+            assert!(wait >= config.retry_period);
+            assert!(wait <= config.retry_period.mul_f64(1.0 + config.jitter));
+        }
+        let config = Config::default().jitter(0.0);
+        assert_eq!(config.jittered_retry_period(), config.retry_period);
+    }
+
+    /// Renews a lease held by "me" while another write conflicts, after which the lease is held by `holder`
+    async fn renew_with_conflict(holder: &str) -> LeaderState {
+        let (mock_service, handle) = mock::pair::<Request<Body>, Response<Body>>();
+        let lease = |holder: &str| {
+            let lease = Lease {
+                metadata: ObjectMeta {
+                    name: Some("lock".into()),
+                    resource_version: Some("1".into()),
+                    ..ObjectMeta::default()
+                },
+                spec: Some(lease(Some(holder), Duration::seconds(1), 15)),
+            };
+            Body::from(serde_json::to_vec(&lease).unwrap())
+        };
+        let holder = holder.to_string();
+        let apiserver = tokio::spawn(async move {
+            let mut handle = pin!(handle);
+            let (request, send) = handle.next_request().await.unwrap();
+            assert_eq!(request.method(), Method::GET);
+            send.send_response(Response::new(lease("me")));
+
+            let (request, send) = handle.next_request().await.unwrap();
+            assert_eq!(request.method(), Method::PUT);
+            let conflict = serde_json::json!({
+                "kind": "Status", "apiVersion": "v1", "metadata": {}, "status": "Failure",
+                "message": "the object has been modified", "reason": "Conflict", "code": 409,
+            });
+            send.send_response(
+                Response::builder()
+                    .status(StatusCode::CONFLICT)
+                    .body(Body::from(conflict.to_string().into_bytes()))
+                    .unwrap(),
+            );
+
+            let (request, send) = handle.next_request().await.unwrap();
+            assert_eq!(request.method(), Method::GET);
+            send.send_response(Response::new(lease(&holder)));
+        });
+
+        let api = Api::<Lease>::namespaced(Client::new(mock_service, "default"), "default");
+        let elector = LeaderElector::new(api, "lock", "me", Config::default());
+        elector.set_state(LeaderState::Leading);
+        let state = elector.try_acquire_or_renew().await.unwrap();
+        assert_eq!(elector.leadership().state(), state);
+        apiserver.await.unwrap();
+        state
+    }
+
+    #[tokio::test]
+    async fn conflicting_renewals_only_step_down_if_the_holder_changed() {
+        assert_eq!(renew_with_conflict("me").await, LeaderState::Leading);
+        assert_eq!(renew_with_conflict("other").await, LeaderState::Following {
+            holder: "other".into()
+        });
+    }
+}
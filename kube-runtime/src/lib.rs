@@ -20,10 +20,17 @@
 // Triggered by nightly clippy on idiomatic code
 #![allow(clippy::let_underscore_untyped)]
 
+#[cfg(feature = "admission-server")] pub mod admission;
+pub mod children;
 pub mod controller;
+pub mod conversion;
 pub mod events;
 
 pub mod finalizer;
+pub mod leader;
+pub mod logs;
+pub mod manager;
+#[cfg(feature = "metrics")] pub mod metrics;
 pub mod reflector;
 pub mod scheduler;
 pub mod utils;
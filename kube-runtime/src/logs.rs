@@ -0,0 +1,386 @@
+//! Follows the logs of containers across reconnects, and of every pod matching a selector
+use std::{collections::HashMap, fmt::Display, pin::Pin, task::Poll};
+
+use futures::{
+    stream::{self, AbortHandle, Abortable, BoxStream, SelectAll},
+    AsyncBufReadExt, Stream, StreamExt,
+};
+use k8s_openapi::{
+    api::core::v1::Pod,
+    chrono::{DateTime, Utc},
+};
+use kube_client::{api::LogParams, Api, ResourceExt};
+use thiserror::Error;
+
+use crate::{
+    watcher::{self, watcher},
+    WatchStreamExt,
+};
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("failed to start streaming logs: {0}")]
+    Connect(#[source] kube_client::Error),
+    #[error("failed to read logs: {0}")]
+    Read(#[source] std::io::Error),
+    #[error("failed to check whether the pod has finished: {0}")]
+    CheckPod(#[source] kube_client::Error),
+    #[error("failed to watch pods: {0}")]
+    Watch(#[source] watcher::Error),
+}
+
+/// A line of logs of a container, see [`follow`]
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct LogLine {
+    /// Name of the pod
+    pub pod: String,
+    /// Name of the container, or `None` for the only container of the pod
+    pub container: Option<String>,
+    /// When the container wrote the line, if the kubelet reported it
+    pub timestamp: Option<DateTime<Utc>>,
+    /// The line itself, without its newline
+    pub line: String,
+}
+
+impl Display for LogLine {
+    /// Formats the line prefixed by its source, like `kubectl logs --prefix`
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match &self.container {
+            Some(container) => write!(f, "[pod/{}/{container}] {}", self.pod, self.line),
+            None => write!(f, "[pod/{}] {}", self.pod, self.line),
+        }
+    }
+}
+
+/// Where to resume following logs after a reconnect
+///
+/// `sinceTime` only has a precision of seconds, so the lines of the last second are received again.
+/// The lines already emitted at the last timestamp are counted to skip exactly those.
+#[derive(Debug, Default)]
+struct Resume {
+    last: Option<DateTime<Utc>>,
+    emitted_at_last: usize,
+}
+
+impl Resume {
+    /// Parses a line with a timestamp prefix, or returns `None` if it was already emitted
+    fn parse(&mut self, raw: &str, skip_at_last: &mut usize) -> Option<(Option<DateTime<Utc>>, String)> {
+        let parsed = raw.split_once(' ').and_then(|(timestamp, line)| {
+            let timestamp = DateTime::parse_from_rfc3339(timestamp).ok()?;
+            Some((timestamp.with_timezone(&Utc), line))
+        });
+        let Some((timestamp, line)) = parsed else {
+            return Some((None, raw.to_string()));
+        };
+        match self.last {
+            Some(last) if timestamp < last => return None,
+            Some(last) if timestamp == last => {
+                if *skip_at_last > 0 {
+                    *skip_at_last -= 1;
+                    return None;
+                }
+                self.emitted_at_last += 1;
+            }
+            _ => {
+                self.last = Some(timestamp);
+                self.emitted_at_last = 1;
+            }
+        }
+        Some((Some(timestamp), line.to_string()))
+    }
+}
+
+type Lines = Pin<Box<dyn Stream<Item = std::io::Result<String>> + Send>>;
+
+enum State {
+    Connect,
+    Reading { lines: Lines, skip_at_last: usize },
+    Done,
+}
+
+/// Follows the logs of a container of pod `name`, reconnecting when the stream ends early
+///
+/// Log streams are regularly cut by the apiserver, load balancers, or kubelet restarts.
+/// After such a disconnect, the logs are resumed from the timestamp of the last line with `sinceTime`,
+/// without emitting any line twice. The stream ends once the pod has finished or was deleted.
+///
+/// `follow` and `timestamps` are always enabled, the timestamps are parsed into [`LogLine::timestamp`].
+/// The `tail_lines`, `since_seconds` and `since_time` of `lp` only apply to the first connection.
+///
+/// Errors are returned as they happen, and the next item reconnects. Like a [`watcher`](crate::watcher()),
+/// this should be used with a [backoff](crate::WatchStreamExt::default_backoff) to avoid hammering the apiserver.
+///
+/// ```no_run
+/// use futures::{stream, StreamExt, TryStreamExt};
+/// use k8s_openapi::api::core::v1::Pod;
+/// use kube::{api::LogParams, runtime::{logs, WatchStreamExt}, Api};
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client: kube::Client = todo!();
+///
+/// let pods: Api<Pod> = Api::default_namespaced(client);
+/// let containers = ["app", "sidecar"].map(|container| {
+///     let lp = LogParams { container: Some(container.into()), ..LogParams::default() };
+///     logs::follow(pods.clone(), "my-pod", lp).default_backoff().boxed()
+/// });
+/// let mut lines = stream::select_all(containers);
+/// while let Some(line) = lines.try_next().await? {
+///     println!("{line}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub fn follow(api: Api<Pod>, name: &str, lp: LogParams) -> impl Stream<Item = Result<LogLine, Error>> + Send {
+    let name = name.to_string();
+    let lp = LogParams {
+        follow: true,
+        timestamps: true,
+        ..lp
+    };
+    stream::unfold(
+        (State::Connect, Resume::default()),
+        move |(mut state, mut resume)| {
+            let (api, name, mut lp) = (api.clone(), name.clone(), lp.clone());
+            async move {
+                loop {
+                    match state {
+                        State::Connect => {
+                            if let Some(last) = resume.last {
+                                lp.since_time = Some(last);
+                                lp.since_seconds = None;
+                                lp.tail_lines = None;
+                            }
+                            match api.log_stream(&name, &lp).await {
+                                Ok(logs) => {
+                                    let skip_at_last = resume.emitted_at_last;
+                                    state = State::Reading {
+                                        lines: logs.lines().boxed(),
+                                        skip_at_last,
+                                    };
+                                }
+                                Err(err) => {
+                                    return Some((Err(Error::Connect(err)), (State::Connect, resume)))
+                                }
+                            }
+                        }
+                        State::Reading {
+                            mut lines,
+                            mut skip_at_last,
+                        } => match lines.next().await {
+                            Some(Ok(raw)) => {
+                                let parsed = resume.parse(&raw, &mut skip_at_last);
+                                state = State::Reading { lines, skip_at_last };
+                                if let Some((timestamp, line)) = parsed {
+                                    let line = LogLine {
+                                        pod: name.clone(),
+                                        container: lp.container.clone(),
+                                        timestamp,
+                                        line,
+                                    };
+                                    return Some((Ok(line), (state, resume)));
+                                }
+                            }
+                            Some(Err(err)) => return Some((Err(Error::Read(err)), (State::Connect, resume))),
+                            None => match api.get_opt(&name).await {
+                                Ok(Some(pod)) if !has_finished(&pod) => state = State::Connect,
+                                Ok(_) => state = State::Done,
+                                Err(err) => {
+                                    return Some((Err(Error::CheckPod(err)), (State::Connect, resume)))
+                                }
+                            },
+                        },
+                        State::Done => return None,
+                    }
+                }
+            }
+        },
+    )
+}
+
+/// Follows the logs of every container of the pods matching a selector, like `stern`
+///
+/// Pods are watched with a [`watcher`], and their containers are [followed](follow) once they have started.
+/// Logs of new pods are merged into the stream as they appear, and those of deleted pods are dropped.
+/// Each container is retried with the [default backoff](crate::WatchStreamExt::default_backoff),
+/// its errors are returned along with the lines of the other containers.
+///
+/// ```no_run
+/// use futures::TryStreamExt;
+/// use k8s_openapi::api::core::v1::Pod;
+/// use kube::{api::LogParams, runtime::{logs::LogAggregator, watcher}, Api};
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// # let client: kube::Client = todo!();
+///
+/// let pods: Api<Pod> = Api::default_namespaced(client);
+/// let mut lines = LogAggregator::new(pods, watcher::Config::default().labels("app=web"))
+///     .log_params(LogParams { tail_lines: Some(10), ..LogParams::default() })
+///     .stream();
+/// while let Some(line) = lines.try_next().await? {
+///     println!("{line}");
+/// }
+/// # Ok(())
+/// # }
+/// ```
+pub struct LogAggregator {
+    api: Api<Pod>,
+    wc: watcher::Config,
+    lp: LogParams,
+}
+
+impl LogAggregator {
+    /// Follow the logs of the pods in the scope of `api` that match `wc`
+    #[must_use]
+    pub fn new(api: Api<Pod>, wc: watcher::Config) -> Self {
+        Self {
+            api,
+            wc,
+            lp: LogParams::default(),
+        }
+    }
+
+    /// Parameters used to follow each container
+    ///
+    /// The `container` is set for each container, see [`follow`] for how the other parameters are used.
+    #[must_use]
+    pub fn log_params(mut self, lp: LogParams) -> Self {
+        self.lp = lp;
+        self
+    }
+
+    /// Start following logs, as a merged stream of the lines of every container
+    ///
+    /// The stream only ends if the pod watch does.
+    pub fn stream(self) -> impl Stream<Item = Result<LogLine, Error>> + Send {
+        let Self { api, wc, lp } = self;
+        let client = api.clone().into_client();
+        let mut pods = watcher(api, wc).default_backoff().boxed();
+        let mut pods_done = false;
+        let mut tails = SelectAll::<BoxStream<'static, Result<LogLine, Error>>>::new();
+        let mut tailing = HashMap::<(String, String), AbortHandle>::new();
+        stream::poll_fn(move |cx| {
+            while !pods_done {
+                match pods.poll_next_unpin(cx) {
+                    Poll::Ready(Some(Ok(watcher::Event::Apply(pod) | watcher::Event::InitApply(pod)))) => {
+                        for container in started_containers(&pod) {
+                            let key = (pod.uid().unwrap_or_default(), container.clone());
+                            if tailing.contains_key(&key) {
+                                continue;
+                            }
+                            let api = Api::namespaced(client.clone(), &pod.namespace().unwrap_or_default());
+                            let lp = LogParams {
+                                container: Some(container),
+                                ..lp.clone()
+                            };
+                            let (abort, registration) = AbortHandle::new_pair();
+                            let tail = follow(api, &pod.name_any(), lp).default_backoff();
+                            tails.push(Abortable::new(tail, registration).boxed());
+                            tailing.insert(key, abort);
+                        }
+                    }
+                    Poll::Ready(Some(Ok(watcher::Event::Delete(pod)))) => {
+                        let uid = pod.uid().unwrap_or_default();
+                        tailing.retain(|(pod_uid, _), abort| {
+                            let keep = *pod_uid != uid;
+                            if !keep {
+                                abort.abort();
+                            }
+                            keep
+                        });
+                    }
+                    Poll::Ready(Some(Ok(watcher::Event::Init | watcher::Event::InitDone))) => {}
+                    Poll::Ready(Some(Err(err))) => return Poll::Ready(Some(Err(Error::Watch(err)))),
+                    Poll::Ready(None) => pods_done = true,
+                    Poll::Pending => break,
+                }
+            }
+            match tails.poll_next_unpin(cx) {
+                Poll::Ready(None) if !pods_done => Poll::Pending,
+                poll => poll,
+            }
+        })
+    }
+}
+
+/// Containers of `pod` that have logs, because they are running or have run
+fn started_containers(pod: &Pod) -> impl Iterator<Item = String> + '_ {
+    let statuses = pod
+        .status
+        .iter()
+        .flat_map(|status| status.container_statuses.iter().flatten());
+    statuses
+        .filter(|status| {
+            let state = status.state.as_ref();
+            state.is_some_and(|state| state.running.is_some() || state.terminated.is_some())
+        })
+        .map(|status| status.name.clone())
+}
+
+/// Whether the containers of `pod` will not write any more logs
+fn has_finished(pod: &Pod) -> bool {
+    let phase = pod.status.as_ref().and_then(|status| status.phase.as_deref());
+    matches!(phase, Some("Succeeded" | "Failed")) || pod.metadata.deletion_timestamp.is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{started_containers, LogLine, Resume};
+    use k8s_openapi::api::core::v1::Pod;
+
+    #[test]
+    fn resume_skips_lines_already_emitted() {
+        let mut resume = Resume::default();
+        let mut skip = 0;
+        let first = [
+            "2024-01-01T00:00:00.100Z starting",
+            "2024-01-01T00:00:01Z ready",
+            "2024-01-01T00:00:01Z serving",
+        ];
+        let emitted = first
+            .iter()
+            .filter_map(|raw| resume.parse(raw, &mut skip))
+            .count();
+        assert_eq!(emitted, 3);
+
+        // reconnecting from the last second sends its lines again
+        let mut skip = resume.emitted_at_last;
+        let second = [
+            "2024-01-01T00:00:00.100Z starting",
+            "2024-01-01T00:00:01Z ready",
+            "2024-01-01T00:00:01Z serving",
+            "2024-01-01T00:00:01Z request",
+            "not a timestamped line",
+        ];
+        let lines = second
+            .iter()
+            .filter_map(|raw| resume.parse(raw, &mut skip))
+            .map(|(_, line)| line)
+            .collect::<Vec<_>>();
+        assert_eq!(lines, ["request", "not a timestamped line"]);
+    }
+
+    #[test]
+    fn lines_are_prefixed_by_their_source() {
+        let line = LogLine {
+            pod: "web".into(),
+            container: Some("app".into()),
+            timestamp: None,
+            line: "ready".into(),
+        };
+        assert_eq!(line.to_string(), "[pod/web/app] ready");
+    }
+
+    #[test]
+    fn only_started_containers_are_followed() {
+        let pod: Pod = serde_json::from_value(serde_json::json!({
+            "metadata": { "name": "web" },
+            "status": {
+                "containerStatuses": [
+                    { "name": "app", "state": { "running": {} }, "image": "", "imageID": "", "ready": true, "restartCount": 0 },
+                    { "name": "migrate", "state": { "terminated": { "exitCode": 0 } }, "image": "", "imageID": "", "ready": false, "restartCount": 0 },
+                    { "name": "sidecar", "state": { "waiting": {} }, "image": "", "imageID": "", "ready": false, "restartCount": 0 },
+                ],
+            },
+        }))
+        .unwrap();
+        assert_eq!(started_containers(&pod).collect::<Vec<_>>(), ["app", "migrate"]);
+    }
+}
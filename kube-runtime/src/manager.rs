@@ -0,0 +1,690 @@
+//! Runs multiple controllers on top of shared caches
+//!
+//! A [`Manager`] owns a [`Client`], a set of shared caches (one [`watcher`] and [`Store`] per resource and selector),
+//! and the controllers that are registered on it. Controllers that are interested in the same resources share a
+//! single watch stream and cache, which keeps memory usage and API server load flat as more controllers are added.
+//!
+//! The manager also takes care of graceful shutdown (every registered controller is stopped before
+//! [`Manager::run`] returns), optional [leader election](crate::leader), and exposes aggregate
+//! health and readiness through [`Health`].
+//!
+//! ```no_run
+//! use k8s_openapi::api::{apps::v1::Deployment, core::v1::Pod};
+//! use kube::{Api, Client};
+//! use kube::runtime::{controller::Action, manager::Manager, watcher};
+//! use std::{convert::Infallible, sync::Arc};
+//!
+//! # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::try_default().await?;
+//! let mut manager = Manager::new(client.clone()).shutdown_on_signal();
+//!
+//! // Both controllers are driven by the same Pod watch
+//! let pods = manager.subscribe(Api::<Pod>::all(client.clone()), watcher::Config::default());
+//! let deployments = manager
+//!     .controller(Api::<Deployment>::all(client.clone()), watcher::Config::default())
+//!     .watches_shared_stream(pods, |_| None)
+//!     .run(
+//!         |_, _| async { Ok::<_, Infallible>(Action::await_change()) },
+//!         |_, _, _| Action::await_change(),
+//!         Arc::new(()),
+//!     );
+//! manager.add("deployments", deployments);
+//! let pods = manager
+//!     .controller(Api::<Pod>::all(client), watcher::Config::default())
+//!     .run(
+//!         |_, _| async { Ok::<_, Infallible>(Action::await_change()) },
+//!         |_, _, _| Action::await_change(),
+//!         Arc::new(()),
+//!     );
+//! manager.add("pods", pods);
+//!
+//! manager.run().await?;
+//! # Ok(())
+//! # }
+//! ```
+//!
+//! [`Store`]: crate::reflector::Store
+use crate::{
+    controller::{self, Probe},
+    leader::{self, LeaderElector},
+    reflector::{self, ReflectHandle},
+    watcher::{self, watcher},
+    Controller, WatchStreamExt,
+};
+use futures::{
+    channel,
+    future::{self, BoxFuture, Either},
+    stream::FuturesUnordered,
+    Future, FutureExt, Stream, StreamExt,
+};
+use kube_client::{Api, Client, Resource};
+use parking_lot::Mutex;
+use serde::de::DeserializeOwned;
+use std::{
+    any::{Any, TypeId},
+    collections::{BTreeMap, HashMap},
+    fmt::{Debug, Display},
+    hash::Hash,
+    pin::pin,
+    sync::Arc,
+};
+use thiserror::Error;
+use tokio::sync::watch;
+
+/// The default number of events buffered for each shared cache
+///
+/// See [`Manager::buffer_size`].
+pub const DEFAULT_BUFFER_SIZE: usize = 1024;
+
+#[derive(Debug, Error)]
+pub enum Error {
+    #[error("leader election failed: {0}")]
+    LeaderElection(#[source] leader::Error),
+    #[error("lost leadership")]
+    LeadershipLost,
+}
+
+/// The state of a single component (cache or controller) of a [`Manager`]
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Status {
+    /// The component has not started yet, or a cache has not completed its initial list
+    Starting,
+    /// The component is running, and caches have completed their initial list
+    Ready,
+    /// The component has stopped before the manager was asked to shut down
+    Stopped,
+}
+
+#[derive(Debug, Default)]
+struct HealthState {
+    standby: bool,
+    components: BTreeMap<String, Status>,
+    /// The health of controllers created with [`Manager::controller`]
+    controllers: Vec<controller::Health>,
+}
+
+/// A cloneable view into the aggregate health of a [`Manager`]
+///
+/// This is typically exposed through liveness and readiness probes:
+///
+/// - [`Health::is_healthy`] turns false once any cache or controller stops unexpectedly,
+///   or a controller created with [`Manager::controller`] is stuck backing off from trigger errors
+/// - [`Health::is_ready`] turns true once every cache has synced and every controller is running
+///
+/// Replicas waiting for leadership are considered ready, since they are healthy standbys.
+/// [`Health::respond`] answers the [`Probe`]s on their conventional HTTP paths.
+#[derive(Clone, Debug, Default)]
+pub struct Health {
+    state: Arc<Mutex<HealthState>>,
+}
+
+impl Health {
+    /// Whether all components of the manager are still running
+    #[must_use]
+    pub fn is_healthy(&self) -> bool {
+        let state = self.state.lock();
+        !state.components.values().any(|status| *status == Status::Stopped)
+            && state.controllers.iter().all(controller::Health::is_live)
+    }
+
+    /// Whether the manager is waiting for leadership, or all of its components are ready
+    #[must_use]
+    pub fn is_ready(&self) -> bool {
+        let state = self.state.lock();
+        state.standby || state.components.values().all(|status| *status == Status::Ready)
+    }
+
+    /// Whether `probe` currently passes
+    #[must_use]
+    pub fn check(&self, probe: Probe) -> bool {
+        match probe {
+            Probe::Readiness => self.is_ready(),
+            Probe::Liveness => self.is_healthy(),
+        }
+    }
+
+    /// The HTTP status code and body to respond to a request for `path` with, if it is a [`Probe::path`]
+    ///
+    /// ```
+    /// # use kube::runtime::manager::Health;
+    /// fn handle(health: &Health, path: &str) -> (u16, &'static str) {
+    ///     health.respond(path).unwrap_or((404, "not found"))
+    /// }
+    /// ```
+    #[must_use]
+    pub fn respond(&self, path: &str) -> Option<(u16, &'static str)> {
+        Probe::from_path(path).map(|probe| Probe::response(self.check(probe)))
+    }
+
+    /// The [`Status`] of every registered cache and controller, by name
+    #[must_use]
+    pub fn components(&self) -> BTreeMap<String, Status> {
+        self.state.lock().components.clone()
+    }
+
+    fn set(&self, name: &str, status: Status) {
+        self.state.lock().components.insert(name.to_string(), status);
+    }
+
+    fn set_standby(&self, standby: bool) {
+        self.state.lock().standby = standby;
+    }
+
+    fn watch_controller(&self, health: controller::Health) {
+        self.state.lock().controllers.push(health);
+    }
+}
+
+/// Identifies a shared cache by the type, url and selectors of its watch
+#[derive(Debug, PartialEq, Eq, Hash)]
+struct CacheKey {
+    type_id: TypeId,
+    url: String,
+    label_selector: Option<String>,
+    field_selector: Option<String>,
+}
+
+impl Display for CacheKey {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.url)?;
+        if let Some(labels) = &self.label_selector {
+            write!(f, " labels={labels}")?;
+        }
+        if let Some(fields) = &self.field_selector {
+            write!(f, " fields={fields}")?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs a set of controllers on top of shared caches
+///
+/// See the [module level documentation](crate::manager) for an example.
+pub struct Manager {
+    client: Client,
+    buffer_size: usize,
+    // Values are `ReflectHandle<K>` for the `K` in the key
+    caches: HashMap<CacheKey, Box<dyn Any + Send>>,
+    reflectors: Vec<BoxFuture<'static, ()>>,
+    controllers: Vec<(String, BoxFuture<'static, ()>)>,
+    graceful_shutdown_selector: Vec<BoxFuture<'static, ()>>,
+    forceful_shutdown_selector: Vec<BoxFuture<'static, ()>>,
+    shutdown: watch::Sender<bool>,
+    leader: Option<LeaderElector>,
+    health: Health,
+}
+
+impl Manager {
+    /// Creates an empty manager that watches resources through `client`
+    #[must_use]
+    pub fn new(client: Client) -> Self {
+        let (shutdown, _) = watch::channel(false);
+        Self {
+            client,
+            buffer_size: DEFAULT_BUFFER_SIZE,
+            caches: HashMap::new(),
+            reflectors: Vec::new(),
+            controllers: Vec::new(),
+            graceful_shutdown_selector: vec![
+                // Fallback future, ensuring that we never shut down if no triggers are added
+                future::pending().boxed(),
+            ],
+            forceful_shutdown_selector: vec![
+                // Fallback future, ensuring that we never shut down if no triggers are added
+                future::pending().boxed(),
+            ],
+            shutdown,
+            leader: None,
+            health: Health::default(),
+        }
+    }
+
+    /// Sets the number of events buffered for each shared cache
+    ///
+    /// Every controller subscribed to a cache must consume an event before it is dropped from the buffer,
+    /// so a full buffer means that the watch is paused until the slowest controller has caught up.
+    ///
+    /// This only affects caches that are created after calling this.
+    #[must_use]
+    pub fn buffer_size(mut self, buffer_size: usize) -> Self {
+        self.buffer_size = buffer_size;
+        self
+    }
+
+    /// Only runs the controllers while `elector` holds its lease
+    ///
+    /// Caches and controllers are started once leadership is acquired. If leadership is lost
+    /// then all controllers are shut down gracefully, and [`Manager::run`] fails with [`Error::LeadershipLost`],
+    /// after which the process is expected to exit and restart.
+    #[must_use]
+    pub fn leader_election(mut self, elector: LeaderElector) -> Self {
+        self.leader = Some(elector);
+        self
+    }
+
+    /// Shuts down all controllers gracefully once `trigger` resolves
+    ///
+    /// Same as [`Controller::graceful_shutdown_on`], for every controller of the manager.
+    #[must_use]
+    pub fn graceful_shutdown_on(mut self, trigger: impl Future<Output = ()> + Send + Sync + 'static) -> Self {
+        self.graceful_shutdown_selector.push(trigger.boxed());
+        self
+    }
+
+    /// Shuts down gracefully on the first SIGTERM or SIGINT, and forcefully on the second
+    ///
+    /// Same as [`Controller::shutdown_on_signal`], for every controller of the manager.
+    #[must_use]
+    pub fn shutdown_on_signal(mut self) -> Self {
+        async fn shutdown_signal() {
+            futures::future::select(
+                tokio::signal::ctrl_c().map(|_| ()).boxed(),
+                #[cfg(unix)]
+                tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate())
+                    .unwrap()
+                    .recv()
+                    .map(|_| ())
+                    .boxed(),
+                // Assume that ctrl_c is enough on non-Unix platforms (such as Windows)
+                #[cfg(not(unix))]
+                futures::future::pending::<()>(),
+            )
+            .await;
+        }
+
+        let (graceful_tx, graceful_rx) = channel::oneshot::channel();
+        self.graceful_shutdown_selector
+            .push(graceful_rx.map(|_| ()).boxed());
+        self.forceful_shutdown_selector.push(
+            async {
+                tracing::info!("press ctrl+c to shut down gracefully");
+                shutdown_signal().await;
+                if let Ok(()) = graceful_tx.send(()) {
+                    tracing::info!("graceful shutdown requested, press ctrl+c again to force shutdown");
+                } else {
+                    tracing::info!(
+                        "graceful shutdown already requested, press ctrl+c again to force shutdown"
+                    );
+                }
+                shutdown_signal().await;
+                tracing::info!("forced shutdown requested");
+            }
+            .boxed(),
+        );
+        self
+    }
+
+    /// The [`Client`] that the manager was created with
+    #[must_use]
+    pub fn client(&self) -> Client {
+        self.client.clone()
+    }
+
+    /// Returns a handle for observing the health of the manager
+    #[must_use]
+    pub fn health(&self) -> Health {
+        self.health.clone()
+    }
+
+    /// Returns a [`Future`] that resolves once the manager starts shutting down gracefully
+    ///
+    /// Controllers created with [`Manager::controller`] already stop on this signal, but streams
+    /// built in other ways must also stop on it before being passed to [`Manager::add`].
+    pub fn shutdown_signal(&self) -> impl Future<Output = ()> + Send + Sync + 'static {
+        signaled(self.shutdown.subscribe())
+    }
+
+    /// Subscribes to the shared cache of the resources in `api` that match `wc`
+    ///
+    /// The cache (and its watch) is created on the first subscription, and reused by any later subscription
+    /// for the same resource type, url, and selectors. Other fields of `wc` are only taken into account
+    /// when the cache is created.
+    ///
+    /// The returned [`ReflectHandle`] can be passed to the shared stream constructors of the [`Controller`],
+    /// such as [`Controller::owns_shared_stream`] and [`Controller::watches_shared_stream`], and
+    /// its [`Store`] can be retrieved with [`ReflectHandle::reader`].
+    /// Every subscriber must be polled, or be dropped before [`Manager::run`] is called, so that it does not
+    /// hold back the watch.
+    pub fn subscribe<K>(&mut self, api: Api<K>, wc: watcher::Config) -> ReflectHandle<K>
+    where
+        K: Clone + Resource + DeserializeOwned + Debug + Send + Sync + 'static,
+        K::DynamicType: Eq + Hash + Clone + Default + Send + Sync,
+    {
+        let key = CacheKey {
+            type_id: TypeId::of::<K>(),
+            url: api.resource_url().to_string(),
+            label_selector: wc.label_selector.clone(),
+            field_selector: wc.field_selector.clone(),
+        };
+        if let Some(subscriber) = self
+            .caches
+            .get(&key)
+            .and_then(|subscriber| subscriber.downcast_ref::<ReflectHandle<K>>())
+        {
+            return subscriber.clone();
+        }
+
+        let (reader, writer) = reflector::store_shared(self.buffer_size);
+        let Some(subscriber) = writer.subscribe() else {
+            unreachable!("subscribers can always be created from shared stores")
+        };
+        let name = format!("cache {key}");
+        self.health.set(&name, Status::Starting);
+        let health = self.health.clone();
+        let stream = watcher(api, wc).default_backoff().reflect_shared(writer);
+        self.reflectors.push(
+            async move {
+                let ready = async {
+                    if reader.wait_until_ready().await.is_ok() {
+                        health.set(&name, Status::Ready);
+                    }
+                };
+                let watch = stream.for_each(|event| {
+                    if let Err(error) = event {
+                        tracing::warn!(cache = %name, %error, "watcher error");
+                    }
+                    std::future::ready(())
+                });
+                future::join(ready, watch).await;
+                // Watchers retry forever, so this only happens if the manager is dropped
+                health.set(&name, Status::Stopped);
+            }
+            .boxed(),
+        );
+        self.caches.insert(key, Box::new(subscriber.clone()));
+        subscriber
+    }
+
+    /// Creates a [`Controller`] for the resources in `api` that match `wc`, backed by a shared cache
+    ///
+    /// The controller is stopped when the manager shuts down. Configure it as usual, and pass the stream
+    /// returned by [`Controller::run`] to [`Manager::add`]. The manager is unhealthy while the trigger watches
+    /// of the controller are stuck, see [`Controller::trigger_unhealthy_after`].
+    pub fn controller<K>(&mut self, api: Api<K>, wc: watcher::Config) -> Controller<K>
+    where
+        K: Clone + Resource + DeserializeOwned + Debug + Send + Sync + 'static,
+        K::DynamicType: Eq + Hash + Clone + Default + Send + Sync,
+    {
+        let controller =
+            Controller::for_subscriber(self.subscribe(api, wc)).graceful_shutdown_on(self.shutdown_signal());
+        self.health.watch_controller(controller.health());
+        controller
+    }
+
+    /// Runs the stream of a controller (as returned by [`Controller::run`]) as part of the manager
+    ///
+    /// Reconciliation errors are logged, under the given `name`. The stream is expected to run
+    /// until [`Manager::shutdown_signal`] resolves; stopping before that marks the manager as unhealthy.
+    pub fn add<S, T, E>(&mut self, name: impl Into<String>, stream: S)
+    where
+        S: Stream<Item = Result<T, E>> + Send + 'static,
+        T: Debug,
+        E: Display,
+    {
+        let name = name.into();
+        self.health.set(&name, Status::Starting);
+        let controller = {
+            let name = name.clone();
+            stream.for_each(move |result| {
+                match result {
+                    Ok(obj) => tracing::debug!(controller = %name, ?obj, "reconciled"),
+                    Err(error) => tracing::warn!(controller = %name, %error, "reconcile failed"),
+                }
+                std::future::ready(())
+            })
+        };
+        self.controllers.push((name, controller.boxed()));
+    }
+
+    /// Runs all caches and controllers until the manager is shut down
+    ///
+    /// Shutdown is triggered through [`Manager::graceful_shutdown_on`] or [`Manager::shutdown_on_signal`],
+    /// and resolves once every controller has stopped.
+    ///
+    /// # Errors
+    ///
+    /// Fails if leader election was enabled, and either leadership was lost or the lease could not be released
+    /// during shutdown.
+    pub async fn run(self) -> Result<(), Error> {
+        let Self {
+            caches,
+            reflectors,
+            controllers,
+            graceful_shutdown_selector,
+            forceful_shutdown_selector,
+            shutdown,
+            leader,
+            health,
+            ..
+        } = self;
+        // Subscribers that were never handed out would otherwise hold back the shared streams
+        drop(caches);
+
+        let leadership = leader.as_ref().map(LeaderElector::leadership);
+        let (stopped_tx, stopped_rx) = watch::channel(false);
+        let election = async move {
+            match leader {
+                Some(elector) => elector
+                    .run(signaled(stopped_rx))
+                    .await
+                    .map_err(Error::LeaderElection),
+                None => Ok(()),
+            }
+        };
+
+        let main = async move {
+            let mut graceful = future::select_all(graceful_shutdown_selector);
+            if let Some(mut leadership) = leadership.clone() {
+                health.set_standby(true);
+                if let Either::Right(_) = future::select(pin!(leadership.acquired()), &mut graceful).await {
+                    return Ok(());
+                }
+                tracing::info!("acquired leadership, starting controllers");
+                health.set_standby(false);
+            }
+
+            let lost = match &leadership {
+                Some(leadership) => leadership.lost().boxed(),
+                None => future::pending().boxed(),
+            };
+            let trigger = async {
+                let result = match future::select(graceful, lost).await {
+                    Either::Left(_) => Ok(()),
+                    Either::Right(_) => {
+                        tracing::warn!("lost leadership, shutting down");
+                        Err(Error::LeadershipLost)
+                    }
+                };
+                shutdown.send_replace(true);
+                result
+            };
+
+            let mut running = controllers
+                .into_iter()
+                .map(|(name, controller)| {
+                    health.set(&name, Status::Ready);
+                    controller.map(move |()| name)
+                })
+                .collect::<FuturesUnordered<_>>();
+            let controllers = async {
+                while let Some(name) = running.next().await {
+                    if *shutdown.borrow() {
+                        tracing::debug!(controller = %name, "controller stopped");
+                    } else {
+                        tracing::warn!(controller = %name, "controller stopped unexpectedly");
+                        health.set(&name, Status::Stopped);
+                    }
+                }
+            };
+            let reflectors = future::join_all(reflectors).then(|_| future::pending::<()>());
+
+            match future::select(pin!(future::join(controllers, trigger)), pin!(reflectors)).await {
+                Either::Left((((), result), _)) => result,
+                Either::Right(((), _)) => unreachable!("reflectors never stop"),
+            }
+        };
+        let main = async move {
+            let result = main.await;
+            let _ = stopped_tx.send(true);
+            result
+        };
+
+        let forceful = future::select_all(forceful_shutdown_selector);
+        match future::select(pin!(future::join(main, election)), forceful).await {
+            Either::Left(((result, election), _)) => result.and(election),
+            Either::Right(_) => Ok(()),
+        }
+    }
+}
+
+/// Resolves once `rx` is set to true, or its sender is dropped
+async fn signaled(mut rx: watch::Receiver<bool>) {
+    loop {
+        if *rx.borrow_and_update() {
+            return;
+        }
+        if rx.changed().await.is_err() {
+            return;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Health, Manager, Status};
+    use crate::{controller::Action, utils::KubeRuntimeStreamExt, watcher};
+    use futures::{FutureExt, StreamExt};
+    use k8s_openapi::api::core::v1::{ConfigMap, Secret};
+    use kube::{client::fake::FakeApiServer, core::ObjectMeta, Api};
+    use std::{
+        convert::Infallible,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
+    use tokio::time::timeout;
+
+    #[test]
+    fn health_aggregates_components() {
+        let health = Health::default();
+        assert!(health.is_ready());
+        health.set("cache", Status::Starting);
+        health.set("controller", Status::Ready);
+        assert!(health.is_healthy());
+        assert!(!health.is_ready());
+
+        health.set("cache", Status::Ready);
+        assert!(health.is_ready());
+
+        health.set("controller", Status::Stopped);
+        assert!(!health.is_healthy());
+        assert!(!health.is_ready());
+    }
+
+    #[test]
+    fn health_responds_to_probes() {
+        let health = Health::default();
+        health.set("cache", Status::Starting);
+        assert_eq!(health.respond("/readyz"), Some((503, "unavailable")));
+        assert_eq!(health.respond("/livez"), Some((200, "ok")));
+        assert_eq!(health.respond("/"), None);
+    }
+
+    #[test]
+    fn standby_is_ready() {
+        let health = Health::default();
+        health.set("cache", Status::Starting);
+        health.set_standby(true);
+        assert!(health.is_ready());
+        assert!(health.is_healthy());
+    }
+
+    #[tokio::test]
+    async fn controllers_share_caches_by_type_and_selector() {
+        let client = FakeApiServer::new().client();
+        let mut manager = Manager::new(client.clone());
+        let api = Api::<ConfigMap>::all(client.clone());
+        let labelled = watcher::Config::default().labels("app=web");
+
+        let _first = manager.controller(api.clone(), watcher::Config::default());
+        let _second = manager.controller(api.clone(), watcher::Config::default().timeout(10));
+        assert_eq!(manager.caches.len(), 1);
+
+        let _labelled = manager.controller(api, labelled.clone());
+        let _namespaced =
+            manager.subscribe(Api::<ConfigMap>::namespaced(client.clone(), "default"), labelled);
+        let _secrets = manager.subscribe(Api::<Secret>::all(client), watcher::Config::default());
+        assert_eq!(manager.caches.len(), 4);
+        assert_eq!(manager.reflectors.len(), 4);
+        let caches = manager
+            .health()
+            .components()
+            .into_keys()
+            .filter(|name| name.starts_with("cache "))
+            .count();
+        assert_eq!(caches, 4);
+    }
+
+    #[tokio::test]
+    async fn shutdown_stops_every_controller() {
+        let client = FakeApiServer::new().with_resource::<ConfigMap>().client();
+        let cm = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("cm".into()),
+                namespace: Some("default".into()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        Api::<ConfigMap>::namespaced(client.clone(), "default")
+            .create(&Default::default(), &cm)
+            .await
+            .unwrap();
+
+        let (shutdown_tx, shutdown_rx) = futures::channel::oneshot::channel::<()>();
+        let mut manager = Manager::new(client.clone()).graceful_shutdown_on(shutdown_rx.map(|_| ()));
+        let (reconciled_tx, mut reconciled_rx) = futures::channel::mpsc::unbounded();
+        let stopped = Arc::new(AtomicUsize::new(0));
+        for name in ["first", "second"] {
+            let (reconciled_tx, stopped) = (reconciled_tx.clone(), stopped.clone());
+            let controller = manager
+                .controller(Api::<ConfigMap>::all(client.clone()), watcher::Config::default())
+                .run(
+                    move |_, _| {
+                        reconciled_tx.unbounded_send(name).unwrap();
+                        async { Ok::<_, Infallible>(Action::await_change()) }
+                    },
+                    |_, _, _| Action::await_change(),
+                    Arc::new(()),
+                )
+                .on_complete(async move {
+                    stopped.fetch_add(1, Ordering::SeqCst);
+                });
+            manager.add(name, controller);
+        }
+        let health = manager.health();
+        let running = tokio::spawn(manager.run());
+
+        let mut reconciled = Vec::new();
+        for _ in 0..2 {
+            let name = timeout(Duration::from_secs(10), reconciled_rx.next())
+                .await
+                .expect("test timeout expired");
+            reconciled.push(name.unwrap());
+        }
+        reconciled.sort_unstable();
+        assert_eq!(reconciled, ["first", "second"]);
+
+        shutdown_tx.send(()).unwrap();
+        timeout(Duration::from_secs(10), running)
+            .await
+            .expect("manager did not shut down")
+            .unwrap()
+            .unwrap();
+        assert_eq!(stopped.load(Ordering::SeqCst), 2);
+        // Controllers that stop because of the shutdown are not reported as failed
+        assert!(health.is_healthy());
+    }
+}
@@ -0,0 +1,141 @@
+//! Prometheus metrics for the [`Controller`](crate::Controller) and its [`scheduler`](crate::scheduler())
+//!
+//! Every [`Controller`](crate::Controller) keeps its own [`Metrics`], which can be retrieved with
+//! [`Controller::metrics`](crate::Controller::metrics) and registered into the [`Registry`] served by your exporter:
+//!
+//! ```no_run
+//! use k8s_openapi::api::core::v1::ConfigMap;
+//! use kube::{Api, Client};
+//! use kube::runtime::{watcher, Controller};
+//! use prometheus_client::registry::Registry;
+//!
+//! # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+//! let client = Client::try_default().await?;
+//! let controller = Controller::new(Api::<ConfigMap>::all(client), watcher::Config::default());
+//! let mut registry = Registry::with_prefix("configmap_controller");
+//! controller.metrics().register(&mut registry);
+//! # Ok(())
+//! # }
+//! ```
+use prometheus_client::{
+    metrics::{
+        counter::Counter,
+        gauge::Gauge,
+        histogram::{exponential_buckets, Histogram},
+    },
+    registry::{Registry, Unit},
+};
+use std::time::Duration;
+
+/// Handle to the metrics of a single [`Controller`](crate::Controller)
+///
+/// Cloning the handle shares the underlying metrics.
+#[derive(Clone, Debug)]
+pub struct Metrics {
+    pub(crate) reconciliations: Counter,
+    pub(crate) reconcile_failures: Counter,
+    pub(crate) reconcile_duration: Histogram,
+    pub(crate) active_reconciliations: Gauge,
+    pub(crate) queue_depth: Gauge,
+    pub(crate) scheduler_delay: Histogram,
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self {
+            reconciliations: Counter::default(),
+            reconcile_failures: Counter::default(),
+            // 1ms up to ~65s
+            reconcile_duration: Histogram::new(exponential_buckets(0.001, 2.0, 17)),
+            active_reconciliations: Gauge::default(),
+            queue_depth: Gauge::default(),
+            scheduler_delay: Histogram::new(exponential_buckets(0.001, 2.0, 17)),
+        }
+    }
+}
+
+impl Metrics {
+    /// Registers all metrics into `registry`
+    ///
+    /// Use [`Registry::sub_registry_with_prefix`] or [`Registry::with_prefix`] to tell apart the metrics of
+    /// multiple controllers.
+    pub fn register(&self, registry: &mut Registry) {
+        registry.register(
+            "reconciliations",
+            "Number of reconciliations that have completed",
+            self.reconciliations.clone(),
+        );
+        registry.register(
+            "reconcile_failures",
+            "Number of reconciliations that have failed",
+            self.reconcile_failures.clone(),
+        );
+        registry.register_with_unit(
+            "reconcile_duration",
+            "Time spent running the reconciler",
+            Unit::Seconds,
+            self.reconcile_duration.clone(),
+        );
+        registry.register(
+            "active_reconciliations",
+            "Number of reconciliations that are currently running",
+            self.active_reconciliations.clone(),
+        );
+        registry.register(
+            "queue_depth",
+            "Number of reconciliations that are scheduled but have not been started yet",
+            self.queue_depth.clone(),
+        );
+        registry.register_with_unit(
+            "scheduler_delay",
+            "Time between a reconciliation being due and it being started",
+            Unit::Seconds,
+            self.scheduler_delay.clone(),
+        );
+    }
+
+    pub(crate) fn reconciled(&self, duration: Duration, failed: bool) {
+        self.reconciliations.inc();
+        if failed {
+            self.reconcile_failures.inc();
+        }
+        self.reconcile_duration.observe(duration.as_secs_f64());
+    }
+
+    pub(crate) fn set_active_reconciliations(&self, active: usize) {
+        self.active_reconciliations
+            .set(active.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub(crate) fn set_queue_depth(&self, depth: usize) {
+        self.queue_depth.set(depth.try_into().unwrap_or(i64::MAX));
+    }
+
+    pub(crate) fn scheduled_after(&self, delay: Duration) {
+        self.scheduler_delay.observe(delay.as_secs_f64());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Metrics;
+    use prometheus_client::{encoding::text::encode, registry::Registry};
+    use std::time::Duration;
+
+    #[test]
+    fn metrics_are_encoded_with_prefix() {
+        let metrics = Metrics::default();
+        metrics.reconciled(Duration::from_millis(5), false);
+        metrics.reconciled(Duration::from_millis(5), true);
+        metrics.set_queue_depth(3);
+
+        let mut registry = Registry::with_prefix("test");
+        metrics.register(&mut registry);
+        let mut encoded = String::new();
+        encode(&mut encoded, &registry).unwrap();
+        assert!(encoded.contains("test_reconciliations_total 2"));
+        assert!(encoded.contains("test_reconcile_failures_total 1"));
+        assert!(encoded.contains("test_queue_depth 3"));
+        assert!(encoded.contains("test_reconcile_duration_seconds_count 2"));
+    }
+}
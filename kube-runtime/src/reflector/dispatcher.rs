@@ -2,7 +2,7 @@ use core::{
     pin::Pin,
     task::{Context, Poll},
 };
-use std::{fmt::Debug, sync::Arc};
+use std::{collections::VecDeque, fmt::Debug, sync::Arc};
 
 use educe::Educe;
 use futures::Stream;
@@ -14,6 +14,25 @@ use async_broadcast::{InactiveReceiver, Receiver, Sender};
 
 use super::Lookup;
 
+/// What a shared [`Writer`] does when its buffer is full, see [`Writer::with_overflow_policy`]
+///
+/// [`Writer`]: crate::reflector::store::Writer
+/// [`Writer::with_overflow_policy`]: crate::reflector::store::Writer::with_overflow_policy
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// Wait until every subscriber has seen the oldest event
+    ///
+    /// Subscribers see every event, but a slow subscriber applies backpressure to the root stream,
+    /// and thereby to every other subscriber.
+    #[default]
+    Block,
+    /// Drop the oldest event to make room for new events
+    ///
+    /// The root stream never waits for subscribers, but subscribers that lag behind by more than the
+    /// buffer size silently skip the dropped events. The store always has the latest state of the objects.
+    DropOldest,
+}
+
 #[derive(Educe)]
 #[educe(Debug(bound("K: Debug, K::DynamicType: Debug")), Clone)]
 // A helper type that holds a broadcast transmitter and a broadcast receiver,
@@ -57,6 +76,12 @@ where
         }
     }
 
+    // Configures whether broadcasts wait for capacity, or drop the oldest event when the buffer is full
+    pub(crate) fn set_overflow_policy(&mut self, policy: OverflowPolicy) {
+        self.dispatch_tx
+            .set_overflow(matches!(policy, OverflowPolicy::DropOldest));
+    }
+
     // Calls broadcast on the channel. Will return when the channel has enough
     // space to send an event.
     pub(crate) async fn broadcast(&mut self, obj_ref: ObjectRef<K>) {
@@ -85,6 +110,16 @@ where
 /// the root stream have been observed. This means [`ReflectHandle`] streams
 /// can still be polled after the root stream has been dropped.
 ///
+/// # Guarantees
+///
+/// - Objects are yielded from the [`Store`] once the root stream has applied them,
+///   so a handle always yields the latest state of an object, which may be newer than the event.
+/// - Deleted objects are not yielded.
+/// - With the default [`OverflowPolicy::Block`], every handle sees every applied object.
+///   With [`OverflowPolicy::DropOldest`], lagging handles may skip objects.
+/// - Handles only see events from after they were created. Use [`ReflectHandle::clone_with_state`]
+///   to attach a subscriber to a running stream that also needs the objects that are already stored.
+///
 /// [`Writer`]: crate::reflector::Writer
 #[pin_project]
 pub struct ReflectHandle<K>
@@ -95,6 +130,8 @@ where
     #[pin]
     rx: Receiver<ObjectRef<K>>,
     reader: Store<K>,
+    // Stored objects to yield before any events, see `clone_with_state`
+    pending: VecDeque<Arc<K>>,
 }
 
 impl<K> Clone for ReflectHandle<K>
@@ -113,9 +150,28 @@ where
     K::DynamicType: Eq + std::hash::Hash + Clone,
 {
     pub(super) fn new(reader: Store<K>, rx: Receiver<ObjectRef<K>>) -> ReflectHandle<K> {
-        Self { rx, reader }
+        Self {
+            rx,
+            reader,
+            pending: VecDeque::new(),
+        }
+    }
+
+    /// Returns a new handle that first yields every object in the [`Store`], and then every new event
+    ///
+    /// This is the shared equivalent of the relist that a new watcher starts with, and lets subscribers
+    /// that attach to an already running stream catch up with the objects that were stored before.
+    /// Objects that change while the state is yielded may be yielded twice.
+    #[must_use]
+    pub fn clone_with_state(&self) -> Self {
+        Self {
+            rx: self.rx.new_receiver(),
+            reader: self.reader.clone(),
+            pending: self.reader.state().into(),
+        }
     }
 
+    /// Returns a reader for the [`Store`] that this handle resolves objects from
     #[must_use]
     pub fn reader(&self) -> Store<K> {
         self.reader.clone()
@@ -131,19 +187,27 @@ where
 
     fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
         let mut this = self.project();
-        match ready!(this.rx.as_mut().poll_next(cx)) {
-            Some(obj_ref) => this
-                .reader
-                .get(&obj_ref)
-                .map_or(Poll::Pending, |obj| Poll::Ready(Some(obj))),
-            None => Poll::Ready(None),
+        if let Some(obj) = this.pending.pop_front() {
+            return Poll::Ready(Some(obj));
+        }
+        loop {
+            match ready!(this.rx.as_mut().poll_next(cx)) {
+                Some(obj_ref) => {
+                    // The object may have been deleted since it was dispatched, in which case
+                    // there is nothing left to yield for it and we move on to the next event
+                    if let Some(obj) = this.reader.get(&obj_ref) {
+                        return Poll::Ready(Some(obj));
+                    }
+                }
+                None => return Poll::Ready(None),
+            }
         }
     }
 }
 
-#[cfg(feature = "unstable-runtime-subscribe")]
 #[cfg(test)]
 pub(crate) mod test {
+    use super::OverflowPolicy;
     use crate::{
         watcher::{Error, Event},
         WatchStreamExt,
@@ -425,4 +489,71 @@ pub(crate) mod test {
     // TODO (matei): tests around cloning subscribers once a watch stream has already
     // been established. This will depend on the interfaces & impl so are left
     // out for now.
+
+    #[tokio::test]
+    async fn reflect_drops_oldest_events_on_overflow() {
+        let foo = testpod("foo");
+        let bar = testpod("bar");
+        let st = stream::iter([Ok(Event::Apply(foo.clone())), Ok(Event::Apply(bar.clone()))]);
+
+        let (_, writer) = reflector::store_shared(1);
+        let writer = writer.with_overflow_policy(OverflowPolicy::DropOldest);
+        let mut subscriber_slow = pin!(writer.subscribe().unwrap());
+        let mut reflect = pin!(st.reflect_shared(writer));
+
+        // The full buffer does not hold back the root stream
+        assert!(matches!(
+            poll!(reflect.next()),
+            Poll::Ready(Some(Ok(Event::Apply(_))))
+        ));
+        assert!(matches!(
+            poll!(reflect.next()),
+            Poll::Ready(Some(Ok(Event::Apply(_))))
+        ));
+        assert!(matches!(poll!(reflect.next()), Poll::Ready(None)));
+
+        // The slow subscriber skipped foo
+        assert_eq!(poll!(subscriber_slow.next()), Poll::Ready(Some(Arc::new(bar))));
+        assert_eq!(poll!(subscriber_slow.next()), Poll::Ready(None));
+    }
+
+    #[tokio::test]
+    async fn late_subscribers_receive_stored_objects() {
+        let foo = testpod("foo");
+        let bar = testpod("bar");
+        let st = stream::iter([
+            Ok(Event::Apply(foo.clone())),
+            Ok(Event::Apply(bar.clone())),
+            Ok(Event::Apply(foo.clone())),
+        ]);
+
+        let (_, writer) = reflector::store_shared(10);
+        let subscriber = writer.subscribe().unwrap();
+        let mut reflect = pin!(st.reflect_shared(writer));
+        for _ in 0..2 {
+            assert!(matches!(
+                poll!(reflect.next()),
+                Poll::Ready(Some(Ok(Event::Apply(_))))
+            ));
+        }
+
+        let mut late = pin!(subscriber.clone_with_state());
+        let mut stored = vec![];
+        for _ in 0..2 {
+            match poll!(late.next()) {
+                Poll::Ready(Some(obj)) => stored.push(obj.metadata.name.clone().unwrap()),
+                other => panic!("expected a stored object, got {other:?}"),
+            }
+        }
+        stored.sort();
+        assert_eq!(stored, ["bar", "foo"]);
+        assert_eq!(poll!(late.next()), Poll::Pending);
+
+        // New events follow the stored objects
+        assert!(matches!(
+            poll!(reflect.next()),
+            Poll::Ready(Some(Ok(Event::Apply(_))))
+        ));
+        assert_eq!(poll!(late.next()), Poll::Ready(Some(Arc::new(foo))));
+    }
 }
@@ -5,15 +5,14 @@ mod object_ref;
 pub mod store;
 
 pub use self::{
-    dispatcher::ReflectHandle,
+    dispatcher::{OverflowPolicy, ReflectHandle},
     object_ref::{Extra as ObjectRefExtra, Lookup, ObjectRef},
 };
 use crate::watcher;
 use async_stream::stream;
 use futures::{Stream, StreamExt};
 use std::hash::Hash;
-#[cfg(feature = "unstable-runtime-subscribe")] pub use store::store_shared;
-pub use store::{store, Store};
+pub use store::{store, store_shared, Store};
 
 /// Cache objects from a [`watcher()`] stream into a local [`Store`]
 ///
@@ -97,6 +96,11 @@ pub use store::{store, Store};
 /// Note that you **cannot drop everything**; you minimally need the spec properties your app relies on.
 /// Additionally, only `labels`, `annotations` and `managed_fields` are safe to drop from `ObjectMeta`.
 ///
+/// 3. Store a projection of the objects with [`projected_reflector`]
+///
+/// When only a few fields are needed, [`Writer::with_projection`](store::Writer::with_projection) stores
+/// a lean type picked from each object instead, while the full objects still flow through the stream.
+///
 /// For more information check out: <https://kube.rs/controllers/optimization/> for graphs and techniques.
 ///
 /// ## Stream sharing
@@ -110,9 +114,9 @@ pub use store::{store, Store};
 /// broadcast an event to all active listeners after caching any object
 /// contained in the event.
 ///
-/// Creating subscribers requires an
-/// [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21)
-/// feature
+/// Subscribers are created with [`Writer::subscribe`](store::Writer::subscribe), and can drive
+/// any number of controllers through [`Controller::for_subscriber`](crate::Controller::for_subscriber)
+/// or the other `_shared_stream` methods of [`Controller`](crate::Controller), all from one watch and one cache.
 pub fn reflector<K, W>(mut writer: store::Writer<K>, stream: W) -> impl Stream<Item = W::Item>
 where
     K: Lookup + Clone,
@@ -134,6 +138,35 @@ where
     }
 }
 
+/// Cache projections of the objects from a [`watcher()`] stream into a local [`Store`]
+///
+/// This is a [`reflector`] that writes to a [`ProjectedWriter`](store::ProjectedWriter), created by
+/// [`Writer::with_projection`](store::Writer::with_projection). Only the projections are stored,
+/// but the raw [`watcher()`] stream of full objects is passed through unmodified,
+/// so that it can still drive controllers and other triggers.
+pub fn projected_reflector<K, P, W>(
+    mut writer: store::ProjectedWriter<K, P>,
+    stream: W,
+) -> impl Stream<Item = W::Item>
+where
+    P: Lookup + Clone,
+    P::DynamicType: Eq + Hash + Clone,
+    W: Stream<Item = watcher::Result<watcher::Event<K>>>,
+{
+    let mut stream = Box::pin(stream);
+    stream! {
+        while let Some(event) = stream.next().await {
+            match event {
+                Ok(ev) => {
+                    writer.apply_and_dispatch(&ev).await;
+                    yield Ok(ev);
+                },
+                Err(ev) => yield Err(ev)
+            }
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::{reflector, store, ObjectRef};
@@ -232,6 +232,57 @@ impl<K: Lookup> ObjectRef<K> {
         }
     }
 
+    /// Create an `ObjectRef` from an `ObjectReference`
+    ///
+    /// Returns `None` if the types do not match, or if the reference has no name.
+    #[must_use]
+    pub fn from_object_reference(reference: &ObjectReference, dyntype: K::DynamicType) -> Option<Self> {
+        if reference.api_version.as_deref() == Some(K::api_version(&dyntype).as_ref())
+            && reference.kind.as_deref() == Some(K::kind(&dyntype).as_ref())
+        {
+            Some(Self {
+                name: reference.name.clone()?,
+                namespace: reference.namespace.clone(),
+                extra: Extra {
+                    resource_version: reference.resource_version.clone(),
+                    uid: reference.uid.clone(),
+                },
+                dyntype,
+            })
+        } else {
+            None
+        }
+    }
+
+    /// Generates an owner reference pointing to the object
+    ///
+    /// Returns `None` if the uid of the object is not known, see [`Extra::uid`].
+    #[must_use]
+    pub fn owner_ref(&self) -> Option<OwnerReference> {
+        Some(OwnerReference {
+            api_version: K::api_version(&self.dyntype).into_owned(),
+            kind: K::kind(&self.dyntype).into_owned(),
+            name: self.name.clone(),
+            uid: self.extra.uid.clone()?,
+            ..OwnerReference::default()
+        })
+    }
+
+    /// Generates a controller owner reference pointing to the object
+    ///
+    /// Like `SetControllerReference` of controller-runtime, this also sets `blockOwnerDeletion`, so that
+    /// foreground deletions of the owner wait for the owned object to be deleted.
+    ///
+    /// Returns `None` if the uid of the object is not known, see [`Extra::uid`].
+    #[must_use]
+    pub fn controller_owner_ref(&self) -> Option<OwnerReference> {
+        Some(OwnerReference {
+            controller: Some(true),
+            block_owner_deletion: Some(true),
+            ..self.owner_ref()?
+        })
+    }
+
     /// Convert into a reference to `K2`
     ///
     /// Note that no checking is done on whether this conversion makes sense. For example, every `Service`
@@ -312,7 +363,7 @@ mod tests {
     use super::{Extra, ObjectRef};
     use k8s_openapi::api::{
         apps::v1::Deployment,
-        core::v1::{Node, Pod},
+        core::v1::{Node, ObjectReference, Pod},
     };
 
     #[test]
@@ -366,4 +417,36 @@ mod tests {
         };
         assert_eq!(hash_value(&minimal), hash_value(&with_extra));
     }
+
+    #[test]
+    fn conversions_should_keep_extra() {
+        let deploy_ref = ObjectRef::<Deployment> {
+            extra: Extra {
+                resource_version: Some("123".to_string()),
+                uid: Some("638ffacd-f666-4402-ba10-7848c66ef576".to_string()),
+            },
+            ..ObjectRef::new("my-deploy").within("my-namespace")
+        };
+
+        let reference = ObjectReference::from(deploy_ref.clone());
+        assert_eq!(reference.api_version.as_deref(), Some("apps/v1"));
+        let converted = ObjectRef::<Deployment>::from_object_reference(&reference, ()).unwrap();
+        assert_eq!(converted, deploy_ref);
+        assert_eq!(
+            converted.extra.resource_version,
+            deploy_ref.extra.resource_version
+        );
+        assert_eq!(converted.extra.uid, deploy_ref.extra.uid);
+        assert_eq!(ObjectRef::<Pod>::from_object_reference(&reference, ()), None);
+
+        let owner_ref = deploy_ref.controller_owner_ref().unwrap();
+        assert_eq!(owner_ref.kind, "Deployment");
+        assert_eq!(owner_ref.uid, "638ffacd-f666-4402-ba10-7848c66ef576");
+        assert_eq!(owner_ref.controller, Some(true));
+        assert_eq!(owner_ref.block_owner_deletion, Some(true));
+        let owner = ObjectRef::<Deployment>::from_owner_ref(Some("my-namespace"), &owner_ref, ()).unwrap();
+        assert_eq!(owner, deploy_ref);
+        assert_eq!(owner.extra.uid, deploy_ref.extra.uid);
+        assert_eq!(ObjectRef::<Deployment>::new("my-deploy").owner_ref(), None);
+    }
 }
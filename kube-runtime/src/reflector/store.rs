@@ -1,17 +1,167 @@
-use super::{dispatcher::Dispatcher, Lookup, ObjectRef};
-#[cfg(feature = "unstable-runtime-subscribe")]
-use crate::reflector::ReflectHandle;
+use super::{
+    dispatcher::{Dispatcher, OverflowPolicy},
+    Lookup, ObjectRef,
+};
 use crate::{
+    reflector::ReflectHandle,
     utils::delayed_init::{self, DelayedInit},
     watcher,
 };
-use ahash::AHashMap;
+use ahash::{AHashMap, AHashSet};
 use educe::Educe;
 use parking_lot::RwLock;
-use std::{fmt::Debug, hash::Hash, sync::Arc};
+use std::{
+    cmp::Ordering,
+    fmt::{self, Debug},
+    hash::Hash,
+    sync::Arc,
+    time::{Duration, Instant},
+};
 use thiserror::Error;
 
 type Cache<K> = Arc<RwLock<AHashMap<ObjectRef<K>, Arc<K>>>>;
+type Indices<K> = Arc<RwLock<AHashMap<String, Index<K>>>>;
+type IndexFn<K> = Arc<dyn Fn(&K) -> Vec<String> + Send + Sync>;
+type ProjectFn<K, P> = Box<dyn Fn(&K) -> P + Send + Sync>;
+
+/// A secondary index over the objects in a [`Store`], registered through [`Writer::with_index`]
+#[derive(Educe)]
+#[educe(Debug(bound("K::DynamicType: Debug")))]
+struct Index<K: 'static + Lookup>
+where
+    K::DynamicType: Eq + Hash,
+{
+    #[educe(Debug(ignore))]
+    index_fn: IndexFn<K>,
+    entries: AHashMap<String, AHashSet<ObjectRef<K>>>,
+}
+
+impl<K: 'static + Lookup> Index<K>
+where
+    K::DynamicType: Eq + Hash + Clone,
+{
+    fn insert(&mut self, key: &ObjectRef<K>, obj: &K) {
+        for value in (self.index_fn)(obj) {
+            self.entries.entry(value).or_default().insert(key.clone());
+        }
+    }
+
+    fn remove(&mut self, key: &ObjectRef<K>, obj: &K) {
+        for value in (self.index_fn)(obj) {
+            if let Some(refs) = self.entries.get_mut(&value) {
+                refs.remove(key);
+                if refs.is_empty() {
+                    self.entries.remove(&value);
+                }
+            }
+        }
+    }
+
+    fn rebuild(&mut self, store: &AHashMap<ObjectRef<K>, Arc<K>>) {
+        self.entries = AHashMap::new();
+        for (key, obj) in store {
+            self.insert(key, obj);
+        }
+    }
+}
+
+/// Decides which objects a [`Writer`] evicts when it exceeds the bounds of its [`Eviction`] policy
+///
+/// Closures of `Fn(&K) -> bool` can be used as strategies that only pick which objects may be evicted.
+pub trait EvictionStrategy<K>: Send + Sync {
+    /// Whether `obj` may be evicted at all
+    fn is_evictable(&self, obj: &K) -> bool;
+
+    /// Orders two evictable objects along with when they were last applied, where lesser objects are evicted first
+    ///
+    /// Defaults to evicting the least recently applied objects first.
+    fn order(&self, a: (&K, Instant), b: (&K, Instant)) -> Ordering {
+        a.1.cmp(&b.1)
+    }
+}
+
+impl<K, F> EvictionStrategy<K> for F
+where
+    F: Fn(&K) -> bool + Send + Sync,
+{
+    fn is_evictable(&self, obj: &K) -> bool {
+        self(obj)
+    }
+}
+
+/// An eviction policy that bounds the number of objects in a [`Store`], registered through [`Writer::with_eviction`]
+///
+/// ```
+/// use k8s_openapi::api::batch::v1::Job;
+/// use kube::runtime::reflector::store::{Eviction, Writer};
+/// use std::time::Duration;
+///
+/// // Only keep up to 1000 finished jobs, and forget them after an hour without updates
+/// let eviction = Eviction::new()
+///     .max_objects(1000)
+///     .max_idle(Duration::from_secs(60 * 60))
+///     .strategy(|job: &Job| job.status.as_ref().is_some_and(|s| s.completion_time.is_some()));
+/// let writer = Writer::<Job>::default().with_eviction(eviction);
+/// ```
+pub struct Eviction<K> {
+    max_objects: Option<usize>,
+    max_idle: Option<Duration>,
+    strategy: Arc<dyn EvictionStrategy<K>>,
+}
+
+impl<K: 'static> Eviction<K> {
+    /// Creates a policy that does not evict anything until bounds are set
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            max_objects: None,
+            max_idle: None,
+            strategy: Arc::new(|_: &K| true),
+        }
+    }
+
+    /// Evict objects when the store holds more than `max_objects`
+    ///
+    /// The store is then trimmed to 90% of `max_objects`, so that evictions are batched.
+    #[must_use]
+    pub fn max_objects(mut self, max_objects: usize) -> Self {
+        self.max_objects = Some(max_objects);
+        self
+    }
+
+    /// Evict objects that have not been applied for `max_idle`
+    ///
+    /// Idle objects are only evicted as watcher events are applied to the store.
+    #[must_use]
+    pub fn max_idle(mut self, max_idle: Duration) -> Self {
+        self.max_idle = Some(max_idle);
+        self
+    }
+
+    /// Set the strategy that picks which objects are evicted
+    ///
+    /// Defaults to evicting any object, least recently applied first.
+    #[must_use]
+    pub fn strategy(mut self, strategy: impl EvictionStrategy<K> + 'static) -> Self {
+        self.strategy = Arc::new(strategy);
+        self
+    }
+}
+
+impl<K: 'static> Default for Eviction<K> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<K> Debug for Eviction<K> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Eviction")
+            .field("max_objects", &self.max_objects)
+            .field("max_idle", &self.max_idle)
+            .finish_non_exhaustive()
+    }
+}
 
 /// A writable Store handle
 ///
@@ -23,11 +173,21 @@ where
     K::DynamicType: Eq + Hash + Clone,
 {
     store: Cache<K>,
+    indices: Indices<K>,
     buffer: AHashMap<ObjectRef<K>, Arc<K>>,
+    /// Apply relists directly to the store, only tracking which objects were seen
+    incremental_relists: bool,
+    seen: AHashSet<ObjectRef<K>>,
     dyntype: K::DynamicType,
     ready_tx: Option<delayed_init::Initializer<()>>,
     ready_rx: Arc<DelayedInit<()>>,
     dispatcher: Option<Dispatcher<K>>,
+    eviction: Option<Eviction<K>>,
+    /// When each object was last applied, only tracked with an eviction policy
+    applied: AHashMap<ObjectRef<K>, Instant>,
+    last_idle_sweep: Option<Instant>,
+    /// The size above which the store is swept again, when too few objects could be evicted by the last sweep
+    evict_above: usize,
 }
 
 impl<K: 'static + Lookup + Clone> Writer<K>
@@ -42,11 +202,18 @@ where
         let (ready_tx, ready_rx) = DelayedInit::new();
         Writer {
             store: Default::default(),
+            indices: Default::default(),
             buffer: Default::default(),
+            incremental_relists: false,
+            seen: Default::default(),
             dyntype,
             ready_tx: Some(ready_tx),
             ready_rx: Arc::new(ready_rx),
             dispatcher: None,
+            eviction: None,
+            applied: AHashMap::new(),
+            last_idle_sweep: None,
+            evict_above: 0,
         }
     }
 
@@ -59,17 +226,153 @@ where
     ///
     /// If the dynamic type is default-able (for example when writer is used with
     /// `k8s_openapi` types) you can use `Default` instead.
-    #[cfg(feature = "unstable-runtime-subscribe")]
     pub fn new_shared(buf_size: usize, dyntype: K::DynamicType) -> Self {
         let (ready_tx, ready_rx) = DelayedInit::new();
         Writer {
             store: Default::default(),
+            indices: Default::default(),
             buffer: Default::default(),
+            incremental_relists: false,
+            seen: Default::default(),
             dyntype,
             ready_tx: Some(ready_tx),
             ready_rx: Arc::new(ready_rx),
             dispatcher: Some(Dispatcher::new(buf_size)),
+            eviction: None,
+            applied: AHashMap::new(),
+            last_idle_sweep: None,
+            evict_above: 0,
+        }
+    }
+
+    /// Register a secondary index on the store
+    ///
+    /// `index_fn` computes the index values of an object, such as its node name or the UIDs of its owners.
+    /// Objects can then be looked up by any of these values through [`Store::by_index`], instead of
+    /// scanning the whole store. The index is kept up to date as watcher events are applied.
+    ///
+    /// Registering an index under an existing name replaces it.
+    ///
+    /// ```
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::runtime::reflector::store::Writer;
+    ///
+    /// let writer = Writer::<Pod>::default()
+    ///     .with_index("byNode", |pod| pod.spec.as_ref().and_then(|spec| spec.node_name.clone()));
+    /// let store = writer.as_reader();
+    /// assert!(store.by_index("byNode", "node-1").is_empty());
+    /// ```
+    #[must_use]
+    pub fn with_index<F, I>(self, name: impl Into<String>, index_fn: F) -> Self
+    where
+        F: Fn(&K) -> I + Send + Sync + 'static,
+        I: IntoIterator<Item = String>,
+    {
+        let index_fn: IndexFn<K> =
+            Arc::new(move |obj: &K| -> Vec<String> { index_fn(obj).into_iter().collect() });
+        let mut index = Index {
+            index_fn,
+            entries: AHashMap::new(),
+        };
+        {
+            let store = self.store.read();
+            index.rebuild(&store);
+            self.indices.write().insert(name.into(), index);
         }
+        self
+    }
+
+    /// Apply relists to the store incrementally, rather than buffering them
+    ///
+    /// By default, the objects of a relist are buffered until [`watcher::Event::InitDone`], and then swapped
+    /// into the store at once. This keeps the store consistent, but holds two full copies of the objects in memory
+    /// during every relist. With incremental relists, objects are written to the store as each page arrives,
+    /// and objects that were not seen again are removed at the end of the relist, so only their keys are buffered.
+    ///
+    /// Readers can observe a mix of old and relisted objects while a relist is in progress,
+    /// including objects that have since been deleted. Combine with [`watcher::Config::page_size`]
+    /// to bound the memory used by the watcher itself for each page.
+    #[must_use]
+    pub fn incremental_relists(mut self) -> Self {
+        self.incremental_relists = true;
+        self
+    }
+
+    /// Bound the memory use of the store by evicting objects according to an [`Eviction`] policy
+    ///
+    /// This is meant for high churn resources like `Event`s or finished `Job`s, where a complete view is not needed.
+    /// Evicted objects are missing from the store until they are applied again by the watcher,
+    /// so readers can no longer assume that the store contains every object.
+    #[must_use]
+    pub fn with_eviction(mut self, eviction: Eviction<K>) -> Self {
+        let now = Instant::now();
+        self.applied = self.store.read().keys().map(|key| (key.clone(), now)).collect();
+        self.eviction = Some(eviction);
+        self
+    }
+
+    /// Store a projection of the objects of another kind `S`, rather than the objects themselves
+    ///
+    /// Only the fields that `project` picks are kept in memory, which can shrink the store by an order
+    /// of magnitude for big objects such as `Pod`s. The projected type must implement [`Lookup`],
+    /// so its name and namespace must be part of the projection.
+    ///
+    /// The returned [`ProjectedWriter`] is driven by [`projected_reflector`](crate::reflector::projected_reflector),
+    /// which passes the full objects through, so they can still trigger controllers.
+    ///
+    /// ```
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::runtime::reflector::{store::Writer, Lookup};
+    /// use std::borrow::Cow;
+    ///
+    /// #[derive(Clone)]
+    /// struct PodPlacement {
+    ///     name: String,
+    ///     namespace: Option<String>,
+    ///     node: Option<String>,
+    /// }
+    ///
+    /// impl Lookup for PodPlacement {
+    ///     type DynamicType = ();
+    ///
+    ///     fn kind(_: &()) -> Cow<'_, str> { "Pod".into() }
+    ///     fn group(_: &()) -> Cow<'_, str> { "".into() }
+    ///     fn version(_: &()) -> Cow<'_, str> { "v1".into() }
+    ///     fn plural(_: &()) -> Cow<'_, str> { "pods".into() }
+    ///     fn name(&self) -> Option<Cow<'_, str>> { Some(self.name.as_str().into()) }
+    ///     fn namespace(&self) -> Option<Cow<'_, str>> { self.namespace.as_deref().map(Into::into) }
+    ///     fn resource_version(&self) -> Option<Cow<'_, str>> { None }
+    ///     fn uid(&self) -> Option<Cow<'_, str>> { None }
+    /// }
+    ///
+    /// let writer = Writer::<PodPlacement>::default().with_projection(|pod: &Pod| PodPlacement {
+    ///     name: pod.metadata.name.clone().unwrap_or_default(),
+    ///     namespace: pod.metadata.namespace.clone(),
+    ///     node: pod.spec.as_ref().and_then(|spec| spec.node_name.clone()),
+    /// });
+    /// let store = writer.as_reader();
+    /// ```
+    #[must_use]
+    pub fn with_projection<S>(
+        self,
+        project: impl Fn(&S) -> K + Send + Sync + 'static,
+    ) -> ProjectedWriter<S, K> {
+        ProjectedWriter {
+            writer: self,
+            project: Box::new(project),
+        }
+    }
+
+    /// Configure what happens when the buffer of a shared writer is full
+    ///
+    /// Defaults to [`OverflowPolicy::Block`], which applies backpressure until every subscriber has caught up.
+    /// This has no effect on writers that are not shared, see [`Writer::new_shared`].
+    #[must_use]
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        if let Some(dispatcher) = &mut self.dispatcher {
+            dispatcher.set_overflow_policy(policy);
+        }
+        self
     }
 
     /// Return a read handle to the store
@@ -80,6 +383,7 @@ where
     pub fn as_reader(&self) -> Store<K> {
         Store {
             store: self.store.clone(),
+            indices: self.indices.clone(),
             ready_rx: self.ready_rx.clone(),
         }
     }
@@ -91,7 +395,6 @@ where
     ///
     /// This function returns a `Some` when the [`Writer`] is constructed through
     /// [`Writer::new_shared`] or [`store_shared`], and a `None` otherwise.
-    #[cfg(feature = "unstable-runtime-subscribe")]
     pub fn subscribe(&self) -> Option<ReflectHandle<K>> {
         self.dispatcher
             .as_ref()
@@ -103,27 +406,73 @@ where
         match event {
             watcher::Event::Apply(obj) => {
                 let key = obj.to_object_ref(self.dyntype.clone());
-                let obj = Arc::new(obj.clone());
-                self.store.write().insert(key, obj);
+                self.upsert(key, obj);
+                self.evict();
             }
             watcher::Event::Delete(obj) => {
                 let key = obj.to_object_ref(self.dyntype.clone());
-                self.store.write().remove(&key);
+                self.applied.remove(&key);
+                let mut store = self.store.write();
+                if let Some(old) = store.remove(&key) {
+                    for index in self.indices.write().values_mut() {
+                        index.remove(&key, &old);
+                    }
+                }
             }
             watcher::Event::Init => {
                 self.buffer = AHashMap::new();
+                self.seen = AHashSet::new();
+            }
+            watcher::Event::InitApply(obj) if self.incremental_relists => {
+                let key = obj.to_object_ref(self.dyntype.clone());
+                self.seen.insert(key.clone());
+                self.upsert(key, obj);
+                self.evict();
             }
             watcher::Event::InitApply(obj) => {
                 let key = obj.to_object_ref(self.dyntype.clone());
                 let obj = Arc::new(obj.clone());
                 self.buffer.insert(key, obj);
             }
+            watcher::Event::InitDone if self.incremental_relists => {
+                let seen = std::mem::take(&mut self.seen);
+                let mut store = self.store.write();
+                let mut indices = self.indices.write();
+                // Remove the objects that were deleted since the previous list
+                store.retain(|key, obj| {
+                    let keep = seen.contains(key);
+                    if !keep {
+                        for index in indices.values_mut() {
+                            index.remove(key, obj);
+                        }
+                    }
+                    keep
+                });
+                self.applied.retain(|key, _| seen.contains(key));
+                drop((store, indices));
+                self.evict();
+
+                if let Some(ready_tx) = self.ready_tx.take() {
+                    ready_tx.init(())
+                }
+            }
             watcher::Event::InitDone => {
                 let mut store = self.store.write();
 
                 // Swap the buffer into the store
                 std::mem::swap(&mut *store, &mut self.buffer);
 
+                // Reindex the new state of the store
+                for index in self.indices.write().values_mut() {
+                    index.rebuild(&store);
+                }
+                if self.eviction.is_some() {
+                    let now = Instant::now();
+                    self.applied = store.keys().map(|key| (key.clone(), now)).collect();
+                }
+                drop(store);
+                self.evict();
+
                 // Clear the buffer
                 // This is preferred over self.buffer.clear(), as clear() will keep the allocated memory for reuse.
                 // This way, the old buffer is dropped.
@@ -137,6 +486,92 @@ where
         }
     }
 
+    /// Insert or replace an object in the store, keeping the indices up to date
+    fn upsert(&mut self, key: ObjectRef<K>, obj: &K) {
+        let obj = Arc::new(obj.clone());
+        let mut store = self.store.write();
+        let mut indices = self.indices.write();
+        for index in indices.values_mut() {
+            if let Some(old) = store.get(&key) {
+                index.remove(&key, old);
+            }
+            index.insert(&key, &obj);
+        }
+        if self.eviction.is_some() {
+            self.applied.insert(key.clone(), Instant::now());
+        }
+        store.insert(key, obj);
+    }
+
+    /// Evict objects that exceed the bounds of the eviction policy, if any
+    ///
+    /// Objects are only looked at once the store has outgrown its bound or an idle sweep is due,
+    /// and only under a read lock, so that readers are blocked just while the evicted objects are removed.
+    fn evict(&mut self) {
+        let Some(eviction) = &self.eviction else {
+            return;
+        };
+        let now = Instant::now();
+        let store = self.store.read();
+        // Trim down to 90% of the bound, so that the store isn't swept again on every insert
+        let excess = eviction
+            .max_objects
+            .filter(|max| store.len() > self.evict_above.max(*max))
+            .map_or(0, |max| store.len() - (max - max / 10));
+        let max_idle = eviction.max_idle.filter(|max_idle| {
+            self.last_idle_sweep
+                .map_or(true, |last| now.duration_since(last) >= *max_idle / 4)
+        });
+        if excess == 0 && max_idle.is_none() {
+            return;
+        }
+        if max_idle.is_some() {
+            self.last_idle_sweep = Some(now);
+        }
+
+        let strategy = &eviction.strategy;
+        let mut candidates = self
+            .applied
+            .iter()
+            .filter_map(|(key, applied)| Some((key, &**store.get(key)?, *applied)))
+            .filter(|(_, obj, _)| strategy.is_evictable(obj))
+            .collect::<Vec<_>>();
+        let mut evicted = Vec::new();
+        if let Some(max_idle) = max_idle {
+            candidates.retain(|(key, _, applied)| {
+                let idle = now.duration_since(*applied) >= max_idle;
+                if idle {
+                    evicted.push((*key).clone());
+                }
+                !idle
+            });
+        }
+        let excess = excess.saturating_sub(evicted.len());
+        if excess > 0 && excess < candidates.len() {
+            // Only the objects to evict need to be found, rather than sorting all of them
+            candidates.select_nth_unstable_by(excess - 1, |(_, a, a_applied), (_, b, b_applied)| {
+                strategy.order((*a, *a_applied), (*b, *b_applied))
+            });
+        }
+        evicted.extend(candidates.into_iter().take(excess).map(|(key, _, _)| key.clone()));
+        if let Some(max) = eviction.max_objects {
+            // If the strategy protected too many objects, wait for the store to grow before trying again
+            self.evict_above = store.len() - evicted.len() + (max / 10).max(1);
+        }
+        drop(store);
+
+        let mut store = self.store.write();
+        let mut indices = self.indices.write();
+        for key in evicted {
+            self.applied.remove(&key);
+            if let Some(old) = store.remove(&key) {
+                for index in indices.values_mut() {
+                    index.remove(&key, &old);
+                }
+            }
+        }
+    }
+
     /// Broadcast an event to any downstream listeners subscribed on the store
     pub(crate) async fn dispatch_event(&mut self, event: &watcher::Event<K>) {
         if let Some(ref mut dispatcher) = self.dispatcher {
@@ -175,6 +610,57 @@ where
     }
 }
 
+/// A [`Writer`] that stores projections `P` of objects of kind `K`, created by [`Writer::with_projection`]
+#[derive(Educe)]
+#[educe(Debug(bound("P: Debug, P::DynamicType: Debug")))]
+pub struct ProjectedWriter<K, P: 'static + Lookup + Clone>
+where
+    P::DynamicType: Eq + Hash + Clone,
+{
+    writer: Writer<P>,
+    #[educe(Debug(ignore))]
+    project: ProjectFn<K, P>,
+}
+
+impl<K, P: 'static + Lookup + Clone> ProjectedWriter<K, P>
+where
+    P::DynamicType: Eq + Hash + Clone,
+{
+    /// Return a read handle to the store of projections, see [`Writer::as_reader`]
+    #[must_use]
+    pub fn as_reader(&self) -> Store<P> {
+        self.writer.as_reader()
+    }
+
+    /// Return a handle to a subscriber of the projections, see [`Writer::subscribe`]
+    pub fn subscribe(&self) -> Option<ReflectHandle<P>> {
+        self.writer.subscribe()
+    }
+
+    /// Applies the projection of a single watcher event to the store
+    pub fn apply_watcher_event(&mut self, event: &watcher::Event<K>) {
+        let event = self.project(event);
+        self.writer.apply_watcher_event(&event);
+    }
+
+    /// Applies the projection of a watcher event, and broadcasts it to any subscribers
+    pub(crate) async fn apply_and_dispatch(&mut self, event: &watcher::Event<K>) {
+        let event = self.project(event);
+        self.writer.apply_watcher_event(&event);
+        self.writer.dispatch_event(&event).await;
+    }
+
+    fn project(&self, event: &watcher::Event<K>) -> watcher::Event<P> {
+        match event {
+            watcher::Event::Apply(obj) => watcher::Event::Apply((self.project)(obj)),
+            watcher::Event::Delete(obj) => watcher::Event::Delete((self.project)(obj)),
+            watcher::Event::Init => watcher::Event::Init,
+            watcher::Event::InitApply(obj) => watcher::Event::InitApply((self.project)(obj)),
+            watcher::Event::InitDone => watcher::Event::InitDone,
+        }
+    }
+}
+
 /// A readable cache of Kubernetes objects of kind `K`
 ///
 /// Cloning will produce a new reference to the same backing store.
@@ -183,11 +669,13 @@ where
 /// use `Writer::as_reader()` instead.
 #[derive(Educe)]
 #[educe(Debug(bound("K: Debug, K::DynamicType: Debug")), Clone)]
+#[allow(clippy::struct_field_names)]
 pub struct Store<K: 'static + Lookup>
 where
     K::DynamicType: Hash + Eq,
 {
     store: Cache<K>,
+    indices: Indices<K>,
     ready_rx: Arc<DelayedInit<()>>,
 }
 
@@ -257,6 +745,24 @@ where
             .cloned()
     }
 
+    /// Retrieve `clone()`s of the entries whose `index` contains `value`
+    ///
+    /// The index must have been registered with [`Writer::with_index`], an unknown index
+    /// returns no entries. The same staleness caveats as [`Store::get`] apply.
+    #[must_use]
+    pub fn by_index(&self, index: &str, value: &str) -> Vec<Arc<K>> {
+        let store = self.store.read();
+        let indices = self.indices.read();
+        indices
+            .get(index)
+            .and_then(|index| index.entries.get(value))
+            .into_iter()
+            .flatten()
+            .filter_map(|key| store.get(key))
+            .cloned()
+            .collect()
+    }
+
     /// Return the number of elements in the store
     #[must_use]
     pub fn len(&self) -> usize {
@@ -295,7 +801,6 @@ where
 /// full, backpressure will be applied by waiting for capacity.
 #[must_use]
 #[allow(clippy::module_name_repetitions)]
-#[cfg(feature = "unstable-runtime-subscribe")]
 pub fn store_shared<K>(buf_size: usize) -> (Store<K>, Writer<K>)
 where
     K: Lookup + Clone + 'static,
@@ -308,10 +813,21 @@ where
 
 #[cfg(test)]
 mod tests {
-    use super::{store, Writer};
-    use crate::{reflector::ObjectRef, watcher};
+    use super::{store, Eviction, Writer};
+    use crate::{
+        reflector::{Lookup, ObjectRef},
+        watcher,
+    };
     use k8s_openapi::api::core::v1::ConfigMap;
     use kube_client::api::ObjectMeta;
+    use std::{
+        borrow::Cow,
+        sync::{
+            atomic::{AtomicUsize, Ordering},
+            Arc,
+        },
+        time::Duration,
+    };
 
     #[test]
     fn should_allow_getting_namespaced_object_by_namespaced_ref() {
@@ -408,4 +924,235 @@ mod tests {
         let found = reader.find(|k| k.metadata.generation == Some(1234));
         assert_eq!(found.as_deref(), Some(&target_cm));
     }
+
+    #[test]
+    fn index_tracks_watcher_events() {
+        let cm = |name: &str, owner: &str| ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("ns".to_string()),
+                labels: Some([("owner".to_string(), owner.to_string())].into()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        let mut writer = Writer::<ConfigMap>::default().with_index("byOwner", |obj| {
+            obj.metadata
+                .labels
+                .as_ref()
+                .and_then(|labels| labels.get("owner").cloned())
+        });
+        let reader = writer.as_reader();
+
+        writer.apply_watcher_event(&watcher::Event::Apply(cm("a", "alice")));
+        writer.apply_watcher_event(&watcher::Event::Apply(cm("b", "alice")));
+        assert_eq!(reader.by_index("byOwner", "alice").len(), 2);
+
+        // Updates move the object to its new index value
+        writer.apply_watcher_event(&watcher::Event::Apply(cm("b", "bob")));
+        assert_eq!(reader.by_index("byOwner", "alice").len(), 1);
+        assert_eq!(reader.by_index("byOwner", "bob").as_slice(), &[Arc::new(cm(
+            "b", "bob"
+        ))]);
+
+        writer.apply_watcher_event(&watcher::Event::Delete(cm("a", "alice")));
+        assert!(reader.by_index("byOwner", "alice").is_empty());
+
+        // Relists rebuild the index from scratch
+        writer.apply_watcher_event(&watcher::Event::Init);
+        writer.apply_watcher_event(&watcher::Event::InitApply(cm("c", "alice")));
+        writer.apply_watcher_event(&watcher::Event::InitDone);
+        assert!(reader.by_index("byOwner", "bob").is_empty());
+        assert_eq!(reader.by_index("byOwner", "alice").as_slice(), &[Arc::new(cm(
+            "c", "alice"
+        ))]);
+        assert!(reader.by_index("unknown", "alice").is_empty());
+    }
+
+    #[test]
+    fn incremental_relists_apply_pages_directly() {
+        let cm = |name: &str, data: &str| ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("ns".to_string()),
+                ..ObjectMeta::default()
+            },
+            data: Some([("key".to_string(), data.to_string())].into()),
+            ..ConfigMap::default()
+        };
+        let mut writer = Writer::<ConfigMap>::default().incremental_relists();
+        let reader = writer.as_reader();
+
+        writer.apply_watcher_event(&watcher::Event::Init);
+        writer.apply_watcher_event(&watcher::Event::InitApply(cm("a", "1")));
+        writer.apply_watcher_event(&watcher::Event::InitApply(cm("b", "1")));
+        writer.apply_watcher_event(&watcher::Event::InitDone);
+        assert_eq!(reader.len(), 2);
+
+        // Relisted objects are visible before the relist completes
+        writer.apply_watcher_event(&watcher::Event::Init);
+        writer.apply_watcher_event(&watcher::Event::InitApply(cm("a", "2")));
+        let a = ObjectRef::new("a").within("ns");
+        assert_eq!(reader.get(&a).as_deref(), Some(&cm("a", "2")));
+        assert_eq!(reader.len(), 2);
+
+        // Objects that were not relisted are removed when it completes
+        writer.apply_watcher_event(&watcher::Event::InitDone);
+        assert_eq!(reader.state(), vec![Arc::new(cm("a", "2"))]);
+    }
+
+    #[test]
+    fn eviction_bounds_store_size() {
+        let cm = |name: &str, keep: bool| ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("ns".to_string()),
+                labels: keep.then(|| [("keep".to_string(), "true".to_string())].into()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        let eviction = Eviction::new()
+            .max_objects(10)
+            .strategy(|cm: &ConfigMap| cm.metadata.labels.is_none());
+        let mut writer = Writer::<ConfigMap>::default().with_eviction(eviction);
+        let reader = writer.as_reader();
+
+        writer.apply_watcher_event(&watcher::Event::Apply(cm("keep", true)));
+        for i in 0..10 {
+            writer.apply_watcher_event(&watcher::Event::Apply(cm(&i.to_string(), false)));
+        }
+        // The store is trimmed to 90% of the bound, without evicting objects the strategy protects
+        assert_eq!(reader.len(), 9);
+        assert!(reader.get(&ObjectRef::new("keep").within("ns")).is_some());
+    }
+
+    #[test]
+    fn eviction_does_not_sweep_protected_stores_on_every_apply() {
+        let cm = |name: String| ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name),
+                namespace: Some("ns".to_string()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        let checked = Arc::new(AtomicUsize::new(0));
+        let eviction = Eviction::new().max_objects(100).strategy({
+            let checked = checked.clone();
+            move |_: &ConfigMap| {
+                checked.fetch_add(1, Ordering::Relaxed);
+                false
+            }
+        });
+        let mut writer = Writer::<ConfigMap>::default().with_eviction(eviction);
+        let reader = writer.as_reader();
+
+        for i in 0..200 {
+            writer.apply_watcher_event(&watcher::Event::Apply(cm(i.to_string())));
+        }
+        assert_eq!(reader.len(), 200);
+        // Sweeping on every apply past the bound would check more than 15000 objects
+        assert!(checked.load(Ordering::Relaxed) < 2000);
+    }
+
+    #[test]
+    fn eviction_removes_idle_objects() {
+        let cm = |name: &str| ConfigMap {
+            metadata: ObjectMeta {
+                name: Some(name.to_string()),
+                namespace: Some("ns".to_string()),
+                ..ObjectMeta::default()
+            },
+            ..ConfigMap::default()
+        };
+        let mut writer =
+            Writer::<ConfigMap>::default().with_eviction(Eviction::new().max_idle(Duration::ZERO));
+        let reader = writer.as_reader();
+
+        writer.apply_watcher_event(&watcher::Event::Init);
+        writer.apply_watcher_event(&watcher::Event::InitApply(cm("a")));
+        writer.apply_watcher_event(&watcher::Event::InitDone);
+        assert!(reader.is_empty());
+
+        // Objects that are never idle are kept
+        let mut writer = Writer::<ConfigMap>::default()
+            .with_eviction(Eviction::new().max_idle(Duration::from_secs(60 * 60)));
+        let reader = writer.as_reader();
+        writer.apply_watcher_event(&watcher::Event::Apply(cm("a")));
+        writer.apply_watcher_event(&watcher::Event::Apply(cm("b")));
+        assert_eq!(reader.len(), 2);
+    }
+
+    #[derive(Clone, Debug, PartialEq)]
+    struct Keys {
+        name: String,
+        keys: Vec<String>,
+    }
+
+    impl Lookup for Keys {
+        type DynamicType = ();
+
+        fn kind(_dyntype: &()) -> Cow<'_, str> {
+            "ConfigMap".into()
+        }
+
+        fn group(_dyntype: &()) -> Cow<'_, str> {
+            "".into()
+        }
+
+        fn version(_dyntype: &()) -> Cow<'_, str> {
+            "v1".into()
+        }
+
+        fn plural(_dyntype: &()) -> Cow<'_, str> {
+            "configmaps".into()
+        }
+
+        fn name(&self) -> Option<Cow<'_, str>> {
+            Some(self.name.as_str().into())
+        }
+
+        fn namespace(&self) -> Option<Cow<'_, str>> {
+            None
+        }
+
+        fn resource_version(&self) -> Option<Cow<'_, str>> {
+            None
+        }
+
+        fn uid(&self) -> Option<Cow<'_, str>> {
+            None
+        }
+    }
+
+    #[test]
+    fn projected_writer_should_store_projections() {
+        let mut writer = Writer::<Keys>::default().with_projection(|cm: &ConfigMap| Keys {
+            name: cm.metadata.name.clone().unwrap_or_default(),
+            keys: cm.data.iter().flatten().map(|(key, _)| key.clone()).collect(),
+        });
+        let store = writer.as_reader();
+        let cm = ConfigMap {
+            metadata: ObjectMeta {
+                name: Some("settings".into()),
+                ..ObjectMeta::default()
+            },
+            data: Some([("debug".to_string(), "a long value".to_string())].into()),
+            ..ConfigMap::default()
+        };
+
+        writer.apply_watcher_event(&watcher::Event::Apply(cm.clone()));
+        let key = ObjectRef::<Keys>::new("settings");
+        assert_eq!(
+            store.get(&key).as_deref(),
+            Some(&Keys {
+                name: "settings".into(),
+                keys: vec!["debug".into()],
+            })
+        );
+
+        writer.apply_watcher_event(&watcher::Event::Delete(cm));
+        assert_eq!(store.get(&key), None);
+    }
 }
@@ -4,7 +4,7 @@ use futures::{stream::Fuse, Stream, StreamExt};
 use hashbrown::{hash_map::RawEntryMut, HashMap};
 use pin_project::pin_project;
 use std::{
-    collections::HashSet,
+    cmp::Reverse,
     hash::Hash,
     pin::Pin,
     task::{Context, Poll},
@@ -13,6 +13,8 @@ use std::{
 use tokio::time::Instant;
 use tokio_util::time::delay_queue::{self, DelayQueue};
 
+#[cfg(feature = "metrics")] use crate::metrics::Metrics;
+
 /// A request to re-emit `message` at a given `Instant` (`run_at`).
 #[derive(Debug)]
 pub struct ScheduleRequest<T> {
@@ -41,7 +43,9 @@ pub struct Scheduler<T, R> {
     /// `scheduled` is considered to hold the "canonical" representation of the message.
     scheduled: HashMap<T, ScheduledEntry>,
     /// Messages that are scheduled to have happened, but have been held using `hold_unless`.
-    pending: HashSet<T>,
+    ///
+    /// Maps each message to the time at which it was originally due.
+    pending: HashMap<T, Instant>,
     /// Incoming queue of scheduling requests.
     #[pin]
     requests: Fuse<R>,
@@ -51,6 +55,11 @@ pub struct Scheduler<T, R> {
     /// for a request to be emitted, if the scheduler is "uninterrupted" for the configured
     /// debounce period. Its primary purpose to deduplicate requests that expire instantly.
     debounce: Duration,
+    /// Ranks the messages that are due at the same time, see [`Scheduler::with_priority`].
+    priority: Option<fn(&T) -> i32>,
+    /// Metrics to record the queue depth and scheduling delay into, if any.
+    #[cfg(feature = "metrics")]
+    metrics: Option<Metrics>,
 }
 
 impl<T, R: Stream> Scheduler<T, R> {
@@ -58,11 +67,34 @@ impl<T, R: Stream> Scheduler<T, R> {
         Self {
             queue: DelayQueue::new(),
             scheduled: HashMap::new(),
-            pending: HashSet::new(),
+            pending: HashMap::new(),
             requests: requests.fuse(),
             debounce,
+            priority: None,
+            #[cfg(feature = "metrics")]
+            metrics: None,
         }
     }
+
+    /// Emits messages with a higher `priority` first when multiple messages are due
+    ///
+    /// Messages only compete when they are held pending, for example while a [`Controller`](crate::Controller)
+    /// is at its concurrency limit. Messages of the same priority are emitted in the order they were due.
+    ///
+    /// When a message is scheduled again before it was emitted, the higher priority version of the message is kept.
+    #[must_use]
+    pub fn with_priority(mut self, priority: fn(&T) -> i32) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Records the queue depth and scheduling delay into `metrics`
+    #[cfg(feature = "metrics")]
+    #[must_use]
+    pub(crate) fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = Some(metrics);
+        self
+    }
 }
 
 impl<T: Hash + Eq + Clone, R> SchedulerProj<'_, T, R> {
@@ -70,27 +102,38 @@ impl<T: Hash + Eq + Clone, R> SchedulerProj<'_, T, R> {
     ///
     /// If the message is already in the queue then the earlier `request.run_at` takes precedence.
     fn schedule_message(&mut self, request: ScheduleRequest<T>) {
-        if self.pending.contains(&request.message) {
-            // Message is already pending, so we can't even expedite it
+        if let Some((pending, &run_at)) = self.pending.get_key_value(&request.message) {
+            // Message is already pending, so we can't even expedite it, but we can still raise its priority
+            if self.priority_of(&request.message) > self.priority_of(pending) {
+                self.pending.remove(&request.message);
+                self.pending.insert(request.message, run_at);
+            }
             return;
         }
         let next_time = request
             .run_at
             .checked_add(*self.debounce)
             .unwrap_or_else(far_future);
+        let priority = *self.priority;
         match self.scheduled.raw_entry_mut().from_key(&request.message) {
             // If new request is supposed to be earlier than the current entry's scheduled
             // time (for eg: the new request is user triggered and the current entry is the
             // reconciler's usual retry), then give priority to the new request.
             RawEntryMut::Occupied(mut old_entry) if old_entry.get().run_at >= request.run_at => {
                 // Old entry will run after the new request, so replace it..
-                let entry = old_entry.get_mut();
+                let (old_message, entry) = old_entry.get_key_value_mut();
                 self.queue.reset_at(&entry.queue_key, next_time);
                 entry.run_at = next_time;
-                old_entry.insert_key(request.message);
+                // ..unless it has a higher priority
+                if priority_of(priority, &request.message) >= priority_of(priority, old_message) {
+                    old_entry.insert_key(request.message);
+                }
             }
-            RawEntryMut::Occupied(_old_entry) => {
-                // Old entry will run before the new request, so ignore the new request..
+            RawEntryMut::Occupied(mut old_entry) => {
+                // Old entry will run before the new request, so ignore the new request, but keep its priority
+                if priority_of(priority, &request.message) > priority_of(priority, old_entry.key()) {
+                    old_entry.insert_key(request.message);
+                }
             }
             RawEntryMut::Vacant(entry) => {
                 // No old entry, we're free to go!
@@ -109,21 +152,41 @@ impl<T: Hash + Eq + Clone, R> SchedulerProj<'_, T, R> {
         cx: &mut Context<'_>,
         can_take_message: impl Fn(&T) -> bool,
     ) -> Poll<T> {
-        if let Some(msg) = self.pending.iter().find(|msg| can_take_message(*msg)).cloned() {
-            return Poll::Ready(self.pending.take(&msg).unwrap());
+        if let Some(priority) = *self.priority {
+            // Collect all expired messages, so that the most important one can be picked
+            self.pop_queue_message_into_pending(cx);
+            let Some(msg) = self
+                .pending
+                .iter()
+                .filter(|(msg, _)| can_take_message(msg))
+                .max_by_key(|(msg, run_at)| (priority(msg), Reverse(**run_at)))
+                .map(|(msg, _)| msg.clone())
+            else {
+                return Poll::Pending;
+            };
+            let (msg, run_at) = self.pending.remove_entry(&msg).unwrap();
+            self.record_emitted(run_at);
+            return Poll::Ready(msg);
+        }
+
+        if let Some(msg) = self.pending.keys().find(|msg| can_take_message(*msg)).cloned() {
+            let (msg, run_at) = self.pending.remove_entry(&msg).unwrap();
+            self.record_emitted(run_at);
+            return Poll::Ready(msg);
         }
 
         loop {
             match self.queue.poll_expired(cx) {
                 Poll::Ready(Some(msg)) => {
                     let msg = msg.into_inner();
-                    let (msg, _) = self.scheduled.remove_entry(&msg).expect(
+                    let (msg, entry) = self.scheduled.remove_entry(&msg).expect(
                         "Expired message was popped from the Scheduler queue, but was not in the metadata map",
                     );
                     if can_take_message(&msg) {
+                        self.record_emitted(entry.run_at);
                         break Poll::Ready(msg);
                     }
-                    self.pending.insert(msg);
+                    self.pending.insert(msg, entry.run_at);
                 }
                 Poll::Ready(None) | Poll::Pending => break Poll::Pending,
             }
@@ -134,10 +197,32 @@ impl<T: Hash + Eq + Clone, R> SchedulerProj<'_, T, R> {
     pub fn pop_queue_message_into_pending(&mut self, cx: &mut Context<'_>) {
         while let Poll::Ready(Some(msg)) = self.queue.poll_expired(cx) {
             let msg = msg.into_inner();
-            self.scheduled.remove_entry(&msg).expect(
+            let (msg, entry) = self.scheduled.remove_entry(&msg).expect(
                 "Expired message was popped from the Scheduler queue, but was not in the metadata map",
             );
-            self.pending.insert(msg);
+            self.pending.insert(msg, entry.run_at);
+        }
+    }
+
+    fn priority_of(&self, msg: &T) -> i32 {
+        priority_of(*self.priority, msg)
+    }
+
+    /// Records that a message that was due at `run_at` has been emitted
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables, clippy::unused_self))]
+    fn record_emitted(&self, run_at: Instant) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.scheduled_after(Instant::now().saturating_duration_since(run_at));
+        }
+    }
+
+    /// Records the number of messages that are waiting to be emitted
+    #[cfg_attr(not(feature = "metrics"), allow(clippy::unused_self))]
+    fn record_queue_depth(&self) {
+        #[cfg(feature = "metrics")]
+        if let Some(metrics) = self.metrics.as_ref() {
+            metrics.set_queue_depth(self.scheduled.len() + self.pending.len());
         }
     }
 }
@@ -167,6 +252,7 @@ where
         }
 
         scheduler.pop_queue_message_into_pending(cx);
+        scheduler.record_queue_depth();
         Poll::Pending
     }
 }
@@ -198,10 +284,12 @@ where
             }
         }
 
-        match scheduler.poll_pop_queue_message(cx, can_take_message) {
+        let next = match scheduler.poll_pop_queue_message(cx, can_take_message) {
             Poll::Ready(expired) => Poll::Ready(Some(expired)),
             Poll::Pending => Poll::Pending,
-        }
+        };
+        scheduler.record_queue_depth();
+        next
     }
 }
 
@@ -238,7 +326,7 @@ where
     /// Checks whether `msg` is currently a pending message (held by `hold_unless`)
     #[cfg(test)]
     pub fn contains_pending(&self, msg: &T) -> bool {
-        self.pending.contains(msg)
+        self.pending.contains_key(msg)
     }
 }
 
@@ -283,6 +371,10 @@ pub fn debounced_scheduler<T: Eq + Hash + Clone, S: Stream<Item = ScheduleReques
     Scheduler::new(requests, debounce)
 }
 
+fn priority_of<T>(priority: Option<fn(&T) -> i32>, msg: &T) -> i32 {
+    priority.map_or(0, |priority| priority(msg))
+}
+
 // internal fallback for overflows in schedule times
 pub(crate) fn far_future() -> Instant {
     // private method from tokio for convenience - remove if upstream becomes pub
@@ -386,6 +478,42 @@ mod tests {
         );
     }
 
+    #[tokio::test]
+    async fn scheduler_should_emit_higher_priority_items_first() {
+        pause();
+        let now = Instant::now();
+        let mut scheduler = Box::pin(
+            scheduler(
+                stream::iter(vec![
+                    ScheduleRequest {
+                        message: 1_u8,
+                        run_at: now,
+                    },
+                    ScheduleRequest {
+                        message: 3,
+                        run_at: now + Duration::from_secs(1),
+                    },
+                    ScheduleRequest {
+                        message: 2,
+                        run_at: now,
+                    },
+                ])
+                .on_complete(sleep(Duration::from_secs(4))),
+            )
+            .with_priority(|msg| i32::from(*msg)),
+        );
+        assert!(poll!(scheduler.as_mut().hold().next()).is_pending());
+        advance(Duration::from_secs(2)).await;
+        assert!(poll!(scheduler.as_mut().hold().next()).is_pending());
+        assert_eq!(
+            scheduler.as_mut().hold_unless(|x| *x != 3).next().await.unwrap(),
+            2
+        );
+        assert_eq!(scheduler.next().await.unwrap(), 3);
+        assert_eq!(scheduler.next().await.unwrap(), 1);
+        assert!(scheduler.next().await.is_none());
+    }
+
     #[tokio::test]
     async fn scheduler_should_emit_items_as_requested() {
         pause();
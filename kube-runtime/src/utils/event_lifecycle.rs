@@ -0,0 +1,163 @@
+use core::{
+    pin::Pin,
+    task::{ready, Context, Poll},
+};
+
+use futures::Stream;
+use pin_project::pin_project;
+
+use crate::watcher::{Error, Event};
+
+/// A transition of a [`watcher()`](crate::watcher()) stream between listing and watching
+///
+/// Passed to the callback of [`inspect_lifecycle`](super::WatchStreamExt::inspect_lifecycle).
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Lifecycle {
+    /// The initial list started
+    Init,
+    /// The initial list completed, so the stream is in sync with the apiserver
+    InitDone,
+    /// The watcher lost track of changes, and started a relist
+    Desync(DesyncReason),
+    /// A relist completed, so the stream is back in sync with the apiserver
+    Resync,
+}
+
+/// Why a [`watcher()`](crate::watcher()) had to relist
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum DesyncReason {
+    /// The resource version being watched is too old (`410 Gone`), typically after a long disconnect
+    Expired,
+    /// Listing or watching failed
+    Failed,
+    /// The apiserver closed the stream before it was in sync
+    StreamEnded,
+}
+
+#[pin_project]
+/// Stream returned by the [`inspect_lifecycle`](super::WatchStreamExt::inspect_lifecycle) method.
+/// Calls a function on every [`Lifecycle`] transition, and passes all items through unchanged.
+pub struct EventLifecycle<St, F> {
+    #[pin]
+    stream: St,
+    f: F,
+    /// Whether any list has completed yet
+    synced: bool,
+    /// The reason for a relist, if one was to happen next
+    desync: Option<DesyncReason>,
+}
+
+impl<St, F> EventLifecycle<St, F> {
+    pub(super) fn new(stream: St, f: F) -> Self {
+        Self {
+            stream,
+            f,
+            synced: false,
+            desync: None,
+        }
+    }
+}
+
+impl<St, F, K> Stream for EventLifecycle<St, F>
+where
+    St: Stream<Item = Result<Event<K>, Error>>,
+    F: FnMut(Lifecycle),
+{
+    type Item = Result<Event<K>, Error>;
+
+    fn poll_next(self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        let me = self.project();
+        let item = ready!(me.stream.poll_next(cx));
+        match &item {
+            Some(Ok(Event::Init)) if *me.synced => {
+                let reason = me.desync.take().unwrap_or(DesyncReason::StreamEnded);
+                (me.f)(Lifecycle::Desync(reason));
+            }
+            Some(Ok(Event::Init)) => {
+                *me.desync = None;
+                (me.f)(Lifecycle::Init);
+            }
+            Some(Ok(Event::InitDone)) => {
+                (me.f)(if *me.synced {
+                    Lifecycle::Resync
+                } else {
+                    Lifecycle::InitDone
+                });
+                *me.synced = true;
+            }
+            // The watch recovered without relisting
+            Some(Ok(Event::Apply(_) | Event::Delete(_))) => *me.desync = None,
+            Some(Ok(Event::InitApply(_))) | None => {}
+            Some(Err(Error::WatchError(err))) if err.code == 410 => *me.desync = Some(DesyncReason::Expired),
+            Some(Err(_)) => *me.desync = Some(DesyncReason::Failed),
+        }
+        Poll::Ready(item)
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod test {
+    use super::{DesyncReason, Error, Event, EventLifecycle, Lifecycle};
+    use futures::{stream, StreamExt};
+    use kube_client::core::ErrorResponse;
+
+    fn gone() -> Error {
+        Error::WatchError(ErrorResponse {
+            status: "Failure".into(),
+            message: "too old resource version".into(),
+            reason: "Expired".into(),
+            code: 410,
+            details: None,
+        })
+    }
+
+    #[tokio::test]
+    async fn lifecycle_reports_relists() {
+        let st = stream::iter([
+            Ok(Event::Init),
+            Ok(Event::InitApply(0)),
+            Ok(Event::InitDone),
+            Ok(Event::Apply(1)),
+            Err(gone()),
+            Ok(Event::Init),
+            Ok(Event::InitDone),
+            Err(Error::NoResourceVersion),
+            Ok(Event::Init),
+            Ok(Event::InitDone),
+            Ok(Event::Init),
+        ]);
+        let mut transitions = vec![];
+        let items = EventLifecycle::new(st, |lifecycle| transitions.push(lifecycle))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(items.len(), 11);
+        assert_eq!(transitions, vec![
+            Lifecycle::Init,
+            Lifecycle::InitDone,
+            Lifecycle::Desync(DesyncReason::Expired),
+            Lifecycle::Resync,
+            Lifecycle::Desync(DesyncReason::Failed),
+            Lifecycle::Resync,
+            Lifecycle::Desync(DesyncReason::StreamEnded),
+        ]);
+    }
+
+    #[tokio::test]
+    async fn lifecycle_ignores_recovered_errors() {
+        let st = stream::iter([
+            Ok(Event::Init),
+            Ok(Event::InitDone),
+            Err(Error::NoResourceVersion),
+            Ok(Event::Apply(0)),
+            Ok(Event::Init),
+        ]);
+        let mut transitions = vec![];
+        EventLifecycle::new(st, |lifecycle| transitions.push(lifecycle))
+            .collect::<Vec<_>>()
+            .await;
+        assert_eq!(
+            transitions.last(),
+            Some(&Lifecycle::Desync(DesyncReason::StreamEnded))
+        );
+    }
+}
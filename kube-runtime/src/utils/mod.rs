@@ -3,6 +3,7 @@
 mod backoff_reset_timer;
 pub(crate) mod delayed_init;
 mod event_decode;
+mod event_lifecycle;
 mod event_modify;
 mod predicate;
 mod reflect;
@@ -11,6 +12,7 @@ mod watch_ext;
 
 pub use backoff_reset_timer::ResetTimerBackoff;
 pub use event_decode::EventDecode;
+pub use event_lifecycle::{DesyncReason, EventLifecycle, Lifecycle};
 pub use event_modify::EventModify;
 pub use predicate::{predicates, Predicate, PredicateFilter};
 pub use reflect::Reflect;
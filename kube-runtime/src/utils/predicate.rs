@@ -5,6 +5,7 @@ use core::{
 };
 use futures::Stream;
 use kube_client::Resource;
+use parking_lot::Mutex;
 use pin_project::pin_project;
 use std::{
     collections::{hash_map::DefaultHasher, HashMap},
@@ -57,6 +58,66 @@ pub trait Predicate<K> {
     {
         Combine(self, f)
     }
+
+    /// Returns a `Predicate` that changes when either property changes
+    ///
+    /// This is the same as [`Predicate::combine`].
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// use kube::runtime::{predicates, Predicate};
+    /// # fn blah<K>(a: impl Predicate<K>) {}
+    /// let pred = predicates::generation.or(predicates::deletion);
+    /// blah::<Pod>(pred);
+    /// ```
+    fn or<F: Predicate<K>>(self, f: F) -> Combine<Self, F>
+    where
+        Self: Sized,
+    {
+        Combine(self, f)
+    }
+
+    /// Returns a `Predicate` that only changes once both properties have changed
+    ///
+    /// Objects are let through when both properties differ from the last object that was let through.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// use kube::runtime::{predicates, Predicate};
+    /// # fn blah<K>(a: impl Predicate<K>) {}
+    /// let pred = predicates::generation.and(predicates::labels);
+    /// blah::<Pod>(pred);
+    /// ```
+    fn and<F: Predicate<K>>(self, f: F) -> And<Self, F>
+    where
+        Self: Sized,
+    {
+        And(self, f, Mutex::default())
+    }
+
+    /// Returns a `Predicate` that changes whenever the property stays the same
+    ///
+    /// Objects are let through when the property is unchanged, such as status updates with `generation.not()`.
+    ///
+    /// # Usage
+    ///
+    /// ```
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// use kube::runtime::{predicates, Predicate};
+    /// # fn blah<K>(a: impl Predicate<K>) {}
+    /// let pred = predicates::generation.not();
+    /// blah::<Pod>(pred);
+    /// ```
+    fn not(self) -> Not<Self>
+    where
+        Self: Sized,
+    {
+        Not(self, Mutex::default())
+    }
 }
 
 impl<K, F: Fn(&K) -> Option<u64>> Predicate<K> for F {
@@ -95,6 +156,85 @@ where
     }
 }
 
+/// The identity of an object, to track the state of stateful predicates
+type ObjectKey = (Option<String>, Option<String>);
+
+fn object_key<K: Resource>(obj: &K) -> ObjectKey {
+    (obj.meta().namespace.clone(), obj.meta().name.clone())
+}
+
+/// See [`Predicate::and`]
+#[derive(Debug)]
+pub struct And<A, B>(pub(super) A, pub(super) B, Mutex<HashMap<ObjectKey, AndState>>);
+
+#[derive(Debug)]
+struct AndState {
+    first: Option<u64>,
+    second: Option<u64>,
+    hash: u64,
+}
+
+impl<A, B, K> Predicate<K> for And<A, B>
+where
+    A: Predicate<K>,
+    B: Predicate<K>,
+    K: Resource,
+{
+    fn hash_property(&self, obj: &K) -> Option<u64> {
+        let (first, second) = (self.0.hash_property(obj), self.1.hash_property(obj));
+        if first.is_none() && second.is_none() {
+            return None;
+        }
+        let mut states = self.2.lock();
+        let state = states.entry(object_key(obj)).or_insert_with(|| AndState {
+            first,
+            second,
+            hash: hash(&(first, second)),
+        });
+        if state.first != first && state.second != second {
+            *state = AndState {
+                first,
+                second,
+                hash: hash(&(first, second)),
+            };
+        }
+        Some(state.hash)
+    }
+}
+
+/// See [`Predicate::not`]
+#[derive(Debug)]
+pub struct Not<A>(pub(super) A, Mutex<HashMap<ObjectKey, NotState>>);
+
+#[derive(Debug)]
+struct NotState {
+    property: Option<u64>,
+    hash: u64,
+}
+
+impl<A, K> Predicate<K> for Not<A>
+where
+    A: Predicate<K>,
+    K: Resource,
+{
+    fn hash_property(&self, obj: &K) -> Option<u64> {
+        let property = self.0.hash_property(obj);
+        let mut states = self.1.lock();
+        // every version of an unchanged property gets a new hash, while changes repeat the last hash
+        let version_hash = hash(&(property, &obj.meta().resource_version));
+        let state = states.entry(object_key(obj)).or_insert(NotState {
+            property,
+            hash: version_hash,
+        });
+        if state.property == property {
+            state.hash = version_hash;
+        } else {
+            state.property = property;
+        }
+        Some(state.hash)
+    }
+}
+
 #[allow(clippy::pedantic)]
 #[pin_project]
 /// Stream returned by the [`predicate_filter`](super::WatchStreamExt::predicate_filter) method.
@@ -171,6 +311,8 @@ where
 pub mod predicates {
     use super::hash;
     use kube_client::{Resource, ResourceExt};
+    use serde::Serialize;
+    use std::collections::BTreeSet;
 
     /// Hash the generation of a Resource K
     pub fn generation<K: Resource>(obj: &K) -> Option<u64> {
@@ -196,13 +338,60 @@ pub mod predicates {
     pub fn finalizers<K: Resource>(obj: &K) -> Option<u64> {
         Some(hash(obj.finalizers()))
     }
+
+    /// Hash whether a Resource K is being deleted
+    ///
+    /// This only changes when the deletionTimestamp is set, and not on every update of a deleted object.
+    pub fn deletion<K: Resource>(obj: &K) -> Option<u64> {
+        Some(hash(&obj.meta().deletion_timestamp.is_some()))
+    }
+
+    /// Hash the spec of a Resource K
+    ///
+    /// Unlike [`generation`], this also works for resources that do not track a generation,
+    /// at the cost of serializing every object. Objects without a spec are always let through.
+    pub fn spec<K: Resource + Serialize>(obj: &K) -> Option<u64> {
+        let value = serde_json::to_value(obj).ok()?;
+        value.get("spec").map(|spec| hash(&spec.to_string()))
+    }
+
+    /// Hash the labels of a Resource K with one of the given `keys`
+    ///
+    /// ```
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// use kube::runtime::{predicates, Predicate};
+    /// # fn blah<K>(a: impl Predicate<K>) {}
+    /// let pred = predicates::label_keys(["app.kubernetes.io/version"]);
+    /// blah::<Pod>(pred);
+    /// ```
+    pub fn label_keys<K: Resource>(
+        keys: impl IntoIterator<Item = impl Into<String>>,
+    ) -> impl Fn(&K) -> Option<u64> + Clone {
+        let keys = keys.into_iter().map(Into::into).collect::<BTreeSet<String>>();
+        move |obj| Some(hash(&selected(obj.labels(), &keys)))
+    }
+
+    /// Hash the annotations of a Resource K with one of the given `keys`
+    pub fn annotation_keys<K: Resource>(
+        keys: impl IntoIterator<Item = impl Into<String>>,
+    ) -> impl Fn(&K) -> Option<u64> + Clone {
+        let keys = keys.into_iter().map(Into::into).collect::<BTreeSet<String>>();
+        move |obj| Some(hash(&selected(obj.annotations(), &keys)))
+    }
+
+    fn selected<'a>(
+        values: &'a std::collections::BTreeMap<String, String>,
+        keys: &BTreeSet<String>,
+    ) -> Vec<(&'a String, &'a String)> {
+        values.iter().filter(|(key, _)| keys.contains(*key)).collect()
+    }
 }
 
 #[cfg(test)]
 pub(crate) mod tests {
     use std::{pin::pin, task::Poll};
 
-    use super::{predicates, Error, PredicateFilter};
+    use super::{predicates, Error, Predicate, PredicateFilter};
     use futures::{poll, stream, FutureExt, StreamExt};
     use kube_client::Resource;
     use serde_json::json;
@@ -251,4 +440,40 @@ pub(crate) mod tests {
         assert_eq!(second.meta().generation, Some(2));
         assert!(matches!(poll!(rx.next()), Poll::Ready(None)));
     }
+
+    #[test]
+    fn predicate_combinators() {
+        use k8s_openapi::api::core::v1::Pod;
+        let mkobj = |gen: i64, labels: serde_json::Value, rv: &str| {
+            let p: Pod = serde_json::from_value(json!({
+                "apiVersion": "v1",
+                "kind": "Pod",
+                "metadata": {
+                    "name": "blog",
+                    "generation": gen,
+                    "labels": labels,
+                    "resourceVersion": rv,
+                },
+            }))
+            .unwrap();
+            p
+        };
+        let app = json!({"app": "blog"});
+        let web = json!({"app": "web"});
+
+        let version_labels = predicates::label_keys(["version"]);
+        let first = version_labels(&mkobj(1, app.clone(), "1"));
+        assert_eq!(first, version_labels(&mkobj(1, web.clone(), "2")));
+
+        let and = predicates::generation.and(predicates::labels);
+        let first = and.hash_property(&mkobj(1, app.clone(), "1"));
+        assert_eq!(first, and.hash_property(&mkobj(2, app.clone(), "2")));
+        assert_ne!(first, and.hash_property(&mkobj(3, web.clone(), "3")));
+
+        let not = predicates::generation.not();
+        let first = not.hash_property(&mkobj(1, app.clone(), "1"));
+        let status_update = not.hash_property(&mkobj(1, app.clone(), "2"));
+        assert_ne!(first, status_update);
+        assert_eq!(status_update, not.hash_property(&mkobj(2, app, "3")));
+    }
 }
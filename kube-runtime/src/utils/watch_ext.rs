@@ -1,6 +1,7 @@
 use crate::{
     utils::{
         event_decode::EventDecode,
+        event_lifecycle::{EventLifecycle, Lifecycle},
         event_modify::EventModify,
         predicate::{Predicate, PredicateFilter},
         stream_backoff::StreamBackoff,
@@ -91,6 +92,41 @@ pub trait WatchStreamExt: Stream {
         EventModify::new(self, f)
     }
 
+    /// Inspect the [`Lifecycle`] transitions of a [`watcher()`] stream.
+    ///
+    /// Calls `f` when the initial list starts and completes, when the watcher has to relist
+    /// (along with the [`DesyncReason`](crate::utils::DesyncReason)), and when a relist completes.
+    /// All items are passed through unchanged.
+    ///
+    /// This can be used to log or export metrics for relists, which are otherwise silent.
+    ///
+    /// ```no_run
+    /// # use futures::{Stream, StreamExt, TryStreamExt};
+    /// # use kube::{Api, Client};
+    /// # use kube_runtime::{utils::Lifecycle, watcher, WatchStreamExt};
+    /// # use k8s_openapi::api::core::v1::Pod;
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::all(client);
+    /// watcher(pods, watcher::Config::default())
+    ///     .inspect_lifecycle(|lifecycle| match lifecycle {
+    ///         Lifecycle::Desync(reason) => tracing::warn!(?reason, "pod watcher is relisting"),
+    ///         lifecycle => tracing::info!(?lifecycle, "pod watcher transitioned"),
+    ///     })
+    ///     .applied_objects()
+    ///     .try_for_each(|_pod| async { Ok(()) })
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    fn inspect_lifecycle<K, F>(self, f: F) -> EventLifecycle<Self, F>
+    where
+        Self: Stream<Item = watcher::Result<watcher::Event<K>>> + Sized,
+        F: FnMut(Lifecycle),
+    {
+        EventLifecycle::new(self, f)
+    }
+
     /// Filter a stream based on on [`predicates`](crate::predicates).
     ///
     /// This will filter out repeat calls where the predicate returns the same result.
@@ -196,10 +232,6 @@ pub trait WatchStreamExt: Stream {
     /// the root stream have been observed. This means [`ReflectHandle`] streams
     /// can still be polled after the root stream has been dropped.
     ///
-    /// **NB**: This adapter requires an
-    /// [`unstable`](https://github.com/kube-rs/kube/blob/main/kube-runtime/Cargo.toml#L17-L21)
-    /// feature
-    ///
     /// ## Warning
     ///
     /// If the root [`Stream`] is not polled, [`ReflectHandle`] streams will
@@ -260,7 +292,6 @@ pub trait WatchStreamExt: Stream {
     /// # Ok(())
     /// # }
     /// ```
-    #[cfg(feature = "unstable-runtime-subscribe")]
     fn reflect_shared<K>(self, writer: Writer<K>) -> impl Stream<Item = Self::Item>
     where
         Self: Stream<Item = watcher::Result<watcher::Event<K>>> + Sized,
@@ -161,9 +161,14 @@ impl<K, F: Fn(Option<&K>) -> bool> Condition<K> for F {
 
 /// Common conditions to wait for
 pub mod conditions {
+    use super::rollout::Rollout;
     pub use super::Condition;
     use k8s_openapi::{
-        api::{batch::v1::Job, core::v1::Pod},
+        api::{
+            apps::v1::{DaemonSet, Deployment, StatefulSet},
+            batch::v1::Job,
+            core::v1::Pod,
+        },
         apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition,
     };
     use kube_client::Resource;
@@ -237,6 +242,37 @@ pub mod conditions {
         }
     }
 
+    /// An await condition for `Deployment` that returns `true` once its rollout has completed
+    ///
+    /// The deployment must have observed its latest spec, and all of its replicas must be updated and available.
+    /// See [`rollout_status`](super::rollout::rollout_status) to also fail on rollouts that cannot complete.
+    #[must_use]
+    pub fn is_deployment_rolled_out() -> impl Condition<Deployment> {
+        is_rolled_out
+    }
+
+    /// An await condition for `StatefulSet` that returns `true` once its rollout has completed
+    ///
+    /// The statefulset must have observed its latest spec, all of its replicas must be ready,
+    /// and all pods outside of its partition must be at the update revision.
+    #[must_use]
+    pub fn is_statefulset_rolled_out() -> impl Condition<StatefulSet> {
+        is_rolled_out
+    }
+
+    /// An await condition for `DaemonSet` that returns `true` once its rollout has completed
+    ///
+    /// The daemonset must have observed its latest spec, and its pods must be updated and available on every node.
+    #[must_use]
+    pub fn is_daemonset_rolled_out() -> impl Condition<DaemonSet> {
+        is_rolled_out
+    }
+
+    fn is_rolled_out<K: Rollout>(obj: Option<&K>) -> bool {
+        obj.and_then(|obj| obj.rollout_status().ok())
+            .is_some_and(|status| status.is_complete())
+    }
+
     /// See [`Condition::not`]
     #[derive(Copy, Clone, Debug, PartialEq, Eq)]
     pub struct Not<A>(pub(super) A);
@@ -276,9 +312,21 @@ pub mod conditions {
 /// Utilities for deleting objects
 pub mod delete {
     use super::{await_condition, conditions};
-    use kube_client::{api::DeleteParams, Api, Resource};
+    use crate::{
+        watcher::{self, watcher},
+        WatchStreamExt,
+    };
+    use async_stream::stream;
+    use futures::{Stream, StreamExt};
+    use kube_client::{
+        api::{DeleteParams, ListParams},
+        Api, Resource, ResourceExt,
+    };
     use serde::de::DeserializeOwned;
-    use std::fmt::Debug;
+    use std::{
+        collections::{HashMap, HashSet},
+        fmt::Debug,
+    };
     use thiserror::Error;
 
     #[derive(Debug, Error)]
@@ -289,6 +337,8 @@ pub mod delete {
         Delete(#[source] kube_client::Error),
         #[error("failed to wait for object to be deleted: {0}")]
         Await(#[source] super::Error),
+        #[error("failed to watch objects being deleted: {0}")]
+        Watch(#[source] watcher::Error),
     }
 
     /// Delete an object, and wait for it to be removed from the Kubernetes API (including waiting for all finalizers to unregister themselves).
@@ -316,4 +366,774 @@ pub mod delete {
             .map_err(Error::Await)?;
         Ok(())
     }
+
+    /// The progress of a [`delete_collection_and_finalize`] call
+    #[derive(Clone, Debug, PartialEq)]
+    pub enum DeletionProgress<K> {
+        /// The object is marked for deletion, and waits for its finalizers or grace period
+        Finalizing(K),
+        /// The object has been removed from the Kubernetes API
+        Deleted(K),
+    }
+
+    /// Delete a collection of objects, and follow them until they are removed from the Kubernetes API
+    ///
+    /// Every deleted object is emitted as [`DeletionProgress::Deleted`] once it is gone. Objects that are
+    /// held back by finalizers or a grace period are first emitted as [`DeletionProgress::Finalizing`],
+    /// and then watched with the selectors of `lp`. The stream ends once every object is removed.
+    ///
+    /// ```no_run
+    /// use futures::TryStreamExt;
+    /// use k8s_openapi::api::core::v1::Pod;
+    /// use kube::{
+    ///     api::{Api, DeleteParams, ListParams},
+    ///     runtime::wait::delete::{delete_collection_and_finalize, DeletionProgress},
+    /// };
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let pods: Api<Pod> = Api::default_namespaced(client);
+    /// let lp = ListParams::default().labels("app=blog");
+    /// let mut progress = std::pin::pin!(delete_collection_and_finalize(pods, &DeleteParams::default(), &lp));
+    /// while let Some(event) = progress.try_next().await? {
+    ///     if let DeletionProgress::Deleted(pod) = event {
+    ///         println!("Deleted {}", pod.metadata.name.unwrap_or_default());
+    ///     }
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// The stream ends with an [`Error::Delete`] if the collection could not be deleted.
+    /// Watch errors are emitted as [`Error::Watch`] while the watch is retried with a backoff.
+    #[allow(clippy::module_name_repetitions)]
+    pub fn delete_collection_and_finalize<K>(
+        api: Api<K>,
+        dp: &DeleteParams,
+        lp: &ListParams,
+    ) -> impl Stream<Item = Result<DeletionProgress<K>, Error>> + Send
+    where
+        K: Clone + Debug + Send + DeserializeOwned + Resource + 'static,
+    {
+        let (dp, lp) = (dp.clone(), lp.clone());
+        stream! {
+            let deleted = match api.delete_collection(&dp, &lp).await {
+                Ok(deleted) => deleted,
+                Err(err) => {
+                    yield Err(Error::Delete(err));
+                    return;
+                }
+            };
+            // A Status response means that the collection was removed without returning the objects
+            let Some(list) = deleted.left() else { return };
+
+            let mut finalizing = Finalizing::default();
+            for obj in list {
+                if obj.meta().deletion_timestamp.is_some() && finalizing.insert(obj.clone()) {
+                    yield Ok(DeletionProgress::Finalizing(obj));
+                } else {
+                    yield Ok(DeletionProgress::Deleted(obj));
+                }
+            }
+            if finalizing.is_empty() {
+                return;
+            }
+
+            let wc = watcher::Config {
+                label_selector: lp.label_selector,
+                field_selector: lp.field_selector,
+                ..watcher::Config::default()
+            };
+            let mut events = watcher(api, wc).default_backoff().boxed();
+            while let Some(event) = events.next().await {
+                match event {
+                    Ok(event) => {
+                        for obj in finalizing.observe(event) {
+                            yield Ok(DeletionProgress::Deleted(obj));
+                        }
+                    }
+                    Err(err) => yield Err(Error::Watch(err)),
+                }
+                if finalizing.is_empty() {
+                    break;
+                }
+            }
+        }
+    }
+
+    /// Objects that are marked for deletion, but still exist
+    struct Finalizing<K> {
+        objects: HashMap<String, K>,
+        relisted: HashSet<String>,
+    }
+
+    impl<K> Default for Finalizing<K> {
+        fn default() -> Self {
+            Self {
+                objects: HashMap::new(),
+                relisted: HashSet::new(),
+            }
+        }
+    }
+
+    impl<K: Resource> Finalizing<K> {
+        fn insert(&mut self, obj: K) -> bool {
+            obj.uid()
+                .is_some_and(|uid| self.objects.insert(uid, obj).is_none())
+        }
+
+        fn is_empty(&self) -> bool {
+            self.objects.is_empty()
+        }
+
+        /// Returns the objects that are known to be gone after a watch event
+        fn observe(&mut self, event: watcher::Event<K>) -> Vec<K> {
+            match event {
+                watcher::Event::Init => {
+                    self.relisted.clear();
+                    Vec::new()
+                }
+                watcher::Event::InitApply(obj) => {
+                    self.relisted.extend(obj.uid());
+                    Vec::new()
+                }
+                watcher::Event::InitDone => {
+                    let relisted = std::mem::take(&mut self.relisted);
+                    let (remaining, gone): (HashMap<_, _>, HashMap<_, _>) = std::mem::take(&mut self.objects)
+                        .into_iter()
+                        .partition(|(uid, _)| relisted.contains(uid));
+                    self.objects = remaining;
+                    gone.into_values().collect()
+                }
+                watcher::Event::Apply(_) => Vec::new(),
+                watcher::Event::Delete(obj) => match obj.uid() {
+                    Some(uid) if self.objects.remove(&uid).is_some() => vec![obj],
+                    _ => Vec::new(),
+                },
+            }
+        }
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::Finalizing;
+        use crate::watcher::Event;
+        use k8s_openapi::api::core::v1::ConfigMap;
+        use kube_client::api::ObjectMeta;
+
+        fn cm(uid: &str) -> ConfigMap {
+            ConfigMap {
+                metadata: ObjectMeta {
+                    name: Some(uid.to_string()),
+                    uid: Some(uid.to_string()),
+                    ..ObjectMeta::default()
+                },
+                ..ConfigMap::default()
+            }
+        }
+
+        #[test]
+        fn objects_are_gone_once_deleted_or_missing_from_a_relist() {
+            let mut finalizing = Finalizing::default();
+            assert!(finalizing.insert(cm("a")));
+            assert!(finalizing.insert(cm("b")));
+            assert!(finalizing.insert(cm("c")));
+            assert!(!finalizing.insert(ConfigMap::default()));
+
+            assert!(finalizing.observe(Event::Apply(cm("a"))).is_empty());
+            assert_eq!(finalizing.observe(Event::Delete(cm("a"))), vec![cm("a")]);
+            assert!(finalizing.observe(Event::Delete(cm("a"))).is_empty());
+
+            assert!(finalizing.observe(Event::Init).is_empty());
+            assert!(finalizing.observe(Event::InitApply(cm("b"))).is_empty());
+            assert_eq!(finalizing.observe(Event::InitDone), vec![cm("c")]);
+            assert!(!finalizing.is_empty());
+            assert_eq!(finalizing.observe(Event::Delete(cm("b"))), vec![cm("b")]);
+            assert!(finalizing.is_empty());
+        }
+    }
+}
+
+/// Waiting on objects of any kind, for tooling that discovers kinds at runtime
+pub mod dynamic {
+    use std::str::FromStr;
+
+    use jsonpath_rust::{JsonPath, JsonPathValue};
+    use kube_client::{api::DynamicObject, Api};
+    use serde_json::Value;
+    use thiserror::Error;
+
+    use super::{await_condition, Condition};
+
+    /// A condition string could not be parsed
+    #[derive(Debug, Error)]
+    #[error("invalid condition {condition:?}: {reason}")]
+    pub struct ParseConditionError {
+        condition: String,
+        reason: String,
+    }
+
+    impl ParseConditionError {
+        fn new(condition: &str, reason: impl Into<String>) -> Self {
+            Self {
+                condition: condition.to_string(),
+                reason: reason.into(),
+            }
+        }
+    }
+
+    /// A condition on an object of any kind
+    ///
+    /// Parsed from the `--for` syntax of `kubectl wait`:
+    ///
+    /// - `create` holds once the object exists
+    /// - `delete` holds once the object does not exist
+    /// - `condition=Ready` holds once the object has a `Ready` status condition with status `True`,
+    ///   and `condition=Ready=false` once its status is `False`
+    /// - `jsonpath={.status.phase}=Running` holds once the value at a `JSONPath` equals `Running`,
+    ///   and `jsonpath={.status.loadBalancer.ingress}` once the `JSONPath` has any non-empty value
+    ///
+    /// ```
+    /// use kube::{
+    ///     api::DynamicObject,
+    ///     runtime::wait::{dynamic::DynamicCondition, Condition},
+    /// };
+    /// let pod: DynamicObject = serde_json::from_value(serde_json::json!({
+    ///     "apiVersion": "v1",
+    ///     "kind": "Pod",
+    ///     "metadata": { "name": "web" },
+    ///     "status": { "phase": "Running" },
+    /// }))?;
+    /// let running: DynamicCondition = "jsonpath={.status.phase}=Running".parse()?;
+    /// assert!(running.matches_object(Some(&pod)));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[derive(Clone, Debug)]
+    pub enum DynamicCondition {
+        /// The object exists
+        Create,
+        /// The object does not exist
+        Delete,
+        /// The object has a status condition of a type with a status, both compared case-insensitively
+        Condition {
+            /// The type of the condition, such as `Ready`
+            type_: String,
+            /// The status of the condition, usually `True` or `False`
+            status: String,
+        },
+        /// The values at a `JSONPath` equal a value
+        JsonPath {
+            /// The path to the values
+            path: JsonPath,
+            /// The value to compare to, or `None` to only require a non-empty value
+            value: Option<String>,
+        },
+    }
+
+    impl FromStr for DynamicCondition {
+        type Err = ParseConditionError;
+
+        fn from_str(condition: &str) -> Result<Self, Self::Err> {
+            match condition.split_once('=') {
+                None if condition.eq_ignore_ascii_case("create") => Ok(Self::Create),
+                None if condition.eq_ignore_ascii_case("delete") => Ok(Self::Delete),
+                Some((kind, rest)) if kind.eq_ignore_ascii_case("condition") => {
+                    let (type_, status) = rest.split_once('=').unwrap_or((rest, "True"));
+                    if type_.is_empty() {
+                        return Err(ParseConditionError::new(condition, "missing condition type"));
+                    }
+                    Ok(Self::Condition {
+                        type_: type_.to_string(),
+                        status: status.to_string(),
+                    })
+                }
+                Some((kind, rest)) if kind.eq_ignore_ascii_case("jsonpath") => {
+                    // `{...}` may contain filter expressions with `=`, so only split after the closing brace
+                    let (path, value) = if let Some(braced) = rest.strip_prefix('{') {
+                        let Some((path, value)) = braced.rsplit_once('}') else {
+                            return Err(ParseConditionError::new(
+                                condition,
+                                "unclosed JSONPath expression",
+                            ));
+                        };
+                        match value.strip_prefix('=') {
+                            Some(value) => (path, Some(value)),
+                            None if value.is_empty() => (path, None),
+                            None => {
+                                return Err(ParseConditionError::new(
+                                    condition,
+                                    "expected `=` after the JSONPath expression",
+                                ))
+                            }
+                        }
+                    } else {
+                        rest.split_once('=')
+                            .map_or((rest, None), |(path, value)| (path, Some(value)))
+                    };
+                    let path = if path.starts_with('$') {
+                        path.to_string()
+                    } else {
+                        format!("${path}")
+                    };
+                    let path = path
+                        .parse::<JsonPath>()
+                        .map_err(|err| ParseConditionError::new(condition, err.to_string()))?;
+                    Ok(Self::JsonPath {
+                        path,
+                        value: value.map(str::to_string),
+                    })
+                }
+                _ => Err(ParseConditionError::new(
+                    condition,
+                    "expected create, delete, condition=<type>[=<status>] or jsonpath=<path>[=<value>]",
+                )),
+            }
+        }
+    }
+
+    impl Condition<DynamicObject> for DynamicCondition {
+        fn matches_object(&self, obj: Option<&DynamicObject>) -> bool {
+            let Some(obj) = obj else {
+                return matches!(self, Self::Delete);
+            };
+            match self {
+                Self::Create => true,
+                Self::Delete => false,
+                Self::Condition { type_, status } => {
+                    let generation = obj.metadata.generation;
+                    obj.data["status"]["conditions"]
+                        .as_array()
+                        .into_iter()
+                        .flatten()
+                        .filter(|cond| {
+                            cond["type"]
+                                .as_str()
+                                .is_some_and(|t| t.eq_ignore_ascii_case(type_))
+                        })
+                        // Skip conditions that describe an older generation of the object
+                        .filter(|cond| {
+                            cond["observedGeneration"].as_i64().map_or(true, |observed| {
+                                generation.map_or(true, |generation| observed >= generation)
+                            })
+                        })
+                        .any(|cond| {
+                            cond["status"]
+                                .as_str()
+                                .is_some_and(|s| s.eq_ignore_ascii_case(status))
+                        })
+                }
+                Self::JsonPath { path, value } => {
+                    let Ok(json) = serde_json::to_value(obj) else {
+                        return false;
+                    };
+                    let found = path
+                        .find_slice(&json)
+                        .into_iter()
+                        .filter(|found| !matches!(found, JsonPathValue::NoValue))
+                        .map(JsonPathValue::to_data)
+                        .collect::<Vec<_>>();
+                    match value {
+                        Some(value) => {
+                            !found.is_empty() && found.iter().all(|found| value_to_string(found) == *value)
+                        }
+                        None => found.iter().any(|found| !is_empty(found)),
+                    }
+                }
+            }
+        }
+    }
+
+    /// Formats a value like the `JSONPath` output of kubectl, without quoting strings
+    fn value_to_string(value: &Value) -> String {
+        match value {
+            Value::String(value) => value.clone(),
+            value => value.to_string(),
+        }
+    }
+
+    fn is_empty(value: &Value) -> bool {
+        match value {
+            Value::Null => true,
+            Value::String(value) => value.is_empty(),
+            Value::Array(values) => values.is_empty(),
+            Value::Object(values) => values.is_empty(),
+            Value::Bool(_) | Value::Number(_) => false,
+        }
+    }
+
+    /// Watches an object of any kind, and waits for a [`DynamicCondition`] to hold, like `kubectl wait`
+    ///
+    /// The `api` determines the kind and scope to watch, and is typically created from a discovered
+    /// [`ApiResource`](kube_client::core::ApiResource) with [`Api::namespaced_with`] or [`Api::all_with`].
+    ///
+    /// ```no_run
+    /// use kube::{
+    ///     api::{Api, ApiResource, DynamicObject, GroupVersionKind},
+    ///     runtime::wait::dynamic::await_dynamic_condition,
+    /// };
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let ar = ApiResource::from_gvk(&GroupVersionKind::gvk("apps", "v1", "Deployment"));
+    /// let api: Api<DynamicObject> = Api::namespaced_with(client, "default", &ar);
+    /// let condition = "condition=Available".parse()?;
+    /// let available = await_dynamic_condition(api, "web", condition);
+    /// tokio::time::timeout(std::time::Duration::from_secs(60), available).await??;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// See [`await_condition`].
+    pub async fn await_dynamic_condition(
+        api: Api<DynamicObject>,
+        name: &str,
+        condition: DynamicCondition,
+    ) -> Result<Option<DynamicObject>, super::Error> {
+        await_condition(api, name, condition).await
+    }
+
+    #[cfg(test)]
+    mod tests {
+        use super::{Condition, DynamicCondition};
+        use kube_client::api::DynamicObject;
+        use serde_json::json;
+
+        fn deployment() -> DynamicObject {
+            serde_json::from_value(json!({
+                "apiVersion": "apps/v1",
+                "kind": "Deployment",
+                "metadata": { "name": "web", "generation": 2 },
+                "spec": { "replicas": 2 },
+                "status": {
+                    "replicas": 2,
+                    "conditions": [
+                        { "type": "Available", "status": "True", "observedGeneration": 2 },
+                        { "type": "Progressing", "status": "True", "observedGeneration": 1 },
+                    ],
+                },
+            }))
+            .unwrap()
+        }
+
+        fn matches(condition: &str, obj: Option<&DynamicObject>) -> bool {
+            condition.parse::<DynamicCondition>().unwrap().matches_object(obj)
+        }
+
+        #[test]
+        fn matches_kubectl_wait_conditions() {
+            let obj = deployment();
+            assert!(matches("create", Some(&obj)));
+            assert!(!matches("delete", Some(&obj)));
+            assert!(matches("delete", None));
+            assert!(matches("condition=Available", Some(&obj)));
+            assert!(matches("condition=available=true", Some(&obj)));
+            assert!(!matches("condition=Available=false", Some(&obj)));
+            assert!(!matches("condition=Progressing", Some(&obj)));
+            assert!(matches("jsonpath={.status.replicas}=2", Some(&obj)));
+            assert!(matches("jsonpath=.spec.replicas=2", Some(&obj)));
+            assert!(!matches("jsonpath={.status.replicas}=3", Some(&obj)));
+            assert!(matches("jsonpath={.status.conditions}", Some(&obj)));
+            assert!(!matches("jsonpath={.status.readyReplicas}", Some(&obj)));
+        }
+
+        #[test]
+        fn rejects_invalid_conditions() {
+            for condition in ["ready", "condition=", "jsonpath={.status", "jsonpath={.status}x"] {
+                assert!(condition.parse::<DynamicCondition>().is_err(), "{condition}");
+            }
+        }
+    }
+}
+
+/// Utilities for waiting on the rollout of workloads
+pub mod rollout {
+    use std::{fmt::Debug, pin::pin};
+
+    use futures::TryStreamExt;
+    use k8s_openapi::api::apps::v1::{DaemonSet, Deployment, StatefulSet};
+    use kube_client::{Api, Resource};
+    use serde::de::DeserializeOwned;
+    use thiserror::Error;
+
+    use crate::watcher::{self, watch_object};
+
+    #[derive(Debug, Error)]
+    pub enum Error {
+        #[error("{kind} {name:?} not found")]
+        NotFound { kind: String, name: String },
+        #[error("deployment {0:?} exceeded its progress deadline")]
+        ProgressDeadlineExceeded(String),
+        #[error("rollout status is only available for the RollingUpdate strategy, not {0:?}")]
+        UnsupportedStrategy(String),
+        #[error("failed to watch the rollout: {0}")]
+        Watch(#[source] watcher::Error),
+    }
+
+    /// The progress of a rollout, see [`Rollout::rollout_status`]
+    #[derive(Clone, Debug, PartialEq, Eq)]
+    pub enum RolloutStatus {
+        /// The rollout is ongoing, with a description of what it is waiting for
+        Progressing(String),
+        /// The rollout has completed, with a description of the result
+        Complete(String),
+    }
+
+    impl RolloutStatus {
+        /// Whether the rollout has completed
+        #[must_use]
+        pub fn is_complete(&self) -> bool {
+            matches!(self, RolloutStatus::Complete(_))
+        }
+    }
+
+    /// A workload whose rollout can be tracked, like with `kubectl rollout status`
+    pub trait Rollout: Resource {
+        /// Evaluates the progress of the current rollout
+        ///
+        /// # Errors
+        ///
+        /// Fails if the rollout cannot complete, or cannot be tracked.
+        fn rollout_status(&self) -> Result<RolloutStatus, Error>;
+    }
+
+    fn waiting_for_spec_update(kind: &str) -> RolloutStatus {
+        RolloutStatus::Progressing(format!("waiting for {kind} spec update to be observed"))
+    }
+
+    impl Rollout for Deployment {
+        fn rollout_status(&self) -> Result<RolloutStatus, Error> {
+            let status = self.status.clone().unwrap_or_default();
+            if self.metadata.generation > status.observed_generation {
+                return Ok(waiting_for_spec_update("deployment"));
+            }
+            let progress_deadline_exceeded = status.conditions.iter().flatten().any(|cond| {
+                cond.type_ == "Progressing" && cond.reason.as_deref() == Some("ProgressDeadlineExceeded")
+            });
+            if progress_deadline_exceeded {
+                return Err(Error::ProgressDeadlineExceeded(
+                    self.metadata.name.clone().unwrap_or_default(),
+                ));
+            }
+            let updated = status.updated_replicas.unwrap_or_default();
+            let replicas = status.replicas.unwrap_or_default();
+            let available = status.available_replicas.unwrap_or_default();
+            if let Some(desired) = self.spec.as_ref().and_then(|spec| spec.replicas) {
+                if updated < desired {
+                    return Ok(RolloutStatus::Progressing(format!(
+                        "{updated} out of {desired} new replicas have been updated"
+                    )));
+                }
+            }
+            if replicas > updated {
+                return Ok(RolloutStatus::Progressing(format!(
+                    "{} old replicas are pending termination",
+                    replicas - updated
+                )));
+            }
+            if available < updated {
+                return Ok(RolloutStatus::Progressing(format!(
+                    "{available} of {updated} updated replicas are available"
+                )));
+            }
+            Ok(RolloutStatus::Complete("successfully rolled out".into()))
+        }
+    }
+
+    impl Rollout for DaemonSet {
+        fn rollout_status(&self) -> Result<RolloutStatus, Error> {
+            let strategy = self
+                .spec
+                .as_ref()
+                .and_then(|spec| spec.update_strategy.as_ref())
+                .and_then(|strategy| strategy.type_.as_deref());
+            if let Some(strategy) = strategy.filter(|strategy| *strategy != "RollingUpdate") {
+                return Err(Error::UnsupportedStrategy(strategy.to_string()));
+            }
+            let status = self.status.clone().unwrap_or_default();
+            if self.metadata.generation > status.observed_generation {
+                return Ok(waiting_for_spec_update("daemon set"));
+            }
+            let desired = status.desired_number_scheduled;
+            let updated = status.updated_number_scheduled.unwrap_or_default();
+            let available = status.number_available.unwrap_or_default();
+            if updated < desired {
+                return Ok(RolloutStatus::Progressing(format!(
+                    "{updated} out of {desired} new pods have been updated"
+                )));
+            }
+            if available < desired {
+                return Ok(RolloutStatus::Progressing(format!(
+                    "{available} of {desired} updated pods are available"
+                )));
+            }
+            Ok(RolloutStatus::Complete("successfully rolled out".into()))
+        }
+    }
+
+    impl Rollout for StatefulSet {
+        fn rollout_status(&self) -> Result<RolloutStatus, Error> {
+            let spec = self.spec.clone().unwrap_or_default();
+            let strategy = spec.update_strategy.unwrap_or_default();
+            if let Some(type_) = strategy.type_.filter(|type_| type_ != "RollingUpdate") {
+                return Err(Error::UnsupportedStrategy(type_));
+            }
+            let status = self.status.clone().unwrap_or_default();
+            if status.observed_generation.unwrap_or_default() == 0
+                || self.metadata.generation > status.observed_generation
+            {
+                return Ok(waiting_for_spec_update("statefulset"));
+            }
+            let ready = status.ready_replicas.unwrap_or_default();
+            let updated = status.updated_replicas.unwrap_or_default();
+            if let Some(desired) = spec.replicas {
+                if ready < desired {
+                    return Ok(RolloutStatus::Progressing(format!(
+                        "waiting for {} pods to be ready",
+                        desired - ready
+                    )));
+                }
+            }
+            let partition = strategy
+                .rolling_update
+                .and_then(|rolling_update| rolling_update.partition);
+            if let (Some(partition), Some(desired)) = (partition, spec.replicas) {
+                if updated < desired - partition {
+                    return Ok(RolloutStatus::Progressing(format!(
+                        "waiting for partitioned roll out to finish: {updated} out of {} new pods have been updated",
+                        desired - partition
+                    )));
+                }
+                return Ok(RolloutStatus::Complete(format!(
+                    "partitioned roll out complete: {updated} new pods have been updated"
+                )));
+            }
+            let update_revision = status.update_revision.unwrap_or_default();
+            if status.current_revision.as_ref() != Some(&update_revision) {
+                return Ok(RolloutStatus::Progressing(format!(
+                    "waiting for statefulset rolling update to complete {updated} pods at revision {update_revision}"
+                )));
+            }
+            Ok(RolloutStatus::Complete(format!(
+                "rolling update complete {} pods at revision {}",
+                status.current_replicas.unwrap_or_default(),
+                update_revision
+            )))
+        }
+    }
+
+    /// Waits for the rollout of a workload to complete, like `kubectl rollout status`
+    ///
+    /// The workload is returned once its rollout has completed, and the progress is logged in the meantime.
+    /// Like [`await_condition`](super::await_condition), this does not time out by itself.
+    ///
+    /// ```no_run
+    /// use k8s_openapi::api::apps::v1::Deployment;
+    /// use kube::{Api, runtime::wait::rollout::rollout_status};
+    /// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+    /// # let client: kube::Client = todo!();
+    /// let deployments: Api<Deployment> = Api::default_namespaced(client);
+    /// let rollout = rollout_status(deployments, "web");
+    /// tokio::time::timeout(std::time::Duration::from_secs(300), rollout).await??;
+    /// # Ok(())
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Fails if the workload is not found, if the rollout cannot complete, or if the watch fails.
+    #[allow(clippy::missing_panics_doc)] // watch never actually terminates, expect cannot fail
+    #[allow(clippy::module_name_repetitions)]
+    pub async fn rollout_status<K>(api: Api<K>, name: &str) -> Result<K, Error>
+    where
+        K: Rollout + Clone + Debug + Send + DeserializeOwned + 'static,
+        K::DynamicType: Default,
+    {
+        let kind = K::kind(&K::DynamicType::default()).to_string();
+        let mut stream = pin!(watch_object(api, name));
+        loop {
+            let obj = stream
+                .try_next()
+                .await
+                .map_err(Error::Watch)?
+                .expect("stream must not terminate");
+            let Some(obj) = obj else {
+                return Err(Error::NotFound {
+                    kind,
+                    name: name.to_string(),
+                });
+            };
+            match obj.rollout_status()? {
+                RolloutStatus::Complete(message) => {
+                    tracing::debug!(%kind, name, %message, "rollout complete");
+                    return Ok(obj);
+                }
+                RolloutStatus::Progressing(message) => {
+                    tracing::debug!(%kind, name, %message, "waiting for rollout");
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        conditions,
+        rollout::{Rollout, RolloutStatus},
+        Condition,
+    };
+    use k8s_openapi::api::apps::v1::Deployment;
+    use serde_json::json;
+
+    #[allow(clippy::needless_pass_by_value)]
+    fn deployment(generation: i64, status: serde_json::Value) -> Deployment {
+        serde_json::from_value(json!({
+            "metadata": { "name": "web", "generation": generation },
+            "spec": { "replicas": 3, "selector": {}, "template": {} },
+            "status": status,
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn deployment_rollout_status() {
+        let unobserved = deployment(2, json!({ "observedGeneration": 1 }));
+        assert!(matches!(
+            unobserved.rollout_status(),
+            Ok(RolloutStatus::Progressing(_))
+        ));
+
+        let updating = deployment(
+            2,
+            json!({
+                "observedGeneration": 2, "replicas": 4, "updatedReplicas": 3, "availableReplicas": 2,
+            }),
+        );
+        assert_eq!(
+            updating.rollout_status().unwrap(),
+            RolloutStatus::Progressing("1 old replicas are pending termination".into())
+        );
+        assert!(!conditions::is_deployment_rolled_out().matches_object(Some(&updating)));
+
+        let complete = deployment(
+            2,
+            json!({
+                "observedGeneration": 2, "replicas": 3, "updatedReplicas": 3, "availableReplicas": 3,
+            }),
+        );
+        assert!(complete.rollout_status().unwrap().is_complete());
+        assert!(conditions::is_deployment_rolled_out().matches_object(Some(&complete)));
+        assert!(!conditions::is_deployment_rolled_out().matches_object(None));
+
+        let stuck = deployment(
+            2,
+            json!({
+                "observedGeneration": 2,
+                "conditions": [{ "type": "Progressing", "status": "False", "reason": "ProgressDeadlineExceeded" }],
+            }),
+        );
+        assert!(stuck.rollout_status().is_err());
+    }
 }
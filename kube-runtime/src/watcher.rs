@@ -9,13 +9,14 @@ use educe::Educe;
 use futures::{stream::BoxStream, Stream, StreamExt};
 use kube_client::{
     api::{ListParams, Resource, ResourceExt, VersionMatch, WatchEvent, WatchParams},
-    core::{metadata::PartialObjectMeta, ObjectList, Selector},
+    core::{metadata::PartialObjectMeta, FieldSelector, ObjectList, Selector},
     error::ErrorResponse,
     Api, Error as ClientErr,
 };
 use serde::de::DeserializeOwned;
 use std::{clone::Clone, collections::VecDeque, fmt::Debug, future, time::Duration};
 use thiserror::Error;
+use tokio::sync::watch;
 use tracing::{debug, error, warn};
 
 #[derive(Debug, Error)]
@@ -47,6 +48,9 @@ pub enum Event<K> {
     ///
     /// A series of `InitApply` events are expected to follow until all matching objects
     /// have been listed. This event can be used to prepare a buffer for `InitApply` events.
+    ///
+    /// Watchers resuming from [`Config::resume_from`] only emit this event when the resourceVersion
+    /// has expired, and all objects have to be relisted.
     Init,
     /// Received an object during `Init`.
     ///
@@ -161,6 +165,18 @@ enum State<K> {
     },
 }
 
+impl<K> State<K> {
+    /// The resourceVersion that a new watch could resume from, if all events up to it have been emitted
+    fn resource_version(&self) -> Option<&str> {
+        match self {
+            State::InitListed { resource_version } | State::Watching { resource_version, .. } => {
+                Some(resource_version)
+            }
+            State::Empty | State::InitPage { .. } | State::InitialWatch { .. } => None,
+        }
+    }
+}
+
 /// Used to control whether the watcher receives the full object, or only the
 /// metadata
 #[async_trait]
@@ -270,6 +286,10 @@ pub struct Config {
     ///
     /// Defaults to 500. Note that `None` represents unbounded.
     ///
+    /// Pages are emitted as [`Event::InitApply`] events as they arrive, so only one page is held by the watcher.
+    /// See [`Writer::incremental_relists`](crate::reflector::store::Writer::incremental_relists)
+    /// to also avoid buffering the full list in reflectors.
+    ///
     /// NB: This option only has an effect for [`InitialListStrategy::ListWatch`].
     pub page_size: Option<u32>,
 
@@ -277,7 +297,18 @@ pub struct Config {
     ///
     /// Requests watch bookmarks from the apiserver when enabled for improved watch precision and reduced list calls.
     /// This is default enabled and should generally not be turned off.
+    ///
+    /// The apiserver decides how often bookmarks are sent, currently about once a minute and before a watch
+    /// times out. Bookmarks are not emitted as events, but the resourceVersion they carry is published to the
+    /// [`Checkpoint`] of [`watcher_with_checkpoint`].
+    ///
+    /// NB: The initial watch of [`InitialListStrategy::StreamingList`] always requests bookmarks.
     pub bookmarks: bool,
+
+    /// A resourceVersion to resume watching from, instead of starting with a list of all objects.
+    ///
+    /// Defaults to starting with a list if `None`. See [`Config::resume_from`].
+    pub resume_from: Option<String>,
 }
 
 impl Default for Config {
@@ -292,6 +323,7 @@ impl Default for Config {
             // https://github.com/kubernetes/client-go/blob/aed71fa5cf054e1c196d67b2e21f66fd967b8ab1/tools/pager/pager.go#L31
             page_size: Some(500),
             initial_list_strategy: InitialListStrategy::ListWatch,
+            resume_from: None,
         }
     }
 }
@@ -358,6 +390,20 @@ impl Config {
         self
     }
 
+    /// Configure typed field selectors
+    ///
+    /// ```
+    /// use kube_runtime::watcher::Config;
+    /// use kube_client::core::{fields, FieldSelector};
+    /// let selector = FieldSelector::default().equal(fields::METADATA_NAME, "my-pod");
+    /// let cfg = Config::default().fields_from(&selector);
+    ///```
+    #[must_use]
+    pub fn fields_from(mut self, selector: &FieldSelector) -> Self {
+        self.field_selector = Some(selector.to_string());
+        self
+    }
+
     /// Sets list semantic to configure re-list performance and consistency
     ///
     /// NB: This option only has an effect for [`InitialListStrategy::ListWatch`].
@@ -375,6 +421,13 @@ impl Config {
         self.list_semantic(ListSemantic::Any)
     }
 
+    /// Configures whether to request watch bookmarks, see [`Config::bookmarks`]
+    #[must_use]
+    pub fn bookmarks(mut self, enabled: bool) -> Self {
+        self.bookmarks = enabled;
+        self
+    }
+
     /// Disables watch bookmarks to simplify watch handling
     ///
     /// This is not recommended to use with production watchers as it can cause desyncs.
@@ -405,6 +458,33 @@ impl Config {
         self
     }
 
+    /// Resumes watching from a resourceVersion, such as one stored from the [`Checkpoint`] of an earlier watcher
+    ///
+    /// The watcher skips the initial list, and only emits the changes made after `resource_version`.
+    /// This avoids a full relist when a controller that persists its progress restarts.
+    ///
+    /// If the resourceVersion has expired (`410 Gone`), the watcher falls back to a relist, starting with an
+    /// [`Event::Init`]. Callers that keep state derived from the watch should treat that event as a signal
+    /// that objects may have been deleted while they were not watching.
+    ///
+    /// NB: A [`reflector`](crate::reflector()) only becomes ready after a relist, so its store stays empty
+    /// until the resourceVersion expires.
+    #[must_use]
+    pub fn resume_from(mut self, resource_version: impl Into<String>) -> Self {
+        self.resume_from = Some(resource_version.into());
+        self
+    }
+
+    /// The state a watcher starts in
+    fn initial_state<K>(&self) -> State<K> {
+        match &self.resume_from {
+            Some(resource_version) => State::InitListed {
+                resource_version: resource_version.clone(),
+            },
+            None => State::default(),
+        }
+    }
+
     /// Converts generic `watcher::Config` structure to the instance of `ListParams` used for list requests.
     fn to_list_params(&self) -> ListParams {
         let (resource_version, version_match) = match self.list_semantic {
@@ -430,7 +510,19 @@ impl Config {
             field_selector: self.field_selector.clone(),
             timeout: self.timeout,
             bookmarks: self.bookmarks,
-            send_initial_events: self.initial_list_strategy == InitialListStrategy::StreamingList,
+            send_initial_events: false,
+        }
+    }
+
+    /// Converts generic `watcher::Config` structure to the instance of `WatchParams` used for the
+    /// initial watch of [`InitialListStrategy::StreamingList`].
+    ///
+    /// The end of the initial events is signalled by a bookmark, so bookmarks are always enabled here.
+    fn to_initial_watch_params(&self) -> WatchParams {
+        WatchParams {
+            bookmarks: true,
+            send_initial_events: true,
+            ..self.to_watch_params()
         }
     }
 }
@@ -502,8 +594,8 @@ where
                 objects: VecDeque::default(),
                 last_bookmark: None,
             }),
-            InitialListStrategy::StreamingList => match api.watch(&wc.to_watch_params(), "0").await {
-                Ok(stream) => (None, State::InitialWatch { stream }),
+            InitialListStrategy::StreamingList => match api.watch(&wc.to_initial_watch_params(), "0").await {
+                Ok(stream) => (Some(Ok(Event::Init)), State::InitialWatch { stream }),
                 Err(err) => {
                     if std::matches!(err, ClientErr::Api(ErrorResponse { code: 403, .. })) {
                         warn!("watch initlist error with 403: {err:?}");
@@ -619,9 +711,13 @@ where
                     } else {
                         debug!("watch initlist error: {err:?}");
                     }
-                    (Some(Err(Error::WatchStartFailed(err))), State::InitListed {
-                        resource_version,
-                    })
+                    // HTTP GONE, the resourceVersion has expired so we need to start over and re-list
+                    let new_state = if std::matches!(err, ClientErr::Api(ErrorResponse { code: 410, .. })) {
+                        State::default()
+                    } else {
+                        State::InitListed { resource_version }
+                    };
+                    (Some(Err(Error::WatchStartFailed(err))), new_state)
                 }
             }
         }
@@ -689,19 +785,32 @@ where
 }
 
 /// Trampoline helper for `step_trampolined`
+///
+/// Publishes the resourceVersion of every state to `checkpoint`, including those only seen in bookmarks.
 async fn step<A>(
     api: &A,
     config: &Config,
     mut state: State<A::Value>,
+    checkpoint: &watch::Sender<Option<String>>,
 ) -> (Result<Event<A::Value>>, State<A::Value>)
 where
     A: ApiMode,
     A::Value: Resource + 'static,
 {
     loop {
-        match step_trampolined(api, config, state).await {
-            (Some(result), new_state) => return (result, new_state),
-            (None, new_state) => state = new_state,
+        let (result, new_state) = step_trampolined(api, config, state).await;
+        if let Some(resource_version) = new_state.resource_version() {
+            checkpoint.send_if_modified(|current| {
+                let modified = current.as_deref() != Some(resource_version);
+                if modified {
+                    *current = Some(resource_version.to_string());
+                }
+                modified
+            });
+        }
+        match result {
+            Some(result) => return (result, new_state),
+            None => state = new_state,
         }
     }
 }
@@ -759,13 +868,76 @@ pub fn watcher<K: Resource + Clone + DeserializeOwned + Debug + Send + 'static>(
     api: Api<K>,
     watcher_config: Config,
 ) -> impl Stream<Item = Result<Event<K>>> + Send {
-    futures::stream::unfold(
-        (api, watcher_config, State::default()),
-        |(api, watcher_config, state)| async {
-            let (event, state) = step(&FullObject { api: &api }, &watcher_config, state).await;
-            Some((event, (api, watcher_config, state)))
+    watcher_with_checkpoint(api, watcher_config).0
+}
+
+/// Watches a Kubernetes Resource for changes continuously, and tracks the last seen resourceVersion
+///
+/// This is a [`watcher`] that also returns a [`Checkpoint`] handle. The handle is updated with the
+/// resourceVersion of every event of the watch and every bookmark from the apiserver, so applications can
+/// store it, such as when shutting down, and later [resume](Config::resume_from) the watch from there.
+///
+/// ```no_run
+/// use kube::{api::Api, Client, runtime::{watcher, WatchStreamExt}};
+/// use k8s_openapi::api::core::v1::Pod;
+/// use futures::TryStreamExt;
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let client = Client::try_default().await?;
+/// let pods: Api<Pod> = Api::all(client);
+/// let (stream, checkpoint) = watcher::watcher_with_checkpoint(pods, watcher::Config::default());
+/// let checkpoint = &checkpoint;
+/// stream
+///     .applied_objects()
+///     .try_for_each(|_pod| async move {
+///         // the event has been handled, so the watch would not need to be replayed up to here
+///         if let Some(resource_version) = checkpoint.resource_version() {
+///             println!("processed up to {resource_version}");
+///         }
+///         Ok(())
+///     })
+///     .await?;
+/// # Ok(())
+/// # }
+/// ```
+#[allow(clippy::module_name_repetitions)]
+pub fn watcher_with_checkpoint<K: Resource + Clone + DeserializeOwned + Debug + Send + 'static>(
+    api: Api<K>,
+    watcher_config: Config,
+) -> (impl Stream<Item = Result<Event<K>>> + Send, Checkpoint) {
+    let (checkpoint, receiver) = watch::channel(watcher_config.resume_from.clone());
+    let state = watcher_config.initial_state();
+    let stream = futures::stream::unfold(
+        (api, watcher_config, state, checkpoint),
+        |(api, watcher_config, state, checkpoint)| async {
+            let (event, state) = step(&FullObject { api: &api }, &watcher_config, state, &checkpoint).await;
+            Some((event, (api, watcher_config, state, checkpoint)))
         },
-    )
+    );
+    (stream, Checkpoint(receiver))
+}
+
+/// The last resourceVersion seen by a watcher, see [`watcher_with_checkpoint`]
+///
+/// The resourceVersion is updated before the event carrying it is emitted, so it is safe to store once that
+/// event has been processed. It is `None` until the initial list has completed, unless the watcher
+/// [resumed](Config::resume_from) from a resourceVersion.
+#[derive(Clone, Debug)]
+pub struct Checkpoint(watch::Receiver<Option<String>>);
+
+impl Checkpoint {
+    /// The last resourceVersion seen by the watcher
+    #[must_use]
+    pub fn resource_version(&self) -> Option<String> {
+        self.0.borrow().clone()
+    }
+
+    /// Waits until the watcher sees a new resourceVersion, and returns it
+    ///
+    /// Returns `None` once the watcher stream has been dropped.
+    pub async fn changed(&mut self) -> Option<String> {
+        self.0.changed().await.ok()?;
+        self.0.borrow_and_update().clone()
+    }
 }
 
 /// Watches a Kubernetes Resource for changes continuously and receives only the
@@ -823,11 +995,13 @@ pub fn metadata_watcher<K: Resource + Clone + DeserializeOwned + Debug + Send +
     api: Api<K>,
     watcher_config: Config,
 ) -> impl Stream<Item = Result<Event<PartialObjectMeta<K>>>> + Send {
+    let (checkpoint, _) = watch::channel(None);
+    let state = watcher_config.initial_state();
     futures::stream::unfold(
-        (api, watcher_config, State::default()),
-        |(api, watcher_config, state)| async {
-            let (event, state) = step(&MetaOnly { api: &api }, &watcher_config, state).await;
-            Some((event, (api, watcher_config, state)))
+        (api, watcher_config, state, checkpoint),
+        |(api, watcher_config, state, checkpoint)| async {
+            let (event, state) = step(&MetaOnly { api: &api }, &watcher_config, state, &checkpoint).await;
+            Some((event, (api, watcher_config, state, checkpoint)))
         },
     )
 }
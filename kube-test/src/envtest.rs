@@ -0,0 +1,458 @@
+//! Standalone apiservers for integration tests, see [`Environment`].
+use std::{
+    fs::File,
+    net::TcpListener,
+    path::{Path, PathBuf},
+    process::{Child, Command, Stdio},
+    time::Duration,
+};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+use kube::{
+    api::{Api, PostParams},
+    config::{
+        AuthInfo, Cluster, Context, KubeConfigOptions, Kubeconfig, NamedAuthInfo, NamedCluster, NamedContext,
+    },
+    runtime::wait::{await_condition, conditions},
+    Client, Config, ResourceExt,
+};
+use rcgen::{
+    BasicConstraints, CertificateParams, DnType, ExtendedKeyUsagePurpose, IsCa, KeyPair, KeyUsagePurpose,
+};
+use tempfile::TempDir;
+use tokio::time::Instant;
+
+use crate::{Error, TestNamespace};
+
+/// The environment variable pointing at a directory with the `kube-apiserver` and `etcd` binaries
+///
+/// This is the same variable as used by controller-runtime's envtest, and is printed by `setup-envtest use -p env`.
+pub const ASSETS_ENV: &str = "KUBEBUILDER_ASSETS";
+
+const DEFAULT_STARTUP_TIMEOUT: Duration = Duration::from_secs(60);
+const CONTEXT: &str = "envtest";
+const LOG_TAIL_LINES: usize = 20;
+
+/// Builds and starts a [`TestApiServer`]
+///
+/// The `kube-apiserver` and `etcd` binaries are taken from the first of:
+/// 1. the directory set with [`Environment::assets_dir`]
+/// 2. the directory in the [`KUBEBUILDER_ASSETS`](ASSETS_ENV) environment variable
+/// 3. a download by [`setup-envtest`](https://github.com/kubernetes-sigs/controller-runtime/tree/main/tools/setup-envtest),
+///    of the version set with [`Environment::kubernetes_version`], or the latest version otherwise
+///
+/// ```no_run
+/// use k8s_openapi::apiextensions_apiserver::pkg::apis::apiextensions::v1::CustomResourceDefinition;
+/// use kube::{api::{Api, DynamicObject, GroupVersionKind}, discovery};
+/// use kube_test::envtest::Environment;
+///
+/// # async fn wrapper(document_crd: CustomResourceDefinition) -> Result<(), Box<dyn std::error::Error>> {
+/// let server = Environment::new().crd(document_crd).start().await?;
+/// let client = server.client();
+/// let gvk = GroupVersionKind::gvk("kube.rs", "v1", "Document");
+/// let (document, _) = discovery::pinned_kind(&client, &gvk).await?;
+/// let documents: Api<DynamicObject> = Api::default_namespaced_with(client, &document);
+/// assert!(documents.list(&Default::default()).await?.items.is_empty());
+/// # Ok(())
+/// # }
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct Environment {
+    assets_dir: Option<PathBuf>,
+    kubernetes_version: Option<String>,
+    crds: Vec<CustomResourceDefinition>,
+    apiserver_args: Vec<String>,
+    startup_timeout: Option<Duration>,
+}
+
+impl Environment {
+    /// An environment with the default settings
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Use the `kube-apiserver` and `etcd` binaries in `dir`
+    #[must_use]
+    pub fn assets_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.assets_dir = Some(dir.into());
+        self
+    }
+
+    /// Download this version of Kubernetes when the binaries are not installed, such as `1.32.x`
+    #[must_use]
+    pub fn kubernetes_version(mut self, version: impl Into<String>) -> Self {
+        self.kubernetes_version = Some(version.into());
+        self
+    }
+
+    /// Install a `CustomResourceDefinition` before the server is returned
+    ///
+    /// The server is only returned once the CRD is established, so its resource can be used right away.
+    #[must_use]
+    pub fn crd(mut self, crd: CustomResourceDefinition) -> Self {
+        self.crds.push(crd);
+        self
+    }
+
+    /// Pass an extra flag to the `kube-apiserver`, such as `--feature-gates=...`
+    #[must_use]
+    pub fn apiserver_arg(mut self, arg: impl Into<String>) -> Self {
+        self.apiserver_args.push(arg.into());
+        self
+    }
+
+    /// How long to wait for the apiserver to become ready, and for the CRDs to be established
+    ///
+    /// Defaults to 60 seconds.
+    #[must_use]
+    pub fn startup_timeout(mut self, timeout: Duration) -> Self {
+        self.startup_timeout = Some(timeout);
+        self
+    }
+
+    /// Start `etcd` and the `kube-apiserver`, and install the CRDs
+    ///
+    /// # Errors
+    ///
+    /// Fails if the binaries cannot be found or downloaded, if either process fails to start,
+    /// or if the apiserver is not ready within the [startup timeout](Environment::startup_timeout).
+    pub async fn start(self) -> Result<TestApiServer, Error> {
+        let timeout = self.startup_timeout.unwrap_or(DEFAULT_STARTUP_TIMEOUT);
+        let assets = self.assets().await?;
+        let dir = tempfile::Builder::new()
+            .prefix("kube-envtest-")
+            .tempdir()
+            .map_err(Error::Io)?;
+        let certs = Certificates::generate().map_err(Error::Certificates)?;
+        certs.write(dir.path()).map_err(Error::Io)?;
+
+        let port = free_port().map_err(Error::Io)?;
+        let kubeconfig = certs.kubeconfig(format!("https://127.0.0.1:{port}"));
+        let config = Config::from_custom_kubeconfig(kubeconfig.clone(), &KubeConfigOptions::default())
+            .await
+            .map_err(Error::Kubeconfig)?;
+        let client = Client::try_from(config.clone()).map_err(Error::Kube)?;
+
+        let etcd_url = format!("http://127.0.0.1:{}", free_port().map_err(Error::Io)?);
+        let peer_url = format!("http://127.0.0.1:{}", free_port().map_err(Error::Io)?);
+        let mut etcd = spawn(
+            Command::new(assets.join("etcd"))
+                .arg("--name=envtest")
+                .arg(format!("--data-dir={}", dir.path().join("etcd").display()))
+                .arg(format!("--listen-client-urls={etcd_url}"))
+                .arg(format!("--advertise-client-urls={etcd_url}"))
+                .arg(format!("--listen-peer-urls={peer_url}"))
+                .arg(format!("--initial-advertise-peer-urls={peer_url}"))
+                .arg(format!("--initial-cluster=envtest={peer_url}"))
+                .arg("--unsafe-no-fsync"),
+            "etcd",
+            dir.path(),
+        )?;
+
+        let file = |name: &str| dir.path().join(name).display().to_string();
+        let apiserver = spawn(
+            Command::new(assets.join("kube-apiserver"))
+                .arg(format!("--etcd-servers={etcd_url}"))
+                .arg("--bind-address=127.0.0.1")
+                .arg("--advertise-address=127.0.0.1")
+                .arg(format!("--secure-port={port}"))
+                .arg(format!("--cert-dir={}", dir.path().display()))
+                .arg(format!("--tls-cert-file={}", file("apiserver.crt")))
+                .arg(format!("--tls-private-key-file={}", file("apiserver.key")))
+                .arg(format!("--client-ca-file={}", file("ca.crt")))
+                .arg(format!("--service-account-issuer=https://127.0.0.1:{port}"))
+                .arg(format!("--service-account-key-file={}", file("sa.key")))
+                .arg(format!("--service-account-signing-key-file={}", file("sa.key")))
+                .arg("--service-cluster-ip-range=10.0.0.0/24")
+                .arg("--allow-privileged=true")
+                .arg("--authorization-mode=RBAC")
+                .arg("--disable-admission-plugins=ServiceAccount")
+                .args(&self.apiserver_args),
+            "kube-apiserver",
+            dir.path(),
+        )
+        .inspect_err(|_| stop(&mut etcd))?;
+
+        let mut server = TestApiServer {
+            etcd,
+            apiserver,
+            config,
+            kubeconfig,
+            client,
+            dir,
+        };
+        server.wait_ready(timeout).await?;
+        for crd in self.crds {
+            server.install_crd(crd, timeout).await?;
+        }
+        Ok(server)
+    }
+
+    async fn assets(&self) -> Result<PathBuf, Error> {
+        if let Some(dir) = &self.assets_dir {
+            return Ok(dir.clone());
+        }
+        if let Some(dir) = std::env::var_os(ASSETS_ENV) {
+            return Ok(dir.into());
+        }
+        let mut setup = tokio::process::Command::new("setup-envtest");
+        setup.arg("use");
+        if let Some(version) = &self.kubernetes_version {
+            setup.arg(version);
+        }
+        let output = setup.args(["--print", "path"]).output().await.map_err(|err| {
+            Error::Assets(format!(
+                "{ASSETS_ENV} is not set, and setup-envtest could not be run to download them: {err}"
+            ))
+        })?;
+        if !output.status.success() {
+            return Err(Error::Assets(format!(
+                "setup-envtest failed: {}",
+                String::from_utf8_lossy(&output.stderr).trim()
+            )));
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).trim().into())
+    }
+}
+
+/// A running `kube-apiserver` and `etcd`, see [`Environment`]
+///
+/// Both processes are stopped, and their data deleted, when this is dropped.
+pub struct TestApiServer {
+    etcd: Child,
+    apiserver: Child,
+    config: Config,
+    kubeconfig: Kubeconfig,
+    client: Client,
+    dir: TempDir,
+}
+
+impl TestApiServer {
+    /// A client authenticated as a cluster admin
+    pub fn client(&self) -> Client {
+        self.client.clone()
+    }
+
+    /// The configuration of [`TestApiServer::client`]
+    pub fn config(&self) -> &Config {
+        &self.config
+    }
+
+    /// A kubeconfig for the server, to share it with other tools such as `kubectl`
+    pub fn kubeconfig(&self) -> &Kubeconfig {
+        &self.kubeconfig
+    }
+
+    /// Creates a [`TestNamespace`] on the server
+    ///
+    /// There is no namespace controller to finalize deleted namespaces, so the namespace is deleted without waiting.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the namespace cannot be created.
+    pub async fn namespace(&self) -> Result<TestNamespace, Error> {
+        Ok(TestNamespace::new(self.config.clone())
+            .await?
+            .deletion_timeout(Duration::ZERO))
+    }
+
+    async fn wait_ready(&mut self, timeout: Duration) -> Result<(), Error> {
+        let deadline = Instant::now() + timeout;
+        loop {
+            for (name, process) in [("etcd", &mut self.etcd), ("kube-apiserver", &mut self.apiserver)] {
+                if let Some(status) = process.try_wait().map_err(Error::Io)? {
+                    let log = log_tail(self.dir.path(), name);
+                    return Err(Error::Exited(format!("{name} ({status})"), log));
+                }
+            }
+            let readyz = http::Request::get("/readyz")
+                .body(Vec::new())
+                .expect("static request is valid");
+            if self.client.request_text(readyz).await.is_ok() {
+                return Ok(());
+            }
+            if Instant::now() >= deadline {
+                return Err(Error::StartupTimeout(
+                    timeout,
+                    log_tail(self.dir.path(), "kube-apiserver"),
+                ));
+            }
+            tokio::time::sleep(Duration::from_millis(200)).await;
+        }
+    }
+
+    async fn install_crd(&self, crd: CustomResourceDefinition, timeout: Duration) -> Result<(), Error> {
+        let crds: Api<CustomResourceDefinition> = Api::all(self.client());
+        let name = crd.name_any();
+        crds.create(&PostParams::default(), &crd)
+            .await
+            .map_err(Error::Kube)?;
+        let established = await_condition(crds, &name, conditions::is_crd_established());
+        tokio::time::timeout(timeout, established)
+            .await
+            .map_err(|_| Error::Timeout(format!("CRD {name} was not established"), timeout))?
+            .map_err(Error::Wait)?;
+        Ok(())
+    }
+}
+
+impl Drop for TestApiServer {
+    fn drop(&mut self) {
+        // The apiserver is stopped first, so it does not log errors about the missing etcd
+        stop(&mut self.apiserver);
+        stop(&mut self.etcd);
+    }
+}
+
+/// The certificates and keys of a test environment, all signed by a generated CA
+struct Certificates {
+    ca: String,
+    serving_cert: String,
+    serving_key: String,
+    client_cert: String,
+    client_key: String,
+    service_account_key: String,
+}
+
+impl Certificates {
+    fn generate() -> Result<Self, rcgen::Error> {
+        let ca_key = KeyPair::generate()?;
+        let mut ca_params = CertificateParams::new(Vec::new())?;
+        ca_params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+        ca_params
+            .distinguished_name
+            .push(DnType::CommonName, "kube-envtest-ca");
+        ca_params.key_usages = vec![KeyUsagePurpose::KeyCertSign, KeyUsagePurpose::DigitalSignature];
+        let ca = ca_params.self_signed(&ca_key)?;
+
+        let serving_key = KeyPair::generate()?;
+        let mut serving_params =
+            CertificateParams::new(vec!["localhost".to_owned(), "127.0.0.1".to_owned()])?;
+        serving_params
+            .distinguished_name
+            .push(DnType::CommonName, "kube-apiserver");
+        serving_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ServerAuth];
+        let serving_cert = serving_params.signed_by(&serving_key, &ca, &ca_key)?;
+
+        // members of system:masters are allowed everything by RBAC
+        let client_key = KeyPair::generate()?;
+        let mut client_params = CertificateParams::new(Vec::new())?;
+        client_params
+            .distinguished_name
+            .push(DnType::CommonName, "kube-envtest-admin");
+        client_params
+            .distinguished_name
+            .push(DnType::OrganizationName, "system:masters");
+        client_params.extended_key_usages = vec![ExtendedKeyUsagePurpose::ClientAuth];
+        let client_cert = client_params.signed_by(&client_key, &ca, &ca_key)?;
+
+        Ok(Self {
+            ca: ca.pem(),
+            serving_cert: serving_cert.pem(),
+            serving_key: serving_key.serialize_pem(),
+            client_cert: client_cert.pem(),
+            client_key: client_key.serialize_pem(),
+            service_account_key: KeyPair::generate()?.serialize_pem(),
+        })
+    }
+
+    fn write(&self, dir: &Path) -> std::io::Result<()> {
+        std::fs::write(dir.join("ca.crt"), &self.ca)?;
+        std::fs::write(dir.join("apiserver.crt"), &self.serving_cert)?;
+        std::fs::write(dir.join("apiserver.key"), &self.serving_key)?;
+        std::fs::write(dir.join("sa.key"), &self.service_account_key)
+    }
+
+    fn kubeconfig(&self, server: String) -> Kubeconfig {
+        Kubeconfig {
+            clusters: vec![NamedCluster {
+                name: CONTEXT.into(),
+                cluster: Some(Cluster {
+                    server: Some(server),
+                    certificate_authority_data: Some(STANDARD.encode(&self.ca)),
+                    ..Cluster::default()
+                }),
+            }],
+            auth_infos: vec![NamedAuthInfo {
+                name: CONTEXT.into(),
+                auth_info: Some(AuthInfo {
+                    client_certificate_data: Some(STANDARD.encode(&self.client_cert)),
+                    client_key_data: Some(STANDARD.encode(&self.client_key).into()),
+                    ..AuthInfo::default()
+                }),
+            }],
+            contexts: vec![NamedContext {
+                name: CONTEXT.into(),
+                context: Some(Context {
+                    cluster: CONTEXT.into(),
+                    user: Some(CONTEXT.into()),
+                    ..Context::default()
+                }),
+            }],
+            current_context: Some(CONTEXT.into()),
+            ..Kubeconfig::default()
+        }
+    }
+}
+
+/// Spawns a process of the environment, with its output logged to `<name>.log` in `dir`
+fn spawn(command: &mut Command, name: &str, dir: &Path) -> Result<Child, Error> {
+    let log = File::create(dir.join(format!("{name}.log"))).map_err(Error::Io)?;
+    command
+        .stdin(Stdio::null())
+        .stdout(log.try_clone().map_err(Error::Io)?)
+        .stderr(log)
+        .spawn()
+        .map_err(|err| Error::Spawn(name.to_owned(), err))
+}
+
+/// Kills a process of the environment, and waits for it to exit
+fn stop(process: &mut Child) {
+    if let Err(err) = process.kill().and_then(|()| process.wait()) {
+        tracing::warn!("failed to stop envtest process {}: {err}", process.id());
+    }
+}
+
+/// The last lines logged by a process, to explain why it failed
+fn log_tail(dir: &Path, name: &str) -> String {
+    let log = std::fs::read_to_string(dir.join(format!("{name}.log"))).unwrap_or_default();
+    let lines = log.lines().collect::<Vec<_>>();
+    lines[lines.len().saturating_sub(LOG_TAIL_LINES)..].join("\n")
+}
+
+/// A port that is free to listen on
+///
+/// Another process could take the port before it is used, but that is unlikely enough for tests.
+fn free_port() -> std::io::Result<u16> {
+    Ok(TcpListener::bind("127.0.0.1:0")?.local_addr()?.port())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Certificates, Environment};
+    use k8s_openapi::api::core::v1::Namespace;
+    use kube::{api::Api, Config};
+
+    #[tokio::test]
+    async fn kubeconfig_trusts_the_generated_ca() {
+        let certs = Certificates::generate().unwrap();
+        let kubeconfig = certs.kubeconfig("https://127.0.0.1:6443".into());
+        let config = Config::from_custom_kubeconfig(kubeconfig, &Default::default())
+            .await
+            .unwrap();
+        assert_eq!(config.cluster_url.port_u16(), Some(6443));
+        assert_eq!(config.root_cert.map(|certs| certs.len()), Some(1));
+        assert!(config.auth_info.client_certificate_data.is_some());
+        assert!(!config.accept_invalid_certs);
+    }
+
+    #[tokio::test]
+    #[ignore = "needs envtest binaries (see setup-envtest)"]
+    async fn serves_requests_as_an_admin() {
+        let server = Environment::new().start().await.unwrap();
+        let namespaces: Api<Namespace> = Api::all(server.client());
+        let default = namespaces.get("default").await.unwrap();
+        assert_eq!(default.metadata.name.as_deref(), Some("default"));
+    }
+}
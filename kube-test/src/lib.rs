@@ -0,0 +1,64 @@
+//! Integration test harnesses for kube
+//!
+//! The [`envtest`] module runs a standalone `kube-apiserver` and `etcd`, like controller-runtime's
+//! [envtest](https://book.kubebuilder.io/reference/envtest), so integration tests can run against a
+//! real apiserver without Docker or an existing cluster.
+//!
+//! Only the apiserver is run, so there are no controllers, schedulers or kubelets:
+//! pods are never scheduled, garbage collection does not happen, and namespaces are never finalized.
+//! Tests that need these should use a full cluster, like the one started by `just k3d`.
+//!
+//! A [`TestNamespace`] gives each test its own namespace, which is deleted when the test ends,
+//! so tests can run in parallel against the same apiserver or cluster.
+#![cfg_attr(docsrs, feature(doc_cfg))]
+
+pub mod envtest;
+pub mod namespace;
+pub use namespace::TestNamespace;
+
+use std::time::Duration;
+use thiserror::Error;
+
+/// Possible errors when setting up or tearing down a test environment
+#[derive(Error, Debug)]
+pub enum Error {
+    /// The `kube-apiserver` and `etcd` binaries could not be found or downloaded
+    #[error("failed to find the envtest binaries: {0}")]
+    Assets(String),
+
+    /// A process could not be started
+    #[error("failed to start {0}: {1}")]
+    Spawn(String, #[source] std::io::Error),
+
+    /// A process exited before the apiserver became ready
+    #[error("{0} exited during startup:\n{1}")]
+    Exited(String, String),
+
+    /// The apiserver did not become ready in time
+    #[error("kube-apiserver did not become ready within {0:?}:\n{1}")]
+    StartupTimeout(Duration, String),
+
+    /// Certificates for the apiserver could not be generated
+    #[error("failed to generate certificates: {0}")]
+    Certificates(#[source] rcgen::Error),
+
+    /// The working directory of the test environment could not be set up
+    #[error("failed to set up the test environment: {0}")]
+    Io(#[source] std::io::Error),
+
+    /// The kubeconfig of the test environment was rejected
+    #[error("failed to load the kubeconfig of the test environment: {0}")]
+    Kubeconfig(#[source] kube::config::KubeconfigError),
+
+    /// A request to the apiserver failed
+    #[error("request to the apiserver failed: {0}")]
+    Kube(#[source] kube::Error),
+
+    /// Waiting for a condition on an object failed
+    #[error("failed to wait for a condition: {0}")]
+    Wait(#[source] kube::runtime::wait::Error),
+
+    /// A condition was not fulfilled in time
+    #[error("{0} within {1:?}")]
+    Timeout(String, Duration),
+}
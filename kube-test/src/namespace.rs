@@ -0,0 +1,260 @@
+//! Isolated namespaces for tests that run in parallel, see [`TestNamespace`].
+use std::{collections::BTreeMap, time::Duration};
+
+use k8s_openapi::{api::core::v1::Namespace, NamespaceResourceScope};
+use kube::{
+    api::{Api, DeleteParams, ObjectMeta, PostParams},
+    core::{DynamicResourceScope, ErrorResponse},
+    runtime::wait::{await_condition, conditions},
+    Client, Config, Resource, ResourceExt,
+};
+
+use crate::Error;
+
+/// The label set on every namespace created by [`TestNamespace`], to find namespaces left behind by killed tests
+pub const MANAGED_BY_LABEL: (&str, &str) = ("app.kubernetes.io/managed-by", "kube-test");
+
+const DEFAULT_PREFIX: &str = "kube-test-";
+const DEFAULT_DELETION_TIMEOUT: Duration = Duration::from_secs(60);
+
+/// A uniquely named namespace that is deleted when dropped
+///
+/// Tests that each use their own namespace can run in parallel against the same cluster without colliding.
+/// The namespace is deleted, and its finalization awaited, when the guard is dropped. This also happens when the
+/// test panics, so failed tests do not leave their objects behind.
+///
+/// ```no_run
+/// use k8s_openapi::api::core::v1::ConfigMap;
+/// use kube::{api::{Api, PostParams}, Config};
+/// use kube_test::TestNamespace;
+///
+/// # async fn wrapper() -> Result<(), Box<dyn std::error::Error>> {
+/// let namespace = TestNamespace::new(Config::infer().await?).await?;
+/// let configmaps: Api<ConfigMap> = namespace.api();
+/// configmaps.create(&PostParams::default(), &Default::default()).await.ok();
+/// // the namespace and the configmaps in it are deleted here
+/// # Ok(())
+/// # }
+/// ```
+///
+/// Namespaces are finalized by the namespace controller of `kube-controller-manager`,
+/// which does not run in an [`envtest`](crate::envtest) environment. Deleted namespaces then stay `Terminating`,
+/// so [`TestApiServer::namespace`](crate::envtest::TestApiServer::namespace) does not wait for them.
+pub struct TestNamespace {
+    config: Config,
+    client: Client,
+    name: String,
+    uid: String,
+    deletion_timeout: Duration,
+    deleted: bool,
+}
+
+impl TestNamespace {
+    /// Creates a namespace named `kube-test-` followed by a random suffix
+    ///
+    /// The `config` is kept to delete the namespace on drop, from outside of the runtime of the test,
+    /// which may be blocked or shutting down by then.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the client cannot be created, or if the namespace cannot be created.
+    pub async fn new(config: Config) -> Result<Self, Error> {
+        Self::with_prefix(config, DEFAULT_PREFIX).await
+    }
+
+    /// Creates a namespace named `prefix` followed by a random suffix
+    ///
+    /// # Errors
+    ///
+    /// Fails if the client cannot be created, or if the namespace cannot be created.
+    pub async fn with_prefix(config: Config, prefix: &str) -> Result<Self, Error> {
+        let client = Client::try_from(config.clone()).map_err(Error::Kube)?;
+        let namespaces: Api<Namespace> = Api::all(client);
+        let namespace = Namespace {
+            metadata: ObjectMeta {
+                generate_name: Some(prefix.to_owned()),
+                labels: Some(BTreeMap::from([(
+                    MANAGED_BY_LABEL.0.to_owned(),
+                    MANAGED_BY_LABEL.1.to_owned(),
+                )])),
+                ..ObjectMeta::default()
+            },
+            ..Namespace::default()
+        };
+        let namespace = namespaces
+            .create(&PostParams::default(), &namespace)
+            .await
+            .map_err(Error::Kube)?;
+        let name = namespace.name_any();
+        let uid = namespace.uid().unwrap_or_default();
+
+        let mut config = config;
+        config.default_namespace.clone_from(&name);
+        let client = Client::try_from(config.clone()).map_err(Error::Kube)?;
+        Ok(Self {
+            config,
+            client,
+            name,
+            uid,
+            deletion_timeout: DEFAULT_DELETION_TIMEOUT,
+            deleted: false,
+        })
+    }
+
+    /// How long to wait for the namespace to be finalized when it is deleted
+    ///
+    /// Defaults to 60 seconds. With a zero timeout, the namespace is deleted without waiting for it.
+    #[must_use]
+    pub fn deletion_timeout(mut self, timeout: Duration) -> Self {
+        self.deletion_timeout = timeout;
+        self
+    }
+
+    /// The name of the namespace
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// A client that defaults to the namespace
+    ///
+    /// [`Api::default_namespaced`] handles created from this client are bound to the namespace.
+    pub fn client(&self) -> Client {
+        self.client.clone()
+    }
+
+    /// An [`Api`] for the resources of kind `K` in the namespace
+    pub fn api<K>(&self) -> Api<K>
+    where
+        K: Resource<Scope = NamespaceResourceScope>,
+        <K as Resource>::DynamicType: Default,
+    {
+        Api::namespaced(self.client(), &self.name)
+    }
+
+    /// An [`Api`] for the resources described by `dyntype` in the namespace
+    pub fn api_with<K>(&self, dyntype: &K::DynamicType) -> Api<K>
+    where
+        K: Resource<Scope = DynamicResourceScope>,
+    {
+        Api::namespaced_with(self.client(), &self.name, dyntype)
+    }
+
+    /// Deletes the namespace, and waits for it to be finalized
+    ///
+    /// This is done on drop as well, but errors are then only logged.
+    ///
+    /// # Errors
+    ///
+    /// Fails if the namespace cannot be deleted, or if it is not finalized within the
+    /// [`deletion_timeout`](TestNamespace::deletion_timeout).
+    pub async fn delete(mut self) -> Result<(), Error> {
+        self.deleted = true;
+        delete(self.client.clone(), &self.name, &self.uid, self.deletion_timeout).await
+    }
+}
+
+impl Drop for TestNamespace {
+    fn drop(&mut self) {
+        if self.deleted {
+            return;
+        }
+        let config = self.config.clone();
+        let (name, uid, timeout) = (self.name.clone(), self.uid.clone(), self.deletion_timeout);
+        // The runtime of the test may be blocked on this drop, or already shutting down when the test panicked,
+        // so the namespace is deleted from another thread, with its own runtime and client.
+        let deletion = std::thread::spawn(move || {
+            let runtime = tokio::runtime::Builder::new_current_thread()
+                .enable_all()
+                .build()
+                .map_err(Error::Io)?;
+            runtime.block_on(async {
+                let client = Client::try_from(config).map_err(Error::Kube)?;
+                delete(client, &name, &uid, timeout).await
+            })
+        });
+        match deletion.join() {
+            Ok(Ok(())) => {}
+            Ok(Err(err)) => tracing::warn!("failed to delete test namespace {}: {err}", self.name),
+            Err(_) => tracing::warn!("failed to delete test namespace {}: deletion panicked", self.name),
+        }
+    }
+}
+
+async fn delete(client: Client, name: &str, uid: &str, timeout: Duration) -> Result<(), Error> {
+    let namespaces: Api<Namespace> = Api::all(client);
+    match namespaces.delete(name, &DeleteParams::background()).await {
+        Ok(_) | Err(kube::Error::Api(ErrorResponse { code: 404, .. })) => {}
+        Err(err) => return Err(Error::Kube(err)),
+    }
+    if timeout.is_zero() {
+        return Ok(());
+    }
+    tokio::time::timeout(
+        timeout,
+        await_condition(namespaces, name, conditions::is_deleted(uid)),
+    )
+    .await
+    .map_err(|_| Error::Timeout(format!("namespace {name} was not finalized"), timeout))?
+    .map_err(Error::Wait)?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TestNamespace;
+    use crate::envtest::Environment;
+    use k8s_openapi::api::core::v1::{ConfigMap, Namespace};
+    use kube::{
+        api::{Api, PostParams},
+        Client, Config,
+    };
+
+    #[tokio::test]
+    #[ignore = "needs envtest binaries (see setup-envtest)"]
+    async fn namespaces_are_unique_and_deleted_on_drop() {
+        let server = Environment::new().start().await.unwrap();
+        let first = server.namespace().await.unwrap();
+        let second = server.namespace().await.unwrap();
+        assert_ne!(first.name(), second.name());
+
+        let configmaps: Api<ConfigMap> = Api::default_namespaced(first.client());
+        let configmap = ConfigMap {
+            metadata: kube::api::ObjectMeta {
+                name: Some("settings".into()),
+                ..Default::default()
+            },
+            ..Default::default()
+        };
+        configmaps
+            .create(&PostParams::default(), &configmap)
+            .await
+            .unwrap();
+        assert!(second
+            .api::<ConfigMap>()
+            .get_opt("settings")
+            .await
+            .unwrap()
+            .is_none());
+
+        let name = first.name().to_owned();
+        drop(first);
+        // envtest has no namespace controller, so the namespace is only marked for deletion
+        let namespaces: Api<Namespace> = Api::all(server.client());
+        let namespace = namespaces.get(&name).await.unwrap();
+        assert!(namespace.metadata.deletion_timestamp.is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "needs cluster (creates and deletes a namespace)"]
+    async fn namespaces_are_finalized_on_delete() {
+        let config = Config::infer().await.unwrap();
+        let namespace = TestNamespace::with_prefix(config.clone(), "kube-rs-test-")
+            .await
+            .unwrap();
+        let name = namespace.name().to_owned();
+        namespace.delete().await.unwrap();
+
+        let namespaces: Api<Namespace> = Api::all(Client::try_from(config).unwrap());
+        assert!(namespaces.get_opt(&name).await.unwrap().is_none());
+    }
+}
@@ -12,6 +12,7 @@ replace-docs() {
   # shellcheck disable=SC2016
   sd ' \#(\d+)' ' [#$1](https://github.com/kube-rs/kube/issues/$1)' CHANGELOG.md
   sd "${PREV_VERSION}" "${NEW_VERSION}" kube-derive/README.md
+  sd "${PREV_VERSION}" "${NEW_VERSION}" kube-test/README.md
   sd "${PREV_VERSION}" "${NEW_VERSION}" README.md
 }
 